@@ -0,0 +1,89 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_base::utils::topic_trie::{topic_match_filter, TopicTrie};
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+const TOPIC_NUM: usize = 200_000;
+const FILTER_NUM: usize = 100_000;
+
+fn topic_name(i: usize) -> String {
+    format!("/region-{}/device-{}/sensor-{}", i % 10, i % 2000, i)
+}
+
+fn filter_path(i: usize) -> String {
+    match i % 4 {
+        0 => topic_name(i),
+        1 => format!("/region-{}/device-{}/+", i % 10, i % 2000),
+        2 => format!("/region-{}/+/sensor-{}", i % 10, i),
+        _ => format!("/region-{}/device-{}/#", i % 10, i % 2000),
+    }
+}
+
+fn topic_trie_benchmark(c: &mut Criterion) {
+    let topic_trie = TopicTrie::new();
+    for i in 0..TOPIC_NUM {
+        let name = topic_name(i);
+        topic_trie.insert(&name, &name, name.clone());
+    }
+
+    let filter_trie = TopicTrie::new();
+    for i in 0..FILTER_NUM {
+        let path = filter_path(i);
+        filter_trie.insert(&path, &i.to_string(), i);
+    }
+
+    c.bench_function("match_topic", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 1) % TOPIC_NUM;
+            black_box(filter_trie.match_topic(&topic_name(i)))
+        })
+    });
+
+    c.bench_function("match_filter_single_level", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 1) % FILTER_NUM;
+            black_box(topic_trie.match_filter(&format!("/region-{}/device-{}/+", i % 10, i % 2000)))
+        })
+    });
+
+    c.bench_function("match_filter_multi_level", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 1) % 10;
+            black_box(topic_trie.match_filter(&format!("/region-{}/#", i)))
+        })
+    });
+
+    // The linear scan the trie replaces, over all filters for one topic
+    let filters: Vec<String> = (0..FILTER_NUM).map(filter_path).collect();
+    c.bench_function("match_topic_linear_scan", |b| {
+        let mut i = 0;
+        b.iter(|| {
+            i = (i + 1) % TOPIC_NUM;
+            let name = topic_name(i);
+            black_box(
+                filters
+                    .iter()
+                    .filter(|filter| topic_match_filter(&name, filter))
+                    .count(),
+            )
+        })
+    });
+}
+
+criterion_group!(benches, topic_trie_benchmark);
+criterion_main!(benches);
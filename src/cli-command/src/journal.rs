@@ -0,0 +1,638 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::sync::Arc;
+use std::time::Duration;
+
+use grpc_clients::journal::admin::call::{
+    journal_admin_create_namespace, journal_admin_create_shard, journal_admin_delete_namespace,
+    journal_admin_delete_shard, journal_admin_list_group_offset, journal_admin_list_namespace,
+    journal_admin_list_segment, journal_admin_list_segment_meta, journal_admin_list_shard,
+};
+use grpc_clients::pool::ClientPool;
+use journal_client::client::JournalClient;
+use metadata_struct::adapter::read_config::ReadConfig;
+use metadata_struct::adapter::record::Record;
+use metadata_struct::journal::namespace::JournalNamespace;
+use metadata_struct::journal::segment::JournalSegment;
+use metadata_struct::journal::segment_meta::JournalSegmentMetadata;
+use metadata_struct::journal::shard::JournalShard;
+use prettytable::{row, Table};
+use protocol::journal_server::journal_admin::{
+    CreateNamespaceRequest, CreateShardRequest, DeleteNamespaceRequest, DeleteShardRequest,
+    ListGroupOffsetRequest, ListNamespaceRequest, ListSegmentMetaRequest, ListSegmentRequest,
+    ListShardRequest,
+};
+use serde::de::DeserializeOwned;
+use tokio::time::sleep;
+
+use crate::{error_info, grpc_addr};
+
+#[derive(Clone)]
+pub struct JournalCliCommandParam {
+    // journal server grpc address, used by the admin actions
+    pub server: String,
+    // journal server tcp address, used by the read/tail actions
+    pub journal_server: String,
+    pub action: JournalActionType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JournalActionType {
+    // namespace
+    ListNamespace(ListNamespaceRequest),
+    CreateNamespace(CreateNamespaceRequest),
+    DeleteNamespace(DeleteNamespaceRequest),
+
+    // shard
+    ListShard(ListShardRequest),
+    CreateShard(CreateShardRequest),
+    DeleteShard(DeleteShardRequest),
+
+    // segment
+    ListSegment(ListSegmentRequest),
+    ListSegmentMeta(ListSegmentMetaRequest),
+
+    // record
+    Read(JournalReadRequest),
+    Tail(JournalTailRequest),
+
+    // consumer group
+    ListGroupOffset(ListGroupOffsetRequest),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JournalReadFilter {
+    Offset(u64),
+    Key { offset: u64, key: String },
+    Tag { offset: u64, tag: String },
+    Timestamp(u64),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JournalReadRequest {
+    pub namespace: String,
+    pub shard_name: String,
+    pub filter: JournalReadFilter,
+    pub record_num: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JournalTailRequest {
+    pub namespace: String,
+    pub shard_name: String,
+    // When it is None, tail starts from the end of the shard
+    pub offset: Option<u64>,
+}
+
+pub struct JournalEngineCommand {}
+
+impl Default for JournalEngineCommand {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl JournalEngineCommand {
+    pub fn new() -> Self {
+        JournalEngineCommand {}
+    }
+
+    pub async fn start(&self, params: JournalCliCommandParam) {
+        let client_pool = Arc::new(ClientPool::new(100));
+        let addrs = grpc_addr(params.server.clone());
+
+        match params.action {
+            JournalActionType::ListNamespace(request) => {
+                self.list_namespace(&client_pool, &addrs, request).await;
+            }
+            JournalActionType::CreateNamespace(request) => {
+                self.create_namespace(&client_pool, &addrs, request).await;
+            }
+            JournalActionType::DeleteNamespace(request) => {
+                self.delete_namespace(&client_pool, &addrs, request).await;
+            }
+            JournalActionType::ListShard(request) => {
+                self.list_shard(&client_pool, &addrs, request).await;
+            }
+            JournalActionType::CreateShard(request) => {
+                self.create_shard(&client_pool, &addrs, request).await;
+            }
+            JournalActionType::DeleteShard(request) => {
+                self.delete_shard(&client_pool, &addrs, request).await;
+            }
+            JournalActionType::ListSegment(request) => {
+                self.list_segment(&client_pool, &addrs, request).await;
+            }
+            JournalActionType::ListSegmentMeta(request) => {
+                self.list_segment_meta(&client_pool, &addrs, request).await;
+            }
+            JournalActionType::Read(request) => {
+                self.read(params.journal_server, request).await;
+            }
+            JournalActionType::Tail(request) => {
+                self.tail(&client_pool, &addrs, params.journal_server, request)
+                    .await;
+            }
+            JournalActionType::ListGroupOffset(request) => {
+                self.list_group_offset(&client_pool, &addrs, request).await;
+            }
+        }
+    }
+
+    async fn list_namespace(
+        &self,
+        client_pool: &ClientPool,
+        addrs: &[String],
+        request: ListNamespaceRequest,
+    ) {
+        match journal_admin_list_namespace(client_pool, addrs, request).await {
+            Ok(reply) => {
+                let namespaces: Vec<JournalNamespace> = match parse_json_list(&reply.namespaces) {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error_info(e.to_string());
+                        return;
+                    }
+                };
+                let mut table = Table::new();
+                table.add_row(row![
+                    "namespace",
+                    "max_shard_num",
+                    "max_storage_bytes",
+                    "max_write_bytes_per_sec",
+                    "create_time"
+                ]);
+                for namespace in namespaces {
+                    table.add_row(row![
+                        namespace.namespace_name,
+                        namespace.quota.max_shard_num,
+                        namespace.quota.max_storage_bytes,
+                        namespace.quota.max_write_bytes_per_sec,
+                        namespace.create_time
+                    ]);
+                }
+                table.printstd();
+            }
+            Err(e) => {
+                println!("Journal engine list namespace exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn create_namespace(
+        &self,
+        client_pool: &ClientPool,
+        addrs: &[String],
+        request: CreateNamespaceRequest,
+    ) {
+        match journal_admin_create_namespace(client_pool, addrs, request).await {
+            Ok(_) => {
+                println!("Created successfully!");
+            }
+            Err(e) => {
+                println!("Journal engine create namespace exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn delete_namespace(
+        &self,
+        client_pool: &ClientPool,
+        addrs: &[String],
+        request: DeleteNamespaceRequest,
+    ) {
+        match journal_admin_delete_namespace(client_pool, addrs, request).await {
+            Ok(_) => {
+                println!("Deleted successfully!");
+            }
+            Err(e) => {
+                println!("Journal engine delete namespace exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn list_shard(
+        &self,
+        client_pool: &ClientPool,
+        addrs: &[String],
+        request: ListShardRequest,
+    ) {
+        match journal_admin_list_shard(client_pool, addrs, request).await {
+            Ok(reply) => {
+                let shards: Vec<JournalShard> = match parse_json_list(&reply.shards) {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error_info(e.to_string());
+                        return;
+                    }
+                };
+                let mut table = Table::new();
+                table.add_row(row![
+                    "namespace",
+                    "shard_name",
+                    "replica",
+                    "status",
+                    "start_segment",
+                    "active_segment",
+                    "last_segment",
+                    "create_time"
+                ]);
+                for shard in shards {
+                    table.add_row(row![
+                        shard.namespace,
+                        shard.shard_name,
+                        shard.replica,
+                        format!("{:?}", shard.status),
+                        shard.start_segment_seq,
+                        shard.active_segment_seq,
+                        shard.last_segment_seq,
+                        shard.create_time
+                    ]);
+                }
+                table.printstd();
+            }
+            Err(e) => {
+                println!("Journal engine list shard exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn create_shard(
+        &self,
+        client_pool: &ClientPool,
+        addrs: &[String],
+        request: CreateShardRequest,
+    ) {
+        match journal_admin_create_shard(client_pool, addrs, request).await {
+            Ok(_) => {
+                println!("Created successfully!");
+            }
+            Err(e) => {
+                println!("Journal engine create shard exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn delete_shard(
+        &self,
+        client_pool: &ClientPool,
+        addrs: &[String],
+        request: DeleteShardRequest,
+    ) {
+        match journal_admin_delete_shard(client_pool, addrs, request).await {
+            Ok(_) => {
+                println!("Deleted successfully!");
+            }
+            Err(e) => {
+                println!("Journal engine delete shard exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn list_segment(
+        &self,
+        client_pool: &ClientPool,
+        addrs: &[String],
+        request: ListSegmentRequest,
+    ) {
+        match journal_admin_list_segment(client_pool, addrs, request).await {
+            Ok(reply) => {
+                let segments: Vec<JournalSegment> = match parse_json_list(&reply.segments) {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error_info(e.to_string());
+                        return;
+                    }
+                };
+                let mut table = Table::new();
+                table.add_row(row![
+                    "namespace",
+                    "shard_name",
+                    "segment_seq",
+                    "status",
+                    "leader",
+                    "leader_epoch",
+                    "replicas",
+                    "isr"
+                ]);
+                for segment in segments {
+                    let replicas: Vec<u64> = segment.replicas.iter().map(|r| r.node_id).collect();
+                    table.add_row(row![
+                        segment.namespace,
+                        segment.shard_name,
+                        segment.segment_seq,
+                        segment.status,
+                        segment.leader,
+                        segment.leader_epoch,
+                        format!("{:?}", replicas),
+                        format!("{:?}", segment.isr)
+                    ]);
+                }
+                table.printstd();
+            }
+            Err(e) => {
+                println!("Journal engine list segment exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn list_segment_meta(
+        &self,
+        client_pool: &ClientPool,
+        addrs: &[String],
+        request: ListSegmentMetaRequest,
+    ) {
+        match journal_admin_list_segment_meta(client_pool, addrs, request).await {
+            Ok(reply) => {
+                let metas: Vec<JournalSegmentMetadata> = match parse_json_list(&reply.segment_metas)
+                {
+                    Ok(data) => data,
+                    Err(e) => {
+                        error_info(e.to_string());
+                        return;
+                    }
+                };
+                let mut table = Table::new();
+                table.add_row(row![
+                    "namespace",
+                    "shard_name",
+                    "segment_seq",
+                    "start_offset",
+                    "end_offset",
+                    "start_timestamp",
+                    "end_timestamp"
+                ]);
+                for meta in metas {
+                    table.add_row(row![
+                        meta.namespace,
+                        meta.shard_name,
+                        meta.segment_seq,
+                        meta.start_offset,
+                        meta.end_offset,
+                        meta.start_timestamp,
+                        meta.end_timestamp
+                    ]);
+                }
+                table.printstd();
+            }
+            Err(e) => {
+                println!("Journal engine list segment meta exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn read(&self, journal_server: String, request: JournalReadRequest) {
+        let client = JournalClient::new(grpc_addr(journal_server));
+        if let Err(e) = client.connect().await {
+            println!("Journal engine connect exception");
+            error_info(e.to_string());
+            return;
+        }
+
+        let read_config = ReadConfig {
+            max_record_num: request.record_num,
+            ..ReadConfig::new()
+        };
+
+        let result = match request.filter {
+            JournalReadFilter::Offset(offset) => {
+                client
+                    .read_by_offset(
+                        &request.namespace,
+                        &request.shard_name,
+                        offset,
+                        &read_config,
+                    )
+                    .await
+            }
+            JournalReadFilter::Key { offset, ref key } => {
+                client
+                    .read_by_key(
+                        &request.namespace,
+                        &request.shard_name,
+                        offset,
+                        key,
+                        &read_config,
+                    )
+                    .await
+            }
+            JournalReadFilter::Tag { offset, ref tag } => {
+                client
+                    .read_by_tag(
+                        &request.namespace,
+                        &request.shard_name,
+                        offset,
+                        tag,
+                        &read_config,
+                    )
+                    .await
+            }
+            JournalReadFilter::Timestamp(timestamp) => {
+                match client
+                    .get_offset_by_timestamp(&request.namespace, &request.shard_name, timestamp)
+                    .await
+                {
+                    Ok((_, offset)) => {
+                        client
+                            .read_by_offset(
+                                &request.namespace,
+                                &request.shard_name,
+                                offset,
+                                &read_config,
+                            )
+                            .await
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+        };
+
+        match result {
+            Ok(records) => print_records(&records),
+            Err(e) => {
+                println!("Journal engine read exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn tail(
+        &self,
+        client_pool: &ClientPool,
+        addrs: &[String],
+        journal_server: String,
+        request: JournalTailRequest,
+    ) {
+        let mut offset = if let Some(offset) = request.offset {
+            offset
+        } else {
+            let meta_request = ListSegmentMetaRequest {
+                namespace: request.namespace.clone(),
+                shard_name: request.shard_name.clone(),
+                segment_no: -1,
+            };
+            match journal_admin_list_segment_meta(client_pool, addrs, meta_request).await {
+                Ok(reply) => {
+                    let metas: Vec<JournalSegmentMetadata> =
+                        parse_json_list(&reply.segment_metas).unwrap_or_default();
+                    tail_start_offset(&metas)
+                }
+                Err(e) => {
+                    println!("Journal engine list segment meta exception");
+                    error_info(e.to_string());
+                    return;
+                }
+            }
+        };
+
+        let client = JournalClient::new(grpc_addr(journal_server));
+        if let Err(e) = client.connect().await {
+            println!("Journal engine connect exception");
+            error_info(e.to_string());
+            return;
+        }
+
+        let read_config = ReadConfig::new();
+        loop {
+            match client
+                .read_by_offset(
+                    &request.namespace,
+                    &request.shard_name,
+                    offset,
+                    &read_config,
+                )
+                .await
+            {
+                Ok(records) => {
+                    if records.is_empty() {
+                        sleep(Duration::from_secs(1)).await;
+                        continue;
+                    }
+                    for record in records.iter() {
+                        print_record(record);
+                        if let Some(record_offset) = record.offset {
+                            offset = offset.max(record_offset + 1);
+                        }
+                    }
+                }
+                Err(e) => {
+                    error_info(e.to_string());
+                    sleep(Duration::from_secs(1)).await;
+                }
+            }
+        }
+    }
+
+    async fn list_group_offset(
+        &self,
+        client_pool: &ClientPool,
+        addrs: &[String],
+        request: ListGroupOffsetRequest,
+    ) {
+        match journal_admin_list_group_offset(client_pool, addrs, request).await {
+            Ok(reply) => {
+                let mut table = Table::new();
+                table.add_row(row!["namespace", "shard_name", "offset"]);
+                for raw in reply.offsets {
+                    table.add_row(row![raw.namespace, raw.shard_name, raw.offset]);
+                }
+                table.printstd();
+            }
+            Err(e) => {
+                println!("Journal engine list group offset exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+}
+
+// The admin replies carry every item as a separate JSON string
+fn parse_json_list<T: DeserializeOwned>(list: &[String]) -> Result<Vec<T>, serde_json::Error> {
+    list.iter().map(|raw| serde_json::from_str(raw)).collect()
+}
+
+// The next offset to be written to the shard, according to the segment metadata.
+// Segments that have not finished writing have an end offset of -1.
+fn tail_start_offset(metas: &[JournalSegmentMetadata]) -> u64 {
+    let mut offset = 0;
+    for meta in metas {
+        if meta.end_offset >= 0 {
+            offset = offset.max(meta.end_offset as u64 + 1);
+        } else if meta.start_offset >= 0 {
+            offset = offset.max(meta.start_offset as u64);
+        }
+    }
+    offset
+}
+
+fn print_records(records: &[Record]) {
+    let mut table = Table::new();
+    table.add_row(row!["offset", "key", "tags", "timestamp", "data"]);
+    for record in records {
+        table.add_row(row![
+            record.offset.map(|o| o.to_string()).unwrap_or_default(),
+            record.key,
+            format!("{:?}", record.tags),
+            record.timestamp,
+            String::from_utf8_lossy(&record.data)
+        ]);
+    }
+    table.printstd();
+}
+
+fn print_record(record: &Record) {
+    println!(
+        "offset:{}, key:{}, tags:{:?}, timestamp:{}, data:{}",
+        record.offset.map(|o| o.to_string()).unwrap_or_default(),
+        record.key,
+        record.tags,
+        record.timestamp,
+        String::from_utf8_lossy(&record.data)
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use metadata_struct::journal::segment_meta::JournalSegmentMetadata;
+
+    use super::tail_start_offset;
+
+    #[test]
+    fn tail_start_offset_test() {
+        assert_eq!(tail_start_offset(&[]), 0);
+
+        let sealed = JournalSegmentMetadata {
+            segment_seq: 0,
+            start_offset: 0,
+            end_offset: 99,
+            ..Default::default()
+        };
+        let active = JournalSegmentMetadata {
+            segment_seq: 1,
+            start_offset: 100,
+            end_offset: -1,
+            ..Default::default()
+        };
+        assert_eq!(tail_start_offset(&[sealed.clone()]), 100);
+        assert_eq!(tail_start_offset(&[sealed, active]), 100);
+    }
+}
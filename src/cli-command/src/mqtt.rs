@@ -17,16 +17,31 @@ use std::sync::Arc;
 
 use common_base::enum_type::common_enum::SortType;
 use grpc_clients::mqtt::admin::call::{
-    cluster_status, mqtt_broker_create_user, mqtt_broker_delete_user,
-    mqtt_broker_enable_slow_subscribe, mqtt_broker_list_connection,
-    mqtt_broker_list_slow_subscribe, mqtt_broker_list_topic, mqtt_broker_list_user,
+    cluster_status, mqtt_broker_bind_schema, mqtt_broker_create_rule, mqtt_broker_create_schema,
+    mqtt_broker_create_user, mqtt_broker_delete_auto_subscribe_rule, mqtt_broker_delete_rule,
+    mqtt_broker_delete_schema, mqtt_broker_delete_user, mqtt_broker_enable_slow_subscribe,
+    mqtt_broker_list_auto_subscribe_rule, mqtt_broker_list_connection,
+    mqtt_broker_list_exclusive_topic, mqtt_broker_list_rule, mqtt_broker_list_schema,
+    mqtt_broker_list_schema_binding, mqtt_broker_list_slow_subscribe, mqtt_broker_list_topic,
+    mqtt_broker_list_user, mqtt_broker_release_exclusive_topic,
+    mqtt_broker_set_auto_subscribe_rule, mqtt_broker_set_share_sub_strategy,
+    mqtt_broker_unbind_schema,
 };
 use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::auto_subscribe_rule::MqttAutoSubscribeRule;
+use metadata_struct::mqtt::exclusive_topic::MqttExclusiveTopic;
+use metadata_struct::mqtt::rule::MqttRule;
+use metadata_struct::mqtt::schema::{MqttSchema, MqttSchemaBinding};
 use metadata_struct::mqtt::user::MqttUser;
 use prettytable::{row, Table};
 use protocol::broker_mqtt::broker_mqtt_admin::{
-    ClusterStatusRequest, CreateUserRequest, DeleteUserRequest, EnableSlowSubscribeRequest,
-    ListConnectionRequest, ListSlowSubscribeRequest, ListTopicRequest, ListUserRequest,
+    BindSchemaRequest, ClusterStatusRequest, CreateRuleRequest, CreateSchemaRequest,
+    CreateUserRequest, DeleteAutoSubscribeRuleRequest, DeleteRuleRequest, DeleteSchemaRequest,
+    DeleteUserRequest, EnableSlowSubscribeRequest, ListAutoSubscribeRuleRequest,
+    ListConnectionRequest, ListExclusiveTopicRequest, ListRuleRequest, ListSchemaBindingRequest,
+    ListSchemaRequest, ListSlowSubscribeRequest, ListTopicRequest, ListUserRequest,
+    ReleaseExclusiveTopicRequest, SetAutoSubscribeRuleRequest, SetShareSubStrategyRequest,
+    UnbindSchemaRequest,
 };
 
 use crate::{error_info, grpc_addr};
@@ -54,6 +69,31 @@ pub enum MqttActionType {
     ListSlowSubscribe(ListSlowSubscribeRequest),
 
     ListTopic(ListTopicRequest),
+
+    // auto subscribe rule
+    SetAutoSubscribeRule(SetAutoSubscribeRuleRequest),
+    DeleteAutoSubscribeRule(DeleteAutoSubscribeRuleRequest),
+    ListAutoSubscribeRule,
+
+    // rule engine
+    CreateRule(CreateRuleRequest),
+    DeleteRule(DeleteRuleRequest),
+    ListRule,
+
+    // schema registry
+    CreateSchema(CreateSchemaRequest),
+    DeleteSchema(DeleteSchemaRequest),
+    ListSchema,
+    BindSchema(BindSchemaRequest),
+    UnbindSchema(UnbindSchemaRequest),
+    ListSchemaBinding,
+
+    // shared subscription
+    SetShareSubStrategy(SetShareSubStrategyRequest),
+
+    // exclusive subscription
+    ListExclusiveTopic,
+    ReleaseExclusiveTopic(ReleaseExclusiveTopicRequest),
 }
 
 pub struct MqttBrokerCommand {}
@@ -101,6 +141,63 @@ impl MqttBrokerCommand {
                 self.list_slow_subscribe(&client_pool, params.clone(), request.clone())
                     .await;
             }
+            MqttActionType::SetAutoSubscribeRule(ref request) => {
+                self.set_auto_subscribe_rule(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::DeleteAutoSubscribeRule(ref request) => {
+                self.delete_auto_subscribe_rule(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::ListAutoSubscribeRule => {
+                self.list_auto_subscribe_rule(&client_pool, params.clone())
+                    .await;
+            }
+            MqttActionType::CreateRule(ref request) => {
+                self.create_rule(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::DeleteRule(ref request) => {
+                self.delete_rule(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::ListRule => {
+                self.list_rule(&client_pool, params.clone()).await;
+            }
+            MqttActionType::CreateSchema(ref request) => {
+                self.create_schema(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::DeleteSchema(ref request) => {
+                self.delete_schema(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::ListSchema => {
+                self.list_schema(&client_pool, params.clone()).await;
+            }
+            MqttActionType::BindSchema(ref request) => {
+                self.bind_schema(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::UnbindSchema(ref request) => {
+                self.unbind_schema(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::ListSchemaBinding => {
+                self.list_schema_binding(&client_pool, params.clone()).await;
+            }
+            MqttActionType::SetShareSubStrategy(ref request) => {
+                self.set_share_sub_strategy(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
+            MqttActionType::ListExclusiveTopic => {
+                self.list_exclusive_topic(&client_pool, params.clone())
+                    .await;
+            }
+            MqttActionType::ReleaseExclusiveTopic(ref request) => {
+                self.release_exclusive_topic(&client_pool, params.clone(), request.clone())
+                    .await;
+            }
         }
     }
 
@@ -302,6 +399,345 @@ impl MqttBrokerCommand {
             }
         }
     }
+
+    // ------------ auto subscribe rule ----------
+    async fn set_auto_subscribe_rule(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: SetAutoSubscribeRuleRequest,
+    ) {
+        match mqtt_broker_set_auto_subscribe_rule(
+            client_pool,
+            &grpc_addr(params.server),
+            cli_request,
+        )
+        .await
+        {
+            Ok(_) => {
+                println!("Created successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker set auto subscribe rule exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn delete_auto_subscribe_rule(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: DeleteAutoSubscribeRuleRequest,
+    ) {
+        match mqtt_broker_delete_auto_subscribe_rule(
+            client_pool,
+            &grpc_addr(params.server),
+            cli_request,
+        )
+        .await
+        {
+            Ok(_) => {
+                println!("Deleted successfully!");
+            }
+            Err(e) => {
+                println!("MQTT broker delete auto subscribe rule exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn list_auto_subscribe_rule(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+    ) {
+        let request = ListAutoSubscribeRuleRequest {};
+        match mqtt_broker_list_auto_subscribe_rule(client_pool, &grpc_addr(params.server), request)
+            .await
+        {
+            Ok(data) => {
+                let mut table = Table::new();
+                table.add_row(row![
+                    "topic",
+                    "qos",
+                    "no_local",
+                    "retain_as_published",
+                    "retained_handling"
+                ]);
+                for raw in data.auto_subscribe_rules {
+                    let rule = serde_json::from_slice::<MqttAutoSubscribeRule>(&raw).unwrap();
+                    table.add_row(row![
+                        rule.topic,
+                        u8::from(rule.qos),
+                        rule.no_local,
+                        rule.retain_as_published,
+                        u8::from(rule.retained_handling)
+                    ]);
+                }
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker list auto subscribe rule exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    // ------------ rule engine ----------
+    async fn create_rule(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: CreateRuleRequest,
+    ) {
+        match mqtt_broker_create_rule(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(_) => {
+                println!("Created successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker create rule exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn delete_rule(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: DeleteRuleRequest,
+    ) {
+        match mqtt_broker_delete_rule(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(_) => {
+                println!("Deleted successfully!");
+            }
+            Err(e) => {
+                println!("MQTT broker delete rule exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn list_rule(&self, client_pool: &ClientPool, params: MqttCliCommandParam) {
+        let request = ListRuleRequest {};
+        match mqtt_broker_list_rule(client_pool, &grpc_addr(params.server), request).await {
+            Ok(data) => {
+                let mut table = Table::new();
+                table.add_row(row!["rule_name", "sql", "actions", "description"]);
+                for raw in data.rules {
+                    let rule = serde_json::from_slice::<MqttRule>(&raw).unwrap();
+                    table.add_row(row![
+                        rule.rule_name,
+                        rule.sql,
+                        format!("{:?}", rule.actions),
+                        rule.description
+                    ]);
+                }
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker list rule exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    // ------------ schema registry ------------
+    async fn create_schema(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: CreateSchemaRequest,
+    ) {
+        match mqtt_broker_create_schema(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(_) => {
+                println!("Created successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker create schema exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn delete_schema(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: DeleteSchemaRequest,
+    ) {
+        match mqtt_broker_delete_schema(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(_) => {
+                println!("Deleted successfully!");
+            }
+            Err(e) => {
+                println!("MQTT broker delete schema exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn list_schema(&self, client_pool: &ClientPool, params: MqttCliCommandParam) {
+        let request = ListSchemaRequest {};
+        match mqtt_broker_list_schema(client_pool, &grpc_addr(params.server), request).await {
+            Ok(data) => {
+                let mut table = Table::new();
+                table.add_row(row![
+                    "schema_name",
+                    "schema_type",
+                    "message_name",
+                    "description"
+                ]);
+                for raw in data.schemas {
+                    let schema = serde_json::from_slice::<MqttSchema>(&raw).unwrap();
+                    table.add_row(row![
+                        schema.schema_name,
+                        format!("{:?}", schema.schema_type),
+                        schema.message_name,
+                        schema.description
+                    ]);
+                }
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker list schema exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn bind_schema(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: BindSchemaRequest,
+    ) {
+        match mqtt_broker_bind_schema(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(_) => {
+                println!("Bound successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker bind schema exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn unbind_schema(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: UnbindSchemaRequest,
+    ) {
+        match mqtt_broker_unbind_schema(client_pool, &grpc_addr(params.server), cli_request).await {
+            Ok(_) => {
+                println!("Unbound successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker unbind schema exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn list_schema_binding(&self, client_pool: &ClientPool, params: MqttCliCommandParam) {
+        let request = ListSchemaBindingRequest {};
+        match mqtt_broker_list_schema_binding(client_pool, &grpc_addr(params.server), request).await
+        {
+            Ok(data) => {
+                let mut table = Table::new();
+                table.add_row(row!["schema_name", "topic_filter", "failure_strategy"]);
+                for raw in data.bindings {
+                    let binding = serde_json::from_slice::<MqttSchemaBinding>(&raw).unwrap();
+                    table.add_row(row![
+                        binding.schema_name,
+                        binding.topic_filter,
+                        format!("{:?}", binding.failure_strategy)
+                    ]);
+                }
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker list schema binding exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    // ------------ shared subscription ------------
+    async fn set_share_sub_strategy(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: SetShareSubStrategyRequest,
+    ) {
+        match mqtt_broker_set_share_sub_strategy(
+            client_pool,
+            &grpc_addr(params.server),
+            cli_request,
+        )
+        .await
+        {
+            Ok(_) => {
+                println!("Set share subscription strategy successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker set share subscription strategy exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    // ------------ exclusive subscription ------------
+    async fn list_exclusive_topic(&self, client_pool: &ClientPool, params: MqttCliCommandParam) {
+        let request = ListExclusiveTopicRequest {};
+        match mqtt_broker_list_exclusive_topic(client_pool, &grpc_addr(params.server), request)
+            .await
+        {
+            Ok(data) => {
+                let mut table = Table::new();
+                table.add_row(row!["topic_name", "client_id", "broker_id", "create_time"]);
+                for raw in data.exclusive_topics {
+                    let exclusive = serde_json::from_slice::<MqttExclusiveTopic>(&raw).unwrap();
+                    table.add_row(row![
+                        exclusive.topic_name,
+                        exclusive.client_id,
+                        exclusive.broker_id,
+                        exclusive.create_time
+                    ]);
+                }
+                table.printstd()
+            }
+            Err(e) => {
+                println!("MQTT broker list exclusive topic exception");
+                error_info(e.to_string());
+            }
+        }
+    }
+
+    async fn release_exclusive_topic(
+        &self,
+        client_pool: &ClientPool,
+        params: MqttCliCommandParam,
+        cli_request: ReleaseExclusiveTopicRequest,
+    ) {
+        match mqtt_broker_release_exclusive_topic(
+            client_pool,
+            &grpc_addr(params.server),
+            cli_request,
+        )
+        .await
+        {
+            Ok(_) => {
+                println!("Released successfully!")
+            }
+            Err(e) => {
+                println!("MQTT broker release exclusive topic exception");
+                error_info(e.to_string());
+            }
+        }
+    }
 }
 
 #[cfg(test)]
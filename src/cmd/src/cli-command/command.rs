@@ -12,21 +12,40 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub(crate) mod journal;
 pub(crate) mod mqtt;
 
 use clap::{arg, Parser, Subcommand, ValueEnum};
+use cli_command::journal::{JournalActionType, JournalCliCommandParam, JournalEngineCommand};
 use cli_command::mqtt::{MqttActionType, MqttBrokerCommand, MqttCliCommandParam};
 use cli_command::placement::{
     PlacementActionType, PlacementCenterCommand, PlacementCliCommandParam,
 };
 use protocol::broker_mqtt::broker_mqtt_admin::{
-    CreateUserRequest, DeleteUserRequest, ListTopicRequest,
+    CreateUserRequest, DeleteAutoSubscribeRuleRequest, DeleteRuleRequest, DeleteSchemaRequest,
+    DeleteUserRequest, ListTopicRequest, ReleaseExclusiveTopicRequest, UnbindSchemaRequest,
+};
+use protocol::journal_server::journal_admin::{
+    CreateNamespaceRequest, DeleteNamespaceRequest, DeleteShardRequest, ListGroupOffsetRequest,
+    ListNamespaceRequest, ListSegmentMetaRequest, ListSegmentRequest, ListShardRequest,
 };
 use protocol::placement_center::placement_center_openraft::{
     AddLearnerRequest, ChangeMembershipRequest, Node,
 };
 
-use crate::mqtt::admin::{process_slow_sub_args, CreateUserArgs, DeleteUserArgs, SlowSubArgs};
+use crate::journal::admin::{
+    process_create_shard_args, process_read_args, process_tail_args, CreateNamespaceArgs,
+    CreateShardArgs, DeleteNamespaceArgs, DeleteShardArgs, ListGroupOffsetArgs, ListNamespaceArgs,
+    ListSegmentArgs, ListShardArgs, ReadArgs, TailArgs,
+};
+use crate::mqtt::admin::{
+    process_bind_schema_args, process_create_rule_args, process_create_schema_args,
+    process_set_auto_subscribe_rule_args, process_set_share_sub_strategy_args,
+    process_slow_sub_args, BindSchemaArgs, CreateRuleArgs, CreateSchemaArgs, CreateUserArgs,
+    DeleteAutoSubscribeRuleArgs, DeleteRuleArgs, DeleteSchemaArgs, DeleteUserArgs,
+    ReleaseExclusiveTopicArgs, SetAutoSubscribeRuleArgs, SetShareSubStrategyArgs, SlowSubArgs,
+    UnbindSchemaArgs,
+};
 
 #[derive(Parser)] // requires `derive` feature
 #[command(name = "robust-ctl")]
@@ -73,6 +92,31 @@ enum MQTTAction {
 
     ListTopic(ListTopicArgs),
 
+    // auto subscribe rule
+    SetAutoSubscribeRule(SetAutoSubscribeRuleArgs),
+    DeleteAutoSubscribeRule(DeleteAutoSubscribeRuleArgs),
+    ListAutoSubscribeRule,
+
+    // rule engine
+    CreateRule(CreateRuleArgs),
+    DeleteRule(DeleteRuleArgs),
+    ListRule,
+
+    // schema registry
+    CreateSchema(CreateSchemaArgs),
+    DeleteSchema(DeleteSchemaArgs),
+    ListSchema,
+    BindSchema(BindSchemaArgs),
+    UnbindSchema(UnbindSchemaArgs),
+    ListSchemaBinding,
+
+    // shared subscription
+    SetShareSubStrategy(SetShareSubStrategyArgs),
+
+    // exclusive subscription
+    ListExclusiveTopic,
+    ReleaseExclusiveTopic(ReleaseExclusiveTopicArgs),
+
     // observability: slow-sub feat
     #[clap(name = "slow-sub")]
     SlowSub(SlowSubArgs),
@@ -143,11 +187,38 @@ struct ChangeMembershipArgs {
 #[command(author="RobustMQ", about="Command line tool for journal engine", long_about = None)]
 #[command(next_line_help = true)]
 struct JournalArgs {
-    #[arg(short, long,default_value_t =String::from("127.0.0.1:1228"))]
+    #[arg(short, long,default_value_t =String::from("127.0.0.1:2228"))]
     server: String,
 
-    #[arg(short, long,default_value_t =String::from("status"))]
-    action: String,
+    #[arg(short, long,default_value_t =String::from("127.0.0.1:3110"))]
+    journal_server: String,
+
+    #[clap(subcommand)]
+    action: JournalAction,
+}
+
+#[derive(Debug, Subcommand)]
+enum JournalAction {
+    // Namespace
+    ListNamespace(ListNamespaceArgs),
+    CreateNamespace(CreateNamespaceArgs),
+    DeleteNamespace(DeleteNamespaceArgs),
+
+    // Shard
+    ListShard(ListShardArgs),
+    CreateShard(CreateShardArgs),
+    DeleteShard(DeleteShardArgs),
+
+    // Segment
+    ListSegment(ListSegmentArgs),
+    ListSegmentMeta(ListSegmentArgs),
+
+    // Record
+    Read(ReadArgs),
+    Tail(TailArgs),
+
+    // Consumer group
+    ListGroupOffset(ListGroupOffsetArgs),
 }
 
 #[tokio::main]
@@ -156,7 +227,7 @@ async fn main() {
     match args {
         RobustMQCli::Mqtt(args) => handle_mqtt(args, MqttBrokerCommand::new()).await,
         RobustMQCli::Place(args) => handle_placement(args, PlacementCenterCommand::new()).await,
-        RobustMQCli::Journal(args) => handle_journal(args).await,
+        RobustMQCli::Journal(args) => handle_journal(args, JournalEngineCommand::new()).await,
     }
 }
 
@@ -183,6 +254,36 @@ async fn handle_mqtt(args: MqttArgs, cmd: MqttBrokerCommand) {
                     MatchOption::S => 2,
                 },
             }),
+            MQTTAction::SetAutoSubscribeRule(args) => process_set_auto_subscribe_rule_args(args),
+            MQTTAction::DeleteAutoSubscribeRule(args) => {
+                MqttActionType::DeleteAutoSubscribeRule(DeleteAutoSubscribeRuleRequest {
+                    topic: args.topic,
+                })
+            }
+            MQTTAction::ListAutoSubscribeRule => MqttActionType::ListAutoSubscribeRule,
+            MQTTAction::CreateRule(args) => process_create_rule_args(args),
+            MQTTAction::DeleteRule(args) => MqttActionType::DeleteRule(DeleteRuleRequest {
+                rule_name: args.rule_name,
+            }),
+            MQTTAction::ListRule => MqttActionType::ListRule,
+            MQTTAction::CreateSchema(args) => process_create_schema_args(args),
+            MQTTAction::DeleteSchema(args) => MqttActionType::DeleteSchema(DeleteSchemaRequest {
+                schema_name: args.schema_name,
+            }),
+            MQTTAction::ListSchema => MqttActionType::ListSchema,
+            MQTTAction::BindSchema(args) => process_bind_schema_args(args),
+            MQTTAction::UnbindSchema(args) => MqttActionType::UnbindSchema(UnbindSchemaRequest {
+                schema_name: args.schema_name,
+                topic_filter: args.topic_filter,
+            }),
+            MQTTAction::ListSchemaBinding => MqttActionType::ListSchemaBinding,
+            MQTTAction::SetShareSubStrategy(args) => process_set_share_sub_strategy_args(args),
+            MQTTAction::ListExclusiveTopic => MqttActionType::ListExclusiveTopic,
+            MQTTAction::ReleaseExclusiveTopic(args) => {
+                MqttActionType::ReleaseExclusiveTopic(ReleaseExclusiveTopicRequest {
+                    topic_name: args.topic_name,
+                })
+            }
             MQTTAction::SlowSub(args) => process_slow_sub_args(args), // _ => unreachable!("UnSupport command"),
         },
     };
@@ -215,7 +316,58 @@ async fn handle_placement(args: PlacementArgs, cmd: PlacementCenterCommand) {
     cmd.start(params).await;
 }
 
-// TODO: implement journal engine
-async fn handle_journal(args: JournalArgs) {
-    println!("{:?}", args);
+async fn handle_journal(args: JournalArgs, cmd: JournalEngineCommand) {
+    let params = JournalCliCommandParam {
+        server: args.server,
+        journal_server: args.journal_server,
+        action: match args.action {
+            JournalAction::ListNamespace(arg) => {
+                JournalActionType::ListNamespace(ListNamespaceRequest {
+                    namespace: arg.namespace,
+                })
+            }
+            JournalAction::CreateNamespace(arg) => {
+                JournalActionType::CreateNamespace(CreateNamespaceRequest {
+                    namespace: arg.namespace,
+                    max_shard_num: arg.max_shard_num,
+                    max_storage_bytes: arg.max_storage_bytes,
+                    max_write_bytes_per_sec: arg.max_write_bytes_per_sec,
+                })
+            }
+            JournalAction::DeleteNamespace(arg) => {
+                JournalActionType::DeleteNamespace(DeleteNamespaceRequest {
+                    namespace: arg.namespace,
+                })
+            }
+            JournalAction::ListShard(arg) => JournalActionType::ListShard(ListShardRequest {
+                namespace: arg.namespace,
+                shard_name: arg.shard_name,
+            }),
+            JournalAction::CreateShard(arg) => process_create_shard_args(arg),
+            JournalAction::DeleteShard(arg) => JournalActionType::DeleteShard(DeleteShardRequest {
+                namespace: arg.namespace,
+                shard_name: arg.shard_name,
+            }),
+            JournalAction::ListSegment(arg) => JournalActionType::ListSegment(ListSegmentRequest {
+                namespace: arg.namespace,
+                shard_name: arg.shard_name,
+                segment_no: arg.segment_no,
+            }),
+            JournalAction::ListSegmentMeta(arg) => {
+                JournalActionType::ListSegmentMeta(ListSegmentMetaRequest {
+                    namespace: arg.namespace,
+                    shard_name: arg.shard_name,
+                    segment_no: arg.segment_no,
+                })
+            }
+            JournalAction::Read(arg) => process_read_args(arg),
+            JournalAction::Tail(arg) => process_tail_args(arg),
+            JournalAction::ListGroupOffset(arg) => {
+                JournalActionType::ListGroupOffset(ListGroupOffsetRequest {
+                    group_name: arg.group,
+                })
+            }
+        },
+    };
+    cmd.start(params).await;
 }
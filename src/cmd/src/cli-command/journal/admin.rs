@@ -0,0 +1,295 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use cli_command::journal::{
+    JournalActionType, JournalReadFilter, JournalReadRequest, JournalTailRequest,
+};
+use metadata_struct::journal::shard::{
+    JournalCompressionType, JournalDurabilityLevel, JournalShardConfig,
+};
+use protocol::journal_server::journal_admin::CreateShardRequest;
+
+// namespace
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: list namespaces", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ListNamespaceArgs {
+    #[arg(short, long, default_value_t = String::from(""))]
+    pub(crate) namespace: String,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: create namespace or update its quota", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct CreateNamespaceArgs {
+    #[arg(short, long, required = true)]
+    pub(crate) namespace: String,
+
+    #[arg(long, default_value_t = 0, help = "0 means unlimited")]
+    pub(crate) max_shard_num: u32,
+
+    #[arg(long, default_value_t = 0, help = "0 means unlimited")]
+    pub(crate) max_storage_bytes: u64,
+
+    #[arg(long, default_value_t = 0, help = "0 means unlimited")]
+    pub(crate) max_write_bytes_per_sec: u64,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: delete namespace", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct DeleteNamespaceArgs {
+    #[arg(short, long, required = true)]
+    pub(crate) namespace: String,
+}
+
+// shard
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: list shards", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ListShardArgs {
+    #[arg(short, long, default_value_t = String::from(""))]
+    pub(crate) namespace: String,
+
+    #[arg(short, long, default_value_t = String::from(""), requires = "namespace")]
+    pub(crate) shard_name: String,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: create shard", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct CreateShardArgs {
+    #[arg(short, long, required = true)]
+    pub(crate) namespace: String,
+
+    #[arg(short, long, required = true)]
+    pub(crate) shard_name: String,
+
+    #[arg(short, long, default_value_t = 1)]
+    pub(crate) replica: u32,
+
+    #[arg(long, default_value_t = 0)]
+    pub(crate) retention_sec: u64,
+
+    #[arg(long, default_value_t = 0)]
+    pub(crate) retention_bytes: u64,
+
+    #[arg(long, default_value_t = 0)]
+    pub(crate) retention_segment_num: u32,
+
+    #[arg(long, default_value_t = false)]
+    pub(crate) enable_compact: bool,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: delete shard", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct DeleteShardArgs {
+    #[arg(short, long, required = true)]
+    pub(crate) namespace: String,
+
+    #[arg(short, long, required = true)]
+    pub(crate) shard_name: String,
+}
+
+// segment
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: list segments or segment metadata", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ListSegmentArgs {
+    #[arg(short, long, default_value_t = String::from(""))]
+    pub(crate) namespace: String,
+
+    #[arg(short, long, default_value_t = String::from(""), requires = "namespace")]
+    pub(crate) shard_name: String,
+
+    #[arg(short = 'g', long, default_value_t = -1, allow_negative_numbers = true, requires = "shard_name")]
+    pub(crate) segment_no: i32,
+}
+
+// record
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: read records", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ReadArgs {
+    #[arg(short, long, required = true)]
+    pub(crate) namespace: String,
+
+    #[arg(short, long, required = true)]
+    pub(crate) shard_name: String,
+
+    #[arg(short, long, default_value_t = 0)]
+    pub(crate) offset: u64,
+
+    #[arg(short, long, conflicts_with_all = ["tag", "timestamp"])]
+    pub(crate) key: Option<String>,
+
+    #[arg(short, long, conflicts_with_all = ["key", "timestamp"])]
+    pub(crate) tag: Option<String>,
+
+    #[arg(long, conflicts_with_all = ["key", "tag", "offset"])]
+    pub(crate) timestamp: Option<u64>,
+
+    #[arg(short = 'm', long, default_value_t = 10)]
+    pub(crate) num: u64,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: follow the records written to a shard", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct TailArgs {
+    #[arg(short, long, required = true)]
+    pub(crate) namespace: String,
+
+    #[arg(short, long, required = true)]
+    pub(crate) shard_name: String,
+
+    #[arg(short, long, help = "Start from the end of the shard when not set")]
+    pub(crate) offset: Option<u64>,
+}
+
+// consumer group
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: list the committed offsets of a consumer group", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ListGroupOffsetArgs {
+    #[arg(short, long, required = true)]
+    pub(crate) group: String,
+}
+
+pub fn process_create_shard_args(args: CreateShardArgs) -> JournalActionType {
+    let shard_config = JournalShardConfig {
+        retention_sec: args.retention_sec,
+        retention_bytes: args.retention_bytes,
+        retention_segment_num: args.retention_segment_num,
+        enable_compact: args.enable_compact,
+        tombstone_retention_sec: 0,
+        compression: JournalCompressionType::None,
+        durability: JournalDurabilityLevel::OsBuffered,
+        fsync_interval_ms: 0,
+        fsync_bytes: 0,
+    };
+    JournalActionType::CreateShard(CreateShardRequest {
+        namespace: args.namespace,
+        shard_name: args.shard_name,
+        replica: args.replica,
+        shard_config: serde_json::to_vec(&shard_config).unwrap(),
+    })
+}
+
+pub fn process_read_args(args: ReadArgs) -> JournalActionType {
+    let filter = if let Some(timestamp) = args.timestamp {
+        JournalReadFilter::Timestamp(timestamp)
+    } else if let Some(key) = args.key {
+        JournalReadFilter::Key {
+            offset: args.offset,
+            key,
+        }
+    } else if let Some(tag) = args.tag {
+        JournalReadFilter::Tag {
+            offset: args.offset,
+            tag,
+        }
+    } else {
+        JournalReadFilter::Offset(args.offset)
+    };
+
+    JournalActionType::Read(JournalReadRequest {
+        namespace: args.namespace,
+        shard_name: args.shard_name,
+        filter,
+        record_num: args.num,
+    })
+}
+
+pub fn process_tail_args(args: TailArgs) -> JournalActionType {
+    JournalActionType::Tail(JournalTailRequest {
+        namespace: args.namespace,
+        shard_name: args.shard_name,
+        offset: args.offset,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read_args() -> ReadArgs {
+        ReadArgs {
+            namespace: "ns".to_string(),
+            shard_name: "s1".to_string(),
+            offset: 5,
+            key: None,
+            tag: None,
+            timestamp: None,
+            num: 10,
+        }
+    }
+
+    #[test]
+    fn test_process_read_args_by_offset() {
+        let action_type = process_read_args(read_args());
+        assert_eq!(
+            JournalActionType::Read(JournalReadRequest {
+                namespace: "ns".to_string(),
+                shard_name: "s1".to_string(),
+                filter: JournalReadFilter::Offset(5),
+                record_num: 10,
+            }),
+            action_type
+        );
+    }
+
+    #[test]
+    fn test_process_read_args_by_key_and_timestamp() {
+        let mut args = read_args();
+        args.key = Some("k1".to_string());
+        let JournalActionType::Read(request) = process_read_args(args) else {
+            panic!("unexpected action type");
+        };
+        assert_eq!(
+            request.filter,
+            JournalReadFilter::Key {
+                offset: 5,
+                key: "k1".to_string()
+            }
+        );
+
+        let mut args = read_args();
+        args.timestamp = Some(1000);
+        let JournalActionType::Read(request) = process_read_args(args) else {
+            panic!("unexpected action type");
+        };
+        assert_eq!(request.filter, JournalReadFilter::Timestamp(1000));
+    }
+
+    #[test]
+    fn test_process_create_shard_args() {
+        let args = CreateShardArgs {
+            namespace: "ns".to_string(),
+            shard_name: "s1".to_string(),
+            replica: 3,
+            retention_sec: 60,
+            retention_bytes: 0,
+            retention_segment_num: 0,
+            enable_compact: false,
+        };
+        let JournalActionType::CreateShard(request) = process_create_shard_args(args) else {
+            panic!("unexpected action type");
+        };
+        assert_eq!(request.shard_name, "s1");
+        assert_eq!(request.replica, 3);
+        let config: JournalShardConfig = serde_json::from_slice(&request.shard_config).unwrap();
+        assert_eq!(config.retention_sec, 60);
+    }
+}
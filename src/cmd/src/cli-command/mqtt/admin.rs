@@ -19,7 +19,9 @@ use clap::Parser;
 use cli_command::mqtt::MqttActionType;
 use common_base::enum_type::common_enum::SortType;
 use protocol::broker_mqtt::broker_mqtt_admin::{
-    EnableSlowSubscribeRequest, ListSlowSubscribeRequest,
+    BindSchemaRequest, CreateRuleRequest, CreateSchemaRequest, EnableSlowSubscribeRequest,
+    ListSlowSubscribeRequest, RuleAction, RuleActionType, SchemaFailureStrategy, SchemaType,
+    SetAutoSubscribeRuleRequest, SetShareSubStrategyRequest, ShareSubStrategy,
 };
 
 // security: user feat
@@ -45,6 +47,180 @@ pub(crate) struct DeleteUserArgs {
     pub(crate) username: String,
 }
 
+// auto subscribe rule
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: set auto subscribe rule", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct SetAutoSubscribeRuleArgs {
+    #[arg(short, long, required = true)]
+    #[arg(help = "Topic template, supports ${clientid} and ${username}")]
+    pub(crate) topic: String,
+
+    #[arg(short, long, default_value_t = 0)]
+    #[arg(value_parser = RangedU64ValueParser::<u32>::new().range(0..=2))]
+    pub(crate) qos: u32,
+
+    #[arg(short, long, default_value_t = false)]
+    pub(crate) no_local: bool,
+
+    #[arg(short = 'r', long, default_value_t = false)]
+    pub(crate) retain_as_published: bool,
+
+    #[arg(short = 'R', long, default_value_t = 0)]
+    #[arg(value_parser = RangedU64ValueParser::<u32>::new().range(0..=2))]
+    pub(crate) retained_handling: u32,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: delete auto subscribe rule", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct DeleteAutoSubscribeRuleArgs {
+    #[arg(short, long, required = true)]
+    pub(crate) topic: String,
+}
+
+// rule engine
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: create rule", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct CreateRuleArgs {
+    #[arg(short = 'n', long, required = true)]
+    pub(crate) rule_name: String,
+
+    #[arg(short, long, required = true)]
+    #[arg(help = "e.g. SELECT payload.temp AS temp FROM \"sensor/#\" WHERE payload.temp > 30")]
+    pub(crate) sql: String,
+
+    #[arg(short, long, default_value = "")]
+    pub(crate) description: String,
+
+    #[arg(long, help = "Republish the results to this topic, supports ${field}")]
+    pub(crate) republish_topic: Option<String>,
+
+    #[arg(long, default_value_t = 0)]
+    #[arg(value_parser = RangedU64ValueParser::<u32>::new().range(0..=2))]
+    pub(crate) republish_qos: u32,
+
+    #[arg(long, default_value_t = false)]
+    pub(crate) republish_retain: bool,
+
+    #[arg(long, requires = "journal_shard")]
+    pub(crate) journal_namespace: Option<String>,
+
+    #[arg(long, requires = "journal_namespace")]
+    pub(crate) journal_shard: Option<String>,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: delete rule", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct DeleteRuleArgs {
+    #[arg(short = 'n', long, required = true)]
+    pub(crate) rule_name: String,
+}
+
+// schema registry
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub(crate) enum SchemaTypeArg {
+    Json,
+    Protobuf,
+    Avro,
+}
+
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub(crate) enum SchemaFailureStrategyArg {
+    Reject,
+    Tag,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: create schema", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct CreateSchemaArgs {
+    #[arg(short = 'n', long, required = true)]
+    pub(crate) schema_name: String,
+
+    #[arg(short = 't', long, value_enum, required = true)]
+    pub(crate) schema_type: SchemaTypeArg,
+
+    #[arg(short = 'f', long, required = true)]
+    #[arg(help = "json/avro: the schema document, protobuf: a protoc descriptor set")]
+    pub(crate) schema_file: String,
+
+    #[arg(short, long, default_value = "")]
+    #[arg(help = "protobuf: the full name of the message, e.g. sensor.Reading")]
+    pub(crate) message_name: String,
+
+    #[arg(short, long, default_value = "")]
+    pub(crate) description: String,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: delete schema", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct DeleteSchemaArgs {
+    #[arg(short = 'n', long, required = true)]
+    pub(crate) schema_name: String,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: bind schema to a topic filter", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct BindSchemaArgs {
+    #[arg(short = 'n', long, required = true)]
+    pub(crate) schema_name: String,
+
+    #[arg(short, long, required = true)]
+    pub(crate) topic_filter: String,
+
+    #[arg(short, long, value_enum, default_value = "reject")]
+    #[arg(help = "reject the message or tag it with a user property")]
+    pub(crate) failure_strategy: SchemaFailureStrategyArg,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: unbind schema from a topic filter", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct UnbindSchemaArgs {
+    #[arg(short = 'n', long, required = true)]
+    pub(crate) schema_name: String,
+
+    #[arg(short, long, required = true)]
+    pub(crate) topic_filter: String,
+}
+
+// shared subscription
+#[derive(clap::ValueEnum, Clone, Debug)]
+pub(crate) enum ShareSubStrategyArg {
+    RoundRobin,
+    Random,
+    HashClientId,
+    HashTopic,
+    Sticky,
+    LeastInflight,
+}
+
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: set the dispatch strategy of shared subscriptions", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct SetShareSubStrategyArgs {
+    #[arg(short, long, default_value = "")]
+    #[arg(help = "the shared subscription group, empty sets the cluster default")]
+    pub(crate) group_name: String,
+
+    #[arg(short, long, value_enum, required = true)]
+    pub(crate) strategy: ShareSubStrategyArg,
+}
+
+// exclusive subscription
+#[derive(clap::Args, Debug)]
+#[command(author="RobustMQ", about="action: release the lock held on an exclusive topic", long_about = None)]
+#[command(next_line_help = true)]
+pub(crate) struct ReleaseExclusiveTopicArgs {
+    #[arg(short, long, required = true)]
+    pub(crate) topic_name: String,
+}
+
 // observability: slow-sub feat
 #[derive(Debug, Parser)]
 #[command(author="RobustMQ", about="", long_about = None)]
@@ -87,6 +263,91 @@ pub(crate) struct SlowSubArgs {
     client_id: Option<String>,
 }
 
+pub fn process_set_auto_subscribe_rule_args(args: SetAutoSubscribeRuleArgs) -> MqttActionType {
+    MqttActionType::SetAutoSubscribeRule(SetAutoSubscribeRuleRequest {
+        topic: args.topic,
+        qos: args.qos,
+        no_local: args.no_local,
+        retain_as_published: args.retain_as_published,
+        retained_handling: args.retained_handling,
+    })
+}
+
+pub fn process_create_rule_args(args: CreateRuleArgs) -> MqttActionType {
+    let mut actions = Vec::new();
+    if let Some(topic) = args.republish_topic {
+        actions.push(RuleAction {
+            action_type: RuleActionType::Republish.into(),
+            topic,
+            qos: args.republish_qos,
+            retain: args.republish_retain,
+            ..Default::default()
+        });
+    }
+    if let (Some(namespace), Some(shard_name)) = (args.journal_namespace, args.journal_shard) {
+        actions.push(RuleAction {
+            action_type: RuleActionType::Journal.into(),
+            namespace,
+            shard_name,
+            ..Default::default()
+        });
+    }
+    MqttActionType::CreateRule(CreateRuleRequest {
+        rule_name: args.rule_name,
+        sql: args.sql,
+        actions,
+        description: args.description,
+    })
+}
+
+pub fn process_create_schema_args(args: CreateSchemaArgs) -> MqttActionType {
+    let schema = std::fs::read(&args.schema_file).unwrap_or_else(|e| {
+        panic!(
+            "Failed to read the schema file {}, error message: {}",
+            args.schema_file, e
+        )
+    });
+    let schema_type = match args.schema_type {
+        SchemaTypeArg::Json => SchemaType::Json,
+        SchemaTypeArg::Protobuf => SchemaType::Protobuf,
+        SchemaTypeArg::Avro => SchemaType::Avro,
+    };
+    MqttActionType::CreateSchema(CreateSchemaRequest {
+        schema_name: args.schema_name,
+        schema_type: schema_type.into(),
+        schema,
+        message_name: args.message_name,
+        description: args.description,
+    })
+}
+
+pub fn process_bind_schema_args(args: BindSchemaArgs) -> MqttActionType {
+    let failure_strategy = match args.failure_strategy {
+        SchemaFailureStrategyArg::Reject => SchemaFailureStrategy::Reject,
+        SchemaFailureStrategyArg::Tag => SchemaFailureStrategy::Tag,
+    };
+    MqttActionType::BindSchema(BindSchemaRequest {
+        schema_name: args.schema_name,
+        topic_filter: args.topic_filter,
+        failure_strategy: failure_strategy.into(),
+    })
+}
+
+pub fn process_set_share_sub_strategy_args(args: SetShareSubStrategyArgs) -> MqttActionType {
+    let strategy = match args.strategy {
+        ShareSubStrategyArg::RoundRobin => ShareSubStrategy::RoundRobin,
+        ShareSubStrategyArg::Random => ShareSubStrategy::Random,
+        ShareSubStrategyArg::HashClientId => ShareSubStrategy::HashClientId,
+        ShareSubStrategyArg::HashTopic => ShareSubStrategy::HashTopic,
+        ShareSubStrategyArg::Sticky => ShareSubStrategy::Sticky,
+        ShareSubStrategyArg::LeastInflight => ShareSubStrategy::LeastInflight,
+    };
+    MqttActionType::SetShareSubStrategy(SetShareSubStrategyRequest {
+        group_name: args.group_name,
+        strategy: strategy.into(),
+    })
+}
+
 pub fn process_slow_sub_args(args: SlowSubArgs) -> MqttActionType {
     if args.is_enable.is_none() {
         if args.list.is_none() {
@@ -14,5 +14,6 @@
 
 pub mod file_utils;
 pub mod time_util;
+pub mod topic_trie;
 pub mod topic_util;
 pub mod vec_util;
@@ -0,0 +1,334 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::RwLock;
+
+const TOPIC_LEVEL_SEPARATOR: char = '/';
+const SINGLE_LEVEL_WILDCARD: &str = "+";
+const MULTI_LEVEL_WILDCARD: &str = "#";
+const SYSTEM_TOPIC_PREFIX: char = '$';
+
+/// Trie of MQTT topic names or topic filters, one node per topic level.
+///
+/// The same trie answers both directions of routing: which stored filters match a
+/// published topic ([`TopicTrie::match_topic`]) and which stored topics are matched by
+/// a subscription filter ([`TopicTrie::match_filter`]). Every path can hold several
+/// values, each identified by a key so that it can be removed again.
+///
+/// Following the MQTT spec, a filter starting with a wildcard does not match topics
+/// whose first level starts with `$`.
+pub struct TopicTrie<V> {
+    root: RwLock<TrieNode<V>>,
+}
+
+struct TrieNode<V> {
+    children: HashMap<String, TrieNode<V>>,
+    values: HashMap<String, V>,
+}
+
+impl<V> Default for TrieNode<V> {
+    fn default() -> Self {
+        TrieNode {
+            children: HashMap::new(),
+            values: HashMap::new(),
+        }
+    }
+}
+
+impl<V> TrieNode<V> {
+    fn is_empty(&self) -> bool {
+        self.children.is_empty() && self.values.is_empty()
+    }
+}
+
+impl<V: Clone> Default for TopicTrie<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V: Clone> TopicTrie<V> {
+    pub fn new() -> Self {
+        TopicTrie {
+            root: RwLock::new(TrieNode::default()),
+        }
+    }
+
+    pub fn insert(&self, path: &str, key: &str, value: V) {
+        let mut root = self.root.write().unwrap();
+        let mut node = &mut *root;
+        for level in path.split(TOPIC_LEVEL_SEPARATOR) {
+            node = node.children.entry(level.to_string()).or_default();
+        }
+        node.values.insert(key.to_string(), value);
+    }
+
+    pub fn remove(&self, path: &str, key: &str) -> Option<V> {
+        let levels: Vec<&str> = path.split(TOPIC_LEVEL_SEPARATOR).collect();
+        let mut root = self.root.write().unwrap();
+        remove_value(&mut root, &levels, key)
+    }
+
+    /// Values stored under the filters that match the topic name.
+    pub fn match_topic(&self, topic_name: &str) -> Vec<V> {
+        let levels: Vec<&str> = topic_name.split(TOPIC_LEVEL_SEPARATOR).collect();
+        let root = self.root.read().unwrap();
+        let mut results = Vec::new();
+        collect_by_topic(&root, &levels, true, &mut results);
+        results
+    }
+
+    /// Values stored under the topic names that the filter matches.
+    pub fn match_filter(&self, filter: &str) -> Vec<V> {
+        let levels: Vec<&str> = filter.split(TOPIC_LEVEL_SEPARATOR).collect();
+        let root = self.root.read().unwrap();
+        let mut results = Vec::new();
+        collect_by_filter(&root, &levels, true, &mut results);
+        results
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.root.read().unwrap().is_empty()
+    }
+}
+
+fn remove_value<V>(node: &mut TrieNode<V>, levels: &[&str], key: &str) -> Option<V> {
+    let Some((level, rest)) = levels.split_first() else {
+        return node.values.remove(key);
+    };
+
+    let child = node.children.get_mut(*level)?;
+    let value = remove_value(child, rest, key);
+    if child.is_empty() {
+        node.children.remove(*level);
+    }
+    value
+}
+
+fn collect_by_topic<V: Clone>(
+    node: &TrieNode<V>,
+    levels: &[&str],
+    is_first_level: bool,
+    results: &mut Vec<V>,
+) {
+    let skip_wildcard = is_first_level
+        && levels
+            .first()
+            .is_some_and(|level| level.starts_with(SYSTEM_TOPIC_PREFIX));
+
+    // "#" also matches the parent level, so it is checked before the levels run out
+    if !skip_wildcard {
+        if let Some(child) = node.children.get(MULTI_LEVEL_WILDCARD) {
+            results.extend(child.values.values().cloned());
+        }
+    }
+
+    let Some((level, rest)) = levels.split_first() else {
+        results.extend(node.values.values().cloned());
+        return;
+    };
+
+    if let Some(child) = node.children.get(*level) {
+        collect_by_topic(child, rest, false, results);
+    }
+
+    if !skip_wildcard {
+        if let Some(child) = node.children.get(SINGLE_LEVEL_WILDCARD) {
+            collect_by_topic(child, rest, false, results);
+        }
+    }
+}
+
+fn collect_by_filter<V: Clone>(
+    node: &TrieNode<V>,
+    levels: &[&str],
+    is_first_level: bool,
+    results: &mut Vec<V>,
+) {
+    let Some((level, rest)) = levels.split_first() else {
+        results.extend(node.values.values().cloned());
+        return;
+    };
+
+    match *level {
+        MULTI_LEVEL_WILDCARD => {
+            // "#" is only valid as the last level
+            if !rest.is_empty() {
+                return;
+            }
+            results.extend(node.values.values().cloned());
+            for (name, child) in node.children.iter() {
+                if is_first_level && name.starts_with(SYSTEM_TOPIC_PREFIX) {
+                    continue;
+                }
+                collect_all(child, results);
+            }
+        }
+        SINGLE_LEVEL_WILDCARD => {
+            for (name, child) in node.children.iter() {
+                if is_first_level && name.starts_with(SYSTEM_TOPIC_PREFIX) {
+                    continue;
+                }
+                collect_by_filter(child, rest, false, results);
+            }
+        }
+        _ => {
+            if let Some(child) = node.children.get(*level) {
+                collect_by_filter(child, rest, false, results);
+            }
+        }
+    }
+}
+
+fn collect_all<V: Clone>(node: &TrieNode<V>, results: &mut Vec<V>) {
+    results.extend(node.values.values().cloned());
+    for child in node.children.values() {
+        collect_all(child, results);
+    }
+}
+
+/// Whether the topic name matches the topic filter, level by level.
+pub fn topic_match_filter(topic_name: &str, filter: &str) -> bool {
+    if topic_name.starts_with(SYSTEM_TOPIC_PREFIX)
+        && (filter.starts_with(SINGLE_LEVEL_WILDCARD) || filter.starts_with(MULTI_LEVEL_WILDCARD))
+    {
+        return false;
+    }
+
+    let mut topic_levels = topic_name.split(TOPIC_LEVEL_SEPARATOR);
+    let mut filter_levels = filter.split(TOPIC_LEVEL_SEPARATOR).peekable();
+    while let Some(filter_level) = filter_levels.next() {
+        if filter_level == MULTI_LEVEL_WILDCARD {
+            return filter_levels.peek().is_none();
+        }
+
+        match topic_levels.next() {
+            Some(topic_level) => {
+                if filter_level != SINGLE_LEVEL_WILDCARD && filter_level != topic_level {
+                    return false;
+                }
+            }
+            None => return false,
+        }
+    }
+    topic_levels.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{topic_match_filter, TopicTrie};
+
+    fn sorted(mut values: Vec<String>) -> Vec<String> {
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn topic_match_filter_test() {
+        assert!(topic_match_filter(
+            "/sensor/1/temperature",
+            "/sensor/+/temperature"
+        ));
+        assert!(!topic_match_filter(
+            "/sensor/1/2/temperature",
+            "/sensor/+/temperature"
+        ));
+        assert!(!topic_match_filter(
+            "/sensor/temperature",
+            "/sensor/+/temperature"
+        ));
+        assert!(topic_match_filter("/sensor/1/temperature", "/sensor/#"));
+        assert!(topic_match_filter("/sensor", "/sensor/#"));
+        assert!(!topic_match_filter("/sensor/1", "/sensor/#/1"));
+        assert!(topic_match_filter("/sensor", "#"));
+        assert!(!topic_match_filter("$SYS/broker/uptime", "#"));
+        assert!(!topic_match_filter("$SYS/broker/uptime", "+/broker/uptime"));
+        assert!(topic_match_filter("$SYS/broker/uptime", "$SYS/#"));
+        assert!(!topic_match_filter("/sensor/1", "/sensor/1/2"));
+    }
+
+    #[test]
+    fn match_topic_test() {
+        let trie = TopicTrie::new();
+        for filter in [
+            "/sensor/1/temperature",
+            "/sensor/+/temperature",
+            "/sensor/#",
+            "#",
+            "+/+/+/temperature",
+            "$SYS/#",
+            "/other/+",
+        ] {
+            trie.insert(filter, filter, filter.to_string());
+        }
+
+        assert_eq!(
+            sorted(trie.match_topic("/sensor/1/temperature")),
+            vec![
+                "#",
+                "+/+/+/temperature",
+                "/sensor/#",
+                "/sensor/+/temperature",
+                "/sensor/1/temperature"
+            ]
+        );
+        assert_eq!(sorted(trie.match_topic("/sensor")), vec!["#", "/sensor/#"]);
+        assert_eq!(sorted(trie.match_topic("/other/1")), vec!["#", "/other/+"]);
+        assert_eq!(trie.match_topic("$SYS/broker/uptime"), vec!["$SYS/#"]);
+
+        assert_eq!(
+            trie.remove("/sensor/#", "/sensor/#"),
+            Some("/sensor/#".to_string())
+        );
+        assert_eq!(trie.remove("/sensor/#", "/sensor/#"), None);
+        assert_eq!(sorted(trie.match_topic("/sensor")), vec!["#"]);
+    }
+
+    #[test]
+    fn match_filter_test() {
+        let trie = TopicTrie::new();
+        for topic in [
+            "/sensor/1/temperature",
+            "/sensor/2/temperature",
+            "/sensor/2/humidity",
+            "/sensor",
+            "$SYS/broker/uptime",
+        ] {
+            trie.insert(topic, topic, topic.to_string());
+        }
+
+        assert_eq!(
+            sorted(trie.match_filter("/sensor/+/temperature")),
+            vec!["/sensor/1/temperature", "/sensor/2/temperature"]
+        );
+        assert_eq!(trie.match_filter("/sensor/#").len(), 4);
+        assert_eq!(trie.match_filter("#").len(), 4);
+        assert!(trie.match_filter("+/broker/uptime").is_empty());
+        assert_eq!(trie.match_filter("$SYS/+/uptime").len(), 1);
+        assert!(trie.match_filter("/sensor/#/temperature").is_empty());
+        assert_eq!(trie.match_filter("/sensor"), vec!["/sensor"]);
+
+        for topic in [
+            "/sensor/1/temperature",
+            "/sensor/2/temperature",
+            "/sensor/2/humidity",
+            "/sensor",
+            "$SYS/broker/uptime",
+        ] {
+            trie.remove(topic, topic);
+        }
+        assert!(trie.is_empty());
+    }
+}
@@ -17,6 +17,9 @@ const EXCLUSIVE_SUB_PREFIX: &str = "$exclusive";
 pub fn is_exclusive_sub(sub_path: &str) -> bool {
     sub_path.starts_with(EXCLUSIVE_SUB_PREFIX)
 }
+pub fn encode_exclusive_sub_path(topic_name: &str) -> String {
+    format!("{}{}", EXCLUSIVE_SUB_PREFIX, topic_name)
+}
 pub fn decode_exclusive_sub_path_to_topic_name(sub_path: &str) -> &str {
     if is_exclusive_sub(sub_path) {
         sub_path.trim_start_matches(EXCLUSIVE_SUB_PREFIX)
@@ -83,6 +86,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_encode_exclusive_sub_path() {
+        assert_eq!(encode_exclusive_sub_path("/topic"), "$exclusive/topic");
+        assert_eq!(
+            decode_exclusive_sub_path_to_topic_name(&encode_exclusive_sub_path("/another/topic")),
+            "/another/topic"
+        );
+    }
+
     #[test]
     fn test_topic_name_regex_match() {
         assert!(topic_name_regex_match("topic", "topic"));
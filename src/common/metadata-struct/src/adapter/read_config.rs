@@ -26,3 +26,13 @@ impl ReadConfig {
         }
     }
 }
+
+/// Records with a timestamp in `[start_timestamp, end_timestamp)`, optionally narrowed to a key and a tag.
+#[derive(Default, Clone, Debug)]
+pub struct ReadTimeRange {
+    pub start_timestamp: u64,
+    // 0 means unbounded
+    pub end_timestamp: u64,
+    pub key: Option<String>,
+    pub tag: Option<String>,
+}
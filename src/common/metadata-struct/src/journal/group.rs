@@ -12,13 +12,61 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+use std::str::FromStr;
+
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
+/// A consumer group of a namespace. The shards of the group are divided among its members,
+/// and every time a member joins or leaves, the shards are reassigned and the generation grows.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct JournalGroup {
-    namespace: String,
-    group_name: String,
-    shard_list: Vec<String>,
+    pub cluster_name: String,
+    pub namespace: String,
+    pub group_name: String,
+    pub strategy: JournalGroupAssignStrategy,
+    pub generation: u64,
+    pub shard_list: Vec<String>,
+    pub members: HashMap<String, JournalGroupMember>,
+    // member id -> shards assigned to the member
+    pub assignment: HashMap<String, Vec<String>>,
+}
+
+impl JournalGroup {
+    pub fn member_shards(&self, member_id: &str) -> Vec<String> {
+        if let Some(shards) = self.assignment.get(member_id) {
+            return shards.clone();
+        }
+        Vec::new()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct JournalGroupMember {
+    pub member_id: String,
+    pub session_timeout_ms: u64,
+    pub last_heartbeat_ms: u128,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JournalGroupAssignStrategy {
+    #[default]
+    Range,
+    RoundRobin,
+    Sticky,
+}
+
+impl FromStr for JournalGroupAssignStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "" | "range" => Ok(JournalGroupAssignStrategy::Range),
+            "round_robin" => Ok(JournalGroupAssignStrategy::RoundRobin),
+            "sticky" => Ok(JournalGroupAssignStrategy::Sticky),
+            _ => Err(format!("unknown group assign strategy {}", s)),
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -15,6 +15,7 @@
 pub mod group;
 pub mod namespace;
 pub mod node_extend;
+pub mod producer;
 pub mod segment;
 pub mod segment_meta;
 pub mod shard;
@@ -14,8 +14,29 @@
 
 use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Deserialize, Debug)]
-
+/// A namespace groups the shards of one tenant, and limits the resources they can use.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
 pub struct JournalNamespace {
-    namespace_name: String,
+    pub cluster_name: String,
+    pub namespace_name: String,
+    pub quota: JournalNamespaceQuota,
+    pub create_time: u128,
+}
+
+/// Resource limits of a namespace. A value of 0 means the corresponding limit is disabled.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct JournalNamespaceQuota {
+    // Maximum number of shards in the namespace
+    pub max_shard_num: u32,
+    // Maximum total size of the shards in the namespace, in bytes
+    pub max_storage_bytes: u64,
+    // Maximum bytes written to the namespace per second across the whole cluster,
+    // every journal server node admits an equal share of it
+    pub max_write_bytes_per_sec: u64,
+}
+
+impl JournalNamespace {
+    pub fn name(&self) -> String {
+        format!("{},{}", self.cluster_name, self.namespace_name)
+    }
 }
@@ -0,0 +1,40 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Sequence state of an idempotent producer on a shard. It is stored in the placement center,
+/// so that a retry is still recognized after the leader of the shard has changed.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct JournalProducerSequence {
+    pub cluster_name: String,
+    pub namespace: String,
+    pub shard_name: String,
+    pub producer_id: String,
+    pub last_pkid: u64,
+    // pkid -> offset of the most recent records written by the producer
+    pub last_batch: HashMap<u64, u64>,
+    pub update_time: u64,
+}
+
+impl JournalProducerSequence {
+    pub fn name(&self) -> String {
+        format!(
+            "{},{},{},{}",
+            self.cluster_name, self.namespace, self.shard_name, self.producer_id
+        )
+    }
+}
@@ -24,6 +24,9 @@ pub struct JournalSegmentMetadata {
     pub end_offset: i64,
     pub start_timestamp: i64,
     pub end_timestamp: i64,
+    // Size of the segment file in bytes, 0 when it has not been reported yet
+    #[serde(default)]
+    pub end_position: i64,
 }
 
 impl JournalSegmentMetadata {
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use protocol::journal_server::journal_engine::{CompressionType, DurabilityLevel};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Clone, Default)]
@@ -27,6 +28,102 @@ pub struct JournalShard {
     pub last_segment_seq: u32,
     pub status: JournalShardStatus,
     pub create_time: u128,
+    #[serde(default)]
+    pub config: JournalShardConfig,
+}
+
+/// Per-shard configuration: retention, compaction, compression and write durability.
+/// A retention limit of 0 means that limit is disabled.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct JournalShardConfig {
+    // Maximum age of a sealed segment, in seconds, measured from its end timestamp
+    pub retention_sec: u64,
+    // Maximum total size of the shard, in bytes
+    pub retention_bytes: u64,
+    // Maximum number of segments kept by the shard
+    pub retention_segment_num: u32,
+    // Sealed segments are rewritten to keep only the newest record per key
+    #[serde(default)]
+    pub enable_compact: bool,
+    // How long a tombstone (record with empty payload) is kept by compaction, in seconds
+    #[serde(default)]
+    pub tombstone_retention_sec: u64,
+    // Compression of each record content, used when the producer does not choose one
+    #[serde(default)]
+    pub compression: JournalCompressionType,
+    // When segment writes are fsynced before they are acknowledged
+    #[serde(default)]
+    pub durability: JournalDurabilityLevel,
+    // Used by FsyncInterval, 0 disables the corresponding trigger
+    #[serde(default)]
+    pub fsync_interval_ms: u64,
+    #[serde(default)]
+    pub fsync_bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JournalCompressionType {
+    #[default]
+    None,
+    Lz4,
+    Zstd,
+    Snappy,
+}
+
+impl From<JournalCompressionType> for CompressionType {
+    fn from(value: JournalCompressionType) -> Self {
+        match value {
+            JournalCompressionType::None => CompressionType::Uncompressed,
+            JournalCompressionType::Lz4 => CompressionType::Lz4,
+            JournalCompressionType::Zstd => CompressionType::Zstd,
+            JournalCompressionType::Snappy => CompressionType::Snappy,
+        }
+    }
+}
+
+impl From<CompressionType> for JournalCompressionType {
+    fn from(value: CompressionType) -> Self {
+        match value {
+            CompressionType::Uncompressed => JournalCompressionType::None,
+            CompressionType::Lz4 => JournalCompressionType::Lz4,
+            CompressionType::Zstd => JournalCompressionType::Zstd,
+            CompressionType::Snappy => JournalCompressionType::Snappy,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JournalDurabilityLevel {
+    #[default]
+    OsBuffered,
+    FsyncBatch,
+    FsyncInterval,
+}
+
+impl From<JournalDurabilityLevel> for DurabilityLevel {
+    fn from(value: JournalDurabilityLevel) -> Self {
+        match value {
+            JournalDurabilityLevel::OsBuffered => DurabilityLevel::OsBuffered,
+            JournalDurabilityLevel::FsyncBatch => DurabilityLevel::FsyncBatch,
+            JournalDurabilityLevel::FsyncInterval => DurabilityLevel::FsyncInterval,
+        }
+    }
+}
+
+impl From<DurabilityLevel> for JournalDurabilityLevel {
+    fn from(value: DurabilityLevel) -> Self {
+        match value {
+            DurabilityLevel::OsBuffered => JournalDurabilityLevel::OsBuffered,
+            DurabilityLevel::FsyncBatch => JournalDurabilityLevel::FsyncBatch,
+            DurabilityLevel::FsyncInterval => JournalDurabilityLevel::FsyncInterval,
+        }
+    }
+}
+
+impl JournalShardConfig {
+    pub fn enable_retention(&self) -> bool {
+        self.retention_sec > 0 || self.retention_bytes > 0 || self.retention_segment_num > 0
+    }
 }
 
 impl JournalShard {
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
+
 use protocol::mqtt::common::QoS;
 use serde::{Deserialize, Serialize};
 
@@ -23,6 +25,8 @@ pub struct MqttClusterDynamicConfig {
     pub security: MqttClusterDynamicConfigSecurity,
     pub network: MqttClusterDynamicConfigNetwork,
     pub slow: MqttClusterDynamicSlowSub,
+    #[serde(default)]
+    pub share_sub: MqttClusterDynamicShareSub,
 }
 
 // MQTT cluster protocol related dynamic configuration
@@ -75,6 +79,63 @@ pub struct MqttClusterDynamicSlowSub {
     pub response_ms: u32,
 }
 
+// How the leader of a shared subscription picks the group member that receives a message
+#[derive(Serialize, Deserialize, Default, Clone, Debug, PartialEq)]
+pub enum MqttShareSubStrategy {
+    #[default]
+    RoundRobin,
+    Random,
+    // Messages of the same publisher always go to the same member
+    HashClientId,
+    // Messages of the same topic always go to the same member
+    HashTopic,
+    // Keep sending to one member until it is no longer available
+    Sticky,
+    // The member with the fewest unacknowledged QoS 1/2 messages
+    LeastInflight,
+}
+
+// Shared subscription strategy of the cluster, group_strategy overrides it for single groups
+#[derive(Serialize, Deserialize, Default, Clone)]
+pub struct MqttClusterDynamicShareSub {
+    pub strategy: MqttShareSubStrategy,
+    pub group_strategy: HashMap<String, MqttShareSubStrategy>,
+}
+
+impl MqttClusterDynamicShareSub {
+    pub fn get_strategy(&self, group_name: &str) -> MqttShareSubStrategy {
+        if let Some(strategy) = self.group_strategy.get(group_name) {
+            return strategy.clone();
+        }
+        self.strategy.clone()
+    }
+
+    // An empty group name changes the cluster default, which applies to every group
+    // without a strategy of its own.
+    pub fn set_strategy(&mut self, group_name: &str, strategy: MqttShareSubStrategy) {
+        if group_name.is_empty() {
+            self.strategy = strategy;
+        } else {
+            self.group_strategy.insert(group_name.to_string(), strategy);
+        }
+    }
+}
+
+// The strategy of one shared subscription group as stored in the Placement Center, an empty
+// group_name is the cluster default
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MqttShareSubGroupStrategy {
+    pub cluster_name: String,
+    pub group_name: String,
+    pub strategy: MqttShareSubStrategy,
+}
+
+impl MqttShareSubGroupStrategy {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+}
+
 impl MqttClusterDynamicConfig {
     pub fn new() -> Self {
         MqttClusterDynamicConfig {
@@ -114,6 +175,7 @@ impl MqttClusterDynamicConfig {
                 internal_ms: 0,
                 response_ms: 0,
             },
+            share_sub: MqttClusterDynamicShareSub::default(),
         }
     }
 
@@ -131,11 +193,36 @@ pub enum AvailableFlag {
 
 #[cfg(test)]
 mod tests {
-    use crate::mqtt::cluster::AvailableFlag;
+    use crate::mqtt::cluster::{
+        AvailableFlag, MqttClusterDynamicConfig, MqttClusterDynamicShareSub, MqttShareSubStrategy,
+    };
 
     #[test]
     fn client34_connect_test() {
         assert_eq!(AvailableFlag::Disable as u8, 0);
         assert_eq!(AvailableFlag::Enable as u8, 1);
     }
+
+    #[test]
+    fn share_sub_strategy_test() {
+        let mut share_sub = MqttClusterDynamicShareSub::default();
+        assert_eq!(
+            share_sub.get_strategy("g1"),
+            MqttShareSubStrategy::RoundRobin
+        );
+
+        share_sub.set_strategy("", MqttShareSubStrategy::Random);
+        share_sub.set_strategy("g1", MqttShareSubStrategy::HashTopic);
+        assert_eq!(
+            share_sub.get_strategy("g1"),
+            MqttShareSubStrategy::HashTopic
+        );
+        assert_eq!(share_sub.get_strategy("g2"), MqttShareSubStrategy::Random);
+
+        // Configurations stored before the strategy existed still decode
+        let mut value = serde_json::to_value(MqttClusterDynamicConfig::new()).unwrap();
+        value.as_object_mut().unwrap().remove("share_sub");
+        let config = serde_json::from_value::<MqttClusterDynamicConfig>(value).unwrap();
+        assert_eq!(config.share_sub.strategy, MqttShareSubStrategy::RoundRobin);
+    }
 }
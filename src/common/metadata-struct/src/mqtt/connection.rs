@@ -39,7 +39,7 @@ pub struct MQTTConnection {
     pub client_max_receive_maximum: u16,
     // Record the connection dimension, the size of the maximum request packet that can be received.
     pub max_packet_size: u32,
-    // Record the maximum number of connection dimensions and topic aliases. The default value ranges from 0 to 65535
+    // The Topic Alias Maximum of the client, the highest alias the broker may use when publishing to it. 0 means no alias is accepted
     pub topic_alias_max: u16,
     // Flags whether to return a detailed error message to the client when an error occurs.
     pub request_problem_info: u8,
@@ -116,4 +116,18 @@ impl MQTTConnection {
     pub fn send_qos_message_decr(&self) {
         self.sender_qos_message.fetch_add(-1, Ordering::Relaxed);
     }
+
+    // Takes one entry of the client's Receive Maximum quota, returns false when the quota is used up.
+    pub fn try_send_qos_message_incr(&self) -> bool {
+        let receive_maximum = self.client_max_receive_maximum as isize;
+        self.sender_qos_message
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |num| {
+                if num < receive_maximum {
+                    Some(num + 1)
+                } else {
+                    None
+                }
+            })
+            .is_ok()
+    }
 }
@@ -0,0 +1,32 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+/// The lock behind a `$exclusive` subscription. It belongs to the session of `client_id` and is
+/// only valid while `broker_id` is still registered in the cluster.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct MqttExclusiveTopic {
+    pub cluster_name: String,
+    pub topic_name: String,
+    pub client_id: String,
+    pub broker_id: u64,
+    pub create_time: u64,
+}
+
+impl MqttExclusiveTopic {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+}
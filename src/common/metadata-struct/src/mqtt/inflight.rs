@@ -0,0 +1,51 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum MqttInflightState {
+    // QoS 1 Publish was sent, waiting for PubAck
+    WaitPubAck,
+    // QoS 2 Publish was sent, waiting for PubRec
+    WaitPubRec,
+    // QoS 2 PubRel was sent, waiting for PubComp
+    WaitPubComp,
+    // QoS 2 Publish was received from the client and PubRec was sent, waiting for PubRel
+    WaitPubRel,
+}
+
+/// A QoS 1/2 message the client has not completed yet. For outbound messages the message itself
+/// stays in the topic storage, `group_id`, `topic_id` and `offset` locate it for retransmission.
+/// Inbound messages are in the `WaitPubRel` state and only keep the packet identifier.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct MqttInflightMessage {
+    pub client_id: String,
+    pub pkid: u16,
+    pub state: MqttInflightState,
+    pub group_id: String,
+    pub topic_id: String,
+    pub offset: u64,
+    pub create_time: u64,
+}
+
+impl MqttInflightMessage {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+
+    pub fn is_inbound(&self) -> bool {
+        self.state == MqttInflightState::WaitPubRel
+    }
+}
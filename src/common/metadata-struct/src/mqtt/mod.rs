@@ -12,11 +12,16 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod auto_subscribe_rule;
 pub mod cluster;
 pub mod connection;
+pub mod exclusive_topic;
+pub mod inflight;
 pub mod lastwill;
 pub mod message;
 pub mod node_extend;
+pub mod rule;
+pub mod schema;
 pub mod session;
 pub mod topic;
 pub mod topic_rewrite_rule;
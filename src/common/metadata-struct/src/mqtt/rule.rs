@@ -0,0 +1,51 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use protocol::mqtt::common::QoS;
+use serde::{Deserialize, Serialize};
+
+/// A rule engine rule, `sql` selects and filters the messages published to the topics in its
+/// FROM clause and every result is handed to each of the `actions`.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct MqttRule {
+    pub cluster: String,
+    pub rule_name: String,
+    pub sql: String,
+    pub actions: Vec<MqttRuleAction>,
+    pub description: String,
+    pub create_time: u64,
+}
+
+/// There is no action that feeds a bridge yet. The crates under mqtt-bridge are still empty and
+/// no bridge can be created, so that action is left for when bridges exist.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum MqttRuleAction {
+    // Publish the result to another topic, `${field}` in the topic is replaced by the field of the result
+    Republish {
+        topic: String,
+        qos: QoS,
+        retain: bool,
+    },
+    // Append the result to a shard of the message storage
+    Journal {
+        namespace: String,
+        shard_name: String,
+    },
+}
+
+impl MqttRule {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+}
@@ -0,0 +1,65 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum MqttSchemaType {
+    // `schema` is a JSON Schema document
+    Json,
+    // `schema` is an encoded FileDescriptorSet, `message_name` is the full name of the message
+    Protobuf,
+    // `schema` is an Avro schema in JSON form, payloads are single Avro datums
+    Avro,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct MqttSchema {
+    pub cluster: String,
+    pub schema_name: String,
+    pub schema_type: MqttSchemaType,
+    pub schema: Vec<u8>,
+    pub message_name: String,
+    pub description: String,
+    pub create_time: u64,
+}
+
+impl MqttSchema {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+}
+
+/// What the broker does with a message whose payload does not match the bound schema.
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub enum MqttSchemaFailureStrategy {
+    // Reject the message, the publisher receives the Payload Format Invalid reason code
+    Reject,
+    // Accept the message and add a user property describing the validation error
+    Tag,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
+pub struct MqttSchemaBinding {
+    pub cluster: String,
+    pub schema_name: String,
+    pub topic_filter: String,
+    pub failure_strategy: MqttSchemaFailureStrategy,
+}
+
+impl MqttSchemaBinding {
+    pub fn encode(&self) -> Vec<u8> {
+        serde_json::to_vec(&self).unwrap()
+    }
+}
@@ -14,7 +14,13 @@
 
 use common_base::error::common::CommonError;
 use protocol::journal_server::journal_admin::{
+    CreateNamespaceReply, CreateNamespaceRequest, CreateShardReply, CreateShardRequest,
+    DeleteNamespaceReply, DeleteNamespaceRequest, DeleteShardReply, DeleteShardRequest,
+    DescribeSegmentReply, DescribeSegmentRequest, ListGroupOffsetReply, ListGroupOffsetRequest,
+    ListNamespaceReply, ListNamespaceRequest, ListSegmentMetaReply, ListSegmentMetaRequest,
     ListSegmentReply, ListSegmentRequest, ListShardReply, ListShardRequest,
+    ReassignSegmentReplicaReply, ReassignSegmentReplicaRequest, RebuildSegmentIndexReply,
+    RebuildSegmentIndexRequest, SealUpSegmentReply, SealUpSegmentRequest,
 };
 
 use crate::pool::ClientPool;
@@ -35,3 +41,91 @@ pub async fn journal_admin_list_segment(
 ) -> Result<ListSegmentReply, CommonError> {
     retry_call(client_pool, addrs, request).await
 }
+
+pub async fn journal_admin_describe_segment(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: DescribeSegmentRequest,
+) -> Result<DescribeSegmentReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn journal_admin_rebuild_segment_index(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: RebuildSegmentIndexRequest,
+) -> Result<RebuildSegmentIndexReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn journal_admin_sealup_segment(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: SealUpSegmentRequest,
+) -> Result<SealUpSegmentReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn journal_admin_reassign_segment_replica(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: ReassignSegmentReplicaRequest,
+) -> Result<ReassignSegmentReplicaReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn journal_admin_list_namespace(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: ListNamespaceRequest,
+) -> Result<ListNamespaceReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn journal_admin_create_namespace(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: CreateNamespaceRequest,
+) -> Result<CreateNamespaceReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn journal_admin_delete_namespace(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: DeleteNamespaceRequest,
+) -> Result<DeleteNamespaceReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn journal_admin_create_shard(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: CreateShardRequest,
+) -> Result<CreateShardReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn journal_admin_delete_shard(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: DeleteShardRequest,
+) -> Result<DeleteShardReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn journal_admin_list_segment_meta(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: ListSegmentMetaRequest,
+) -> Result<ListSegmentMetaReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn journal_admin_list_group_offset(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: ListGroupOffsetRequest,
+) -> Result<ListGroupOffsetReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
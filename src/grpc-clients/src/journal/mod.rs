@@ -14,7 +14,13 @@
 
 use protocol::journal_server::journal_admin::journal_server_admin_service_client::JournalServerAdminServiceClient;
 use protocol::journal_server::journal_admin::{
+    CreateNamespaceReply, CreateNamespaceRequest, CreateShardReply, CreateShardRequest,
+    DeleteNamespaceReply, DeleteNamespaceRequest, DeleteShardReply, DeleteShardRequest,
+    DescribeSegmentReply, DescribeSegmentRequest, ListGroupOffsetReply, ListGroupOffsetRequest,
+    ListNamespaceReply, ListNamespaceRequest, ListSegmentMetaReply, ListSegmentMetaRequest,
     ListSegmentReply, ListSegmentRequest, ListShardReply, ListShardRequest,
+    ReassignSegmentReplicaReply, ReassignSegmentReplicaRequest, RebuildSegmentIndexReply,
+    RebuildSegmentIndexRequest, SealUpSegmentReply, SealUpSegmentRequest,
 };
 use protocol::journal_server::journal_inner::journal_server_inner_service_client::JournalServerInnerServiceClient;
 use protocol::journal_server::journal_inner::{
@@ -84,3 +90,91 @@ impl_retriable_request!(
     journal_admin_services_client,
     list_segment
 );
+
+impl_retriable_request!(
+    DescribeSegmentRequest,
+    JournalServerAdminServiceClient<Channel>,
+    DescribeSegmentReply,
+    journal_admin_services_client,
+    describe_segment
+);
+
+impl_retriable_request!(
+    RebuildSegmentIndexRequest,
+    JournalServerAdminServiceClient<Channel>,
+    RebuildSegmentIndexReply,
+    journal_admin_services_client,
+    rebuild_segment_index
+);
+
+impl_retriable_request!(
+    SealUpSegmentRequest,
+    JournalServerAdminServiceClient<Channel>,
+    SealUpSegmentReply,
+    journal_admin_services_client,
+    seal_up_segment
+);
+
+impl_retriable_request!(
+    ReassignSegmentReplicaRequest,
+    JournalServerAdminServiceClient<Channel>,
+    ReassignSegmentReplicaReply,
+    journal_admin_services_client,
+    reassign_segment_replica
+);
+
+impl_retriable_request!(
+    ListNamespaceRequest,
+    JournalServerAdminServiceClient<Channel>,
+    ListNamespaceReply,
+    journal_admin_services_client,
+    list_namespace
+);
+
+impl_retriable_request!(
+    CreateNamespaceRequest,
+    JournalServerAdminServiceClient<Channel>,
+    CreateNamespaceReply,
+    journal_admin_services_client,
+    create_namespace
+);
+
+impl_retriable_request!(
+    DeleteNamespaceRequest,
+    JournalServerAdminServiceClient<Channel>,
+    DeleteNamespaceReply,
+    journal_admin_services_client,
+    delete_namespace
+);
+
+impl_retriable_request!(
+    CreateShardRequest,
+    JournalServerAdminServiceClient<Channel>,
+    CreateShardReply,
+    journal_admin_services_client,
+    create_shard
+);
+
+impl_retriable_request!(
+    DeleteShardRequest,
+    JournalServerAdminServiceClient<Channel>,
+    DeleteShardReply,
+    journal_admin_services_client,
+    delete_shard
+);
+
+impl_retriable_request!(
+    ListSegmentMetaRequest,
+    JournalServerAdminServiceClient<Channel>,
+    ListSegmentMetaReply,
+    journal_admin_services_client,
+    list_segment_meta
+);
+
+impl_retriable_request!(
+    ListGroupOffsetRequest,
+    JournalServerAdminServiceClient<Channel>,
+    ListGroupOffsetReply,
+    journal_admin_services_client,
+    list_group_offset
+);
@@ -14,14 +14,22 @@
 
 use common_base::error::common::CommonError;
 use protocol::broker_mqtt::broker_mqtt_admin::{
-    ClusterStatusReply, ClusterStatusRequest, CreateAclReply, CreateAclRequest,
-    CreateBlacklistReply, CreateBlacklistRequest, CreateTopicRewriteRuleReply,
+    BindSchemaReply, BindSchemaRequest, ClusterStatusReply, ClusterStatusRequest, CreateAclReply,
+    CreateAclRequest, CreateBlacklistReply, CreateBlacklistRequest, CreateRuleReply,
+    CreateRuleRequest, CreateSchemaReply, CreateSchemaRequest, CreateTopicRewriteRuleReply,
     CreateTopicRewriteRuleRequest, CreateUserReply, CreateUserRequest, DeleteAclReply,
-    DeleteAclRequest, DeleteBlacklistReply, DeleteBlacklistRequest, DeleteTopicRewriteRuleReply,
+    DeleteAclRequest, DeleteAutoSubscribeRuleReply, DeleteAutoSubscribeRuleRequest,
+    DeleteBlacklistReply, DeleteBlacklistRequest, DeleteRuleReply, DeleteRuleRequest,
+    DeleteSchemaReply, DeleteSchemaRequest, DeleteTopicRewriteRuleReply,
     DeleteTopicRewriteRuleRequest, DeleteUserReply, DeleteUserRequest, EnableSlowSubScribeReply,
-    EnableSlowSubscribeRequest, ListAclReply, ListAclRequest, ListBlacklistReply,
-    ListBlacklistRequest, ListConnectionReply, ListConnectionRequest, ListSlowSubscribeReply,
-    ListSlowSubscribeRequest, ListTopicReply, ListTopicRequest, ListUserReply, ListUserRequest,
+    EnableSlowSubscribeRequest, ListAclReply, ListAclRequest, ListAutoSubscribeRuleReply,
+    ListAutoSubscribeRuleRequest, ListBlacklistReply, ListBlacklistRequest, ListConnectionReply,
+    ListConnectionRequest, ListExclusiveTopicReply, ListExclusiveTopicRequest, ListRuleReply,
+    ListRuleRequest, ListSchemaBindingReply, ListSchemaBindingRequest, ListSchemaReply,
+    ListSchemaRequest, ListSlowSubscribeReply, ListSlowSubscribeRequest, ListTopicReply,
+    ListTopicRequest, ListUserReply, ListUserRequest, ReleaseExclusiveTopicReply,
+    ReleaseExclusiveTopicRequest, SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest,
+    SetShareSubStrategyReply, SetShareSubStrategyRequest, UnbindSchemaReply, UnbindSchemaRequest,
 };
 
 use crate::pool::ClientPool;
@@ -160,3 +168,128 @@ pub async fn mqtt_broker_delete_topic_rewrite_rule(
 ) -> Result<DeleteTopicRewriteRuleReply, CommonError> {
     retry_call(client_pool, addrs, request).await
 }
+
+// ------ exclusive topic -------
+pub async fn mqtt_broker_list_exclusive_topic(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: ListExclusiveTopicRequest,
+) -> Result<ListExclusiveTopicReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn mqtt_broker_release_exclusive_topic(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: ReleaseExclusiveTopicRequest,
+) -> Result<ReleaseExclusiveTopicReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+// ------ auto subscribe rule -------
+pub async fn mqtt_broker_set_auto_subscribe_rule(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: SetAutoSubscribeRuleRequest,
+) -> Result<SetAutoSubscribeRuleReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn mqtt_broker_delete_auto_subscribe_rule(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: DeleteAutoSubscribeRuleRequest,
+) -> Result<DeleteAutoSubscribeRuleReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn mqtt_broker_list_auto_subscribe_rule(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: ListAutoSubscribeRuleRequest,
+) -> Result<ListAutoSubscribeRuleReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+// ------ rule engine -------
+pub async fn mqtt_broker_create_rule(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: CreateRuleRequest,
+) -> Result<CreateRuleReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn mqtt_broker_delete_rule(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: DeleteRuleRequest,
+) -> Result<DeleteRuleReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn mqtt_broker_list_rule(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: ListRuleRequest,
+) -> Result<ListRuleReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+// ------ schema registry -------
+pub async fn mqtt_broker_create_schema(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: CreateSchemaRequest,
+) -> Result<CreateSchemaReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn mqtt_broker_delete_schema(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: DeleteSchemaRequest,
+) -> Result<DeleteSchemaReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn mqtt_broker_list_schema(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: ListSchemaRequest,
+) -> Result<ListSchemaReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn mqtt_broker_bind_schema(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: BindSchemaRequest,
+) -> Result<BindSchemaReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn mqtt_broker_unbind_schema(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: UnbindSchemaRequest,
+) -> Result<UnbindSchemaReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+pub async fn mqtt_broker_list_schema_binding(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: ListSchemaBindingRequest,
+) -> Result<ListSchemaBindingReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
+
+// --------- shared subscription --------
+pub async fn mqtt_broker_set_share_sub_strategy(
+    client_pool: &ClientPool,
+    addrs: &[impl AsRef<str>],
+    request: SetShareSubStrategyRequest,
+) -> Result<SetShareSubStrategyReply, CommonError> {
+    retry_call(client_pool, addrs, request).await
+}
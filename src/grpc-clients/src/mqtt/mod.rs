@@ -14,14 +14,22 @@
 
 use protocol::broker_mqtt::broker_mqtt_admin::mqtt_broker_admin_service_client::MqttBrokerAdminServiceClient;
 use protocol::broker_mqtt::broker_mqtt_admin::{
-    ClusterStatusReply, ClusterStatusRequest, CreateAclReply, CreateAclRequest,
-    CreateBlacklistReply, CreateBlacklistRequest, CreateTopicRewriteRuleReply,
+    BindSchemaReply, BindSchemaRequest, ClusterStatusReply, ClusterStatusRequest, CreateAclReply,
+    CreateAclRequest, CreateBlacklistReply, CreateBlacklistRequest, CreateRuleReply,
+    CreateRuleRequest, CreateSchemaReply, CreateSchemaRequest, CreateTopicRewriteRuleReply,
     CreateTopicRewriteRuleRequest, CreateUserReply, CreateUserRequest, DeleteAclReply,
-    DeleteAclRequest, DeleteBlacklistReply, DeleteBlacklistRequest, DeleteTopicRewriteRuleReply,
+    DeleteAclRequest, DeleteAutoSubscribeRuleReply, DeleteAutoSubscribeRuleRequest,
+    DeleteBlacklistReply, DeleteBlacklistRequest, DeleteRuleReply, DeleteRuleRequest,
+    DeleteSchemaReply, DeleteSchemaRequest, DeleteTopicRewriteRuleReply,
     DeleteTopicRewriteRuleRequest, DeleteUserReply, DeleteUserRequest, EnableSlowSubScribeReply,
-    EnableSlowSubscribeRequest, ListAclReply, ListAclRequest, ListBlacklistReply,
-    ListBlacklistRequest, ListConnectionReply, ListConnectionRequest, ListSlowSubscribeReply,
-    ListSlowSubscribeRequest, ListTopicReply, ListTopicRequest, ListUserReply, ListUserRequest,
+    EnableSlowSubscribeRequest, ListAclReply, ListAclRequest, ListAutoSubscribeRuleReply,
+    ListAutoSubscribeRuleRequest, ListBlacklistReply, ListBlacklistRequest, ListConnectionReply,
+    ListConnectionRequest, ListExclusiveTopicReply, ListExclusiveTopicRequest, ListRuleReply,
+    ListRuleRequest, ListSchemaBindingReply, ListSchemaBindingRequest, ListSchemaReply,
+    ListSchemaRequest, ListSlowSubscribeReply, ListSlowSubscribeRequest, ListTopicReply,
+    ListTopicRequest, ListUserReply, ListUserRequest, ReleaseExclusiveTopicReply,
+    ReleaseExclusiveTopicRequest, SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest,
+    SetShareSubStrategyReply, SetShareSubStrategyRequest, UnbindSchemaReply, UnbindSchemaRequest,
 };
 use protocol::broker_mqtt::broker_mqtt_inner::mqtt_broker_inner_service_client::MqttBrokerInnerServiceClient;
 use protocol::broker_mqtt::broker_mqtt_inner::{
@@ -187,5 +195,125 @@ impl_retriable_request!(
     mqtt_broker_delete_topic_rewrite_rule
 );
 
+impl_retriable_request!(
+    ListExclusiveTopicRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    ListExclusiveTopicReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_list_exclusive_topic
+);
+
+impl_retriable_request!(
+    ReleaseExclusiveTopicRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    ReleaseExclusiveTopicReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_release_exclusive_topic
+);
+
+impl_retriable_request!(
+    SetAutoSubscribeRuleRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    SetAutoSubscribeRuleReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_set_auto_subscribe_rule
+);
+
+impl_retriable_request!(
+    DeleteAutoSubscribeRuleRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    DeleteAutoSubscribeRuleReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_delete_auto_subscribe_rule
+);
+
+impl_retriable_request!(
+    ListAutoSubscribeRuleRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    ListAutoSubscribeRuleReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_list_auto_subscribe_rule
+);
+
+impl_retriable_request!(
+    CreateRuleRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    CreateRuleReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_create_rule
+);
+
+impl_retriable_request!(
+    DeleteRuleRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    DeleteRuleReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_delete_rule
+);
+
+impl_retriable_request!(
+    ListRuleRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    ListRuleReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_list_rule
+);
+
+impl_retriable_request!(
+    CreateSchemaRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    CreateSchemaReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_create_schema
+);
+
+impl_retriable_request!(
+    DeleteSchemaRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    DeleteSchemaReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_delete_schema
+);
+
+impl_retriable_request!(
+    ListSchemaRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    ListSchemaReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_list_schema
+);
+
+impl_retriable_request!(
+    BindSchemaRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    BindSchemaReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_bind_schema
+);
+
+impl_retriable_request!(
+    UnbindSchemaRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    UnbindSchemaReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_unbind_schema
+);
+
+impl_retriable_request!(
+    ListSchemaBindingRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    ListSchemaBindingReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_list_schema_binding
+);
+
+impl_retriable_request!(
+    SetShareSubStrategyRequest,
+    MqttBrokerAdminServiceClient<Channel>,
+    SetShareSubStrategyReply,
+    mqtt_broker_admin_services_client,
+    mqtt_broker_set_share_sub_strategy
+);
+
 #[cfg(test)]
 mod tests {}
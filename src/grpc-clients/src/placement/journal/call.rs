@@ -14,10 +14,15 @@
 
 use common_base::error::common::CommonError;
 use protocol::placement_center::placement_center_journal::{
+    CommitGroupOffsetReply, CommitGroupOffsetRequest, CreateNamespaceReply, CreateNamespaceRequest,
     CreateNextSegmentReply, CreateNextSegmentRequest, CreateShardReply, CreateShardRequest,
-    DeleteSegmentReply, DeleteSegmentRequest, DeleteShardReply, DeleteShardRequest,
-    ListSegmentMetaReply, ListSegmentMetaRequest, ListSegmentReply, ListSegmentRequest,
-    ListShardReply, ListShardRequest, UpdateSegmentMetaReply, UpdateSegmentMetaRequest,
+    DeleteNamespaceReply, DeleteNamespaceRequest, DeleteSegmentReply, DeleteSegmentRequest,
+    DeleteShardReply, DeleteShardRequest, GetProducerSequenceReply, GetProducerSequenceRequest,
+    GroupHeartbeatReply, GroupHeartbeatRequest, JoinGroupReply, JoinGroupRequest, LeaveGroupReply,
+    LeaveGroupRequest, ListNamespaceReply, ListNamespaceRequest, ListSegmentMetaReply,
+    ListSegmentMetaRequest, ListSegmentReply, ListSegmentRequest, ListShardReply, ListShardRequest,
+    SetProducerSequenceReply, SetProducerSequenceRequest, UpdateSegmentMetaReply,
+    UpdateSegmentMetaRequest, UpdateSegmentReplicaReply, UpdateSegmentReplicaRequest,
     UpdateSegmentStatusReply, UpdateSegmentStatusRequest,
 };
 
@@ -72,6 +77,12 @@ generate_journal_service_call!(
     UpdateSegmentStatusReply,
     UpdateSegmentStatus
 );
+generate_journal_service_call!(
+    update_segment_replica,
+    UpdateSegmentReplicaRequest,
+    UpdateSegmentReplicaReply,
+    UpdateSegmentReplica
+);
 generate_journal_service_call!(
     list_segment_meta,
     ListSegmentMetaRequest,
@@ -84,3 +95,47 @@ generate_journal_service_call!(
     UpdateSegmentMetaReply,
     UpdateSegmentMeta
 );
+generate_journal_service_call!(join_group, JoinGroupRequest, JoinGroupReply, JoinGroup);
+generate_journal_service_call!(
+    group_heartbeat,
+    GroupHeartbeatRequest,
+    GroupHeartbeatReply,
+    GroupHeartbeat
+);
+generate_journal_service_call!(leave_group, LeaveGroupRequest, LeaveGroupReply, LeaveGroup);
+generate_journal_service_call!(
+    commit_group_offset,
+    CommitGroupOffsetRequest,
+    CommitGroupOffsetReply,
+    CommitGroupOffset
+);
+generate_journal_service_call!(
+    create_namespace,
+    CreateNamespaceRequest,
+    CreateNamespaceReply,
+    CreateNamespace
+);
+generate_journal_service_call!(
+    list_namespace,
+    ListNamespaceRequest,
+    ListNamespaceReply,
+    ListNamespace
+);
+generate_journal_service_call!(
+    delete_namespace,
+    DeleteNamespaceRequest,
+    DeleteNamespaceReply,
+    DeleteNamespace
+);
+generate_journal_service_call!(
+    set_producer_sequence,
+    SetProducerSequenceRequest,
+    SetProducerSequenceReply,
+    SetProducerSequence
+);
+generate_journal_service_call!(
+    get_producer_sequence,
+    GetProducerSequenceRequest,
+    GetProducerSequenceReply,
+    GetProducerSequence
+);
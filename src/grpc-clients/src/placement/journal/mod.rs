@@ -16,10 +16,15 @@ use common_base::error::common::CommonError;
 use mobc::Manager;
 use protocol::placement_center::placement_center_journal::engine_service_client::EngineServiceClient;
 use protocol::placement_center::placement_center_journal::{
+    CommitGroupOffsetReply, CommitGroupOffsetRequest, CreateNamespaceReply, CreateNamespaceRequest,
     CreateNextSegmentReply, CreateNextSegmentRequest, CreateShardReply, CreateShardRequest,
-    DeleteSegmentReply, DeleteSegmentRequest, DeleteShardReply, DeleteShardRequest,
-    ListSegmentMetaReply, ListSegmentMetaRequest, ListSegmentReply, ListSegmentRequest,
-    ListShardReply, ListShardRequest, UpdateSegmentMetaReply, UpdateSegmentMetaRequest,
+    DeleteNamespaceReply, DeleteNamespaceRequest, DeleteSegmentReply, DeleteSegmentRequest,
+    DeleteShardReply, DeleteShardRequest, GetProducerSequenceReply, GetProducerSequenceRequest,
+    GroupHeartbeatReply, GroupHeartbeatRequest, JoinGroupReply, JoinGroupRequest, LeaveGroupReply,
+    LeaveGroupRequest, ListNamespaceReply, ListNamespaceRequest, ListSegmentMetaReply,
+    ListSegmentMetaRequest, ListSegmentReply, ListSegmentRequest, ListShardReply, ListShardRequest,
+    SetProducerSequenceReply, SetProducerSequenceRequest, UpdateSegmentMetaReply,
+    UpdateSegmentMetaRequest, UpdateSegmentReplicaReply, UpdateSegmentReplicaRequest,
     UpdateSegmentStatusReply, UpdateSegmentStatusRequest,
 };
 use tonic::transport::Channel;
@@ -127,6 +132,15 @@ impl_retriable_request!(
     true
 );
 
+impl_retriable_request!(
+    UpdateSegmentReplicaRequest,
+    EngineServiceClient<Channel>,
+    UpdateSegmentReplicaReply,
+    placement_center_journal_services_client,
+    update_segment_replica,
+    true
+);
+
 impl_retriable_request!(
     ListSegmentMetaRequest,
     EngineServiceClient<Channel>,
@@ -144,3 +158,84 @@ impl_retriable_request!(
     update_segment_meta,
     true
 );
+
+impl_retriable_request!(
+    JoinGroupRequest,
+    EngineServiceClient<Channel>,
+    JoinGroupReply,
+    placement_center_journal_services_client,
+    join_group,
+    true
+);
+
+impl_retriable_request!(
+    GroupHeartbeatRequest,
+    EngineServiceClient<Channel>,
+    GroupHeartbeatReply,
+    placement_center_journal_services_client,
+    group_heartbeat,
+    true
+);
+
+impl_retriable_request!(
+    LeaveGroupRequest,
+    EngineServiceClient<Channel>,
+    LeaveGroupReply,
+    placement_center_journal_services_client,
+    leave_group,
+    true
+);
+
+impl_retriable_request!(
+    CommitGroupOffsetRequest,
+    EngineServiceClient<Channel>,
+    CommitGroupOffsetReply,
+    placement_center_journal_services_client,
+    commit_group_offset,
+    true
+);
+
+impl_retriable_request!(
+    CreateNamespaceRequest,
+    EngineServiceClient<Channel>,
+    CreateNamespaceReply,
+    placement_center_journal_services_client,
+    create_namespace,
+    true
+);
+
+impl_retriable_request!(
+    ListNamespaceRequest,
+    EngineServiceClient<Channel>,
+    ListNamespaceReply,
+    placement_center_journal_services_client,
+    list_namespace,
+    true
+);
+
+impl_retriable_request!(
+    DeleteNamespaceRequest,
+    EngineServiceClient<Channel>,
+    DeleteNamespaceReply,
+    placement_center_journal_services_client,
+    delete_namespace,
+    true
+);
+
+impl_retriable_request!(
+    SetProducerSequenceRequest,
+    EngineServiceClient<Channel>,
+    SetProducerSequenceReply,
+    placement_center_journal_services_client,
+    set_producer_sequence,
+    true
+);
+
+impl_retriable_request!(
+    GetProducerSequenceRequest,
+    EngineServiceClient<Channel>,
+    GetProducerSequenceReply,
+    placement_center_journal_services_client,
+    get_producer_sequence,
+    true
+);
@@ -41,8 +41,16 @@ pub enum PlacementCenterInterface {
     CreateSegment,
     DeleteSegment,
     UpdateSegmentStatus,
+    UpdateSegmentReplica,
     ListSegmentMeta,
     UpdateSegmentMeta,
+    JoinGroup,
+    GroupHeartbeat,
+    LeaveGroup,
+    CommitGroupOffset,
+    CreateNamespace,
+    ListNamespace,
+    DeleteNamespace,
 
     // mqtt service interface
     GetShareSubLeader,
@@ -14,19 +14,28 @@
 
 use common_base::error::common::CommonError;
 use protocol::placement_center::placement_center_mqtt::{
+    BatchInflightMessageReply, BatchInflightMessageRequest, BindSchemaReply, BindSchemaRequest,
     CreateAclReply, CreateAclRequest, CreateBlacklistReply, CreateBlacklistRequest,
-    CreateSessionReply, CreateSessionRequest, CreateTopicReply, CreateTopicRequest,
-    CreateTopicRewriteRuleReply, CreateTopicRewriteRuleRequest, CreateUserReply, CreateUserRequest,
-    DeleteAclReply, DeleteAclRequest, DeleteBlacklistReply, DeleteBlacklistRequest,
-    DeleteExclusiveTopicReply, DeleteExclusiveTopicRequest, DeleteSessionReply,
-    DeleteSessionRequest, DeleteTopicReply, DeleteTopicRequest, DeleteTopicRewriteRuleReply,
-    DeleteTopicRewriteRuleRequest, DeleteUserReply, DeleteUserRequest, GetShareSubLeaderReply,
-    GetShareSubLeaderRequest, ListAclReply, ListAclRequest, ListBlacklistReply,
-    ListBlacklistRequest, ListSessionReply, ListSessionRequest, ListTopicReply, ListTopicRequest,
-    ListTopicRewriteRuleReply, ListTopicRewriteRuleRequest, ListUserReply, ListUserRequest,
-    SaveLastWillMessageReply, SaveLastWillMessageRequest, SetExclusiveTopicReply,
-    SetExclusiveTopicRequest, SetTopicRetainMessageReply, SetTopicRetainMessageRequest,
-    UpdateSessionReply, UpdateSessionRequest,
+    CreateRuleReply, CreateRuleRequest, CreateSchemaReply, CreateSchemaRequest, CreateSessionReply,
+    CreateSessionRequest, CreateTopicReply, CreateTopicRequest, CreateTopicRewriteRuleReply,
+    CreateTopicRewriteRuleRequest, CreateUserReply, CreateUserRequest, DeleteAclReply,
+    DeleteAclRequest, DeleteAutoSubscribeRuleReply, DeleteAutoSubscribeRuleRequest,
+    DeleteBlacklistReply, DeleteBlacklistRequest, DeleteExclusiveTopicReply,
+    DeleteExclusiveTopicRequest, DeleteRuleReply, DeleteRuleRequest, DeleteSchemaReply,
+    DeleteSchemaRequest, DeleteSessionReply, DeleteSessionRequest, DeleteTopicReply,
+    DeleteTopicRequest, DeleteTopicRewriteRuleReply, DeleteTopicRewriteRuleRequest,
+    DeleteUserReply, DeleteUserRequest, GetShareSubLeaderReply, GetShareSubLeaderRequest,
+    ListAclReply, ListAclRequest, ListAutoSubscribeRuleReply, ListAutoSubscribeRuleRequest,
+    ListBlacklistReply, ListBlacklistRequest, ListExclusiveTopicReply, ListExclusiveTopicRequest,
+    ListInflightMessageReply, ListInflightMessageRequest, ListRuleReply, ListRuleRequest,
+    ListSchemaBindingReply, ListSchemaBindingRequest, ListSchemaReply, ListSchemaRequest,
+    ListSessionReply, ListSessionRequest, ListShareSubStrategyReply, ListShareSubStrategyRequest,
+    ListTopicReply, ListTopicRequest, ListTopicRewriteRuleReply, ListTopicRewriteRuleRequest,
+    ListUserReply, ListUserRequest, SaveLastWillMessageReply, SaveLastWillMessageRequest,
+    SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest, SetExclusiveTopicReply,
+    SetExclusiveTopicRequest, SetShareSubStrategyReply, SetShareSubStrategyRequest,
+    SetTopicRetainMessageReply, SetTopicRetainMessageRequest, UnbindSchemaReply,
+    UnbindSchemaRequest, UpdateSessionReply, UpdateSessionRequest,
 };
 
 use crate::pool::ClientPool;
@@ -103,6 +112,12 @@ generate_mqtt_service_call!(
     DeleteExclusiveTopicReply,
     DeleteExclusiveTopic
 );
+generate_mqtt_service_call!(
+    placement_list_exclusive_topic,
+    ListExclusiveTopicRequest,
+    ListExclusiveTopicReply,
+    ListExclusiveTopic
+);
 generate_mqtt_service_call!(
     placement_create_session,
     CreateSessionRequest,
@@ -172,3 +187,99 @@ generate_mqtt_service_call!(
     DeleteTopicRewriteRuleReply,
     DeleteTopicRewriteRule
 );
+generate_mqtt_service_call!(
+    placement_set_auto_subscribe_rule,
+    SetAutoSubscribeRuleRequest,
+    SetAutoSubscribeRuleReply,
+    SetAutoSubscribeRule
+);
+generate_mqtt_service_call!(
+    placement_delete_auto_subscribe_rule,
+    DeleteAutoSubscribeRuleRequest,
+    DeleteAutoSubscribeRuleReply,
+    DeleteAutoSubscribeRule
+);
+generate_mqtt_service_call!(
+    placement_list_auto_subscribe_rule,
+    ListAutoSubscribeRuleRequest,
+    ListAutoSubscribeRuleReply,
+    ListAutoSubscribeRule
+);
+generate_mqtt_service_call!(
+    placement_create_rule,
+    CreateRuleRequest,
+    CreateRuleReply,
+    CreateRule
+);
+generate_mqtt_service_call!(
+    placement_delete_rule,
+    DeleteRuleRequest,
+    DeleteRuleReply,
+    DeleteRule
+);
+generate_mqtt_service_call!(
+    placement_list_rule,
+    ListRuleRequest,
+    ListRuleReply,
+    ListRule
+);
+generate_mqtt_service_call!(
+    placement_create_schema,
+    CreateSchemaRequest,
+    CreateSchemaReply,
+    CreateSchema
+);
+generate_mqtt_service_call!(
+    placement_delete_schema,
+    DeleteSchemaRequest,
+    DeleteSchemaReply,
+    DeleteSchema
+);
+generate_mqtt_service_call!(
+    placement_list_schema,
+    ListSchemaRequest,
+    ListSchemaReply,
+    ListSchema
+);
+generate_mqtt_service_call!(
+    placement_bind_schema,
+    BindSchemaRequest,
+    BindSchemaReply,
+    BindSchema
+);
+generate_mqtt_service_call!(
+    placement_unbind_schema,
+    UnbindSchemaRequest,
+    UnbindSchemaReply,
+    UnbindSchema
+);
+generate_mqtt_service_call!(
+    placement_list_schema_binding,
+    ListSchemaBindingRequest,
+    ListSchemaBindingReply,
+    ListSchemaBinding
+);
+generate_mqtt_service_call!(
+    placement_batch_inflight_message,
+    BatchInflightMessageRequest,
+    BatchInflightMessageReply,
+    BatchInflightMessage
+);
+generate_mqtt_service_call!(
+    placement_list_inflight_message,
+    ListInflightMessageRequest,
+    ListInflightMessageReply,
+    ListInflightMessage
+);
+generate_mqtt_service_call!(
+    placement_set_share_sub_strategy,
+    SetShareSubStrategyRequest,
+    SetShareSubStrategyReply,
+    SetShareSubStrategy
+);
+generate_mqtt_service_call!(
+    placement_list_share_sub_strategy,
+    ListShareSubStrategyRequest,
+    ListShareSubStrategyReply,
+    ListShareSubStrategy
+);
@@ -16,19 +16,28 @@ use common_base::error::common::CommonError;
 use mobc::Manager;
 use protocol::placement_center::placement_center_mqtt::mqtt_service_client::MqttServiceClient;
 use protocol::placement_center::placement_center_mqtt::{
+    BatchInflightMessageReply, BatchInflightMessageRequest, BindSchemaReply, BindSchemaRequest,
     CreateAclReply, CreateAclRequest, CreateBlacklistReply, CreateBlacklistRequest,
-    CreateSessionReply, CreateSessionRequest, CreateTopicReply, CreateTopicRequest,
-    CreateTopicRewriteRuleReply, CreateTopicRewriteRuleRequest, CreateUserReply, CreateUserRequest,
-    DeleteAclReply, DeleteAclRequest, DeleteBlacklistReply, DeleteBlacklistRequest,
-    DeleteExclusiveTopicReply, DeleteExclusiveTopicRequest, DeleteSessionReply,
-    DeleteSessionRequest, DeleteTopicReply, DeleteTopicRequest, DeleteTopicRewriteRuleReply,
-    DeleteTopicRewriteRuleRequest, DeleteUserReply, DeleteUserRequest, GetShareSubLeaderReply,
-    GetShareSubLeaderRequest, ListAclReply, ListAclRequest, ListBlacklistReply,
-    ListBlacklistRequest, ListSessionReply, ListSessionRequest, ListTopicReply, ListTopicRequest,
-    ListTopicRewriteRuleReply, ListTopicRewriteRuleRequest, ListUserReply, ListUserRequest,
-    SaveLastWillMessageReply, SaveLastWillMessageRequest, SetExclusiveTopicReply,
-    SetExclusiveTopicRequest, SetTopicRetainMessageReply, SetTopicRetainMessageRequest,
-    UpdateSessionReply, UpdateSessionRequest,
+    CreateRuleReply, CreateRuleRequest, CreateSchemaReply, CreateSchemaRequest, CreateSessionReply,
+    CreateSessionRequest, CreateTopicReply, CreateTopicRequest, CreateTopicRewriteRuleReply,
+    CreateTopicRewriteRuleRequest, CreateUserReply, CreateUserRequest, DeleteAclReply,
+    DeleteAclRequest, DeleteAutoSubscribeRuleReply, DeleteAutoSubscribeRuleRequest,
+    DeleteBlacklistReply, DeleteBlacklistRequest, DeleteExclusiveTopicReply,
+    DeleteExclusiveTopicRequest, DeleteRuleReply, DeleteRuleRequest, DeleteSchemaReply,
+    DeleteSchemaRequest, DeleteSessionReply, DeleteSessionRequest, DeleteTopicReply,
+    DeleteTopicRequest, DeleteTopicRewriteRuleReply, DeleteTopicRewriteRuleRequest,
+    DeleteUserReply, DeleteUserRequest, GetShareSubLeaderReply, GetShareSubLeaderRequest,
+    ListAclReply, ListAclRequest, ListAutoSubscribeRuleReply, ListAutoSubscribeRuleRequest,
+    ListBlacklistReply, ListBlacklistRequest, ListExclusiveTopicReply, ListExclusiveTopicRequest,
+    ListInflightMessageReply, ListInflightMessageRequest, ListRuleReply, ListRuleRequest,
+    ListSchemaBindingReply, ListSchemaBindingRequest, ListSchemaReply, ListSchemaRequest,
+    ListSessionReply, ListSessionRequest, ListShareSubStrategyReply, ListShareSubStrategyRequest,
+    ListTopicReply, ListTopicRequest, ListTopicRewriteRuleReply, ListTopicRewriteRuleRequest,
+    ListUserReply, ListUserRequest, SaveLastWillMessageReply, SaveLastWillMessageRequest,
+    SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest, SetExclusiveTopicReply,
+    SetExclusiveTopicRequest, SetShareSubStrategyReply, SetShareSubStrategyRequest,
+    SetTopicRetainMessageReply, SetTopicRetainMessageRequest, UnbindSchemaReply,
+    UnbindSchemaRequest, UpdateSessionReply, UpdateSessionRequest,
 };
 use tonic::transport::Channel;
 
@@ -162,6 +171,15 @@ impl_retriable_request!(
     true
 );
 
+impl_retriable_request!(
+    ListExclusiveTopicRequest,
+    MqttServiceClient<Channel>,
+    ListExclusiveTopicReply,
+    placement_center_mqtt_services_client,
+    list_exclusive_topic,
+    true
+);
+
 impl_retriable_request!(
     CreateSessionRequest,
     MqttServiceClient<Channel>,
@@ -287,3 +305,147 @@ impl_retriable_request!(
     delete_topic_rewrite_rule,
     true
 );
+
+impl_retriable_request!(
+    SetAutoSubscribeRuleRequest,
+    MqttServiceClient<Channel>,
+    SetAutoSubscribeRuleReply,
+    placement_center_mqtt_services_client,
+    set_auto_subscribe_rule,
+    true
+);
+
+impl_retriable_request!(
+    DeleteAutoSubscribeRuleRequest,
+    MqttServiceClient<Channel>,
+    DeleteAutoSubscribeRuleReply,
+    placement_center_mqtt_services_client,
+    delete_auto_subscribe_rule,
+    true
+);
+
+impl_retriable_request!(
+    ListAutoSubscribeRuleRequest,
+    MqttServiceClient<Channel>,
+    ListAutoSubscribeRuleReply,
+    placement_center_mqtt_services_client,
+    list_auto_subscribe_rule,
+    true
+);
+
+impl_retriable_request!(
+    CreateRuleRequest,
+    MqttServiceClient<Channel>,
+    CreateRuleReply,
+    placement_center_mqtt_services_client,
+    create_rule,
+    true
+);
+
+impl_retriable_request!(
+    DeleteRuleRequest,
+    MqttServiceClient<Channel>,
+    DeleteRuleReply,
+    placement_center_mqtt_services_client,
+    delete_rule,
+    true
+);
+
+impl_retriable_request!(
+    ListRuleRequest,
+    MqttServiceClient<Channel>,
+    ListRuleReply,
+    placement_center_mqtt_services_client,
+    list_rule,
+    true
+);
+
+impl_retriable_request!(
+    CreateSchemaRequest,
+    MqttServiceClient<Channel>,
+    CreateSchemaReply,
+    placement_center_mqtt_services_client,
+    create_schema,
+    true
+);
+
+impl_retriable_request!(
+    DeleteSchemaRequest,
+    MqttServiceClient<Channel>,
+    DeleteSchemaReply,
+    placement_center_mqtt_services_client,
+    delete_schema,
+    true
+);
+
+impl_retriable_request!(
+    ListSchemaRequest,
+    MqttServiceClient<Channel>,
+    ListSchemaReply,
+    placement_center_mqtt_services_client,
+    list_schema,
+    true
+);
+
+impl_retriable_request!(
+    BindSchemaRequest,
+    MqttServiceClient<Channel>,
+    BindSchemaReply,
+    placement_center_mqtt_services_client,
+    bind_schema,
+    true
+);
+
+impl_retriable_request!(
+    UnbindSchemaRequest,
+    MqttServiceClient<Channel>,
+    UnbindSchemaReply,
+    placement_center_mqtt_services_client,
+    unbind_schema,
+    true
+);
+
+impl_retriable_request!(
+    ListSchemaBindingRequest,
+    MqttServiceClient<Channel>,
+    ListSchemaBindingReply,
+    placement_center_mqtt_services_client,
+    list_schema_binding,
+    true
+);
+
+impl_retriable_request!(
+    BatchInflightMessageRequest,
+    MqttServiceClient<Channel>,
+    BatchInflightMessageReply,
+    placement_center_mqtt_services_client,
+    batch_inflight_message,
+    true
+);
+
+impl_retriable_request!(
+    ListInflightMessageRequest,
+    MqttServiceClient<Channel>,
+    ListInflightMessageReply,
+    placement_center_mqtt_services_client,
+    list_inflight_message,
+    true
+);
+
+impl_retriable_request!(
+    SetShareSubStrategyRequest,
+    MqttServiceClient<Channel>,
+    SetShareSubStrategyReply,
+    placement_center_mqtt_services_client,
+    set_share_sub_strategy,
+    true
+);
+
+impl_retriable_request!(
+    ListShareSubStrategyRequest,
+    MqttServiceClient<Channel>,
+    ListShareSubStrategyReply,
+    placement_center_mqtt_services_client,
+    list_share_sub_strategy,
+    true
+);
@@ -57,6 +57,7 @@ mod tests {
             namespace: namespace.clone(),
             shard_name: shard_name.clone(),
             replica: 1,
+            shard_config: Vec::new(),
         };
         let res = create_shard(&client_pool, &addrs, request).await.unwrap();
         assert_eq!(res.replica.len(), 1);
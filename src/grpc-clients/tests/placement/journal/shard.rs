@@ -74,6 +74,7 @@ mod tests {
             shard_name: shard_name.clone(),
             namespace: namespace.clone(),
             replica: 1,
+            shard_config: Vec::new(),
         };
         if let Err(e) = create_shard(&client_pool, &addrs, request).await {
             println!("{}", e);
@@ -285,6 +286,7 @@ mod tests {
             shard_name: shard_name.clone(),
             namespace: namespace.clone(),
             replica: 1,
+            shard_config: Vec::new(),
         };
         if let Err(e) = create_shard(&client_pool, &addrs, request).await {
             println!("{}", e);
@@ -356,6 +358,7 @@ mod tests {
             end_offset: 1000,
             start_timestamp: -1,
             end_timestamp: 1731576014,
+            end_position: 2048,
         };
         if let Err(e) = update_segment_meta(&client_pool, &addrs, request).await {
             println!("{}", e);
@@ -422,6 +425,7 @@ mod tests {
             shard_name: shard_name.clone(),
             namespace: namespace.clone(),
             replica: 1,
+            shard_config: Vec::new(),
         };
         if let Err(e) = create_shard(&client_pool, &addrs, request).await {
             println!("{}", e);
@@ -527,6 +531,7 @@ mod tests {
             shard_name: shard_name.clone(),
             namespace: namespace.clone(),
             replica: 1,
+            shard_config: Vec::new(),
         };
         if let Err(e) = create_shard(&client_pool, &addrs, request).await {
             println!("{}", e);
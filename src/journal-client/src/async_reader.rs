@@ -18,9 +18,10 @@ use std::time::Duration;
 use common_base::tools::now_mills;
 use dashmap::DashMap;
 use log::error;
-use metadata_struct::adapter::read_config::ReadConfig;
+use metadata_struct::adapter::read_config::{ReadConfig, ReadTimeRange};
 use metadata_struct::journal::segment::segment_name;
 use metadata_struct::journal::shard::shard_name_iden;
+use protocol::journal_server::compression::decompress;
 use protocol::journal_server::journal_engine::{
     FetchOffsetReqBody, FetchOffsetShard, ReadReqBody, ReadReqFilter, ReadReqMessage,
     ReadReqOptions, ReadType,
@@ -177,6 +178,7 @@ pub async fn async_read_data_by_offset(
                 options: Some(ReadReqOptions {
                     max_size: read_config.max_size,
                     max_record: read_config.max_record_num,
+                    accept_compressed: true,
                 }),
             });
         }
@@ -191,7 +193,7 @@ pub async fn async_read_data_by_offset(
                     segment: shard_data.segment,
                     offset: message.offset,
                     key: message.key,
-                    value: message.value,
+                    value: decompress(message.compression(), &message.value)?,
                     tags: message.tags,
                     timestamp: message.timestamp,
                 };
@@ -227,6 +229,7 @@ pub async fn async_read_data_by_key(
                 options: Some(ReadReqOptions {
                     max_size: read_config.max_size,
                     max_record: read_config.max_record_num,
+                    accept_compressed: true,
                 }),
             });
         }
@@ -241,7 +244,7 @@ pub async fn async_read_data_by_key(
                     segment: shard_data.segment,
                     offset: message.offset,
                     key: message.key,
-                    value: message.value,
+                    value: decompress(message.compression(), &message.value)?,
                     tags: message.tags,
                     timestamp: message.timestamp,
                 };
@@ -277,6 +280,7 @@ pub async fn async_read_data_by_tag(
                 options: Some(ReadReqOptions {
                     max_size: read_config.max_size,
                     max_record: read_config.max_record_num,
+                    accept_compressed: true,
                 }),
             });
         }
@@ -291,7 +295,7 @@ pub async fn async_read_data_by_tag(
                     segment: shard_data.segment,
                     offset: message.offset,
                     key: message.key,
-                    value: message.value,
+                    value: decompress(message.compression(), &message.value)?,
                     tags: message.tags,
                     timestamp: message.timestamp,
                 };
@@ -302,6 +306,126 @@ pub async fn async_read_data_by_tag(
     Ok(results)
 }
 
+/// Read one page of the records of a shard within `time_range`. The segments whose time span overlaps
+/// the window are read in order, and the returned page token (segment and server side token) resumes
+/// the read where this page stopped. No token is returned once the window has been read to the end.
+pub async fn async_read_data_by_time_range(
+    connection_manager: &Arc<ConnectionManager>,
+    metadata_cache: &Arc<MetadataCache>,
+    namespace: &str,
+    shard_name: &str,
+    time_range: &ReadTimeRange,
+    page_token: Option<&str>,
+    read_config: &ReadConfig,
+) -> Result<(Vec<ReadMessageData>, Option<String>), JournalClientError> {
+    let (start_segment, segment_page_token) = if let Some(token) = page_token {
+        decode_time_range_page_token(token)?
+    } else {
+        (0, String::new())
+    };
+
+    let mut segments: Vec<u32> =
+        get_metadata_by_shard(metadata_cache, connection_manager, namespace, shard_name)
+            .await
+            .iter()
+            .filter(|meta| {
+                meta.segment_no >= start_segment
+                    && (meta.end_timestamp < 0
+                        || meta.end_timestamp as u64 >= time_range.start_timestamp)
+                    && (time_range.end_timestamp == 0
+                        || meta.start_timestamp < 0
+                        || (meta.start_timestamp as u64) < time_range.end_timestamp)
+            })
+            .map(|meta| meta.segment_no)
+            .collect();
+    segments.sort();
+
+    let mut results = Vec::new();
+    for segment in segments.iter() {
+        if results.len() as u64 >= read_config.max_record_num {
+            return Ok((results, Some(encode_time_range_page_token(*segment, ""))));
+        }
+
+        let leader_id = if let Some(leader_id) =
+            metadata_cache.get_leader_by_segment(namespace, shard_name, *segment)
+        {
+            leader_id
+        } else {
+            return Err(JournalClientError::NotLeader(segment_name(
+                namespace, shard_name, *segment,
+            )));
+        };
+
+        // The page token only belongs to the segment it was returned for
+        let token = if *segment == start_segment {
+            segment_page_token.clone()
+        } else {
+            String::new()
+        };
+
+        let body = ReadReqBody {
+            messages: vec![ReadReqMessage {
+                namespace: namespace.to_owned(),
+                shard_name: shard_name.to_owned(),
+                segment: *segment,
+                ready_type: ReadType::TimeRange.into(),
+                filter: Some(ReadReqFilter {
+                    timestamp: time_range.start_timestamp,
+                    end_timestamp: time_range.end_timestamp,
+                    key: time_range.key.clone().unwrap_or_default(),
+                    tag: time_range.tag.clone().unwrap_or_default(),
+                    page_token: token,
+                    ..Default::default()
+                }),
+                options: Some(ReadReqOptions {
+                    max_size: read_config.max_size,
+                    max_record: read_config.max_record_num - results.len() as u64,
+                    accept_compressed: true,
+                }),
+            }],
+        };
+        let result = batch_read(connection_manager, leader_id, body).await?;
+        for shard_data in result.messages {
+            for message in shard_data.messages {
+                results.push(ReadMessageData {
+                    namespace: shard_data.namespace.clone(),
+                    shard_name: shard_data.shard_name.clone(),
+                    segment: shard_data.segment,
+                    offset: message.offset,
+                    key: message.key,
+                    value: decompress(message.compression(), &message.value)?,
+                    tags: message.tags,
+                    timestamp: message.timestamp,
+                });
+            }
+
+            if !shard_data.next_page_token.is_empty() {
+                return Ok((
+                    results,
+                    Some(encode_time_range_page_token(
+                        shard_data.segment,
+                        &shard_data.next_page_token,
+                    )),
+                ));
+            }
+        }
+    }
+    Ok((results, None))
+}
+
+fn encode_time_range_page_token(segment: u32, segment_page_token: &str) -> String {
+    format!("{}/{}", segment, segment_page_token)
+}
+
+fn decode_time_range_page_token(token: &str) -> Result<(u32, String), JournalClientError> {
+    if let Some((segment, segment_page_token)) = token.split_once('/') {
+        if let Ok(segment) = segment.parse::<u32>() {
+            return Ok((segment, segment_page_token.to_string()));
+        }
+    }
+    Err(JournalClientError::InvalidPageToken(token.to_string()))
+}
+
 async fn group_by_reader_leader(
     connection_manager: &Arc<ConnectionManager>,
     metadata_cache: &Arc<MetadataCache>,
@@ -13,27 +13,53 @@
 // limitations under the License.
 
 use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
+use std::task::{Context, Poll};
 use std::time::Duration;
 
-use common_base::tools::now_mills;
+use common_base::tools::{now_mills, unique_id};
 use dashmap::DashMap;
 use log::error;
 use metadata_struct::journal::segment::segment_name;
+use metadata_struct::journal::shard::shard_name_iden;
+use protocol::journal_server::compression::compress;
 use protocol::journal_server::journal_engine::{
-    WriteReqBody, WriteReqMessages, WriteReqSegmentMessages,
+    CompressionType, DurabilityLevel, WriteReqBody, WriteReqMessages, WriteReqSegmentMessages,
+    WriteRespBody,
 };
 use tokio::select;
 use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::sync::{oneshot, OwnedSemaphorePermit, Semaphore};
 use tokio::time::{sleep, timeout};
 
-use crate::cache::{get_segment_leader, MetadataCache};
+use crate::cache::{get_active_segment, get_segment_leader, load_shards_cache, MetadataCache};
 use crate::client::JournalClientWriteData;
 use crate::connection::ConnectionManager;
 use crate::error::JournalClientError;
+use crate::option::{JournalClientOption, WriteBufferFullStrategy};
 use crate::service::batch_write;
 
+// The number of times a batch is sent before its messages are failed. Retries reuse the same
+// producer id and pkids, so the server does not write them twice.
+const WRITE_RETRY_TIMES: u64 = 3;
+
+// The number of times records are sent again after the segment leader moved or the active segment
+// was sealed. These records were rejected by the server, so sending them again cannot duplicate them.
+const WRITE_REROUTE_TIMES: u64 = 10;
+
+// Journal server error codes that mean the records were rejected because the active segment or
+// its leader changed
+const REROUTE_ERROR_CODES: [&str; 5] = [
+    "NotLeader",
+    "NotActiveSegmet",
+    "SegmentAlreadySealUp",
+    "SegmentStatusError",
+    "SegmentNotExist",
+];
+
 // Send Message Struct
 #[derive(Clone)]
 pub struct SenderMessage {
@@ -57,6 +83,18 @@ impl SenderMessage {
             data,
         }
     }
+
+    // The bytes the records take in the write buffer
+    fn bytes(&self) -> u64 {
+        self.data
+            .iter()
+            .map(|raw| {
+                (raw.key.len()
+                    + raw.content.len()
+                    + raw.tags.iter().map(|tag| tag.len()).sum::<usize>()) as u64
+            })
+            .sum()
+    }
 }
 
 // Send Message Resp Struct
@@ -64,6 +102,35 @@ impl SenderMessage {
 pub struct SenderMessageResp {
     pub offset: u64,
     pub error: Option<String>,
+    pub error_code: Option<String>,
+    // Durability level the record was written with on the server
+    pub durability: DurabilityLevel,
+}
+
+impl SenderMessageResp {
+    fn need_reroute(&self) -> bool {
+        if let Some(code) = &self.error_code {
+            return REROUTE_ERROR_CODES.contains(&code.as_str());
+        }
+        false
+    }
+}
+
+/// Resolves to the write results of the records once the server has acknowledged them.
+pub struct WriteDeliveryFuture {
+    recv: oneshot::Receiver<Result<Vec<SenderMessageResp>, JournalClientError>>,
+}
+
+impl Future for WriteDeliveryFuture {
+    type Output = Result<Vec<SenderMessageResp>, JournalClientError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match Pin::new(&mut self.recv).poll(cx) {
+            Poll::Ready(Ok(resp)) => Poll::Ready(resp),
+            Poll::Ready(Err(_)) => Poll::Ready(Err(JournalClientError::WriteDeliveryCanceled)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
 }
 
 // Node Sender Threade Struct
@@ -87,20 +154,43 @@ pub struct AsyncWriter {
     connection_manager: Arc<ConnectionManager>,
     metadata_cache: Arc<MetadataCache>,
     send_pkid_generator: AtomicU64,
+    producer_id: String,
+    pkid_generator: Arc<AtomicU64>,
+    // Bytes of the records that are not acknowledged yet, None when the buffer is unlimited
+    inflight_bytes: Option<Arc<Semaphore>>,
+    max_inflight_bytes: u64,
+    buffer_full_strategy: WriteBufferFullStrategy,
+    compression: CompressionType,
 }
 
 impl AsyncWriter {
     pub fn new(
         connection_manager: Arc<ConnectionManager>,
         metadata_cache: Arc<MetadataCache>,
+        option: &JournalClientOption,
     ) -> Self {
         let node_senders = DashMap::with_capacity(2);
         let send_pkid_generator = AtomicU64::new(0);
+
+        // A write takes its size in permits at once, which is limited to u32
+        let max_inflight_bytes = option.max_inflight_bytes.min(u32::MAX as u64);
+        let inflight_bytes = if max_inflight_bytes > 0 {
+            Some(Arc::new(Semaphore::new(max_inflight_bytes as usize)))
+        } else {
+            None
+        };
+
         AsyncWriter {
             node_senders,
             connection_manager,
             metadata_cache,
             send_pkid_generator,
+            producer_id: unique_id(),
+            pkid_generator: Arc::new(AtomicU64::new(0)),
+            inflight_bytes,
+            max_inflight_bytes,
+            buffer_full_strategy: option.buffer_full_strategy,
+            compression: option.compression,
         }
     }
 
@@ -119,6 +209,9 @@ impl AsyncWriter {
             node_id,
             self.connection_manager.clone(),
             self.metadata_cache.clone(),
+            self.producer_id.clone(),
+            self.pkid_generator.clone(),
+            self.compression,
             data_recv,
             stop_recv,
         );
@@ -138,18 +231,153 @@ impl AsyncWriter {
         &self,
         message: &SenderMessage,
     ) -> Result<Vec<SenderMessageResp>, JournalClientError> {
-        let leader = get_segment_leader(
-            &self.metadata_cache,
-            &self.connection_manager,
-            &message.namespace,
-            &message.shard_name,
-        )
-        .await;
-
-        let sender = if let Some(sender) = self.node_senders.get(&leader) {
+        let permit = self.acquire_buffer(message.bytes()).await?;
+        let resp = self.send_with_reroute(message).await;
+        drop(permit);
+        resp
+    }
+
+    /// Reserve room in the write buffer and write the records in the background. The buffer limit
+    /// applies when this is called, the returned future resolves once the records are acknowledged.
+    pub async fn send_async(
+        self: &Arc<Self>,
+        message: SenderMessage,
+    ) -> Result<WriteDeliveryFuture, JournalClientError> {
+        let permit = self.acquire_buffer(message.bytes()).await?;
+        let (resp_sx, resp_rx) = oneshot::channel();
+        let writer = self.clone();
+        tokio::spawn(async move {
+            let resp = writer.send_with_reroute(&message).await;
+            drop(permit);
+            let _ = resp_sx.send(resp);
+        });
+        Ok(WriteDeliveryFuture { recv: resp_rx })
+    }
+
+    async fn acquire_buffer(
+        &self,
+        bytes: u64,
+    ) -> Result<Option<OwnedSemaphorePermit>, JournalClientError> {
+        let Some(inflight_bytes) = &self.inflight_bytes else {
+            return Ok(None);
+        };
+
+        if bytes > self.max_inflight_bytes {
+            return Err(JournalClientError::WriteExceedsBufferSize(
+                bytes,
+                self.max_inflight_bytes,
+            ));
+        }
+
+        let permits = bytes.max(1) as u32;
+        let permit = match self.buffer_full_strategy {
+            WriteBufferFullStrategy::Block => inflight_bytes
+                .clone()
+                .acquire_many_owned(permits)
+                .await
+                .map_err(|_| JournalClientError::WriteBufferFull(self.max_inflight_bytes))?,
+            WriteBufferFullStrategy::Fail => inflight_bytes
+                .clone()
+                .try_acquire_many_owned(permits)
+                .map_err(|_| JournalClientError::WriteBufferFull(self.max_inflight_bytes))?,
+        };
+        Ok(Some(permit))
+    }
+
+    // Write the records to the leader of the active segment of the shard. The records rejected because
+    // the leader moved or the segment was sealed are written again once the shard metadata is reloaded.
+    // When a reroute fails, the records written before keep their results and the records that were
+    // still pending carry the error, so that a caller does not write the former a second time.
+    async fn send_with_reroute(
+        &self,
+        message: &SenderMessage,
+    ) -> Result<Vec<SenderMessageResp>, JournalClientError> {
+        let mut results: Vec<Option<SenderMessageResp>> = vec![None; message.data.len()];
+        let mut pending: Vec<usize> = (0..message.data.len()).collect();
+        let mut times = 0;
+        loop {
+            let segment = get_active_segment(
+                &self.metadata_cache,
+                &self.connection_manager,
+                &message.namespace,
+                &message.shard_name,
+            )
+            .await;
+            let leader = get_segment_leader(
+                &self.metadata_cache,
+                &self.connection_manager,
+                &message.namespace,
+                &message.shard_name,
+            )
+            .await;
+
+            let pending_message = SenderMessage {
+                segment,
+                data: pending.iter().map(|i| message.data[*i].clone()).collect(),
+                ..message.clone()
+            };
+            let resp_list = match self.send_to_node(leader, pending_message).await {
+                Ok(resp_list) => resp_list,
+                Err(e) => {
+                    if times == 0 {
+                        return Err(e);
+                    }
+                    for i in pending.iter() {
+                        results[*i] = Some(SenderMessageResp {
+                            error: Some(e.to_string()),
+                            ..Default::default()
+                        });
+                    }
+                    break;
+                }
+            };
+            times += 1;
+
+            let mut reroute = Vec::new();
+            for (i, resp) in pending.iter().zip(resp_list) {
+                if resp.need_reroute() && times < WRITE_REROUTE_TIMES {
+                    reroute.push(*i);
+                }
+                results[*i] = Some(resp);
+            }
+
+            if reroute.is_empty() {
+                break;
+            }
+
+            if let Err(e) = load_shards_cache(
+                &self.metadata_cache,
+                &self.connection_manager,
+                &message.namespace,
+                &message.shard_name,
+            )
+            .await
+            {
+                error!(
+                    "Loading Shard {} Metadata info failed, error message :{}",
+                    shard_name_iden(&message.namespace, &message.shard_name),
+                    e
+                );
+            }
+            sleep(Duration::from_millis(100 * times)).await;
+            pending = reroute;
+        }
+
+        Ok(results
+            .into_iter()
+            .map(|resp| resp.unwrap_or_else(no_response))
+            .collect())
+    }
+
+    async fn send_to_node(
+        &self,
+        node_id: u64,
+        message: SenderMessage,
+    ) -> Result<Vec<SenderMessageResp>, JournalClientError> {
+        let sender = if let Some(sender) = self.node_senders.get(&node_id) {
             sender.node_send.clone()
         } else {
-            self.add_node_sender(leader)
+            self.add_node_sender(node_id)
         };
 
         let (callback_sx, mut callback_rx) = mpsc::channel::<Vec<SenderMessageResp>>(2);
@@ -158,12 +386,14 @@ impl AsyncWriter {
                 .send_pkid_generator
                 .fetch_add(1, std::sync::atomic::Ordering::Relaxed),
             callback_sx,
-            message: message.to_owned(),
+            message,
         };
 
-        let rs = sender.send(data).await;
+        if let Err(e) = sender.send(data).await {
+            return Err(JournalClientError::SendRequestError(node_id, e.to_string()));
+        }
         let resp_data = timeout(Duration::from_secs(30), callback_rx.recv()).await?;
-        Ok(resp_data.unwrap())
+        resp_data.ok_or(JournalClientError::WriteDeliveryCanceled)
     }
 
     pub async fn close(&self) -> Result<(), JournalClientError> {
@@ -178,12 +408,13 @@ pub fn start_sender_thread(
     node_id: u64,
     connection_manager: Arc<ConnectionManager>,
     metadata_cache: Arc<MetadataCache>,
+    producer_id: String,
+    pkid_generator: Arc<AtomicU64>,
+    compression: CompressionType,
     mut node_recv: Receiver<DataSenderPkg>,
     mut stop_recv: Receiver<bool>,
 ) {
     tokio::spawn(async move {
-        let pkid_generator = AtomicU64::new(0);
-
         loop {
             select! {
                 val = stop_recv.recv()=>{
@@ -196,7 +427,7 @@ pub fn start_sender_thread(
                         sleep(Duration::from_millis(100)).await;
                         continue;
                     }
-                    batch_sender_message(&connection_manager,&metadata_cache, node_id, &pkid_generator, messages).await;
+                    batch_sender_message(&connection_manager,&metadata_cache, node_id, &producer_id, &pkid_generator, compression, messages).await;
                 }
             }
         }
@@ -207,28 +438,36 @@ async fn batch_sender_message(
     connection_manager: &Arc<ConnectionManager>,
     metadata_cache: &Arc<MetadataCache>,
     node_id: u64,
+    producer_id: &str,
     pkid_generator: &AtomicU64,
+    compression: CompressionType,
     messages: Vec<DataSenderPkg>,
 ) {
-    let (segments, data_pkgs, callback_sx) = build_send_data(pkid_generator, messages);
+    let (segments, data_pkgs, callback_sx) = build_send_data(pkid_generator, compression, messages);
 
     // send data
-    let body = WriteReqBody { data: segments };
-    match batch_write(connection_manager, node_id, body).await {
+    let body = WriteReqBody {
+        data: segments,
+        producer_id: producer_id.to_string(),
+    };
+    match batch_write_with_retry(connection_manager, node_id, body).await {
         Ok(data) => {
             // callback resp
             let mut pkid_resp = HashMap::new();
             for shard_msg in data.status {
+                let durability = shard_msg.durability();
                 for msg in shard_msg.messages {
                     let resp = if let Some(e) = msg.error {
                         SenderMessageResp {
                             error: Some(format!("{}:{}", e.code, e.error)),
+                            error_code: Some(e.code),
                             ..Default::default()
                         }
                     } else {
                         SenderMessageResp {
                             offset: msg.offset,
-                            error: None,
+                            durability,
+                            ..Default::default()
                         }
                     };
                     pkid_resp.insert(msg.pkid, resp);
@@ -237,9 +476,12 @@ async fn batch_sender_message(
 
             for (id, pkids) in data_pkgs {
                 let mut results = Vec::new();
+                // One result per record, in the order the records were sent
                 for pkid in pkids {
                     if let Some(resp) = pkid_resp.get(&pkid) {
                         results.push(resp.to_owned());
+                    } else {
+                        results.push(no_response());
                     }
                 }
 
@@ -252,11 +494,17 @@ async fn batch_sender_message(
         }
         Err(e) => {
             // callback error
+            let error_code = if let JournalClientError::JournalEngineError(code, _) = &e {
+                Some(code.to_owned())
+            } else {
+                None
+            };
             for (id, pkids) in data_pkgs {
                 let mut results = Vec::new();
                 for pkid in pkids {
                     results.push(SenderMessageResp {
                         error: Some(e.to_string()),
+                        error_code: error_code.clone(),
                         ..Default::default()
                     });
                 }
@@ -271,9 +519,54 @@ async fn batch_sender_message(
     }
 }
 
+fn need_reroute_error(e: &JournalClientError) -> bool {
+    if let JournalClientError::JournalEngineError(code, _) = e {
+        return REROUTE_ERROR_CODES.contains(&code.as_str());
+    }
+    false
+}
+
+fn no_response() -> SenderMessageResp {
+    SenderMessageResp {
+        error: Some("The write response does not contain the result of the record".to_string()),
+        ..Default::default()
+    }
+}
+
+async fn batch_write_with_retry(
+    connection_manager: &Arc<ConnectionManager>,
+    node_id: u64,
+    body: WriteReqBody,
+) -> Result<WriteRespBody, JournalClientError> {
+    let mut times = 0;
+    loop {
+        match batch_write(connection_manager, node_id, body.clone()).await {
+            Ok(data) => return Ok(data),
+            Err(e) => {
+                // The node is no longer the leader of the segment, sending to it again cannot succeed.
+                // The caller reloads the shard metadata and sends the messages to the new leader.
+                if need_reroute_error(&e) {
+                    return Err(e);
+                }
+
+                times += 1;
+                if times >= WRITE_RETRY_TIMES {
+                    return Err(e);
+                }
+                error!(
+                    "Failed to write data to node {}, retry for the {} time, error message: {}",
+                    node_id, times, e
+                );
+                sleep(Duration::from_millis(100 * times)).await;
+            }
+        }
+    }
+}
+
 #[allow(clippy::type_complexity)]
 fn build_send_data(
     pkid_generator: &AtomicU64,
+    compression: CompressionType,
     messages: Vec<DataSenderPkg>,
 ) -> (
     Vec<WriteReqSegmentMessages>,
@@ -319,6 +612,7 @@ fn build_send_data(
                     key: raw.key.to_owned(),
                     value: raw.content.to_owned(),
                     tags: raw.tags.to_owned(),
+                    raw_len: raw.content.len() as u32,
                 });
                 if let Some(mut data_mut) = data_pkgs.get_mut(&msg.sender_pkg_id) {
                     data_mut.push(pkid);
@@ -329,17 +623,70 @@ fn build_send_data(
             callback_sx.insert(msg.sender_pkg_id, msg.callback_sx);
         }
 
+        let compression = compress_record_values(compression, &mut write_req_segment_messages);
         let msg = WriteReqSegmentMessages {
             namespace,
             shard_name,
             segment,
             messages: write_req_segment_messages,
+            compression: compression.into(),
         };
         segments.push(msg);
     }
     (segments, data_pkgs, callback_sx)
 }
 
+/// Compress each value of the batch on its own and return the compression they were compressed with.
+///
+/// Records are compressed one at a time rather than as a batch, so that a record is stored and read
+/// by offset without the rest of the batch it was written in. The trade-off is a lower ratio and a
+/// codec header per record, which can outweigh the gain for small values, so the batch is sent
+/// uncompressed when compressing its values does not make it smaller. When a value fails to
+/// compress, the batch is sent uncompressed as well so that the write still goes through.
+fn compress_record_values(
+    compression: CompressionType,
+    messages: &mut [WriteReqMessages],
+) -> CompressionType {
+    if compression == CompressionType::Uncompressed {
+        return compression;
+    }
+
+    let mut raw_len = 0;
+    let mut compressed_len = 0;
+    let mut values = Vec::with_capacity(messages.len());
+    for message in messages.iter() {
+        // Tombstones stay empty so that compaction can still recognize them
+        if message.value.is_empty() {
+            values.push(Vec::new());
+            continue;
+        }
+        match compress(compression, &message.value) {
+            Ok(value) => {
+                raw_len += message.value.len();
+                compressed_len += value.len();
+                values.push(value);
+            }
+            Err(e) => {
+                error!(
+                    "Failed to compress the write batch with {}, sending it uncompressed, error message: {}",
+                    compression.as_str_name(),
+                    e
+                );
+                return CompressionType::Uncompressed;
+            }
+        }
+    }
+
+    if compressed_len >= raw_len {
+        return CompressionType::Uncompressed;
+    }
+
+    for (message, value) in messages.iter_mut().zip(values) {
+        message.value = value;
+    }
+    compression
+}
+
 // Fetching data in bulk
 async fn get_batch_message(recv: &mut Receiver<DataSenderPkg>, line_ms: u64) -> Vec<DataSenderPkg> {
     let mut results = Vec::new();
@@ -357,3 +704,123 @@ async fn get_batch_message(recv: &mut Receiver<DataSenderPkg>, line_ms: u64) ->
     }
     results
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use protocol::journal_server::compression::decompress;
+    use protocol::journal_server::journal_engine::{CompressionType, WriteReqMessages};
+
+    use super::{compress_record_values, need_reroute_error, AsyncWriter, SenderMessage};
+    use crate::cache::MetadataCache;
+    use crate::client::JournalClientWriteData;
+    use crate::connection::ConnectionManager;
+    use crate::error::JournalClientError;
+    use crate::option::{JournalClientOption, WriteBufferFullStrategy};
+
+    fn build_writer(max_inflight_bytes: u64, strategy: WriteBufferFullStrategy) -> AsyncWriter {
+        let metadata_cache = Arc::new(MetadataCache::new(Vec::new()));
+        let connection_manager = Arc::new(ConnectionManager::new(metadata_cache.clone()));
+        let mut option = JournalClientOption::build();
+        option.set_max_inflight_bytes(max_inflight_bytes);
+        option.set_buffer_full_strategy(strategy);
+        AsyncWriter::new(connection_manager, metadata_cache, &option)
+    }
+
+    #[test]
+    fn sender_message_bytes_test() {
+        let message = SenderMessage::build(
+            &"n1".to_string(),
+            &"s1".to_string(),
+            0,
+            vec![JournalClientWriteData {
+                key: "k1".to_string(),
+                content: vec![0; 10],
+                tags: vec!["t1".to_string(), "t22".to_string()],
+            }],
+        );
+        assert_eq!(message.bytes(), 17);
+    }
+
+    #[test]
+    fn compress_record_values_test() {
+        let value = "{\"temperature\":21.5}".repeat(10).into_bytes();
+        let build_messages = || {
+            vec![
+                WriteReqMessages {
+                    pkid: 1,
+                    value: value.clone(),
+                    raw_len: value.len() as u32,
+                    ..Default::default()
+                },
+                WriteReqMessages {
+                    pkid: 2,
+                    ..Default::default()
+                },
+            ]
+        };
+
+        let mut messages = build_messages();
+        let compression = compress_record_values(CompressionType::Zstd, &mut messages);
+        assert_eq!(compression, CompressionType::Zstd);
+        assert!(messages[0].value.len() < value.len());
+        assert_eq!(decompress(compression, &messages[0].value).unwrap(), value);
+        assert!(messages[1].value.is_empty());
+
+        let mut messages = build_messages();
+        let compression = compress_record_values(CompressionType::Uncompressed, &mut messages);
+        assert_eq!(compression, CompressionType::Uncompressed);
+        assert_eq!(messages[0].value, value);
+
+        // A value too small to gain from compression is sent as it is
+        let mut messages = vec![WriteReqMessages {
+            pkid: 1,
+            value: b"{}".to_vec(),
+            raw_len: 2,
+            ..Default::default()
+        }];
+        let compression = compress_record_values(CompressionType::Zstd, &mut messages);
+        assert_eq!(compression, CompressionType::Uncompressed);
+        assert_eq!(messages[0].value, b"{}".to_vec());
+    }
+
+    #[test]
+    fn need_reroute_error_test() {
+        let e = JournalClientError::JournalEngineError("NotLeader".to_string(), "n1".to_string());
+        assert!(need_reroute_error(&e));
+
+        let e = JournalClientError::JournalEngineError(
+            "RequestBodyNotEmpty".to_string(),
+            "write".to_string(),
+        );
+        assert!(!need_reroute_error(&e));
+        assert!(!need_reroute_error(
+            &JournalClientError::WriteDeliveryCanceled
+        ));
+    }
+
+    #[tokio::test]
+    async fn acquire_buffer_test() {
+        let writer = build_writer(100, WriteBufferFullStrategy::Fail);
+
+        let permit = writer.acquire_buffer(60).await.unwrap();
+        assert!(permit.is_some());
+
+        let res = writer.acquire_buffer(60).await;
+        assert!(matches!(res, Err(JournalClientError::WriteBufferFull(100))));
+
+        drop(permit);
+        assert!(writer.acquire_buffer(60).await.unwrap().is_some());
+
+        let res = writer.acquire_buffer(200).await;
+        assert!(matches!(
+            res,
+            Err(JournalClientError::WriteExceedsBufferSize(200, 100))
+        ));
+
+        // no limit
+        let writer = build_writer(0, WriteBufferFullStrategy::Fail);
+        assert!(writer.acquire_buffer(200).await.unwrap().is_none());
+    }
+}
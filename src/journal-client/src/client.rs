@@ -15,9 +15,12 @@
 use std::sync::Arc;
 
 use common_base::error::common::CommonError;
-use metadata_struct::adapter::read_config::ReadConfig;
+use metadata_struct::adapter::read_config::{ReadConfig, ReadTimeRange};
 use metadata_struct::adapter::record::Record;
-use protocol::journal_server::journal_engine::{CreateShardReqBody, DeleteShardReqBody};
+use metadata_struct::journal::shard::JournalShardConfig;
+use protocol::journal_server::journal_engine::{
+    CompressionType, CreateShardReqBody, DeleteShardReqBody, DurabilityLevel,
+};
 use tokio::sync::broadcast::{self, Sender};
 
 use super::cache::{load_node_cache, start_update_cache_thread, MetadataCache};
@@ -25,10 +28,12 @@ use super::connection::{start_conn_gc_thread, ConnectionManager};
 use super::error::JournalClientError;
 use crate::async_reader::{
     async_read_data_by_key, async_read_data_by_offset, async_read_data_by_tag,
-    fetch_offset_by_timestamp, AsyncReader, ReadShardByOffset,
+    async_read_data_by_time_range, fetch_offset_by_timestamp, AsyncReader, ReadShardByOffset,
 };
-use crate::async_writer::{AsyncWriter, SenderMessage, SenderMessageResp};
+use crate::async_writer::{AsyncWriter, SenderMessage, SenderMessageResp, WriteDeliveryFuture};
 use crate::cache::get_active_segment;
+use crate::consumer::{JournalGroupConsumer, JournalGroupConsumerOption};
+use crate::option::JournalClientOption;
 use crate::service::{create_shard, delete_shard};
 
 #[derive(Default, Clone)]
@@ -49,12 +54,19 @@ pub struct JournalClient {
 
 impl JournalClient {
     pub fn new(addrs: Vec<String>) -> Self {
-        let metadata_cache = Arc::new(MetadataCache::new(addrs));
+        let mut option = JournalClientOption::build();
+        option.set_addrs(addrs);
+        JournalClient::new_with_option(option)
+    }
+
+    pub fn new_with_option(option: JournalClientOption) -> Self {
+        let metadata_cache = Arc::new(MetadataCache::new(option.addrs.clone()));
         let connection_manager = Arc::new(ConnectionManager::new(metadata_cache.clone()));
         let (stop_send, _) = broadcast::channel::<bool>(2);
         let writer = Arc::new(AsyncWriter::new(
             connection_manager.clone(),
             metadata_cache.clone(),
+            &option,
         ));
 
         let reader = Arc::new(AsyncReader::new(
@@ -93,11 +105,21 @@ impl JournalClient {
         namespace: &str,
         shard_name: &str,
         replica_num: u32,
+        shard_config: &JournalShardConfig,
     ) -> Result<(), JournalClientError> {
         let body = CreateShardReqBody {
             namespace: namespace.to_string(),
             shard_name: shard_name.to_string(),
             replica_num,
+            retention_sec: shard_config.retention_sec,
+            retention_bytes: shard_config.retention_bytes,
+            retention_segment_num: shard_config.retention_segment_num,
+            enable_compact: shard_config.enable_compact,
+            tombstone_retention_sec: shard_config.tombstone_retention_sec,
+            compression: CompressionType::from(shard_config.compression).into(),
+            durability: DurabilityLevel::from(shard_config.durability).into(),
+            fsync_interval_ms: shard_config.fsync_interval_ms,
+            fsync_bytes: shard_config.fsync_bytes,
         };
         let _ = create_shard(&self.connection_manager, body).await?;
         Ok(())
@@ -134,6 +156,45 @@ impl JournalClient {
         self.writer.send(&message).await
     }
 
+    /// Write the records in the background. Waits for (or fails, depending on the buffer full
+    /// strategy) room in the write buffer, and returns a future that resolves to the result of
+    /// each record once the server has acknowledged them.
+    pub async fn batch_write_async(
+        &self,
+        namespace: String,
+        shard_name: String,
+        data: Vec<JournalClientWriteData>,
+    ) -> Result<WriteDeliveryFuture, JournalClientError> {
+        let active_segment = get_active_segment(
+            &self.metadata_cache,
+            &self.connection_manager,
+            &namespace,
+            &shard_name,
+        )
+        .await;
+
+        let message = SenderMessage::build(&namespace, &shard_name, active_segment, data);
+        self.writer.send_async(message).await
+    }
+
+    /// Like `batch_write_async`, but calls `callback` with the results of the records.
+    pub async fn batch_write_with_callback<F>(
+        &self,
+        namespace: String,
+        shard_name: String,
+        data: Vec<JournalClientWriteData>,
+        callback: F,
+    ) -> Result<(), JournalClientError>
+    where
+        F: FnOnce(Result<Vec<SenderMessageResp>, JournalClientError>) + Send + 'static,
+    {
+        let delivery = self.batch_write_async(namespace, shard_name, data).await?;
+        tokio::spawn(async move {
+            callback(delivery.await);
+        });
+        Ok(())
+    }
+
     pub async fn write(
         &self,
         namespace: String,
@@ -274,6 +335,62 @@ impl JournalClient {
         Ok(results)
     }
 
+    /// Read the records of the shard within `time_range`, one page per call. Pass the returned page
+    /// token to the next call to continue, `None` is returned once the whole window has been read.
+    pub async fn read_by_time_range(
+        &self,
+        namespace: &str,
+        shard_name: &str,
+        time_range: &ReadTimeRange,
+        page_token: Option<&str>,
+        read_config: &ReadConfig,
+    ) -> Result<(Vec<Record>, Option<String>), JournalClientError> {
+        let (data_list, next_page_token) = async_read_data_by_time_range(
+            &self.connection_manager,
+            &self.metadata_cache,
+            namespace,
+            shard_name,
+            time_range,
+            page_token,
+            read_config,
+        )
+        .await?;
+
+        let mut results = Vec::new();
+        for raw in data_list {
+            let record = Record {
+                offset: Some(raw.offset),
+                key: raw.key,
+                data: raw.value,
+                tags: raw.tags,
+                header: Vec::new(),
+                timestamp: raw.timestamp,
+            };
+            results.push(record);
+        }
+        Ok((results, next_page_token))
+    }
+
+    /// Join the consumer group `group_name` to consume `shard_names` of the namespace together
+    /// with the other members of the group.
+    pub async fn group_consumer(
+        &self,
+        namespace: &str,
+        group_name: &str,
+        shard_names: Vec<String>,
+        option: JournalGroupConsumerOption,
+    ) -> Result<JournalGroupConsumer, JournalClientError> {
+        JournalGroupConsumer::join(
+            self.connection_manager.clone(),
+            self.metadata_cache.clone(),
+            namespace,
+            group_name,
+            shard_names,
+            option,
+        )
+        .await
+    }
+
     pub async fn close(&self) -> Result<(), CommonError> {
         Ok(())
     }
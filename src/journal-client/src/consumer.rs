@@ -0,0 +1,419 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::tools::unique_id;
+use log::{error, info, warn};
+use metadata_struct::adapter::read_config::ReadConfig;
+use metadata_struct::adapter::record::Record;
+use protocol::journal_server::journal_engine::{
+    ClientSegmentMetadata, CommitOffsetReqBody, GetCommitOffsetReqBody, GroupHeartbeatReqBody,
+    GroupShardOffset, JoinGroupReqBody, LeaveGroupReqBody,
+};
+use tokio::select;
+use tokio::sync::broadcast::{self, Receiver, Sender};
+use tokio::sync::{Mutex, RwLock};
+use tokio::time::sleep;
+
+use crate::async_reader::{async_read_data_by_offset, ReadShardByOffset};
+use crate::cache::{get_metadata_by_shard, MetadataCache};
+use crate::connection::ConnectionManager;
+use crate::error::JournalClientError;
+use crate::service::{commit_offset, get_commit_offset, group_heartbeat, join_group, leave_group};
+
+#[derive(Clone)]
+pub struct JournalGroupConsumerOption {
+    // range, round_robin or sticky
+    pub strategy: String,
+    pub session_timeout_ms: u64,
+    pub heartbeat_interval_ms: u64,
+    pub read_config: ReadConfig,
+}
+
+impl Default for JournalGroupConsumerOption {
+    fn default() -> Self {
+        JournalGroupConsumerOption {
+            strategy: "range".to_string(),
+            session_timeout_ms: 30000,
+            heartbeat_interval_ms: 3000,
+            read_config: ReadConfig::new(),
+        }
+    }
+}
+
+#[derive(Default, Clone)]
+struct GroupAssignment {
+    generation: u64,
+    shard_names: Vec<String>,
+}
+
+/// A member of a consumer group. The shards of the group are divided among all its
+/// members by the Placement Center, and are reassigned when members join or leave.
+/// Offsets are committed per group, so a shard moved to another member continues
+/// from the last committed offset.
+pub struct JournalGroupConsumer {
+    connection_manager: Arc<ConnectionManager>,
+    metadata_cache: Arc<MetadataCache>,
+    namespace: String,
+    group_name: String,
+    member_id: String,
+    shard_names: Vec<String>,
+    option: JournalGroupConsumerOption,
+    // latest assignment received from the coordinator
+    latest_assignment: Arc<RwLock<GroupAssignment>>,
+    // generation whose assignment is being consumed
+    generation: Mutex<u64>,
+    // shard name -> next offset to read
+    positions: Mutex<HashMap<String, u64>>,
+    stop_send: Sender<bool>,
+}
+
+impl JournalGroupConsumer {
+    pub(crate) async fn join(
+        connection_manager: Arc<ConnectionManager>,
+        metadata_cache: Arc<MetadataCache>,
+        namespace: &str,
+        group_name: &str,
+        shard_names: Vec<String>,
+        option: JournalGroupConsumerOption,
+    ) -> Result<JournalGroupConsumer, JournalClientError> {
+        let (stop_send, _) = broadcast::channel::<bool>(2);
+        let consumer = JournalGroupConsumer {
+            connection_manager,
+            metadata_cache,
+            namespace: namespace.to_string(),
+            group_name: group_name.to_string(),
+            member_id: unique_id(),
+            shard_names,
+            option,
+            latest_assignment: Arc::new(RwLock::new(GroupAssignment::default())),
+            generation: Mutex::new(0),
+            positions: Mutex::new(HashMap::new()),
+            stop_send,
+        };
+
+        let assignment =
+            join_group(&consumer.connection_manager, consumer.join_group_body()).await?;
+        *consumer.latest_assignment.write().await = GroupAssignment {
+            generation: assignment.generation,
+            shard_names: assignment.shard_names,
+        };
+
+        start_heartbeat_thread(
+            consumer.connection_manager.clone(),
+            consumer.join_group_body(),
+            consumer.option.heartbeat_interval_ms,
+            consumer.latest_assignment.clone(),
+            consumer.stop_send.subscribe(),
+        );
+        Ok(consumer)
+    }
+
+    pub fn member_id(&self) -> String {
+        self.member_id.clone()
+    }
+
+    /// Shards currently consumed by this member.
+    pub async fn assignment(&self) -> Vec<String> {
+        self.positions.lock().await.keys().cloned().collect()
+    }
+
+    /// Read the next batch of records from the shards assigned to this member.
+    pub async fn poll(&self) -> Result<Vec<Record>, JournalClientError> {
+        self.apply_assignment().await?;
+
+        let mut positions = self.positions.lock().await;
+        if positions.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let shards: Vec<ReadShardByOffset> = positions
+            .iter()
+            .map(|(shard_name, offset)| ReadShardByOffset {
+                namespace: self.namespace.clone(),
+                shard_name: shard_name.clone(),
+                offset: *offset,
+            })
+            .collect();
+        let data_list = async_read_data_by_offset(
+            &self.connection_manager,
+            &self.metadata_cache,
+            &shards,
+            &self.option.read_config,
+        )
+        .await?;
+
+        let mut results = Vec::new();
+        for raw in data_list {
+            if let Some(position) = positions.get_mut(&raw.shard_name) {
+                if raw.offset + 1 > *position {
+                    *position = raw.offset + 1;
+                }
+            }
+            results.push(Record {
+                offset: Some(raw.offset),
+                key: raw.key,
+                data: raw.value,
+                tags: raw.tags,
+                header: Vec::new(),
+                timestamp: raw.timestamp,
+            });
+        }
+        Ok(results)
+    }
+
+    /// Commit the offsets of all records returned by `poll` so far. The commit is rejected
+    /// when a rebalance has started a new generation, the next `poll` moves this member to it.
+    pub async fn commit(&self) -> Result<(), JournalClientError> {
+        let generation = self.generation.lock().await;
+        let positions = self.positions.lock().await;
+        self.commit_positions(*generation, &positions).await
+    }
+
+    /// Commit the offsets and leave the group, the shards of this member are
+    /// reassigned to the other members.
+    pub async fn close(&self) -> Result<(), JournalClientError> {
+        if let Err(e) = self.commit().await {
+            warn!(
+                "Member {} failed to commit the offsets of group {} before leaving, error message: {}",
+                self.member_id, self.group_name, e
+            );
+        }
+        self.stop_send.send(true)?;
+        leave_group(
+            &self.connection_manager,
+            LeaveGroupReqBody {
+                namespace: self.namespace.clone(),
+                group_name: self.group_name.clone(),
+                member_id: self.member_id.clone(),
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    // When the coordinator has started a new generation, continue the new shards from their
+    // committed offsets. The shards that were taken away can not be committed in the new
+    // generation anymore, their new owner continues from the last commit. A shard without a
+    // commit, or whose committed records were removed by retention, starts at its earliest record.
+    async fn apply_assignment(&self) -> Result<(), JournalClientError> {
+        let latest = self.latest_assignment.read().await.clone();
+        let mut generation = self.generation.lock().await;
+        let mut positions = self.positions.lock().await;
+        if *generation == latest.generation
+            && positions.len() == latest.shard_names.len()
+            && latest
+                .shard_names
+                .iter()
+                .all(|shard_name| positions.contains_key(shard_name))
+        {
+            return Ok(());
+        }
+
+        let added: Vec<String> = latest
+            .shard_names
+            .iter()
+            .filter(|shard_name| !positions.contains_key(*shard_name))
+            .cloned()
+            .collect();
+        let committed = if added.is_empty() {
+            Vec::new()
+        } else {
+            get_commit_offset(
+                &self.connection_manager,
+                GetCommitOffsetReqBody {
+                    namespace: self.namespace.clone(),
+                    group_name: self.group_name.clone(),
+                    shard_names: added.clone(),
+                },
+            )
+            .await?
+            .offsets
+        };
+
+        positions.retain(|shard_name, _| latest.shard_names.contains(shard_name));
+        for shard_name in added {
+            let segments = get_metadata_by_shard(
+                &self.metadata_cache,
+                &self.connection_manager,
+                &self.namespace,
+                &shard_name,
+            )
+            .await;
+            let earliest = earliest_offset(&segments);
+            let offset = committed
+                .iter()
+                .find(|raw| raw.shard_name == shard_name)
+                .map(|raw| raw.offset.max(earliest))
+                .unwrap_or(earliest);
+            positions.insert(shard_name, offset);
+        }
+
+        info!(
+            "Member {} of group {} moves to generation {}, assigned shards: {:?}",
+            self.member_id, self.group_name, latest.generation, latest.shard_names
+        );
+        *generation = latest.generation;
+        Ok(())
+    }
+
+    async fn commit_positions(
+        &self,
+        generation: u64,
+        positions: &HashMap<String, u64>,
+    ) -> Result<(), JournalClientError> {
+        if positions.is_empty() {
+            return Ok(());
+        }
+
+        let offsets = positions
+            .iter()
+            .map(|(shard_name, offset)| GroupShardOffset {
+                shard_name: shard_name.clone(),
+                offset: *offset,
+            })
+            .collect();
+        commit_offset(
+            &self.connection_manager,
+            CommitOffsetReqBody {
+                namespace: self.namespace.clone(),
+                group_name: self.group_name.clone(),
+                offsets,
+                member_id: self.member_id.clone(),
+                generation,
+            },
+        )
+        .await?;
+        Ok(())
+    }
+
+    fn join_group_body(&self) -> JoinGroupReqBody {
+        JoinGroupReqBody {
+            namespace: self.namespace.clone(),
+            group_name: self.group_name.clone(),
+            member_id: self.member_id.clone(),
+            shard_names: self.shard_names.clone(),
+            strategy: self.option.strategy.clone(),
+            session_timeout_ms: self.option.session_timeout_ms,
+        }
+    }
+}
+
+// Segments without records report a start offset of -1
+fn earliest_offset(segments: &[ClientSegmentMetadata]) -> u64 {
+    segments
+        .iter()
+        .filter(|segment| segment.start_offset >= 0)
+        .map(|segment| segment.start_offset as u64)
+        .min()
+        .unwrap_or(0)
+}
+
+fn start_heartbeat_thread(
+    connection_manager: Arc<ConnectionManager>,
+    join_body: JoinGroupReqBody,
+    heartbeat_interval_ms: u64,
+    latest_assignment: Arc<RwLock<GroupAssignment>>,
+    mut stop_recv: Receiver<bool>,
+) {
+    tokio::spawn(async move {
+        loop {
+            select! {
+                val = stop_recv.recv() => {
+                    if let Ok(flag) = val {
+                        if flag {
+                            break;
+                        }
+                    }
+                },
+                _ = sleep(Duration::from_millis(heartbeat_interval_ms)) => {
+                    heartbeat(&connection_manager, &join_body, &latest_assignment).await;
+                }
+            }
+        }
+    });
+}
+
+async fn heartbeat(
+    connection_manager: &Arc<ConnectionManager>,
+    join_body: &JoinGroupReqBody,
+    latest_assignment: &Arc<RwLock<GroupAssignment>>,
+) {
+    let body = GroupHeartbeatReqBody {
+        namespace: join_body.namespace.clone(),
+        group_name: join_body.group_name.clone(),
+        member_id: join_body.member_id.clone(),
+    };
+    let assignment = match group_heartbeat(connection_manager, body).await {
+        Ok(resp) => GroupAssignment {
+            generation: resp.generation,
+            shard_names: resp.shard_names,
+        },
+        Err(e) => {
+            // The member may have been removed after its session timed out, join the group again.
+            warn!(
+                "Member {} of group {} heartbeat failed, ready to join the group again, error message: {}",
+                join_body.member_id, join_body.group_name, e
+            );
+            match join_group(connection_manager, join_body.clone()).await {
+                Ok(resp) => GroupAssignment {
+                    generation: resp.generation,
+                    shard_names: resp.shard_names,
+                },
+                Err(e) => {
+                    error!(
+                        "Member {} failed to join group {} with error message: {}",
+                        join_body.member_id, join_body.group_name, e
+                    );
+                    return;
+                }
+            }
+        }
+    };
+
+    *latest_assignment.write().await = assignment;
+}
+
+#[cfg(test)]
+mod tests {
+    use protocol::journal_server::journal_engine::ClientSegmentMetadata;
+
+    use super::earliest_offset;
+
+    #[test]
+    fn earliest_offset_test() {
+        assert_eq!(earliest_offset(&[]), 0);
+
+        let segment = |segment_no, start_offset, end_offset| ClientSegmentMetadata {
+            segment_no,
+            start_offset,
+            end_offset,
+            ..Default::default()
+        };
+
+        // the first segments were removed by retention
+        let segments = vec![
+            segment(3, 300, 399),
+            segment(4, 400, -1),
+            segment(5, -1, -1),
+        ];
+        assert_eq!(earliest_offset(&segments), 300);
+
+        let segments = vec![segment(0, -1, -1)];
+        assert_eq!(earliest_offset(&segments), 0);
+    }
+}
@@ -70,6 +70,20 @@ pub enum JournalClientError {
     #[error("Shard {0} metadata no exists")]
     NotShardMetadata(String),
 
+    #[error("Read page token {0} is invalid")]
+    InvalidPageToken(String),
+
+    #[error(
+        "The write buffer of {0} bytes is full, too many writes are waiting to be acknowledged"
+    )]
+    WriteBufferFull(u64),
+
+    #[error("The write of {0} bytes is larger than the write buffer of {1} bytes")]
+    WriteExceedsBufferSize(u64, u64),
+
+    #[error("The write was dropped before it was acknowledged")]
+    WriteDeliveryCanceled,
+
     #[error("The write request returns empty")]
     WriteReqReturnTmpty,
 }
@@ -14,10 +14,11 @@
 
 #![allow(dead_code, unused_variables)]
 mod async_reader;
-mod async_writer;
+pub mod async_writer;
 mod cache;
 pub mod client;
 mod connection;
+pub mod consumer;
 mod error;
 pub mod option;
 mod service;
@@ -13,17 +13,35 @@
 // limitations under the License.
 
 use common_base::error::common::CommonError;
+use protocol::journal_server::journal_engine::CompressionType;
 
 #[derive(Default, Clone)]
 pub struct JournalClientOption {
     pub addrs: Vec<String>,
     pub line_ms: u64,
+    // Upper bound of the bytes of the records that are written but not acknowledged yet, 0 means no limit
+    pub max_inflight_bytes: u64,
+    pub buffer_full_strategy: WriteBufferFullStrategy,
+    // Compression applied to each record value of a write batch before it is sent, records are
+    // compressed one at a time. Uncompressed leaves it to the compression configured on the shard
+    pub compression: CompressionType,
+}
+
+/// What a write does when the in-flight buffer has no room for its records.
+#[derive(Default, Clone, Copy, Debug, PartialEq)]
+pub enum WriteBufferFullStrategy {
+    // Wait until earlier writes are acknowledged
+    #[default]
+    Block,
+    // Return JournalClientError::WriteBufferFull immediately
+    Fail,
 }
 
 impl JournalClientOption {
     pub fn build() -> Self {
         JournalClientOption {
             line_ms: 10,
+            max_inflight_bytes: 64 * 1024 * 1024,
             ..Default::default()
         }
     }
@@ -31,6 +49,18 @@ impl JournalClientOption {
     pub fn set_addrs(&mut self, addrs: Vec<String>) {
         self.addrs = addrs;
     }
+
+    pub fn set_max_inflight_bytes(&mut self, max_inflight_bytes: u64) {
+        self.max_inflight_bytes = max_inflight_bytes;
+    }
+
+    pub fn set_buffer_full_strategy(&mut self, strategy: WriteBufferFullStrategy) {
+        self.buffer_full_strategy = strategy;
+    }
+
+    pub fn set_compression(&mut self, compression: CompressionType) {
+        self.compression = compression;
+    }
 }
 
 pub fn options_validator(option: &JournalClientOption) -> Result<(), CommonError> {
@@ -16,11 +16,15 @@ use std::sync::Arc;
 
 use protocol::journal_server::codec::JournalEnginePacket;
 use protocol::journal_server::journal_engine::{
-    ApiKey, ApiVersion, CreateShardReq, CreateShardReqBody, CreateShardRespBody, DeleteShardReq,
-    DeleteShardReqBody, DeleteShardRespBody, FetchOffsetReq, FetchOffsetReqBody,
-    FetchOffsetRespBody, GetClusterMetadataReq, GetClusterMetadataRespBody, GetShardMetadataReq,
-    GetShardMetadataReqBody, GetShardMetadataReqShard, GetShardMetadataRespBody, ReadReq,
-    ReadReqBody, ReadRespBody, ReqHeader, WriteReq, WriteReqBody, WriteRespBody,
+    ApiKey, ApiVersion, CommitOffsetReq, CommitOffsetReqBody, CommitOffsetRespBody, CreateShardReq,
+    CreateShardReqBody, CreateShardRespBody, DeleteShardReq, DeleteShardReqBody,
+    DeleteShardRespBody, FetchOffsetReq, FetchOffsetReqBody, FetchOffsetRespBody,
+    GetClusterMetadataReq, GetClusterMetadataRespBody, GetCommitOffsetReq, GetCommitOffsetReqBody,
+    GetCommitOffsetRespBody, GetShardMetadataReq, GetShardMetadataReqBody,
+    GetShardMetadataReqShard, GetShardMetadataRespBody, GroupHeartbeatReq, GroupHeartbeatReqBody,
+    GroupHeartbeatRespBody, JoinGroupReq, JoinGroupReqBody, JoinGroupRespBody, LeaveGroupReq,
+    LeaveGroupReqBody, LeaveGroupRespBody, ReadReq, ReadReqBody, ReadRespBody, ReqHeader, WriteReq,
+    WriteReqBody, WriteRespBody,
 };
 
 use crate::connection::ConnectionManager;
@@ -243,3 +247,153 @@ pub(crate) async fn fetch_offset(
         resp_packet.to_string(),
     ))
 }
+
+pub(crate) async fn join_group(
+    connection_manager: &Arc<ConnectionManager>,
+    body: JoinGroupReqBody,
+) -> Result<JoinGroupRespBody, JournalClientError> {
+    let req_packet = JournalEnginePacket::JoinGroupReq(JoinGroupReq {
+        header: Some(ReqHeader {
+            api_key: ApiKey::JoinGroup.into(),
+            api_version: ApiVersion::V0.into(),
+        }),
+        body: Some(body),
+    });
+
+    let resp_packet = connection_manager.admin_send(req_packet.clone()).await?;
+
+    if let JournalEnginePacket::JoinGroupResp(data) = resp_packet {
+        resp_header_error(&data.header, req_packet.clone())?;
+        if let Some(body) = data.body {
+            return Ok(body);
+        }
+        return Err(JournalClientError::ReceivedPacketNotContainBody(
+            req_packet.to_string(),
+        ));
+    }
+
+    Err(JournalClientError::ReceivedPacketTypeError(
+        req_packet.to_string(),
+        resp_packet.to_string(),
+    ))
+}
+
+pub(crate) async fn group_heartbeat(
+    connection_manager: &Arc<ConnectionManager>,
+    body: GroupHeartbeatReqBody,
+) -> Result<GroupHeartbeatRespBody, JournalClientError> {
+    let req_packet = JournalEnginePacket::GroupHeartbeatReq(GroupHeartbeatReq {
+        header: Some(ReqHeader {
+            api_key: ApiKey::GroupHeartbeat.into(),
+            api_version: ApiVersion::V0.into(),
+        }),
+        body: Some(body),
+    });
+
+    let resp_packet = connection_manager.admin_send(req_packet.clone()).await?;
+
+    if let JournalEnginePacket::GroupHeartbeatResp(data) = resp_packet {
+        resp_header_error(&data.header, req_packet.clone())?;
+        if let Some(body) = data.body {
+            return Ok(body);
+        }
+        return Err(JournalClientError::ReceivedPacketNotContainBody(
+            req_packet.to_string(),
+        ));
+    }
+
+    Err(JournalClientError::ReceivedPacketTypeError(
+        req_packet.to_string(),
+        resp_packet.to_string(),
+    ))
+}
+
+pub(crate) async fn leave_group(
+    connection_manager: &Arc<ConnectionManager>,
+    body: LeaveGroupReqBody,
+) -> Result<LeaveGroupRespBody, JournalClientError> {
+    let req_packet = JournalEnginePacket::LeaveGroupReq(LeaveGroupReq {
+        header: Some(ReqHeader {
+            api_key: ApiKey::LeaveGroup.into(),
+            api_version: ApiVersion::V0.into(),
+        }),
+        body: Some(body),
+    });
+
+    let resp_packet = connection_manager.admin_send(req_packet.clone()).await?;
+
+    if let JournalEnginePacket::LeaveGroupResp(data) = resp_packet {
+        resp_header_error(&data.header, req_packet.clone())?;
+        if let Some(body) = data.body {
+            return Ok(body);
+        }
+        return Err(JournalClientError::ReceivedPacketNotContainBody(
+            req_packet.to_string(),
+        ));
+    }
+
+    Err(JournalClientError::ReceivedPacketTypeError(
+        req_packet.to_string(),
+        resp_packet.to_string(),
+    ))
+}
+
+pub(crate) async fn commit_offset(
+    connection_manager: &Arc<ConnectionManager>,
+    body: CommitOffsetReqBody,
+) -> Result<CommitOffsetRespBody, JournalClientError> {
+    let req_packet = JournalEnginePacket::CommitOffsetReq(CommitOffsetReq {
+        header: Some(ReqHeader {
+            api_key: ApiKey::CommitOffset.into(),
+            api_version: ApiVersion::V0.into(),
+        }),
+        body: Some(body),
+    });
+
+    let resp_packet = connection_manager.admin_send(req_packet.clone()).await?;
+
+    if let JournalEnginePacket::CommitOffsetResp(data) = resp_packet {
+        resp_header_error(&data.header, req_packet.clone())?;
+        if let Some(body) = data.body {
+            return Ok(body);
+        }
+        return Err(JournalClientError::ReceivedPacketNotContainBody(
+            req_packet.to_string(),
+        ));
+    }
+
+    Err(JournalClientError::ReceivedPacketTypeError(
+        req_packet.to_string(),
+        resp_packet.to_string(),
+    ))
+}
+
+pub(crate) async fn get_commit_offset(
+    connection_manager: &Arc<ConnectionManager>,
+    body: GetCommitOffsetReqBody,
+) -> Result<GetCommitOffsetRespBody, JournalClientError> {
+    let req_packet = JournalEnginePacket::GetCommitOffsetReq(GetCommitOffsetReq {
+        header: Some(ReqHeader {
+            api_key: ApiKey::GetCommitOffset.into(),
+            api_version: ApiVersion::V0.into(),
+        }),
+        body: Some(body),
+    });
+
+    let resp_packet = connection_manager.admin_send(req_packet.clone()).await?;
+
+    if let JournalEnginePacket::GetCommitOffsetResp(data) = resp_packet {
+        resp_header_error(&data.header, req_packet.clone())?;
+        if let Some(body) = data.body {
+            return Ok(body);
+        }
+        return Err(JournalClientError::ReceivedPacketNotContainBody(
+            req_packet.to_string(),
+        ));
+    }
+
+    Err(JournalClientError::ReceivedPacketTypeError(
+        req_packet.to_string(),
+        resp_packet.to_string(),
+    ))
+}
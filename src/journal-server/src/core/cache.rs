@@ -18,20 +18,27 @@ use common_base::config::journal_server::journal_server_conf;
 use common_base::tools::now_second;
 use dashmap::DashMap;
 use grpc_clients::placement::inner::call::node_list;
-use grpc_clients::placement::journal::call::{list_segment, list_segment_meta, list_shard};
+use grpc_clients::placement::journal::call::{
+    list_namespace, list_segment, list_segment_meta, list_shard,
+};
 use grpc_clients::pool::ClientPool;
 use log::{error, info};
+use metadata_struct::journal::namespace::JournalNamespace;
+use metadata_struct::journal::producer::JournalProducerSequence;
 use metadata_struct::journal::segment::{JournalSegment, SegmentStatus};
 use metadata_struct::journal::segment_meta::JournalSegmentMetadata;
 use metadata_struct::journal::shard::{shard_name_iden, JournalShard};
 use metadata_struct::placement::node::BrokerNode;
 use protocol::placement_center::placement_center_inner::NodeListRequest;
 use protocol::placement_center::placement_center_journal::{
-    ListSegmentMetaRequest, ListSegmentRequest, ListShardRequest,
+    ListNamespaceRequest, ListSegmentMetaRequest, ListSegmentRequest, ListShardRequest,
 };
+use tokio::sync::Mutex;
 
 use super::cluster_config::JournalEngineClusterConfig;
+use super::namespace::NamespaceWriteWindow;
 use crate::index::build::IndexBuildThreadData;
+use crate::segment::read_cache::SegmentReadCache;
 use crate::segment::write::SegmentWrite;
 use crate::segment::SegmentIdentity;
 
@@ -43,6 +50,15 @@ pub struct CacheManager {
     // (node_id, BrokerNode)
     node_list: DashMap<u64, BrokerNode>,
 
+    // (namespace, JournalNamespace)
+    namespaces: DashMap<String, JournalNamespace>,
+
+    // (namespace, estimated storage bytes)
+    namespace_storage_bytes: DashMap<String, u64>,
+
+    // (namespace, NamespaceWriteWindow)
+    namespace_write_windows: DashMap<String, NamespaceWriteWindow>,
+
     // (shard_name, JournalShard)
     shards: DashMap<String, JournalShard>,
 
@@ -60,27 +76,47 @@ pub struct CacheManager {
 
     // (segment_name, SegmentWrite)
     segment_writes: DashMap<String, SegmentWrite>,
+
+    // mmap of sealed segments and recent records of active segments
+    segment_read_cache: Arc<SegmentReadCache>,
+
+    // (producer_sequence_key, lock held while a write of the producer is checked and applied)
+    producer_locks: DashMap<String, Arc<Mutex<()>>>,
+
+    // (producer_sequence_key, JournalProducerSequence) not yet saved to the placement center
+    producer_sequence_sync: DashMap<String, JournalProducerSequence>,
 }
 
 impl CacheManager {
     pub fn new() -> Self {
         let cluster = DashMap::with_capacity(2);
         let node_list = DashMap::with_capacity(2);
+        let namespaces = DashMap::with_capacity(2);
+        let namespace_storage_bytes = DashMap::with_capacity(2);
+        let namespace_write_windows = DashMap::with_capacity(2);
         let shards = DashMap::with_capacity(8);
         let segments = DashMap::with_capacity(8);
         let segment_metadatas = DashMap::with_capacity(8);
         let leader_segments = DashMap::with_capacity(8);
         let segment_index_build_thread = DashMap::with_capacity(2);
         let segment_write = DashMap::with_capacity(2);
+        let producer_locks = DashMap::with_capacity(8);
+        let producer_sequence_sync = DashMap::with_capacity(8);
         CacheManager {
             cluster,
             node_list,
+            namespaces,
+            namespace_storage_bytes,
+            namespace_write_windows,
             shards,
             segments,
             segment_metadatas,
             leader_segments,
             segment_index_build_thread,
             segment_writes: segment_write,
+            segment_read_cache: Arc::new(SegmentReadCache::new()),
+            producer_locks,
+            producer_sequence_sync,
         }
     }
 
@@ -93,6 +129,10 @@ impl CacheManager {
         self.node_list.remove(&node_id);
     }
 
+    pub fn node_num(&self) -> usize {
+        self.node_list.len()
+    }
+
     pub fn all_node(&self) -> Vec<BrokerNode> {
         let mut results = Vec::new();
         for raw in self.node_list.iter() {
@@ -125,6 +165,67 @@ impl CacheManager {
         false
     }
 
+    // Namespace
+    pub fn set_namespace(&self, namespace: JournalNamespace) {
+        self.namespaces
+            .insert(namespace.namespace_name.clone(), namespace);
+    }
+
+    pub fn get_namespace(&self, namespace: &str) -> Option<JournalNamespace> {
+        if let Some(data) = self.namespaces.get(namespace) {
+            return Some(data.clone());
+        }
+        None
+    }
+
+    pub fn get_namespaces(&self) -> Vec<JournalNamespace> {
+        let mut results = Vec::new();
+        for raw in self.namespaces.iter() {
+            results.push(raw.value().clone());
+        }
+        results
+    }
+
+    pub fn delete_namespace(&self, namespace: &str) {
+        self.namespaces.remove(namespace);
+        self.namespace_storage_bytes.remove(namespace);
+        self.namespace_write_windows.remove(namespace);
+    }
+
+    pub fn set_namespace_storage_bytes(&self, namespace: &str, bytes: u64) {
+        self.namespace_storage_bytes
+            .insert(namespace.to_string(), bytes);
+    }
+
+    pub fn get_namespace_storage_bytes(&self, namespace: &str) -> u64 {
+        if let Some(bytes) = self.namespace_storage_bytes.get(namespace) {
+            return *bytes;
+        }
+        0
+    }
+
+    /// Account `bytes` written to the namespace in `second`. Returns false,
+    /// without accounting them, when they would exceed `max_bytes_per_sec`.
+    pub fn try_add_namespace_write_bytes(
+        &self,
+        namespace: &str,
+        second: u64,
+        bytes: u64,
+        max_bytes_per_sec: u64,
+    ) -> bool {
+        let mut window = self
+            .namespace_write_windows
+            .entry(namespace.to_string())
+            .or_default();
+        window.try_add(second, bytes, max_bytes_per_sec)
+    }
+
+    pub fn remove_namespace_write_bytes(&self, namespace: &str, second: u64, bytes: u64) {
+        if let Some(mut window) = self.namespace_write_windows.get_mut(namespace) {
+            window.remove(second, bytes);
+        }
+    }
+
     // Shard
     pub fn set_shard(&self, shard: JournalShard) {
         let key = shard_name_iden(&shard.namespace, &shard.shard_name);
@@ -179,14 +280,17 @@ impl CacheManager {
             self.segments.insert(key, data);
         }
 
-        // add to leader
+        // add to leader, the leader may have been moved to another node
         let conf = journal_server_conf();
+        let segment_iden = SegmentIdentity {
+            namespace: segment.namespace,
+            shard_name: segment.shard_name,
+            segment_seq: segment.segment_seq,
+        };
         if segment.leader == conf.node_id {
-            self.add_leader_segment(&SegmentIdentity {
-                namespace: segment.namespace,
-                shard_name: segment.shard_name,
-                segment_seq: segment.segment_seq,
-            });
+            self.add_leader_segment(&segment_iden);
+        } else {
+            self.remove_leader_segment(&segment_iden);
         }
     }
 
@@ -212,6 +316,8 @@ impl CacheManager {
             }
         }
 
+        self.segment_read_cache.remove(segment);
+
         // delete write thread by segment
         if let Some(write) = self.segment_writes.get(&key) {
             if let Err(e) = write.stop_sender.send(true) {
@@ -220,6 +326,10 @@ impl CacheManager {
         }
     }
 
+    pub fn get_segment_read_cache(&self) -> Arc<SegmentReadCache> {
+        self.segment_read_cache.clone()
+    }
+
     pub fn get_segment(&self, segment: &SegmentIdentity) -> Option<JournalSegment> {
         let key = shard_name_iden(&segment.namespace, &segment.shard_name);
         if let Some(sgement_list) = self.segments.get(&key) {
@@ -299,6 +409,16 @@ impl CacheManager {
             .contains_key(&segment_iden.name())
     }
 
+    pub fn get_build_index_thread(
+        &self,
+        segment_iden: &SegmentIdentity,
+    ) -> Option<IndexBuildThreadData> {
+        if let Some(data) = self.segment_index_build_thread.get(&segment_iden.name()) {
+            return Some(data.clone());
+        }
+        None
+    }
+
     // Segment Write Thread
     pub fn add_segment_write_thread(
         &self,
@@ -337,6 +457,46 @@ impl CacheManager {
     fn remove_leader_segment(&self, segment_iden: &SegmentIdentity) {
         self.leader_segments.remove(&segment_iden.name());
     }
+
+    // Producer Sequence
+    pub fn get_producer_lock(&self, key: &str) -> Arc<Mutex<()>> {
+        self.producer_locks
+            .entry(key.to_string())
+            .or_insert_with(|| Arc::new(Mutex::new(())))
+            .clone()
+    }
+
+    // The map itself keeps one reference, a lock nobody else holds or waits for can be dropped
+    pub fn clean_producer_lock(&self) {
+        self.producer_locks
+            .retain(|_, lock| Arc::strong_count(lock) > 1);
+    }
+
+    pub fn add_producer_sequence_sync(&self, key: String, sequence: JournalProducerSequence) {
+        self.producer_sequence_sync.insert(key, sequence);
+    }
+
+    pub fn requeue_producer_sequence_sync(&self, key: String, sequence: JournalProducerSequence) {
+        self.producer_sequence_sync.entry(key).or_insert(sequence);
+    }
+
+    pub fn take_producer_sequence_sync(&self) -> Vec<(String, JournalProducerSequence)> {
+        let keys: Vec<String> = self
+            .producer_sequence_sync
+            .iter()
+            .map(|raw| raw.key().clone())
+            .collect();
+        keys.into_iter()
+            .filter_map(|key| self.producer_sequence_sync.remove(&key))
+            .collect()
+    }
+
+    pub fn remove_producer_sequence_by_prefix(&self, prefix: &str) {
+        self.producer_locks
+            .retain(|key, _| !key.starts_with(prefix));
+        self.producer_sequence_sync
+            .retain(|key, _| !key.starts_with(prefix));
+    }
 }
 
 pub async fn load_metadata_cache(cache_manager: &Arc<CacheManager>, client_pool: &Arc<ClientPool>) {
@@ -374,6 +534,34 @@ pub async fn load_metadata_cache(cache_manager: &Arc<CacheManager>, client_pool:
         }
     }
 
+    // load namespace
+    let request = ListNamespaceRequest {
+        cluster_name: conf.cluster_name.clone(),
+        ..Default::default()
+    };
+    match list_namespace(client_pool, &conf.placement_center, request).await {
+        Ok(list) => match serde_json::from_slice::<Vec<JournalNamespace>>(&list.namespaces) {
+            Ok(data) => {
+                info!(
+                    "Load the namespace cache, the number of namespaces is {}",
+                    data.len()
+                );
+                for namespace in data {
+                    cache_manager.set_namespace(namespace);
+                }
+            }
+            Err(e) => {
+                panic!("Failed to decode the JournalNamespace information, {}", e);
+            }
+        },
+        Err(e) => {
+            panic!(
+                "Loading the namespace cache from the Placement Center failed, {}",
+                e
+            );
+        }
+    }
+
     // load shard
     let request = ListShardRequest {
         cluster_name: conf.cluster_name.clone(),
@@ -15,3 +15,31 @@
 pub const DB_COLUMN_FAMILY_INDEX: &str = "index";
 
 pub const BUILD_INDE_PER_RECORD_NUM: u64 = 10000;
+
+pub const GROUP_COMMIT_MAX_BATCH_NUM: usize = 64;
+
+pub const DURABILITY_CHECK_INTERVAL_MS: u64 = 50;
+
+pub const DEFAULT_FSYNC_INTERVAL_MS: u64 = 1000;
+
+pub const SEALED_SEGMENT_MMAP_MAX_NUM: usize = 256;
+
+pub const HOT_SEGMENT_CACHE_MAX_BYTES: u64 = 8 * 1024 * 1024;
+
+pub const HOT_SEGMENT_CACHE_TOTAL_MAX_BYTES: u64 = 256 * 1024 * 1024;
+
+pub const HOT_SEGMENT_CACHE_IDLE_SEC: u64 = 300;
+
+pub const TIME_RANGE_READ_MAX_SCAN_RECORD: u64 = 10000;
+
+pub const SEGMENT_COMPACT_INTERVAL_SEC: u64 = 600;
+
+pub const NAMESPACE_STORAGE_CALC_INTERVAL_SEC: u64 = 10;
+
+pub const METRICS_COLLECT_INTERVAL_SEC: u64 = 10;
+
+pub const PRODUCER_SEQUENCE_EXPIRE_SEC: u64 = 7 * 24 * 3600;
+
+pub const PRODUCER_SEQUENCE_WINDOW: usize = 256;
+
+pub const PRODUCER_SEQUENCE_SYNC_INTERVAL_MS: u64 = 100;
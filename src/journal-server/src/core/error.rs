@@ -46,6 +46,9 @@ pub enum JournalServerError {
     #[error("{0}")]
     TokioTimeErrorElapsed(#[from] tokio::time::error::Elapsed),
 
+    #[error("{0}")]
+    TokioJoinError(#[from] tokio::task::JoinError),
+
     #[error("{0}")]
     SerdeJsonError(#[from] serde_json::Error),
 
@@ -99,6 +102,33 @@ pub enum JournalServerError {
 
     #[error("Offset for timestamp {0} is not available in Segment {1}.")]
     NotAvailableOffsetByTimestamp(u64, String),
+
+    #[error("Offset {0} is out of range, the earliest available offset of Shard {2} is {1}")]
+    OffsetOutOfRange(u64, i64, String),
+
+    #[error("Record at position {1} of Segment {0} failed the CRC check, the segment file may be corrupted")]
+    RecordCrcCheckFailed(String, u64),
+
+    #[error("Segment {0} was written in format version {1}, which this version of the server cannot read")]
+    UnsupportedSegmentFormatVersion(String, u32),
+
+    #[error("Sequence {1} of producer {0} is not greater than the last written sequence {2} and is not a retry of a recent request")]
+    ProducerSequenceOutOfOrder(String, u64, u64),
+
+    #[error("Time range [{0}, {1}) is empty, the end timestamp must be greater than the start timestamp")]
+    InvalidTimeRange(u64, u64),
+
+    #[error("Read page token {0} is invalid")]
+    InvalidReadPageToken(String),
+
+    #[error("Namespace {0} has used up its storage quota of {1} bytes")]
+    NamespaceStorageQuotaExceeded(String, u64),
+
+    #[error("Writes to namespace {0} exceed its throughput quota of {1} bytes per second")]
+    NamespaceWriteThroughputExceeded(String, u64),
+
+    #[error("Failed to fsync Segment {0}, the write is not durable: {1}")]
+    SegmentSyncFailed(String, String),
 }
 
 pub fn get_journal_server_code(e: &JournalServerError) -> String {
@@ -112,6 +142,7 @@ pub fn get_journal_server_code(e: &JournalServerError) -> String {
         JournalServerError::FromUtf8Error(_) => "FromUtf8Error".to_string(),
         JournalServerError::SegmentAlreadySealUp(_) => "SegmentAlreadySealUp".to_string(),
         JournalServerError::TokioTimeErrorElapsed(_) => "TokioTimeErrorElapsed".to_string(),
+        JournalServerError::TokioJoinError(_) => "TokioJoinError".to_string(),
         JournalServerError::StdIoError(_) => "StdIoError".to_string(),
         JournalServerError::ProstDecodeError(_) => "ProstDecodeError".to_string(),
         JournalServerError::SerdeJsonError(_) => "SerdeJsonError".to_string(),
@@ -139,6 +170,23 @@ pub fn get_journal_server_code(e: &JournalServerError) -> String {
         JournalServerError::NotAvailableOffsetByTimestamp(_, _) => {
             "NotAvailableOffsetByTimestamp".to_string()
         }
+        JournalServerError::OffsetOutOfRange(_, _, _) => "OffsetOutOfRange".to_string(),
+        JournalServerError::RecordCrcCheckFailed(_, _) => "RecordCrcCheckFailed".to_string(),
+        JournalServerError::UnsupportedSegmentFormatVersion(_, _) => {
+            "UnsupportedSegmentFormatVersion".to_string()
+        }
+        JournalServerError::ProducerSequenceOutOfOrder(_, _, _) => {
+            "ProducerSequenceOutOfOrder".to_string()
+        }
+        JournalServerError::InvalidTimeRange(_, _) => "InvalidTimeRange".to_string(),
+        JournalServerError::InvalidReadPageToken(_) => "InvalidReadPageToken".to_string(),
+        JournalServerError::NamespaceStorageQuotaExceeded(_, _) => {
+            "NamespaceStorageQuotaExceeded".to_string()
+        }
+        JournalServerError::NamespaceWriteThroughputExceeded(_, _) => {
+            "NamespaceWriteThroughputExceeded".to_string()
+        }
+        JournalServerError::SegmentSyncFailed(_, _) => "SegmentSyncFailed".to_string(),
     }
 }
 #[cfg(test)]
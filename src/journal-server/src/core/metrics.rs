@@ -0,0 +1,352 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::config::journal_server::journal_server_conf;
+use lazy_static::lazy_static;
+use log::{error, info};
+use metadata_struct::journal::segment::SegmentStatus;
+use prometheus::{
+    exponential_buckets, register_gauge_vec, register_histogram_vec, register_int_counter_vec,
+    register_int_gauge_vec, GaugeVec, HistogramVec, IntCounterVec, IntGaugeVec,
+};
+use rocksdb_engine::RocksDBEngine;
+use tokio::time::sleep;
+
+use super::cache::CacheManager;
+use super::consts::METRICS_COLLECT_INTERVAL_SEC;
+use crate::index::build::get_segment_index_build_status;
+use crate::segment::manager::SegmentFileManager;
+use crate::segment::SegmentIdentity;
+
+const METRICS_LABEL_NAMESPACE: &str = "namespace";
+const METRICS_LABEL_SHARD: &str = "shard_name";
+const METRICS_LABEL_COMPRESSION: &str = "compression";
+const METRICS_LABEL_SEGMENT: &str = "segment";
+const METRICS_LABEL_STATUS: &str = "status";
+const METRICS_LABEL_NETWORK_TYPE: &str = "network_type";
+const METRICS_LABEL_SOURCE: &str = "source";
+
+lazy_static! {
+    static ref JOURNAL_RECORD_RAW_BYTES: IntCounterVec = register_int_counter_vec!(
+        "journal_record_raw_bytes",
+        "journal record content bytes before compression",
+        &[
+            METRICS_LABEL_NAMESPACE,
+            METRICS_LABEL_SHARD,
+            METRICS_LABEL_COMPRESSION
+        ]
+    )
+    .unwrap();
+    static ref JOURNAL_RECORD_STORED_BYTES: IntCounterVec = register_int_counter_vec!(
+        "journal_record_stored_bytes",
+        "journal record content bytes written to the segment file",
+        &[
+            METRICS_LABEL_NAMESPACE,
+            METRICS_LABEL_SHARD,
+            METRICS_LABEL_COMPRESSION
+        ]
+    )
+    .unwrap();
+    static ref JOURNAL_COMPRESSION_RATIO: GaugeVec = register_gauge_vec!(
+        "journal_compression_ratio",
+        "journal record stored bytes divided by raw bytes",
+        &[
+            METRICS_LABEL_NAMESPACE,
+            METRICS_LABEL_SHARD,
+            METRICS_LABEL_COMPRESSION
+        ]
+    )
+    .unwrap();
+    static ref JOURNAL_WRITE_REQUEST_NUM: IntCounterVec = register_int_counter_vec!(
+        "journal_write_request_num",
+        "journal write requests received by the shard",
+        &[METRICS_LABEL_NAMESPACE, METRICS_LABEL_SHARD]
+    )
+    .unwrap();
+    static ref JOURNAL_WRITE_RECORD_NUM: IntCounterVec = register_int_counter_vec!(
+        "journal_write_record_num",
+        "journal records received by the shard",
+        &[METRICS_LABEL_NAMESPACE, METRICS_LABEL_SHARD]
+    )
+    .unwrap();
+    static ref JOURNAL_WRITE_BATCH_SIZE: HistogramVec = register_histogram_vec!(
+        "journal_write_batch_size",
+        "journal records per write request of the shard",
+        &[METRICS_LABEL_NAMESPACE, METRICS_LABEL_SHARD],
+        vec![1.0, 5.0, 10.0, 50.0, 100.0, 500.0, 1000.0, 5000.0]
+    )
+    .unwrap();
+    static ref JOURNAL_SEGMENT_FLUSH_MS: HistogramVec = register_histogram_vec!(
+        "journal_segment_flush_ms",
+        "journal time spent writing and flushing a batch of records to the segment file",
+        &[METRICS_LABEL_NAMESPACE, METRICS_LABEL_SHARD],
+        exponential_buckets(0.5, 2.0, 14).unwrap()
+    )
+    .unwrap();
+    static ref JOURNAL_SEGMENT_FSYNC_MS: HistogramVec = register_histogram_vec!(
+        "journal_segment_fsync_ms",
+        "journal time spent on fsync of a segment file",
+        &[METRICS_LABEL_NAMESPACE, METRICS_LABEL_SHARD],
+        exponential_buckets(0.5, 2.0, 14).unwrap()
+    )
+    .unwrap();
+    static ref JOURNAL_GROUP_COMMIT_BATCH_NUM: HistogramVec = register_histogram_vec!(
+        "journal_group_commit_batch_num",
+        "journal number of write batches that shared one fsync",
+        &[METRICS_LABEL_NAMESPACE, METRICS_LABEL_SHARD],
+        exponential_buckets(1.0, 2.0, 7).unwrap()
+    )
+    .unwrap();
+    static ref JOURNAL_READ_SOURCE_NUM: IntCounterVec = register_int_counter_vec!(
+        "journal_read_source_num",
+        "journal number of segment reads served by each source: mmap, hot or file",
+        &[
+            METRICS_LABEL_NAMESPACE,
+            METRICS_LABEL_SHARD,
+            METRICS_LABEL_SOURCE
+        ]
+    )
+    .unwrap();
+    static ref JOURNAL_READ_MS: HistogramVec = register_histogram_vec!(
+        "journal_read_ms",
+        "journal time spent reading records of a segment",
+        &[METRICS_LABEL_NAMESPACE, METRICS_LABEL_SHARD],
+        exponential_buckets(0.5, 2.0, 14).unwrap()
+    )
+    .unwrap();
+    static ref JOURNAL_READ_RECORD_NUM: IntCounterVec = register_int_counter_vec!(
+        "journal_read_record_num",
+        "journal records returned to readers of the shard",
+        &[METRICS_LABEL_NAMESPACE, METRICS_LABEL_SHARD]
+    )
+    .unwrap();
+    static ref JOURNAL_INDEX_BUILD_LAG: IntGaugeVec = register_int_gauge_vec!(
+        "journal_index_build_lag",
+        "journal records of the local segment that are written but not indexed yet",
+        &[
+            METRICS_LABEL_NAMESPACE,
+            METRICS_LABEL_SHARD,
+            METRICS_LABEL_SEGMENT
+        ]
+    )
+    .unwrap();
+    static ref JOURNAL_SEGMENT_NUM: IntGaugeVec = register_int_gauge_vec!(
+        "journal_segment_num",
+        "journal segments known to the node by status",
+        &[METRICS_LABEL_STATUS]
+    )
+    .unwrap();
+    static ref JOURNAL_CONNECTION_NUM: IntGaugeVec = register_int_gauge_vec!(
+        "journal_connection_num",
+        "journal client connections of the node",
+        &[METRICS_LABEL_NETWORK_TYPE]
+    )
+    .unwrap();
+}
+
+pub fn metrics_record_compression(
+    namespace: &str,
+    shard_name: &str,
+    compression: &str,
+    raw_bytes: u64,
+    stored_bytes: u64,
+) {
+    let labels = [namespace, shard_name, compression];
+    let raw = JOURNAL_RECORD_RAW_BYTES.with_label_values(&labels);
+    let stored = JOURNAL_RECORD_STORED_BYTES.with_label_values(&labels);
+    raw.inc_by(raw_bytes);
+    stored.inc_by(stored_bytes);
+
+    if raw.get() > 0 {
+        JOURNAL_COMPRESSION_RATIO
+            .with_label_values(&labels)
+            .set(stored.get() as f64 / raw.get() as f64);
+    }
+}
+
+pub fn metrics_write_request(namespace: &str, shard_name: &str, record_num: usize) {
+    let labels = [namespace, shard_name];
+    JOURNAL_WRITE_REQUEST_NUM.with_label_values(&labels).inc();
+    JOURNAL_WRITE_RECORD_NUM
+        .with_label_values(&labels)
+        .inc_by(record_num as u64);
+    JOURNAL_WRITE_BATCH_SIZE
+        .with_label_values(&labels)
+        .observe(record_num as f64);
+}
+
+pub fn metrics_segment_flush_ms(namespace: &str, shard_name: &str, ms: f64) {
+    JOURNAL_SEGMENT_FLUSH_MS
+        .with_label_values(&[namespace, shard_name])
+        .observe(ms);
+}
+
+pub fn metrics_segment_fsync(namespace: &str, shard_name: &str, ms: f64, batch_num: usize) {
+    let labels = [namespace, shard_name];
+    JOURNAL_SEGMENT_FSYNC_MS
+        .with_label_values(&labels)
+        .observe(ms);
+    JOURNAL_GROUP_COMMIT_BATCH_NUM
+        .with_label_values(&labels)
+        .observe(batch_num as f64);
+}
+
+pub fn metrics_read_source(namespace: &str, shard_name: &str, source: &str) {
+    JOURNAL_READ_SOURCE_NUM
+        .with_label_values(&[namespace, shard_name, source])
+        .inc();
+}
+
+pub fn metrics_read(namespace: &str, shard_name: &str, ms: f64, record_num: usize) {
+    let labels = [namespace, shard_name];
+    JOURNAL_READ_MS.with_label_values(&labels).observe(ms);
+    JOURNAL_READ_RECORD_NUM
+        .with_label_values(&labels)
+        .inc_by(record_num as u64);
+}
+
+pub fn metrics_connection_inc(network_type: &str) {
+    JOURNAL_CONNECTION_NUM
+        .with_label_values(&[network_type])
+        .inc();
+}
+
+pub fn metrics_connection_dec(network_type: &str) {
+    JOURNAL_CONNECTION_NUM
+        .with_label_values(&[network_type])
+        .dec();
+}
+
+/// Periodically refresh the gauges that are computed from the node state rather than updated on the
+/// data path: the number of segments by status and the index build lag of the local segments.
+pub async fn start_metrics_collect_thread(
+    cache_manager: Arc<CacheManager>,
+    segment_file_manager: Arc<SegmentFileManager>,
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+) {
+    info!("Journal metrics collect thread started successfully");
+    loop {
+        collect_segment_num(&cache_manager);
+        collect_index_build_lag(
+            &cache_manager,
+            &segment_file_manager,
+            &rocksdb_engine_handler,
+        );
+        sleep(Duration::from_secs(METRICS_COLLECT_INTERVAL_SEC)).await;
+    }
+}
+
+fn collect_segment_num(cache_manager: &Arc<CacheManager>) {
+    // Statuses without segments are reported as 0 instead of keeping their last value
+    let mut segment_num: HashMap<String, i64> = [
+        SegmentStatus::Idle,
+        SegmentStatus::PreWrite,
+        SegmentStatus::Write,
+        SegmentStatus::PreSealUp,
+        SegmentStatus::SealUp,
+        SegmentStatus::PreDelete,
+        SegmentStatus::Deleting,
+    ]
+    .iter()
+    .map(|status| (status.to_string(), 0))
+    .collect();
+
+    for shard in cache_manager.get_shards() {
+        for segment in cache_manager.get_segments_list_by_shard(&shard.namespace, &shard.shard_name)
+        {
+            *segment_num.entry(segment.status.to_string()).or_default() += 1;
+        }
+    }
+
+    for (status, num) in segment_num {
+        JOURNAL_SEGMENT_NUM
+            .with_label_values(&[status.as_str()])
+            .set(num);
+    }
+}
+
+fn collect_index_build_lag(
+    cache_manager: &Arc<CacheManager>,
+    segment_file_manager: &Arc<SegmentFileManager>,
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+) {
+    // Segments that were deleted or moved away from this node drop out of the gauge
+    JOURNAL_INDEX_BUILD_LAG.reset();
+
+    let conf = journal_server_conf();
+    for shard in cache_manager.get_shards() {
+        for segment in cache_manager.get_segments_list_by_shard(&shard.namespace, &shard.shard_name)
+        {
+            if segment.get_fold(conf.node_id).is_none() {
+                continue;
+            }
+
+            let segment_iden =
+                SegmentIdentity::new(&segment.namespace, &segment.shard_name, segment.segment_seq);
+            let Some(segment_file) = segment_file_manager.get_segment_file(&segment_iden) else {
+                continue;
+            };
+
+            let status = match get_segment_index_build_status(
+                cache_manager,
+                rocksdb_engine_handler,
+                &segment_iden,
+            ) {
+                Ok(status) => status,
+                Err(e) => {
+                    error!(
+                        "Failed to get the index build status of segment {}, error message: {}",
+                        segment_iden.name(),
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            JOURNAL_INDEX_BUILD_LAG
+                .with_label_values(&[
+                    segment_iden.namespace.as_str(),
+                    segment_iden.shard_name.as_str(),
+                    segment_iden.segment_seq.to_string().as_str(),
+                ])
+                .set(index_build_lag(segment_file.end_offset, status.last_offset));
+        }
+    }
+}
+
+// end_offset is -1 when the segment has no record yet
+fn index_build_lag(end_offset: i64, last_index_offset: Option<u64>) -> i64 {
+    let indexed = match last_index_offset {
+        Some(offset) => offset as i64,
+        None => -1,
+    };
+    (end_offset - indexed).max(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::index_build_lag;
+
+    #[test]
+    fn index_build_lag_test() {
+        assert_eq!(index_build_lag(-1, None), 0);
+        assert_eq!(index_build_lag(9, None), 10);
+        assert_eq!(index_build_lag(9, Some(4)), 5);
+        assert_eq!(index_build_lag(9, Some(9)), 0);
+    }
+}
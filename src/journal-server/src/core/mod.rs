@@ -17,7 +17,10 @@ pub mod cluster;
 pub mod cluster_config;
 pub mod consts;
 pub mod error;
+pub mod metrics;
+pub mod namespace;
 pub mod notification;
+pub mod producer;
 pub mod segment;
 pub mod segment_meta;
 pub mod segment_status;
@@ -0,0 +1,234 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::config::journal_server::journal_server_conf;
+use common_base::tools::now_second;
+use log::{error, info};
+use metadata_struct::journal::segment::{JournalSegment, SegmentStatus};
+use protocol::journal_server::journal_engine::WriteReqBody;
+use tokio::time::sleep;
+
+use super::cache::CacheManager;
+use super::consts::NAMESPACE_STORAGE_CALC_INTERVAL_SEC;
+use super::error::JournalServerError;
+use crate::segment::file::SegmentFile;
+
+/// Bytes written to a namespace within one second.
+#[derive(Clone, Default)]
+pub struct NamespaceWriteWindow {
+    pub second: u64,
+    pub bytes: u64,
+}
+
+impl NamespaceWriteWindow {
+    pub fn try_add(&mut self, now_second: u64, bytes: u64, max_bytes_per_sec: u64) -> bool {
+        if self.second != now_second {
+            self.second = now_second;
+            self.bytes = 0;
+        }
+
+        // A single batch larger than the quota is still accepted when nothing else was
+        // written in this second, otherwise it could never be written.
+        if max_bytes_per_sec > 0 && self.bytes > 0 && self.bytes + bytes > max_bytes_per_sec {
+            return false;
+        }
+        self.bytes += bytes;
+        true
+    }
+
+    /// Give back the bytes of a request that was rejected after they were added.
+    pub fn remove(&mut self, second: u64, bytes: u64) {
+        if self.second == second {
+            self.bytes = self.bytes.saturating_sub(bytes);
+        }
+    }
+}
+
+/// Reject the write request if any namespace it writes to is over its storage or throughput quota.
+/// The throughput of a request is only charged when the request is admitted for all its namespaces.
+pub fn check_namespace_quota(
+    cache_manager: &Arc<CacheManager>,
+    req_body: &WriteReqBody,
+) -> Result<(), JournalServerError> {
+    let mut write_bytes: HashMap<String, u64> = HashMap::new();
+    for shard in req_body.data.iter() {
+        let bytes: u64 = shard
+            .messages
+            .iter()
+            .map(|raw| raw.value.len() as u64)
+            .sum();
+        *write_bytes.entry(shard.namespace.clone()).or_default() += bytes;
+    }
+
+    let mut quotas = Vec::with_capacity(write_bytes.len());
+    for (namespace, bytes) in write_bytes {
+        let Some(data) = cache_manager.get_namespace(&namespace) else {
+            continue;
+        };
+
+        let quota = data.quota;
+        if quota.max_storage_bytes > 0
+            && cache_manager.get_namespace_storage_bytes(&namespace) >= quota.max_storage_bytes
+        {
+            return Err(JournalServerError::NamespaceStorageQuotaExceeded(
+                namespace,
+                quota.max_storage_bytes,
+            ));
+        }
+        quotas.push((namespace, bytes, quota.max_write_bytes_per_sec));
+    }
+
+    let second = now_second();
+    let node_num = cache_manager.node_num();
+    for (i, (namespace, bytes, max_write_bytes_per_sec)) in quotas.iter().enumerate() {
+        if cache_manager.try_add_namespace_write_bytes(
+            namespace,
+            second,
+            *bytes,
+            node_write_bytes_per_sec(*max_write_bytes_per_sec, node_num),
+        ) {
+            continue;
+        }
+
+        for (namespace, bytes, _) in quotas[..i].iter() {
+            cache_manager.remove_namespace_write_bytes(namespace, second, *bytes);
+        }
+        return Err(JournalServerError::NamespaceWriteThroughputExceeded(
+            namespace.clone(),
+            *max_write_bytes_per_sec,
+        ));
+    }
+    Ok(())
+}
+
+// The throughput quota limits the whole cluster. The shard leaders are spread over all
+// nodes, so every node admits an equal share of it without coordinating with the others.
+fn node_write_bytes_per_sec(max_write_bytes_per_sec: u64, node_num: usize) -> u64 {
+    if max_write_bytes_per_sec == 0 {
+        return 0;
+    }
+    (max_write_bytes_per_sec / node_num.max(1) as u64).max(1)
+}
+
+/// Periodically estimate the storage used by each namespace that has a storage quota.
+pub async fn start_namespace_storage_thread(cache_manager: Arc<CacheManager>) {
+    info!("Namespace storage calculation thread started successfully");
+    loop {
+        for namespace in cache_manager.get_namespaces() {
+            if namespace.quota.max_storage_bytes == 0 {
+                continue;
+            }
+
+            let bytes =
+                calc_namespace_storage_bytes(&cache_manager, &namespace.namespace_name).await;
+            cache_manager.set_namespace_storage_bytes(&namespace.namespace_name, bytes);
+        }
+        sleep(Duration::from_secs(NAMESPACE_STORAGE_CALC_INTERVAL_SEC)).await;
+    }
+}
+
+async fn calc_namespace_storage_bytes(cache_manager: &Arc<CacheManager>, namespace: &str) -> u64 {
+    let mut total = 0;
+    for shard in cache_manager.get_shards() {
+        if shard.namespace != namespace {
+            continue;
+        }
+
+        for segment in cache_manager.get_segments_list_by_shard(&shard.namespace, &shard.shard_name)
+        {
+            if segment.segment_seq < shard.start_segment_seq {
+                continue;
+            }
+            total += segment_storage_bytes(&segment).await;
+        }
+    }
+    total
+}
+
+// The size of the local segment file when this node holds a replica of the segment.
+// Otherwise a sealed segment is assumed to be full, and an unsealed one to be empty.
+async fn segment_storage_bytes(segment: &JournalSegment) -> u64 {
+    let conf = journal_server_conf();
+    if let Some(fold) = segment.get_fold(conf.node_id) {
+        let segment_file = SegmentFile::new(
+            segment.namespace.clone(),
+            segment.shard_name.clone(),
+            segment.segment_seq,
+            fold,
+        );
+        if segment_file.exists() {
+            match segment_file.size().await {
+                Ok(size) => return size,
+                Err(e) => {
+                    error!(
+                        "Failed to get the size of segment {} with error message: {}",
+                        segment.name(),
+                        e
+                    );
+                }
+            }
+        }
+    }
+
+    if segment.status == SegmentStatus::SealUp || segment.status == SegmentStatus::PreSealUp {
+        return segment.config.max_segment_size;
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{node_write_bytes_per_sec, NamespaceWriteWindow};
+
+    #[test]
+    fn namespace_write_window_test() {
+        let mut window = NamespaceWriteWindow::default();
+        assert!(window.try_add(1, 60, 100));
+        assert!(window.try_add(1, 40, 100));
+        assert!(!window.try_add(1, 1, 100));
+        assert_eq!(window.bytes, 100);
+
+        // a new second starts a new window
+        assert!(window.try_add(2, 100, 100));
+        assert_eq!(window.bytes, 100);
+
+        // a single batch over the quota is accepted in an empty window
+        assert!(window.try_add(3, 150, 100));
+        assert!(!window.try_add(3, 1, 100));
+
+        // no quota
+        assert!(window.try_add(3, 1000, 0));
+
+        // bytes of a rejected request are given back, but not to a newer window
+        let mut window = NamespaceWriteWindow::default();
+        assert!(window.try_add(4, 60, 100));
+        window.remove(4, 60);
+        assert_eq!(window.bytes, 0);
+        assert!(window.try_add(5, 60, 100));
+        window.remove(4, 60);
+        assert_eq!(window.bytes, 60);
+    }
+
+    #[test]
+    fn node_write_bytes_per_sec_test() {
+        assert_eq!(node_write_bytes_per_sec(0, 3), 0);
+        assert_eq!(node_write_bytes_per_sec(900, 3), 300);
+        assert_eq!(node_write_bytes_per_sec(900, 0), 900);
+        assert_eq!(node_write_bytes_per_sec(2, 3), 1);
+    }
+}
@@ -15,6 +15,7 @@
 use std::sync::Arc;
 
 use log::{error, info};
+use metadata_struct::journal::namespace::JournalNamespace;
 use metadata_struct::journal::segment::JournalSegment;
 use metadata_struct::journal::segment_meta::JournalSegmentMetadata;
 use metadata_struct::journal::shard::JournalShard;
@@ -42,6 +43,9 @@ pub async fn parse_notification(
         JournalUpdateCacheResourceType::SegmentMeta => {
             parse_segment_meta(cache_manager, action_type, data).await
         }
+        JournalUpdateCacheResourceType::Namespace => {
+            parse_namespace(cache_manager, action_type, data)
+        }
     }
 }
 
@@ -79,6 +83,37 @@ fn parse_node(
     }
 }
 
+fn parse_namespace(
+    cache_manager: &Arc<CacheManager>,
+    action_type: JournalUpdateCacheActionType,
+    data: &str,
+) {
+    match serde_json::from_str::<JournalNamespace>(data) {
+        Ok(namespace) => match action_type {
+            JournalUpdateCacheActionType::Set => {
+                info!(
+                    "Update the cache, set namespace, namespace: {}",
+                    namespace.namespace_name
+                );
+                cache_manager.set_namespace(namespace);
+            }
+            JournalUpdateCacheActionType::Delete => {
+                info!(
+                    "Update the cache, remove namespace, namespace: {}",
+                    namespace.namespace_name
+                );
+                cache_manager.delete_namespace(&namespace.namespace_name);
+            }
+        },
+        Err(e) => {
+            error!(
+                "Namespace information failed to parse with error message :{},body:{}",
+                e, data,
+            );
+        }
+    }
+}
+
 fn parse_shard(
     cache_manager: &Arc<CacheManager>,
     action_type: JournalUpdateCacheActionType,
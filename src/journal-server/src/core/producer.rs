@@ -0,0 +1,568 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::config::journal_server::journal_server_conf;
+use common_base::tools::now_second;
+use grpc_clients::placement::journal::call::{get_producer_sequence, set_producer_sequence};
+use grpc_clients::pool::ClientPool;
+use log::{error, info};
+use metadata_struct::journal::producer::JournalProducerSequence;
+use metadata_struct::journal::segment::JournalSegment;
+use protocol::placement_center::placement_center_journal::{
+    GetProducerSequenceRequest, SetProducerSequenceRequest,
+};
+use rocksdb_engine::engine::{
+    rocksdb_engine_delete, rocksdb_engine_get, rocksdb_engine_prefix_map, rocksdb_engine_save,
+};
+use rocksdb_engine::RocksDBEngine;
+use serde::{Deserialize, Serialize};
+use tokio::select;
+use tokio::sync::{broadcast, OwnedMutexGuard};
+use tokio::time::sleep;
+
+use super::cache::CacheManager;
+use super::consts::{
+    DB_COLUMN_FAMILY_INDEX, PRODUCER_SEQUENCE_EXPIRE_SEC, PRODUCER_SEQUENCE_SYNC_INTERVAL_MS,
+    PRODUCER_SEQUENCE_WINDOW,
+};
+use super::error::JournalServerError;
+
+/// Sequence state of a producer on a shard. It is kept per shard rather than per segment,
+/// so that a retry arriving after the active segment has rolled is still recognized.
+/// The shard leader keeps the state locally and replicates it to the placement center in the
+/// background, the placement center copy lets a new leader pick it up.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProducerSequence {
+    pub producer_id: String,
+    pub last_pkid: u64,
+    // pkid -> offset of the most recent records of the producer, at most PRODUCER_SEQUENCE_WINDOW
+    #[serde(alias = "last_batch")]
+    pub recent_offsets: BTreeMap<u64, u64>,
+    pub update_time: u64,
+    // The segment and leader epoch the local copy was saved under. Another node may have written
+    // the shard since, so the local copy is only used while they are still current.
+    #[serde(default)]
+    pub segment_seq: u32,
+    #[serde(default)]
+    pub leader_epoch: u32,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum ProducerSequenceCheck {
+    Accept,
+    Duplicate(u64),
+    OutOfOrder,
+}
+
+impl ProducerSequence {
+    pub fn check(&self, pkid: u64) -> ProducerSequenceCheck {
+        if pkid > self.last_pkid {
+            return ProducerSequenceCheck::Accept;
+        }
+
+        if let Some(offset) = self.recent_offsets.get(&pkid) {
+            return ProducerSequenceCheck::Duplicate(*offset);
+        }
+
+        ProducerSequenceCheck::OutOfOrder
+    }
+
+    /// Record the offsets of the records just written. Older pkids stay in the window, so a
+    /// retry of an earlier request that is still in flight is recognized as a duplicate.
+    pub fn record(&mut self, offsets: &HashMap<u64, u64>) {
+        for (pkid, offset) in offsets.iter() {
+            self.last_pkid = self.last_pkid.max(*pkid);
+            self.recent_offsets.insert(*pkid, *offset);
+        }
+        self.trim();
+        self.update_time = now_second();
+    }
+
+    // Both copies only ever hold pkids the shard has accepted, so the union of them is still valid
+    fn merge(&mut self, other: ProducerSequence) {
+        self.last_pkid = self.last_pkid.max(other.last_pkid);
+        self.recent_offsets.extend(other.recent_offsets);
+        self.update_time = self.update_time.max(other.update_time);
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        while self.recent_offsets.len() > PRODUCER_SEQUENCE_WINDOW {
+            self.recent_offsets.pop_first();
+        }
+    }
+
+    fn expired(&self) -> bool {
+        now_second().saturating_sub(self.update_time) > PRODUCER_SEQUENCE_EXPIRE_SEC
+    }
+
+    fn saved_under(&self, segment: &JournalSegment) -> bool {
+        self.segment_seq == segment.segment_seq && self.leader_epoch == segment.leader_epoch
+    }
+}
+
+pub struct ProducerSequenceManager {
+    cache_manager: Arc<CacheManager>,
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+}
+
+impl ProducerSequenceManager {
+    pub fn new(
+        cache_manager: Arc<CacheManager>,
+        rocksdb_engine_handler: Arc<RocksDBEngine>,
+    ) -> Self {
+        ProducerSequenceManager {
+            cache_manager,
+            rocksdb_engine_handler,
+        }
+    }
+
+    /// Serialize the writes of the producer to the shard. The guard must be held from get to save,
+    /// otherwise two requests of the producer could both be accepted against the same state.
+    pub async fn lock(
+        &self,
+        namespace: &str,
+        shard_name: &str,
+        producer_id: &str,
+    ) -> OwnedMutexGuard<()> {
+        let key = producer_sequence_key(namespace, shard_name, producer_id);
+        self.cache_manager
+            .get_producer_lock(&key)
+            .lock_owned()
+            .await
+    }
+
+    /// Get the sequence state of the producer for a write to the segment. The local copy is used
+    /// while it was saved writing the same segment at the same leader epoch. Otherwise another node
+    /// may have written the shard since, so it is merged with the copy in the placement center.
+    pub async fn get(
+        &self,
+        client_pool: &Arc<ClientPool>,
+        segment: &JournalSegment,
+        producer_id: &str,
+    ) -> Result<Option<ProducerSequence>, JournalServerError> {
+        let local = self.get_local(&segment.namespace, &segment.shard_name, producer_id)?;
+        let sequence = match local {
+            Some(sequence) if sequence.saved_under(segment) => Some(sequence),
+            local => {
+                let placement = self
+                    .get_from_placement(client_pool, segment, producer_id)
+                    .await?;
+                match (local, placement) {
+                    (Some(mut local), Some(placement)) => {
+                        local.merge(placement);
+                        Some(local)
+                    }
+                    (local, placement) => local.or(placement),
+                }
+            }
+        };
+
+        // The producer has been idle for too long, its retries are no longer tracked
+        Ok(sequence.filter(|sequence| !sequence.expired()))
+    }
+
+    /// Save the sequence state of the producer after a write to the segment. Only the local copy is
+    /// saved before the write returns, the placement center copy is queued and written in batches
+    /// by start_producer_sequence_sync_thread.
+    pub fn save(
+        &self,
+        segment: &JournalSegment,
+        mut sequence: ProducerSequence,
+    ) -> Result<(), JournalServerError> {
+        sequence.segment_seq = segment.segment_seq;
+        sequence.leader_epoch = segment.leader_epoch;
+        self.save_local(&segment.namespace, &segment.shard_name, sequence.clone())?;
+
+        let conf = journal_server_conf();
+        let key = producer_sequence_key(
+            &segment.namespace,
+            &segment.shard_name,
+            &sequence.producer_id,
+        );
+        let data = JournalProducerSequence {
+            cluster_name: conf.cluster_name.clone(),
+            namespace: segment.namespace.clone(),
+            shard_name: segment.shard_name.clone(),
+            producer_id: sequence.producer_id,
+            last_pkid: sequence.last_pkid,
+            last_batch: sequence.recent_offsets.into_iter().collect(),
+            update_time: sequence.update_time,
+        };
+        self.cache_manager.add_producer_sequence_sync(key, data);
+        Ok(())
+    }
+
+    async fn get_from_placement(
+        &self,
+        client_pool: &Arc<ClientPool>,
+        segment: &JournalSegment,
+        producer_id: &str,
+    ) -> Result<Option<ProducerSequence>, JournalServerError> {
+        let conf = journal_server_conf();
+        let request = GetProducerSequenceRequest {
+            cluster_name: conf.cluster_name.clone(),
+            namespace: segment.namespace.clone(),
+            shard_name: segment.shard_name.clone(),
+            producer_id: producer_id.to_string(),
+        };
+        let reply = get_producer_sequence(client_pool, &conf.placement_center, request).await?;
+        if reply.sequence.is_empty() {
+            return Ok(None);
+        }
+
+        let data = serde_json::from_slice::<JournalProducerSequence>(&reply.sequence)?;
+        Ok(Some(ProducerSequence {
+            producer_id: data.producer_id,
+            last_pkid: data.last_pkid,
+            recent_offsets: data.last_batch.into_iter().collect(),
+            update_time: data.update_time,
+            ..Default::default()
+        }))
+    }
+
+    fn get_local(
+        &self,
+        namespace: &str,
+        shard_name: &str,
+        producer_id: &str,
+    ) -> Result<Option<ProducerSequence>, JournalServerError> {
+        let key = producer_sequence_key(namespace, shard_name, producer_id);
+        if let Some(res) = rocksdb_engine_get(
+            self.rocksdb_engine_handler.clone(),
+            DB_COLUMN_FAMILY_INDEX,
+            key,
+        )? {
+            return Ok(Some(serde_json::from_slice::<ProducerSequence>(&res.data)?));
+        }
+        Ok(None)
+    }
+
+    fn save_local(
+        &self,
+        namespace: &str,
+        shard_name: &str,
+        sequence: ProducerSequence,
+    ) -> Result<(), JournalServerError> {
+        let key = producer_sequence_key(namespace, shard_name, &sequence.producer_id);
+        Ok(rocksdb_engine_save(
+            self.rocksdb_engine_handler.clone(),
+            DB_COLUMN_FAMILY_INDEX,
+            key,
+            sequence,
+        )?)
+    }
+
+    pub fn delete_by_shard(
+        &self,
+        namespace: &str,
+        shard_name: &str,
+    ) -> Result<(), JournalServerError> {
+        self.cache_manager
+            .remove_producer_sequence_by_prefix(&producer_sequence_shard_prefix(
+                namespace, shard_name,
+            ));
+
+        let data = rocksdb_engine_prefix_map(
+            self.rocksdb_engine_handler.clone(),
+            DB_COLUMN_FAMILY_INDEX,
+            producer_sequence_shard_prefix(namespace, shard_name),
+        )?;
+        for raw in data.iter() {
+            rocksdb_engine_delete(
+                self.rocksdb_engine_handler.clone(),
+                DB_COLUMN_FAMILY_INDEX,
+                raw.key().to_string(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+pub async fn start_producer_sequence_sync_thread(
+    cache_manager: Arc<CacheManager>,
+    client_pool: Arc<ClientPool>,
+    stop_send: broadcast::Sender<bool>,
+) {
+    let mut stop_recv = stop_send.subscribe();
+    loop {
+        select! {
+            val = stop_recv.recv() =>{
+                if let Ok(flag) = val {
+                    if flag {
+                        sync_producer_sequence(&cache_manager, &client_pool).await;
+                        info!("{}","Producer sequence sync thread exited successfully");
+                        break;
+                    }
+                }
+            }
+            _ = sleep(Duration::from_millis(PRODUCER_SEQUENCE_SYNC_INTERVAL_MS)) => {
+                sync_producer_sequence(&cache_manager, &client_pool).await;
+                cache_manager.clean_producer_lock();
+            }
+        }
+    }
+}
+
+// Writes all queued sequences with a single request. When the request fails they are queued again,
+// unless a newer sequence of the same producer was queued in the meantime.
+async fn sync_producer_sequence(cache_manager: &Arc<CacheManager>, client_pool: &Arc<ClientPool>) {
+    let sequences = cache_manager.take_producer_sequence_sync();
+    if sequences.is_empty() {
+        return;
+    }
+
+    let conf = journal_server_conf();
+    let mut data = Vec::new();
+    for (_, sequence) in sequences.iter() {
+        match serde_json::to_vec(sequence) {
+            Ok(raw) => data.push(raw),
+            Err(e) => error!("{}", e),
+        }
+    }
+    let request = SetProducerSequenceRequest {
+        cluster_name: conf.cluster_name.clone(),
+        sequences: data,
+    };
+    if let Err(e) = set_producer_sequence(client_pool, &conf.placement_center, request).await {
+        error!(
+            "Failed to save producer sequences to the placement center, error message: {}",
+            e
+        );
+        for (key, sequence) in sequences {
+            cache_manager.requeue_producer_sequence_sync(key, sequence);
+        }
+    }
+}
+
+fn producer_sequence_key(namespace: &str, shard_name: &str, producer_id: &str) -> String {
+    format!("/producer/{}/{}/{}", namespace, shard_name, producer_id)
+}
+
+fn producer_sequence_shard_prefix(namespace: &str, shard_name: &str) -> String {
+    format!("/producer/{}/{}/", namespace, shard_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashMap};
+    use std::sync::Arc;
+
+    use common_base::tools::{now_second, unique_id};
+    use metadata_struct::journal::segment::JournalSegment;
+
+    use super::{
+        producer_sequence_key, ProducerSequence, ProducerSequenceCheck, ProducerSequenceManager,
+    };
+    use crate::core::cache::CacheManager;
+    use crate::core::consts::{PRODUCER_SEQUENCE_EXPIRE_SEC, PRODUCER_SEQUENCE_WINDOW};
+    use crate::core::test::test_build_rocksdb_sgement;
+
+    #[test]
+    fn producer_sequence_check_test() {
+        let mut recent_offsets = BTreeMap::new();
+        recent_offsets.insert(8, 100);
+        recent_offsets.insert(9, 101);
+        let sequence = ProducerSequence {
+            producer_id: unique_id(),
+            last_pkid: 9,
+            recent_offsets,
+            update_time: now_second(),
+            ..Default::default()
+        };
+
+        assert_eq!(sequence.check(10), ProducerSequenceCheck::Accept);
+        assert_eq!(sequence.check(9), ProducerSequenceCheck::Duplicate(101));
+        assert_eq!(sequence.check(8), ProducerSequenceCheck::Duplicate(100));
+        assert_eq!(sequence.check(7), ProducerSequenceCheck::OutOfOrder);
+    }
+
+    #[test]
+    fn producer_sequence_record_test() {
+        let mut sequence = ProducerSequence {
+            producer_id: unique_id(),
+            ..Default::default()
+        };
+
+        let mut offsets = HashMap::new();
+        offsets.insert(1, 10);
+        offsets.insert(2, 11);
+        sequence.record(&offsets);
+        let mut offsets = HashMap::new();
+        offsets.insert(3, 12);
+        sequence.record(&offsets);
+
+        // an earlier request retried after a later one was accepted is still a duplicate
+        assert_eq!(sequence.last_pkid, 3);
+        assert_eq!(sequence.check(1), ProducerSequenceCheck::Duplicate(10));
+        assert_eq!(sequence.check(3), ProducerSequenceCheck::Duplicate(12));
+
+        // a request that finished after a later one does not move last_pkid back
+        let mut offsets = HashMap::new();
+        offsets.insert(2, 11);
+        sequence.record(&offsets);
+        assert_eq!(sequence.last_pkid, 3);
+
+        let offsets = (4..PRODUCER_SEQUENCE_WINDOW as u64 + 4)
+            .map(|pkid| (pkid, pkid + 9))
+            .collect::<HashMap<u64, u64>>();
+        sequence.record(&offsets);
+        assert_eq!(sequence.recent_offsets.len(), PRODUCER_SEQUENCE_WINDOW);
+        assert_eq!(sequence.check(3), ProducerSequenceCheck::OutOfOrder);
+        assert_eq!(sequence.check(4), ProducerSequenceCheck::Duplicate(13));
+    }
+
+    #[test]
+    fn producer_sequence_merge_test() {
+        let producer_id = unique_id();
+        let mut local = ProducerSequence {
+            producer_id: producer_id.clone(),
+            last_pkid: 5,
+            recent_offsets: BTreeMap::from([(4, 40), (5, 50)]),
+            update_time: now_second(),
+            ..Default::default()
+        };
+        let placement = ProducerSequence {
+            producer_id,
+            last_pkid: 7,
+            recent_offsets: BTreeMap::from([(6, 60), (7, 70)]),
+            update_time: now_second(),
+            ..Default::default()
+        };
+        local.merge(placement);
+        assert_eq!(local.last_pkid, 7);
+        assert_eq!(local.check(5), ProducerSequenceCheck::Duplicate(50));
+        assert_eq!(local.check(7), ProducerSequenceCheck::Duplicate(70));
+        assert_eq!(local.check(8), ProducerSequenceCheck::Accept);
+    }
+
+    #[test]
+    fn producer_sequence_saved_under_test() {
+        let sequence = ProducerSequence {
+            producer_id: unique_id(),
+            update_time: now_second(),
+            segment_seq: 1,
+            leader_epoch: 2,
+            ..Default::default()
+        };
+        let mut segment = JournalSegment {
+            segment_seq: 1,
+            leader_epoch: 2,
+            ..Default::default()
+        };
+        assert!(sequence.saved_under(&segment));
+        assert!(!sequence.expired());
+
+        // another leader has written the segment since
+        segment.leader_epoch = 3;
+        assert!(!sequence.saved_under(&segment));
+
+        // the shard has rolled to the next segment
+        segment.segment_seq = 2;
+        segment.leader_epoch = 2;
+        assert!(!sequence.saved_under(&segment));
+
+        let sequence = ProducerSequence {
+            update_time: now_second() - PRODUCER_SEQUENCE_EXPIRE_SEC - 1,
+            ..sequence
+        };
+        assert!(sequence.expired());
+    }
+
+    #[test]
+    fn producer_sequence_manager_test() {
+        let (rocksdb_engine_handler, segment_iden) = test_build_rocksdb_sgement();
+        let cache_manager = Arc::new(CacheManager::new());
+        let manager = ProducerSequenceManager::new(cache_manager.clone(), rocksdb_engine_handler);
+        let namespace = segment_iden.namespace.clone();
+        let shard_name = segment_iden.shard_name.clone();
+        let producer_id = unique_id();
+
+        let res = manager
+            .get_local(&namespace, &shard_name, &producer_id)
+            .unwrap();
+        assert!(res.is_none());
+
+        let segment = JournalSegment {
+            namespace: namespace.clone(),
+            shard_name: shard_name.clone(),
+            segment_seq: 1,
+            leader_epoch: 2,
+            ..Default::default()
+        };
+        let sequence = ProducerSequence {
+            producer_id: producer_id.clone(),
+            last_pkid: 3,
+            recent_offsets: BTreeMap::from([(3, 30)]),
+            update_time: now_second(),
+            ..Default::default()
+        };
+        manager.save(&segment, sequence.clone()).unwrap();
+        let res = manager
+            .get_local(&namespace, &shard_name, &producer_id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(res.last_pkid, 3);
+        assert!(res.saved_under(&segment));
+
+        // the placement center copy is only queued
+        let queued = cache_manager.take_producer_sequence_sync();
+        assert_eq!(queued.len(), 1);
+        assert_eq!(
+            queued[0].0,
+            producer_sequence_key(&namespace, &shard_name, &producer_id)
+        );
+        assert_eq!(queued[0].1.last_batch.get(&3), Some(&30));
+
+        manager.save(&segment, sequence).unwrap();
+        manager.delete_by_shard(&namespace, &shard_name).unwrap();
+        let res = manager
+            .get_local(&namespace, &shard_name, &producer_id)
+            .unwrap();
+        assert!(res.is_none());
+        assert!(cache_manager.take_producer_sequence_sync().is_empty());
+    }
+
+    #[tokio::test]
+    async fn producer_sequence_lock_test() {
+        let (rocksdb_engine_handler, segment_iden) = test_build_rocksdb_sgement();
+        let cache_manager = Arc::new(CacheManager::new());
+        let manager = ProducerSequenceManager::new(cache_manager.clone(), rocksdb_engine_handler);
+        let producer_id = unique_id();
+
+        let guard = manager
+            .lock(
+                &segment_iden.namespace,
+                &segment_iden.shard_name,
+                &producer_id,
+            )
+            .await;
+        let key = producer_sequence_key(
+            &segment_iden.namespace,
+            &segment_iden.shard_name,
+            &producer_id,
+        );
+        assert!(cache_manager.get_producer_lock(&key).try_lock().is_err());
+
+        cache_manager.clean_producer_lock();
+        assert!(cache_manager.get_producer_lock(&key).try_lock().is_err());
+
+        drop(guard);
+        assert!(cache_manager.get_producer_lock(&key).try_lock().is_ok());
+    }
+}
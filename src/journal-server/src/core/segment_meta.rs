@@ -38,6 +38,7 @@ pub async fn update_meta_start_offset(
         end_offset: -1,
         start_timestamp: -1,
         end_timestamp: -1,
+        end_position: -1,
     };
     update_segment_meta(&client_pool, &conf.placement_center, request).await?;
     Ok(())
@@ -59,6 +60,7 @@ pub async fn update_meta_end_offset(
         end_offset: end_offset as i64,
         start_timestamp: -1,
         end_timestamp: -1,
+        end_position: -1,
     };
     update_segment_meta(&client_pool, &conf.placement_center, request).await?;
     Ok(())
@@ -80,15 +82,19 @@ pub async fn update_meta_start_timestamp(
         end_offset: -1,
         start_timestamp: start_timestamp as i64,
         end_timestamp: -1,
+        end_position: -1,
     };
     update_segment_meta(&client_pool, &conf.placement_center, request).await?;
     Ok(())
 }
 
+// The end position is reported together with the end timestamp, so the placement center knows the
+// real size of a segment when applying the retention policy.
 pub async fn update_meta_end_timestamp(
     client_pool: Arc<ClientPool>,
     segment_iden: &SegmentIdentity,
     end_timestamp: u64,
+    end_position: u64,
 ) -> Result<(), JournalServerError> {
     let conf = journal_server_conf();
     let next_segment_no = segment_iden.segment_seq;
@@ -101,6 +107,7 @@ pub async fn update_meta_end_timestamp(
         end_offset: -1,
         start_timestamp: -1,
         end_timestamp: end_timestamp as i64,
+        end_position: end_position as i64,
     };
     update_segment_meta(&client_pool, &conf.placement_center, request).await?;
     Ok(())
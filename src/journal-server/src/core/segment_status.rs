@@ -13,18 +13,113 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use common_base::config::journal_server::journal_server_conf;
-use grpc_clients::placement::journal::call::update_segment_status;
+use grpc_clients::placement::journal::call::{create_next_segment, update_segment_status};
 use grpc_clients::pool::ClientPool;
-use log::warn;
+use log::{info, warn};
 use metadata_struct::journal::segment::SegmentStatus;
-use protocol::placement_center::placement_center_journal::UpdateSegmentStatusRequest;
+use protocol::placement_center::placement_center_journal::{
+    CreateNextSegmentRequest, UpdateSegmentStatusRequest,
+};
+use tokio::time::sleep;
 
 use super::cache::CacheManager;
 use super::error::JournalServerError;
+use super::segment_meta::{update_meta_end_offset, update_meta_start_offset};
+use crate::segment::manager::SegmentFileManager;
+use crate::segment::write::drain_write_thread;
 use crate::segment::SegmentIdentity;
 
+/// Seal the active segment immediately instead of waiting for it to fill up, the next segment
+/// becomes the active segment. Must be called on the leader of the segment, returns the end
+/// offset of the sealed segment (-1 when it is empty).
+pub async fn force_sealup_segment(
+    cache_manager: &Arc<CacheManager>,
+    client_pool: &Arc<ClientPool>,
+    segment_file_manager: &Arc<SegmentFileManager>,
+    segment_iden: &SegmentIdentity,
+) -> Result<i64, JournalServerError> {
+    let conf = journal_server_conf();
+    let segment = if let Some(segment) = cache_manager.get_segment(segment_iden) {
+        segment
+    } else {
+        return Err(JournalServerError::SegmentNotExist(segment_iden.name()));
+    };
+
+    if segment.leader != conf.node_id {
+        return Err(JournalServerError::NotLeader(segment_iden.name()));
+    }
+
+    if !(segment.status == SegmentStatus::Write || segment.status == SegmentStatus::PreSealUp) {
+        return Err(JournalServerError::SegmentStatusError(
+            segment_iden.name(),
+            segment.status.to_string(),
+        ));
+    }
+
+    // make sure the next segment exists and has been pushed to this node
+    let request = CreateNextSegmentRequest {
+        cluster_name: conf.cluster_name.clone(),
+        namespace: segment_iden.namespace.clone(),
+        shard_name: segment_iden.shard_name.clone(),
+    };
+    create_next_segment(client_pool, &conf.placement_center, request).await?;
+
+    let mut next_segment_iden = segment_iden.clone();
+    next_segment_iden.segment_seq = segment_iden.segment_seq + 1;
+    let mut next_segment_ready = false;
+    for _ in 0..50 {
+        if cache_manager.get_segment(&next_segment_iden).is_some() {
+            next_segment_ready = true;
+            break;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+    if !next_segment_ready {
+        return Err(JournalServerError::SegmentNotExist(
+            next_segment_iden.name(),
+        ));
+    }
+
+    // PreSealUp rejects new write requests, the batches already queued on the write thread are
+    // drained before the end offset is read so that no record is written after it
+    if segment.status == SegmentStatus::Write {
+        pre_sealup_segment(cache_manager, client_pool, segment_iden).await?;
+    }
+
+    let end_offset = if let Some(offset) = drain_write_thread(cache_manager, segment_iden).await? {
+        offset
+    } else if let Some(offset) = segment_file_manager.get_end_offset(segment_iden) {
+        offset
+    } else {
+        return Err(JournalServerError::SegmentFileMetaNotExists(
+            segment_iden.name(),
+        ));
+    };
+
+    // the records after the end offset belong to the next segment
+    if end_offset >= 0 {
+        update_meta_end_offset(client_pool.clone(), segment_iden, end_offset as u64).await?;
+        update_meta_start_offset(
+            client_pool.clone(),
+            &next_segment_iden,
+            end_offset as u64 + 1,
+        )
+        .await?;
+    }
+
+    sealup_segment(cache_manager, client_pool, segment_iden).await?;
+
+    info!(
+        "Segment {} was forcibly sealed at offset {}",
+        segment_iden.name(),
+        end_offset
+    );
+    Ok(end_offset)
+}
+
 pub async fn sealup_segment(
     cache_manager: &Arc<CacheManager>,
     client_pool: &Arc<ClientPool>,
@@ -20,7 +20,7 @@ use std::time::Duration;
 use common_base::config::journal_server::journal_server_conf;
 use grpc_clients::pool::ClientPool;
 use log::error;
-use metadata_struct::journal::shard::shard_name_iden;
+use metadata_struct::journal::shard::{shard_name_iden, JournalShardConfig};
 use protocol::journal_server::journal_inner::{
     DeleteShardFileRequest, GetShardDeleteStatusRequest,
 };
@@ -32,6 +32,7 @@ use tokio::time::sleep;
 
 use super::cache::CacheManager;
 use super::error::JournalServerError;
+use super::producer::ProducerSequenceManager;
 use super::segment::delete_local_segment;
 use crate::segment::file::data_fold_shard;
 use crate::segment::manager::SegmentFileManager;
@@ -68,6 +69,13 @@ pub fn delete_local_shard(
             }
         }
 
+        // delete producer sequence
+        let producer_sequence_manager =
+            ProducerSequenceManager::new(cache_manager.clone(), rocksdb_engine_handler.clone());
+        if let Err(e) = producer_sequence_manager.delete_by_shard(&req.namespace, &req.shard_name) {
+            error!("{}", e);
+        }
+
         // delete shard
         cache_manager.delete_shard(&req.namespace, &req.shard_name);
 
@@ -104,6 +112,7 @@ pub async fn create_shard_to_place(
     namespace: &str,
     shard_name: &str,
     replica_num: u32,
+    shard_config: &JournalShardConfig,
 ) -> Result<(), JournalServerError> {
     let conf = journal_server_conf();
     let request = CreateShardRequest {
@@ -111,6 +120,7 @@ pub async fn create_shard_to_place(
         namespace: namespace.to_string(),
         shard_name: shard_name.to_string(),
         replica: replica_num,
+        shard_config: serde_json::to_vec(shard_config)?,
     };
     grpc_clients::placement::journal::call::create_shard(
         &client_pool,
@@ -163,6 +173,7 @@ pub async fn try_auto_create_shard(
         namespace,
         shard_name,
         cache_manager.get_cluster().default_shard_replica_num,
+        &JournalShardConfig::default(),
     )
     .await?;
     let mut i = 0;
@@ -19,15 +19,18 @@ use grpc_clients::pool::ClientPool;
 use log::{error, info};
 use protocol::journal_server::codec::JournalEnginePacket;
 use protocol::journal_server::journal_engine::{
-    ApiKey, ApiVersion, CreateShardResp, CreateShardRespBody, DeleteShardResp, DeleteShardRespBody,
-    FetchOffsetResp, FetchOffsetRespBody, GetClusterMetadataResp, GetClusterMetadataRespBody,
-    GetShardMetadataResp, GetShardMetadataRespBody, JournalEngineError, ReadResp, ReadRespBody,
-    RespHeader, WriteResp, WriteRespBody,
+    ApiKey, ApiVersion, CommitOffsetResp, CommitOffsetRespBody, CreateShardResp,
+    CreateShardRespBody, DeleteShardResp, DeleteShardRespBody, FetchOffsetResp,
+    FetchOffsetRespBody, GetClusterMetadataResp, GetClusterMetadataRespBody, GetCommitOffsetResp,
+    GetCommitOffsetRespBody, GetShardMetadataResp, GetShardMetadataRespBody, GroupHeartbeatResp,
+    GroupHeartbeatRespBody, JoinGroupResp, JoinGroupRespBody, JournalEngineError, LeaveGroupResp,
+    LeaveGroupRespBody, ReadResp, ReadRespBody, RespHeader, WriteResp, WriteRespBody,
 };
 use rocksdb_engine::RocksDBEngine;
 
 use super::cluster::ClusterHandler;
 use super::data::DataHandler;
+use super::group::GroupHandler;
 use super::shard::ShardHandler;
 use crate::core::cache::CacheManager;
 use crate::core::error::get_journal_server_code;
@@ -40,6 +43,7 @@ pub struct Command {
     cluster_handler: ClusterHandler,
     shard_handler: ShardHandler,
     data_handler: DataHandler,
+    group_handler: GroupHandler,
 }
 
 impl Command {
@@ -51,6 +55,7 @@ impl Command {
     ) -> Self {
         let cluster_handler = ClusterHandler::new(cache_manager.clone());
         let shard_handler = ShardHandler::new(cache_manager.clone(), client_pool.clone());
+        let group_handler = GroupHandler::new(client_pool.clone());
         let data_handler = DataHandler::new(
             cache_manager,
             segment_file_manager,
@@ -61,6 +66,7 @@ impl Command {
             cluster_handler,
             shard_handler,
             data_handler,
+            group_handler,
         }
     }
 
@@ -237,6 +243,122 @@ impl Command {
                 return Some(JournalEnginePacket::FetchOffsetResp(resp));
             }
 
+            /* Group Handler */
+            JournalEnginePacket::JoinGroupReq(request) => {
+                let mut resp = JoinGroupResp::default();
+                let mut header = RespHeader {
+                    api_key: ApiKey::JoinGroup.into(),
+                    api_version: ApiVersion::V0.into(),
+                    ..Default::default()
+                };
+                match self.group_handler.join_group(request).await {
+                    Ok(data) => {
+                        resp.body = Some(data);
+                    }
+                    Err(e) => {
+                        header.error = Some(JournalEngineError {
+                            code: get_journal_server_code(&e),
+                            error: e.to_string(),
+                        });
+                        resp.body = Some(JoinGroupRespBody::default());
+                    }
+                }
+                resp.header = Some(header);
+                return Some(JournalEnginePacket::JoinGroupResp(resp));
+            }
+
+            JournalEnginePacket::GroupHeartbeatReq(request) => {
+                let mut resp = GroupHeartbeatResp::default();
+                let mut header = RespHeader {
+                    api_key: ApiKey::GroupHeartbeat.into(),
+                    api_version: ApiVersion::V0.into(),
+                    ..Default::default()
+                };
+                match self.group_handler.group_heartbeat(request).await {
+                    Ok(data) => {
+                        resp.body = Some(data);
+                    }
+                    Err(e) => {
+                        header.error = Some(JournalEngineError {
+                            code: get_journal_server_code(&e),
+                            error: e.to_string(),
+                        });
+                        resp.body = Some(GroupHeartbeatRespBody::default());
+                    }
+                }
+                resp.header = Some(header);
+                return Some(JournalEnginePacket::GroupHeartbeatResp(resp));
+            }
+
+            JournalEnginePacket::LeaveGroupReq(request) => {
+                let mut resp = LeaveGroupResp::default();
+                let mut header = RespHeader {
+                    api_key: ApiKey::LeaveGroup.into(),
+                    api_version: ApiVersion::V0.into(),
+                    ..Default::default()
+                };
+                match self.group_handler.leave_group(request).await {
+                    Ok(()) => {
+                        resp.body = Some(LeaveGroupRespBody {});
+                    }
+                    Err(e) => {
+                        header.error = Some(JournalEngineError {
+                            code: get_journal_server_code(&e),
+                            error: e.to_string(),
+                        });
+                        resp.body = Some(LeaveGroupRespBody::default());
+                    }
+                }
+                resp.header = Some(header);
+                return Some(JournalEnginePacket::LeaveGroupResp(resp));
+            }
+
+            JournalEnginePacket::CommitOffsetReq(request) => {
+                let mut resp = CommitOffsetResp::default();
+                let mut header = RespHeader {
+                    api_key: ApiKey::CommitOffset.into(),
+                    api_version: ApiVersion::V0.into(),
+                    ..Default::default()
+                };
+                match self.group_handler.commit_offset(request).await {
+                    Ok(()) => {
+                        resp.body = Some(CommitOffsetRespBody {});
+                    }
+                    Err(e) => {
+                        header.error = Some(JournalEngineError {
+                            code: get_journal_server_code(&e),
+                            error: e.to_string(),
+                        });
+                        resp.body = Some(CommitOffsetRespBody::default());
+                    }
+                }
+                resp.header = Some(header);
+                return Some(JournalEnginePacket::CommitOffsetResp(resp));
+            }
+
+            JournalEnginePacket::GetCommitOffsetReq(request) => {
+                let mut resp = GetCommitOffsetResp::default();
+                let mut header = RespHeader {
+                    api_key: ApiKey::GetCommitOffset.into(),
+                    api_version: ApiVersion::V0.into(),
+                    ..Default::default()
+                };
+                match self.group_handler.get_commit_offset(request).await {
+                    Ok(offsets) => {
+                        resp.body = Some(GetCommitOffsetRespBody { offsets });
+                    }
+                    Err(e) => {
+                        header.error = Some(JournalEngineError {
+                            code: get_journal_server_code(&e),
+                            error: e.to_string(),
+                        });
+                        resp.body = Some(GetCommitOffsetRespBody::default());
+                    }
+                }
+                resp.header = Some(header);
+                return Some(JournalEnginePacket::GetCommitOffsetResp(resp));
+            }
+
             _ => {
                 error!(
                     "server received an unrecognized request, request info: {:?}",
@@ -19,12 +19,13 @@ use grpc_clients::pool::ClientPool;
 use metadata_struct::journal::shard::shard_name_iden;
 use protocol::journal_server::journal_engine::{
     FetchOffsetReq, FetchOffsetRespBody, FetchOffsetShardMeta, JournalEngineError, ReadReq,
-    ReadRespSegmentMessage, WriteReq, WriteRespMessage,
+    ReadReqMessage, ReadRespSegmentMessage, ReadType, WriteReq, WriteRespMessage,
 };
 use rocksdb_engine::RocksDBEngine;
 
 use crate::core::cache::CacheManager;
 use crate::core::error::{get_journal_server_code, JournalServerError};
+use crate::core::namespace::check_namespace_quota;
 use crate::core::shard::try_auto_create_shard;
 use crate::index::time::TimestampIndexManager;
 use crate::segment::manager::SegmentFileManager;
@@ -81,6 +82,8 @@ impl DataHandler {
             self.validator(&segment_identity)?;
         }
 
+        check_namespace_quota(&self.cache_manager, &req_body)?;
+
         let results = write_data_req(
             &self.cache_manager,
             &self.rocksdb_engine_handler,
@@ -115,6 +118,7 @@ impl DataHandler {
                 shard_name: row.shard_name.to_string(),
                 segment_seq: row.segment,
             };
+            self.read_range_validator(&row)?;
             self.validator(&segment_identity)?;
         }
 
@@ -272,6 +276,57 @@ impl DataHandler {
         })
     }
 
+    // Segments before the start segment of the shard have been removed by the retention policy,
+    // so reads that fall into them are rejected with an explicit offset out of range error.
+    fn read_range_validator(&self, row: &ReadReqMessage) -> Result<(), JournalServerError> {
+        let shard = if let Some(shard) = self
+            .cache_manager
+            .get_shard(&row.namespace, &row.shard_name)
+        {
+            shard
+        } else {
+            return Err(JournalServerError::ShardNotExist(
+                row.shard_name.to_string(),
+            ));
+        };
+
+        let start_segment_iden =
+            SegmentIdentity::new(&row.namespace, &row.shard_name, shard.start_segment_seq);
+        let earliest_offset =
+            if let Some(meta) = self.cache_manager.get_segment_meta(&start_segment_iden) {
+                meta.start_offset
+            } else {
+                -1
+            };
+
+        let offset = if let Some(filter) = row.filter.clone() {
+            filter.offset
+        } else {
+            0
+        };
+
+        if row.segment < shard.start_segment_seq {
+            return Err(JournalServerError::OffsetOutOfRange(
+                offset,
+                earliest_offset,
+                shard.name(),
+            ));
+        }
+
+        if row.ready_type() == ReadType::Offset
+            && earliest_offset >= 0
+            && (offset as i64) < earliest_offset
+        {
+            return Err(JournalServerError::OffsetOutOfRange(
+                offset,
+                earliest_offset,
+                shard.name(),
+            ));
+        }
+
+        Ok(())
+    }
+
     fn validator(&self, segment_identity: &SegmentIdentity) -> Result<(), JournalServerError> {
         if self
             .cache_manager
@@ -0,0 +1,200 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::config::journal_server::journal_server_conf;
+use grpc_clients::pool::ClientPool;
+use protocol::journal_server::journal_engine::{
+    CommitOffsetReq, GetCommitOffsetReq, GroupHeartbeatReq, GroupHeartbeatRespBody,
+    GroupShardOffset, JoinGroupReq, JoinGroupRespBody, LeaveGroupReq,
+};
+use protocol::placement_center::placement_center_inner::GetOffsetDataRequest;
+use protocol::placement_center::placement_center_journal::{
+    CommitGroupOffsetRequest, CommitGroupOffsetRequestOffset, GroupHeartbeatRequest,
+    JoinGroupRequest, LeaveGroupRequest,
+};
+
+use crate::core::error::JournalServerError;
+
+/// Consumer groups are coordinated by the Placement Center, the journal server only
+/// forwards the group requests of the clients to it.
+#[derive(Clone)]
+pub struct GroupHandler {
+    client_pool: Arc<ClientPool>,
+}
+
+impl GroupHandler {
+    pub fn new(client_pool: Arc<ClientPool>) -> GroupHandler {
+        GroupHandler { client_pool }
+    }
+
+    pub async fn join_group(
+        &self,
+        request: JoinGroupReq,
+    ) -> Result<JoinGroupRespBody, JournalServerError> {
+        let Some(req_body) = request.body else {
+            return Err(JournalServerError::RequestBodyNotEmpty(
+                "join_group".to_string(),
+            ));
+        };
+
+        let conf = journal_server_conf();
+        let request = JoinGroupRequest {
+            cluster_name: conf.cluster_name.to_string(),
+            namespace: req_body.namespace,
+            group_name: req_body.group_name,
+            member_id: req_body.member_id,
+            shard_names: req_body.shard_names,
+            strategy: req_body.strategy,
+            session_timeout_ms: req_body.session_timeout_ms,
+        };
+        let reply = grpc_clients::placement::journal::call::join_group(
+            &self.client_pool,
+            &conf.placement_center,
+            request,
+        )
+        .await?;
+
+        Ok(JoinGroupRespBody {
+            generation: reply.generation,
+            shard_names: reply.shard_names,
+        })
+    }
+
+    pub async fn group_heartbeat(
+        &self,
+        request: GroupHeartbeatReq,
+    ) -> Result<GroupHeartbeatRespBody, JournalServerError> {
+        let Some(req_body) = request.body else {
+            return Err(JournalServerError::RequestBodyNotEmpty(
+                "group_heartbeat".to_string(),
+            ));
+        };
+
+        let conf = journal_server_conf();
+        let request = GroupHeartbeatRequest {
+            cluster_name: conf.cluster_name.to_string(),
+            namespace: req_body.namespace,
+            group_name: req_body.group_name,
+            member_id: req_body.member_id,
+        };
+        let reply = grpc_clients::placement::journal::call::group_heartbeat(
+            &self.client_pool,
+            &conf.placement_center,
+            request,
+        )
+        .await?;
+
+        Ok(GroupHeartbeatRespBody {
+            generation: reply.generation,
+            shard_names: reply.shard_names,
+        })
+    }
+
+    pub async fn leave_group(&self, request: LeaveGroupReq) -> Result<(), JournalServerError> {
+        let Some(req_body) = request.body else {
+            return Err(JournalServerError::RequestBodyNotEmpty(
+                "leave_group".to_string(),
+            ));
+        };
+
+        let conf = journal_server_conf();
+        let request = LeaveGroupRequest {
+            cluster_name: conf.cluster_name.to_string(),
+            namespace: req_body.namespace,
+            group_name: req_body.group_name,
+            member_id: req_body.member_id,
+        };
+        grpc_clients::placement::journal::call::leave_group(
+            &self.client_pool,
+            &conf.placement_center,
+            request,
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn commit_offset(&self, request: CommitOffsetReq) -> Result<(), JournalServerError> {
+        let Some(req_body) = request.body else {
+            return Err(JournalServerError::RequestBodyNotEmpty(
+                "commit_offset".to_string(),
+            ));
+        };
+
+        // The Placement Center rejects the commit when the member has been fenced out of the group
+        let conf = journal_server_conf();
+        let offsets = req_body
+            .offsets
+            .into_iter()
+            .map(|raw| CommitGroupOffsetRequestOffset {
+                shard_name: raw.shard_name,
+                offset: raw.offset,
+            })
+            .collect();
+        let request = CommitGroupOffsetRequest {
+            cluster_name: conf.cluster_name.to_string(),
+            namespace: req_body.namespace,
+            group_name: req_body.group_name,
+            member_id: req_body.member_id,
+            generation: req_body.generation,
+            offsets,
+        };
+        grpc_clients::placement::journal::call::commit_group_offset(
+            &self.client_pool,
+            &conf.placement_center,
+            request,
+        )
+        .await?;
+        Ok(())
+    }
+
+    pub async fn get_commit_offset(
+        &self,
+        request: GetCommitOffsetReq,
+    ) -> Result<Vec<GroupShardOffset>, JournalServerError> {
+        let Some(req_body) = request.body else {
+            return Err(JournalServerError::RequestBodyNotEmpty(
+                "get_commit_offset".to_string(),
+            ));
+        };
+
+        let conf = journal_server_conf();
+        let request = GetOffsetDataRequest {
+            cluster_name: conf.cluster_name.to_string(),
+            group: req_body.group_name,
+        };
+        let reply = grpc_clients::placement::inner::call::get_offset_data(
+            &self.client_pool,
+            &conf.placement_center,
+            request,
+        )
+        .await?;
+
+        // An empty shard list returns the offsets of all shards in the namespace
+        Ok(reply
+            .offsets
+            .into_iter()
+            .filter(|raw| {
+                raw.namespace == req_body.namespace
+                    && (req_body.shard_names.is_empty()
+                        || req_body.shard_names.contains(&raw.shard_name))
+            })
+            .map(|raw| GroupShardOffset {
+                shard_name: raw.shard_name,
+                offset: raw.offset,
+            })
+            .collect())
+    }
+}
@@ -18,6 +18,7 @@ use common_base::config::journal_server::journal_server_conf;
 use grpc_clients::placement::journal::call::update_segment_status;
 use grpc_clients::pool::ClientPool;
 use metadata_struct::journal::segment::{JournalSegment, SegmentStatus};
+use metadata_struct::journal::shard::JournalShardConfig;
 use protocol::journal_server::journal_engine::{
     ClientSegmentMetadata, CreateShardReq, DeleteShardReq, GetShardMetadataReq,
     GetShardMetadataRespShard,
@@ -58,11 +59,23 @@ impl ShardHandler {
             .get_shard(&req_body.namespace, &req_body.shard_name)
             .is_none()
         {
+            let shard_config = JournalShardConfig {
+                retention_sec: req_body.retention_sec,
+                retention_bytes: req_body.retention_bytes,
+                retention_segment_num: req_body.retention_segment_num,
+                enable_compact: req_body.enable_compact,
+                tombstone_retention_sec: req_body.tombstone_retention_sec,
+                compression: req_body.compression().into(),
+                durability: req_body.durability().into(),
+                fsync_interval_ms: req_body.fsync_interval_ms,
+                fsync_bytes: req_body.fsync_bytes,
+            };
             create_shard_to_place(
                 self.client_pool.clone(),
                 &req_body.namespace,
                 &req_body.shard_name,
                 req_body.replica_num,
+                &shard_config,
             )
             .await?;
         };
@@ -26,7 +26,11 @@ use tokio::select;
 use tokio::sync::broadcast::{self, Receiver};
 use tokio::time::sleep;
 
-use super::keys::{finish_build_index, last_offset_build_index, segment_index_prefix};
+use super::keys::{
+    finish_build_index, key_segment_all_prefix, last_offset_build_index,
+    offset_segment_position_prefix, segment_index_prefix, tag_segment_all_prefix,
+    timestamp_segment_time_prefix,
+};
 use super::offset::OffsetIndexManager;
 use super::tag::TagIndexManager;
 use super::time::TimestampIndexManager;
@@ -34,7 +38,7 @@ use crate::core::cache::CacheManager;
 use crate::core::consts::{BUILD_INDE_PER_RECORD_NUM, DB_COLUMN_FAMILY_INDEX};
 use crate::core::error::JournalServerError;
 use crate::index::IndexData;
-use crate::segment::file::{open_segment_write, ReadData};
+use crate::segment::file::{open_segment_write, ReadData, SegmentFile};
 use crate::segment::manager::SegmentFileManager;
 use crate::segment::SegmentIdentity;
 
@@ -265,7 +269,7 @@ fn save_finish_build_index(
     )?)
 }
 
-fn is_finish_build_index(
+pub(crate) fn is_finish_build_index(
     rocksdb_engine_handler: &Arc<RocksDBEngine>,
     segment_iden: &SegmentIdentity,
 ) -> Result<bool, JournalServerError> {
@@ -317,6 +321,234 @@ fn get_last_offset_build_index(
     Ok(None)
 }
 
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SegmentIndexBuildStatus {
+    // An index build thread is running for the segment
+    pub building: bool,
+    // The segment is sealed and all of its records are indexed
+    pub finished: bool,
+    // The offset of the last indexed record
+    pub last_offset: Option<u64>,
+}
+
+pub fn get_segment_index_build_status(
+    cache_manager: &Arc<CacheManager>,
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    segment_iden: &SegmentIdentity,
+) -> Result<SegmentIndexBuildStatus, JournalServerError> {
+    Ok(SegmentIndexBuildStatus {
+        building: cache_manager.contain_build_index_thread(segment_iden),
+        finished: is_finish_build_index(rocksdb_engine_handler, segment_iden)?,
+        last_offset: get_last_offset_build_index(rocksdb_engine_handler, segment_iden)?,
+    })
+}
+
+/// Drop the data indexes of a segment and build them again from the segment file.
+/// A sealed segment is rebuilt before returning, for a segment that is still being written
+/// the index build thread is restarted from the beginning of the file.
+pub async fn trigger_rebuild_segment_index(
+    cache_manager: &Arc<CacheManager>,
+    segment_file_manager: &Arc<SegmentFileManager>,
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    segment_iden: &SegmentIdentity,
+) -> Result<(), JournalServerError> {
+    let segment = if let Some(segment) = cache_manager.get_segment(segment_iden) {
+        segment
+    } else {
+        return Err(JournalServerError::SegmentNotExist(segment_iden.name()));
+    };
+
+    let segment_file_meta =
+        if let Some(segment_file) = segment_file_manager.get_segment_file(segment_iden) {
+            segment_file
+        } else {
+            return Err(JournalServerError::SegmentFileMetaNotExists(
+                segment_iden.name(),
+            ));
+        };
+
+    stop_segment_build_index_thread(cache_manager, segment_iden).await?;
+    delete_segment_data_index(rocksdb_engine_handler, segment_iden)?;
+
+    if segment.status == SegmentStatus::SealUp {
+        let (segment_file, _) = open_segment_write(cache_manager, segment_iden).await?;
+        rebuild_segment_index(
+            rocksdb_engine_handler,
+            &segment_file,
+            segment_iden,
+            segment_file_meta.start_offset.max(0) as u64,
+        )
+        .await
+    } else {
+        try_trigger_build_index(
+            cache_manager,
+            segment_file_manager,
+            rocksdb_engine_handler,
+            segment_iden,
+        )
+        .await
+    }
+}
+
+async fn stop_segment_build_index_thread(
+    cache_manager: &Arc<CacheManager>,
+    segment_iden: &SegmentIdentity,
+) -> Result<(), JournalServerError> {
+    let thread_data = if let Some(data) = cache_manager.get_build_index_thread(segment_iden) {
+        data
+    } else {
+        return Ok(());
+    };
+
+    thread_data.stop_send.send(true)?;
+
+    // The thread removes itself from the cache when it exits, wait for it so that the
+    // entry of the new thread is not removed by the old one.
+    for _ in 0..50 {
+        if !cache_manager.contain_build_index_thread(segment_iden) {
+            return Ok(());
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+
+    warn!(
+        "segment {} index build thread did not exit in time and is removed from the cache.",
+        segment_iden.name()
+    );
+    cache_manager.remove_build_index_thread(segment_iden);
+    Ok(())
+}
+
+/// Rebuild the position, timestamp, key and tag indexes of a sealed segment whose file has been rewritten.
+/// The old indexes should be removed by `delete_segment_data_index` before the file is rewritten.
+pub async fn rebuild_segment_index(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    segment_file: &SegmentFile,
+    segment_iden: &SegmentIdentity,
+    start_offset: u64,
+) -> Result<(), JournalServerError> {
+    let offset_index = OffsetIndexManager::new(rocksdb_engine_handler.clone());
+    let time_index = TimestampIndexManager::new(rocksdb_engine_handler.clone());
+    let tag_index = TagIndexManager::new(rocksdb_engine_handler.clone());
+
+    let size = 10 * 1024 * 1024;
+    let mut start_position = 0;
+    let mut last_build_offset = 0;
+    loop {
+        let data = segment_file
+            .read_by_offset(start_position, last_build_offset, size, 1000)
+            .await?;
+
+        let last = if let Some(last) = data.last() {
+            last.clone()
+        } else {
+            break;
+        };
+
+        save_record_index(
+            &data,
+            start_offset,
+            segment_iden,
+            &offset_index,
+            &time_index,
+            &tag_index,
+        )?;
+
+        start_position = last.position;
+        last_build_offset = last.record.offset as u64 + 1;
+        save_last_offset_build_index(
+            rocksdb_engine_handler,
+            segment_iden,
+            last.record.offset as u64,
+        )?;
+    }
+
+    save_finish_build_index(rocksdb_engine_handler, segment_iden)
+}
+
+/// Remove the index entries of records after `last_offset`, which no longer exist after the segment file
+/// has been truncated by the recovery pass. The index build restarts from `last_offset`.
+pub fn truncate_segment_index(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    segment_iden: &SegmentIdentity,
+    last_offset: i64,
+) -> Result<(), JournalServerError> {
+    let prefix_list = vec![
+        offset_segment_position_prefix(segment_iden),
+        timestamp_segment_time_prefix(segment_iden),
+        tag_segment_all_prefix(segment_iden),
+        key_segment_all_prefix(segment_iden),
+    ];
+
+    for prefix_key_name in prefix_list {
+        let data = rocksdb_engine_prefix_map(
+            rocksdb_engine_handler.clone(),
+            DB_COLUMN_FAMILY_INDEX,
+            prefix_key_name,
+        )?;
+        for raw in data.iter() {
+            let index_data = serde_json::from_slice::<IndexData>(&raw.value().data)?;
+            if index_data.offset as i64 > last_offset {
+                rocksdb_engine_delete(
+                    rocksdb_engine_handler.clone(),
+                    DB_COLUMN_FAMILY_INDEX,
+                    raw.key().to_string(),
+                )?;
+            }
+        }
+    }
+
+    rocksdb_engine_delete(
+        rocksdb_engine_handler.clone(),
+        DB_COLUMN_FAMILY_INDEX,
+        finish_build_index(segment_iden),
+    )?;
+
+    if last_offset < 0 {
+        return remove_last_offset_build_index(rocksdb_engine_handler, segment_iden);
+    }
+
+    if let Some(last_build_offset) =
+        get_last_offset_build_index(rocksdb_engine_handler, segment_iden)?
+    {
+        if last_build_offset as i64 > last_offset {
+            save_last_offset_build_index(rocksdb_engine_handler, segment_iden, last_offset as u64)?;
+        }
+    }
+    Ok(())
+}
+
+/// Remove the data indexes of a segment, the start/end offset and timestamp of the segment are kept.
+pub(crate) fn delete_segment_data_index(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    segment_iden: &SegmentIdentity,
+) -> Result<(), JournalServerError> {
+    let prefix_list = vec![
+        offset_segment_position_prefix(segment_iden),
+        timestamp_segment_time_prefix(segment_iden),
+        tag_segment_all_prefix(segment_iden),
+        key_segment_all_prefix(segment_iden),
+        finish_build_index(segment_iden),
+        last_offset_build_index(segment_iden),
+    ];
+
+    for prefix_key_name in prefix_list {
+        let data = rocksdb_engine_prefix_map(
+            rocksdb_engine_handler.clone(),
+            DB_COLUMN_FAMILY_INDEX,
+            prefix_key_name,
+        )?;
+        for raw in data.iter() {
+            rocksdb_engine_delete(
+                rocksdb_engine_handler.clone(),
+                DB_COLUMN_FAMILY_INDEX,
+                raw.key().to_string(),
+            )?;
+        }
+    }
+    Ok(())
+}
+
 pub fn delete_segment_index(
     rocksdb_engine_handler: &Arc<RocksDBEngine>,
     segment_iden: &SegmentIdentity,
@@ -97,6 +97,20 @@ pub(crate) fn key_segment_prefix(segment_iden: &SegmentIdentity, key: String) ->
     )
 }
 
+pub(crate) fn tag_segment_all_prefix(segment_iden: &SegmentIdentity) -> String {
+    format!(
+        "/index/{}/{}/{}/tag/",
+        segment_iden.namespace, segment_iden.shard_name, segment_iden.segment_seq
+    )
+}
+
+pub(crate) fn key_segment_all_prefix(segment_iden: &SegmentIdentity) -> String {
+    format!(
+        "/index/{}/{}/{}/key/",
+        segment_iden.namespace, segment_iden.shard_name, segment_iden.segment_seq
+    )
+}
+
 pub(crate) fn finish_build_index(segment_iden: &SegmentIdentity) -> String {
     format!(
         "/index/{}/{}/{}/build/finish",
@@ -160,6 +160,56 @@ impl TimestampIndexManager {
 
         Ok(None)
     }
+
+    /// The timestamp index is sparse, so records at or after `timestamp` may sit before the first
+    /// index entry that reaches it. Reads that must not miss them start from the last entry before it.
+    pub async fn get_last_position_before_timestamp(
+        &self,
+        segment_iden: &SegmentIdentity,
+        timestamp: u64,
+    ) -> Result<Option<IndexData>, JournalServerError> {
+        let prefix_key = timestamp_segment_time_prefix(segment_iden);
+
+        let cf = if let Some(cf) = self
+            .rocksdb_engine_handler
+            .cf_handle(DB_COLUMN_FAMILY_INDEX)
+        {
+            cf
+        } else {
+            return Err(
+                CommonError::RocksDBFamilyNotAvailable(DB_COLUMN_FAMILY_INDEX.to_string()).into(),
+            );
+        };
+
+        let mut iter = self.rocksdb_engine_handler.db.raw_iterator_cf(&cf);
+        iter.seek(prefix_key.clone());
+
+        let mut result: Option<IndexData> = None;
+        while iter.valid() {
+            if let Some(key) = iter.key() {
+                if let Some(val) = iter.value() {
+                    let key = String::from_utf8(key.to_vec())?;
+                    if !key.starts_with(&prefix_key) {
+                        break;
+                    }
+
+                    let data = serde_json::from_slice::<StorageDataWrap>(val)?;
+                    let index_data = serde_json::from_slice::<IndexData>(data.data.as_ref())?;
+
+                    if index_data.timestamp < timestamp
+                        && result
+                            .as_ref()
+                            .map_or(true, |last| index_data.offset > last.offset)
+                    {
+                        result = Some(index_data);
+                    }
+                }
+            }
+            iter.next();
+        }
+
+        Ok(result)
+    }
 }
 
 #[cfg(test)]
@@ -242,5 +292,23 @@ mod tests {
         assert!(res_op.is_some());
         let data = res_op.unwrap();
         assert_eq!(data.offset, 5);
+
+        // before timestamp + 0
+        let res = time_index
+            .get_last_position_before_timestamp(&segment_iden, timestamp)
+            .await;
+        assert!(res.is_ok());
+        assert!(res.unwrap().is_none());
+
+        // before timestamp + 55
+        let res = time_index
+            .get_last_position_before_timestamp(&segment_iden, timestamp + 55)
+            .await;
+        assert!(res.is_ok());
+        let res_op = res.unwrap();
+        assert!(res_op.is_some());
+        let data = res_op.unwrap();
+        assert_eq!(data.offset, 5);
+        assert_eq!(data.position, 25);
     }
 }
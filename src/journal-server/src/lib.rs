@@ -18,6 +18,9 @@ use core::cache::{load_metadata_cache, CacheManager};
 use core::cluster::{
     register_journal_node, report_heartbeat, report_monitor, unregister_journal_node,
 };
+use core::metrics::start_metrics_collect_thread;
+use core::namespace::start_namespace_storage_thread;
+use core::producer::start_producer_sequence_sync_thread;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Duration;
@@ -29,8 +32,10 @@ use grpc_clients::pool::ClientPool;
 use index::engine::{column_family_list, storage_data_fold};
 use log::{error, info};
 use rocksdb_engine::RocksDBEngine;
+use segment::compact::SegmentCompactManager;
 use segment::manager::{
-    load_local_segment_cache, metadata_and_local_segment_diff_check, SegmentFileManager,
+    load_local_segment_cache, metadata_and_local_segment_diff_check, recover_local_segment,
+    SegmentFileManager,
 };
 use segment::scroll::SegmentScrollManager;
 use server::connection_manager::ConnectionManager;
@@ -114,6 +119,7 @@ impl JournalServer {
             self.cache_manager.clone(),
             self.segment_file_manager.clone(),
             self.rocksdb_engine_handler.clone(),
+            self.client_pool.clone(),
         );
         self.server_runtime.spawn(async move {
             match server.start().await {
@@ -173,6 +179,38 @@ impl JournalServer {
         self.daemon_runtime.spawn(async move {
             segment_scroll.trigger_segment_scroll().await;
         });
+
+        let segment_compact = SegmentCompactManager::new(
+            self.cache_manager.clone(),
+            self.rocksdb_engine_handler.clone(),
+        );
+        self.daemon_runtime.spawn(async move {
+            segment_compact.start_compact_thread().await;
+        });
+
+        let cache_manager = self.cache_manager.clone();
+        self.daemon_runtime.spawn(async move {
+            start_namespace_storage_thread(cache_manager).await;
+        });
+
+        let cache_manager = self.cache_manager.clone();
+        let client_pool = self.client_pool.clone();
+        let stop_sx = self.stop_send.clone();
+        self.daemon_runtime.spawn(async move {
+            start_producer_sequence_sync_thread(cache_manager, client_pool, stop_sx).await;
+        });
+
+        let cache_manager = self.cache_manager.clone();
+        let segment_file_manager = self.segment_file_manager.clone();
+        let rocksdb_engine_handler = self.rocksdb_engine_handler.clone();
+        self.daemon_runtime.spawn(async move {
+            start_metrics_collect_thread(
+                cache_manager,
+                segment_file_manager,
+                rocksdb_engine_handler,
+            )
+            .await;
+        });
     }
 
     fn waiting_stop(&self) {
@@ -213,6 +251,16 @@ impl JournalServer {
                 }
             }
 
+            if let Err(e) = recover_local_segment(
+                &self.cache_manager,
+                &self.rocksdb_engine_handler,
+                &self.segment_file_manager,
+            )
+            .await
+            {
+                panic!("{}", e);
+            }
+
             metadata_and_local_segment_diff_check();
 
             // todo
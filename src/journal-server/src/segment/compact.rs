@@ -0,0 +1,369 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_base::config::journal_server::journal_server_conf;
+use log::{error, info};
+use metadata_struct::journal::segment::SegmentStatus;
+use metadata_struct::journal::shard::JournalShard;
+use protocol::journal_server::journal_record::JournalRecord;
+use rocksdb_engine::RocksDBEngine;
+use tokio::time::sleep;
+
+use super::file::SegmentFile;
+use super::SegmentIdentity;
+use crate::core::cache::CacheManager;
+use crate::core::consts::SEGMENT_COMPACT_INTERVAL_SEC;
+use crate::core::error::JournalServerError;
+use crate::index::build::{
+    delete_segment_data_index, is_finish_build_index, rebuild_segment_index,
+};
+
+pub struct SegmentCompactManager {
+    cache_manager: Arc<CacheManager>,
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+}
+
+impl SegmentCompactManager {
+    pub fn new(
+        cache_manager: Arc<CacheManager>,
+        rocksdb_engine_handler: Arc<RocksDBEngine>,
+    ) -> Self {
+        SegmentCompactManager {
+            cache_manager,
+            rocksdb_engine_handler,
+        }
+    }
+
+    pub async fn start_compact_thread(&self) {
+        info!("Segment compact thread started successfully");
+        loop {
+            for shard in self.cache_manager.get_shards() {
+                if !shard.config.enable_compact {
+                    continue;
+                }
+
+                if let Err(e) = self.compact_shard(&shard).await {
+                    error!(
+                        "Shard {} compaction failed with error message :{}",
+                        shard.name(),
+                        e
+                    );
+                }
+            }
+            sleep(Duration::from_secs(SEGMENT_COMPACT_INTERVAL_SEC)).await;
+        }
+    }
+
+    async fn compact_shard(&self, shard: &JournalShard) -> Result<(), JournalServerError> {
+        let conf = journal_server_conf();
+        let mut segments = self
+            .cache_manager
+            .get_segments_list_by_shard(&shard.namespace, &shard.shard_name);
+        segments.retain(|segment| segment.segment_seq >= shard.start_segment_seq);
+        // Newest segment first, so that the keys of newer segments are known when an older segment is compacted
+        segments.sort_by(|a, b| b.segment_seq.cmp(&a.segment_seq));
+
+        // Every replica compacts its own copy of the segment. The result only depends on the content
+        // of the sealed segments and on their metadata, so all replicas end up with the same file.
+        // The active segment is left out, its content may differ between replicas at this moment.
+        segments.retain(|segment| segment.status == SegmentStatus::SealUp);
+
+        // Only sealed segments whose index has been built on this node are rewritten.
+        let mut compactable_segments = HashSet::new();
+        let mut blocked_segments = Vec::new();
+        for segment in segments.iter() {
+            let segment_iden = SegmentIdentity::from_journal_segment(segment);
+            if is_finish_build_index(&self.rocksdb_engine_handler, &segment_iden)? {
+                compactable_segments.insert(segment.segment_seq);
+            } else {
+                blocked_segments.push(segment.segment_seq);
+            }
+        }
+
+        // Tombstones expire relative to the end of the newest sealed segment instead of the local
+        // clock, so that replicas compacting at different times remove the same tombstones.
+        let tombstone_base_time = segments
+            .first()
+            .and_then(|segment| {
+                self.cache_manager
+                    .get_segment_meta(&SegmentIdentity::from_journal_segment(segment))
+            })
+            .map(|meta| meta.end_timestamp.max(0) as u64)
+            .unwrap_or_default();
+
+        let mut newer_keys = HashSet::new();
+        for segment in segments {
+            let fold = if let Some(fold) = segment.get_fold(conf.node_id) {
+                fold
+            } else {
+                continue;
+            };
+
+            let segment_iden = SegmentIdentity::from_journal_segment(&segment);
+            let segment_file = SegmentFile::new(
+                segment_iden.namespace.clone(),
+                segment_iden.shard_name.clone(),
+                segment_iden.segment_seq,
+                fold,
+            );
+
+            if !segment_file.exists() {
+                continue;
+            }
+
+            if !compactable_segments.contains(&segment.segment_seq) {
+                for record in read_segment_records(&segment_file).await? {
+                    if !record.key.is_empty() {
+                        newer_keys.insert(record.key);
+                    }
+                }
+                continue;
+            }
+
+            // A tombstone can only be removed when all older segments are compacted as well,
+            // otherwise an older value of the key would become visible again.
+            let tombstone_expire_time = if tombstone_base_time == 0
+                || blocked_segments
+                    .iter()
+                    .any(|seq| *seq < segment.segment_seq)
+            {
+                None
+            } else {
+                Some(tombstone_base_time.saturating_sub(shard.config.tombstone_retention_sec))
+            };
+
+            let start_offset =
+                if let Some(meta) = self.cache_manager.get_segment_meta(&segment_iden) {
+                    meta.start_offset.max(0) as u64
+                } else {
+                    0
+                };
+
+            if compact_segment(
+                &self.rocksdb_engine_handler,
+                &segment_file,
+                &segment_iden,
+                start_offset,
+                &mut newer_keys,
+                tombstone_expire_time,
+            )
+            .await?
+            {
+                info!("Segment {} compaction completed", segment_iden.name());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Rewrite a sealed segment so that it only keeps the newest record of each key.
+///
+/// Records without a key are always kept and the offsets of the kept records are preserved.
+/// Records whose key appears in `newer_keys` (written by a newer segment) are removed, and
+/// tombstones (records with an empty payload) created before `tombstone_expire_time` are removed.
+/// Returns whether the segment file was rewritten.
+pub(crate) async fn compact_segment(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    segment_file: &SegmentFile,
+    segment_iden: &SegmentIdentity,
+    start_offset: u64,
+    newer_keys: &mut HashSet<String>,
+    tombstone_expire_time: Option<u64>,
+) -> Result<bool, JournalServerError> {
+    let records = read_segment_records(segment_file).await?;
+
+    let mut latest_offsets = HashMap::new();
+    for record in records.iter() {
+        if !record.key.is_empty() {
+            latest_offsets.insert(record.key.clone(), record.offset);
+        }
+    }
+
+    let total = records.len();
+    let mut results = Vec::new();
+    for record in records {
+        if record.key.is_empty() {
+            results.push(record);
+            continue;
+        }
+
+        if newer_keys.contains(&record.key) {
+            continue;
+        }
+
+        if latest_offsets.get(&record.key) != Some(&record.offset) {
+            continue;
+        }
+
+        if record.content.is_empty() {
+            if let Some(expire_time) = tombstone_expire_time {
+                if record.create_time < expire_time {
+                    continue;
+                }
+            }
+        }
+
+        results.push(record);
+    }
+
+    newer_keys.extend(latest_offsets.into_keys());
+
+    if results.len() == total {
+        return Ok(false);
+    }
+
+    // Remove the old index first, reads by offset fall back to scanning the file while the index is rebuilt.
+    delete_segment_data_index(rocksdb_engine_handler, segment_iden)?;
+    segment_file.rewrite(&results).await?;
+    rebuild_segment_index(
+        rocksdb_engine_handler,
+        segment_file,
+        segment_iden,
+        start_offset,
+    )
+    .await?;
+    Ok(true)
+}
+
+async fn read_segment_records(
+    segment_file: &SegmentFile,
+) -> Result<Vec<JournalRecord>, JournalServerError> {
+    let size = 10 * 1024 * 1024;
+    let mut start_position = 0;
+    let mut start_offset = 0;
+    let mut results = Vec::new();
+    loop {
+        let data = segment_file
+            .read_by_offset(start_position, start_offset, size, 1000)
+            .await?;
+
+        let last = if let Some(last) = data.last() {
+            last.clone()
+        } else {
+            break;
+        };
+
+        start_position = last.position;
+        start_offset = last.record.offset as u64 + 1;
+        results.extend(data.into_iter().map(|raw| raw.record));
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use common_base::tools::now_second;
+    use protocol::journal_server::journal_record::JournalRecord;
+
+    use super::{compact_segment, read_segment_records};
+    use crate::core::test::{test_build_data_fold, test_build_rocksdb_sgement};
+    use crate::index::tag::TagIndexManager;
+    use crate::segment::file::SegmentFile;
+
+    #[tokio::test]
+    async fn compact_segment_test() {
+        let (rocksdb_engine_handler, segment_iden) = test_build_rocksdb_sgement();
+        let data_fold = test_build_data_fold();
+        let segment_file = SegmentFile::new(
+            segment_iden.namespace.to_string(),
+            segment_iden.shard_name.to_string(),
+            segment_iden.segment_seq,
+            data_fold.first().unwrap().to_string(),
+        );
+        segment_file.try_create().await.unwrap();
+
+        let now = now_second();
+        // (key, content, create_time)
+        let rows = vec![
+            ("k1", "v1", now),
+            ("k2", "v1", now),
+            ("k1", "v2", now),
+            ("", "no-key", now),
+            ("k3", "v1", now),
+            ("k3", "", now - 100),
+            ("k2", "", now),
+            ("k4", "v1", now),
+        ];
+        let mut records = Vec::new();
+        for (i, (key, content, create_time)) in rows.into_iter().enumerate() {
+            records.push(JournalRecord {
+                key: key.to_string(),
+                content: content.as_bytes().to_vec(),
+                create_time,
+                offset: 100 + i as i64,
+                pkid: i as u64,
+                ..Default::default()
+            });
+        }
+        segment_file.append(&records).await.unwrap();
+
+        // k4 has been written by a newer segment
+        let mut newer_keys = HashSet::new();
+        newer_keys.insert("k4".to_string());
+
+        let res = compact_segment(
+            &rocksdb_engine_handler,
+            &segment_file,
+            &segment_iden,
+            100,
+            &mut newer_keys,
+            Some(now - 10),
+        )
+        .await
+        .unwrap();
+        assert!(res);
+
+        let offsets: Vec<i64> = read_segment_records(&segment_file)
+            .await
+            .unwrap()
+            .iter()
+            .map(|record| record.offset)
+            .collect();
+        assert_eq!(offsets, vec![102, 103, 106]);
+        assert!(newer_keys.contains("k1"));
+        assert!(newer_keys.contains("k3"));
+
+        // the key index points to the new positions
+        let tag_index = TagIndexManager::new(rocksdb_engine_handler.clone());
+        let index_list = tag_index
+            .get_last_positions_by_key(&segment_iden, 0, "k1".to_string(), 10)
+            .await
+            .unwrap();
+        assert_eq!(index_list.len(), 1);
+        let data = segment_file
+            .read_by_positions(vec![index_list.first().unwrap().position])
+            .await
+            .unwrap();
+        assert_eq!(data.first().unwrap().record.offset, 102);
+
+        // nothing left to compact
+        let res = compact_segment(
+            &rocksdb_engine_handler,
+            &segment_file,
+            &segment_iden,
+            100,
+            &mut HashSet::new(),
+            Some(now - 10),
+        )
+        .await
+        .unwrap();
+        assert!(!res);
+    }
+}
@@ -0,0 +1,167 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use protocol::journal_server::journal_engine::DurabilityLevel;
+
+use crate::core::cache::CacheManager;
+use crate::core::consts::DEFAULT_FSYNC_INTERVAL_MS;
+
+/// When the data written to a segment file is fsynced, taken from the shard config.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DurabilityPolicy {
+    pub level: DurabilityLevel,
+    pub fsync_interval_ms: u64,
+    pub fsync_bytes: u64,
+}
+
+impl DurabilityPolicy {
+    pub fn from_shard(
+        cache_manager: &Arc<CacheManager>,
+        namespace: &str,
+        shard_name: &str,
+    ) -> Self {
+        if let Some(shard) = cache_manager.get_shard(namespace, shard_name) {
+            return DurabilityPolicy {
+                level: shard.config.durability.into(),
+                fsync_interval_ms: shard.config.fsync_interval_ms,
+                fsync_bytes: shard.config.fsync_bytes,
+            };
+        }
+        DurabilityPolicy {
+            level: DurabilityLevel::OsBuffered,
+            fsync_interval_ms: 0,
+            fsync_bytes: 0,
+        }
+    }
+
+    fn fsync_interval(&self) -> Duration {
+        // Without any trigger the data would never be synced
+        if self.fsync_interval_ms == 0 && self.fsync_bytes == 0 {
+            return Duration::from_millis(DEFAULT_FSYNC_INTERVAL_MS);
+        }
+        Duration::from_millis(self.fsync_interval_ms)
+    }
+}
+
+/// Tracks the data written to a segment file since its last fsync.
+pub struct SyncState {
+    unsynced_bytes: u64,
+    last_sync: Instant,
+}
+
+impl SyncState {
+    pub fn new() -> Self {
+        SyncState {
+            unsynced_bytes: 0,
+            last_sync: Instant::now(),
+        }
+    }
+
+    pub fn record_write(&mut self, bytes: u64) {
+        self.unsynced_bytes += bytes;
+    }
+
+    pub fn has_unsynced(&self) -> bool {
+        self.unsynced_bytes > 0
+    }
+
+    pub fn synced(&mut self, now: Instant) {
+        self.unsynced_bytes = 0;
+        self.last_sync = now;
+    }
+
+    pub fn need_sync(&self, policy: &DurabilityPolicy, now: Instant) -> bool {
+        if !self.has_unsynced() {
+            return false;
+        }
+
+        match policy.level {
+            DurabilityLevel::OsBuffered => false,
+            DurabilityLevel::FsyncBatch => true,
+            DurabilityLevel::FsyncInterval => {
+                let interval = policy.fsync_interval();
+                (policy.fsync_bytes > 0 && self.unsynced_bytes >= policy.fsync_bytes)
+                    || (!interval.is_zero() && now.duration_since(self.last_sync) >= interval)
+            }
+        }
+    }
+}
+
+impl Default for SyncState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use protocol::journal_server::journal_engine::DurabilityLevel;
+
+    use super::{DurabilityPolicy, SyncState};
+
+    fn policy(
+        level: DurabilityLevel,
+        fsync_interval_ms: u64,
+        fsync_bytes: u64,
+    ) -> DurabilityPolicy {
+        DurabilityPolicy {
+            level,
+            fsync_interval_ms,
+            fsync_bytes,
+        }
+    }
+
+    #[test]
+    fn need_sync_by_level_test() {
+        let mut state = SyncState::new();
+        let now = Instant::now();
+        let batch = policy(DurabilityLevel::FsyncBatch, 0, 0);
+        assert!(!state.need_sync(&batch, now));
+
+        state.record_write(100);
+        assert!(state.need_sync(&batch, now));
+        assert!(!state.need_sync(&policy(DurabilityLevel::OsBuffered, 0, 0), now));
+
+        state.synced(now);
+        assert!(!state.has_unsynced());
+        assert!(!state.need_sync(&batch, now));
+    }
+
+    #[test]
+    fn need_sync_by_interval_test() {
+        let mut state = SyncState::new();
+        let start = Instant::now();
+        state.synced(start);
+        state.record_write(100);
+
+        let by_bytes = policy(DurabilityLevel::FsyncInterval, 0, 1024);
+        assert!(!state.need_sync(&by_bytes, start));
+        state.record_write(1024);
+        assert!(state.need_sync(&by_bytes, start));
+
+        let by_time = policy(DurabilityLevel::FsyncInterval, 200, 0);
+        assert!(!state.need_sync(&by_time, start + Duration::from_millis(100)));
+        assert!(state.need_sync(&by_time, start + Duration::from_millis(200)));
+
+        // Without a trigger the default interval applies
+        let default = policy(DurabilityLevel::FsyncInterval, 0, 0);
+        assert!(!state.need_sync(&default, start + Duration::from_millis(500)));
+        assert!(state.need_sync(&default, start + Duration::from_millis(1000)));
+    }
+}
@@ -12,19 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
 use std::fs::remove_file;
 use std::io::ErrorKind;
 use std::path::Path;
 use std::sync::Arc;
 
-use bytes::BytesMut;
 use common_base::config::journal_server::journal_server_conf;
 use common_base::tools::{file_exists, try_create_fold};
+use crc32c::crc32c;
+use metadata_struct::journal::segment::segment_name;
 use prost::Message;
 use protocol::journal_server::journal_record::JournalRecord;
 use tokio::fs::{self, File, OpenOptions};
-use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncSeekExt, AsyncWrite, AsyncWriteExt};
 
 use super::SegmentIdentity;
 use crate::core::cache::CacheManager;
@@ -67,7 +67,7 @@ pub async fn open_segment_write(
     ))
 }
 
-#[derive(Default)]
+#[derive(Default, Clone)]
 pub struct SegmentFile {
     pub namespace: String,
     pub shard_name: String,
@@ -86,13 +86,19 @@ impl SegmentFile {
         }
     }
 
+    pub fn name(&self) -> String {
+        segment_name(&self.namespace, &self.shard_name, self.segment_no)
+    }
+
     pub async fn try_create(&self) -> Result<(), JournalServerError> {
         try_create_fold(&self.data_fold)?;
         let segment_file = data_file_segment(&self.data_fold, self.segment_no);
         if file_exists(&segment_file) {
             return Ok(());
         }
-        File::create(segment_file).await?;
+        let mut file = File::create(segment_file).await?;
+        file.write_all(&segment_file_header()).await?;
+        file.flush().await?;
         Ok(())
     }
 
@@ -105,28 +111,65 @@ impl SegmentFile {
         Ok(remove_file(segment_file)?)
     }
 
-    pub async fn write(
-        &self,
-        records: &[JournalRecord],
-    ) -> Result<HashMap<u64, u64>, JournalServerError> {
+    /// Append the records to the segment file and return their positions, in the order of the records.
+    /// A segment written in the legacy format keeps that format, so its records stay readable as a whole.
+    pub async fn append(&self, records: &[JournalRecord]) -> Result<Vec<u64>, JournalServerError> {
         let segment_file = data_file_segment(&self.data_fold, self.segment_no);
-        let file = OpenOptions::new().append(true).open(segment_file).await?;
+        let mut file = OpenOptions::new()
+            .read(true)
+            .append(true)
+            .open(segment_file)
+            .await?;
+        // An empty file was created without a header, by try_create of an older version
+        let format = if file.metadata().await?.len() == 0 {
+            file.write_all(&segment_file_header()).await?;
+            SegmentFormat::V1
+        } else {
+            self.read_format(&mut file).await?
+        };
+
         let mut writer = tokio::io::BufWriter::new(file);
+        // A file opened in append mode reports position 0 until the first write
+        writer.seek(std::io::SeekFrom::End(0)).await?;
 
-        let mut results = HashMap::new();
+        let mut results = Vec::with_capacity(records.len());
         for record in records {
             let position = writer.stream_position().await?;
+            write_record(&mut writer, format, record).await?;
+            results.push(position);
+        }
 
-            let data = JournalRecord::encode_to_vec(record);
-            writer.write_u64(record.offset as u64).await?;
-            writer.write_u32(data.len() as u32).await?;
-            writer.write_all(data.as_ref()).await?;
+        writer.flush().await?;
+        Ok(results)
+    }
 
-            results.insert(record.pkid, position);
+    /// Flush the data written to the segment file to disk.
+    /// fsync covers the dirty pages of the file no matter which handle wrote them.
+    pub async fn sync(&self) -> Result<(), JournalServerError> {
+        let segment_file = data_file_segment(&self.data_fold, self.segment_no);
+        let file = OpenOptions::new().append(true).open(segment_file).await?;
+        file.sync_data().await?;
+        Ok(())
+    }
+
+    /// Replace the contents of the segment file with the given records.
+    /// The records are written to a temporary file first, which is then renamed over the segment file.
+    /// The new file is always in the current format, which also migrates a legacy segment.
+    pub async fn rewrite(&self, records: &[JournalRecord]) -> Result<(), JournalServerError> {
+        let segment_file = data_file_segment(&self.data_fold, self.segment_no);
+        let tmp_file = data_file_segment_tmp(&self.data_fold, self.segment_no);
+        let file = File::create(&tmp_file).await?;
+        let mut writer = tokio::io::BufWriter::new(file);
+
+        writer.write_all(&segment_file_header()).await?;
+        for record in records {
+            write_record(&mut writer, SegmentFormat::V1, record).await?;
         }
 
         writer.flush().await?;
-        Ok(results)
+        writer.get_ref().sync_all().await?;
+        fs::rename(tmp_file, segment_file).await?;
+        Ok(())
     }
 
     pub async fn size(&self) -> Result<u64, JournalServerError> {
@@ -143,11 +186,15 @@ impl SegmentFile {
         max_record: u64,
     ) -> Result<Vec<ReadData>, JournalServerError> {
         let segment_file = data_file_segment(&self.data_fold, self.segment_no);
-        let file = File::open(segment_file).await?;
+        let mut file = File::open(segment_file).await?;
+        let format = self.read_format(&mut file).await?;
         let mut reader = tokio::io::BufReader::new(file);
 
+        // position 0 means reading from the first record, which comes after the file header
         reader
-            .seek(std::io::SeekFrom::Current(start_position as i64))
+            .seek(std::io::SeekFrom::Start(
+                start_position.max(format.data_start()),
+            ))
             .await?;
 
         let mut results = Vec::new();
@@ -157,33 +204,26 @@ impl SegmentFile {
                 break;
             }
 
-            // read offset
             let position = reader.stream_position().await?;
-
-            let record_offset = match reader.read_u64().await {
-                Ok(offset) => offset,
-                Err(e) => {
-                    if e.kind() == ErrorKind::UnexpectedEof {
-                        break;
-                    }
-                    return Err(e.into());
-                }
+            let header = match read_record_header(&mut reader, format).await? {
+                Some(header) => header,
+                None => break,
             };
 
-            // read len
-            let len = reader.read_u32().await?;
-
-            if record_offset < start_offset {
-                reader.seek(std::io::SeekFrom::Current(len as i64)).await?;
+            if header.offset < start_offset {
+                reader
+                    .seek(std::io::SeekFrom::Current(header.len as i64))
+                    .await?;
                 continue;
             }
 
             // read body
-            let mut buf = BytesMut::with_capacity(len as usize);
-            reader.read_buf(&mut buf).await?;
+            let mut buf = vec![0; header.len as usize];
+            reader.read_exact(&mut buf).await?;
+            self.check_record_crc(position, &header, &buf)?;
 
             already_size += buf.len() as u64;
-            let record = JournalRecord::decode(buf)?;
+            let record = JournalRecord::decode(buf.as_ref())?;
             results.push(ReadData { position, record });
 
             if results.len() >= max_record as usize {
@@ -199,7 +239,8 @@ impl SegmentFile {
         positions: Vec<u64>,
     ) -> Result<Vec<ReadData>, JournalServerError> {
         let segment_file = data_file_segment(&self.data_fold, self.segment_no);
-        let file = File::open(segment_file).await?;
+        let mut file = File::open(segment_file).await?;
+        let format = self.read_format(&mut file).await?;
         let mut reader = tokio::io::BufReader::new(file);
 
         let mut results = Vec::new();
@@ -207,29 +248,21 @@ impl SegmentFile {
         for position in positions {
             reader.seek(std::io::SeekFrom::Start(position)).await?;
 
-            // read offset
-            let _ = match reader.read_u64().await {
-                Ok(offset) => offset,
-                Err(e) => {
-                    if e.kind() == ErrorKind::UnexpectedEof {
-                        break;
-                    }
-                    return Err(e.into());
-                }
+            let header = match read_record_header(&mut reader, format).await? {
+                Some(header) => header,
+                None => break,
             };
 
-            // read len
-            let len = reader.read_u32().await?;
-
-            if len == 0 {
+            if header.len == 0 {
                 continue;
             }
 
             // read body
-            let mut buf = BytesMut::with_capacity(len as usize);
-            reader.read_buf(&mut buf).await?;
+            let mut buf = vec![0; header.len as usize];
+            reader.read_exact(&mut buf).await?;
+            self.check_record_crc(position, &header, &buf)?;
 
-            let record = JournalRecord::decode(buf)?;
+            let record = JournalRecord::decode(buf.as_ref())?;
 
             results.push(ReadData { position, record });
         }
@@ -237,10 +270,269 @@ impl SegmentFile {
         Ok(results)
     }
 
+    /// Scan the segment file from the beginning and truncate it at the first record that is incomplete
+    /// or fails the CRC check, which is what a torn write leaves behind after a crash.
+    /// Records of a legacy segment carry no CRC, so only their length and body decoding are checked.
+    pub async fn recover(&self) -> Result<SegmentRecoverData, JournalServerError> {
+        let segment_file = data_file_segment(&self.data_fold, self.segment_no);
+        let mut file = File::open(&segment_file).await?;
+        let file_size = file.metadata().await?.len();
+        let format = self.read_format(&mut file).await?;
+        let mut reader = tokio::io::BufReader::new(file);
+
+        // A header cut short by a crash leaves nothing worth keeping
+        let valid_size = if file_size >= format.data_start() {
+            format.data_start()
+        } else {
+            0
+        };
+        let mut result = SegmentRecoverData {
+            last_offset: -1,
+            last_timestamp: 0,
+            valid_size,
+            truncated: false,
+        };
+        reader.seek(std::io::SeekFrom::Start(valid_size)).await?;
+
+        loop {
+            let position = reader.stream_position().await?;
+            if position >= file_size {
+                break;
+            }
+
+            match read_valid_record(&mut reader, format, file_size - position).await {
+                Ok(Some(record)) => {
+                    result.last_offset = record.offset;
+                    result.last_timestamp = record.create_time;
+                    result.valid_size = reader.stream_position().await?;
+                }
+                Ok(None) => break,
+                Err(e) => {
+                    if e.kind() == ErrorKind::UnexpectedEof || e.kind() == ErrorKind::InvalidData {
+                        break;
+                    }
+                    return Err(e.into());
+                }
+            }
+        }
+
+        if result.valid_size < file_size {
+            let file = OpenOptions::new().write(true).open(&segment_file).await?;
+            file.set_len(result.valid_size).await?;
+            file.sync_all().await?;
+            result.truncated = true;
+        }
+
+        Ok(result)
+    }
+
     pub fn exists(&self) -> bool {
         let segment_file = data_file_segment(&self.data_fold, self.segment_no);
         Path::new(&segment_file).exists()
     }
+
+    pub(crate) fn check_record_crc(
+        &self,
+        position: u64,
+        header: &RecordHeader,
+        data: &[u8],
+    ) -> Result<(), JournalServerError> {
+        if let Some(crc) = header.crc {
+            if crc32c(data) != crc {
+                return Err(JournalServerError::RecordCrcCheckFailed(
+                    self.name(),
+                    position,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Read the file header and leave the file positioned anywhere within it.
+    async fn read_format(&self, file: &mut File) -> Result<SegmentFormat, JournalServerError> {
+        let mut head = Vec::with_capacity(SEGMENT_FILE_HEADER_LEN as usize);
+        file.seek(std::io::SeekFrom::Start(0)).await?;
+        (&mut *file)
+            .take(SEGMENT_FILE_HEADER_LEN)
+            .read_to_end(&mut head)
+            .await?;
+        SegmentFormat::detect(&self.name(), &head)
+    }
+}
+
+// Segment file header: magic(4) | format version(u32).
+// Segment files written before the header was introduced start directly with a record, whose first
+// bytes are the high bytes of a record offset and can never match the magic.
+const SEGMENT_FILE_MAGIC: [u8; 4] = *b"RJSG";
+const SEGMENT_FORMAT_VERSION_V1: u32 = 1;
+pub const SEGMENT_FILE_HEADER_LEN: u64 = 8;
+
+fn segment_file_header() -> Vec<u8> {
+    let mut header = Vec::with_capacity(SEGMENT_FILE_HEADER_LEN as usize);
+    header.extend_from_slice(&SEGMENT_FILE_MAGIC);
+    header.extend_from_slice(&SEGMENT_FORMAT_VERSION_V1.to_be_bytes());
+    header
+}
+
+/// Record layout of a segment file, detected from its header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SegmentFormat {
+    // No file header, records are offset(u64) | body len(u32) | body(protobuf JournalRecord)
+    Legacy,
+    // File header, records are offset(u64) | body len(u32) | body crc32c(u32) | body(protobuf JournalRecord)
+    V1,
+}
+
+impl SegmentFormat {
+    /// Detect the format from the first bytes of the segment file.
+    /// A file shorter than the header holds no complete record in either format, so it is treated as a new file.
+    pub fn detect(segment_name: &str, head: &[u8]) -> Result<SegmentFormat, JournalServerError> {
+        if head.len() < SEGMENT_FILE_HEADER_LEN as usize {
+            return Ok(SegmentFormat::V1);
+        }
+
+        if head[0..4] != SEGMENT_FILE_MAGIC {
+            return Ok(SegmentFormat::Legacy);
+        }
+
+        let version = u32::from_be_bytes(head[4..8].try_into().unwrap());
+        if version != SEGMENT_FORMAT_VERSION_V1 {
+            return Err(JournalServerError::UnsupportedSegmentFormatVersion(
+                segment_name.to_string(),
+                version,
+            ));
+        }
+        Ok(SegmentFormat::V1)
+    }
+
+    /// Position of the first record in the file
+    pub fn data_start(&self) -> u64 {
+        match self {
+            SegmentFormat::Legacy => 0,
+            SegmentFormat::V1 => SEGMENT_FILE_HEADER_LEN,
+        }
+    }
+
+    pub fn record_header_len(&self) -> u64 {
+        match self {
+            SegmentFormat::Legacy => 12,
+            SegmentFormat::V1 => 16,
+        }
+    }
+}
+
+/// Whether the position is that of the first record of a segment file, in either format.
+pub fn is_first_record_position(position: u64) -> bool {
+    position == SegmentFormat::Legacy.data_start() || position == SegmentFormat::V1.data_start()
+}
+
+#[derive(Debug, Clone)]
+pub struct RecordHeader {
+    pub offset: u64,
+    pub len: u32,
+    // None for records of a legacy segment
+    pub crc: Option<u32>,
+}
+
+impl RecordHeader {
+    /// Parse the record header, `data` must hold at least `format.record_header_len()` bytes.
+    pub fn parse(format: SegmentFormat, data: &[u8]) -> RecordHeader {
+        let offset = u64::from_be_bytes(data[0..8].try_into().unwrap());
+        let len = u32::from_be_bytes(data[8..12].try_into().unwrap());
+        let crc = match format {
+            SegmentFormat::Legacy => None,
+            SegmentFormat::V1 => Some(u32::from_be_bytes(data[12..16].try_into().unwrap())),
+        };
+        RecordHeader { offset, len, crc }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SegmentRecoverData {
+    // Offset of the last valid record, -1 if the segment has no valid record
+    pub last_offset: i64,
+    pub last_timestamp: u64,
+    pub valid_size: u64,
+    pub truncated: bool,
+}
+
+/// Size of the record in a segment file of the current format: offset(8) + len(4) + crc(4) + body
+pub fn record_size(record: &JournalRecord) -> u64 {
+    SegmentFormat::V1.record_header_len() + record.encoded_len() as u64
+}
+
+async fn write_record<W: AsyncWrite + Unpin>(
+    writer: &mut W,
+    format: SegmentFormat,
+    record: &JournalRecord,
+) -> Result<(), JournalServerError> {
+    let data = JournalRecord::encode_to_vec(record);
+    writer.write_u64(record.offset as u64).await?;
+    writer.write_u32(data.len() as u32).await?;
+    if format == SegmentFormat::V1 {
+        writer.write_u32(crc32c(&data)).await?;
+    }
+    writer.write_all(data.as_ref()).await?;
+    Ok(())
+}
+
+/// Read the header of the next record, returns None at the end of the file.
+async fn read_record_header<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    format: SegmentFormat,
+) -> Result<Option<RecordHeader>, std::io::Error> {
+    let mut buf = vec![0; format.record_header_len() as usize];
+    match reader.read_exact(&mut buf).await {
+        Ok(_) => Ok(Some(RecordHeader::parse(format, &buf))),
+        Err(e) => {
+            if e.kind() == ErrorKind::UnexpectedEof {
+                return Ok(None);
+            }
+            Err(e)
+        }
+    }
+}
+
+async fn read_valid_record<R: AsyncRead + Unpin>(
+    reader: &mut R,
+    format: SegmentFormat,
+    remaining_size: u64,
+) -> Result<Option<JournalRecord>, std::io::Error> {
+    let header = match read_record_header(reader, format).await? {
+        Some(header) => header,
+        None => return Ok(None),
+    };
+
+    // A torn header may carry a garbage length, so check it against the file size before reading the body
+    if format.record_header_len() + header.len as u64 > remaining_size {
+        return Err(std::io::Error::new(
+            ErrorKind::UnexpectedEof,
+            "record body exceeds the end of the segment file",
+        ));
+    }
+
+    let mut buf = vec![0; header.len as usize];
+    reader.read_exact(&mut buf).await?;
+
+    if let Some(crc) = header.crc {
+        if crc32c(&buf) != crc {
+            return Err(std::io::Error::new(
+                ErrorKind::InvalidData,
+                "record crc check failed",
+            ));
+        }
+    }
+
+    let record = JournalRecord::decode(buf.as_ref())
+        .map_err(|e| std::io::Error::new(ErrorKind::InvalidData, e))?;
+
+    if record.offset as u64 != header.offset {
+        return Err(std::io::Error::new(
+            ErrorKind::InvalidData,
+            "record offset does not match the record header",
+        ));
+    }
+    Ok(Some(record))
 }
 
 pub fn data_fold_shard(namespace: &str, shard_name: &str, data_fold: &str) -> String {
@@ -252,8 +544,14 @@ pub fn data_file_segment(data_fold: &str, segment_no: u32) -> String {
     format!("{}/{}.msg", data_fold, segment_no)
 }
 
+pub fn data_file_segment_tmp(data_fold: &str, segment_no: u32) -> String {
+    format!("{}/{}.msg.tmp", data_fold, segment_no)
+}
+
 #[cfg(test)]
 mod tests {
+    use std::fs::OpenOptions;
+    use std::io::Write;
     use std::sync::Arc;
 
     use common_base::config::journal_server::{
@@ -261,9 +559,13 @@ mod tests {
     };
     use common_base::tools::{now_second, unique_id};
     use metadata_struct::journal::segment::{JournalSegment, Replica, SegmentConfig};
+    use prost::Message;
     use protocol::journal_server::journal_record::JournalRecord;
 
-    use super::{data_file_segment, data_fold_shard, open_segment_write, SegmentFile};
+    use super::{
+        data_file_segment, data_fold_shard, open_segment_write, record_size, segment_file_header,
+        SegmentFile, SegmentFormat, SEGMENT_FILE_HEADER_LEN,
+    };
     use crate::core::cache::CacheManager;
     use crate::core::test::{test_build_data_fold, test_build_segment};
     use crate::segment::SegmentIdentity;
@@ -366,7 +668,7 @@ mod tests {
                 tags: vec![],
                 ..Default::default()
             };
-            match segment.write(&[record.clone()]).await {
+            match segment.append(&[record.clone()]).await {
                 Ok(_) => {}
                 Err(e) => {
                     panic!("{:?}", e);
@@ -381,6 +683,37 @@ mod tests {
         assert_eq!(res.len(), 5);
     }
 
+    #[tokio::test]
+    async fn segment_append_position_test() {
+        let data_fold = test_build_data_fold();
+        let segment_iden = test_build_segment();
+
+        let segment = SegmentFile::new(
+            segment_iden.namespace.to_string(),
+            segment_iden.shard_name.to_string(),
+            segment_iden.segment_seq,
+            data_fold.first().unwrap().to_string(),
+        );
+
+        segment.try_create().await.unwrap();
+        let mut expect_position = SEGMENT_FILE_HEADER_LEN;
+        for i in 0..3 {
+            let records: Vec<JournalRecord> = (0..2)
+                .map(|j| JournalRecord {
+                    content: format!("data-{}-{}", i, j).into_bytes(),
+                    offset: i * 2 + j,
+                    ..Default::default()
+                })
+                .collect();
+            let positions = segment.append(&records).await.unwrap();
+            for (record, position) in records.iter().zip(positions) {
+                assert_eq!(position, expect_position);
+                expect_position += record_size(record);
+            }
+        }
+        assert_eq!(segment.size().await.unwrap(), expect_position);
+    }
+
     #[tokio::test]
     async fn segment_read_position_test() {
         let data_fold = test_build_data_fold();
@@ -407,7 +740,7 @@ mod tests {
                 tags: vec![],
                 ..Default::default()
             };
-            match segment.write(&[record.clone()]).await {
+            match segment.append(&[record.clone()]).await {
                 Ok(_) => {}
                 Err(e) => {
                     panic!("{:?}", e);
@@ -415,16 +748,188 @@ mod tests {
             }
         }
 
-        let res = segment.read_by_positions(vec![0]).await.unwrap();
+        let res = segment.read_by_positions(vec![8]).await.unwrap();
         assert_eq!(res.len(), 1);
 
-        let res = segment.read_by_positions(vec![45]).await.unwrap();
+        let res = segment.read_by_positions(vec![57]).await.unwrap();
         assert_eq!(res.len(), 1);
 
-        let res = segment.read_by_positions(vec![0, 45, 90]).await.unwrap();
+        let res = segment.read_by_positions(vec![8, 57, 106]).await.unwrap();
         assert_eq!(res.len(), 3);
 
         let size = segment.size().await.unwrap();
         assert!(size > 0);
     }
+
+    #[tokio::test]
+    async fn segment_recover_test() {
+        let data_fold = test_build_data_fold();
+        let segment_iden = test_build_segment();
+
+        let segment = SegmentFile::new(
+            segment_iden.namespace.to_string(),
+            segment_iden.shard_name.to_string(),
+            segment_iden.segment_seq,
+            data_fold.first().unwrap().to_string(),
+        );
+
+        segment.try_create().await.unwrap();
+        let recover_data = segment.recover().await.unwrap();
+        assert_eq!(recover_data.last_offset, -1);
+        assert!(!recover_data.truncated);
+
+        for i in 0..5 {
+            let record = JournalRecord {
+                content: format!("data1#-{}", i).as_bytes().to_vec(),
+                create_time: now_second(),
+                key: format!("k{}", i),
+                namespace: "n1".to_string(),
+                shard_name: "s1".to_string(),
+                offset: 1000 + i,
+                segment: 1,
+                ..Default::default()
+            };
+            segment.append(&[record]).await.unwrap();
+        }
+
+        let size = segment.size().await.unwrap();
+        let recover_data = segment.recover().await.unwrap();
+        assert_eq!(recover_data.last_offset, 1004);
+        assert_eq!(recover_data.valid_size, size);
+        assert!(!recover_data.truncated);
+
+        // a record header whose body was never written
+        let file_path = data_file_segment(&segment.data_fold, segment.segment_no);
+        let mut file = OpenOptions::new().append(true).open(&file_path).unwrap();
+        file.write_all(&[0, 0, 0, 0, 0, 0, 3, 237, 0, 0, 0, 100, 1, 2])
+            .unwrap();
+
+        let recover_data = segment.recover().await.unwrap();
+        assert_eq!(recover_data.last_offset, 1004);
+        assert_eq!(recover_data.valid_size, size);
+        assert!(recover_data.truncated);
+        assert_eq!(segment.size().await.unwrap(), size);
+
+        // flip a byte in the body of the last record
+        let mut data = std::fs::read(&file_path).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 0xff;
+        std::fs::write(&file_path, data).unwrap();
+
+        let recover_data = segment.recover().await.unwrap();
+        assert_eq!(recover_data.last_offset, 1003);
+        assert!(recover_data.truncated);
+
+        let res = segment
+            .read_by_offset(0, 0, 1024 * 1024, 100)
+            .await
+            .unwrap();
+        assert_eq!(res.len(), 4);
+    }
+
+    #[test]
+    fn segment_format_detect_test() {
+        assert_eq!(
+            SegmentFormat::detect("s1", &segment_file_header()).unwrap(),
+            SegmentFormat::V1
+        );
+        assert_eq!(SegmentFormat::detect("s1", &[]).unwrap(), SegmentFormat::V1);
+        assert_eq!(
+            SegmentFormat::detect("s1", &[0, 0, 0, 0, 0, 0, 3, 232]).unwrap(),
+            SegmentFormat::Legacy
+        );
+
+        let mut header = segment_file_header();
+        header[7] = 2;
+        assert!(SegmentFormat::detect("s1", &header).is_err());
+    }
+
+    #[tokio::test]
+    async fn segment_legacy_format_upgrade_test() {
+        let data_fold = test_build_data_fold();
+        let segment_iden = test_build_segment();
+
+        let segment = SegmentFile::new(
+            segment_iden.namespace.to_string(),
+            segment_iden.shard_name.to_string(),
+            segment_iden.segment_seq,
+            data_fold.first().unwrap().to_string(),
+        );
+        let build_record = |i: i64| JournalRecord {
+            content: format!("data1#-{}", i).as_bytes().to_vec(),
+            create_time: now_second(),
+            key: format!("k{}", i),
+            offset: 1000 + i,
+            ..Default::default()
+        };
+
+        // a segment file written before the file header: offset | len | body, with no crc
+        std::fs::create_dir_all(&segment.data_fold).unwrap();
+        let file_path = data_file_segment(&segment.data_fold, segment.segment_no);
+        let mut data = Vec::new();
+        let mut positions = Vec::new();
+        for i in 0..5 {
+            let body = build_record(i).encode_to_vec();
+            positions.push(data.len() as u64);
+            data.extend_from_slice(&(1000 + i as u64).to_be_bytes());
+            data.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            data.extend_from_slice(&body);
+        }
+        std::fs::write(&file_path, &data).unwrap();
+
+        let recover_data = segment.recover().await.unwrap();
+        assert_eq!(recover_data.last_offset, 1004);
+        assert_eq!(recover_data.valid_size, data.len() as u64);
+        assert!(!recover_data.truncated);
+
+        let res = segment
+            .read_by_offset(0, 1002, 1024 * 1024, 100)
+            .await
+            .unwrap();
+        assert_eq!(res.len(), 3);
+        assert_eq!(res.first().unwrap().position, positions[2]);
+
+        let res = segment
+            .read_by_positions(vec![positions[0], positions[4]])
+            .await
+            .unwrap();
+        assert_eq!(res.last().unwrap().record.key, "k4");
+
+        // appends keep the legacy layout, so the file stays readable as a whole
+        let appended = segment.append(&[build_record(5)]).await.unwrap();
+        assert_eq!(appended, vec![data.len() as u64]);
+        let recover_data = segment.recover().await.unwrap();
+        assert_eq!(recover_data.last_offset, 1005);
+        assert!(!recover_data.truncated);
+
+        // a torn legacy record is truncated
+        let mut file = OpenOptions::new().append(true).open(&file_path).unwrap();
+        file.write_all(&[0, 0, 0, 0, 0, 0, 3, 238, 0, 0, 0, 100, 1, 2])
+            .unwrap();
+        let recover_data = segment.recover().await.unwrap();
+        assert_eq!(recover_data.last_offset, 1005);
+        assert!(recover_data.truncated);
+
+        // rewriting migrates the segment to the current format
+        let records: Vec<JournalRecord> = segment
+            .read_by_offset(0, 0, 1024 * 1024, 100)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|raw| raw.record)
+            .collect();
+        assert_eq!(records.len(), 6);
+        segment.rewrite(&records).await.unwrap();
+        let head = std::fs::read(&file_path).unwrap();
+        assert_eq!(
+            SegmentFormat::detect("s1", &head[..8]).unwrap(),
+            SegmentFormat::V1
+        );
+        let res = segment
+            .read_by_offset(0, 0, 1024 * 1024, 100)
+            .await
+            .unwrap();
+        assert_eq!(res.len(), 6);
+        assert_eq!(res.first().unwrap().position, SEGMENT_FILE_HEADER_LEN);
+    }
 }
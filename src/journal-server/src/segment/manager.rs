@@ -18,14 +18,15 @@ use std::sync::Arc;
 
 use common_base::config::journal_server::journal_server_conf;
 use dashmap::DashMap;
-use log::error;
-use metadata_struct::journal::segment::{segment_name, JournalSegment};
+use log::{error, info, warn};
+use metadata_struct::journal::segment::{segment_name, JournalSegment, SegmentStatus};
 use rocksdb_engine::RocksDBEngine;
 
 use super::file::SegmentFile;
 use super::SegmentIdentity;
 use crate::core::cache::CacheManager;
 use crate::core::error::JournalServerError;
+use crate::index::build::{truncate_segment_index, try_trigger_build_index};
 use crate::index::engine::storage_data_fold;
 use crate::index::offset::OffsetIndexManager;
 use crate::index::time::TimestampIndexManager;
@@ -180,6 +181,18 @@ pub fn load_local_segment_cache(
             let shard_name = tmp_dir_slice.get(1).unwrap();
 
             let file_path = path.display().to_string();
+
+            // A temporary file is left behind when the node stops while a segment is being rewritten
+            if file_path.ends_with(".msg.tmp") {
+                warn!("Remove the unfinished segment temporary file {}", file_path);
+                fs::remove_file(&path)?;
+                continue;
+            }
+
+            if !file_path.ends_with(".msg") {
+                continue;
+            }
+
             let segment_file = file_path.split("/").last().unwrap();
             let segment = segment_file.replace(".msg", "");
             let segment_no = segment.parse::<u32>()?;
@@ -211,6 +224,92 @@ pub fn load_local_segment_cache(
     Ok(())
 }
 
+/// Check the segments that may still be written on this node, truncate the torn record left behind by a crash,
+/// and make the local end offset and index consistent with the records that remain in the file.
+pub async fn recover_local_segment(
+    cache_manager: &Arc<CacheManager>,
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    segment_file_manager: &Arc<SegmentFileManager>,
+) -> Result<(), JournalServerError> {
+    let conf = journal_server_conf();
+    for shard in cache_manager.get_shards() {
+        for segment in cache_manager.get_segments_list_by_shard(&shard.namespace, &shard.shard_name)
+        {
+            if segment.status != SegmentStatus::PreWrite
+                && segment.status != SegmentStatus::Write
+                && segment.status != SegmentStatus::PreSealUp
+            {
+                continue;
+            }
+
+            let fold = if let Some(fold) = segment.get_fold(conf.node_id) {
+                fold
+            } else {
+                continue;
+            };
+
+            let segment_iden = SegmentIdentity::from_journal_segment(&segment);
+            let segment_file = SegmentFile::new(
+                segment_iden.namespace.clone(),
+                segment_iden.shard_name.clone(),
+                segment_iden.segment_seq,
+                fold,
+            );
+
+            if !segment_file.exists() {
+                continue;
+            }
+
+            let recover_data = segment_file.recover().await?;
+            if recover_data.truncated {
+                warn!(
+                    "Segment {} contains an incomplete or corrupted record, the file has been truncated to {} bytes, last offset {}",
+                    segment_iden.name(),
+                    recover_data.valid_size,
+                    recover_data.last_offset
+                );
+                truncate_segment_index(
+                    rocksdb_engine_handler,
+                    &segment_iden,
+                    recover_data.last_offset,
+                )?;
+            }
+
+            let file_meta =
+                if let Some(file_meta) = segment_file_manager.get_segment_file(&segment_iden) {
+                    file_meta
+                } else {
+                    continue;
+                };
+
+            if file_meta.end_offset != recover_data.last_offset {
+                segment_file_manager.update_end_offset(&segment_iden, recover_data.last_offset)?;
+                if recover_data.last_offset >= 0 {
+                    segment_file_manager
+                        .update_end_timestamp(&segment_iden, recover_data.last_timestamp)?;
+                }
+            }
+
+            if recover_data.last_offset >= 0 {
+                try_trigger_build_index(
+                    cache_manager,
+                    segment_file_manager,
+                    rocksdb_engine_handler,
+                    &segment_iden,
+                )
+                .await?;
+            }
+
+            info!(
+                "Segment {} recovery check completed, last offset {}",
+                segment_iden.name(),
+                recover_data.last_offset
+            );
+        }
+    }
+    Ok(())
+}
+
 pub fn metadata_and_local_segment_diff_check() {
     //todo
 }
@@ -226,11 +325,25 @@ pub async fn create_local_segment(
         segment_seq: segment.segment_seq,
     };
 
-    if cache_manager.get_segment(&segment_iden).is_some() {
-        return Ok(());
+    let conf = journal_server_conf();
+    if let Some(mut current) = cache_manager.get_segment(&segment_iden) {
+        // The replicas of the segment may have been reassigned. Only the replica assignment
+        // is refreshed, the status is maintained locally. The local file is created when
+        // this node has just become a replica.
+        current.replicas = segment.replicas.clone();
+        current.leader = segment.leader;
+        current.leader_epoch = segment.leader_epoch;
+        current.isr = segment.isr.clone();
+        cache_manager.set_segment(current);
+        if segment_file_manager
+            .get_segment_file(&segment_iden)
+            .is_some()
+            || segment.get_fold(conf.node_id).is_none()
+        {
+            return Ok(());
+        }
     }
 
-    let conf = journal_server_conf();
     let fold = if let Some(fold) = segment.get_fold(conf.node_id) {
         fold
     } else {
@@ -269,13 +382,17 @@ pub async fn create_local_segment(
 #[cfg(test)]
 mod tests {
 
+    use std::fs::OpenOptions;
+    use std::io::Write;
     use std::sync::Arc;
 
     use common_base::tools::now_second;
+    use metadata_struct::journal::segment::SegmentStatus;
+    use metadata_struct::journal::shard::JournalShard;
 
-    use super::{SegmentFileManager, SegmentFileMetadata};
-    use crate::core::test::{test_build_rocksdb_sgement, test_init_segment};
-    use crate::segment::file::SegmentFile;
+    use super::{recover_local_segment, SegmentFileManager, SegmentFileMetadata};
+    use crate::core::test::{test_base_write_data, test_build_rocksdb_sgement, test_init_segment};
+    use crate::segment::file::{data_file_segment, SegmentFile};
 
     #[tokio::test]
     async fn segment_metadata_test() {
@@ -348,4 +465,51 @@ mod tests {
 
     #[tokio::test]
     async fn load_local_segment_cache_test() {}
+
+    #[tokio::test]
+    async fn recover_local_segment_test() {
+        let (segment_iden, cache_manager, segment_file_manager, fold, rocksdb_engine_handler) =
+            test_base_write_data(10).await;
+
+        let segment_write = SegmentFile::new(
+            segment_iden.namespace.clone(),
+            segment_iden.shard_name.clone(),
+            segment_iden.segment_seq,
+            fold,
+        );
+
+        // simulate a torn write at the end of the segment file
+        let segment_file = data_file_segment(&segment_write.data_fold, segment_iden.segment_seq);
+        let mut file = OpenOptions::new().append(true).open(segment_file).unwrap();
+        file.write_all(&[0, 0, 0, 0, 0, 0, 0, 10, 0, 0, 1]).unwrap();
+        let size = segment_write.size().await.unwrap();
+
+        let mut segment = cache_manager.get_segment(&segment_iden).unwrap();
+        segment.status = SegmentStatus::Write;
+        cache_manager.set_segment(segment);
+        cache_manager.set_shard(JournalShard {
+            namespace: segment_iden.namespace.clone(),
+            shard_name: segment_iden.shard_name.clone(),
+            ..Default::default()
+        });
+
+        let res = recover_local_segment(
+            &cache_manager,
+            &rocksdb_engine_handler,
+            &segment_file_manager,
+        )
+        .await;
+        assert!(res.is_ok());
+
+        assert_eq!(segment_write.size().await.unwrap(), size - 11);
+        let data = segment_write
+            .read_by_offset(0, 0, 1024 * 1024, 1000)
+            .await
+            .unwrap();
+        assert_eq!(data.len(), 10);
+        assert_eq!(
+            segment_file_manager.get_end_offset(&segment_iden),
+            Some(data.last().unwrap().record.offset)
+        );
+    }
 }
@@ -14,9 +14,12 @@
 
 use metadata_struct::journal::segment::{segment_name, JournalSegment};
 
+pub mod compact;
+pub mod durability;
 pub mod file;
 pub mod manager;
 pub mod read;
+pub mod read_cache;
 pub mod scroll;
 pub mod write;
 
@@ -13,18 +13,26 @@
 // limitations under the License.
 
 use std::sync::Arc;
+use std::time::Instant;
 
+use metadata_struct::journal::segment::SegmentStatus;
+use protocol::journal_server::compression::{compression_type, decompress};
 use protocol::journal_server::journal_engine::{
-    ReadReqBody, ReadReqFilter, ReadReqOptions, ReadRespMessage, ReadRespSegmentMessage, ReadType,
+    CompressionType, ReadReqBody, ReadReqFilter, ReadReqOptions, ReadRespMessage,
+    ReadRespSegmentMessage, ReadType,
 };
 use rocksdb_engine::RocksDBEngine;
 
 use super::file::{ReadData, SegmentFile};
+use super::read_cache::SegmentReader;
 use super::SegmentIdentity;
 use crate::core::cache::CacheManager;
+use crate::core::consts::TIME_RANGE_READ_MAX_SCAN_RECORD;
 use crate::core::error::JournalServerError;
+use crate::core::metrics::metrics_read;
 use crate::index::offset::OffsetIndexManager;
 use crate::index::tag::TagIndexManager;
+use crate::index::time::TimestampIndexManager;
 
 pub async fn read_data_req(
     cache_manager: &Arc<CacheManager>,
@@ -34,6 +42,7 @@ pub async fn read_data_req(
 ) -> Result<Vec<ReadRespSegmentMessage>, JournalServerError> {
     let mut results = Vec::new();
     for raw in req_body.messages.iter() {
+        let start = Instant::now();
         let mut shard_message = ReadRespSegmentMessage {
             namespace: raw.namespace.to_string(),
             shard_name: raw.shard_name.to_string(),
@@ -62,11 +71,15 @@ pub async fn read_data_req(
             ));
         };
 
-        let segment_file = SegmentFile::new(
-            segment_iden.namespace.clone(),
-            segment_iden.shard_name.clone(),
-            segment_iden.segment_seq,
-            fold,
+        let segment_reader = SegmentReader::with_cache(
+            SegmentFile::new(
+                segment_iden.namespace.clone(),
+                segment_iden.shard_name.clone(),
+                segment_iden.segment_seq,
+                fold,
+            ),
+            cache_manager.get_segment_read_cache(),
+            segment.status == SegmentStatus::SealUp,
         );
 
         let filter = if let Some(filter) = raw.filter.clone() {
@@ -84,36 +97,51 @@ pub async fn read_data_req(
             ReadReqOptions {
                 max_size: 1024 * 1024,
                 max_record: 100,
+                accept_compressed: false,
             }
         };
 
-        let read_data_list = match raw.ready_type() {
-            ReadType::Offset => {
+        let (read_data_list, next_page_token) = match raw.ready_type() {
+            ReadType::Offset => (
                 read_by_offset(
                     rocksdb_engine_handler,
-                    &segment_file,
+                    &segment_reader,
                     &segment_iden,
                     &filter,
                     &read_options,
                 )
-                .await?
-            }
+                .await?,
+                String::new(),
+            ),
 
-            ReadType::Key => {
+            ReadType::Key => (
                 read_by_key(
                     rocksdb_engine_handler,
-                    &segment_file,
+                    &segment_reader,
                     &segment_iden,
                     &filter,
                     &read_options,
                 )
-                .await?
-            }
+                .await?,
+                String::new(),
+            ),
 
-            ReadType::Tag => {
+            ReadType::Tag => (
                 read_by_tag(
                     rocksdb_engine_handler,
-                    &segment_file,
+                    &segment_reader,
+                    &segment_iden,
+                    &filter,
+                    &read_options,
+                )
+                .await?,
+                String::new(),
+            ),
+
+            ReadType::TimeRange => {
+                read_by_time_range(
+                    rocksdb_engine_handler,
+                    &segment_reader,
                     &segment_iden,
                     &filter,
                     &read_options,
@@ -125,24 +153,54 @@ pub async fn read_data_req(
         let mut record_message = Vec::new();
         for read_data in read_data_list {
             let record = read_data.record;
+            let (value, compression) = record_content(
+                record.compression,
+                record.content,
+                read_options.accept_compressed,
+            )?;
             record_message.push(ReadRespMessage {
                 offset: record.offset as u64,
                 key: record.key,
-                value: record.content,
+                value,
                 tags: record.tags,
                 timestamp: record.create_time,
+                compression: compression.into(),
             });
         }
         shard_message.messages = record_message;
+        shard_message.next_page_token = next_page_token;
+
+        metrics_read(
+            &raw.namespace,
+            &raw.shard_name,
+            start.elapsed().as_secs_f64() * 1000.0,
+            shard_message.messages.len(),
+        );
 
         results.push(shard_message);
     }
     Ok(results)
 }
 
+/// Decompress the record content, unless the reader accepts the content as it is stored.
+fn record_content(
+    compression: i32,
+    content: Vec<u8>,
+    accept_compressed: bool,
+) -> Result<(Vec<u8>, CompressionType), JournalServerError> {
+    let compression = compression_type(compression)?;
+    if accept_compressed || compression == CompressionType::Uncompressed {
+        return Ok((content, compression));
+    }
+    Ok((
+        decompress(compression, &content)?,
+        CompressionType::Uncompressed,
+    ))
+}
+
 async fn read_by_offset(
     rocksdb_engine_handler: &Arc<RocksDBEngine>,
-    segment_file: &SegmentFile,
+    segment_reader: &SegmentReader,
     segment_iden: &SegmentIdentity,
     filter: &ReadReqFilter,
     read_options: &ReadReqOptions,
@@ -157,7 +215,7 @@ async fn read_by_offset(
         0
     };
 
-    let res = segment_file
+    let res = segment_reader
         .read_by_offset(
             start_position,
             filter.offset,
@@ -171,7 +229,7 @@ async fn read_by_offset(
 
 async fn read_by_key(
     rocksdb_engine_handler: &Arc<RocksDBEngine>,
-    segment_file: &SegmentFile,
+    segment_reader: &SegmentReader,
     segment_iden: &SegmentIdentity,
     filter: &ReadReqFilter,
     read_options: &ReadReqOptions,
@@ -188,12 +246,12 @@ async fn read_by_key(
 
     let positions = index_data_list.iter().map(|raw| raw.position).collect();
 
-    segment_file.read_by_positions(positions).await
+    segment_reader.read_by_positions(positions).await
 }
 
 async fn read_by_tag(
     rocksdb_engine_handler: &Arc<RocksDBEngine>,
-    segment_file: &SegmentFile,
+    segment_reader: &SegmentReader,
     segment_iden: &SegmentIdentity,
     filter: &ReadReqFilter,
     read_options: &ReadReqOptions,
@@ -208,7 +266,99 @@ async fn read_by_tag(
         )
         .await?;
     let positions = index_data_list.iter().map(|raw| raw.position).collect();
-    segment_file.read_by_positions(positions).await
+    segment_reader.read_by_positions(positions).await
+}
+
+/// Scan the segment from the last timestamp index entry before the window (or from the page token)
+/// and return the records in `[filter.timestamp, filter.end_timestamp)` that match the optional key
+/// and tag. Records are appended in time order, so the scan stops at the first record past the window.
+/// A page token is returned when the read stops early because of the size, record or scan limits.
+async fn read_by_time_range(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    segment_reader: &SegmentReader,
+    segment_iden: &SegmentIdentity,
+    filter: &ReadReqFilter,
+    read_options: &ReadReqOptions,
+) -> Result<(Vec<ReadData>, String), JournalServerError> {
+    if filter.end_timestamp > 0 && filter.end_timestamp <= filter.timestamp {
+        return Err(JournalServerError::InvalidTimeRange(
+            filter.timestamp,
+            filter.end_timestamp,
+        ));
+    }
+
+    let (mut start_offset, mut start_position) = if filter.page_token.is_empty() {
+        let time_index = TimestampIndexManager::new(rocksdb_engine_handler.clone());
+        if let Some(index_data) = time_index
+            .get_last_position_before_timestamp(segment_iden, filter.timestamp)
+            .await?
+        {
+            (index_data.offset, index_data.position)
+        } else {
+            (0, 0)
+        }
+    } else {
+        decode_page_token(&filter.page_token)?
+    };
+
+    let mut results = Vec::new();
+    let mut already_size = 0;
+    let mut scan_num = 0;
+    loop {
+        let read_data_list = segment_reader
+            .read_by_offset(
+                start_position,
+                start_offset,
+                read_options.max_size,
+                read_options.max_record.max(1),
+            )
+            .await?;
+
+        if read_data_list.is_empty() {
+            return Ok((results, String::new()));
+        }
+
+        for read_data in read_data_list {
+            let record = &read_data.record;
+            if filter.end_timestamp > 0 && record.create_time >= filter.end_timestamp {
+                return Ok((results, String::new()));
+            }
+
+            scan_num += 1;
+            start_offset = record.offset as u64 + 1;
+            start_position = read_data.position;
+
+            if record.create_time >= filter.timestamp
+                && (filter.key.is_empty() || record.key == filter.key)
+                && (filter.tag.is_empty() || record.tags.contains(&filter.tag))
+            {
+                already_size += record.content.len() as u64;
+                results.push(read_data);
+            }
+
+            if results.len() as u64 >= read_options.max_record
+                || already_size >= read_options.max_size
+                || scan_num >= TIME_RANGE_READ_MAX_SCAN_RECORD
+            {
+                return Ok((results, encode_page_token(start_offset, start_position)));
+            }
+        }
+    }
+}
+
+// The token carries the next offset to scan and the position of the record before it,
+// so the next page resumes without looking up the index again.
+fn encode_page_token(offset: u64, position: u64) -> String {
+    format!("{}:{}", offset, position)
+}
+
+fn decode_page_token(token: &str) -> Result<(u64, u64), JournalServerError> {
+    if let Some((offset, position)) = token.split_once(':') {
+        if let (Ok(offset), Ok(position)) = (offset.parse::<u64>(), position.parse::<u64>()) {
+            return Ok((offset, position));
+        }
+    }
+    Err(JournalServerError::InvalidReadPageToken(token.to_string()))
 }
 
 #[cfg(test)]
@@ -216,30 +366,188 @@ mod tests {
     use std::time::Duration;
 
     use common_base::config::journal_server::journal_server_conf;
+    use common_base::tools::now_second;
+    use protocol::journal_server::compression::compress;
     use protocol::journal_server::journal_engine::{
-        ReadReqBody, ReadReqFilter, ReadReqMessage, ReadReqOptions, ReadType,
+        CompressionType, ReadReqBody, ReadReqFilter, ReadReqMessage, ReadReqOptions, ReadType,
     };
     use tokio::time::sleep;
 
-    use super::{read_by_key, read_by_offset, read_by_tag, read_data_req};
+    use super::{
+        decode_page_token, encode_page_token, read_by_key, read_by_offset, read_by_tag,
+        read_by_time_range, read_data_req, record_content,
+    };
     use crate::core::test::test_base_write_data;
     use crate::index::build::try_trigger_build_index;
     use crate::segment::file::SegmentFile;
+    use crate::segment::read_cache::SegmentReader;
+
+    #[test]
+    fn record_content_test() {
+        let raw = "{\"temperature\":21.5}".repeat(10).into_bytes();
+        let compressed = compress(CompressionType::Zstd, &raw).unwrap();
+
+        let (value, compression) =
+            record_content(CompressionType::Zstd.into(), compressed.clone(), false).unwrap();
+        assert_eq!(value, raw);
+        assert_eq!(compression, CompressionType::Uncompressed);
+
+        let (value, compression) =
+            record_content(CompressionType::Zstd.into(), compressed.clone(), true).unwrap();
+        assert_eq!(value, compressed);
+        assert_eq!(compression, CompressionType::Zstd);
+
+        let (value, compression) = record_content(0, raw.clone(), false).unwrap();
+        assert_eq!(value, raw);
+        assert_eq!(compression, CompressionType::Uncompressed);
+
+        assert!(record_content(100, raw, false).is_err());
+    }
+
+    #[test]
+    fn page_token_test() {
+        let token = encode_page_token(10, 2048);
+        assert_eq!(decode_page_token(&token).unwrap(), (10, 2048));
+
+        assert!(decode_page_token("10").is_err());
+        assert!(decode_page_token("a:1").is_err());
+        assert!(decode_page_token("").is_err());
+    }
+
+    #[tokio::test]
+    async fn read_by_time_range_test() {
+        let (segment_iden, _, _, fold, rocksdb_engine_handler) = test_base_write_data(30).await;
+
+        let segment_reader = SegmentReader::new(SegmentFile::new(
+            segment_iden.namespace.clone(),
+            segment_iden.shard_name.clone(),
+            segment_iden.segment_seq,
+            fold,
+        ));
+
+        let read_options = ReadReqOptions {
+            max_record: 10,
+            max_size: 1024 * 1024 * 1024,
+            ..Default::default()
+        };
+
+        // page through the whole window
+        let end_timestamp = now_second() + 100;
+        let mut filter = ReadReqFilter {
+            timestamp: 0,
+            end_timestamp,
+            ..Default::default()
+        };
+        let mut i = 0;
+        loop {
+            let (resp, next_page_token) = read_by_time_range(
+                &rocksdb_engine_handler,
+                &segment_reader,
+                &segment_iden,
+                &filter,
+                &read_options,
+            )
+            .await
+            .unwrap();
+            for row in resp {
+                assert_eq!(row.record.key, format!("key-{}", i));
+                i += 1;
+            }
+            if next_page_token.is_empty() {
+                break;
+            }
+            filter.page_token = next_page_token;
+        }
+        assert_eq!(i, 30);
+
+        // key and tag filters
+        let filter = ReadReqFilter {
+            timestamp: 0,
+            end_timestamp,
+            key: "key-5".to_string(),
+            tag: "tag-5".to_string(),
+            ..Default::default()
+        };
+        let (resp, _) = read_by_time_range(
+            &rocksdb_engine_handler,
+            &segment_reader,
+            &segment_iden,
+            &filter,
+            &read_options,
+        )
+        .await
+        .unwrap();
+        assert_eq!(resp.len(), 1);
+        assert_eq!(resp.first().unwrap().record.key, "key-5");
+
+        let filter = ReadReqFilter {
+            timestamp: 0,
+            end_timestamp,
+            key: "key-5".to_string(),
+            tag: "tag-6".to_string(),
+            ..Default::default()
+        };
+        let (resp, _) = read_by_time_range(
+            &rocksdb_engine_handler,
+            &segment_reader,
+            &segment_iden,
+            &filter,
+            &read_options,
+        )
+        .await
+        .unwrap();
+        assert!(resp.is_empty());
+
+        // window before the data
+        let filter = ReadReqFilter {
+            timestamp: 0,
+            end_timestamp: 1,
+            ..Default::default()
+        };
+        let (resp, next_page_token) = read_by_time_range(
+            &rocksdb_engine_handler,
+            &segment_reader,
+            &segment_iden,
+            &filter,
+            &read_options,
+        )
+        .await
+        .unwrap();
+        assert!(resp.is_empty());
+        assert!(next_page_token.is_empty());
+
+        // empty window
+        let filter = ReadReqFilter {
+            timestamp: 10,
+            end_timestamp: 10,
+            ..Default::default()
+        };
+        assert!(read_by_time_range(
+            &rocksdb_engine_handler,
+            &segment_reader,
+            &segment_iden,
+            &filter,
+            &read_options,
+        )
+        .await
+        .is_err());
+    }
 
     #[tokio::test]
     async fn read_by_offset_test() {
         let (segment_iden, _, _, fold, rocksdb_engine_handler) = test_base_write_data(30).await;
 
-        let segment_file = SegmentFile::new(
+        let segment_reader = SegmentReader::new(SegmentFile::new(
             segment_iden.namespace.clone(),
             segment_iden.shard_name.clone(),
             segment_iden.segment_seq,
             fold,
-        );
+        ));
 
         let read_options = ReadReqOptions {
             max_record: 2,
             max_size: 1024 * 1024 * 1024,
+            ..Default::default()
         };
 
         let filter = ReadReqFilter {
@@ -248,7 +556,7 @@ mod tests {
         };
         let res = read_by_offset(
             &rocksdb_engine_handler,
-            &segment_file,
+            &segment_reader,
             &segment_iden,
             &filter,
             &read_options,
@@ -268,6 +576,7 @@ mod tests {
         let read_options = ReadReqOptions {
             max_record: 5,
             max_size: 1024 * 1024 * 1024,
+            ..Default::default()
         };
         let filter = ReadReqFilter {
             offset: 10,
@@ -275,7 +584,7 @@ mod tests {
         };
         let res = read_by_offset(
             &rocksdb_engine_handler,
-            &segment_file,
+            &segment_reader,
             &segment_iden,
             &filter,
             &read_options,
@@ -309,16 +618,17 @@ mod tests {
 
         sleep(Duration::from_secs(10)).await;
 
-        let segment_file = SegmentFile::new(
+        let segment_reader = SegmentReader::new(SegmentFile::new(
             segment_iden.namespace.clone(),
             segment_iden.shard_name.clone(),
             segment_iden.segment_seq,
             fold,
-        );
+        ));
 
         let read_options = ReadReqOptions {
             max_record: 10,
             max_size: 1024 * 1024 * 1024,
+            ..Default::default()
         };
 
         let key = "key-5".to_string();
@@ -329,7 +639,7 @@ mod tests {
         };
         let res = read_by_key(
             &rocksdb_engine_handler,
-            &segment_file,
+            &segment_reader,
             &segment_iden,
             &filter,
             &read_options,
@@ -358,16 +668,17 @@ mod tests {
 
         sleep(Duration::from_secs(10)).await;
 
-        let segment_file = SegmentFile::new(
+        let segment_reader = SegmentReader::new(SegmentFile::new(
             segment_iden.namespace.clone(),
             segment_iden.shard_name.clone(),
             segment_iden.segment_seq,
             fold,
-        );
+        ));
 
         let read_options = ReadReqOptions {
             max_record: 10,
             max_size: 1024 * 1024 * 1024,
+            ..Default::default()
         };
 
         let tag = "tag-5".to_string();
@@ -378,7 +689,7 @@ mod tests {
         };
         let res = read_by_tag(
             &rocksdb_engine_handler,
-            &segment_file,
+            &segment_reader,
             &segment_iden,
             &filter,
             &read_options,
@@ -421,6 +732,7 @@ mod tests {
                 options: Some(ReadReqOptions {
                     max_size: 1024 * 1024 * 1024,
                     max_record: 2,
+                    ..Default::default()
                 }),
             }],
         };
@@ -461,6 +773,7 @@ mod tests {
                 options: Some(ReadReqOptions {
                     max_size: 1024 * 1024 * 1024,
                     max_record: 2,
+                    ..Default::default()
                 }),
             }],
         };
@@ -497,6 +810,7 @@ mod tests {
                 options: Some(ReadReqOptions {
                     max_size: 1024 * 1024 * 1024,
                     max_record: 2,
+                    ..Default::default()
                 }),
             }],
         };
@@ -0,0 +1,617 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::VecDeque;
+use std::fs;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use common_base::tools::now_second;
+use dashmap::DashMap;
+use memmap2::Mmap;
+use prost::Message;
+use protocol::journal_server::journal_record::JournalRecord;
+use tokio::task::spawn_blocking;
+
+use super::file::{
+    data_file_segment, record_size, ReadData, RecordHeader, SegmentFile, SegmentFormat,
+};
+use super::SegmentIdentity;
+use crate::core::consts::{
+    HOT_SEGMENT_CACHE_IDLE_SEC, HOT_SEGMENT_CACHE_MAX_BYTES, HOT_SEGMENT_CACHE_TOTAL_MAX_BYTES,
+    SEALED_SEGMENT_MMAP_MAX_NUM,
+};
+use crate::core::error::JournalServerError;
+use crate::core::metrics::metrics_read_source;
+
+// offset(8) + len(4) + crc(4)
+
+/// Shared read cache of the segments on this node.
+///
+/// Sealed segments are served from memory-mapped files, so concurrent readers share the
+/// OS page cache instead of each doing its own reads. Active segments that are being read
+/// keep their most recently written records in memory, which is where tailing readers are.
+/// The records kept for all active segments together stay within a byte budget, the segments
+/// read least recently are dropped first.
+#[derive(Default)]
+pub struct SegmentReadCache {
+    // (segment_name, SealedSegmentMap)
+    sealed: DashMap<String, Arc<SealedSegmentMap>>,
+    // (segment_name, HotSegmentCache)
+    hot: DashMap<String, HotSegmentCache>,
+    // Bytes of the records held by all the hot segment caches
+    hot_bytes: AtomicU64,
+}
+
+struct SealedSegmentMap {
+    data: Mmap,
+    // The file is mapped again when it is replaced, e.g. by compaction
+    len: u64,
+    modified: Option<SystemTime>,
+    last_read: AtomicU64,
+}
+
+#[derive(Default)]
+struct HotSegmentCache {
+    records: VecDeque<ReadData>,
+    bytes: u64,
+    last_read: AtomicU64,
+}
+
+impl SegmentReadCache {
+    pub fn new() -> Self {
+        SegmentReadCache::default()
+    }
+
+    /// Add the records just written to an active segment, if the segment is being read.
+    pub fn append_hot(&self, segment_iden: &SegmentIdentity, records: Vec<ReadData>) {
+        let name = segment_iden.name();
+        let Some(mut cache) = self.hot.get_mut(&name) else {
+            return;
+        };
+
+        if now_second().saturating_sub(cache.last_read.load(Ordering::Relaxed))
+            >= HOT_SEGMENT_CACHE_IDLE_SEC
+        {
+            drop(cache);
+            self.remove_hot(&name);
+            return;
+        }
+
+        let before = cache.bytes;
+        cache.append(records, HOT_SEGMENT_CACHE_MAX_BYTES);
+        // Counted while the entry is locked, so that a concurrent remove_hot sees the new size
+        if cache.bytes >= before {
+            self.hot_bytes
+                .fetch_add(cache.bytes - before, Ordering::Relaxed);
+        } else {
+            self.hot_bytes
+                .fetch_sub(before - cache.bytes, Ordering::Relaxed);
+        }
+        drop(cache);
+
+        self.evict_hot(&name, HOT_SEGMENT_CACHE_TOTAL_MAX_BYTES);
+    }
+
+    pub fn remove(&self, segment_iden: &SegmentIdentity) {
+        let name = segment_iden.name();
+        self.sealed.remove(&name);
+        self.remove_hot(&name);
+    }
+
+    fn remove_hot(&self, name: &str) {
+        if let Some((_, cache)) = self.hot.remove(name) {
+            self.hot_bytes.fetch_sub(cache.bytes, Ordering::Relaxed);
+        }
+    }
+
+    /// Drop the hot caches of the segments read least recently until all of them fit in
+    /// `max_bytes`. The segment just written to is kept, its own cache has a size limit.
+    fn evict_hot(&self, keep: &str, max_bytes: u64) {
+        while self.hot_bytes.load(Ordering::Relaxed) > max_bytes {
+            let oldest = self
+                .hot
+                .iter()
+                .filter(|raw| raw.key() != keep)
+                .min_by_key(|raw| raw.value().last_read.load(Ordering::Relaxed))
+                .map(|raw| raw.key().clone());
+            match oldest {
+                Some(name) => self.remove_hot(&name),
+                None => break,
+            }
+        }
+    }
+
+    fn read_hot_by_offset(
+        &self,
+        name: &str,
+        start_offset: u64,
+        max_size: u64,
+        max_record: u64,
+    ) -> Option<Vec<ReadData>> {
+        if let Some(cache) = self.hot.get(name) {
+            cache.last_read.store(now_second(), Ordering::Relaxed);
+            return cache.read_by_offset(start_offset, max_size, max_record);
+        }
+
+        // Start caching the writes of the segment now that it is read
+        self.hot.insert(
+            name.to_string(),
+            HotSegmentCache {
+                last_read: AtomicU64::new(now_second()),
+                ..Default::default()
+            },
+        );
+        None
+    }
+
+    fn read_hot_by_positions(&self, name: &str, positions: &[u64]) -> Option<Vec<ReadData>> {
+        let cache = self.hot.get(name)?;
+        cache.last_read.store(now_second(), Ordering::Relaxed);
+        cache.read_by_positions(positions)
+    }
+
+    // The file system calls block, they run on the blocking pool instead of the tokio workers
+    async fn get_sealed(
+        &self,
+        segment_file: &SegmentFile,
+    ) -> Result<Option<Arc<SealedSegmentMap>>, JournalServerError> {
+        let name = segment_file.name();
+        let path = data_file_segment(&segment_file.data_fold, segment_file.segment_no);
+        let metadata_path = path.clone();
+        let metadata = spawn_blocking(move || fs::metadata(metadata_path)).await??;
+        let modified = metadata.modified().ok();
+
+        if let Some(map) = self.sealed.get(&name) {
+            if map.len == metadata.len() && map.modified == modified {
+                map.last_read.store(now_second(), Ordering::Relaxed);
+                return Ok(Some(map.clone()));
+            }
+        }
+
+        if metadata.len() == 0 {
+            return Ok(None);
+        }
+
+        let data = spawn_blocking(move || {
+            let file = fs::File::open(&path)?;
+            // SAFETY: sealed segment files are never modified in place. Compaction writes a new
+            // file and renames it over the old one, which leaves this mapping pointing at the old
+            // file, and the changed modification time makes the next read map the new file.
+            unsafe { Mmap::map(&file) }
+        })
+        .await??;
+        let map = Arc::new(SealedSegmentMap {
+            len: data.len() as u64,
+            data,
+            modified,
+            last_read: AtomicU64::new(now_second()),
+        });
+
+        if self.sealed.len() >= SEALED_SEGMENT_MMAP_MAX_NUM && !self.sealed.contains_key(&name) {
+            self.evict_sealed();
+        }
+        // The records cached while the segment was active are served by the mapping now
+        self.remove_hot(&name);
+        self.sealed.insert(name, map.clone());
+        Ok(Some(map))
+    }
+
+    fn evict_sealed(&self) {
+        let oldest = self
+            .sealed
+            .iter()
+            .min_by_key(|raw| raw.value().last_read.load(Ordering::Relaxed))
+            .map(|raw| raw.key().clone());
+        if let Some(name) = oldest {
+            self.sealed.remove(&name);
+        }
+    }
+}
+
+impl HotSegmentCache {
+    fn append(&mut self, records: Vec<ReadData>, max_bytes: u64) {
+        for read_data in records {
+            // Only a contiguous run of offsets can be served from the cache
+            if let Some(last) = self.records.back() {
+                if read_data.record.offset != last.record.offset + 1 {
+                    self.records.clear();
+                    self.bytes = 0;
+                }
+            }
+            self.bytes += record_size(&read_data.record);
+            self.records.push_back(read_data);
+        }
+
+        while self.bytes > max_bytes && self.records.len() > 1 {
+            if let Some(read_data) = self.records.pop_front() {
+                self.bytes -= record_size(&read_data.record);
+            }
+        }
+    }
+
+    /// Returns None when the cache does not hold `start_offset`, the caller then reads the file.
+    fn read_by_offset(
+        &self,
+        start_offset: u64,
+        max_size: u64,
+        max_record: u64,
+    ) -> Option<Vec<ReadData>> {
+        let first = self.records.front()?.record.offset as u64;
+        let last = self.records.back()?.record.offset as u64;
+        if start_offset < first || start_offset > last {
+            return None;
+        }
+
+        let mut results = Vec::new();
+        let mut already_size = 0;
+        for read_data in self.records.range((start_offset - first) as usize..) {
+            if already_size > max_size {
+                break;
+            }
+            already_size += read_data.record.encoded_len() as u64;
+            results.push(read_data.clone());
+            if results.len() >= max_record as usize {
+                break;
+            }
+        }
+        Some(results)
+    }
+
+    fn read_by_positions(&self, positions: &[u64]) -> Option<Vec<ReadData>> {
+        let mut results = Vec::with_capacity(positions.len());
+        for position in positions {
+            let index = self
+                .records
+                .binary_search_by_key(position, |raw| raw.position)
+                .ok()?;
+            results.push(self.records[index].clone());
+        }
+        Some(results)
+    }
+}
+
+/// Reads the records of a segment, through the read cache when one is given.
+pub struct SegmentReader {
+    pub segment_file: SegmentFile,
+    read_cache: Option<Arc<SegmentReadCache>>,
+    sealed: bool,
+}
+
+impl SegmentReader {
+    pub fn new(segment_file: SegmentFile) -> Self {
+        SegmentReader {
+            segment_file,
+            read_cache: None,
+            sealed: false,
+        }
+    }
+
+    pub fn with_cache(
+        segment_file: SegmentFile,
+        read_cache: Arc<SegmentReadCache>,
+        sealed: bool,
+    ) -> Self {
+        SegmentReader {
+            segment_file,
+            read_cache: Some(read_cache),
+            sealed,
+        }
+    }
+
+    pub async fn read_by_offset(
+        &self,
+        start_position: u64,
+        start_offset: u64,
+        max_size: u64,
+        max_record: u64,
+    ) -> Result<Vec<ReadData>, JournalServerError> {
+        if let Some(read_cache) = &self.read_cache {
+            if self.sealed {
+                if let Some(map) = read_cache.get_sealed(&self.segment_file).await? {
+                    self.metrics_source("mmap");
+                    // Reading the mapping page faults on the file, which blocks like a read does
+                    let segment_file = self.segment_file.clone();
+                    return spawn_blocking(move || {
+                        read_by_offset_from_slice(
+                            &segment_file,
+                            &map.data,
+                            start_position,
+                            start_offset,
+                            max_size,
+                            max_record,
+                        )
+                    })
+                    .await?;
+                }
+            } else if let Some(results) = read_cache.read_hot_by_offset(
+                &self.segment_file.name(),
+                start_offset,
+                max_size,
+                max_record,
+            ) {
+                self.metrics_source("hot");
+                return Ok(results);
+            }
+        }
+
+        self.metrics_source("file");
+        self.segment_file
+            .read_by_offset(start_position, start_offset, max_size, max_record)
+            .await
+    }
+
+    pub async fn read_by_positions(
+        &self,
+        positions: Vec<u64>,
+    ) -> Result<Vec<ReadData>, JournalServerError> {
+        if let Some(read_cache) = &self.read_cache {
+            if self.sealed {
+                if let Some(map) = read_cache.get_sealed(&self.segment_file).await? {
+                    self.metrics_source("mmap");
+                    let segment_file = self.segment_file.clone();
+                    return spawn_blocking(move || {
+                        read_by_positions_from_slice(&segment_file, &map.data, &positions)
+                    })
+                    .await?;
+                }
+            } else if let Some(results) =
+                read_cache.read_hot_by_positions(&self.segment_file.name(), &positions)
+            {
+                self.metrics_source("hot");
+                return Ok(results);
+            }
+        }
+
+        self.metrics_source("file");
+        self.segment_file.read_by_positions(positions).await
+    }
+
+    fn metrics_source(&self, source: &str) {
+        metrics_read_source(
+            &self.segment_file.namespace,
+            &self.segment_file.shard_name,
+            source,
+        );
+    }
+}
+
+/// Decode the record at `position`, returns None when the data ends before the record does.
+fn decode_record(
+    segment_file: &SegmentFile,
+    format: SegmentFormat,
+    data: &[u8],
+    position: usize,
+    decode_body: bool,
+) -> Result<Option<(u64, usize, Option<JournalRecord>)>, JournalServerError> {
+    let header_len = format.record_header_len() as usize;
+    if position + header_len > data.len() {
+        return Ok(None);
+    }
+    let header = RecordHeader::parse(format, &data[position..position + header_len]);
+
+    let next_position = position + header_len + header.len as usize;
+    if next_position > data.len() {
+        return Ok(None);
+    }
+    if !decode_body {
+        return Ok(Some((header.offset, next_position, None)));
+    }
+
+    let body = &data[position + header_len..next_position];
+    segment_file.check_record_crc(position as u64, &header, body)?;
+    Ok(Some((
+        header.offset,
+        next_position,
+        Some(JournalRecord::decode(body)?),
+    )))
+}
+
+/// Same as [`SegmentFile::read_by_offset`], over the mapped segment file.
+fn read_by_offset_from_slice(
+    segment_file: &SegmentFile,
+    data: &[u8],
+    start_position: u64,
+    start_offset: u64,
+    max_size: u64,
+    max_record: u64,
+) -> Result<Vec<ReadData>, JournalServerError> {
+    let format = SegmentFormat::detect(&segment_file.name(), data)?;
+    let mut results = Vec::new();
+    let mut already_size = 0;
+    let mut position = start_position.max(format.data_start()) as usize;
+    loop {
+        if already_size > max_size {
+            break;
+        }
+
+        let Some((offset, next_position, _)) =
+            decode_record(segment_file, format, data, position, false)?
+        else {
+            break;
+        };
+
+        if offset >= start_offset {
+            if let Some((_, _, Some(record))) =
+                decode_record(segment_file, format, data, position, true)?
+            {
+                already_size += (next_position - position) as u64 - format.record_header_len();
+                results.push(ReadData {
+                    position: position as u64,
+                    record,
+                });
+            }
+
+            if results.len() >= max_record as usize {
+                break;
+            }
+        }
+        position = next_position;
+    }
+    Ok(results)
+}
+
+/// Same as [`SegmentFile::read_by_positions`], over the mapped segment file.
+fn read_by_positions_from_slice(
+    segment_file: &SegmentFile,
+    data: &[u8],
+    positions: &[u64],
+) -> Result<Vec<ReadData>, JournalServerError> {
+    let format = SegmentFormat::detect(&segment_file.name(), data)?;
+    let mut results = Vec::new();
+    for position in positions {
+        match decode_record(segment_file, format, data, *position as usize, true)? {
+            Some((_, _, Some(record))) => results.push(ReadData {
+                position: *position,
+                record,
+            }),
+            _ => break,
+        }
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+    use std::sync::Arc;
+
+    use protocol::journal_server::journal_record::JournalRecord;
+
+    use super::{HotSegmentCache, SegmentReadCache, SegmentReader};
+    use crate::core::test::{test_build_data_fold, test_build_segment};
+    use crate::segment::file::{record_size, ReadData, SegmentFile};
+
+    fn build_records(start: u64, num: u64) -> Vec<JournalRecord> {
+        (start..start + num)
+            .map(|i| JournalRecord {
+                content: format!("data-{}", i).into_bytes(),
+                key: format!("key-{}", i),
+                offset: i as i64,
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn build_read_data(records: Vec<JournalRecord>, mut position: u64) -> Vec<ReadData> {
+        let mut results = Vec::new();
+        for record in records {
+            let size = record_size(&record);
+            results.push(ReadData { position, record });
+            position += size;
+        }
+        results
+    }
+
+    #[test]
+    fn hot_segment_cache_test() {
+        let mut cache = HotSegmentCache::default();
+        let read_data = build_read_data(build_records(0, 10), 0);
+        let positions: Vec<u64> = read_data.iter().map(|raw| raw.position).collect();
+        cache.append(read_data, 1024 * 1024);
+
+        let res = cache.read_by_offset(3, 1024 * 1024, 4).unwrap();
+        assert_eq!(res.len(), 4);
+        assert_eq!(res.first().unwrap().record.offset, 3);
+        assert!(cache.read_by_offset(10, 1024 * 1024, 4).is_none());
+
+        let res = cache.read_by_positions(&positions[5..7]).unwrap();
+        assert_eq!(res.first().unwrap().record.offset, 5);
+        assert!(cache.read_by_positions(&[1]).is_none());
+
+        // the oldest records are dropped past the size limit
+        let size = record_size(&build_records(10, 1)[0]);
+        cache.append(build_read_data(build_records(10, 1), 1000), size * 2);
+        assert_eq!(cache.records.len(), 2);
+        assert!(cache.read_by_offset(5, 1024 * 1024, 4).is_none());
+        assert_eq!(cache.read_by_offset(9, 1024 * 1024, 4).unwrap().len(), 2);
+
+        // a gap in the offsets restarts the cache
+        cache.append(build_read_data(build_records(20, 1), 2000), 1024 * 1024);
+        assert_eq!(cache.records.len(), 1);
+    }
+
+    #[test]
+    fn hot_segment_cache_budget_test() {
+        let read_cache = SegmentReadCache::new();
+        let segment_a = test_build_segment();
+        let mut segment_b = test_build_segment();
+        segment_b.segment_seq += 1;
+
+        // a segment caches its writes once it is read
+        for segment_iden in [&segment_a, &segment_b] {
+            assert!(read_cache
+                .read_hot_by_offset(&segment_iden.name(), 0, 1024, 10)
+                .is_none());
+            read_cache.append_hot(segment_iden, build_read_data(build_records(0, 10), 0));
+        }
+        let segment_bytes: u64 = build_records(0, 10).iter().map(record_size).sum();
+        assert_eq!(
+            read_cache.hot_bytes.load(Ordering::Relaxed),
+            segment_bytes * 2
+        );
+
+        // past the budget the other segment is dropped, the one just written to is kept
+        read_cache.evict_hot(&segment_b.name(), segment_bytes);
+        assert!(!read_cache.hot.contains_key(&segment_a.name()));
+        assert!(read_cache.hot.contains_key(&segment_b.name()));
+        assert_eq!(read_cache.hot_bytes.load(Ordering::Relaxed), segment_bytes);
+
+        read_cache.remove(&segment_b);
+        assert_eq!(read_cache.hot_bytes.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn sealed_segment_read_test() {
+        let data_fold = test_build_data_fold();
+        let segment_iden = test_build_segment();
+        let segment_file = SegmentFile::new(
+            segment_iden.namespace.to_string(),
+            segment_iden.shard_name.to_string(),
+            segment_iden.segment_seq,
+            data_fold.first().unwrap().to_string(),
+        );
+        segment_file.try_create().await.unwrap();
+        let positions = segment_file.append(&build_records(0, 20)).await.unwrap();
+
+        let read_cache = Arc::new(SegmentReadCache::new());
+        let reader = SegmentReader::with_cache(segment_file, read_cache.clone(), true);
+
+        let res = reader.read_by_offset(0, 5, 1024 * 1024, 10).await.unwrap();
+        assert_eq!(res.len(), 10);
+        for (i, row) in res.iter().enumerate() {
+            assert_eq!(row.record.offset, 5 + i as i64);
+            assert_eq!(row.position, positions[5 + i]);
+        }
+        assert_eq!(read_cache.sealed.len(), 1);
+
+        let res = reader
+            .read_by_positions(vec![positions[2], positions[19]])
+            .await
+            .unwrap();
+        assert_eq!(res.len(), 2);
+        assert_eq!(res.last().unwrap().record.key, "key-19");
+
+        // the file is mapped again after it is replaced
+        reader
+            .segment_file
+            .rewrite(&build_records(100, 3))
+            .await
+            .unwrap();
+        let res = reader.read_by_offset(0, 0, 1024 * 1024, 10).await.unwrap();
+        assert_eq!(res.len(), 3);
+        assert_eq!(res.first().unwrap().record.offset, 100);
+    }
+}
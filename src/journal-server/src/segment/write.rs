@@ -14,28 +14,39 @@
 
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use common_base::tools::now_second;
 use grpc_clients::pool::ClientPool;
 use log::error;
 use metadata_struct::journal::segment::SegmentStatus;
+use protocol::journal_server::compression::compress;
 use protocol::journal_server::journal_engine::{
-    WriteReqBody, WriteRespMessage, WriteRespMessageStatus,
+    CompressionType, DurabilityLevel, JournalEngineError, WriteReqBody, WriteReqSegmentMessages,
+    WriteRespMessage, WriteRespMessageStatus,
 };
 use protocol::journal_server::journal_record::JournalRecord;
 use rocksdb_engine::RocksDBEngine;
 use tokio::select;
 use tokio::sync::mpsc::{self, Receiver, Sender};
 use tokio::sync::{broadcast, oneshot};
-use tokio::time::{sleep, timeout};
+use tokio::time::{interval, sleep, timeout};
 
 use crate::core::cache::CacheManager;
-use crate::core::error::JournalServerError;
+use crate::core::consts::{DURABILITY_CHECK_INTERVAL_MS, GROUP_COMMIT_MAX_BATCH_NUM};
+use crate::core::error::{get_journal_server_code, JournalServerError};
+use crate::core::metrics::{
+    metrics_record_compression, metrics_segment_flush_ms, metrics_segment_fsync,
+    metrics_write_request,
+};
+use crate::core::producer::{ProducerSequence, ProducerSequenceCheck, ProducerSequenceManager};
 use crate::core::segment_meta::{update_meta_end_timestamp, update_meta_start_timestamp};
 use crate::core::segment_status::sealup_segment;
 use crate::index::build::try_trigger_build_index;
-use crate::segment::file::{open_segment_write, SegmentFile};
+use crate::segment::durability::{DurabilityPolicy, SyncState};
+use crate::segment::file::{
+    is_first_record_position, open_segment_write, record_size, ReadData, SegmentFile,
+};
 use crate::segment::manager::SegmentFileManager;
 use crate::segment::SegmentIdentity;
 
@@ -55,7 +66,13 @@ pub struct SegmentWriteResp {
     offsets: HashMap<u64, u64>,
     positions: HashMap<u64, u64>,
     last_offset: u64,
+    // Position right after the last written record, i.e. the size of the segment file
+    end_position: u64,
+    bytes: u64,
+    durability: DurabilityLevel,
     error: Option<JournalServerError>,
+    // End offset of the write thread when an empty packet is answered, see drain_write_thread
+    segment_end_offset: i64,
 }
 
 pub async fn write_data_req(
@@ -65,6 +82,10 @@ pub async fn write_data_req(
     client_pool: &Arc<ClientPool>,
     req_body: &WriteReqBody,
 ) -> Result<Vec<WriteRespMessage>, JournalServerError> {
+    let producer_sequence_manager =
+        ProducerSequenceManager::new(cache_manager.clone(), rocksdb_engine_handler.clone());
+    let producer_id = req_body.producer_id.clone();
+
     let mut results = Vec::new();
     for shard_data in req_body.data.clone() {
         let mut resp_message = WriteRespMessage {
@@ -80,11 +101,99 @@ pub async fn write_data_req(
             shard_data.segment,
         );
 
+        let Some(segment) = cache_manager.get_segment(&segment_iden) else {
+            return Err(JournalServerError::SegmentNotExist(segment_iden.name()));
+        };
+
+        // Held until the sequence is saved, the next request of the producer is checked against it
+        let (_producer_guard, producer_sequence) = if producer_id.is_empty() {
+            (None, None)
+        } else {
+            let guard = producer_sequence_manager
+                .lock(&shard_data.namespace, &shard_data.shard_name, &producer_id)
+                .await;
+            let sequence = producer_sequence_manager
+                .get(client_pool, &segment, &producer_id)
+                .await?;
+            (Some(guard), sequence)
+        };
+
+        metrics_write_request(
+            &shard_data.namespace,
+            &shard_data.shard_name,
+            shard_data.messages.len(),
+        );
+
+        // Duplicates were made durable by the write that first accepted them
+        resp_message.durability = DurabilityPolicy::from_shard(
+            cache_manager,
+            &shard_data.namespace,
+            &shard_data.shard_name,
+        )
+        .level
+        .into();
+
+        let compression = write_compression(cache_manager, &shard_data);
+        let mut raw_bytes = 0;
+        let mut stored_bytes = 0;
+
+        let mut resp_message_status = Vec::new();
         let mut data_list = Vec::new();
         for message in shard_data.messages.iter() {
             // todo data validator
+
+            // A retried message returns the offset it was written to the first time
+            if let Some(sequence) = &producer_sequence {
+                match sequence.check(message.pkid) {
+                    ProducerSequenceCheck::Accept => {}
+                    ProducerSequenceCheck::Duplicate(offset) => {
+                        resp_message_status.push(WriteRespMessageStatus {
+                            pkid: message.pkid,
+                            offset,
+                            ..Default::default()
+                        });
+                        continue;
+                    }
+                    ProducerSequenceCheck::OutOfOrder => {
+                        let e = JournalServerError::ProducerSequenceOutOfOrder(
+                            producer_id.clone(),
+                            message.pkid,
+                            sequence.last_pkid,
+                        );
+                        resp_message_status.push(WriteRespMessageStatus {
+                            pkid: message.pkid,
+                            error: Some(JournalEngineError {
+                                code: get_journal_server_code(&e),
+                                error: e.to_string(),
+                            }),
+                            ..Default::default()
+                        });
+                        continue;
+                    }
+                }
+            }
+
+            // Tombstones stay empty so that compaction can still recognize them
+            let (content, record_compression, raw_len) =
+                if compression == CompressionType::Uncompressed || message.value.is_empty() {
+                    (
+                        message.value.clone(),
+                        CompressionType::Uncompressed,
+                        message.value.len() as u64,
+                    )
+                } else if shard_data.compression() != CompressionType::Uncompressed {
+                    // The producer compressed the value in its write batch
+                    (message.value.clone(), compression, message.raw_len as u64)
+                } else {
+                    let (content, record_compression) =
+                        compress_record(compression, &message.value)?;
+                    (content, record_compression, message.value.len() as u64)
+                };
+            raw_bytes += raw_len;
+            stored_bytes += content.len() as u64;
+
             let record = JournalRecord {
-                content: message.value.clone(),
+                content,
                 create_time: now_second(),
                 key: message.key.clone(),
                 namespace: shard_data.namespace.clone(),
@@ -92,12 +201,27 @@ pub async fn write_data_req(
                 segment: shard_data.segment,
                 tags: message.tags.clone(),
                 pkid: message.pkid,
-                producer_id: "".to_string(),
+                producer_id: producer_id.clone(),
                 offset: -1,
+                compression: record_compression.into(),
             };
             data_list.push(record);
         }
 
+        metrics_record_compression(
+            &shard_data.namespace,
+            &shard_data.shard_name,
+            compression.as_str_name(),
+            raw_bytes,
+            stored_bytes,
+        );
+
+        if data_list.is_empty() {
+            resp_message.messages = resp_message_status;
+            results.push(resp_message);
+            continue;
+        }
+
         let resp = write_data(
             cache_manager,
             rocksdb_engine_handler,
@@ -112,15 +236,16 @@ pub async fn write_data_req(
             return Err(e);
         }
 
-        // if position = 0, update start/timestamp
-        for (_, position) in resp.positions.iter() {
-            if *position == 0 {
+        // if this is the first record of the segment, update start/timestamp
+        for (pkid, position) in resp.positions.iter() {
+            if is_first_record_position(*position) {
                 let first_record = data_list.first().unwrap();
+                let start_offset = resp.offsets.get(pkid).cloned().unwrap_or_default();
                 segment_position0_ac(
                     segment_file_manager,
                     client_pool,
                     &segment_iden,
-                    *position as i64,
+                    start_offset as i64,
                     first_record.create_time,
                 )
                 .await?;
@@ -134,10 +259,25 @@ pub async fn write_data_req(
             &segment_iden,
             resp.last_offset as i64,
             last_record.create_time,
+            resp.end_position,
         )
         .await?;
 
-        let mut resp_message_status = Vec::new();
+        // The records are written, a failure here must not make the producer retry them
+        if !producer_id.is_empty() {
+            let mut sequence = producer_sequence.unwrap_or_else(|| ProducerSequence {
+                producer_id: producer_id.clone(),
+                ..Default::default()
+            });
+            sequence.record(&resp.offsets);
+            if let Err(e) = producer_sequence_manager.save(&segment, sequence) {
+                error!(
+                    "Failed to save the sequence of producer {}, error message: {}",
+                    producer_id, e
+                );
+            }
+        }
+
         for (pkid, offset) in resp.offsets {
             let status = WriteRespMessageStatus {
                 pkid,
@@ -147,11 +287,41 @@ pub async fn write_data_req(
             resp_message_status.push(status);
         }
         resp_message.messages = resp_message_status;
+        resp_message.durability = resp.durability.into();
         results.push(resp_message);
     }
     Ok(results)
 }
 
+/// Compress the content of one record, a record that does not get smaller is stored uncompressed so
+/// that small values do not pay for the codec header.
+fn compress_record(
+    compression: CompressionType,
+    value: &[u8],
+) -> Result<(Vec<u8>, CompressionType), JournalServerError> {
+    let content = compress(compression, value)?;
+    if content.len() >= value.len() {
+        return Ok((value.to_vec(), CompressionType::Uncompressed));
+    }
+    Ok((content, compression))
+}
+
+/// Values the producer compressed are stored as they are, otherwise the server compresses each
+/// record with the compression configured on the shard.
+fn write_compression(
+    cache_manager: &Arc<CacheManager>,
+    shard_data: &WriteReqSegmentMessages,
+) -> CompressionType {
+    if shard_data.compression() != CompressionType::Uncompressed {
+        return shard_data.compression();
+    }
+
+    if let Some(shard) = cache_manager.get_shard(&shard_data.namespace, &shard_data.shard_name) {
+        return shard.config.compression.into();
+    }
+    CompressionType::Uncompressed
+}
+
 pub(crate) async fn write_data(
     cache_manager: &Arc<CacheManager>,
     rocksdb_engine_handler: &Arc<RocksDBEngine>,
@@ -181,6 +351,30 @@ pub(crate) async fn write_data(
     Ok(time_res?)
 }
 
+/// Wait until every batch queued on the write thread of the segment has been written, returns the
+/// end offset of the segment file at that point (-1 when it is empty), or None when the segment
+/// has no write thread on this node.
+pub(crate) async fn drain_write_thread(
+    cache_manager: &Arc<CacheManager>,
+    segment_iden: &SegmentIdentity,
+) -> Result<Option<i64>, JournalServerError> {
+    let Some(write) = cache_manager.get_segment_write_thread(segment_iden) else {
+        return Ok(None);
+    };
+
+    // The write thread answers an empty packet after the packets queued before it
+    let (sx, rx) = oneshot::channel::<SegmentWriteResp>();
+    let data = SegmentWriteData {
+        data: Vec::new(),
+        resp_sx: sx,
+    };
+    write.data_sender.send(data).await?;
+
+    let time_res: Result<SegmentWriteResp, oneshot::error::RecvError> =
+        timeout(Duration::from_secs(30), rx).await?;
+    Ok(Some(time_res?.segment_end_offset))
+}
+
 async fn get_write(
     cache_manager: &Arc<CacheManager>,
     rocksdb_engine_handler: &Arc<RocksDBEngine>,
@@ -261,15 +455,43 @@ async fn create_write_thread0(
     mut stop_recv: broadcast::Receiver<bool>,
 ) {
     tokio::spawn(async move {
+        let mut sync_state = SyncState::new();
+        let mut sync_interval = interval(Duration::from_millis(DURABILITY_CHECK_INTERVAL_MS));
         loop {
             select! {
                 val = stop_recv.recv() =>{
                     if let Ok(flag) = val {
                         if flag {
+                            let policy = DurabilityPolicy::from_shard(
+                                &cache_manager,
+                                &segment_iden.namespace,
+                                &segment_iden.shard_name,
+                            );
+                            if policy.level != DurabilityLevel::OsBuffered
+                                && sync_state.has_unsynced()
+                            {
+                                let res = sync_segment(&segment_write, &mut sync_state, 0).await;
+                                if let Err(e) = res {
+                                    error!("{}", e);
+                                }
+                            }
                             break;
                         }
                     }
                 },
+                _ = sync_interval.tick() => {
+                    // Data written under FsyncInterval must not wait for the next write
+                    let policy = DurabilityPolicy::from_shard(
+                        &cache_manager,
+                        &segment_iden.namespace,
+                        &segment_iden.shard_name,
+                    );
+                    if sync_state.need_sync(&policy, Instant::now()) {
+                        if let Err(e) = sync_segment(&segment_write, &mut sync_state, 0).await {
+                            error!("{}", e);
+                        }
+                    }
+                },
                 val = data_recv.recv()=>{
 
                     if val.is_none(){
@@ -277,38 +499,78 @@ async fn create_write_thread0(
                         continue;
                     }
 
-                    let packet = val.unwrap();
+                    // Group commit: the batches queued while the previous fsync was running
+                    // are written together and share a single fsync.
+                    let mut packets = vec![val.unwrap()];
+                    while packets.len() < GROUP_COMMIT_MAX_BATCH_NUM {
+                        match data_recv.try_recv() {
+                            Ok(packet) => packets.push(packet),
+                            Err(_) => break,
+                        }
+                    }
 
-                    let resp = match batch_write(
-                        &rocksdb_engine_handler,
-                        &segment_iden,
-                        &segment_file_manager,
+                    let policy = DurabilityPolicy::from_shard(
                         &cache_manager,
-                        &client_pool,
-                        local_segment_end_offset,
-                        &segment_write,
-                        max_file_size,
-                        packet.data
-                    ).await{
-                        Ok(Some(resp)) => {
-                            local_segment_end_offset = resp.last_offset as i64;
-                            resp
-                        },
-                        Ok(None) =>{
-                            sleep(Duration::from_millis(100)).await;
-                            continue;
-                        },
-                        Err(e) => {
-                            SegmentWriteResp {
-                                error: Some(e),
-                                ..Default::default()
+                        &segment_iden.namespace,
+                        &segment_iden.shard_name,
+                    );
+
+                    let mut resps = Vec::new();
+                    for packet in packets {
+                        let resp = match batch_write(
+                            &rocksdb_engine_handler,
+                            &segment_iden,
+                            &segment_file_manager,
+                            &cache_manager,
+                            &client_pool,
+                            local_segment_end_offset,
+                            &segment_write,
+                            max_file_size,
+                            packet.data
+                        ).await{
+                            Ok(Some(resp)) => {
+                                if resp.error.is_none() {
+                                    local_segment_end_offset = resp.last_offset as i64;
+                                    sync_state.record_write(resp.bytes);
+                                }
+                                resp
+                            },
+                            Ok(None) =>{
+                                SegmentWriteResp {
+                                    segment_end_offset: local_segment_end_offset,
+                                    ..Default::default()
+                                }
+                            },
+                            Err(e) => {
+                                SegmentWriteResp {
+                                    error: Some(e),
+                                    ..Default::default()
+                                }
                             }
-                        }
-                    };
+                        };
+                        resps.push((packet.resp_sx, resp));
+                    }
 
+                    if sync_state.need_sync(&policy, Instant::now()) {
+                        let res = sync_segment(&segment_write, &mut sync_state, resps.len()).await;
+                        if let Err(e) = res {
+                            // The batches were written but cannot be acknowledged as durable
+                            for (_, resp) in resps.iter_mut() {
+                                if resp.error.is_none() {
+                                    resp.error = Some(JournalServerError::SegmentSyncFailed(
+                                        segment_iden.name(),
+                                        e.to_string(),
+                                    ));
+                                }
+                            }
+                        }
+                    }
 
-                    if packet.resp_sx.send(resp).is_err(){
-                        error!("Write data to the Segment file, write success, call the oneshot channel to return the write information failed. Failure message");
+                    for (resp_sx, mut resp) in resps {
+                        resp.durability = policy.level;
+                        if resp_sx.send(resp).is_err(){
+                            error!("Write data to the Segment file, write success, call the oneshot channel to return the write information failed. Failure message");
+                        }
                     }
                 }
             }
@@ -316,6 +578,23 @@ async fn create_write_thread0(
     });
 }
 
+async fn sync_segment(
+    segment_write: &SegmentFile,
+    sync_state: &mut SyncState,
+    batch_num: usize,
+) -> Result<(), JournalServerError> {
+    let start = Instant::now();
+    segment_write.sync().await?;
+    sync_state.synced(Instant::now());
+    metrics_segment_fsync(
+        &segment_write.namespace,
+        &segment_write.shard_name,
+        start.elapsed().as_secs_f64() * 1000.0,
+        batch_num,
+    );
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn batch_write(
     rocksdb_engine_handler: &Arc<RocksDBEngine>,
@@ -351,7 +630,14 @@ async fn batch_write(
         }));
     }
 
-    let resp = batch_write0(data, segment_write, local_segment_end_offset as u64).await?;
+    let resp = batch_write0(
+        cache_manager,
+        segment_iden,
+        data,
+        segment_write,
+        local_segment_end_offset as u64,
+    )
+    .await?;
 
     try_trigger_build_index(
         cache_manager,
@@ -365,6 +651,8 @@ async fn batch_write(
 }
 
 async fn batch_write0(
+    cache_manager: &Arc<CacheManager>,
+    segment_iden: &SegmentIdentity,
     data: Vec<JournalRecord>,
     segment_write: &SegmentFile,
     mut local_segment_end_offset: u64,
@@ -386,13 +674,40 @@ async fn batch_write0(
     }
 
     // batch write data
-    match segment_write.write(&records).await {
+    let start = Instant::now();
+    let res = segment_write.append(&records).await;
+    metrics_segment_flush_ms(
+        &segment_write.namespace,
+        &segment_write.shard_name,
+        start.elapsed().as_secs_f64() * 1000.0,
+    );
+    match res {
         Ok(positions) => {
-            let record = records.last().unwrap();
+            let last_offset = records.last().unwrap().offset as u64;
+            let bytes = records.iter().map(record_size).sum();
+            let end_position = positions.last().cloned().unwrap_or_default()
+                + record_size(records.last().unwrap());
+            let position_map = records
+                .iter()
+                .zip(positions.iter())
+                .map(|(record, position)| (record.pkid, *position))
+                .collect();
+
+            cache_manager.get_segment_read_cache().append_hot(
+                segment_iden,
+                records
+                    .into_iter()
+                    .zip(positions)
+                    .map(|(record, position)| ReadData { position, record })
+                    .collect(),
+            );
+
             Ok(Some(SegmentWriteResp {
                 offsets: offsets.clone(),
-                positions,
-                last_offset: record.offset as u64,
+                positions: position_map,
+                last_offset,
+                end_position,
+                bytes,
                 ..Default::default()
             }))
         }
@@ -407,11 +722,11 @@ async fn segment_position0_ac(
     segment_file_manager: &Arc<SegmentFileManager>,
     client_pool: &Arc<ClientPool>,
     segment_iden: &SegmentIdentity,
-    first_posi: i64,
+    start_offset: i64,
     start_timestamp: u64,
 ) -> Result<(), JournalServerError> {
     // update local file start offset
-    segment_file_manager.update_start_offset(segment_iden, first_posi)?;
+    segment_file_manager.update_start_offset(segment_iden, start_offset)?;
 
     // update local file start timestamp
     segment_file_manager.update_start_timestamp(segment_iden, start_timestamp)?;
@@ -427,6 +742,7 @@ async fn segment_position9_ac(
     segment_iden: &SegmentIdentity,
     end_offset: i64,
     end_timestamp: u64,
+    end_position: u64,
 ) -> Result<(), JournalServerError> {
     // update local file end offset
     segment_file_manager.update_end_offset(segment_iden, end_offset)?;
@@ -434,8 +750,14 @@ async fn segment_position9_ac(
     // update local file end timestamp
     segment_file_manager.update_end_timestamp(segment_iden, end_timestamp)?;
 
-    // update meta end timestamp
-    update_meta_end_timestamp(client_pool.clone(), segment_iden, end_timestamp).await?;
+    // update meta end timestamp and position
+    update_meta_end_timestamp(
+        client_pool.clone(),
+        segment_iden,
+        end_timestamp,
+        end_position,
+    )
+    .await?;
     Ok(())
 }
 
@@ -503,12 +825,26 @@ fn is_sealup_segment0(
 mod tests {
     use common_base::tools::unique_id;
     use prost::Message;
+    use protocol::journal_server::compression::decompress;
+    use protocol::journal_server::journal_engine::{CompressionType, DurabilityLevel};
     use protocol::journal_server::journal_record::JournalRecord;
 
-    use super::{create_write_thread, is_sealup_segment0, write_data};
+    use super::{compress_record, create_write_thread, is_sealup_segment0, write_data};
     use crate::core::test::{test_init_client_pool, test_init_segment};
     use crate::segment::file::open_segment_write;
 
+    #[test]
+    fn compress_record_test() {
+        let value = "{\"temperature\":21.5}".repeat(10).into_bytes();
+        let (content, compression) = compress_record(CompressionType::Lz4, &value).unwrap();
+        assert_eq!(compression, CompressionType::Lz4);
+        assert_eq!(decompress(compression, &content).unwrap(), value);
+
+        let (content, compression) = compress_record(CompressionType::Lz4, b"{}").unwrap();
+        assert_eq!(compression, CompressionType::Uncompressed);
+        assert_eq!(content, b"{}".to_vec());
+    }
+
     #[tokio::test]
     async fn is_sealup_segment_test() {
         let mut end_offset = -1;
@@ -592,6 +928,8 @@ mod tests {
         assert!(resp.error.is_none());
         assert_eq!(resp.offsets.len(), 10);
         assert_eq!(resp.last_offset, 9);
+        assert!(resp.bytes > 0);
+        assert_eq!(resp.durability, DurabilityLevel::OsBuffered);
 
         let mut data_list = Vec::new();
         for i in 10..20 {
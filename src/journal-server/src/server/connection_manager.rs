@@ -23,6 +23,7 @@ use tokio::time::sleep;
 use tokio_util::codec::FramedWrite;
 
 use super::connection::{NetworkConnection, NetworkConnectionType};
+use crate::core::metrics::{metrics_connection_dec, metrics_connection_inc};
 
 pub struct ConnectionManager {
     connections: DashMap<u64, NetworkConnection>,
@@ -54,6 +55,7 @@ impl ConnectionManager {
 
     pub fn add_connection(&self, connection: NetworkConnection) -> u64 {
         let connection_id = connection.connection_id();
+        metrics_connection_inc(&connection.connection_type.to_string());
         self.connections.insert(connection_id, connection);
         connection_id
     }
@@ -85,6 +87,7 @@ impl ConnectionManager {
 
     pub async fn close_connect(&self, connection_id: u64) {
         if let Some((_, connection)) = self.connections.remove(&connection_id) {
+            metrics_connection_dec(&connection.connection_type.to_string());
             connection.stop_connection().await;
         }
 
@@ -14,22 +14,55 @@
 
 use std::sync::Arc;
 
+use common_base::config::journal_server::journal_server_conf;
+use grpc_clients::placement::inner::call::get_offset_data;
+use grpc_clients::placement::journal::call::{
+    create_namespace, create_shard, delete_namespace, delete_shard, update_segment_replica,
+};
+use grpc_clients::pool::ClientPool;
 use protocol::journal_server::journal_admin::journal_server_admin_service_server::JournalServerAdminService;
 use protocol::journal_server::journal_admin::{
-    ListSegmentReply, ListSegmentRequest, ListShardReply, ListShardRequest,
+    CreateNamespaceReply, CreateNamespaceRequest, CreateShardReply, CreateShardRequest,
+    DeleteNamespaceReply, DeleteNamespaceRequest, DeleteShardReply, DeleteShardRequest,
+    DescribeSegmentReply, DescribeSegmentRequest, GroupShardOffset, ListGroupOffsetReply,
+    ListGroupOffsetRequest, ListNamespaceReply, ListNamespaceRequest, ListSegmentMetaReply,
+    ListSegmentMetaRequest, ListSegmentReply, ListSegmentRequest, ListShardReply, ListShardRequest,
+    ReassignSegmentReplicaReply, ReassignSegmentReplicaRequest, RebuildSegmentIndexReply,
+    RebuildSegmentIndexRequest, SealUpSegmentReply, SealUpSegmentRequest, SegmentReplicaPosition,
 };
+use protocol::placement_center::placement_center_inner::GetOffsetDataRequest;
+use protocol::placement_center::placement_center_journal;
+use protocol::placement_center::placement_center_journal::UpdateSegmentReplicaRequest;
+use rocksdb_engine::RocksDBEngine;
 use tonic::{Request, Response, Status};
 
 use crate::core::cache::CacheManager;
+use crate::core::segment_status::force_sealup_segment;
+use crate::index::build::{get_segment_index_build_status, trigger_rebuild_segment_index};
+use crate::segment::file::open_segment_write;
+use crate::segment::manager::SegmentFileManager;
 use crate::segment::SegmentIdentity;
 
 pub struct GrpcJournalServerAdminService {
     cache_manager: Arc<CacheManager>,
+    segment_file_manager: Arc<SegmentFileManager>,
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+    client_pool: Arc<ClientPool>,
 }
 
 impl GrpcJournalServerAdminService {
-    pub fn new(cache_manager: Arc<CacheManager>) -> Self {
-        GrpcJournalServerAdminService { cache_manager }
+    pub fn new(
+        cache_manager: Arc<CacheManager>,
+        segment_file_manager: Arc<SegmentFileManager>,
+        rocksdb_engine_handler: Arc<RocksDBEngine>,
+        client_pool: Arc<ClientPool>,
+    ) -> Self {
+        GrpcJournalServerAdminService {
+            cache_manager,
+            segment_file_manager,
+            rocksdb_engine_handler,
+            client_pool,
+        }
     }
 }
 
@@ -42,8 +75,11 @@ impl JournalServerAdminService for GrpcJournalServerAdminService {
         let req = request.into_inner();
         let mut shards = Vec::new();
         if req.shard_name.is_empty() {
-            // Get all shard
+            // Get all shard, or all shard of the namespace
             for shard in self.cache_manager.get_shards() {
+                if !req.namespace.is_empty() && shard.namespace != req.namespace {
+                    continue;
+                }
                 match serde_json::to_string(&shard) {
                     Ok(data) => {
                         shards.push(data);
@@ -114,4 +150,336 @@ impl JournalServerAdminService for GrpcJournalServerAdminService {
         }
         return Ok(Response::new(ListSegmentReply { segments }));
     }
+    async fn describe_segment(
+        &self,
+        request: Request<DescribeSegmentRequest>,
+    ) -> Result<Response<DescribeSegmentReply>, Status> {
+        let req = request.into_inner();
+        let segment_iden = SegmentIdentity::new(&req.namespace, &req.shard_name, req.segment_no);
+
+        let segment = if let Some(segment) = self.cache_manager.get_segment(&segment_iden) {
+            segment
+        } else {
+            return Err(Status::cancelled(format!(
+                "Segment {} does not exist",
+                segment_iden.name()
+            )));
+        };
+
+        let segment_meta = if let Some(meta) = self.cache_manager.get_segment_meta(&segment_iden) {
+            match serde_json::to_string(&meta) {
+                Ok(data) => data,
+                Err(e) => {
+                    return Err(Status::cancelled(e.to_string()));
+                }
+            }
+        } else {
+            "".to_string()
+        };
+
+        // The segment file only exists on the replicas of the segment
+        let (file_exists, file_size) =
+            match open_segment_write(&self.cache_manager, &segment_iden).await {
+                Ok((segment_file, _)) => {
+                    if segment_file.exists() {
+                        (true, segment_file.size().await.unwrap_or_default())
+                    } else {
+                        (false, 0)
+                    }
+                }
+                Err(_) => (false, 0),
+            };
+
+        let mut reply = DescribeSegmentReply {
+            segment_meta,
+            file_exists,
+            file_size,
+            local_start_offset: -1,
+            local_end_offset: -1,
+            local_start_timestamp: -1,
+            local_end_timestamp: -1,
+            index_last_offset: -1,
+            ..Default::default()
+        };
+
+        if let Some(file_meta) = self.segment_file_manager.get_segment_file(&segment_iden) {
+            reply.local_start_offset = file_meta.start_offset;
+            reply.local_end_offset = file_meta.end_offset;
+            reply.local_start_timestamp = file_meta.start_timestamp;
+            reply.local_end_timestamp = file_meta.end_timestamp;
+        }
+
+        match get_segment_index_build_status(
+            &self.cache_manager,
+            &self.rocksdb_engine_handler,
+            &segment_iden,
+        ) {
+            Ok(status) => {
+                reply.index_building = status.building;
+                reply.index_finished = status.finished;
+                if let Some(offset) = status.last_offset {
+                    reply.index_last_offset = offset as i64;
+                }
+            }
+            Err(e) => {
+                return Err(Status::cancelled(e.to_string()));
+            }
+        }
+
+        for replica in segment.replicas.iter() {
+            reply.replicas.push(SegmentReplicaPosition {
+                node_id: replica.node_id,
+                replica_seq: replica.replica_seq,
+                fold: replica.fold.clone(),
+                is_leader: replica.node_id == segment.leader,
+                in_isr: segment.isr.contains(&replica.node_id),
+            });
+        }
+
+        match serde_json::to_string(&segment) {
+            Ok(data) => reply.segment = data,
+            Err(e) => {
+                return Err(Status::cancelled(e.to_string()));
+            }
+        }
+
+        return Ok(Response::new(reply));
+    }
+
+    async fn rebuild_segment_index(
+        &self,
+        request: Request<RebuildSegmentIndexRequest>,
+    ) -> Result<Response<RebuildSegmentIndexReply>, Status> {
+        let req = request.into_inner();
+        let segment_iden = SegmentIdentity::new(&req.namespace, &req.shard_name, req.segment_no);
+
+        match trigger_rebuild_segment_index(
+            &self.cache_manager,
+            &self.segment_file_manager,
+            &self.rocksdb_engine_handler,
+            &segment_iden,
+        )
+        .await
+        {
+            Ok(()) => return Ok(Response::new(RebuildSegmentIndexReply::default())),
+            Err(e) => {
+                return Err(Status::cancelled(e.to_string()));
+            }
+        }
+    }
+
+    async fn seal_up_segment(
+        &self,
+        request: Request<SealUpSegmentRequest>,
+    ) -> Result<Response<SealUpSegmentReply>, Status> {
+        let req = request.into_inner();
+        let segment_iden = SegmentIdentity::new(&req.namespace, &req.shard_name, req.segment_no);
+
+        match force_sealup_segment(
+            &self.cache_manager,
+            &self.client_pool,
+            &self.segment_file_manager,
+            &segment_iden,
+        )
+        .await
+        {
+            Ok(end_offset) => return Ok(Response::new(SealUpSegmentReply { end_offset })),
+            Err(e) => {
+                return Err(Status::cancelled(e.to_string()));
+            }
+        }
+    }
+
+    async fn reassign_segment_replica(
+        &self,
+        request: Request<ReassignSegmentReplicaRequest>,
+    ) -> Result<Response<ReassignSegmentReplicaReply>, Status> {
+        let req = request.into_inner();
+        let conf = journal_server_conf();
+        let request = UpdateSegmentReplicaRequest {
+            cluster_name: conf.cluster_name.clone(),
+            namespace: req.namespace,
+            shard_name: req.shard_name,
+            segment_seq: req.segment_no,
+            node_ids: req.node_ids,
+        };
+
+        match update_segment_replica(&self.client_pool, &conf.placement_center, request).await {
+            Ok(reply) => match String::from_utf8(reply.segment) {
+                Ok(segment) => return Ok(Response::new(ReassignSegmentReplicaReply { segment })),
+                Err(e) => {
+                    return Err(Status::cancelled(e.to_string()));
+                }
+            },
+            Err(e) => {
+                return Err(Status::cancelled(e.to_string()));
+            }
+        }
+    }
+
+    async fn list_namespace(
+        &self,
+        request: Request<ListNamespaceRequest>,
+    ) -> Result<Response<ListNamespaceReply>, Status> {
+        let req = request.into_inner();
+        let mut namespaces = Vec::new();
+        for namespace in self.cache_manager.get_namespaces() {
+            if !req.namespace.is_empty() && namespace.namespace_name != req.namespace {
+                continue;
+            }
+            match serde_json::to_string(&namespace) {
+                Ok(data) => {
+                    namespaces.push(data);
+                }
+                Err(e) => {
+                    return Err(Status::cancelled(e.to_string()));
+                }
+            }
+        }
+        return Ok(Response::new(ListNamespaceReply { namespaces }));
+    }
+
+    async fn create_namespace(
+        &self,
+        request: Request<CreateNamespaceRequest>,
+    ) -> Result<Response<CreateNamespaceReply>, Status> {
+        let req = request.into_inner();
+        let conf = journal_server_conf();
+        let request = placement_center_journal::CreateNamespaceRequest {
+            cluster_name: conf.cluster_name.clone(),
+            namespace: req.namespace,
+            max_shard_num: req.max_shard_num,
+            max_storage_bytes: req.max_storage_bytes,
+            max_write_bytes_per_sec: req.max_write_bytes_per_sec,
+        };
+
+        match create_namespace(&self.client_pool, &conf.placement_center, request).await {
+            Ok(_) => return Ok(Response::new(CreateNamespaceReply::default())),
+            Err(e) => {
+                return Err(Status::cancelled(e.to_string()));
+            }
+        }
+    }
+
+    async fn delete_namespace(
+        &self,
+        request: Request<DeleteNamespaceRequest>,
+    ) -> Result<Response<DeleteNamespaceReply>, Status> {
+        let req = request.into_inner();
+        let conf = journal_server_conf();
+        let request = placement_center_journal::DeleteNamespaceRequest {
+            cluster_name: conf.cluster_name.clone(),
+            namespace: req.namespace,
+        };
+
+        match delete_namespace(&self.client_pool, &conf.placement_center, request).await {
+            Ok(_) => return Ok(Response::new(DeleteNamespaceReply::default())),
+            Err(e) => {
+                return Err(Status::cancelled(e.to_string()));
+            }
+        }
+    }
+
+    async fn create_shard(
+        &self,
+        request: Request<CreateShardRequest>,
+    ) -> Result<Response<CreateShardReply>, Status> {
+        let req = request.into_inner();
+        let conf = journal_server_conf();
+        let request = placement_center_journal::CreateShardRequest {
+            cluster_name: conf.cluster_name.clone(),
+            namespace: req.namespace,
+            shard_name: req.shard_name,
+            replica: req.replica,
+            shard_config: req.shard_config,
+        };
+
+        match create_shard(&self.client_pool, &conf.placement_center, request).await {
+            Ok(_) => return Ok(Response::new(CreateShardReply::default())),
+            Err(e) => {
+                return Err(Status::cancelled(e.to_string()));
+            }
+        }
+    }
+
+    async fn delete_shard(
+        &self,
+        request: Request<DeleteShardRequest>,
+    ) -> Result<Response<DeleteShardReply>, Status> {
+        let req = request.into_inner();
+        let conf = journal_server_conf();
+        let request = placement_center_journal::DeleteShardRequest {
+            cluster_name: conf.cluster_name.clone(),
+            namespace: req.namespace,
+            shard_name: req.shard_name,
+        };
+
+        match delete_shard(&self.client_pool, &conf.placement_center, request).await {
+            Ok(_) => return Ok(Response::new(DeleteShardReply::default())),
+            Err(e) => {
+                return Err(Status::cancelled(e.to_string()));
+            }
+        }
+    }
+
+    async fn list_segment_meta(
+        &self,
+        request: Request<ListSegmentMetaRequest>,
+    ) -> Result<Response<ListSegmentMetaReply>, Status> {
+        let req = request.into_inner();
+
+        let mut segment_metas = Vec::new();
+        for segment in self
+            .cache_manager
+            .get_segments_list_by_shard(&req.namespace, &req.shard_name)
+        {
+            if req.segment_no != -1 && segment.segment_seq != req.segment_no as u32 {
+                continue;
+            }
+
+            let segment_iden =
+                SegmentIdentity::new(&req.namespace, &req.shard_name, segment.segment_seq);
+            if let Some(meta) = self.cache_manager.get_segment_meta(&segment_iden) {
+                match serde_json::to_string(&meta) {
+                    Ok(data) => {
+                        segment_metas.push(data);
+                    }
+                    Err(e) => {
+                        return Err(Status::cancelled(e.to_string()));
+                    }
+                }
+            }
+        }
+        return Ok(Response::new(ListSegmentMetaReply { segment_metas }));
+    }
+
+    async fn list_group_offset(
+        &self,
+        request: Request<ListGroupOffsetRequest>,
+    ) -> Result<Response<ListGroupOffsetReply>, Status> {
+        let req = request.into_inner();
+        let conf = journal_server_conf();
+        let request = GetOffsetDataRequest {
+            cluster_name: conf.cluster_name.clone(),
+            group: req.group_name,
+        };
+
+        match get_offset_data(&self.client_pool, &conf.placement_center, request).await {
+            Ok(reply) => {
+                let offsets = reply
+                    .offsets
+                    .into_iter()
+                    .map(|raw| GroupShardOffset {
+                        namespace: raw.namespace,
+                        shard_name: raw.shard_name,
+                        offset: raw.offset,
+                    })
+                    .collect();
+                return Ok(Response::new(ListGroupOffsetReply { offsets }));
+            }
+            Err(e) => {
+                return Err(Status::cancelled(e.to_string()));
+            }
+        }
+    }
 }
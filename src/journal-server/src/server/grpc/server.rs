@@ -15,6 +15,7 @@
 use std::sync::Arc;
 
 use common_base::error::common::CommonError;
+use grpc_clients::pool::ClientPool;
 use log::info;
 use protocol::journal_server::journal_admin::journal_server_admin_service_server::JournalServerAdminServiceServer;
 use protocol::journal_server::journal_inner::journal_server_inner_service_server::JournalServerInnerServiceServer;
@@ -31,6 +32,7 @@ pub struct GrpcServer {
     cache_manager: Arc<CacheManager>,
     segment_file_manager: Arc<SegmentFileManager>,
     rocksdb_engine_handler: Arc<RocksDBEngine>,
+    client_pool: Arc<ClientPool>,
 }
 
 impl GrpcServer {
@@ -39,12 +41,14 @@ impl GrpcServer {
         cache_manager: Arc<CacheManager>,
         segment_file_manager: Arc<SegmentFileManager>,
         rocksdb_engine_handler: Arc<RocksDBEngine>,
+        client_pool: Arc<ClientPool>,
     ) -> Self {
         Self {
             port,
             cache_manager,
             segment_file_manager,
             rocksdb_engine_handler,
+            client_pool,
         }
     }
     pub async fn start(&self) -> Result<(), CommonError> {
@@ -53,7 +57,12 @@ impl GrpcServer {
             "Journal Engine Grpc Server start success. port:{}",
             self.port
         );
-        let admin_handler = GrpcJournalServerAdminService::new(self.cache_manager.clone());
+        let admin_handler = GrpcJournalServerAdminService::new(
+            self.cache_manager.clone(),
+            self.segment_file_manager.clone(),
+            self.rocksdb_engine_handler.clone(),
+            self.client_pool.clone(),
+        );
         let inner_handler = GrpcJournalServerInnerService::new(
             self.cache_manager.clone(),
             self.segment_file_manager.clone(),
@@ -0,0 +1,358 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use dashmap::DashMap;
+use grpc_clients::pool::ClientPool;
+use log::warn;
+use metadata_struct::mqtt::auto_subscribe_rule::MqttAutoSubscribeRule;
+use protocol::mqtt::common::{
+    qos, retain_forward_rule, ConnectReturnCode, Filter, MqttPacket, MqttProtocol,
+    RetainForwardRule, Subscribe,
+};
+
+use super::cache::CacheManager;
+use super::error::MqttBrokerError;
+use super::retain::try_send_retain_message;
+use crate::server::connection_manager::ConnectionManager;
+use crate::subscribe::sub_common::sub_path_validator;
+use crate::subscribe::subscribe_manager::SubscribeManager;
+
+const AUTO_SUBSCRIBE_CLIENT_ID_PLACEHOLDER: &str = "${clientid}";
+const AUTO_SUBSCRIBE_USERNAME_PLACEHOLDER: &str = "${username}";
+
+pub fn build_auto_subscribe_rule(
+    cluster: &str,
+    topic: &str,
+    qos_num: u32,
+    no_local: bool,
+    retain_as_published: bool,
+    retained_handling: u32,
+) -> Result<MqttAutoSubscribeRule, MqttBrokerError> {
+    // the placeholders are checked with a plain level, the template itself must be a
+    // valid subscription path
+    let sample_topic = topic
+        .replace(AUTO_SUBSCRIBE_CLIENT_ID_PLACEHOLDER, "clientid")
+        .replace(AUTO_SUBSCRIBE_USERNAME_PLACEHOLDER, "username");
+    if topic.is_empty() || !sub_path_validator(sample_topic) {
+        return Err(MqttBrokerError::InvalidAutoSubscribeRule(format!(
+            "topic {}",
+            topic
+        )));
+    }
+
+    let qos = u8::try_from(qos_num).ok().and_then(qos).ok_or(
+        MqttBrokerError::InvalidAutoSubscribeRule(format!("qos {}", qos_num)),
+    )?;
+    let retained_handling = u8::try_from(retained_handling)
+        .ok()
+        .and_then(retain_forward_rule)
+        .ok_or(MqttBrokerError::InvalidAutoSubscribeRule(format!(
+            "retained_handling {}",
+            retained_handling
+        )))?;
+
+    Ok(MqttAutoSubscribeRule {
+        cluster: cluster.to_string(),
+        topic: topic.to_string(),
+        qos,
+        no_local,
+        retain_as_published,
+        retained_handling,
+    })
+}
+
+pub fn is_connect_success(packet: &MqttPacket) -> bool {
+    matches!(packet, MqttPacket::ConnAck(conn_ack, _) if conn_ack.code == ConnectReturnCode::Success)
+}
+
+/// Make the auto subscriptions of a connection whose CONNACK has just been written. They start
+/// retained message delivery and push threads, and no PUBLISH may reach the client before the
+/// CONNACK does.
+pub async fn auto_subscribe_after_connack(
+    connect_id: u64,
+    cache_manager: &Arc<CacheManager>,
+    subscribe_manager: &Arc<SubscribeManager>,
+    client_pool: &Arc<ClientPool>,
+    connection_manager: &Arc<ConnectionManager>,
+) {
+    let Some(connection) = cache_manager.get_connection(connect_id) else {
+        return;
+    };
+    let Some(protocol) = connection_manager.get_connect_protocol(connect_id) else {
+        return;
+    };
+    try_auto_subscribe(
+        &protocol,
+        &connection.client_id,
+        &connection.login_user,
+        cache_manager,
+        subscribe_manager,
+        client_pool,
+        connection_manager,
+    )
+    .await;
+}
+
+pub async fn try_auto_subscribe(
+    protocol: &MqttProtocol,
+    client_id: &str,
+    login_user: &str,
+    cache_manager: &Arc<CacheManager>,
+    subscribe_manager: &Arc<SubscribeManager>,
+    client_pool: &Arc<ClientPool>,
+    connection_manager: &Arc<ConnectionManager>,
+) {
+    let filters = build_auto_subscribe_filters(
+        protocol,
+        client_id,
+        login_user,
+        &cache_manager.auto_subscribe_rule,
+    );
+    if filters.is_empty() {
+        return;
+    }
+
+    let mut subscribe = Subscribe {
+        packet_identifier: 0,
+        filters,
+    };
+
+    match subscribe_manager
+        .save_exclusive_subscribe(client_id, &subscribe)
+        .await
+    {
+        Ok(rejected) => {
+            for (path, code) in rejected.iter() {
+                warn!(
+                    "client_id:[{}] auto subscribe to {} was rejected, reason code: {:?}",
+                    client_id, path, code
+                );
+            }
+            subscribe
+                .filters
+                .retain(|filter| !rejected.contains_key(&filter.path));
+            if subscribe.filters.is_empty() {
+                return;
+            }
+        }
+        Err(e) => {
+            warn!(
+                "client_id:[{}] auto subscribe failed with error message: {}",
+                client_id, e
+            );
+            return;
+        }
+    }
+
+    cache_manager.add_client_subscribe(
+        client_id.to_owned(),
+        protocol.clone(),
+        subscribe.clone(),
+        None,
+    );
+
+    subscribe_manager
+        .add_subscribe(
+            client_id.to_owned(),
+            protocol.clone(),
+            subscribe.clone(),
+            None,
+        )
+        .await;
+
+    try_send_retain_message(
+        protocol.clone(),
+        client_id.to_owned(),
+        subscribe,
+        None,
+        client_pool.clone(),
+        cache_manager.clone(),
+        connection_manager.clone(),
+    )
+    .await;
+}
+
+pub fn render_auto_subscribe_topic(
+    template: &str,
+    client_id: &str,
+    login_user: &str,
+) -> Option<String> {
+    let mut topic = template.to_string();
+    for (placeholder, value) in [
+        (AUTO_SUBSCRIBE_CLIENT_ID_PLACEHOLDER, client_id),
+        (AUTO_SUBSCRIBE_USERNAME_PLACEHOLDER, login_user),
+    ] {
+        if !topic.contains(placeholder) {
+            continue;
+        }
+        // a value containing wildcards or level separators would widen the subscription
+        if value.is_empty() || value.contains(['+', '#', '/']) {
+            return None;
+        }
+        topic = topic.replace(placeholder, value);
+    }
+    Some(topic)
+}
+
+// Renders the topic template of every rule for the connecting client, the MQTT 5
+// subscription options only take effect for MQTT 5 clients.
+fn build_auto_subscribe_filters(
+    protocol: &MqttProtocol,
+    client_id: &str,
+    login_user: &str,
+    rules: &DashMap<String, MqttAutoSubscribeRule>,
+) -> Vec<Filter> {
+    let mut filters = Vec::new();
+    for rule in rules.iter() {
+        let path = match render_auto_subscribe_topic(&rule.topic, client_id, login_user) {
+            Some(path) => path,
+            None => {
+                warn!(
+                    "client_id:[{}] auto subscribe rule {} cannot be applied to the client",
+                    client_id, rule.topic
+                );
+                continue;
+            }
+        };
+
+        let filter = if protocol.is_mqtt5() {
+            Filter {
+                path,
+                qos: rule.qos,
+                nolocal: rule.no_local,
+                preserve_retain: rule.retain_as_published,
+                retain_forward_rule: rule.retained_handling.clone(),
+            }
+        } else {
+            Filter {
+                path,
+                qos: rule.qos,
+                nolocal: false,
+                preserve_retain: false,
+                retain_forward_rule: RetainForwardRule::OnEverySubscribe,
+            }
+        };
+        filters.push(filter);
+    }
+    filters
+}
+
+#[cfg(test)]
+mod tests {
+    use dashmap::DashMap;
+    use metadata_struct::mqtt::auto_subscribe_rule::MqttAutoSubscribeRule;
+    use protocol::mqtt::common::{MqttProtocol, QoS, RetainForwardRule};
+
+    use super::{
+        build_auto_subscribe_filters, build_auto_subscribe_rule, render_auto_subscribe_topic,
+    };
+
+    fn build_rule(topic: &str) -> MqttAutoSubscribeRule {
+        MqttAutoSubscribeRule {
+            cluster: "test".to_string(),
+            topic: topic.to_string(),
+            qos: QoS::AtLeastOnce,
+            no_local: true,
+            retain_as_published: true,
+            retained_handling: RetainForwardRule::Never,
+        }
+    }
+
+    #[test]
+    fn build_auto_subscribe_filters_test() {
+        let rules = DashMap::new();
+        for topic in ["cmd/${clientid}", "user/${username}/notify", "broadcast/#"] {
+            rules.insert(topic.to_string(), build_rule(topic));
+        }
+
+        let mut filters =
+            build_auto_subscribe_filters(&MqttProtocol::Mqtt5, "device-1", "admin", &rules);
+        filters.sort_by(|a, b| a.path.cmp(&b.path));
+        let paths: Vec<&str> = filters.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["broadcast/#", "cmd/device-1", "user/admin/notify"]
+        );
+        assert!(filters[0].nolocal);
+        assert!(filters[0].preserve_retain);
+        assert_eq!(filters[0].retain_forward_rule, RetainForwardRule::Never);
+        assert_eq!(filters[0].qos, QoS::AtLeastOnce);
+
+        // anonymous clients skip the rules that depend on the username
+        let filters = build_auto_subscribe_filters(&MqttProtocol::Mqtt4, "device-1", "", &rules);
+        assert_eq!(filters.len(), 2);
+        for filter in filters {
+            assert_ne!(filter.path, "user//notify");
+            assert!(!filter.nolocal);
+            assert!(!filter.preserve_retain);
+            assert_eq!(
+                filter.retain_forward_rule,
+                RetainForwardRule::OnEverySubscribe
+            );
+        }
+
+        // client ids with wildcards must not widen the subscription
+        let rules = DashMap::new();
+        rules.insert("cmd/${clientid}".to_string(), build_rule("cmd/${clientid}"));
+        let filters = build_auto_subscribe_filters(&MqttProtocol::Mqtt5, "a/#", "", &rules);
+        assert!(filters.is_empty());
+    }
+
+    #[test]
+    fn build_auto_subscribe_rule_test() {
+        let rule = build_auto_subscribe_rule("test", "cmd/${clientid}", 1, false, true, 2).unwrap();
+        assert_eq!(rule.topic, "cmd/${clientid}");
+        assert_eq!(rule.qos, QoS::AtLeastOnce);
+        assert!(rule.retain_as_published);
+        assert_eq!(rule.retained_handling, RetainForwardRule::Never);
+
+        assert!(build_auto_subscribe_rule("test", "", 1, false, false, 0).is_err());
+        assert!(build_auto_subscribe_rule("test", "cmd/a#", 1, false, false, 0).is_err());
+        assert!(build_auto_subscribe_rule("test", "cmd/${clientid}", 3, false, false, 0).is_err());
+        assert!(
+            build_auto_subscribe_rule("test", "cmd/${clientid}", 257, false, false, 0).is_err()
+        );
+        assert!(build_auto_subscribe_rule("test", "cmd/${clientid}", 0, false, false, 3).is_err());
+    }
+
+    #[test]
+    fn render_auto_subscribe_topic_test() {
+        assert_eq!(
+            render_auto_subscribe_topic("cmd/${clientid}", "device-1", ""),
+            Some("cmd/device-1".to_string())
+        );
+        assert_eq!(
+            render_auto_subscribe_topic("${username}/${clientid}/#", "c1", "u1"),
+            Some("u1/c1/#".to_string())
+        );
+        assert_eq!(
+            render_auto_subscribe_topic("broadcast", "c1", ""),
+            Some("broadcast".to_string())
+        );
+        assert_eq!(
+            render_auto_subscribe_topic("user/${username}", "c1", ""),
+            None
+        );
+        assert_eq!(
+            render_auto_subscribe_topic("cmd/${clientid}", "c+", ""),
+            None
+        );
+        assert_eq!(
+            render_auto_subscribe_topic("cmd/${clientid}", "a/b", ""),
+            None
+        );
+    }
+}
@@ -18,13 +18,20 @@ use std::time::Duration;
 
 use common_base::config::broker_mqtt::broker_mqtt_conf;
 use common_base::tools::now_second;
+use common_base::utils::topic_trie::TopicTrie;
+use common_base::utils::topic_util::encode_exclusive_sub_path;
 use dashmap::DashMap;
 use grpc_clients::pool::ClientPool;
-use log::warn;
+use log::{error, warn};
 use metadata_struct::acl::mqtt_acl::MqttAcl;
 use metadata_struct::acl::mqtt_blacklist::MqttAclBlackList;
-use metadata_struct::mqtt::cluster::MqttClusterDynamicConfig;
+use metadata_struct::mqtt::auto_subscribe_rule::MqttAutoSubscribeRule;
+use metadata_struct::mqtt::cluster::{MqttClusterDynamicConfig, MqttShareSubGroupStrategy};
 use metadata_struct::mqtt::connection::MQTTConnection;
+use metadata_struct::mqtt::exclusive_topic::MqttExclusiveTopic;
+use metadata_struct::mqtt::inflight::MqttInflightMessage;
+use metadata_struct::mqtt::rule::MqttRule;
+use metadata_struct::mqtt::schema::{MqttSchema, MqttSchemaBinding};
 use metadata_struct::mqtt::session::MqttSession;
 use metadata_struct::mqtt::topic::MqttTopic;
 use metadata_struct::mqtt::topic_rewrite_rule::MqttTopicRewriteRule;
@@ -35,13 +42,24 @@ use protocol::broker_mqtt::broker_mqtt_inner::{
 use protocol::mqtt::common::{MqttProtocol, PublishProperties, Subscribe, SubscribeProperties};
 use serde::{Deserialize, Serialize};
 use tokio::sync::broadcast::Sender;
+use tokio::sync::Mutex;
 use tokio::time::sleep;
 
+use crate::handler::error::MqttBrokerError;
+use crate::handler::inflight::{inflight_persist_key, InflightPersistOp};
+use crate::handler::schema::SchemaRuntime;
+use crate::handler::topic_alias::OutboundTopicAlias;
+use crate::rule_engine::RuleRuntime;
 use crate::security::acl::metadata::AclMetadata;
 use crate::security::AuthDriver;
+use crate::storage::auto_subscribe::AutoSubscribeStorage;
 use crate::storage::cluster::ClusterStorage;
+use crate::storage::rule::RuleStorage;
+use crate::storage::schema::SchemaStorage;
 use crate::storage::topic::TopicStorage;
 use crate::storage::user::UserStorage;
+use crate::subscribe::sub_common::sub_path_filter;
+use crate::subscribe::subscribe_manager::SubscribeManager;
 use crate::subscribe::subscriber::SubscribeData;
 
 #[derive(Clone, Serialize, Deserialize)]
@@ -116,6 +134,9 @@ pub struct CacheManager {
     // (client_id, <path,SubscribeData>)
     pub subscribe_filter: DashMap<String, DashMap<String, SubscribeData>>,
 
+    // Filters of subscribe_filter, (client_id_path, (client_id, path))
+    pub subscribe_filter_trie: Arc<TopicTrie<(String, String)>>,
+
     // (client_id, <path,bool>)
     pub subscribe_is_new: DashMap<String, DashMap<String, bool>>,
 
@@ -125,12 +146,19 @@ pub struct CacheManager {
     // (connect_id, Connection)
     pub connection_info: DashMap<u64, MQTTConnection>,
 
+    // Topic aliases assigned to outbound publishes
+    // (connect_id, OutboundTopicAlias)
+    pub outbound_topic_alias: DashMap<u64, Arc<Mutex<OutboundTopicAlias>>>,
+
     // (topic_name, Topic)
     pub topic_info: DashMap<String, MqttTopic>,
 
     // (topic_id, topic_name)
     pub topic_id_name: DashMap<String, String>,
 
+    // Names of topic_info, (topic_name, topic_name)
+    pub topic_trie: Arc<TopicTrie<String>>,
+
     // (client_id, HeartbeatShard)
     pub heartbeat_data: DashMap<String, ConnectionLiveTime>,
 
@@ -140,11 +168,35 @@ pub struct CacheManager {
     // (client_id_pkid, QosPkidData)
     pub client_pkid_data: DashMap<String, ClientPkidData>,
 
+    // Outbound QoS 1/2 messages the client has not completed yet
+    // (client_id_pkid, MqttInflightMessage)
+    pub inflight_message: DashMap<String, MqttInflightMessage>,
+
+    // Changes of the inflight state waiting to be written to the Placement Center
+    // (client_id_pkid_direction, InflightPersistOp)
+    pub inflight_persist: DashMap<String, InflightPersistOp>,
+
     // acl metadata
     pub acl_metadata: AclMetadata,
 
     // All topic rewrite rule
     pub topic_rewrite_rule: DashMap<String, MqttTopicRewriteRule>,
+
+    // All auto subscribe rule
+    // (topic, MqttAutoSubscribeRule)
+    pub auto_subscribe_rule: DashMap<String, MqttAutoSubscribeRule>,
+
+    // All rule engine rule
+    // (rule_name, RuleRuntime)
+    pub rule_info: DashMap<String, Arc<RuleRuntime>>,
+
+    // All payload schema
+    // (schema_name, SchemaRuntime)
+    pub schema_info: DashMap<String, Arc<SchemaRuntime>>,
+
+    // All schema binding
+    // (schema_name/topic_filter, MqttSchemaBinding)
+    pub schema_binding: DashMap<String, MqttSchemaBinding>,
 }
 
 impl CacheManager {
@@ -157,15 +209,24 @@ impl CacheManager {
             session_info: DashMap::with_capacity(8),
             topic_info: DashMap::with_capacity(8),
             topic_id_name: DashMap::with_capacity(8),
+            topic_trie: Arc::new(TopicTrie::new()),
             connection_info: DashMap::with_capacity(8),
+            outbound_topic_alias: DashMap::with_capacity(8),
             subscribe_filter: DashMap::with_capacity(8),
+            subscribe_filter_trie: Arc::new(TopicTrie::new()),
             subscribe_is_new: DashMap::with_capacity(8),
             publish_pkid_info: DashMap::with_capacity(8),
             heartbeat_data: DashMap::with_capacity(8),
             qos_ack_packet: DashMap::with_capacity(8),
             client_pkid_data: DashMap::with_capacity(8),
+            inflight_message: DashMap::with_capacity(8),
+            inflight_persist: DashMap::with_capacity(8),
             acl_metadata: AclMetadata::new(),
             topic_rewrite_rule: DashMap::with_capacity(8),
+            auto_subscribe_rule: DashMap::with_capacity(8),
+            rule_info: DashMap::with_capacity(8),
+            schema_info: DashMap::with_capacity(8),
+            schema_binding: DashMap::with_capacity(8),
         }
     }
 
@@ -179,7 +240,13 @@ impl CacheManager {
         for filter in subscribe.filters {
             let mut is_new = false;
             let path = filter.path.clone();
+            self.subscribe_filter_trie.insert(
+                &sub_path_filter(&path),
+                &self.subscribe_filter_key(&client_id, &path),
+                (client_id.clone(), path.clone()),
+            );
             if let Some(data) = self.subscribe_filter.get_mut(&client_id) {
+                is_new = !data.contains_key(&path);
                 data.insert(
                     path.clone(),
                     SubscribeData {
@@ -219,9 +286,22 @@ impl CacheManager {
                     sub_list.remove(path);
                 }
             }
+            self.subscribe_filter_trie.remove(
+                &sub_path_filter(path),
+                &self.subscribe_filter_key(client_id, path),
+            );
         }
     }
 
+    /// Subscriptions whose filter matches the topic, as (client_id, path).
+    pub fn get_subscribe_filter_by_topic(&self, topic_name: &str) -> Vec<(String, String)> {
+        self.subscribe_filter_trie.match_topic(topic_name)
+    }
+
+    fn subscribe_filter_key(&self, client_id: &str, path: &str) -> String {
+        format!("{}_{}", client_id, path)
+    }
+
     pub fn get_session_info(&self, client_id: &str) -> Option<MqttSession> {
         if let Some(session) = self.session_info.get(client_id) {
             return Some(session.clone());
@@ -290,6 +370,8 @@ impl CacheManager {
         let t = topic.clone();
         self.topic_info.insert(topic_name.to_owned(), t.clone());
         self.topic_id_name.insert(t.topic_id, topic_name.to_owned());
+        self.topic_trie
+            .insert(topic_name, topic_name, topic_name.to_owned());
     }
 
     pub fn add_topic_rewrite_rule(&self, topic_rewrite_rule: MqttTopicRewriteRule) {
@@ -306,6 +388,60 @@ impl CacheManager {
         self.topic_rewrite_rule.remove(&key);
     }
 
+    pub fn add_auto_subscribe_rule(&self, auto_subscribe_rule: MqttAutoSubscribeRule) {
+        self.auto_subscribe_rule
+            .insert(auto_subscribe_rule.topic.clone(), auto_subscribe_rule);
+    }
+
+    pub fn delete_auto_subscribe_rule(&self, topic: &str) {
+        self.auto_subscribe_rule.remove(topic);
+    }
+
+    pub fn add_rule(&self, rule: MqttRule) {
+        let rule_name = rule.rule_name.clone();
+        match RuleRuntime::new(rule) {
+            Ok(runtime) => {
+                self.rule_info.insert(rule_name, Arc::new(runtime));
+            }
+            Err(e) => {
+                error!("Rule {} cannot be loaded, error message: {}", rule_name, e);
+            }
+        }
+    }
+
+    pub fn delete_rule(&self, rule_name: &str) {
+        self.rule_info.remove(rule_name);
+    }
+
+    pub fn add_schema(&self, schema: MqttSchema) {
+        let schema_name = schema.schema_name.clone();
+        match SchemaRuntime::new(schema) {
+            Ok(runtime) => {
+                self.schema_info.insert(schema_name, Arc::new(runtime));
+            }
+            Err(e) => {
+                error!(
+                    "Schema {} cannot be loaded, error message: {}",
+                    schema_name, e
+                );
+            }
+        }
+    }
+
+    pub fn delete_schema(&self, schema_name: &str) {
+        self.schema_info.remove(schema_name);
+    }
+
+    pub fn add_schema_binding(&self, binding: MqttSchemaBinding) {
+        let key = self.schema_binding_key(&binding.schema_name, &binding.topic_filter);
+        self.schema_binding.insert(key, binding);
+    }
+
+    pub fn delete_schema_binding(&self, schema_name: &str, topic_filter: &str) {
+        let key = self.schema_binding_key(schema_name, topic_filter);
+        self.schema_binding.remove(&key);
+    }
+
     pub fn update_topic_retain_message(&self, topic_name: &str, retain_message: Option<Vec<u8>>) {
         if let Some(mut topic) = self.topic_info.get_mut(topic_name) {
             topic.retain_message = retain_message;
@@ -345,7 +481,14 @@ impl CacheManager {
 
     pub fn remove_session(&self, client_id: &str) {
         self.session_info.remove(client_id);
-        self.subscribe_filter.remove(client_id);
+        if let Some((_, sub_list)) = self.subscribe_filter.remove(client_id) {
+            for (path, _) in sub_list {
+                self.subscribe_filter_trie.remove(
+                    &sub_path_filter(&path),
+                    &self.subscribe_filter_key(client_id, &path),
+                );
+            }
+        }
         self.subscribe_is_new.remove(client_id);
         self.publish_pkid_info.remove(client_id);
         self.heartbeat_data.remove(client_id);
@@ -358,13 +501,35 @@ impl CacheManager {
 
         for (key, _) in self.client_pkid_data.clone() {
             if key.starts_with(client_id) {
-                self.qos_ack_packet.remove(&key);
+                self.client_pkid_data.remove(&key);
             }
         }
+
+        self.inflight_message
+            .retain(|_, message| message.client_id != client_id);
+        self.inflight_persist
+            .retain(|_, op| op.client_id() != client_id);
     }
 
     pub fn remove_connection(&self, connect_id: u64) {
         self.connection_info.remove(&connect_id);
+        self.outbound_topic_alias.remove(&connect_id);
+    }
+
+    // Returns None when the client does not accept topic aliases from the broker.
+    pub fn get_outbound_topic_alias(
+        &self,
+        connect_id: u64,
+    ) -> Option<Arc<Mutex<OutboundTopicAlias>>> {
+        let conn = self.connection_info.get(&connect_id)?;
+        if conn.topic_alias_max == 0 {
+            return None;
+        }
+        let alias = self
+            .outbound_topic_alias
+            .entry(connect_id)
+            .or_insert_with(|| Arc::new(Mutex::new(OutboundTopicAlias::new(conn.topic_alias_max))));
+        Some(alias.clone())
     }
 
     pub fn get_topic_alias(&self, connect_id: u64, topic_alias: u16) -> Option<String> {
@@ -447,7 +612,19 @@ impl CacheManager {
 
     pub fn remove_pkid_info(&self, client_id: &str, pkid: u16) {
         if let Some(mut pkid_list) = self.publish_pkid_info.get_mut(client_id) {
-            pkid_list.retain(|x| *x == pkid);
+            pkid_list.retain(|x| *x != pkid);
+        }
+    }
+
+    // Marks a pkid recovered from the inflight state as used, so it is not handed out again.
+    pub fn reserve_pkid(&self, client_id: &str, pkid: u16) {
+        if let Some(mut pkid_list) = self.publish_pkid_info.get_mut(client_id) {
+            if !pkid_list.contains(&pkid) {
+                pkid_list.push(pkid);
+            }
+        } else {
+            self.publish_pkid_info
+                .insert(client_id.to_owned(), vec![pkid]);
         }
     }
 
@@ -477,6 +654,23 @@ impl CacheManager {
         };
         self.set_cluster_info(cluster);
 
+        // load the shared subscription strategies
+        let strategies = match cluster_storage
+            .list_share_sub_strategy(&conf.cluster_name)
+            .await
+        {
+            Ok(list) => list,
+            Err(e) => {
+                panic!(
+                    "Failed to load the shared subscription strategies with error message:{}",
+                    e
+                );
+            }
+        };
+        for strategy in strategies {
+            self.apply_share_sub_strategy(strategy);
+        }
+
         // load all topic
         let topic_storage = TopicStorage::new(self.client_pool.clone());
         let topic_list = match topic_storage.all().await {
@@ -538,6 +732,58 @@ impl CacheManager {
         for topic_rewrite_rule in topic_rewrite_rules {
             self.add_topic_rewrite_rule(topic_rewrite_rule);
         }
+
+        // load All auto subscribe rule
+        let auto_subscribe_storage = AutoSubscribeStorage::new(self.client_pool.clone());
+        let auto_subscribe_rules = match auto_subscribe_storage.list_auto_subscribe_rule().await {
+            Ok(list) => list,
+            Err(e) => {
+                panic!(
+                    "Failed to load the auto subscribe rule list with error message:{}",
+                    e
+                );
+            }
+        };
+        for auto_subscribe_rule in auto_subscribe_rules {
+            self.add_auto_subscribe_rule(auto_subscribe_rule);
+        }
+
+        // load All rule engine rule
+        let rule_storage = RuleStorage::new(self.client_pool.clone());
+        let rules = match rule_storage.list_rule().await {
+            Ok(list) => list,
+            Err(e) => {
+                panic!("Failed to load the rule list with error message:{}", e);
+            }
+        };
+        for rule in rules {
+            self.add_rule(rule);
+        }
+
+        // load All schema and schema binding
+        let schema_storage = SchemaStorage::new(self.client_pool.clone());
+        let schemas = match schema_storage.list_schema().await {
+            Ok(list) => list,
+            Err(e) => {
+                panic!("Failed to load the schema list with error message:{}", e);
+            }
+        };
+        for schema in schemas {
+            self.add_schema(schema);
+        }
+
+        let schema_bindings = match schema_storage.list_schema_binding().await {
+            Ok(list) => list,
+            Err(e) => {
+                panic!(
+                    "Failed to load the schema binding list with error message:{}",
+                    e
+                );
+            }
+        };
+        for binding in schema_bindings {
+            self.add_schema_binding(binding);
+        }
     }
 
     pub async fn init_system_user(&self) {
@@ -634,6 +880,65 @@ impl CacheManager {
         None
     }
 
+    pub fn add_inflight_message(&self, message: MqttInflightMessage) {
+        let key = self.key(&message.client_id, message.pkid);
+        self.inflight_message.insert(key, message);
+    }
+
+    pub fn remove_inflight_message(&self, client_id: &str, pkid: u16) {
+        let key = self.key(client_id, pkid);
+        self.inflight_message.remove(&key);
+    }
+
+    pub fn get_inflight_message_by_client(&self, client_id: &str) -> Vec<MqttInflightMessage> {
+        self.inflight_message
+            .iter()
+            .filter(|raw| raw.client_id == client_id)
+            .map(|raw| raw.value().clone())
+            .collect()
+    }
+
+    // A later change of the same message replaces the queued one, only the last state is written.
+    pub fn add_inflight_persist(&self, op: InflightPersistOp) {
+        let key = match &op {
+            InflightPersistOp::Save(message) => {
+                inflight_persist_key(&message.client_id, message.pkid, message.is_inbound())
+            }
+            InflightPersistOp::Delete {
+                client_id,
+                pkid,
+                inbound,
+            } => inflight_persist_key(client_id, *pkid, *inbound),
+        };
+        self.inflight_persist.insert(key, op);
+    }
+
+    pub fn requeue_inflight_persist(&self, key: String, op: InflightPersistOp) {
+        self.inflight_persist.entry(key).or_insert(op);
+    }
+
+    pub fn take_inflight_persist(&self) -> Vec<(String, InflightPersistOp)> {
+        let keys: Vec<String> = self
+            .inflight_persist
+            .iter()
+            .map(|raw| raw.key().clone())
+            .collect();
+        keys.into_iter()
+            .filter_map(|key| self.inflight_persist.remove(&key))
+            .collect()
+    }
+
+    pub fn get_inflight_persist_by_client(
+        &self,
+        client_id: &str,
+    ) -> Vec<(String, InflightPersistOp)> {
+        self.inflight_persist
+            .iter()
+            .filter(|raw| raw.client_id() == client_id)
+            .map(|raw| (raw.key().clone(), raw.value().clone()))
+            .collect()
+    }
+
     fn key(&self, client_id: &str, pkid: u16) -> String {
         format!("{}_{}", client_id, pkid)
     }
@@ -646,9 +951,17 @@ impl CacheManager {
     ) -> String {
         format!("{}_{}_{}", cluster, action, source_topic)
     }
+
+    pub fn schema_binding_key(&self, schema_name: &str, topic_filter: &str) -> String {
+        format!("{}/{}", schema_name, topic_filter)
+    }
 }
 
-pub fn update_cache_metadata(request: UpdateCacheRequest) {
+pub fn update_cache_metadata(
+    cache_manager: &Arc<CacheManager>,
+    subscribe_manager: &Arc<SubscribeManager>,
+    request: UpdateCacheRequest,
+) -> Result<(), MqttBrokerError> {
     match request.resource_type() {
         MqttBrokerUpdateCacheResourceType::Session => match request.action_type() {
             MqttBrokerUpdateCacheActionType::Add => {}
@@ -658,5 +971,66 @@ pub fn update_cache_metadata(request: UpdateCacheRequest) {
             MqttBrokerUpdateCacheActionType::Add => {}
             MqttBrokerUpdateCacheActionType::Delete => {}
         },
+        MqttBrokerUpdateCacheResourceType::Rule => {
+            let rule = serde_json::from_slice::<MqttRule>(&request.data)?;
+            match request.action_type() {
+                MqttBrokerUpdateCacheActionType::Add => cache_manager.add_rule(rule),
+                MqttBrokerUpdateCacheActionType::Delete => {
+                    cache_manager.delete_rule(&rule.rule_name)
+                }
+            }
+        }
+        MqttBrokerUpdateCacheResourceType::Schema => {
+            let schema = serde_json::from_slice::<MqttSchema>(&request.data)?;
+            match request.action_type() {
+                MqttBrokerUpdateCacheActionType::Add => cache_manager.add_schema(schema),
+                MqttBrokerUpdateCacheActionType::Delete => {
+                    cache_manager.delete_schema(&schema.schema_name)
+                }
+            }
+        }
+        MqttBrokerUpdateCacheResourceType::SchemaBinding => {
+            let binding = serde_json::from_slice::<MqttSchemaBinding>(&request.data)?;
+            match request.action_type() {
+                MqttBrokerUpdateCacheActionType::Add => cache_manager.add_schema_binding(binding),
+                MqttBrokerUpdateCacheActionType::Delete => {
+                    cache_manager.delete_schema_binding(&binding.schema_name, &binding.topic_filter)
+                }
+            }
+        }
+        MqttBrokerUpdateCacheResourceType::AutoSubscribeRule => {
+            let auto_subscribe_rule =
+                serde_json::from_slice::<MqttAutoSubscribeRule>(&request.data)?;
+            match request.action_type() {
+                MqttBrokerUpdateCacheActionType::Add => {
+                    cache_manager.add_auto_subscribe_rule(auto_subscribe_rule)
+                }
+                MqttBrokerUpdateCacheActionType::Delete => {
+                    cache_manager.delete_auto_subscribe_rule(&auto_subscribe_rule.topic)
+                }
+            }
+        }
+        MqttBrokerUpdateCacheResourceType::ShareSubStrategy => {
+            let strategy = serde_json::from_slice::<MqttShareSubGroupStrategy>(&request.data)?;
+            match request.action_type() {
+                MqttBrokerUpdateCacheActionType::Add => {
+                    cache_manager.apply_share_sub_strategy(strategy)
+                }
+                MqttBrokerUpdateCacheActionType::Delete => {}
+            }
+        }
+        MqttBrokerUpdateCacheResourceType::ExclusiveTopic => {
+            let lock = serde_json::from_slice::<MqttExclusiveTopic>(&request.data)?;
+            match request.action_type() {
+                MqttBrokerUpdateCacheActionType::Add => {}
+                // The lock was released by force, the holder loses the subscription with it.
+                MqttBrokerUpdateCacheActionType::Delete => {
+                    let paths = vec![encode_exclusive_sub_path(&lock.topic_name)];
+                    subscribe_manager.remove_subscribe(&lock.client_id, &paths);
+                    cache_manager.remove_filter_by_pkid(&lock.client_id, &paths);
+                }
+            }
+        }
     }
+    Ok(())
 }
@@ -12,7 +12,10 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use metadata_struct::mqtt::cluster::{MqttClusterDynamicConfig, MqttClusterDynamicSlowSub};
+use metadata_struct::mqtt::cluster::{
+    MqttClusterDynamicConfig, MqttClusterDynamicSlowSub, MqttShareSubGroupStrategy,
+    MqttShareSubStrategy,
+};
 
 use crate::handler::cache::CacheManager;
 use crate::handler::error::MqttBrokerError;
@@ -51,4 +54,42 @@ impl CacheManager {
     pub fn get_slow_sub_config(&self) -> MqttClusterDynamicSlowSub {
         self.get_cluster_info().slow
     }
+
+    // An empty group name changes the cluster default, which applies to every group
+    // without a strategy of its own. The Placement Center pushes the change to all brokers,
+    // including the one running the leader of the group.
+    pub async fn set_share_sub_strategy(
+        &self,
+        group_name: &str,
+        strategy: MqttShareSubStrategy,
+    ) -> Result<(), MqttBrokerError> {
+        let data = MqttShareSubGroupStrategy {
+            cluster_name: self.cluster_name.clone(),
+            group_name: group_name.to_string(),
+            strategy,
+        };
+
+        // save in storage
+        let client_pool = self.client_pool.clone();
+        let cluster_storage = ClusterStorage::new(client_pool);
+        cluster_storage.set_share_sub_strategy(data.clone()).await?;
+
+        // save in cache
+        self.apply_share_sub_strategy(data);
+        Ok(())
+    }
+
+    pub fn apply_share_sub_strategy(&self, data: MqttShareSubGroupStrategy) {
+        if let Some(mut cluster) = self.cluster_info.get_mut(&self.cluster_name) {
+            cluster
+                .share_sub
+                .set_strategy(&data.group_name, data.strategy);
+            return;
+        }
+        let mut cluster = MqttClusterDynamicConfig::new();
+        cluster
+            .share_sub
+            .set_strategy(&data.group_name, data.strategy);
+        self.set_cluster_info(cluster);
+    }
 }
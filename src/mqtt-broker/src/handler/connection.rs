@@ -54,10 +54,11 @@ pub fn build_connection(
                 cluster.protocol.max_packet_size
             };
 
+            // The client accepts no topic alias from the broker unless it sends the property.
             let topic_alias_max = if let Some(value) = properties.topic_alias_max {
                 std::cmp::min(value, cluster.protocol.topic_alias_max)
             } else {
-                cluster.protocol.topic_alias_max
+                0
             };
 
             let request_problem_info = properties.request_problem_info.unwrap_or_default();
@@ -72,7 +73,7 @@ pub fn build_connection(
             (
                 cluster.protocol.receive_max,
                 cluster.protocol.max_packet_size,
-                cluster.protocol.topic_alias_max,
+                0,
                 0,
             )
         };
@@ -117,9 +118,6 @@ pub async fn disconnect_connection(
     connection_manager: &Arc<ConnectionManager>,
     subscribe_manager: &Arc<SubscribeManager>,
 ) -> Result<(), CommonError> {
-    subscribe_manager
-        .remove_exclusive_subscribe_by_client_id(client_id)
-        .await?;
     // Remove the connection cache
     cache_manager.remove_connection(connect_id);
     // Remove the client id bound connection information
@@ -194,6 +192,16 @@ mod test {
         assert_eq!(conn.max_packet_size, 100);
         assert_eq!(conn.topic_alias_max, 100);
         assert_eq!(conn.request_problem_info, 0);
+
+        let conn = build_connection(
+            connect_id,
+            client_id.clone(),
+            &cluster,
+            &connect,
+            &Some(ConnectProperties::default()),
+            &addr,
+        );
+        assert_eq!(conn.topic_alias_max, 0);
     }
 
     #[tokio::test]
@@ -249,4 +257,18 @@ mod test {
         conn.send_qos_message_decr();
         assert_eq!(conn.get_send_qos_message(), 0);
     }
+
+    #[tokio::test]
+    pub async fn try_send_qos_message_incr_test() {
+        let conn = MQTTConnection {
+            client_max_receive_maximum: 2,
+            ..Default::default()
+        };
+        assert!(conn.try_send_qos_message_incr());
+        assert!(conn.try_send_qos_message_incr());
+        assert!(!conn.try_send_qos_message_incr());
+        assert_eq!(conn.get_send_qos_message(), 2);
+        conn.send_qos_message_decr();
+        assert!(conn.try_send_qos_message_incr());
+    }
 }
@@ -15,6 +15,8 @@
 pub const SUB_RETAIN_MESSAGE_PUSH_FLAG: &str = "retain_push_flag";
 pub const SUB_RETAIN_MESSAGE_PUSH_FLAG_VALUE: &str = "true";
 pub const WILDCARD_RESOURCE: &str = "*";
+pub const SCHEMA_VALIDATION_ERROR_PROPERTY: &str = "schema_validation_error";
+pub const INFLIGHT_PERSIST_INTERVAL_MS: u64 = 100;
 
 pub const METRICS_KEY_PROTOCOL_NAME: &str = "protocol";
 pub const METRICS_KEY_NETWORK_TYPE: &str = "network";
@@ -93,6 +93,21 @@ pub enum MqttBrokerError {
 
     #[error("topicRewriteRule has been existed")]
     TopicRewriteRuleAlreadyExist,
+
+    #[error("Invalid auto subscribe rule: {0}")]
+    InvalidAutoSubscribeRule(String),
+
+    #[error("Invalid rule sql: {0}")]
+    InvalidRuleSql(String),
+
+    #[error("Invalid rule action: {0}")]
+    InvalidRuleAction(String),
+
+    #[error("Client {0} is not allowed to publish to topic {1}")]
+    RulePublishNotAuthorized(String, String),
+
+    #[error("Invalid schema: {0}")]
+    InvalidSchema(String),
 }
 
 impl From<MqttBrokerError> for Status {
@@ -12,12 +12,19 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use metadata_struct::mqtt::connection::MQTTConnection;
 use protocol::mqtt::common::{MqttProtocol, QoS};
 
 pub fn is_flow_control(protocol: &MqttProtocol, qos: QoS) -> bool {
     protocol.is_mqtt5() && (qos == QoS::AtLeastOnce || qos == QoS::ExactlyOnce)
 }
 
+// The pending QoS 1/2 messages of the connection include the one being received, so the quota is
+// exceeded only once the count goes above the Receive Maximum.
+pub fn is_receive_maximum_exceeded(connection: &MQTTConnection, receive_max: u16) -> bool {
+    connection.get_recv_qos_message() > receive_max as isize
+}
+
 pub fn is_connection_rate_exceeded() -> bool {
     false
 }
@@ -25,3 +32,32 @@ pub fn is_connection_rate_exceeded() -> bool {
 pub fn is_subscribe_rate_exceeded() -> bool {
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use metadata_struct::mqtt::connection::MQTTConnection;
+    use protocol::mqtt::common::{MqttProtocol, QoS};
+
+    use super::{is_flow_control, is_receive_maximum_exceeded};
+
+    #[test]
+    fn is_flow_control_test() {
+        assert!(!is_flow_control(&MqttProtocol::Mqtt4, QoS::AtLeastOnce));
+        assert!(!is_flow_control(&MqttProtocol::Mqtt5, QoS::AtMostOnce));
+        assert!(is_flow_control(&MqttProtocol::Mqtt5, QoS::ExactlyOnce));
+    }
+
+    #[test]
+    fn is_receive_maximum_exceeded_test() {
+        let conn = MQTTConnection::default();
+        conn.recv_qos_message_incr();
+        conn.recv_qos_message_incr();
+        assert!(!is_receive_maximum_exceeded(&conn, 2));
+
+        conn.recv_qos_message_incr();
+        assert!(is_receive_maximum_exceeded(&conn, 2));
+
+        conn.recv_qos_message_decr();
+        assert!(!is_receive_maximum_exceeded(&conn, 2));
+    }
+}
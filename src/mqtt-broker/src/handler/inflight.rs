@@ -0,0 +1,394 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicIsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use log::{error, info};
+use metadata_struct::mqtt::inflight::MqttInflightMessage;
+use protocol::placement_center::placement_center_mqtt::InflightMessageKey;
+use tokio::select;
+use tokio::sync::broadcast;
+use tokio::time::sleep;
+
+use super::cache::CacheManager;
+use super::constant::INFLIGHT_PERSIST_INTERVAL_MS;
+use super::error::MqttBrokerError;
+use crate::storage::inflight::InflightStorage;
+
+/// A change of the inflight state that is not written to the Placement Center yet.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InflightPersistOp {
+    Save(MqttInflightMessage),
+    Delete {
+        client_id: String,
+        pkid: u16,
+        inbound: bool,
+    },
+}
+
+impl InflightPersistOp {
+    pub fn client_id(&self) -> &str {
+        match self {
+            InflightPersistOp::Save(message) => &message.client_id,
+            InflightPersistOp::Delete { client_id, .. } => client_id,
+        }
+    }
+}
+
+// The inflight state of outbound QoS 1/2 messages is kept in memory. When client_pkid_persistent
+// is enabled it is also written to the Placement Center, so that it survives a broker restart.
+// Those writes are queued and flushed in batches by start_inflight_persist_thread, so the push
+// path does not wait for a raft write per packet.
+pub fn inflight_save(cache_manager: &Arc<CacheManager>, message: MqttInflightMessage) {
+    cache_manager.add_inflight_message(message.clone());
+    if is_persistent(cache_manager) {
+        cache_manager.add_inflight_persist(InflightPersistOp::Save(message));
+    }
+}
+
+pub fn inflight_delete(cache_manager: &Arc<CacheManager>, client_id: &str, pkid: u16) {
+    cache_manager.remove_inflight_message(client_id, pkid);
+    if is_persistent(cache_manager) {
+        cache_manager.add_inflight_persist(InflightPersistOp::Delete {
+            client_id: client_id.to_owned(),
+            pkid,
+            inbound: false,
+        });
+    }
+}
+
+pub async fn inflight_list(
+    cache_manager: &Arc<CacheManager>,
+    client_id: &str,
+) -> Result<Vec<MqttInflightMessage>, MqttBrokerError> {
+    if is_persistent(cache_manager) {
+        let results = persisted_inflight_list(cache_manager, client_id).await?;
+        return Ok(results
+            .into_iter()
+            .filter(|raw| !raw.is_inbound())
+            .collect());
+    }
+    Ok(cache_manager.get_inflight_message_by_client(client_id))
+}
+
+// Both the inbound and the outbound state of the client in the Placement Center, with the
+// changes that are still queued applied on top.
+pub(crate) async fn persisted_inflight_list(
+    cache_manager: &Arc<CacheManager>,
+    client_id: &str,
+) -> Result<Vec<MqttInflightMessage>, MqttBrokerError> {
+    let storage = InflightStorage::new(cache_manager.client_pool.clone());
+    let mut results = HashMap::new();
+    for raw in storage.list_inflight_message(client_id).await? {
+        let key = inflight_persist_key(&raw.client_id, raw.pkid, raw.is_inbound());
+        results.insert(key, raw);
+    }
+
+    for (key, op) in cache_manager.get_inflight_persist_by_client(client_id) {
+        match op {
+            InflightPersistOp::Save(message) => {
+                results.insert(key, message);
+            }
+            InflightPersistOp::Delete { .. } => {
+                results.remove(&key);
+            }
+        }
+    }
+    Ok(results.into_values().collect())
+}
+
+pub(crate) fn inflight_persist_key(client_id: &str, pkid: u16, inbound: bool) -> String {
+    let direction = if inbound { "in" } else { "out" };
+    format!("{}_{}_{}", client_id, pkid, direction)
+}
+
+pub(crate) fn is_persistent(cache_manager: &Arc<CacheManager>) -> bool {
+    cache_manager
+        .get_cluster_info()
+        .protocol
+        .client_pkid_persistent
+}
+
+pub async fn start_inflight_persist_thread(
+    cache_manager: Arc<CacheManager>,
+    stop_send: broadcast::Sender<bool>,
+) {
+    let mut stop_recv = stop_send.subscribe();
+    loop {
+        select! {
+            val = stop_recv.recv() =>{
+                if let Ok(flag) = val {
+                    if flag {
+                        flush_inflight_persist(&cache_manager).await;
+                        info!("{}","Inflight persist thread exited successfully");
+                        break;
+                    }
+                }
+            }
+            _ = sleep(Duration::from_millis(INFLIGHT_PERSIST_INTERVAL_MS)) => {
+                flush_inflight_persist(&cache_manager).await;
+            }
+        }
+    }
+}
+
+// Writes all queued changes with a single request. When the request fails they are queued again,
+// unless a newer change of the same message was queued in the meantime.
+async fn flush_inflight_persist(cache_manager: &Arc<CacheManager>) {
+    let ops = cache_manager.take_inflight_persist();
+    if ops.is_empty() {
+        return;
+    }
+
+    let mut saves = Vec::new();
+    let mut deletes = Vec::new();
+    for (_, op) in ops.iter() {
+        match op {
+            InflightPersistOp::Save(message) => saves.push(message.clone()),
+            InflightPersistOp::Delete {
+                client_id,
+                pkid,
+                inbound,
+            } => deletes.push(InflightMessageKey {
+                client_id: client_id.clone(),
+                pkid: *pkid as u32,
+                inbound: *inbound,
+            }),
+        }
+    }
+
+    let storage = InflightStorage::new(cache_manager.client_pool.clone());
+    if let Err(e) = storage.batch_inflight_message(saves, deletes).await {
+        error!("Failed to persist inflight messages, error message: {}", e);
+        for (key, op) in ops {
+            cache_manager.requeue_inflight_persist(key, op);
+        }
+    }
+}
+
+// Holds one entry of a connection's Receive Maximum quota while a QoS 1/2 publish is inflight.
+// The entry is given back when the slot is dropped, or moved when the client reconnects.
+#[derive(Default)]
+pub struct InflightSlot {
+    connect_id: Option<u64>,
+    counter: Option<Arc<AtomicIsize>>,
+}
+
+impl InflightSlot {
+    pub async fn bind(&mut self, cache_manager: &Arc<CacheManager>, connect_id: u64) {
+        if self.connect_id == Some(connect_id) {
+            return;
+        }
+        self.release();
+
+        loop {
+            let conn = if let Some(conn) = cache_manager.get_connection(connect_id) {
+                conn
+            } else {
+                return;
+            };
+
+            if conn.try_send_qos_message_incr() {
+                self.connect_id = Some(connect_id);
+                self.counter = Some(conn.sender_qos_message.clone());
+                return;
+            }
+            sleep(Duration::from_millis(10)).await;
+        }
+    }
+
+    fn release(&mut self) {
+        if let Some(counter) = self.counter.take() {
+            counter.fetch_add(-1, Ordering::Relaxed);
+        }
+        self.connect_id = None;
+    }
+}
+
+impl Drop for InflightSlot {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use common_base::tools::unique_id;
+    use grpc_clients::pool::ClientPool;
+    use metadata_struct::mqtt::connection::{ConnectionConfig, MQTTConnection};
+    use metadata_struct::mqtt::inflight::{MqttInflightMessage, MqttInflightState};
+    use metadata_struct::mqtt::session::MqttSession;
+    use tokio::time::timeout;
+
+    use super::{inflight_delete, inflight_list, inflight_save, InflightPersistOp, InflightSlot};
+    use crate::handler::cache::CacheManager;
+
+    fn build_cache_manager(
+        client_id: &str,
+        connect_id: u64,
+        receive_maximum: u16,
+    ) -> Arc<CacheManager> {
+        let client_pool = Arc::new(ClientPool::new(1));
+        let cache_manager = Arc::new(CacheManager::new(client_pool, "test".to_string()));
+        let session = MqttSession::new(client_id.to_owned(), 60, false, None);
+        cache_manager.add_session(client_id.to_owned(), session);
+
+        let config = ConnectionConfig {
+            connect_id,
+            client_id: client_id.to_owned(),
+            receive_maximum,
+            max_packet_size: 100,
+            topic_alias_max: 100,
+            request_problem_info: 0,
+            keep_alive: 60,
+            source_ip_addr: "127.0.0.1".to_string(),
+        };
+        cache_manager.add_connection(connect_id, MQTTConnection::new(config));
+        cache_manager
+    }
+
+    #[tokio::test]
+    async fn inflight_memory_test() {
+        let client_id = unique_id();
+        let cache_manager = build_cache_manager(&client_id, 1, 10);
+
+        for pkid in [1, 2] {
+            let message = MqttInflightMessage {
+                client_id: client_id.clone(),
+                pkid,
+                state: MqttInflightState::WaitPubAck,
+                group_id: "group".to_string(),
+                topic_id: "topic".to_string(),
+                offset: pkid as u64,
+                create_time: 0,
+            };
+            inflight_save(&cache_manager, message);
+        }
+        assert_eq!(
+            inflight_list(&cache_manager, &client_id)
+                .await
+                .unwrap()
+                .len(),
+            2
+        );
+
+        inflight_delete(&cache_manager, &client_id, 1);
+        assert!(cache_manager.take_inflight_persist().is_empty());
+        let res = inflight_list(&cache_manager, &client_id).await.unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].pkid, 2);
+
+        cache_manager.remove_session(&client_id);
+        assert!(inflight_list(&cache_manager, &client_id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn inflight_persist_queue_test() {
+        let client_id = unique_id();
+        let cache_manager = build_cache_manager(&client_id, 1, 10);
+        let mut cluster_info = cache_manager.get_cluster_info();
+        cluster_info.protocol.client_pkid_persistent = true;
+        cache_manager.set_cluster_info(cluster_info);
+
+        let message = MqttInflightMessage {
+            client_id: client_id.clone(),
+            pkid: 1,
+            state: MqttInflightState::WaitPubRec,
+            group_id: "group".to_string(),
+            topic_id: "topic".to_string(),
+            offset: 1,
+            create_time: 0,
+        };
+        inflight_save(&cache_manager, message.clone());
+
+        let mut message2 = message.clone();
+        message2.state = MqttInflightState::WaitPubComp;
+        inflight_save(&cache_manager, message2.clone());
+
+        // The inbound state of the same pkid is a different entry.
+        let mut inbound = message.clone();
+        inbound.state = MqttInflightState::WaitPubRel;
+        cache_manager.add_inflight_persist(InflightPersistOp::Save(inbound.clone()));
+
+        let ops = cache_manager.take_inflight_persist();
+        assert_eq!(ops.len(), 2);
+        assert!(ops
+            .iter()
+            .any(|(_, op)| *op == InflightPersistOp::Save(message2.clone())));
+        assert!(ops
+            .iter()
+            .any(|(_, op)| *op == InflightPersistOp::Save(inbound.clone())));
+        assert!(cache_manager.take_inflight_persist().is_empty());
+
+        // A failed write does not overwrite a change queued in the meantime.
+        inflight_delete(&cache_manager, &client_id, 1);
+        for (key, op) in ops {
+            cache_manager.requeue_inflight_persist(key, op);
+        }
+        let res = cache_manager.get_inflight_persist_by_client(&client_id);
+        assert_eq!(res.len(), 2);
+        assert!(res.iter().any(|(_, op)| *op
+            == InflightPersistOp::Delete {
+                client_id: client_id.clone(),
+                pkid: 1,
+                inbound: false,
+            }));
+
+        cache_manager.remove_session(&client_id);
+        assert!(cache_manager.take_inflight_persist().is_empty());
+    }
+
+    #[tokio::test]
+    async fn inflight_slot_test() {
+        let client_id = unique_id();
+        let cache_manager = build_cache_manager(&client_id, 1, 2);
+        let conn = cache_manager.get_connection(1).unwrap();
+
+        let mut slot1 = InflightSlot::default();
+        slot1.bind(&cache_manager, 1).await;
+        let mut slot2 = InflightSlot::default();
+        slot2.bind(&cache_manager, 1).await;
+        assert_eq!(conn.get_send_qos_message(), 2);
+
+        // The quota is used up, a third slot has to wait until one is released.
+        let mut slot3 = InflightSlot::default();
+        assert!(
+            timeout(Duration::from_millis(100), slot3.bind(&cache_manager, 1))
+                .await
+                .is_err()
+        );
+
+        drop(slot1);
+        assert_eq!(conn.get_send_qos_message(), 1);
+        slot3.bind(&cache_manager, 1).await;
+        assert_eq!(conn.get_send_qos_message(), 2);
+
+        // Binding again to the same connection does not take another entry.
+        slot3.bind(&cache_manager, 1).await;
+        assert_eq!(conn.get_send_qos_message(), 2);
+
+        drop(slot2);
+        drop(slot3);
+        assert_eq!(conn.get_send_qos_message(), 0);
+    }
+}
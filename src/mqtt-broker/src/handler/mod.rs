@@ -13,6 +13,7 @@
 // limitations under the License.
 
 pub mod acl;
+pub mod auto_subscribe;
 pub mod cache;
 pub mod cluster_config;
 pub mod command;
@@ -21,6 +22,7 @@ pub mod constant;
 pub mod error;
 pub mod flow_control;
 pub mod heartbreat;
+pub mod inflight;
 pub mod keep_alive;
 pub mod lastwill;
 pub mod message;
@@ -28,8 +30,10 @@ pub mod mqtt;
 pub mod pkid;
 pub mod response;
 pub mod retain;
+pub mod schema;
 pub mod session;
 pub mod topic;
+pub mod topic_alias;
 mod topic_rewrite;
 pub mod user;
 pub mod validator;
@@ -30,7 +30,8 @@ use protocol::mqtt::common::{
 use storage_adapter::storage::StorageAdapter;
 
 use super::connection::disconnect_connection;
-use super::flow_control::is_flow_control;
+use super::constant::SCHEMA_VALIDATION_ERROR_PROPERTY;
+use super::flow_control::{is_flow_control, is_receive_maximum_exceeded};
 use super::message::build_message_expire;
 use super::retain::try_send_retain_message;
 use crate::handler::cache::{
@@ -38,7 +39,7 @@ use crate::handler::cache::{
 };
 use crate::handler::connection::{build_connection, get_client_id};
 use crate::handler::lastwill::save_last_will_message;
-use crate::handler::pkid::{pkid_delete, pkid_exists, pkid_save};
+use crate::handler::pkid::{pkid_delete, pkid_exists, pkid_recover, pkid_save};
 use crate::handler::response::{
     response_packet_mqtt_connect_fail, response_packet_mqtt_connect_success,
     response_packet_mqtt_distinct_by_reason, response_packet_mqtt_ping_resp,
@@ -49,6 +50,7 @@ use crate::handler::response::{
     response_packet_mqtt_unsuback,
 };
 use crate::handler::retain::save_retain_message;
+use crate::handler::schema::{validate_publish_payload, SchemaValidateResult};
 use crate::handler::session::{build_session, save_session};
 use crate::handler::topic::{get_topic_name, try_init_topic};
 use crate::handler::topic_rewrite::{process_sub_topic_rewrite, process_unsub_topic_rewrite};
@@ -59,6 +61,7 @@ use crate::observability::system_topic::event::{
     st_report_connected_event, st_report_disconnected_event, st_report_subscribed_event,
     st_report_unsubscribed_event,
 };
+use crate::rule_engine::try_apply_rules;
 use crate::security::AuthDriver;
 use crate::server::connection_manager::ConnectionManager;
 use crate::storage::message::MessageStorage;
@@ -206,6 +209,18 @@ where
             }
         }
 
+        // A resumed session keeps the QoS 2 messages the client has not released yet.
+        if !new_session {
+            if let Err(e) = pkid_recover(&self.cache_manager, &client_id).await {
+                return response_packet_mqtt_connect_fail(
+                    &self.protocol,
+                    ConnectReturnCode::UnspecifiedError,
+                    &connect_properties,
+                    Some(e.to_string()),
+                );
+            }
+        }
+
         match save_last_will_message(
             client_id.clone(),
             &last_will,
@@ -249,6 +264,8 @@ where
         )
         .await;
 
+        // The auto subscriptions are made once the CONNACK has been written, see
+        // auto_subscribe_after_connack
         response_packet_mqtt_connect_success(
             &self.protocol,
             &cluster,
@@ -278,6 +295,16 @@ where
 
         if is_flow_control(&self.protocol, publish.qos) {
             connection.recv_qos_message_incr();
+
+            // The client sent more QoS 1/2 messages than the Receive Maximum in our CONNACK allows.
+            let receive_max = self.cache_manager.get_cluster_info().protocol.receive_max;
+            if is_receive_maximum_exceeded(&connection, receive_max) {
+                connection.recv_qos_message_decr();
+                return Some(response_packet_mqtt_distinct_by_reason(
+                    &self.protocol,
+                    Some(DisconnectReasonCode::ReceiveMaximumExceeded),
+                ));
+            }
         }
 
         if let Some(pkg) = publish_validator(
@@ -358,6 +385,45 @@ where
             }
         }
 
+        let publish_properties =
+            match validate_publish_payload(&self.cache_manager, &topic_name, &publish.payload) {
+                SchemaValidateResult::Pass => publish_properties,
+                SchemaValidateResult::Tag(reason) => {
+                    let mut properties = publish_properties.unwrap_or_default();
+                    properties
+                        .user_properties
+                        .push((SCHEMA_VALIDATION_ERROR_PROPERTY.to_string(), reason));
+                    Some(properties)
+                }
+                SchemaValidateResult::Reject(reason) => {
+                    if is_flow_control(&self.protocol, publish.qos) {
+                        connection.recv_qos_message_decr();
+                    }
+
+                    if publish.qos == QoS::AtMostOnce {
+                        return None;
+                    }
+
+                    if is_puback {
+                        return Some(response_packet_mqtt_puback_fail(
+                            &self.protocol,
+                            &connection,
+                            publish.pkid,
+                            PubAckReason::PayloadFormatInvalid,
+                            Some(reason),
+                        ));
+                    } else {
+                        return Some(response_packet_mqtt_pubrec_fail(
+                            &self.protocol,
+                            &connection,
+                            publish.pkid,
+                            PubRecReason::PayloadFormatInvalid,
+                            Some(reason),
+                        ));
+                    }
+                }
+            };
+
         let topic = match try_init_topic(
             &topic_name,
             &self.cache_manager,
@@ -474,6 +540,15 @@ where
         };
         let user_properties: Vec<(String, String)> = vec![("offset".to_string(), offset)];
 
+        try_apply_rules(
+            &self.cache_manager,
+            &self.message_storage_adapter,
+            &self.client_pool,
+            &connection,
+            &topic_name,
+            &publish,
+        );
+
         self.cache_manager
             .add_topic_alias(connect_id, &topic_name, &publish_properties);
 
@@ -497,39 +572,8 @@ where
                 ))
             }
             QoS::ExactlyOnce => {
-                match pkid_save(
-                    &self.cache_manager,
-                    &self.client_pool,
-                    &client_id,
-                    publish.pkid,
-                )
-                .await
-                {
-                    Ok(()) => {}
-                    Err(e) => {
-                        if is_flow_control(&self.protocol, publish.qos) {
-                            connection.recv_qos_message_decr();
-                        }
+                pkid_save(&self.cache_manager, &client_id, publish.pkid);
 
-                        if is_puback {
-                            return Some(response_packet_mqtt_puback_fail(
-                                &self.protocol,
-                                &connection,
-                                publish.pkid,
-                                PubAckReason::UnspecifiedError,
-                                Some(e.to_string()),
-                            ));
-                        } else {
-                            return Some(response_packet_mqtt_pubrec_fail(
-                                &self.protocol,
-                                &connection,
-                                publish.pkid,
-                                PubRecReason::UnspecifiedError,
-                                Some(e.to_string()),
-                            ));
-                        }
-                    }
-                }
                 let reason_code = if path_contain_sub(&topic_name) {
                     PubRecReason::Success
                 } else {
@@ -650,57 +694,18 @@ where
 
         let client_id = connection.client_id.clone();
 
-        match pkid_exists(
-            &self.cache_manager,
-            &self.client_pool,
-            &client_id,
-            pub_rel.pkid,
-        )
-        .await
-        {
-            Ok(res) => {
-                if !res {
-                    return response_packet_mqtt_pubcomp_fail(
-                        &self.protocol,
-                        &connection,
-                        pub_rel.pkid,
-                        PubCompReason::PacketIdentifierNotFound,
-                        None,
-                    );
-                }
-            }
-            Err(e) => {
-                return response_packet_mqtt_pubcomp_fail(
-                    &self.protocol,
-                    &connection,
-                    pub_rel.pkid,
-                    PubCompReason::PacketIdentifierNotFound,
-                    Some(e.to_string()),
-                );
-            }
-        };
-
-        match pkid_delete(
-            &self.cache_manager,
-            &self.client_pool,
-            &client_id,
-            pub_rel.pkid,
-        )
-        .await
-        {
-            Ok(()) => {
-                connection.recv_qos_message_decr();
-            }
-            Err(e) => {
-                return response_packet_mqtt_pubcomp_fail(
-                    &self.protocol,
-                    &connection,
-                    pub_rel.pkid,
-                    PubCompReason::PacketIdentifierNotFound,
-                    Some(e.to_string()),
-                );
-            }
+        if !pkid_exists(&self.cache_manager, &client_id, pub_rel.pkid) {
+            return response_packet_mqtt_pubcomp_fail(
+                &self.protocol,
+                &connection,
+                pub_rel.pkid,
+                PubCompReason::PacketIdentifierNotFound,
+                None,
+            );
         }
+
+        pkid_delete(&self.cache_manager, &client_id, pub_rel.pkid);
+        connection.recv_qos_message_decr();
         response_packet_mqtt_pubcomp_success(&self.protocol, pub_rel.pkid)
     }
 
@@ -766,18 +771,31 @@ where
 
         match self
             .subscribe_manager
-            .save_exclusive_subscribe(subscribe.clone())
+            .save_exclusive_subscribe(&client_id, &subscribe)
             .await
         {
-            Ok(None) => {}
-            Ok(Some(code)) => {
-                return response_packet_mqtt_suback(
-                    &self.protocol,
-                    &connection,
-                    subscribe.packet_identifier,
-                    vec![code],
-                    None,
-                );
+            Ok(rejected) => {
+                // Only the filters whose exclusive lock is held by another session fail,
+                // the rest of the packet is subscribed as usual.
+                if !rejected.is_empty() {
+                    for (i, filter) in subscribe.filters.iter().enumerate() {
+                        if let Some(code) = rejected.get(&filter.path) {
+                            return_codes[i] = *code;
+                        }
+                    }
+                    subscribe
+                        .filters
+                        .retain(|filter| !rejected.contains_key(&filter.path));
+                    if subscribe.filters.is_empty() {
+                        return response_packet_mqtt_suback(
+                            &self.protocol,
+                            &connection,
+                            subscribe.packet_identifier,
+                            return_codes,
+                            None,
+                        );
+                    }
+                }
             }
             Err(e) => {
                 return response_packet_mqtt_suback(
@@ -903,7 +921,7 @@ where
 
         match self
             .subscribe_manager
-            .remove_exclusive_subscribe(un_subscribe.clone())
+            .remove_exclusive_subscribe(&connection.client_id, un_subscribe.clone())
             .await
         {
             Ok(_) => {}
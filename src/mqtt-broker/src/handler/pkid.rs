@@ -14,102 +14,57 @@
 
 use std::sync::Arc;
 
-use common_base::config::broker_mqtt::broker_mqtt_conf;
-use common_base::error::common::CommonError;
-use grpc_clients::placement::inner::call::{
-    delete_idempotent_data, exists_idempotent_data, set_idempotent_data,
-};
-use grpc_clients::pool::ClientPool;
-use protocol::placement_center::placement_center_inner::{
-    DeleteIdempotentDataRequest, ExistsIdempotentDataRequest, SetIdempotentDataRequest,
-};
+use common_base::tools::now_second;
+use metadata_struct::mqtt::inflight::{MqttInflightMessage, MqttInflightState};
 
 use super::cache::CacheManager;
-
-pub async fn pkid_save(
-    cache_manager: &Arc<CacheManager>,
-    client_pool: &Arc<ClientPool>,
-    client_id: &str,
-    pkid: u16,
-) -> Result<(), CommonError> {
-    if cache_manager
-        .get_cluster_info()
-        .protocol
-        .client_pkid_persistent
-    {
-        let conf = broker_mqtt_conf();
-        let request = SetIdempotentDataRequest {
-            cluster_name: conf.cluster_name.clone(),
-            producer_id: client_id.to_owned(),
-            seq_num: pkid as u64,
-        };
-        match set_idempotent_data(client_pool, &conf.placement_center, request).await {
-            Ok(_) => {
-                return Ok(());
-            }
-            Err(e) => {
-                return Err(e);
-            }
-        }
-    } else {
-        cache_manager.add_client_pkid(client_id, pkid);
+use super::error::MqttBrokerError;
+use super::inflight::{is_persistent, persisted_inflight_list, InflightPersistOp};
+
+// The pkid of an inbound QoS 2 message is kept in memory until PubRel arrives. When
+// client_pkid_persistent is enabled it is also queued for the Placement Center, and pkid_recover
+// loads it again when the client resumes its session, possibly on another broker.
+pub fn pkid_save(cache_manager: &Arc<CacheManager>, client_id: &str, pkid: u16) {
+    cache_manager.add_client_pkid(client_id, pkid);
+    if is_persistent(cache_manager) {
+        cache_manager.add_inflight_persist(InflightPersistOp::Save(MqttInflightMessage {
+            client_id: client_id.to_owned(),
+            pkid,
+            state: MqttInflightState::WaitPubRel,
+            group_id: "".to_string(),
+            topic_id: "".to_string(),
+            offset: 0,
+            create_time: now_second(),
+        }));
     }
-    Ok(())
 }
 
-pub async fn pkid_exists(
-    cache_manager: &Arc<CacheManager>,
-    client_pool: &Arc<ClientPool>,
-    client_id: &str,
-    pkid: u16,
-) -> Result<bool, CommonError> {
-    if cache_manager
-        .get_cluster_info()
-        .protocol
-        .client_pkid_persistent
-    {
-        let conf = broker_mqtt_conf();
-        let request = ExistsIdempotentDataRequest {
-            cluster_name: conf.cluster_name.clone(),
-            producer_id: client_id.to_owned(),
-            seq_num: pkid as u64,
-        };
-        match exists_idempotent_data(client_pool, &conf.placement_center, request).await {
-            Ok(reply) => Ok(reply.exists),
-            Err(e) => Err(e),
-        }
-    } else {
-        Ok(cache_manager.get_client_pkid(client_id, pkid).is_some())
+pub fn pkid_exists(cache_manager: &Arc<CacheManager>, client_id: &str, pkid: u16) -> bool {
+    cache_manager.get_client_pkid(client_id, pkid).is_some()
+}
+
+pub fn pkid_delete(cache_manager: &Arc<CacheManager>, client_id: &str, pkid: u16) {
+    cache_manager.delete_client_pkid(client_id, pkid);
+    if is_persistent(cache_manager) {
+        cache_manager.add_inflight_persist(InflightPersistOp::Delete {
+            client_id: client_id.to_owned(),
+            pkid,
+            inbound: true,
+        });
     }
 }
 
-pub async fn pkid_delete(
+pub async fn pkid_recover(
     cache_manager: &Arc<CacheManager>,
-    client_pool: &Arc<ClientPool>,
     client_id: &str,
-    pkid: u16,
-) -> Result<(), CommonError> {
-    if cache_manager
-        .get_cluster_info()
-        .protocol
-        .client_pkid_persistent
-    {
-        let conf = broker_mqtt_conf();
-        let request = DeleteIdempotentDataRequest {
-            cluster_name: conf.cluster_name.clone(),
-            producer_id: client_id.to_owned(),
-            seq_num: pkid as u64,
-        };
-        match delete_idempotent_data(client_pool, &conf.placement_center, request).await {
-            Ok(_) => {
-                return Ok(());
-            }
-            Err(e) => {
-                return Err(e);
-            }
+) -> Result<(), MqttBrokerError> {
+    if !is_persistent(cache_manager) {
+        return Ok(());
+    }
+    for message in persisted_inflight_list(cache_manager, client_id).await? {
+        if message.is_inbound() {
+            cache_manager.add_client_pkid(client_id, message.pkid);
         }
-    } else {
-        cache_manager.delete_client_pkid(client_id, pkid);
     }
     Ok(())
 }
@@ -118,73 +73,57 @@ pub async fn pkid_delete(
 mod test {
     use std::sync::Arc;
 
-    use common_base::config::broker_mqtt::init_broker_mqtt_conf_by_path;
     use grpc_clients::pool::ClientPool;
 
     use super::{pkid_delete, pkid_exists, pkid_save};
     use crate::handler::cache::CacheManager;
+    use crate::handler::inflight::InflightPersistOp;
 
     #[tokio::test]
-    #[ignore]
     pub async fn pkid_test() {
-        let path = format!(
-            "{}/../../config/mqtt-server.toml",
-            env!("CARGO_MANIFEST_DIR")
-        );
-        init_broker_mqtt_conf_by_path(&path);
-
         let cluster_name = "test".to_string();
         let client_pool = Arc::new(ClientPool::new(10));
         let cache_manager = Arc::new(CacheManager::new(client_pool.clone(), cluster_name));
         let client_id = "test".to_string();
         let pkid = 15;
-        let flag = pkid_exists(&cache_manager, &client_pool, &client_id, pkid)
-            .await
-            .unwrap();
-        assert!(!flag);
-
-        pkid_save(&cache_manager, &client_pool, &client_id, pkid)
-            .await
-            .unwrap();
-
-        let flag = pkid_exists(&cache_manager, &client_pool, &client_id, pkid)
-            .await
-            .unwrap();
-        assert!(flag);
-
-        pkid_delete(&cache_manager, &client_pool, &client_id, pkid)
-            .await
-            .unwrap();
-
-        let flag = pkid_exists(&cache_manager, &client_pool, &client_id, pkid)
-            .await
-            .unwrap();
-        assert!(!flag);
+        assert!(!pkid_exists(&cache_manager, &client_id, pkid));
+
+        pkid_save(&cache_manager, &client_id, pkid);
+        assert!(pkid_exists(&cache_manager, &client_id, pkid));
+
+        pkid_delete(&cache_manager, &client_id, pkid);
+        assert!(!pkid_exists(&cache_manager, &client_id, pkid));
+        assert!(cache_manager.take_inflight_persist().is_empty());
+
         let mut cluset_info = cache_manager.get_cluster_info();
         cluset_info.protocol.client_pkid_persistent = true;
         cache_manager.set_cluster_info(cluset_info);
 
-        let flag = pkid_exists(&cache_manager, &client_pool, &client_id, pkid)
-            .await
-            .unwrap();
-        assert!(!flag);
+        assert!(!pkid_exists(&cache_manager, &client_id, pkid));
 
-        pkid_save(&cache_manager, &client_pool, &client_id, pkid)
-            .await
-            .unwrap();
-
-        let flag = pkid_exists(&cache_manager, &client_pool, &client_id, pkid)
-            .await
-            .unwrap();
-        assert!(flag);
-
-        pkid_delete(&cache_manager, &client_pool, &client_id, pkid)
-            .await
-            .unwrap();
+        pkid_save(&cache_manager, &client_id, pkid);
+        assert!(pkid_exists(&cache_manager, &client_id, pkid));
+        let ops = cache_manager.get_inflight_persist_by_client(&client_id);
+        assert_eq!(ops.len(), 1);
+        match &ops[0].1 {
+            InflightPersistOp::Save(message) => {
+                assert!(message.is_inbound());
+                assert_eq!(message.pkid, pkid);
+            }
+            op => panic!("unexpected op {:?}", op),
+        }
 
-        let flag = pkid_exists(&cache_manager, &client_pool, &client_id, pkid)
-            .await
-            .unwrap();
-        assert!(!flag);
+        pkid_delete(&cache_manager, &client_id, pkid);
+        assert!(!pkid_exists(&cache_manager, &client_id, pkid));
+        let ops = cache_manager.take_inflight_persist();
+        assert_eq!(ops.len(), 1);
+        assert_eq!(
+            ops[0].1,
+            InflightPersistOp::Delete {
+                client_id: client_id.clone(),
+                pkid,
+                inbound: true,
+            }
+        );
     }
 }
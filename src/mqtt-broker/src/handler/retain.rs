@@ -20,7 +20,7 @@ use grpc_clients::pool::ClientPool;
 use log::info;
 use metadata_struct::mqtt::message::MqttMessage;
 use protocol::mqtt::common::{
-    MqttProtocol, Publish, PublishProperties, QoS, RetainForwardRule, Subscribe,
+    Filter, MqttProtocol, Publish, PublishProperties, QoS, RetainForwardRule, Subscribe,
     SubscribeProperties,
 };
 use tokio::sync::broadcast::{self};
@@ -34,7 +34,9 @@ use crate::observability::metrics::packets::{
 };
 use crate::server::connection_manager::ConnectionManager;
 use crate::storage::topic::TopicStorage;
-use crate::subscribe::sub_common::{get_sub_topic_id_list, min_qos, publish_message_qos0};
+use crate::subscribe::sub_common::{
+    get_sub_topic_id_list, is_queue_sub, is_share_sub, min_qos, publish_message_qos0,
+};
 use crate::subscribe::sub_exclusive::{
     exclusive_publish_message_qos1, exclusive_publish_message_qos2,
 };
@@ -84,12 +86,25 @@ pub async fn try_send_retain_message(
     cache_manager: Arc<CacheManager>,
     connection_manager: Arc<ConnectionManager>,
 ) {
+    // Whether a subscription is new must be decided before the packet handler returns,
+    // a later SUBSCRIBE from the same client would otherwise overwrite the flag.
+    let filters: Vec<Filter> = subscribe
+        .filters
+        .iter()
+        .filter(|filter| is_send_retain(filter, cache_manager.is_new_sub(&client_id, &filter.path)))
+        .cloned()
+        .collect();
+
+    if filters.is_empty() {
+        return;
+    }
+
     tokio::spawn(async move {
         let (stop_sx, _) = broadcast::channel(1);
         if let Err(e) = send_retain_message(
             &protocol,
             &client_id,
-            &subscribe,
+            &filters,
             &subscribe_properties,
             &client_pool,
             &cache_manager,
@@ -107,7 +122,7 @@ pub async fn try_send_retain_message(
 async fn send_retain_message(
     protocol: &MqttProtocol,
     client_id: &String,
-    subscribe: &Subscribe,
+    filters: &[Filter],
     subscribe_properties: &Option<SubscribeProperties>,
     client_pool: &Arc<ClientPool>,
     cache_manager: &Arc<CacheManager>,
@@ -121,16 +136,7 @@ async fn send_retain_message(
         }
     }
 
-    for filter in subscribe.filters.iter() {
-        if filter.retain_forward_rule == RetainForwardRule::Never {
-            return Ok(());
-        }
-
-        let is_new_sub = cache_manager.is_new_sub(client_id, &filter.path);
-        if filter.retain_forward_rule == RetainForwardRule::OnNewSubscribe && !is_new_sub {
-            return Ok(());
-        }
-
+    for filter in filters.iter() {
         let topic_id_list = get_sub_topic_id_list(cache_manager, &filter.path).await;
         let topic_storage = TopicStorage::new(client_pool.clone());
         let cluster = cache_manager.get_cluster_info();
@@ -151,12 +157,6 @@ async fn send_retain_message(
                 continue;
             }
 
-            let retain = if filter.preserve_retain {
-                msg.retain
-            } else {
-                false
-            };
-
             let qos = min_qos(cluster.protocol.max_qos, filter.qos);
 
             let mut user_properties = msg.user_properties;
@@ -186,7 +186,9 @@ async fn send_retain_message(
                 dup: false,
                 qos,
                 pkid,
-                retain,
+                // A retained message sent because of a subscription always has the RETAIN flag set,
+                // Retain As Published only applies to messages forwarded as they are published.
+                retain: true,
                 topic: Bytes::from(topic_name.clone()),
                 payload: msg.payload,
             };
@@ -270,3 +272,59 @@ async fn send_retain_message(
     }
     Ok(())
 }
+
+// Retain Handling of the subscription options: 0 sends the retained messages on every
+// subscribe, 1 only when the subscription did not exist before, 2 never sends them.
+// Retained messages are never sent for shared subscriptions [MQTT-4.8.2].
+fn is_send_retain(filter: &Filter, is_new_sub: bool) -> bool {
+    if is_share_sub(filter.path.clone()) || is_queue_sub(filter.path.clone()) {
+        return false;
+    }
+
+    match filter.retain_forward_rule {
+        RetainForwardRule::OnEverySubscribe => true,
+        RetainForwardRule::OnNewSubscribe => is_new_sub,
+        RetainForwardRule::Never => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use protocol::mqtt::common::{Filter, QoS, RetainForwardRule};
+
+    use super::is_send_retain;
+
+    fn build_filter(path: &str, retain_forward_rule: RetainForwardRule) -> Filter {
+        Filter {
+            path: path.to_string(),
+            qos: QoS::AtLeastOnce,
+            nolocal: false,
+            preserve_retain: false,
+            retain_forward_rule,
+        }
+    }
+
+    #[test]
+    fn is_send_retain_test() {
+        let filter = build_filter("/test/#", RetainForwardRule::OnEverySubscribe);
+        assert!(is_send_retain(&filter, true));
+        assert!(is_send_retain(&filter, false));
+
+        let filter = build_filter("/test/+", RetainForwardRule::OnNewSubscribe);
+        assert!(is_send_retain(&filter, true));
+        assert!(!is_send_retain(&filter, false));
+
+        let filter = build_filter("/test/a", RetainForwardRule::Never);
+        assert!(!is_send_retain(&filter, true));
+        assert!(!is_send_retain(&filter, false));
+
+        let filter = build_filter("$share/g1/test/a", RetainForwardRule::OnEverySubscribe);
+        assert!(!is_send_retain(&filter, true));
+
+        let filter = build_filter("$queue/test/a", RetainForwardRule::OnEverySubscribe);
+        assert!(!is_send_retain(&filter, true));
+
+        let filter = build_filter("$exclusive/test/a", RetainForwardRule::OnEverySubscribe);
+        assert!(is_send_retain(&filter, true));
+    }
+}
@@ -0,0 +1,418 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use apache_avro::{from_avro_datum, Schema as AvroSchema};
+use common_base::tools::now_second;
+use common_base::utils::topic_trie::topic_match_filter;
+use jsonschema::JSONSchema;
+use metadata_struct::mqtt::schema::{
+    MqttSchema, MqttSchemaBinding, MqttSchemaFailureStrategy, MqttSchemaType,
+};
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor};
+use protocol::broker_mqtt::broker_mqtt_admin::{
+    BindSchemaRequest, CreateSchemaRequest, SchemaFailureStrategy, SchemaType,
+};
+use serde_json::Value;
+
+use super::cache::CacheManager;
+use super::error::MqttBrokerError;
+use crate::subscribe::sub_common::sub_path_validator;
+
+#[derive(Debug)]
+enum SchemaValidator {
+    Json(JSONSchema),
+    Protobuf(MessageDescriptor),
+    Avro(AvroSchema),
+}
+
+/// A schema together with its compiled validator, as kept in the broker cache.
+#[derive(Debug)]
+pub struct SchemaRuntime {
+    pub schema: MqttSchema,
+    validator: SchemaValidator,
+}
+
+impl SchemaRuntime {
+    pub fn new(schema: MqttSchema) -> Result<Self, MqttBrokerError> {
+        let validator = match schema.schema_type {
+            MqttSchemaType::Json => {
+                let document = serde_json::from_slice::<Value>(&schema.schema)
+                    .map_err(|e| MqttBrokerError::InvalidSchema(e.to_string()))?;
+                let compiled = JSONSchema::compile(&document)
+                    .map_err(|e| MqttBrokerError::InvalidSchema(e.to_string()))?;
+                SchemaValidator::Json(compiled)
+            }
+            MqttSchemaType::Protobuf => {
+                let pool = DescriptorPool::decode(schema.schema.as_slice())
+                    .map_err(|e| MqttBrokerError::InvalidSchema(e.to_string()))?;
+                let descriptor = pool.get_message_by_name(&schema.message_name).ok_or(
+                    MqttBrokerError::InvalidSchema(format!(
+                        "message {} is not defined in the descriptor set",
+                        schema.message_name
+                    )),
+                )?;
+                SchemaValidator::Protobuf(descriptor)
+            }
+            MqttSchemaType::Avro => {
+                let document = std::str::from_utf8(&schema.schema)
+                    .map_err(|e| MqttBrokerError::InvalidSchema(e.to_string()))?;
+                let parsed = AvroSchema::parse_str(document)
+                    .map_err(|e| MqttBrokerError::InvalidSchema(e.to_string()))?;
+                SchemaValidator::Avro(parsed)
+            }
+        };
+        Ok(SchemaRuntime { schema, validator })
+    }
+
+    /// Checks the payload against the schema, the error describes why the payload does not match.
+    pub fn validate(&self, payload: &[u8]) -> Result<(), String> {
+        match &self.validator {
+            SchemaValidator::Json(compiled) => {
+                let instance = serde_json::from_slice::<Value>(payload)
+                    .map_err(|e| format!("payload is not valid JSON: {}", e))?;
+                if let Err(errors) = compiled.validate(&instance) {
+                    let reasons: Vec<String> = errors.map(|e| e.to_string()).collect();
+                    return Err(reasons.join("; "));
+                }
+                Ok(())
+            }
+            SchemaValidator::Protobuf(descriptor) => {
+                DynamicMessage::decode(descriptor.clone(), payload)
+                    .map(|_| ())
+                    .map_err(|e| e.to_string())
+            }
+            SchemaValidator::Avro(schema) => {
+                let mut reader = payload;
+                from_avro_datum(schema, &mut reader, None).map_err(|e| e.to_string())?;
+                if !reader.is_empty() {
+                    return Err(format!(
+                        "{} trailing bytes after the avro datum",
+                        reader.len()
+                    ));
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum SchemaValidateResult {
+    Pass,
+    Reject(String),
+    Tag(String),
+}
+
+pub fn build_schema(
+    cluster: &str,
+    req: CreateSchemaRequest,
+) -> Result<MqttSchema, MqttBrokerError> {
+    if req.schema_name.is_empty() {
+        return Err(MqttBrokerError::InvalidSchema(
+            "schema name cannot be empty".to_string(),
+        ));
+    }
+    let schema_type = match req.schema_type() {
+        SchemaType::Json => MqttSchemaType::Json,
+        SchemaType::Protobuf => MqttSchemaType::Protobuf,
+        SchemaType::Avro => MqttSchemaType::Avro,
+    };
+    let schema = MqttSchema {
+        cluster: cluster.to_string(),
+        schema_name: req.schema_name,
+        schema_type,
+        schema: req.schema,
+        message_name: req.message_name,
+        description: req.description,
+        create_time: now_second(),
+    };
+
+    // compile once so that a broken schema is rejected before it is stored
+    SchemaRuntime::new(schema.clone())?;
+    Ok(schema)
+}
+
+pub fn build_schema_binding(
+    cluster: &str,
+    req: BindSchemaRequest,
+) -> Result<MqttSchemaBinding, MqttBrokerError> {
+    if req.schema_name.is_empty() {
+        return Err(MqttBrokerError::InvalidSchema(
+            "schema name cannot be empty".to_string(),
+        ));
+    }
+    if req.topic_filter.is_empty() || !sub_path_validator(req.topic_filter.clone()) {
+        return Err(MqttBrokerError::InvalidSchema(format!(
+            "topic filter {}",
+            req.topic_filter
+        )));
+    }
+    let failure_strategy = match req.failure_strategy() {
+        SchemaFailureStrategy::Reject => MqttSchemaFailureStrategy::Reject,
+        SchemaFailureStrategy::Tag => MqttSchemaFailureStrategy::Tag,
+    };
+    Ok(MqttSchemaBinding {
+        cluster: cluster.to_string(),
+        schema_name: req.schema_name,
+        topic_filter: req.topic_filter,
+        failure_strategy,
+    })
+}
+
+// Validate the payload against every schema bound to a filter matching the topic.
+// A failing binding with the Reject strategy wins over one with the Tag strategy.
+pub fn validate_publish_payload(
+    cache_manager: &Arc<CacheManager>,
+    topic_name: &str,
+    payload: &[u8],
+) -> SchemaValidateResult {
+    let mut tag_reason = None;
+    for binding in cache_manager.schema_binding.iter() {
+        if !topic_match_filter(topic_name, &binding.topic_filter) {
+            continue;
+        }
+
+        let runtime = if let Some(runtime) = cache_manager.schema_info.get(&binding.schema_name) {
+            runtime.clone()
+        } else {
+            continue;
+        };
+
+        if let Err(e) = runtime.validate(payload) {
+            let reason = format!("schema {}: {}", binding.schema_name, e);
+            match binding.failure_strategy {
+                MqttSchemaFailureStrategy::Reject => return SchemaValidateResult::Reject(reason),
+                MqttSchemaFailureStrategy::Tag => {
+                    if tag_reason.is_none() {
+                        tag_reason = Some(reason);
+                    }
+                }
+            }
+        }
+    }
+
+    match tag_reason {
+        Some(reason) => SchemaValidateResult::Tag(reason),
+        None => SchemaValidateResult::Pass,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use grpc_clients::pool::ClientPool;
+    use metadata_struct::mqtt::schema::{
+        MqttSchema, MqttSchemaBinding, MqttSchemaFailureStrategy, MqttSchemaType,
+    };
+    use prost_reflect::prost::Message;
+    use prost_reflect::prost_types::field_descriptor_proto::Type;
+    use prost_reflect::prost_types::{
+        DescriptorProto, FieldDescriptorProto, FileDescriptorProto, FileDescriptorSet,
+    };
+    use protocol::broker_mqtt::broker_mqtt_admin::{
+        BindSchemaRequest, CreateSchemaRequest, SchemaType,
+    };
+
+    use super::{
+        build_schema, build_schema_binding, validate_publish_payload, SchemaRuntime,
+        SchemaValidateResult,
+    };
+    use crate::handler::cache::CacheManager;
+
+    fn build_test_schema(
+        schema_name: &str,
+        schema_type: MqttSchemaType,
+        schema: Vec<u8>,
+        message_name: &str,
+    ) -> MqttSchema {
+        MqttSchema {
+            cluster: "test".to_string(),
+            schema_name: schema_name.to_string(),
+            schema_type,
+            schema,
+            message_name: message_name.to_string(),
+            description: "".to_string(),
+            create_time: 0,
+        }
+    }
+
+    fn json_schema() -> Vec<u8> {
+        br#"{"type":"object","properties":{"temp":{"type":"number"}},"required":["temp"]}"#.to_vec()
+    }
+
+    #[test]
+    fn json_schema_validate_test() {
+        let schema = build_test_schema("s1", MqttSchemaType::Json, json_schema(), "");
+        let runtime = SchemaRuntime::new(schema).unwrap();
+
+        assert!(runtime.validate(br#"{"temp":25.5}"#).is_ok());
+        assert!(runtime.validate(br#"{"temp":"hot"}"#).is_err());
+        assert!(runtime.validate(br#"{"humidity":40}"#).is_err());
+        assert!(runtime.validate(b"not json").is_err());
+
+        let schema = build_test_schema("s2", MqttSchemaType::Json, b"{".to_vec(), "");
+        assert!(SchemaRuntime::new(schema).is_err());
+    }
+
+    #[test]
+    fn protobuf_schema_validate_test() {
+        let file = FileDescriptorProto {
+            name: Some("sensor.proto".to_string()),
+            package: Some("sensor".to_string()),
+            message_type: vec![DescriptorProto {
+                name: Some("Reading".to_string()),
+                field: vec![FieldDescriptorProto {
+                    name: Some("temp".to_string()),
+                    number: Some(1),
+                    r#type: Some(Type::Double as i32),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            syntax: Some("proto3".to_string()),
+            ..Default::default()
+        };
+        let descriptor_set = FileDescriptorSet { file: vec![file] }.encode_to_vec();
+
+        let schema = build_test_schema(
+            "s1",
+            MqttSchemaType::Protobuf,
+            descriptor_set.clone(),
+            "sensor.Reading",
+        );
+        let runtime = SchemaRuntime::new(schema).unwrap();
+
+        // field 1, fixed64 wire type, 25.5
+        let mut payload = vec![0x09];
+        payload.extend_from_slice(&25.5f64.to_le_bytes());
+        assert!(runtime.validate(&payload).is_ok());
+        // field 1 sent as a varint does not match the double type
+        assert!(runtime.validate(&[0x08, 0x01]).is_err());
+
+        let schema = build_test_schema(
+            "s2",
+            MqttSchemaType::Protobuf,
+            descriptor_set,
+            "sensor.Unknown",
+        );
+        assert!(SchemaRuntime::new(schema).is_err());
+    }
+
+    #[test]
+    fn avro_schema_validate_test() {
+        let schema = build_test_schema(
+            "s1",
+            MqttSchemaType::Avro,
+            br#"{"type":"record","name":"Reading","fields":[{"name":"temp","type":"double"}]}"#
+                .to_vec(),
+            "",
+        );
+        let runtime = SchemaRuntime::new(schema).unwrap();
+
+        let payload = 25.5f64.to_le_bytes();
+        assert!(runtime.validate(&payload).is_ok());
+        assert!(runtime.validate(&payload[..3]).is_err());
+        let mut payload = payload.to_vec();
+        payload.push(0);
+        assert!(runtime.validate(&payload).is_err());
+    }
+
+    #[test]
+    fn build_schema_test() {
+        let req = CreateSchemaRequest {
+            schema_name: "s1".to_string(),
+            schema_type: SchemaType::Json.into(),
+            schema: json_schema(),
+            ..Default::default()
+        };
+        let schema = build_schema("test", req).unwrap();
+        assert_eq!(schema.schema_type, MqttSchemaType::Json);
+
+        let req = CreateSchemaRequest {
+            schema_name: "".to_string(),
+            schema_type: SchemaType::Json.into(),
+            schema: json_schema(),
+            ..Default::default()
+        };
+        assert!(build_schema("test", req).is_err());
+
+        let req = BindSchemaRequest {
+            schema_name: "s1".to_string(),
+            topic_filter: "sensor/+/temp".to_string(),
+            ..Default::default()
+        };
+        let binding = build_schema_binding("test", req).unwrap();
+        assert_eq!(binding.failure_strategy, MqttSchemaFailureStrategy::Reject);
+
+        let req = BindSchemaRequest {
+            schema_name: "s1".to_string(),
+            topic_filter: "sensor/te+".to_string(),
+            ..Default::default()
+        };
+        assert!(build_schema_binding("test", req).is_err());
+    }
+
+    #[test]
+    fn validate_publish_payload_test() {
+        let client_pool = Arc::new(ClientPool::new(1));
+        let cache_manager = Arc::new(CacheManager::new(client_pool, "test".to_string()));
+        cache_manager.add_schema(build_test_schema(
+            "s1",
+            MqttSchemaType::Json,
+            json_schema(),
+            "",
+        ));
+        cache_manager.add_schema_binding(MqttSchemaBinding {
+            cluster: "test".to_string(),
+            schema_name: "s1".to_string(),
+            topic_filter: "sensor/#".to_string(),
+            failure_strategy: MqttSchemaFailureStrategy::Tag,
+        });
+
+        let invalid = br#"{"temp":"hot"}"#;
+        assert_eq!(
+            validate_publish_payload(&cache_manager, "device/1", invalid),
+            SchemaValidateResult::Pass
+        );
+        assert_eq!(
+            validate_publish_payload(&cache_manager, "sensor/1", br#"{"temp":1}"#),
+            SchemaValidateResult::Pass
+        );
+        assert!(matches!(
+            validate_publish_payload(&cache_manager, "sensor/1", invalid),
+            SchemaValidateResult::Tag(_)
+        ));
+
+        cache_manager.add_schema_binding(MqttSchemaBinding {
+            cluster: "test".to_string(),
+            schema_name: "s1".to_string(),
+            topic_filter: "sensor/+".to_string(),
+            failure_strategy: MqttSchemaFailureStrategy::Reject,
+        });
+        assert!(matches!(
+            validate_publish_payload(&cache_manager, "sensor/1", invalid),
+            SchemaValidateResult::Reject(_)
+        ));
+
+        cache_manager.delete_schema_binding("s1", "sensor/+");
+        cache_manager.delete_schema_binding("s1", "sensor/#");
+        assert_eq!(
+            validate_publish_payload(&cache_manager, "sensor/1", invalid),
+            SchemaValidateResult::Pass
+        );
+    }
+}
@@ -0,0 +1,139 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::{BTreeMap, HashMap};
+
+// Topic aliases the broker assigned to the topics it publishes to a connection. The number of
+// aliases is bounded by the Topic Alias Maximum the client sent in CONNECT, once they are all in
+// use the least recently used alias is handed over to the new topic.
+#[derive(Clone, Debug, Default)]
+pub struct OutboundTopicAlias {
+    alias_max: u16,
+    // The highest alias handed out so far
+    last_alias: u16,
+    // Aliases released by remove, handed out again before a new one
+    free_aliases: Vec<u16>,
+    // Increases on every use, orders the topics from least to most recently used
+    seq: u64,
+    // (topic_name, (alias, seq))
+    topics: HashMap<String, (u16, u64)>,
+    // (seq, topic_name)
+    lru: BTreeMap<u64, String>,
+}
+
+impl OutboundTopicAlias {
+    pub fn new(alias_max: u16) -> Self {
+        OutboundTopicAlias {
+            alias_max,
+            ..Default::default()
+        }
+    }
+
+    // Returns the alias to send the topic with, and whether the mapping is new so the
+    // Publish has to carry the topic name for the client to learn it.
+    pub fn assign(&mut self, topic_name: &str) -> Option<(u16, bool)> {
+        if self.alias_max == 0 || topic_name.is_empty() {
+            return None;
+        }
+
+        self.seq += 1;
+        if let Some((alias, seq)) = self.topics.get_mut(topic_name) {
+            self.lru.remove(seq);
+            *seq = self.seq;
+            self.lru.insert(self.seq, topic_name.to_owned());
+            return Some((*alias, false));
+        }
+
+        let alias = if let Some(alias) = self.free_aliases.pop() {
+            alias
+        } else if self.last_alias < self.alias_max {
+            self.last_alias += 1;
+            self.last_alias
+        } else {
+            let (_, evict_topic) = self.lru.pop_first()?;
+            let (alias, _) = self.topics.remove(&evict_topic)?;
+            alias
+        };
+
+        self.topics.insert(topic_name.to_owned(), (alias, self.seq));
+        self.lru.insert(self.seq, topic_name.to_owned());
+        Some((alias, true))
+    }
+
+    // Forgets a mapping the client never received, e.g. when writing the Publish failed.
+    pub fn remove(&mut self, topic_name: &str) {
+        if let Some((alias, seq)) = self.topics.remove(topic_name) {
+            self.lru.remove(&seq);
+            self.free_aliases.push(alias);
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.topics.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.topics.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OutboundTopicAlias;
+
+    #[test]
+    fn assign_disabled_test() {
+        let mut alias = OutboundTopicAlias::new(0);
+        assert!(alias.assign("/test/topic").is_none());
+        assert!(alias.is_empty());
+
+        let mut alias = OutboundTopicAlias::new(2);
+        assert!(alias.assign("").is_none());
+    }
+
+    #[test]
+    fn assign_reuse_test() {
+        let mut alias = OutboundTopicAlias::new(2);
+        assert_eq!(alias.assign("/test/a"), Some((1, true)));
+        assert_eq!(alias.assign("/test/b"), Some((2, true)));
+        assert_eq!(alias.assign("/test/a"), Some((1, false)));
+        assert_eq!(alias.assign("/test/b"), Some((2, false)));
+        assert_eq!(alias.len(), 2);
+    }
+
+    #[test]
+    fn assign_lru_replace_test() {
+        let mut alias = OutboundTopicAlias::new(2);
+        assert_eq!(alias.assign("/test/a"), Some((1, true)));
+        assert_eq!(alias.assign("/test/b"), Some((2, true)));
+        // /test/b becomes the least recently used topic
+        assert_eq!(alias.assign("/test/a"), Some((1, false)));
+
+        assert_eq!(alias.assign("/test/c"), Some((2, true)));
+        assert_eq!(alias.assign("/test/a"), Some((1, false)));
+        assert_eq!(alias.assign("/test/b"), Some((2, true)));
+        assert_eq!(alias.len(), 2);
+    }
+
+    #[test]
+    fn remove_test() {
+        let mut alias = OutboundTopicAlias::new(3);
+        assert_eq!(alias.assign("/test/a"), Some((1, true)));
+        assert_eq!(alias.assign("/test/b"), Some((2, true)));
+        alias.remove("/test/a");
+        assert_eq!(alias.len(), 1);
+        assert_eq!(alias.assign("/test/c"), Some((1, true)));
+        assert_eq!(alias.assign("/test/a"), Some((3, true)));
+        assert_eq!(alias.assign("/test/b"), Some((2, false)));
+    }
+}
@@ -31,9 +31,7 @@ use tokio_util::codec::FramedWrite;
 
 use super::cache::CacheManager;
 use super::error::MqttBrokerError;
-use super::flow_control::{
-    is_connection_rate_exceeded, is_flow_control, is_subscribe_rate_exceeded,
-};
+use super::flow_control::{is_connection_rate_exceeded, is_subscribe_rate_exceeded};
 use super::pkid::pkid_exists;
 use super::response::{
     response_packet_mqtt_connect_fail, response_packet_mqtt_distinct_by_reason,
@@ -286,43 +284,23 @@ pub fn connect_validator(
 pub async fn publish_validator(
     protocol: &MqttProtocol,
     cache_manager: &Arc<CacheManager>,
-    client_pool: &Arc<ClientPool>,
+    _client_pool: &Arc<ClientPool>,
     connection: &MQTTConnection,
     publish: &Publish,
     publish_properties: &Option<PublishProperties>,
 ) -> Option<MqttPacket> {
     let is_puback = publish.qos != QoS::ExactlyOnce;
 
-    if publish.qos == QoS::ExactlyOnce {
-        match pkid_exists(
-            cache_manager,
-            client_pool,
-            &connection.client_id,
+    if publish.qos == QoS::ExactlyOnce
+        && pkid_exists(cache_manager, &connection.client_id, publish.pkid)
+    {
+        return Some(response_packet_mqtt_pubrec_fail(
+            protocol,
+            connection,
             publish.pkid,
-        )
-        .await
-        {
-            Ok(res) => {
-                if res {
-                    return Some(response_packet_mqtt_pubrec_fail(
-                        protocol,
-                        connection,
-                        publish.pkid,
-                        PubRecReason::PacketIdentifierInUse,
-                        None,
-                    ));
-                }
-            }
-            Err(e) => {
-                return Some(response_packet_mqtt_pubrec_fail(
-                    protocol,
-                    connection,
-                    publish.pkid,
-                    PubRecReason::UnspecifiedError,
-                    Some(e.to_string()),
-                ));
-            }
-        };
+            PubRecReason::PacketIdentifierInUse,
+            None,
+        ));
     }
 
     let cluster = cache_manager.get_cluster_info();
@@ -393,28 +371,6 @@ pub async fn publish_validator(
         }
     }
 
-    if is_flow_control(protocol, publish.qos)
-        && connection.get_recv_qos_message() >= cluster.protocol.receive_max as isize
-    {
-        if is_puback {
-            return Some(response_packet_mqtt_puback_fail(
-                protocol,
-                connection,
-                publish.pkid,
-                PubAckReason::QuotaExceeded,
-                None,
-            ));
-        } else {
-            return Some(response_packet_mqtt_pubrec_fail(
-                protocol,
-                connection,
-                publish.pkid,
-                PubRecReason::QuotaExceeded,
-                None,
-            ));
-        }
-    }
-
     if let Some(properties) = publish_properties {
         if let Some(alias) = properties.topic_alias {
             let cluster = cache_manager.get_cluster_info();
@@ -523,37 +479,18 @@ pub async fn subscribe_validator(
 pub async fn un_subscribe_validator(
     client_id: &str,
     cache_manager: &Arc<CacheManager>,
-    client_pool: &Arc<ClientPool>,
+    _client_pool: &Arc<ClientPool>,
     connection: &MQTTConnection,
     un_subscribe: &Unsubscribe,
 ) -> Option<MqttPacket> {
-    match pkid_exists(
-        cache_manager,
-        client_pool,
-        &connection.client_id,
-        un_subscribe.pkid,
-    )
-    .await
-    {
-        Ok(res) => {
-            if res {
-                return Some(response_packet_mqtt_unsuback(
-                    connection,
-                    un_subscribe.pkid,
-                    vec![UnsubAckReason::PacketIdentifierInUse],
-                    None,
-                ));
-            }
-        }
-        Err(e) => {
-            return Some(response_packet_mqtt_unsuback(
-                connection,
-                un_subscribe.pkid,
-                vec![UnsubAckReason::UnspecifiedError],
-                Some(e.to_string()),
-            ));
-        }
-    };
+    if pkid_exists(cache_manager, &connection.client_id, un_subscribe.pkid) {
+        return Some(response_packet_mqtt_unsuback(
+            connection,
+            un_subscribe.pkid,
+            vec![UnsubAckReason::PacketIdentifierInUse],
+            None,
+        ));
+    }
 
     let mut return_codes: Vec<UnsubAckReason> = Vec::new();
     for filter in un_subscribe.filters.clone() {
@@ -23,6 +23,7 @@ use grpc_clients::pool::ClientPool;
 use handler::acl::UpdateAclCache;
 use handler::cache::CacheManager;
 use handler::heartbreat::{register_node, report_heartbeat};
+use handler::inflight::start_inflight_persist_thread;
 use handler::keep_alive::ClientKeepAlive;
 use handler::user::UpdateUserCache;
 use lazy_static::lazy_static;
@@ -55,6 +56,7 @@ lazy_static! {
 
 pub mod handler;
 pub mod observability;
+pub mod rule_engine;
 pub mod security;
 mod server;
 pub mod storage;
@@ -155,6 +157,7 @@ where
         self.start_websocket_server(stop_send.clone());
         self.start_keep_alive_thread(stop_send.clone());
         self.start_cluster_heartbeat_report(stop_send.clone());
+        self.start_inflight_persist_thread(stop_send.clone());
         self.start_update_user_cache_thread(stop_send.clone());
         self.start_update_acl_cache_thread(stop_send.clone());
         self.start_push_server();
@@ -250,6 +253,13 @@ where
         });
     }
 
+    fn start_inflight_persist_thread(&self, stop_send: broadcast::Sender<bool>) {
+        let cache_manager = self.cache_manager.clone();
+        self.runtime.spawn(async move {
+            start_inflight_persist_thread(cache_manager, stop_send).await;
+        });
+    }
+
     fn start_push_server(&self) {
         let subscribe_manager = self.subscribe_manager.clone();
         self.runtime.spawn(async move {
@@ -11,3 +11,24 @@
 // WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
 // See the License for the specific language governing permissions and
 // limitations under the License.
+
+use lazy_static::lazy_static;
+use prometheus::{register_int_gauge_vec, IntGaugeVec};
+
+use crate::handler::constant::METRICS_KEY_TYPE_NAME;
+
+pub const PUBLISH_DROPPED_PACKET_TOO_LARGE: &str = "packet_too_large";
+
+lazy_static! {
+    // Number of publish messages dropped instead of being pushed to the client
+    static ref PUBLISH_MESSAGES_DROPPED: IntGaugeVec = register_int_gauge_vec!(
+        "publish_messages_dropped",
+        "Number of publish messages dropped",
+        &[METRICS_KEY_TYPE_NAME]
+    )
+    .unwrap();
+}
+
+pub fn record_publish_messages_dropped(reason: &str) {
+    PUBLISH_MESSAGES_DROPPED.with_label_values(&[reason]).inc();
+}
@@ -213,11 +213,11 @@ pub async fn st_report_subscribed_event<S>(
         for filter in subscribe.filters.clone() {
             let subopts = SystemTopicSubscribedEventMessageSUbopts {
                 sub_props: HashMap::new(),
-                rh: if filter.preserve_retain { 1 } else { 0 },
-                rap: filter.retain_forward_rule.into(),
+                rh: u8::from(filter.retain_forward_rule.clone()) as u16,
+                rap: if filter.preserve_retain { 1 } else { 0 },
                 qos: filter.qos.into(),
                 nl: if filter.nolocal { 1 } else { 0 },
-                is_new: true,
+                is_new: metadata_cache.is_new_sub(&connection.client_id, &filter.path),
             };
             let event_data = SystemTopicSubscribedEventMessage {
                 username: connection.login_user.clone(),
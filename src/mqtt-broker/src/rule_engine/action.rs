@@ -0,0 +1,171 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use bytes::Bytes;
+use grpc_clients::pool::ClientPool;
+use metadata_struct::acl::mqtt_acl::MqttAclAction;
+use metadata_struct::adapter::record::Record;
+use metadata_struct::mqtt::connection::MQTTConnection;
+use metadata_struct::mqtt::message::MqttMessage;
+use metadata_struct::mqtt::rule::MqttRuleAction;
+use protocol::mqtt::common::Publish;
+use serde_json::Value;
+use storage_adapter::storage::StorageAdapter;
+
+use super::expr::value_to_string;
+use crate::handler::cache::CacheManager;
+use crate::handler::error::MqttBrokerError;
+use crate::handler::message::build_message_expire;
+use crate::handler::retain::save_retain_message;
+use crate::handler::topic::try_init_topic;
+use crate::security::acl::is_allow_acl;
+use crate::storage::message::MessageStorage;
+
+pub async fn execute_action<S>(
+    cache_manager: &Arc<CacheManager>,
+    message_storage_adapter: &Arc<S>,
+    client_pool: &Arc<ClientPool>,
+    connection: &MQTTConnection,
+    action: &MqttRuleAction,
+    output: &Value,
+) -> Result<(), MqttBrokerError>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    let payload = serde_json::to_vec(output)?;
+    let client_id = connection.client_id.as_str();
+    match action {
+        MqttRuleAction::Republish { topic, qos, retain } => {
+            let topic_name = render_republish_topic(topic, output)?;
+            // The message is published on behalf of the client, the same ACL applies
+            if !is_allow_acl(
+                cache_manager,
+                connection,
+                &topic_name,
+                MqttAclAction::Publish,
+                *retain,
+                *qos,
+            ) {
+                return Err(MqttBrokerError::RulePublishNotAuthorized(
+                    client_id.to_string(),
+                    topic_name,
+                ));
+            }
+            let publish = Publish {
+                dup: false,
+                qos: *qos,
+                pkid: 0,
+                retain: *retain,
+                topic: Bytes::from(topic_name.clone()),
+                payload: Bytes::from(payload),
+            };
+
+            save_retain_message(
+                cache_manager,
+                client_pool,
+                topic_name.clone(),
+                client_id,
+                &publish,
+                &None,
+            )
+            .await?;
+
+            let topic = try_init_topic(
+                &topic_name,
+                cache_manager,
+                message_storage_adapter,
+                client_pool,
+            )
+            .await?;
+
+            let message_expire = build_message_expire(cache_manager, &None);
+            if let Some(record) =
+                MqttMessage::build_record(client_id, &publish, &None, message_expire)
+            {
+                let message_storage = MessageStorage::new(message_storage_adapter.clone());
+                message_storage
+                    .append_topic_message(&topic.topic_id, vec![record])
+                    .await?;
+            }
+        }
+        MqttRuleAction::Journal {
+            namespace,
+            shard_name,
+        } => {
+            message_storage_adapter
+                .write(
+                    namespace.clone(),
+                    shard_name.clone(),
+                    Record::build_byte(payload),
+                )
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Replace the `${field}` placeholders of the republish topic with the fields of the rule output.
+pub fn render_republish_topic(template: &str, output: &Value) -> Result<String, MqttBrokerError> {
+    let mut topic = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find("${") {
+        let Some(len) = rest[start..].find('}') else {
+            break;
+        };
+        topic.push_str(&rest[..start]);
+        let field = &rest[start + 2..start + len];
+        let value = output.get(field).map(value_to_string).unwrap_or_default();
+        topic.push_str(&value);
+        rest = &rest[start + len + 1..];
+    }
+    topic.push_str(rest);
+
+    if topic.is_empty() || topic.contains('+') || topic.contains('#') {
+        return Err(MqttBrokerError::InvalidRuleAction(format!(
+            "republish topic {} rendered to invalid topic name \"{}\"",
+            template, topic
+        )));
+    }
+    Ok(topic)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn render_republish_topic_test() {
+        let output = json!({"clientid": "device-1", "temp": 31, "payload.unit": "C"});
+
+        let topic = render_republish_topic("alarm/${clientid}/${temp}", &output).unwrap();
+        assert_eq!(topic, "alarm/device-1/31");
+
+        let topic = render_republish_topic("unit/${payload.unit}", &output).unwrap();
+        assert_eq!(topic, "unit/C");
+
+        // Missing fields render as empty levels
+        let topic = render_republish_topic("alarm/${missing}/x", &output).unwrap();
+        assert_eq!(topic, "alarm//x");
+
+        let topic = render_republish_topic("alarm/${clientid", &output).unwrap();
+        assert_eq!(topic, "alarm/${clientid");
+
+        let output = json!({"clientid": "#"});
+        assert!(render_republish_topic("alarm/${clientid}", &output).is_err());
+    }
+}
@@ -0,0 +1,300 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::cmp::Ordering;
+
+use serde_json::{Map, Number, Value};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BinaryOp {
+    And,
+    Or,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Literal(Value),
+    // A path into the rule context, e.g. `payload.sensor.temp`
+    Field(Vec<String>),
+    Not(Box<Expr>),
+    Neg(Box<Expr>),
+    Binary(BinaryOp, Box<Expr>, Box<Expr>),
+    Function(String, Vec<Expr>),
+}
+
+impl Expr {
+    pub fn eval(&self, context: &Value) -> Value {
+        match self {
+            Expr::Literal(value) => value.clone(),
+            Expr::Field(path) => {
+                let mut current = context;
+                for key in path {
+                    match current.get(key) {
+                        Some(value) => current = value,
+                        None => return Value::Null,
+                    }
+                }
+                current.clone()
+            }
+            Expr::Not(expr) => Value::Bool(!is_true(&expr.eval(context))),
+            Expr::Neg(expr) => match expr.eval(context).as_f64() {
+                Some(num) => number_value(-num),
+                None => Value::Null,
+            },
+            Expr::Binary(op, left, right) => match op {
+                BinaryOp::And => {
+                    Value::Bool(is_true(&left.eval(context)) && is_true(&right.eval(context)))
+                }
+                BinaryOp::Or => {
+                    Value::Bool(is_true(&left.eval(context)) || is_true(&right.eval(context)))
+                }
+                _ => eval_binary(op, &left.eval(context), &right.eval(context)),
+            },
+            Expr::Function(name, args) => {
+                let args: Vec<Value> = args.iter().map(|arg| arg.eval(context)).collect();
+                call_function(name, &args)
+            }
+        }
+    }
+}
+
+/// Only a boolean `true` satisfies a condition, NULL and every other value do not.
+pub fn is_true(value: &Value) -> bool {
+    matches!(value, Value::Bool(true))
+}
+
+/// Check that `name` is a supported function taking `arg_num` arguments.
+pub fn check_function(name: &str, arg_num: usize) -> Result<(), String> {
+    let valid = match name {
+        "lower" | "upper" | "trim" | "length" | "abs" | "round" | "floor" | "ceil" | "str" => {
+            arg_num == 1
+        }
+        "concat" => arg_num >= 1,
+        _ => return Err(format!("unknown function {}", name)),
+    };
+    if !valid {
+        return Err(format!(
+            "function {} does not take {} arguments",
+            name, arg_num
+        ));
+    }
+    Ok(())
+}
+
+fn call_function(name: &str, args: &[Value]) -> Value {
+    match name {
+        "lower" => map_str(&args[0], |s| s.to_lowercase()),
+        "upper" => map_str(&args[0], |s| s.to_uppercase()),
+        "trim" => map_str(&args[0], |s| s.trim().to_string()),
+        "length" => match &args[0] {
+            Value::String(s) => Value::from(s.chars().count()),
+            Value::Array(list) => Value::from(list.len()),
+            Value::Object(map) => Value::from(map.len()),
+            _ => Value::Null,
+        },
+        "abs" => map_num(&args[0], f64::abs),
+        "round" => map_num(&args[0], f64::round),
+        "floor" => map_num(&args[0], f64::floor),
+        "ceil" => map_num(&args[0], f64::ceil),
+        "str" => Value::String(value_to_string(&args[0])),
+        "concat" => Value::String(args.iter().map(value_to_string).collect()),
+        _ => Value::Null,
+    }
+}
+
+/// The text form of a value, strings are not quoted and NULL is empty.
+pub fn value_to_string(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn map_str(value: &Value, f: impl Fn(&str) -> String) -> Value {
+    match value {
+        Value::String(s) => Value::String(f(s)),
+        _ => Value::Null,
+    }
+}
+
+fn map_num(value: &Value, f: impl Fn(f64) -> f64) -> Value {
+    match value.as_f64() {
+        Some(num) => number_value(f(num)),
+        None => Value::Null,
+    }
+}
+
+// Keep integral results as integers so that `1 + 1` is encoded as `2` rather than `2.0`
+fn number_value(num: f64) -> Value {
+    if num.fract() == 0.0 && num.abs() < i64::MAX as f64 {
+        return Value::from(num as i64);
+    }
+    match Number::from_f64(num) {
+        Some(num) => Value::Number(num),
+        None => Value::Null,
+    }
+}
+
+fn eval_binary(op: &BinaryOp, left: &Value, right: &Value) -> Value {
+    match op {
+        BinaryOp::Eq => Value::Bool(values_equal(left, right)),
+        BinaryOp::NotEq => Value::Bool(!values_equal(left, right)),
+        BinaryOp::Lt | BinaryOp::LtEq | BinaryOp::Gt | BinaryOp::GtEq => {
+            let ordering = match compare_values(left, right) {
+                Some(ordering) => ordering,
+                None => return Value::Bool(false),
+            };
+            Value::Bool(match op {
+                BinaryOp::Lt => ordering == Ordering::Less,
+                BinaryOp::LtEq => ordering != Ordering::Greater,
+                BinaryOp::Gt => ordering == Ordering::Greater,
+                _ => ordering != Ordering::Less,
+            })
+        }
+        _ => {
+            let (Some(l), Some(r)) = (left.as_f64(), right.as_f64()) else {
+                return Value::Null;
+            };
+            match op {
+                BinaryOp::Add => number_value(l + r),
+                BinaryOp::Sub => number_value(l - r),
+                BinaryOp::Mul => number_value(l * r),
+                BinaryOp::Div if r != 0.0 => number_value(l / r),
+                BinaryOp::Mod if r != 0.0 => number_value(l % r),
+                _ => Value::Null,
+            }
+        }
+    }
+}
+
+fn values_equal(left: &Value, right: &Value) -> bool {
+    match (left.as_f64(), right.as_f64()) {
+        (Some(l), Some(r)) => l == r,
+        _ => left == right,
+    }
+}
+
+fn compare_values(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) => l.as_f64()?.partial_cmp(&r.as_f64()?),
+        (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+        _ => None,
+    }
+}
+
+/// Build the object the expressions of a rule are evaluated against.
+pub fn build_context(fields: Vec<(&str, Value)>) -> Value {
+    let mut map = Map::new();
+    for (key, value) in fields {
+        map.insert(key.to_string(), value);
+    }
+    Value::Object(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn field(path: &str) -> Expr {
+        Expr::Field(path.split('.').map(|s| s.to_string()).collect())
+    }
+
+    fn binary(op: BinaryOp, left: Expr, right: Expr) -> Expr {
+        Expr::Binary(op, Box::new(left), Box::new(right))
+    }
+
+    #[test]
+    fn eval_test() {
+        let context = json!({
+            "clientid": "c1",
+            "payload": {"temp": 31.5, "unit": "C", "tags": [1, 2]},
+        });
+
+        assert_eq!(field("payload.temp").eval(&context), json!(31.5));
+        assert_eq!(field("payload.missing").eval(&context), Value::Null);
+        assert_eq!(field("clientid.x").eval(&context), Value::Null);
+
+        let expr = binary(
+            BinaryOp::Gt,
+            field("payload.temp"),
+            Expr::Literal(json!(30)),
+        );
+        assert!(is_true(&expr.eval(&context)));
+
+        let expr = binary(
+            BinaryOp::Eq,
+            field("payload.unit"),
+            Expr::Literal(json!("C")),
+        );
+        assert!(is_true(&expr.eval(&context)));
+
+        // Comparing with NULL never matches
+        let expr = binary(
+            BinaryOp::Lt,
+            field("payload.missing"),
+            Expr::Literal(json!(1)),
+        );
+        assert!(!is_true(&expr.eval(&context)));
+
+        let expr = binary(
+            BinaryOp::Mul,
+            Expr::Literal(json!(2)),
+            Expr::Literal(json!(3)),
+        );
+        assert_eq!(expr.eval(&context), json!(6));
+
+        let expr = binary(
+            BinaryOp::Div,
+            Expr::Literal(json!(1)),
+            Expr::Literal(json!(0)),
+        );
+        assert_eq!(expr.eval(&context), Value::Null);
+
+        let expr = Expr::Function(
+            "concat".to_string(),
+            vec![
+                field("clientid"),
+                Expr::Literal(json!("-")),
+                field("payload.temp"),
+            ],
+        );
+        assert_eq!(expr.eval(&context), json!("c1-31.5"));
+
+        let expr = Expr::Function("length".to_string(), vec![field("payload.tags")]);
+        assert_eq!(expr.eval(&context), json!(2));
+    }
+
+    #[test]
+    fn check_function_test() {
+        assert!(check_function("upper", 1).is_ok());
+        assert!(check_function("upper", 2).is_err());
+        assert!(check_function("concat", 3).is_ok());
+        assert!(check_function("concat", 0).is_err());
+        assert!(check_function("unknown", 1).is_err());
+    }
+}
@@ -0,0 +1,311 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use action::execute_action;
+use common_base::tools::{now_mills, now_second};
+use common_base::utils::topic_trie::topic_match_filter;
+use grpc_clients::pool::ClientPool;
+use log::warn;
+use metadata_struct::mqtt::connection::MQTTConnection;
+use metadata_struct::mqtt::rule::{MqttRule, MqttRuleAction};
+use protocol::broker_mqtt::broker_mqtt_admin::{CreateRuleRequest, RuleActionType};
+use protocol::mqtt::common::{qos, Publish};
+use serde_json::Value;
+use sql::{parse_rule_sql, RuleSql};
+use storage_adapter::storage::StorageAdapter;
+
+use crate::handler::cache::CacheManager;
+use crate::handler::error::MqttBrokerError;
+
+pub mod action;
+pub mod expr;
+pub mod sql;
+
+/// A rule together with its parsed SQL, as kept in the broker cache.
+#[derive(Debug, Clone)]
+pub struct RuleRuntime {
+    pub rule: MqttRule,
+    pub sql: RuleSql,
+}
+
+impl RuleRuntime {
+    pub fn new(rule: MqttRule) -> Result<Self, MqttBrokerError> {
+        let sql = parse_rule_sql(&rule.sql)?;
+        Ok(RuleRuntime { rule, sql })
+    }
+
+    pub fn is_match_topic(&self, topic_name: &str) -> bool {
+        self.sql
+            .from
+            .iter()
+            .any(|filter| topic_match_filter(topic_name, filter))
+    }
+}
+
+pub fn build_rule(cluster: &str, req: CreateRuleRequest) -> Result<MqttRule, MqttBrokerError> {
+    if req.rule_name.is_empty() {
+        return Err(MqttBrokerError::InvalidRuleAction(
+            "rule name cannot be empty".to_string(),
+        ));
+    }
+    parse_rule_sql(&req.sql)?;
+
+    if req.actions.is_empty() {
+        return Err(MqttBrokerError::InvalidRuleAction(
+            "a rule needs at least one action".to_string(),
+        ));
+    }
+
+    let mut actions = Vec::with_capacity(req.actions.len());
+    for action in req.actions.iter() {
+        let action = match action.action_type() {
+            RuleActionType::Republish => {
+                if action.topic.is_empty() {
+                    return Err(MqttBrokerError::InvalidRuleAction(
+                        "republish topic cannot be empty".to_string(),
+                    ));
+                }
+                let qos = u8::try_from(action.qos).ok().and_then(qos).ok_or(
+                    MqttBrokerError::InvalidRuleAction(format!("republish qos {}", action.qos)),
+                )?;
+                MqttRuleAction::Republish {
+                    topic: action.topic.clone(),
+                    qos,
+                    retain: action.retain,
+                }
+            }
+            RuleActionType::Journal => {
+                if action.namespace.is_empty() || action.shard_name.is_empty() {
+                    return Err(MqttBrokerError::InvalidRuleAction(
+                        "journal namespace and shard name cannot be empty".to_string(),
+                    ));
+                }
+                MqttRuleAction::Journal {
+                    namespace: action.namespace.clone(),
+                    shard_name: action.shard_name.clone(),
+                }
+            }
+        };
+        actions.push(action);
+    }
+
+    Ok(MqttRule {
+        cluster: cluster.to_string(),
+        rule_name: req.rule_name,
+        sql: req.sql,
+        actions,
+        description: req.description,
+        create_time: now_second(),
+    })
+}
+
+/// The fields a rule can select and filter on.
+pub fn build_rule_context(
+    client_id: &str,
+    username: &str,
+    topic_name: &str,
+    publish: &Publish,
+    timestamp: u128,
+) -> Value {
+    // Payloads that are not JSON are exposed as a string
+    let payload = match serde_json::from_slice::<Value>(&publish.payload) {
+        Ok(value) => value,
+        Err(_) => Value::String(String::from_utf8_lossy(&publish.payload).to_string()),
+    };
+    expr::build_context(vec![
+        ("clientid", Value::from(client_id)),
+        ("username", Value::from(username)),
+        ("topic", Value::from(topic_name)),
+        ("qos", Value::from(u8::from(publish.qos))),
+        ("retain", Value::from(publish.retain)),
+        ("timestamp", Value::from(timestamp as u64)),
+        ("payload", payload),
+    ])
+}
+
+/// Run the rules whose FROM clause matches the topic of a message a client has published.
+/// Messages produced by the republish action do not go through the rules again.
+pub fn try_apply_rules<S>(
+    cache_manager: &Arc<CacheManager>,
+    message_storage_adapter: &Arc<S>,
+    client_pool: &Arc<ClientPool>,
+    connection: &MQTTConnection,
+    topic_name: &str,
+    publish: &Publish,
+) where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    if cache_manager.rule_info.is_empty() {
+        return;
+    }
+
+    let rules: Vec<Arc<RuleRuntime>> = cache_manager
+        .rule_info
+        .iter()
+        .filter(|rule| rule.is_match_topic(topic_name))
+        .map(|rule| rule.value().clone())
+        .collect();
+    if rules.is_empty() {
+        return;
+    }
+
+    let context = build_rule_context(
+        &connection.client_id,
+        &connection.login_user,
+        topic_name,
+        publish,
+        now_mills(),
+    );
+    let connection = connection.clone();
+    let cache_manager = cache_manager.clone();
+    let message_storage_adapter = message_storage_adapter.clone();
+    let client_pool = client_pool.clone();
+    tokio::spawn(async move {
+        for rule in rules {
+            let Some(output) = rule.sql.select(&context) else {
+                continue;
+            };
+            for action in rule.rule.actions.iter() {
+                if let Err(e) = execute_action(
+                    &cache_manager,
+                    &message_storage_adapter,
+                    &client_pool,
+                    &connection,
+                    action,
+                    &output,
+                )
+                .await
+                {
+                    warn!(
+                        "Rule {} failed to execute action {:?}, error message: {}",
+                        rule.rule.rule_name, action, e
+                    );
+                }
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use protocol::broker_mqtt::broker_mqtt_admin::RuleAction;
+    use protocol::mqtt::common::QoS;
+    use serde_json::json;
+
+    use super::*;
+
+    fn republish_action(topic: &str, qos: u32) -> RuleAction {
+        RuleAction {
+            action_type: RuleActionType::Republish.into(),
+            topic: topic.to_string(),
+            qos,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn build_rule_test() {
+        let req = CreateRuleRequest {
+            rule_name: "r1".to_string(),
+            sql: "SELECT * FROM \"t/#\"".to_string(),
+            actions: vec![
+                republish_action("r/${clientid}", 1),
+                RuleAction {
+                    action_type: RuleActionType::Journal.into(),
+                    namespace: "ns".to_string(),
+                    shard_name: "s1".to_string(),
+                    ..Default::default()
+                },
+            ],
+            description: String::new(),
+        };
+        let rule = build_rule("c1", req.clone()).unwrap();
+        assert_eq!(rule.cluster, "c1");
+        assert_eq!(
+            rule.actions,
+            vec![
+                MqttRuleAction::Republish {
+                    topic: "r/${clientid}".to_string(),
+                    qos: QoS::AtLeastOnce,
+                    retain: false,
+                },
+                MqttRuleAction::Journal {
+                    namespace: "ns".to_string(),
+                    shard_name: "s1".to_string(),
+                },
+            ]
+        );
+
+        let mut bad = req.clone();
+        bad.sql = "SELECT * FROM t".to_string();
+        assert!(build_rule("c1", bad).is_err());
+
+        let mut bad = req.clone();
+        bad.actions = vec![republish_action("r/1", 3)];
+        assert!(build_rule("c1", bad).is_err());
+
+        let mut bad = req.clone();
+        bad.actions = vec![RuleAction {
+            action_type: RuleActionType::Journal.into(),
+            ..Default::default()
+        }];
+        assert!(build_rule("c1", bad).is_err());
+
+        let mut bad = req;
+        bad.actions = Vec::new();
+        assert!(build_rule("c1", bad).is_err());
+    }
+
+    #[test]
+    fn rule_runtime_test() {
+        let rule = MqttRule {
+            cluster: "c1".to_string(),
+            rule_name: "r1".to_string(),
+            sql: "SELECT payload.temp AS temp, clientid FROM \"sensor/+/data\" WHERE payload.temp > 30"
+                .to_string(),
+            actions: Vec::new(),
+            description: String::new(),
+            create_time: 0,
+        };
+        let runtime = RuleRuntime::new(rule).unwrap();
+        assert!(runtime.is_match_topic("sensor/1/data"));
+        assert!(!runtime.is_match_topic("sensor/1/status"));
+
+        let publish = Publish {
+            dup: false,
+            qos: QoS::AtLeastOnce,
+            pkid: 1,
+            retain: false,
+            topic: "sensor/1/data".into(),
+            payload: r#"{"temp": 35}"#.into(),
+        };
+        let context = build_rule_context("c1", "u1", "sensor/1/data", &publish, 1000);
+        assert_eq!(context["qos"], json!(1));
+        assert_eq!(context["username"], json!("u1"));
+        assert_eq!(
+            runtime.sql.select(&context),
+            Some(json!({"temp": 35, "clientid": "c1"}))
+        );
+
+        let publish = Publish {
+            payload: "not json".into(),
+            ..publish
+        };
+        let context = build_rule_context("c1", "u1", "sensor/1/data", &publish, 1000);
+        assert_eq!(context["payload"], json!("not json"));
+        assert_eq!(runtime.sql.select(&context), None);
+    }
+}
@@ -0,0 +1,553 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use serde_json::{Map, Value};
+
+use super::expr::{check_function, is_true, BinaryOp, Expr};
+use crate::handler::error::MqttBrokerError;
+
+const KEYWORDS: [&str; 10] = [
+    "SELECT", "FROM", "WHERE", "AS", "AND", "OR", "NOT", "TRUE", "FALSE", "NULL",
+];
+
+/// A parsed rule statement: `SELECT <fields> FROM "<topic filter>"[, ...] [WHERE <condition>]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleSql {
+    pub fields: Vec<SelectField>,
+    pub from: Vec<String>,
+    pub condition: Option<Expr>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum SelectField {
+    // `*`, all the fields of the rule context
+    All,
+    Expr { expr: Expr, alias: String },
+}
+
+impl RuleSql {
+    /// Evaluate the statement against `context`, returns `None` when the WHERE condition does not hold.
+    pub fn select(&self, context: &Value) -> Option<Value> {
+        if let Some(condition) = &self.condition {
+            if !is_true(&condition.eval(context)) {
+                return None;
+            }
+        }
+
+        let mut output = Map::new();
+        for field in self.fields.iter() {
+            match field {
+                SelectField::All => {
+                    if let Value::Object(map) = context {
+                        output.extend(map.clone());
+                    }
+                }
+                SelectField::Expr { expr, alias } => {
+                    output.insert(alias.clone(), expr.eval(context));
+                }
+            }
+        }
+        Some(Value::Object(output))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Number(f64),
+    Symbol(&'static str),
+}
+
+#[derive(Debug, Clone)]
+struct SpannedToken {
+    token: Token,
+    start: usize,
+    end: usize,
+}
+
+const SYMBOLS: [&str; 16] = [
+    "<=", ">=", "!=", "<>", "(", ")", ",", "*", ".", "+", "-", "/", "%", "=", "<", ">",
+];
+
+fn tokenize(sql: &str) -> Result<Vec<SpannedToken>, MqttBrokerError> {
+    let chars: Vec<(usize, char)> = sql.char_indices().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let (start, c) = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+
+        let end_of = |i: usize| chars.get(i).map(|(pos, _)| *pos).unwrap_or(sql.len());
+
+        if c.is_ascii_alphabetic() || c == '_' {
+            let mut j = i;
+            while j < chars.len() && (chars[j].1.is_ascii_alphanumeric() || chars[j].1 == '_') {
+                j += 1;
+            }
+            let end = end_of(j);
+            tokens.push(SpannedToken {
+                token: Token::Ident(sql[start..end].to_string()),
+                start,
+                end,
+            });
+            i = j;
+        } else if c.is_ascii_digit() {
+            let mut j = i;
+            while j < chars.len() && chars[j].1.is_ascii_digit() {
+                j += 1;
+            }
+            if j + 1 < chars.len() && chars[j].1 == '.' && chars[j + 1].1.is_ascii_digit() {
+                j += 1;
+                while j < chars.len() && chars[j].1.is_ascii_digit() {
+                    j += 1;
+                }
+            }
+            let end = end_of(j);
+            let num = sql[start..end]
+                .parse::<f64>()
+                .map_err(|e| MqttBrokerError::InvalidRuleSql(e.to_string()))?;
+            tokens.push(SpannedToken {
+                token: Token::Number(num),
+                start,
+                end,
+            });
+            i = j;
+        } else if c == '\'' || c == '"' {
+            let mut j = i + 1;
+            let mut value = String::new();
+            loop {
+                let Some((_, ch)) = chars.get(j) else {
+                    return Err(MqttBrokerError::InvalidRuleSql(format!(
+                        "unterminated string at position {}",
+                        start
+                    )));
+                };
+                if *ch == c {
+                    break;
+                }
+                if *ch == '\\' {
+                    if let Some((_, escaped)) = chars.get(j + 1) {
+                        value.push(*escaped);
+                        j += 2;
+                        continue;
+                    }
+                }
+                value.push(*ch);
+                j += 1;
+            }
+            let end = end_of(j + 1);
+            tokens.push(SpannedToken {
+                token: Token::Str(value),
+                start,
+                end,
+            });
+            i = j + 1;
+        } else if let Some(symbol) = SYMBOLS.iter().find(|s| sql[start..].starts_with(**s)) {
+            tokens.push(SpannedToken {
+                token: Token::Symbol(*symbol),
+                start,
+                end: start + symbol.len(),
+            });
+            i += symbol.len();
+        } else {
+            return Err(MqttBrokerError::InvalidRuleSql(format!(
+                "unexpected character '{}' at position {}",
+                c, start
+            )));
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser<'a> {
+    sql: &'a str,
+    tokens: Vec<SpannedToken>,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|t| &t.token)
+    }
+
+    fn error(&self, expected: &str) -> MqttBrokerError {
+        match self.tokens.get(self.pos) {
+            Some(token) => MqttBrokerError::InvalidRuleSql(format!(
+                "expected {} at position {}, found '{}'",
+                expected,
+                token.start,
+                &self.sql[token.start..token.end]
+            )),
+            None => {
+                MqttBrokerError::InvalidRuleSql(format!("expected {}, found end of sql", expected))
+            }
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Ident(ident)) if ident.eq_ignore_ascii_case(keyword))
+    }
+
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        if self.is_keyword(keyword) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<(), MqttBrokerError> {
+        if self.eat_keyword(keyword) {
+            return Ok(());
+        }
+        Err(self.error(keyword))
+    }
+
+    fn eat_symbol(&mut self, symbol: &str) -> bool {
+        if matches!(self.peek(), Some(Token::Symbol(s)) if *s == symbol) {
+            self.pos += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expect_symbol(&mut self, symbol: &str) -> Result<(), MqttBrokerError> {
+        if self.eat_symbol(symbol) {
+            return Ok(());
+        }
+        Err(self.error(&format!("'{}'", symbol)))
+    }
+
+    fn ident(&mut self) -> Result<String, MqttBrokerError> {
+        match self.peek() {
+            Some(Token::Ident(ident))
+                if !KEYWORDS.iter().any(|k| ident.eq_ignore_ascii_case(k)) =>
+            {
+                let ident = ident.clone();
+                self.pos += 1;
+                Ok(ident)
+            }
+            _ => Err(self.error("identifier")),
+        }
+    }
+
+    fn parse(&mut self) -> Result<RuleSql, MqttBrokerError> {
+        self.expect_keyword("SELECT")?;
+        let mut fields = vec![self.select_field()?];
+        while self.eat_symbol(",") {
+            fields.push(self.select_field()?);
+        }
+
+        self.expect_keyword("FROM")?;
+        let mut from = vec![self.topic_filter()?];
+        while self.eat_symbol(",") {
+            from.push(self.topic_filter()?);
+        }
+
+        let condition = if self.eat_keyword("WHERE") {
+            Some(self.or_expr()?)
+        } else {
+            None
+        };
+
+        if self.peek().is_some() {
+            return Err(self.error("end of sql"));
+        }
+        Ok(RuleSql {
+            fields,
+            from,
+            condition,
+        })
+    }
+
+    fn select_field(&mut self) -> Result<SelectField, MqttBrokerError> {
+        if self.eat_symbol("*") {
+            return Ok(SelectField::All);
+        }
+
+        let start = self
+            .tokens
+            .get(self.pos)
+            .map(|t| t.start)
+            .unwrap_or_default();
+        let expr = self.or_expr()?;
+        let end = self.tokens[self.pos - 1].end;
+
+        let alias = if self.eat_keyword("AS") {
+            match self.peek().cloned() {
+                Some(Token::Str(alias)) => {
+                    self.pos += 1;
+                    alias
+                }
+                _ => self.ident()?,
+            }
+        } else if let Expr::Field(path) = &expr {
+            path.join(".")
+        } else {
+            self.sql[start..end].to_string()
+        };
+        Ok(SelectField::Expr { expr, alias })
+    }
+
+    fn topic_filter(&mut self) -> Result<String, MqttBrokerError> {
+        match self.peek() {
+            Some(Token::Str(filter)) => {
+                let filter = filter.clone();
+                if !is_valid_topic_filter(&filter) {
+                    return Err(MqttBrokerError::InvalidRuleSql(format!(
+                        "invalid topic filter \"{}\"",
+                        filter
+                    )));
+                }
+                self.pos += 1;
+                Ok(filter)
+            }
+            _ => Err(self.error("quoted topic filter")),
+        }
+    }
+
+    fn or_expr(&mut self) -> Result<Expr, MqttBrokerError> {
+        let mut left = self.and_expr()?;
+        while self.eat_keyword("OR") {
+            let right = self.and_expr()?;
+            left = Expr::Binary(BinaryOp::Or, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn and_expr(&mut self) -> Result<Expr, MqttBrokerError> {
+        let mut left = self.not_expr()?;
+        while self.eat_keyword("AND") {
+            let right = self.not_expr()?;
+            left = Expr::Binary(BinaryOp::And, Box::new(left), Box::new(right));
+        }
+        Ok(left)
+    }
+
+    fn not_expr(&mut self) -> Result<Expr, MqttBrokerError> {
+        if self.eat_keyword("NOT") {
+            return Ok(Expr::Not(Box::new(self.not_expr()?)));
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Expr, MqttBrokerError> {
+        let left = self.additive()?;
+        let op = match self.peek() {
+            Some(Token::Symbol("=")) => BinaryOp::Eq,
+            Some(Token::Symbol("!=")) | Some(Token::Symbol("<>")) => BinaryOp::NotEq,
+            Some(Token::Symbol("<")) => BinaryOp::Lt,
+            Some(Token::Symbol("<=")) => BinaryOp::LtEq,
+            Some(Token::Symbol(">")) => BinaryOp::Gt,
+            Some(Token::Symbol(">=")) => BinaryOp::GtEq,
+            _ => return Ok(left),
+        };
+        self.pos += 1;
+        let right = self.additive()?;
+        Ok(Expr::Binary(op, Box::new(left), Box::new(right)))
+    }
+
+    fn additive(&mut self) -> Result<Expr, MqttBrokerError> {
+        let mut left = self.multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Symbol("+")) => BinaryOp::Add,
+                Some(Token::Symbol("-")) => BinaryOp::Sub,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            let right = self.multiplicative()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn multiplicative(&mut self) -> Result<Expr, MqttBrokerError> {
+        let mut left = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Symbol("*")) => BinaryOp::Mul,
+                Some(Token::Symbol("/")) => BinaryOp::Div,
+                Some(Token::Symbol("%")) => BinaryOp::Mod,
+                _ => return Ok(left),
+            };
+            self.pos += 1;
+            let right = self.unary()?;
+            left = Expr::Binary(op, Box::new(left), Box::new(right));
+        }
+    }
+
+    fn unary(&mut self) -> Result<Expr, MqttBrokerError> {
+        if self.eat_symbol("-") {
+            return Ok(Expr::Neg(Box::new(self.unary()?)));
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<Expr, MqttBrokerError> {
+        if self.eat_symbol("(") {
+            let expr = self.or_expr()?;
+            self.expect_symbol(")")?;
+            return Ok(expr);
+        }
+        if self.eat_keyword("TRUE") {
+            return Ok(Expr::Literal(Value::Bool(true)));
+        }
+        if self.eat_keyword("FALSE") {
+            return Ok(Expr::Literal(Value::Bool(false)));
+        }
+        if self.eat_keyword("NULL") {
+            return Ok(Expr::Literal(Value::Null));
+        }
+
+        match self.peek().cloned() {
+            Some(Token::Number(num)) => {
+                self.pos += 1;
+                let value = if num.fract() == 0.0 && num < i64::MAX as f64 {
+                    Value::from(num as i64)
+                } else {
+                    Value::from(num)
+                };
+                Ok(Expr::Literal(value))
+            }
+            Some(Token::Str(s)) => {
+                self.pos += 1;
+                Ok(Expr::Literal(Value::String(s)))
+            }
+            Some(Token::Ident(_)) => {
+                let name = self.ident()?;
+                if self.eat_symbol("(") {
+                    let mut args = Vec::new();
+                    if !self.eat_symbol(")") {
+                        args.push(self.or_expr()?);
+                        while self.eat_symbol(",") {
+                            args.push(self.or_expr()?);
+                        }
+                        self.expect_symbol(")")?;
+                    }
+                    let name = name.to_lowercase();
+                    check_function(&name, args.len()).map_err(MqttBrokerError::InvalidRuleSql)?;
+                    return Ok(Expr::Function(name, args));
+                }
+
+                let mut path = vec![name];
+                while self.eat_symbol(".") {
+                    path.push(self.ident()?);
+                }
+                Ok(Expr::Field(path))
+            }
+            _ => Err(self.error("expression")),
+        }
+    }
+}
+
+fn is_valid_topic_filter(filter: &str) -> bool {
+    if filter.is_empty() {
+        return false;
+    }
+    let levels: Vec<&str> = filter.split('/').collect();
+    for (i, level) in levels.iter().enumerate() {
+        if level.contains('+') && *level != "+" {
+            return false;
+        }
+        if level.contains('#') && (*level != "#" || i != levels.len() - 1) {
+            return false;
+        }
+    }
+    true
+}
+
+pub fn parse_rule_sql(sql: &str) -> Result<RuleSql, MqttBrokerError> {
+    let tokens = tokenize(sql)?;
+    let mut parser = Parser {
+        sql,
+        tokens,
+        pos: 0,
+    };
+    parser.parse()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn parse_rule_sql_test() {
+        let sql = parse_rule_sql(
+            "SELECT payload.temp AS t, clientid, upper(payload.unit) FROM \"sensor/+/data\", \"alarm/#\" WHERE payload.temp > 30 AND NOT clientid = 'c2'",
+        )
+        .unwrap();
+        assert_eq!(sql.from, vec!["sensor/+/data", "alarm/#"]);
+        assert_eq!(sql.fields.len(), 3);
+        assert!(sql.condition.is_some());
+
+        let aliases: Vec<String> = sql
+            .fields
+            .iter()
+            .map(|field| match field {
+                SelectField::All => "*".to_string(),
+                SelectField::Expr { alias, .. } => alias.clone(),
+            })
+            .collect();
+        assert_eq!(aliases, vec!["t", "clientid", "upper(payload.unit)"]);
+
+        let sql = parse_rule_sql("select * from \"t/1\"").unwrap();
+        assert_eq!(sql.fields, vec![SelectField::All]);
+        assert!(sql.condition.is_none());
+    }
+
+    #[test]
+    fn parse_rule_sql_error_test() {
+        assert!(parse_rule_sql("").is_err());
+        assert!(parse_rule_sql("SELECT * FROM t").is_err());
+        assert!(parse_rule_sql("SELECT * FROM \"a/#/b\"").is_err());
+        assert!(parse_rule_sql("SELECT * FROM \"a\" WHERE").is_err());
+        assert!(parse_rule_sql("SELECT * FROM \"a\" WHERE x = 'open").is_err());
+        assert!(parse_rule_sql("SELECT nope(x) FROM \"a\"").is_err());
+        assert!(parse_rule_sql("SELECT * FROM \"a\" LIMIT 1").is_err());
+        assert!(parse_rule_sql("SELECT from FROM \"a\"").is_err());
+    }
+
+    #[test]
+    fn select_test() {
+        let sql = parse_rule_sql(
+            "SELECT payload.temp * 2 + 1 AS v, clientid FROM \"t/#\" WHERE (payload.temp >= 10 OR qos = 2) AND topic != 't/x'",
+        )
+        .unwrap();
+
+        let context = json!({"clientid": "c1", "topic": "t/1", "qos": 1, "payload": {"temp": 10}});
+        assert_eq!(
+            sql.select(&context),
+            Some(json!({"v": 21, "clientid": "c1"}))
+        );
+
+        let context = json!({"clientid": "c1", "topic": "t/1", "qos": 1, "payload": {"temp": 9}});
+        assert_eq!(sql.select(&context), None);
+
+        let context = json!({"clientid": "c1", "topic": "t/x", "qos": 2, "payload": {"temp": 10}});
+        assert_eq!(sql.select(&context), None);
+
+        let sql = parse_rule_sql("SELECT *, -payload.temp AS neg FROM \"t/#\"").unwrap();
+        let context = json!({"clientid": "c1", "payload": {"temp": 10}});
+        assert_eq!(
+            sql.select(&context),
+            Some(json!({"clientid": "c1", "payload": {"temp": 10}, "neg": -10}))
+        );
+    }
+}
@@ -21,27 +21,42 @@ use common_base::utils::time_util::get_current_millisecond_timestamp;
 use grpc_clients::pool::ClientPool;
 use metadata_struct::acl::mqtt_acl::MqttAcl;
 use metadata_struct::acl::mqtt_blacklist::{MqttAclBlackList, MqttAclBlackListType};
+use metadata_struct::mqtt::cluster::MqttShareSubStrategy;
 use metadata_struct::mqtt::topic_rewrite_rule::MqttTopicRewriteRule;
 use metadata_struct::mqtt::user::MqttUser;
 use protocol::broker_mqtt::broker_mqtt_admin::mqtt_broker_admin_service_server::MqttBrokerAdminService;
 use protocol::broker_mqtt::broker_mqtt_admin::{
-    ClusterStatusReply, ClusterStatusRequest, CreateAclReply, CreateAclRequest,
-    CreateBlacklistReply, CreateBlacklistRequest, CreateTopicRewriteRuleReply,
+    BindSchemaReply, BindSchemaRequest, ClusterStatusReply, ClusterStatusRequest, CreateAclReply,
+    CreateAclRequest, CreateBlacklistReply, CreateBlacklistRequest, CreateRuleReply,
+    CreateRuleRequest, CreateSchemaReply, CreateSchemaRequest, CreateTopicRewriteRuleReply,
     CreateTopicRewriteRuleRequest, CreateUserReply, CreateUserRequest, DeleteAclReply,
-    DeleteAclRequest, DeleteBlacklistReply, DeleteBlacklistRequest, DeleteTopicRewriteRuleReply,
+    DeleteAclRequest, DeleteAutoSubscribeRuleReply, DeleteAutoSubscribeRuleRequest,
+    DeleteBlacklistReply, DeleteBlacklistRequest, DeleteRuleReply, DeleteRuleRequest,
+    DeleteSchemaReply, DeleteSchemaRequest, DeleteTopicRewriteRuleReply,
     DeleteTopicRewriteRuleRequest, DeleteUserReply, DeleteUserRequest, EnableSlowSubScribeReply,
-    EnableSlowSubscribeRequest, ListAclReply, ListAclRequest, ListBlacklistReply,
-    ListBlacklistRequest, ListConnectionRaw, ListConnectionReply, ListConnectionRequest,
-    ListSlowSubScribeRaw, ListSlowSubscribeReply, ListSlowSubscribeRequest, ListTopicReply,
-    ListTopicRequest, ListUserReply, ListUserRequest, MqttTopic,
+    EnableSlowSubscribeRequest, ListAclReply, ListAclRequest, ListAutoSubscribeRuleReply,
+    ListAutoSubscribeRuleRequest, ListBlacklistReply, ListBlacklistRequest, ListConnectionRaw,
+    ListConnectionReply, ListConnectionRequest, ListExclusiveTopicReply, ListExclusiveTopicRequest,
+    ListRuleReply, ListRuleRequest, ListSchemaBindingReply, ListSchemaBindingRequest,
+    ListSchemaReply, ListSchemaRequest, ListSlowSubScribeRaw, ListSlowSubscribeReply,
+    ListSlowSubscribeRequest, ListTopicReply, ListTopicRequest, ListUserReply, ListUserRequest,
+    MqttTopic, ReleaseExclusiveTopicReply, ReleaseExclusiveTopicRequest, SetAutoSubscribeRuleReply,
+    SetAutoSubscribeRuleRequest, SetShareSubStrategyReply, SetShareSubStrategyRequest,
+    ShareSubStrategy, UnbindSchemaReply, UnbindSchemaRequest,
 };
 use tonic::{Request, Response, Status};
 
+use crate::handler::auto_subscribe::build_auto_subscribe_rule;
 use crate::handler::cache::CacheManager;
+use crate::handler::schema::{build_schema, build_schema_binding};
 use crate::observability::slow::sub::{read_slow_sub_record, SlowSubData};
+use crate::rule_engine::build_rule;
 use crate::security::AuthDriver;
 use crate::server::connection_manager::ConnectionManager;
+use crate::storage::auto_subscribe::AutoSubscribeStorage;
 use crate::storage::cluster::ClusterStorage;
+use crate::storage::rule::RuleStorage;
+use crate::storage::schema::SchemaStorage;
 use crate::storage::topic::TopicStorage;
 
 pub struct GrpcAdminServices {
@@ -438,4 +453,261 @@ impl MqttBrokerAdminService for GrpcAdminServices {
             Err(e) => Err(Status::cancelled(e.to_string())),
         }
     }
+
+    async fn mqtt_broker_list_exclusive_topic(
+        &self,
+        _: Request<ListExclusiveTopicRequest>,
+    ) -> Result<Response<ListExclusiveTopicReply>, Status> {
+        let topic_storage = TopicStorage::new(self.client_pool.clone());
+        match topic_storage.list_exclusive_topic().await {
+            Ok(data) => Ok(Response::new(ListExclusiveTopicReply {
+                exclusive_topics: data.iter().map(|raw| raw.encode()).collect(),
+            })),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn mqtt_broker_release_exclusive_topic(
+        &self,
+        request: Request<ReleaseExclusiveTopicRequest>,
+    ) -> Result<Response<ReleaseExclusiveTopicReply>, Status> {
+        let req = request.into_inner();
+        let topic_storage = TopicStorage::new(self.client_pool.clone());
+        match topic_storage.release_exclusive_topic(req.topic_name).await {
+            Ok(_) => Ok(Response::new(ReleaseExclusiveTopicReply::default())),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn mqtt_broker_set_auto_subscribe_rule(
+        &self,
+        request: Request<SetAutoSubscribeRuleRequest>,
+    ) -> Result<Response<SetAutoSubscribeRuleReply>, Status> {
+        let req = request.into_inner();
+        let config = broker_mqtt_conf();
+        let auto_subscribe_rule = build_auto_subscribe_rule(
+            &config.cluster_name,
+            &req.topic,
+            req.qos,
+            req.no_local,
+            req.retain_as_published,
+            req.retained_handling,
+        )?;
+
+        let auto_subscribe_storage = AutoSubscribeStorage::new(self.client_pool.clone());
+        match auto_subscribe_storage
+            .set_auto_subscribe_rule(auto_subscribe_rule.clone())
+            .await
+        {
+            Ok(_) => {
+                self.cache_manager
+                    .add_auto_subscribe_rule(auto_subscribe_rule);
+                Ok(Response::new(SetAutoSubscribeRuleReply::default()))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn mqtt_broker_delete_auto_subscribe_rule(
+        &self,
+        request: Request<DeleteAutoSubscribeRuleRequest>,
+    ) -> Result<Response<DeleteAutoSubscribeRuleReply>, Status> {
+        let req = request.into_inner();
+        let auto_subscribe_storage = AutoSubscribeStorage::new(self.client_pool.clone());
+        match auto_subscribe_storage
+            .delete_auto_subscribe_rule(req.topic.clone())
+            .await
+        {
+            Ok(_) => {
+                self.cache_manager.delete_auto_subscribe_rule(&req.topic);
+                Ok(Response::new(DeleteAutoSubscribeRuleReply::default()))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn mqtt_broker_list_auto_subscribe_rule(
+        &self,
+        _: Request<ListAutoSubscribeRuleRequest>,
+    ) -> Result<Response<ListAutoSubscribeRuleReply>, Status> {
+        let auto_subscribe_rules = self
+            .cache_manager
+            .auto_subscribe_rule
+            .iter()
+            .map(|entry| entry.value().encode())
+            .collect();
+        Ok(Response::new(ListAutoSubscribeRuleReply {
+            auto_subscribe_rules,
+        }))
+    }
+
+    // --- rule engine ---
+    async fn mqtt_broker_create_rule(
+        &self,
+        request: Request<CreateRuleRequest>,
+    ) -> Result<Response<CreateRuleReply>, Status> {
+        let req = request.into_inner();
+        let config = broker_mqtt_conf();
+        let rule = build_rule(&config.cluster_name, req)?;
+
+        // The Placement Center pushes the rule to every broker of the cluster
+        let rule_storage = RuleStorage::new(self.client_pool.clone());
+        match rule_storage.create_rule(rule.clone()).await {
+            Ok(_) => {
+                self.cache_manager.add_rule(rule);
+                Ok(Response::new(CreateRuleReply::default()))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn mqtt_broker_delete_rule(
+        &self,
+        request: Request<DeleteRuleRequest>,
+    ) -> Result<Response<DeleteRuleReply>, Status> {
+        let req = request.into_inner();
+        let rule_storage = RuleStorage::new(self.client_pool.clone());
+        match rule_storage.delete_rule(req.rule_name.clone()).await {
+            Ok(_) => {
+                self.cache_manager.delete_rule(&req.rule_name);
+                Ok(Response::new(DeleteRuleReply::default()))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn mqtt_broker_list_rule(
+        &self,
+        _: Request<ListRuleRequest>,
+    ) -> Result<Response<ListRuleReply>, Status> {
+        let rules = self
+            .cache_manager
+            .rule_info
+            .iter()
+            .map(|entry| entry.value().rule.encode())
+            .collect();
+        Ok(Response::new(ListRuleReply { rules }))
+    }
+
+    // --- schema registry ---
+    async fn mqtt_broker_create_schema(
+        &self,
+        request: Request<CreateSchemaRequest>,
+    ) -> Result<Response<CreateSchemaReply>, Status> {
+        let req = request.into_inner();
+        let config = broker_mqtt_conf();
+        let schema = build_schema(&config.cluster_name, req)?;
+
+        // The Placement Center pushes the schema to every broker of the cluster
+        let schema_storage = SchemaStorage::new(self.client_pool.clone());
+        match schema_storage.create_schema(schema.clone()).await {
+            Ok(_) => {
+                self.cache_manager.add_schema(schema);
+                Ok(Response::new(CreateSchemaReply::default()))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn mqtt_broker_delete_schema(
+        &self,
+        request: Request<DeleteSchemaRequest>,
+    ) -> Result<Response<DeleteSchemaReply>, Status> {
+        let req = request.into_inner();
+        let schema_storage = SchemaStorage::new(self.client_pool.clone());
+        match schema_storage.delete_schema(req.schema_name.clone()).await {
+            Ok(_) => {
+                self.cache_manager.delete_schema(&req.schema_name);
+                Ok(Response::new(DeleteSchemaReply::default()))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn mqtt_broker_list_schema(
+        &self,
+        _: Request<ListSchemaRequest>,
+    ) -> Result<Response<ListSchemaReply>, Status> {
+        let schemas = self
+            .cache_manager
+            .schema_info
+            .iter()
+            .map(|entry| entry.value().schema.encode())
+            .collect();
+        Ok(Response::new(ListSchemaReply { schemas }))
+    }
+
+    async fn mqtt_broker_bind_schema(
+        &self,
+        request: Request<BindSchemaRequest>,
+    ) -> Result<Response<BindSchemaReply>, Status> {
+        let req = request.into_inner();
+        let config = broker_mqtt_conf();
+        let binding = build_schema_binding(&config.cluster_name, req)?;
+
+        let schema_storage = SchemaStorage::new(self.client_pool.clone());
+        match schema_storage.bind_schema(binding.clone()).await {
+            Ok(_) => {
+                self.cache_manager.add_schema_binding(binding);
+                Ok(Response::new(BindSchemaReply::default()))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn mqtt_broker_unbind_schema(
+        &self,
+        request: Request<UnbindSchemaRequest>,
+    ) -> Result<Response<UnbindSchemaReply>, Status> {
+        let req = request.into_inner();
+        let schema_storage = SchemaStorage::new(self.client_pool.clone());
+        match schema_storage
+            .unbind_schema(req.schema_name.clone(), req.topic_filter.clone())
+            .await
+        {
+            Ok(_) => {
+                self.cache_manager
+                    .delete_schema_binding(&req.schema_name, &req.topic_filter);
+                Ok(Response::new(UnbindSchemaReply::default()))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn mqtt_broker_list_schema_binding(
+        &self,
+        _: Request<ListSchemaBindingRequest>,
+    ) -> Result<Response<ListSchemaBindingReply>, Status> {
+        let bindings = self
+            .cache_manager
+            .schema_binding
+            .iter()
+            .map(|entry| entry.value().encode())
+            .collect();
+        Ok(Response::new(ListSchemaBindingReply { bindings }))
+    }
+
+    async fn mqtt_broker_set_share_sub_strategy(
+        &self,
+        request: Request<SetShareSubStrategyRequest>,
+    ) -> Result<Response<SetShareSubStrategyReply>, Status> {
+        let req = request.into_inner();
+        let strategy = match req.strategy() {
+            ShareSubStrategy::RoundRobin => MqttShareSubStrategy::RoundRobin,
+            ShareSubStrategy::Random => MqttShareSubStrategy::Random,
+            ShareSubStrategy::HashClientId => MqttShareSubStrategy::HashClientId,
+            ShareSubStrategy::HashTopic => MqttShareSubStrategy::HashTopic,
+            ShareSubStrategy::Sticky => MqttShareSubStrategy::Sticky,
+            ShareSubStrategy::LeastInflight => MqttShareSubStrategy::LeastInflight,
+        };
+
+        match self
+            .cache_manager
+            .set_share_sub_strategy(&req.group_name, strategy)
+            .await
+        {
+            Ok(_) => Ok(Response::new(SetShareSubStrategyReply::default())),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
 }
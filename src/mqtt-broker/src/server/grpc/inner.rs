@@ -62,7 +62,10 @@ where
         request: Request<UpdateCacheRequest>,
     ) -> Result<Response<UpdateCacheReply>, Status> {
         let req = request.into_inner();
-        update_cache_metadata(req);
+        if self.cache_manager.cluster_name != req.cluster_name {
+            return Err(Status::cancelled("Cluster name does not match".to_string()));
+        }
+        update_cache_metadata(&self.cache_manager, &self.subscribe_manager, req)?;
         return Ok(Response::new(UpdateCacheReply::default()));
     }
 
@@ -23,6 +23,7 @@ use tokio::select;
 use tokio::sync::broadcast;
 use tokio::sync::mpsc::{self, Receiver, Sender};
 
+use crate::handler::auto_subscribe::{auto_subscribe_after_connack, is_connect_success};
 use crate::handler::cache::CacheManager;
 use crate::handler::connection::disconnect_connection;
 use crate::observability::metrics::server::{metrics_request_queue, metrics_response_queue};
@@ -142,7 +143,17 @@ pub(crate) fn response_child_process(
                                 match raw_connect_manager
                                     .write_tcp_frame(response_package.connection_id, packet_wrapper)
                                     .await{
-                                        Ok(()) => {},
+                                        Ok(()) => {
+                                            if is_connect_success(&response_package.packet) {
+                                                auto_subscribe_after_connack(
+                                                    response_package.connection_id,
+                                                    &raw_cache_manager,
+                                                    &raw_subscribe_manager,
+                                                    &raw_client_pool,
+                                                    &raw_connect_manager,
+                                                ).await;
+                                            }
+                                        },
                                         Err(e) => {
                                             error!("{}",e);
                                             raw_connect_manager.close_connect(response_package.connection_id).await;
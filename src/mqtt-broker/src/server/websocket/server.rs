@@ -35,6 +35,7 @@ use storage_adapter::storage::StorageAdapter;
 use tokio::select;
 use tokio::sync::broadcast::{self};
 
+use crate::handler::auto_subscribe::{auto_subscribe_after_connack, is_connect_success};
 use crate::handler::cache::CacheManager;
 use crate::handler::command::Command;
 use crate::security::AuthDriver;
@@ -179,17 +180,24 @@ where
                 command,
                 codec,
                 state.connection_manager.clone(),
+                state.cache_manager.clone(),
+                state.sucscribe_manager.clone(),
+                state.client_pool.clone(),
                 state.stop_sx.clone(),
             )
         })
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn handle_socket<S>(
     socket: WebSocket,
     addr: SocketAddr,
     mut command: Command<S>,
     mut codec: MqttCodec,
     connection_manager: Arc<ConnectionManager>,
+    cache_manager: Arc<CacheManager>,
+    subscribe_manager: Arc<SubscribeManager>,
+    client_pool: Arc<ClientPool>,
     stop_sx: broadcast::Sender<bool>,
 ) where
     S: StorageAdapter + Sync + Send + 'static + Clone,
@@ -240,6 +248,7 @@ async fn handle_socket<S>(
                                             }
                                         }
 
+                                        let connect_success = is_connect_success(&resp_pkg);
                                         let mut response_buff = BytesMut::new();
                                         let packet_wrapper = MqttPacketWrapper {
                                             protocol_version: protocol_version.clone().into(),
@@ -253,7 +262,17 @@ async fn handle_socket<S>(
                                             }
                                         }
                                         match connection_manager.write_websocket_frame(tcp_connection.connection_id, packet_wrapper, Message::Binary(response_buff.to_vec())).await{
-                                            Ok(()) => {},
+                                            Ok(()) => {
+                                                if connect_success {
+                                                    auto_subscribe_after_connack(
+                                                        tcp_connection.connection_id,
+                                                        &cache_manager,
+                                                        &subscribe_manager,
+                                                        &client_pool,
+                                                        &connection_manager,
+                                                    ).await;
+                                                }
+                                            },
                                             Err(e) => {
                                                 error!("websocket returns failure to write the packet to the client with error message {e:?}");
                                                 connection_manager.close_connect(tcp_connection.connection_id).await;
@@ -21,14 +21,20 @@ use grpc_clients::placement::inner::call::{
     delete_resource_config, get_resource_config, heartbeat, node_list, register_node,
     set_resource_config, unregister_node,
 };
+use grpc_clients::placement::mqtt::call::{
+    placement_list_share_sub_strategy, placement_set_share_sub_strategy,
+};
 use grpc_clients::pool::ClientPool;
-use metadata_struct::mqtt::cluster::MqttClusterDynamicConfig;
+use metadata_struct::mqtt::cluster::{MqttClusterDynamicConfig, MqttShareSubGroupStrategy};
 use metadata_struct::mqtt::node_extend::MqttNodeExtend;
 use metadata_struct::placement::node::BrokerNode;
 use protocol::placement_center::placement_center_inner::{
     ClusterType, DeleteResourceConfigRequest, GetResourceConfigRequest, HeartbeatRequest,
     NodeListRequest, RegisterNodeRequest, SetResourceConfigRequest, UnRegisterNodeRequest,
 };
+use protocol::placement_center::placement_center_mqtt::{
+    ListShareSubStrategyRequest, SetShareSubStrategyRequest,
+};
 
 pub struct ClusterStorage {
     client_pool: Arc<ClientPool>,
@@ -127,6 +133,41 @@ impl ClusterStorage {
         Ok(())
     }
 
+    // The strategies are stored per group rather than in the cluster config, so that changes of
+    // different groups do not overwrite each other.
+    pub async fn set_share_sub_strategy(
+        &self,
+        strategy: MqttShareSubGroupStrategy,
+    ) -> Result<(), CommonError> {
+        let config = broker_mqtt_conf();
+        let request = SetShareSubStrategyRequest {
+            cluster_name: strategy.cluster_name.clone(),
+            group_name: strategy.group_name.clone(),
+            content: strategy.encode(),
+        };
+        placement_set_share_sub_strategy(&self.client_pool, &config.placement_center, request)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn list_share_sub_strategy(
+        &self,
+        cluster_name: &str,
+    ) -> Result<Vec<MqttShareSubGroupStrategy>, CommonError> {
+        let config = broker_mqtt_conf();
+        let request = ListShareSubStrategyRequest {
+            cluster_name: cluster_name.to_string(),
+        };
+        let reply =
+            placement_list_share_sub_strategy(&self.client_pool, &config.placement_center, request)
+                .await?;
+        let mut results = Vec::new();
+        for raw in reply.strategies {
+            results.push(serde_json::from_slice::<MqttShareSubGroupStrategy>(&raw)?);
+        }
+        Ok(results)
+    }
+
     pub async fn delete_cluster_config(&self, cluster_name: &str) -> Result<(), CommonError> {
         let config = broker_mqtt_conf();
         let resources = self.cluster_config_resources(cluster_name.to_string());
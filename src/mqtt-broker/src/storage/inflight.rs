@@ -0,0 +1,73 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::config::broker_mqtt::broker_mqtt_conf;
+use grpc_clients::placement::mqtt::call::{
+    placement_batch_inflight_message, placement_list_inflight_message,
+};
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::inflight::MqttInflightMessage;
+use protocol::placement_center::placement_center_mqtt::{
+    BatchInflightMessageRequest, InflightMessageKey, ListInflightMessageRequest,
+};
+
+use crate::handler::error::MqttBrokerError;
+
+pub struct InflightStorage {
+    client_pool: Arc<ClientPool>,
+}
+
+impl InflightStorage {
+    pub fn new(client_pool: Arc<ClientPool>) -> Self {
+        InflightStorage { client_pool }
+    }
+
+    pub async fn batch_inflight_message(
+        &self,
+        saves: Vec<MqttInflightMessage>,
+        deletes: Vec<InflightMessageKey>,
+    ) -> Result<(), MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = BatchInflightMessageRequest {
+            cluster_name: config.cluster_name.clone(),
+            saves: saves.iter().map(|raw| raw.encode()).collect(),
+            deletes,
+        };
+        placement_batch_inflight_message(&self.client_pool, &config.placement_center, request)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn list_inflight_message(
+        &self,
+        client_id: &str,
+    ) -> Result<Vec<MqttInflightMessage>, MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = ListInflightMessageRequest {
+            cluster_name: config.cluster_name.clone(),
+            client_id: client_id.to_owned(),
+        };
+        let reply =
+            placement_list_inflight_message(&self.client_pool, &config.placement_center, request)
+                .await?;
+        let mut results = Vec::with_capacity(8);
+        for raw in reply.messages {
+            let data = serde_json::from_slice::<MqttInflightMessage>(&raw)?;
+            results.push(data);
+        }
+        Ok(results)
+    }
+}
@@ -67,6 +67,20 @@ where
             .await
     }
 
+    pub async fn get_offset_by_timestamp(
+        &self,
+        topic_id: &str,
+        timestamp: u64,
+    ) -> Result<Option<u64>, CommonError> {
+        let shard_name = topic_id;
+        let namespace = cluster_name();
+        let offset = self
+            .storage_adapter
+            .get_offset_by_timestamp(namespace, shard_name.to_owned(), timestamp)
+            .await?;
+        Ok(offset.map(|raw| raw.offset))
+    }
+
     pub async fn get_group_offset(&self, group_id: &str) -> Result<u64, CommonError> {
         let offset_data = self
             .storage_adapter
@@ -13,9 +13,13 @@
 // limitations under the License.
 
 pub mod acl;
+pub mod auto_subscribe;
 pub mod blacklist;
 pub mod cluster;
+pub mod inflight;
 pub mod message;
+pub mod rule;
+pub mod schema;
 pub mod session;
 pub mod topic;
 pub mod user;
@@ -0,0 +1,116 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::config::broker_mqtt::broker_mqtt_conf;
+use grpc_clients::placement::mqtt::call::{
+    placement_bind_schema, placement_create_schema, placement_delete_schema, placement_list_schema,
+    placement_list_schema_binding, placement_unbind_schema,
+};
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::schema::{MqttSchema, MqttSchemaBinding};
+use protocol::placement_center::placement_center_mqtt::{
+    BindSchemaRequest, CreateSchemaRequest, DeleteSchemaRequest, ListSchemaBindingRequest,
+    ListSchemaRequest, UnbindSchemaRequest,
+};
+
+use crate::handler::error::MqttBrokerError;
+
+pub struct SchemaStorage {
+    client_pool: Arc<ClientPool>,
+}
+
+impl SchemaStorage {
+    pub fn new(client_pool: Arc<ClientPool>) -> Self {
+        SchemaStorage { client_pool }
+    }
+
+    pub async fn list_schema(&self) -> Result<Vec<MqttSchema>, MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = ListSchemaRequest {
+            cluster_name: config.cluster_name.clone(),
+        };
+        let reply =
+            placement_list_schema(&self.client_pool, &config.placement_center, request).await?;
+        let mut results = Vec::with_capacity(8);
+        for raw in reply.schemas {
+            let data = serde_json::from_slice::<MqttSchema>(&raw)?;
+            results.push(data);
+        }
+        Ok(results)
+    }
+
+    pub async fn create_schema(&self, schema: MqttSchema) -> Result<(), MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = CreateSchemaRequest {
+            cluster_name: config.cluster_name.clone(),
+            schema_name: schema.schema_name.clone(),
+            content: schema.encode(),
+        };
+        placement_create_schema(&self.client_pool, &config.placement_center, request).await?;
+        Ok(())
+    }
+
+    pub async fn delete_schema(&self, schema_name: String) -> Result<(), MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = DeleteSchemaRequest {
+            cluster_name: config.cluster_name.clone(),
+            schema_name,
+        };
+        placement_delete_schema(&self.client_pool, &config.placement_center, request).await?;
+        Ok(())
+    }
+
+    pub async fn list_schema_binding(&self) -> Result<Vec<MqttSchemaBinding>, MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = ListSchemaBindingRequest {
+            cluster_name: config.cluster_name.clone(),
+        };
+        let reply =
+            placement_list_schema_binding(&self.client_pool, &config.placement_center, request)
+                .await?;
+        let mut results = Vec::with_capacity(8);
+        for raw in reply.bindings {
+            let data = serde_json::from_slice::<MqttSchemaBinding>(&raw)?;
+            results.push(data);
+        }
+        Ok(results)
+    }
+
+    pub async fn bind_schema(&self, binding: MqttSchemaBinding) -> Result<(), MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = BindSchemaRequest {
+            cluster_name: config.cluster_name.clone(),
+            content: binding.encode(),
+        };
+        placement_bind_schema(&self.client_pool, &config.placement_center, request).await?;
+        Ok(())
+    }
+
+    pub async fn unbind_schema(
+        &self,
+        schema_name: String,
+        topic_filter: String,
+    ) -> Result<(), MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = UnbindSchemaRequest {
+            cluster_name: config.cluster_name.clone(),
+            schema_name,
+            topic_filter,
+        };
+        placement_unbind_schema(&self.client_pool, &config.placement_center, request).await?;
+        Ok(())
+    }
+}
@@ -17,18 +17,19 @@ use std::sync::Arc;
 use common_base::config::broker_mqtt::broker_mqtt_conf;
 use dashmap::DashMap;
 use grpc_clients::placement::mqtt::call::{
-    placement_create_topic, placement_create_topic_rewrite_rule, placement_delete_topic,
-    placement_delete_topic_rewrite_rule, placement_list_topic, placement_list_topic_rewrite_rule,
-    placement_set_topic_retain_message,
+    placement_create_topic, placement_create_topic_rewrite_rule, placement_delete_exclusive_topic,
+    placement_delete_topic, placement_delete_topic_rewrite_rule, placement_list_exclusive_topic,
+    placement_list_topic, placement_list_topic_rewrite_rule, placement_set_topic_retain_message,
 };
 use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::exclusive_topic::MqttExclusiveTopic;
 use metadata_struct::mqtt::message::MqttMessage;
 use metadata_struct::mqtt::topic::MqttTopic;
 use metadata_struct::mqtt::topic_rewrite_rule::MqttTopicRewriteRule;
 use protocol::placement_center::placement_center_mqtt::{
-    CreateTopicRequest, CreateTopicRewriteRuleRequest, DeleteTopicRequest,
-    DeleteTopicRewriteRuleRequest, ListTopicRequest, ListTopicRewriteRuleRequest,
-    SetTopicRetainMessageRequest,
+    CreateTopicRequest, CreateTopicRewriteRuleRequest, DeleteExclusiveTopicRequest,
+    DeleteTopicRequest, DeleteTopicRewriteRuleRequest, ListExclusiveTopicRequest, ListTopicRequest,
+    ListTopicRewriteRuleRequest, SetTopicRetainMessageRequest,
 };
 
 use crate::handler::error::MqttBrokerError;
@@ -196,4 +197,33 @@ impl TopicStorage {
             .await?;
         Ok(())
     }
+
+    pub async fn list_exclusive_topic(&self) -> Result<Vec<MqttExclusiveTopic>, MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = ListExclusiveTopicRequest {
+            cluster_name: config.cluster_name.clone(),
+        };
+        let reply =
+            placement_list_exclusive_topic(&self.client_pool, &config.placement_center, request)
+                .await?;
+        let mut results = Vec::new();
+        for raw in reply.exclusive_topics {
+            results.push(serde_json::from_slice::<MqttExclusiveTopic>(&raw)?);
+        }
+        Ok(results)
+    }
+
+    // Release the lock of an exclusive topic whoever holds it. The Placement Center tells the
+    // broker of the holder to drop its subscription.
+    pub async fn release_exclusive_topic(&self, topic_name: String) -> Result<(), MqttBrokerError> {
+        let config = broker_mqtt_conf();
+        let request = DeleteExclusiveTopicRequest {
+            cluster_name: config.cluster_name.clone(),
+            topic_name,
+            client_id: "".to_string(),
+        };
+        placement_delete_exclusive_topic(&self.client_pool, &config.placement_center, request)
+            .await?;
+        Ok(())
+    }
 }
@@ -19,6 +19,7 @@ pub mod sub_common;
 pub mod sub_exclusive;
 pub mod sub_share_follower;
 pub mod sub_share_leader;
+pub mod sub_share_strategy;
 pub mod subscribe_manager;
 pub mod subscriber;
 
@@ -16,18 +16,20 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use axum::extract::ws::Message;
-use bytes::BytesMut;
+use bytes::{Bytes, BytesMut};
 use common_base::config::broker_mqtt::broker_mqtt_conf;
 use common_base::error::common::CommonError;
-use common_base::tools::now_mills;
+use common_base::tools::{now_mills, now_second};
+use common_base::utils::topic_trie::topic_match_filter;
+use common_base::utils::topic_util;
 use grpc_clients::placement::mqtt::call::{
     placement_delete_exclusive_topic, placement_get_share_sub_leader,
     placement_set_nx_exclusive_topic,
 };
 use grpc_clients::pool::ClientPool;
-use log::error;
-use protocol::mqtt::codec::{MqttCodec, MqttPacketWrapper};
-use protocol::mqtt::common::{MqttPacket, MqttProtocol, PubRel, QoS};
+use log::{error, warn};
+use protocol::mqtt::codec::{calc_mqtt_packet_size, MqttCodec, MqttPacketWrapper};
+use protocol::mqtt::common::{MqttPacket, MqttProtocol, PubRel, QoS, SubscribeProperties};
 use protocol::placement_center::placement_center_mqtt::{
     DeleteExclusiveTopicReply, DeleteExclusiveTopicRequest, GetShareSubLeaderReply,
     GetShareSubLeaderRequest, SetExclusiveTopicReply, SetExclusiveTopicRequest,
@@ -38,9 +40,14 @@ use tokio::select;
 use tokio::sync::broadcast::{self, Sender};
 use tokio::time::{sleep, timeout};
 
+use super::subscriber::SubscribeReplay;
 use super::SubPublishParam;
 use crate::handler::cache::{CacheManager, QosAckPackageData};
 use crate::handler::error::MqttBrokerError;
+use crate::handler::topic_alias::OutboundTopicAlias;
+use crate::observability::metrics::publish::{
+    record_publish_messages_dropped, PUBLISH_DROPPED_PACKET_TOO_LARGE,
+};
 use crate::observability::slow::sub::{record_slow_sub_data, SlowSubData};
 use crate::server::connection_manager::ConnectionManager;
 use crate::server::packet::ResponsePackage;
@@ -50,6 +57,68 @@ const SHARE_SUB_PREFIX: &str = "$share";
 
 const QUEUE_SUB_PREFIX: &str = "$queue";
 
+const REPLAY_FROM_TIMESTAMP_PROPERTY: &str = "replay-from-timestamp";
+
+const REPLAY_FROM_OFFSET_PROPERTY: &str = "replay-from-offset";
+
+/// Read the replay start point from the SUBSCRIBE user properties. `replay-from-timestamp` (unix
+/// seconds) takes precedence over `replay-from-offset`, values that are not numbers are ignored.
+pub fn parse_subscribe_replay(
+    subscribe_properties: &Option<SubscribeProperties>,
+) -> Option<SubscribeReplay> {
+    let properties = subscribe_properties.as_ref()?;
+
+    let mut replay = None;
+    for (name, value) in properties.user_properties.iter() {
+        let parse = |value: &str| match value.parse::<u64>() {
+            Ok(val) => Some(val),
+            Err(_) => {
+                warn!(
+                    "Subscribe user property {} has an invalid value {}",
+                    name, value
+                );
+                None
+            }
+        };
+
+        if name == REPLAY_FROM_TIMESTAMP_PROPERTY {
+            if let Some(timestamp) = parse(value) {
+                return Some(SubscribeReplay::Timestamp(timestamp));
+            }
+        } else if name == REPLAY_FROM_OFFSET_PROPERTY && replay.is_none() {
+            replay = parse(value).map(SubscribeReplay::Offset);
+        }
+    }
+    replay
+}
+
+/// The offset a push thread starts from: the replay start point of the subscription if it has one,
+/// otherwise the committed offset of the group. A timestamp after the last message falls back to
+/// the committed offset.
+pub async fn get_sub_start_offset<S>(
+    message_storage: &MessageStorage<S>,
+    group_id: &str,
+    topic_id: &str,
+    replay: &Option<SubscribeReplay>,
+) -> Result<u64, CommonError>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    match replay {
+        Some(SubscribeReplay::Offset(offset)) => Ok(*offset),
+        Some(SubscribeReplay::Timestamp(timestamp)) => {
+            if let Some(offset) = message_storage
+                .get_offset_by_timestamp(topic_id, *timestamp)
+                .await?
+            {
+                return Ok(offset);
+            }
+            message_storage.get_group_offset(group_id).await
+        }
+        None => message_storage.get_group_offset(group_id).await,
+    }
+}
+
 pub fn path_contain_sub(_: &str) -> bool {
     true
 }
@@ -74,45 +143,21 @@ pub fn sub_path_validator(sub_path: String) -> bool {
 }
 
 pub fn path_regex_match(topic_name: String, sub_path: String) -> bool {
-    let path = if is_share_sub(sub_path.clone()) {
-        let (_, group_path) = decode_share_info(sub_path.clone());
-        group_path
-    } else if is_queue_sub(sub_path.clone()) {
-        decode_queue_info(sub_path.clone())
-    } else {
-        sub_path.clone()
-    };
+    topic_match_filter(&sub_path_filter(&topic_name), &sub_path_filter(&sub_path))
+}
 
-    let topic = if is_share_sub(topic_name.clone()) {
-        let (_, group_path) = decode_share_info(topic_name.clone());
+/// The topic filter of a subscription path, without its `$share/{group}`, `$queue` or `$exclusive` prefix.
+pub fn sub_path_filter(sub_path: &str) -> String {
+    if is_share_sub(sub_path.to_string()) {
+        let (_, group_path) = decode_share_info(sub_path.to_string());
         group_path
-    } else if is_queue_sub(topic_name.clone()) {
-        decode_queue_info(topic_name.clone())
+    } else if is_queue_sub(sub_path.to_string()) {
+        decode_queue_info(sub_path.to_string())
+    } else if topic_util::is_exclusive_sub(sub_path) {
+        topic_util::decode_exclusive_sub_path_to_topic_name(sub_path).to_string()
     } else {
-        topic_name.clone()
-    };
-
-    // Path perfect matching
-    if topic == path {
-        return true;
-    }
-
-    if path.contains("+") {
-        let sub_regex = path.replace("+", "[^+*/]+");
-        let re = Regex::new(&sub_regex.to_string()).unwrap();
-        return re.is_match(&topic);
-    }
-
-    if path.contains("#") {
-        if path.split("/").last().unwrap() != "#" {
-            return false;
-        }
-        let sub_regex = path.replace("#", "[^+#]+");
-        let re = Regex::new(&sub_regex.to_string()).unwrap();
-        return re.is_match(&topic);
+        sub_path.to_string()
     }
-
-    false
 }
 
 pub fn min_qos(qos: QoS, sub_qos: QoS) -> QoS {
@@ -122,14 +167,23 @@ pub fn min_qos(qos: QoS, sub_qos: QoS) -> QoS {
     sub_qos
 }
 
+// Retain As Published: the RETAIN flag of the received PUBLISH is kept only when the
+// subscription asked for it, otherwise messages are forwarded with RETAIN set to 0.
+pub fn retain_as_published(preserve_retain: bool, msg_retain: bool) -> bool {
+    preserve_retain && msg_retain
+}
+
 pub async fn get_sub_topic_id_list(
     metadata_cache: &Arc<CacheManager>,
     sub_path: &str,
 ) -> Vec<String> {
     let mut result = Vec::new();
-    for (topic_id, topic_name) in metadata_cache.topic_id_name.clone() {
-        if path_regex_match(topic_name.clone(), sub_path.to_string()) {
-            result.push(topic_id);
+    for topic_name in metadata_cache
+        .topic_trie
+        .match_filter(&sub_path_filter(sub_path))
+    {
+        if let Some(topic) = metadata_cache.topic_info.get(&topic_name) {
+            result.push(topic.topic_id.clone());
         }
     }
     result
@@ -174,11 +228,15 @@ pub async fn get_share_sub_leader(
 pub async fn set_nx_exclusive_topic(
     client_pool: Arc<ClientPool>,
     topic_name: String,
+    client_id: String,
 ) -> Result<SetExclusiveTopicReply, CommonError> {
     let conf = broker_mqtt_conf();
     let req = SetExclusiveTopicRequest {
         cluster_name: conf.cluster_name.clone(),
         topic_name,
+        client_id,
+        broker_id: conf.broker_id,
+        create_time: now_second(),
     };
     match placement_set_nx_exclusive_topic(&client_pool, &conf.placement_center, req).await {
         Ok(reply) => Ok(reply),
@@ -186,14 +244,18 @@ pub async fn set_nx_exclusive_topic(
     }
 }
 
+// An empty topic name releases every lock of the client, an empty client id releases the
+// lock whoever holds it.
 pub async fn delete_exclusive_topic(
     client_pool: Arc<ClientPool>,
     topic_name: String,
+    client_id: String,
 ) -> Result<DeleteExclusiveTopicReply, CommonError> {
     let conf = broker_mqtt_conf();
     let req = DeleteExclusiveTopicRequest {
         cluster_name: conf.cluster_name.clone(),
         topic_name,
+        client_id,
     };
     match placement_delete_exclusive_topic(&client_pool, &conf.placement_center, req).await {
         Ok(reply) => Ok(reply),
@@ -212,6 +274,69 @@ pub async fn wait_packet_ack(sx: &Sender<QosAckPackageData>) -> Option<QosAckPac
     (res.await).unwrap_or_default()
 }
 
+// Like wait_packet_ack, but gives up as soon as the client is no longer on the connection the
+// packet was sent to, so that the caller can retransmit it once the client has reconnected.
+pub async fn wait_packet_ack_or_reconnect(
+    sx: &Sender<QosAckPackageData>,
+    metadata_cache: &Arc<CacheManager>,
+    client_id: &str,
+    connect_id: u64,
+) -> Option<QosAckPackageData> {
+    let mut rx = sx.subscribe();
+    let res = timeout(Duration::from_secs(120), async {
+        loop {
+            select! {
+                val = rx.recv() => {
+                    return val.ok();
+                }
+                _ = sleep(Duration::from_millis(500)) => {
+                    if metadata_cache.get_connect_id(client_id) != Some(connect_id) {
+                        return None;
+                    }
+                }
+            }
+        }
+    });
+
+    (res.await).unwrap_or_default()
+}
+
+// The Maximum Packet Size the client sent in CONNECT limits the whole encoded packet. A Publish
+// that does not fit must not be sent, it is dropped and counted in the metrics instead.
+pub fn is_publish_too_large(
+    metadata_cache: &Arc<CacheManager>,
+    connection_manager: &Arc<ConnectionManager>,
+    resp: &ResponsePackage,
+) -> bool {
+    let conn = if let Some(conn) = metadata_cache.get_connection(resp.connection_id) {
+        conn
+    } else {
+        return false;
+    };
+
+    let protocol =
+        if let Some(protocol) = connection_manager.get_connect_protocol(resp.connection_id) {
+            protocol
+        } else {
+            return false;
+        };
+
+    let packet_size = calc_mqtt_packet_size(MqttPacketWrapper {
+        protocol_version: protocol.into(),
+        packet: resp.packet.clone(),
+    });
+
+    if packet_size > conn.max_packet_size as usize {
+        warn!(
+            "Publish of {} bytes exceeds the Maximum Packet Size {} of client [{}], the message is dropped.",
+            packet_size, conn.max_packet_size, conn.client_id
+        );
+        record_publish_messages_dropped(PUBLISH_DROPPED_PACKET_TOO_LARGE);
+        return true;
+    }
+    false
+}
+
 pub async fn publish_message_to_client(
     resp: ResponsePackage,
     sub_pub_param: &SubPublishParam,
@@ -219,12 +344,33 @@ pub async fn publish_message_to_client(
     metadata_cache: &Arc<CacheManager>,
 ) -> Result<(), MqttBrokerError> {
     if let Some(protocol) = connection_manager.get_connect_protocol(resp.connection_id) {
+        let topic_alias = if protocol.is_mqtt5() {
+            metadata_cache.get_outbound_topic_alias(resp.connection_id)
+        } else {
+            None
+        };
+
+        // The alias lock is held until the packet is written, so the Publish that teaches the
+        // client a mapping always goes out before the ones that only carry the alias.
+        let mut alias_guard = if let Some(alias) = &topic_alias {
+            Some(alias.lock().await)
+        } else {
+            None
+        };
+
+        let mut packet = resp.packet;
+        let new_alias_topic = if let Some(alias) = alias_guard.as_mut() {
+            apply_topic_alias(alias, &mut packet)
+        } else {
+            None
+        };
+
         let response: MqttPacketWrapper = MqttPacketWrapper {
             protocol_version: protocol.clone().into(),
-            packet: resp.packet,
+            packet,
         };
 
-        if connection_manager.is_websocket(resp.connection_id) {
+        let result = if connection_manager.is_websocket(resp.connection_id) {
             let mut codec = MqttCodec::new(Some(protocol.into()));
             let mut buff = BytesMut::new();
             match codec.encode_data(response.clone(), &mut buff) {
@@ -235,12 +381,22 @@ pub async fn publish_message_to_client(
             }
             connection_manager
                 .write_websocket_frame(resp.connection_id, response, Message::Binary(buff.to_vec()))
-                .await?;
+                .await
         } else {
             connection_manager
                 .write_tcp_frame(resp.connection_id, response)
-                .await?
+                .await
+        };
+
+        if result.is_err() {
+            // The client never learned the mapping, it must not be used alias-only later.
+            if let (Some(alias), Some(topic_name)) = (alias_guard.as_mut(), new_alias_topic) {
+                alias.remove(&topic_name);
+            }
         }
+        drop(alias_guard);
+        result?;
+
         // record slow sub data
         if metadata_cache.get_slow_sub_config().enable && sub_pub_param.create_time > 0 {
             let slow_data = SlowSubData::build(
@@ -256,6 +412,28 @@ pub async fn publish_message_to_client(
     Ok(())
 }
 
+// Replaces the topic name of a Publish with its alias. The topic name is kept when the alias is
+// new to the client, which is returned so the mapping can be dropped if the write fails.
+pub fn apply_topic_alias(
+    alias: &mut OutboundTopicAlias,
+    packet: &mut MqttPacket,
+) -> Option<String> {
+    if let MqttPacket::Publish(publish, properties) = packet {
+        let topic_name = String::from_utf8(publish.topic.to_vec()).ok()?;
+        let (topic_alias, is_new) = alias.assign(&topic_name)?;
+
+        let mut publish_properties = properties.take().unwrap_or_default();
+        publish_properties.topic_alias = Some(topic_alias);
+        *properties = Some(publish_properties);
+
+        if is_new {
+            return Some(topic_name);
+        }
+        publish.topic = Bytes::new();
+    }
+    None
+}
+
 pub async fn qos2_send_publish(
     connection_manager: &Arc<ConnectionManager>,
     metadata_cache: &Arc<CacheManager>,
@@ -275,14 +453,8 @@ pub async fn qos2_send_publish(
                 continue;
             };
 
-        if let Some(conn) = metadata_cache.get_connection(connect_id) {
-            if publish.payload.len() > (conn.max_packet_size as usize) {
-                return Err(MqttBrokerError::PacketLengthError(publish.payload.len()));
-            }
-        }
-
         retry_times += 1;
-        publish.dup = retry_times >= 2;
+        publish.dup = sub_pub_param.publish.dup || retry_times >= 2;
 
         let mut contain_properties = false;
         if let Some(protocol) = connection_manager.get_connect_protocol(connect_id) {
@@ -303,6 +475,10 @@ pub async fn qos2_send_publish(
             }
         };
 
+        if is_publish_too_large(metadata_cache, connection_manager, &resp) {
+            return Err(MqttBrokerError::PacketLengthError(publish.payload.len()));
+        }
+
         select! {
             val = stop_rx.recv() => {
                 if let Ok(flag) = val {
@@ -442,12 +618,6 @@ pub async fn publish_message_qos0(
         };
     }
 
-    if let Some(conn) = metadata_cache.get_connection(connect_id) {
-        if sub_pub_param.publish.payload.len() > (conn.max_packet_size as usize) {
-            return;
-        }
-    }
-
     let mut contain_properties = false;
     if let Some(protocol) = connection_manager.get_connect_protocol(connect_id) {
         if MqttProtocol::is_mqtt5(&protocol) {
@@ -470,6 +640,10 @@ pub async fn publish_message_qos0(
         }
     };
 
+    if is_publish_too_large(metadata_cache, connection_manager, &resp) {
+        return;
+    }
+
     // 2. publish to mqtt client
     match publish_message_to_client(
         resp.clone(),
@@ -493,16 +667,114 @@ pub async fn publish_message_qos0(
 mod tests {
     use std::sync::Arc;
 
+    use bytes::Bytes;
+    use common_base::config::broker_mqtt::init_broker_mqtt_conf_by_path;
     use common_base::tools::unique_id;
     use grpc_clients::pool::ClientPool;
+    use metadata_struct::adapter::record::Record;
     use metadata_struct::mqtt::topic::MqttTopic;
-    use protocol::mqtt::common::QoS;
+    use protocol::mqtt::common::{MqttPacket, Publish, QoS, SubscribeProperties};
+    use storage_adapter::memory::MemoryStorageAdapter;
 
     use crate::handler::cache::CacheManager;
+    use crate::handler::topic_alias::OutboundTopicAlias;
+    use crate::storage::message::MessageStorage;
     use crate::subscribe::sub_common::{
-        decode_share_info, get_sub_topic_id_list, is_share_sub, min_qos, path_regex_match,
+        apply_topic_alias, decode_share_info, get_sub_start_offset, get_sub_topic_id_list,
+        is_share_sub, min_qos, parse_subscribe_replay, path_regex_match, sub_path_filter,
         sub_path_validator,
     };
+    use crate::subscribe::subscriber::SubscribeReplay;
+
+    #[test]
+    fn parse_subscribe_replay_test() {
+        assert_eq!(parse_subscribe_replay(&None), None);
+
+        let properties = |user_properties: Vec<(&str, &str)>| {
+            Some(SubscribeProperties {
+                subscription_identifier: None,
+                user_properties: user_properties
+                    .into_iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            })
+        };
+
+        assert_eq!(
+            parse_subscribe_replay(&properties(vec![("replay-from-offset", "10")])),
+            Some(SubscribeReplay::Offset(10))
+        );
+        assert_eq!(
+            parse_subscribe_replay(&properties(vec![
+                ("replay-from-offset", "10"),
+                ("replay-from-timestamp", "1700000000")
+            ])),
+            Some(SubscribeReplay::Timestamp(1700000000))
+        );
+        assert_eq!(
+            parse_subscribe_replay(&properties(vec![
+                ("replay-from-timestamp", "yesterday"),
+                ("replay-from-offset", "3")
+            ])),
+            Some(SubscribeReplay::Offset(3))
+        );
+        assert_eq!(
+            parse_subscribe_replay(&properties(vec![("app", "analytics")])),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn get_sub_start_offset_test() {
+        let path = format!(
+            "{}/../../config/mqtt-server.toml",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        init_broker_mqtt_conf_by_path(&path);
+
+        let message_storage = MessageStorage::new(Arc::new(MemoryStorageAdapter::new()));
+        let topic_id = unique_id();
+        let group_id = unique_id();
+
+        let mut records = Vec::new();
+        for i in 0..5 {
+            let mut record = Record::build_str(format!("data-{}", i));
+            record.timestamp = 1000 + i * 60;
+            records.push(record);
+        }
+        message_storage
+            .append_topic_message(&topic_id, records)
+            .await
+            .unwrap();
+        message_storage
+            .commit_group_offset(&group_id, &topic_id, 4)
+            .await
+            .unwrap();
+
+        let offset = get_sub_start_offset(&message_storage, &group_id, &topic_id, &None)
+            .await
+            .unwrap();
+        assert_eq!(offset, 4);
+
+        let replay = Some(SubscribeReplay::Offset(1));
+        let offset = get_sub_start_offset(&message_storage, &group_id, &topic_id, &replay)
+            .await
+            .unwrap();
+        assert_eq!(offset, 1);
+
+        let replay = Some(SubscribeReplay::Timestamp(1100));
+        let offset = get_sub_start_offset(&message_storage, &group_id, &topic_id, &replay)
+            .await
+            .unwrap();
+        assert_eq!(offset, 2);
+
+        // after the last message
+        let replay = Some(SubscribeReplay::Timestamp(5000));
+        let offset = get_sub_start_offset(&message_storage, &group_id, &topic_id, &replay)
+            .await
+            .unwrap();
+        assert_eq!(offset, 4);
+    }
 
     #[tokio::test]
     async fn is_share_sub_test() {
@@ -625,6 +897,35 @@ mod tests {
         let result = get_sub_topic_id_list(&metadata_cache, &sub_path).await;
         assert!(result.len() == 1);
         assert_eq!(result.first().unwrap().clone(), topic.topic_id);
+
+        let topic_name = "/test/other".to_string();
+        let other = MqttTopic::new(unique_id(), "c1".to_string(), topic_name.clone());
+        metadata_cache.add_topic(&topic_name, &other);
+
+        let result = get_sub_topic_id_list(&metadata_cache, "/test/+").await;
+        assert_eq!(result.len(), 2);
+        let result = get_sub_topic_id_list(&metadata_cache, "$share/g1/test/#").await;
+        assert_eq!(result.len(), 2);
+        let result = get_sub_topic_id_list(&metadata_cache, "/test/other/#").await;
+        assert_eq!(result, vec![other.topic_id]);
+    }
+
+    #[test]
+    fn sub_path_filter_test() {
+        assert_eq!(sub_path_filter("/sensor/+"), "/sensor/+");
+        assert_eq!(sub_path_filter("$share/g1/sensor/+"), "/sensor/+");
+        assert_eq!(sub_path_filter("$queue/sensor/#"), "/sensor/#");
+        assert_eq!(sub_path_filter("$exclusive/sensor/1"), "/sensor/1");
+        assert_eq!(sub_path_filter("$SYS/broker"), "$SYS/broker");
+
+        assert!(path_regex_match(
+            "/sensor/1".to_string(),
+            "$exclusive/sensor/1".to_string()
+        ));
+        assert!(!path_regex_match(
+            "$SYS/broker".to_string(),
+            "#".to_string()
+        ));
     }
 
     #[tokio::test]
@@ -656,4 +957,35 @@ mod tests {
         let path = "$share/loboxu/*test".to_string();
         assert!(!sub_path_validator(path));
     }
+
+    #[test]
+    fn apply_topic_alias_test() {
+        let mut alias = OutboundTopicAlias::new(1);
+        let publish = Publish::new(Bytes::from("/test/a"), Bytes::from("data"), false);
+
+        let mut packet = MqttPacket::Publish(publish.clone(), None);
+        assert_eq!(
+            apply_topic_alias(&mut alias, &mut packet),
+            Some("/test/a".to_string())
+        );
+        if let MqttPacket::Publish(p, properties) = &packet {
+            assert_eq!(p.topic, Bytes::from("/test/a"));
+            assert_eq!(properties.clone().unwrap().topic_alias, Some(1));
+        }
+
+        let mut packet = MqttPacket::Publish(publish.clone(), None);
+        assert!(apply_topic_alias(&mut alias, &mut packet).is_none());
+        if let MqttPacket::Publish(p, properties) = &packet {
+            assert!(p.topic.is_empty());
+            assert_eq!(properties.clone().unwrap().topic_alias, Some(1));
+        }
+
+        let mut packet = MqttPacket::Publish(publish, None);
+        let mut disabled = OutboundTopicAlias::new(0);
+        assert!(apply_topic_alias(&mut disabled, &mut packet).is_none());
+        if let MqttPacket::Publish(p, properties) = &packet {
+            assert_eq!(p.topic, Bytes::from("/test/a"));
+            assert!(properties.is_none());
+        }
+    }
 }
@@ -19,6 +19,7 @@ use bytes::Bytes;
 use common_base::tools::now_second;
 use log::{debug, error, info};
 use metadata_struct::adapter::record::Record;
+use metadata_struct::mqtt::inflight::{MqttInflightMessage, MqttInflightState};
 use metadata_struct::mqtt::message::MqttMessage;
 use protocol::mqtt::common::{MqttPacket, MqttProtocol, Publish, PublishProperties, QoS};
 use storage_adapter::storage::StorageAdapter;
@@ -27,13 +28,15 @@ use tokio::sync::broadcast::{self};
 use tokio::time::sleep;
 
 use super::sub_common::{
-    loop_commit_offset, min_qos, publish_message_qos0, publish_message_to_client,
-    qos2_send_publish, qos2_send_pubrel, wait_packet_ack,
+    get_sub_start_offset, is_publish_too_large, loop_commit_offset, min_qos, publish_message_qos0,
+    publish_message_to_client, qos2_send_publish, qos2_send_pubrel, retain_as_published,
+    wait_packet_ack_or_reconnect,
 };
 use super::subscribe_manager::SubscribeManager;
-use super::subscriber::Subscriber;
+use super::subscriber::{SubscribeReplay, Subscriber};
 use crate::handler::cache::{CacheManager, QosAckPackageData, QosAckPackageType, QosAckPacketInfo};
 use crate::handler::error::MqttBrokerError;
+use crate::handler::inflight::{inflight_delete, inflight_list, inflight_save, InflightSlot};
 use crate::handler::message::is_message_expire;
 use crate::server::connection_manager::ConnectionManager;
 use crate::server::packet::ResponsePackage;
@@ -104,6 +107,9 @@ where
 
             let (sub_thread_stop_sx, mut sub_thread_stop_rx) = broadcast::channel(1);
 
+            // A push thread started again for the same subscription continues from the group offset
+            let replay = take_exclusive_replay(&self.subscribe_manager, &exclusive_key);
+
             let message_storage = MessageStorage::new(self.message_storage.clone());
             let cache_manager = self.cache_manager.clone();
             let connection_manager = self.connection_manager.clone();
@@ -122,7 +128,14 @@ where
                 let qos = build_pub_qos(&cache_manager, &subscriber);
                 let sub_ids = build_sub_ids(&subscriber);
 
-                let mut offset = match message_storage.get_group_offset(&group_id).await {
+                let mut offset = match get_sub_start_offset(
+                    &message_storage,
+                    &group_id,
+                    &subscriber.topic_id,
+                    &replay,
+                )
+                .await
+                {
                     Ok(offset) => offset,
                     Err(e) => {
                         error!("{}", e);
@@ -133,6 +146,41 @@ where
                     }
                 };
 
+                // Finish the QoS 1/2 exchanges left over by a previous push thread or broker
+                // before continuing with new messages.
+                select! {
+                    val = sub_thread_stop_rx.recv() => {
+                        if let Ok(flag) = val {
+                            if flag {
+                                subscribe_manager.exclusive_push_thread.remove(&exclusive_key);
+                                return;
+                            }
+                        }
+                    },
+                    val = retry_inflight_message(
+                        &connection_manager,
+                        &message_storage,
+                        &cache_manager,
+                        &subscriber,
+                        &group_id,
+                        &sub_ids,
+                        &sub_thread_stop_sx,
+                    ) => {
+                        match val {
+                            Ok(Some(inflight_offset)) => {
+                                offset = offset.max(inflight_offset + 1);
+                            }
+                            Ok(None) => {}
+                            Err(e) => {
+                                error!(
+                                    "Retry inflight message failed, failure message: {},topic:{},group{}",
+                                    e, subscriber.topic_id, group_id
+                                );
+                            }
+                        }
+                    }
+                }
+
                 loop {
                     select! {
                         val = sub_thread_stop_rx.recv() =>{
@@ -187,6 +235,16 @@ where
     }
 }
 
+fn take_exclusive_replay(
+    subscribe_manager: &Arc<SubscribeManager>,
+    key: &str,
+) -> Option<SubscribeReplay> {
+    subscribe_manager
+        .exclusive_subscribe
+        .get_mut(key)
+        .and_then(|mut subscriber| subscriber.replay.take())
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn pub_message<S>(
     connection_manager: &Arc<ConnectionManager>,
@@ -224,6 +282,7 @@ where
             subscriber,
             cache_manager,
             sub_ids,
+            None,
         )
         .await?
         {
@@ -233,7 +292,7 @@ where
         };
 
         let pkid = sub_pub_param.pkid;
-        match qos {
+        let inflight = match qos {
             QoS::AtMostOnce => {
                 publish_message_qos0(
                     cache_manager,
@@ -242,68 +301,201 @@ where
                     sub_thread_stop_sx,
                 )
                 .await;
+                None
             }
 
-            QoS::AtLeastOnce => {
-                let (wait_puback_sx, _) = broadcast::channel(1);
-                cache_manager.add_ack_packet(
-                    &client_id,
+            QoS::AtLeastOnce | QoS::ExactlyOnce => {
+                let state = if *qos == QoS::AtLeastOnce {
+                    MqttInflightState::WaitPubAck
+                } else {
+                    MqttInflightState::WaitPubRec
+                };
+                let inflight = MqttInflightMessage {
+                    client_id: client_id.clone(),
                     pkid,
-                    QosAckPacketInfo {
-                        sx: wait_puback_sx.clone(),
-                        create_time: now_second(),
-                    },
-                );
+                    state,
+                    group_id: group_id.to_owned(),
+                    topic_id: subscriber.topic_id.clone(),
+                    offset: record_offset,
+                    create_time: now_second(),
+                };
+                inflight_save(cache_manager, inflight.clone());
 
-                exclusive_publish_message_qos1(
+                match publish_inflight_message(
                     cache_manager,
                     connection_manager,
                     &sub_pub_param,
+                    inflight.clone(),
                     sub_thread_stop_sx,
-                    &wait_puback_sx,
                 )
-                .await?;
-
-                cache_manager.remove_pkid_info(&client_id, pkid);
-                cache_manager.remove_ack_packet(&client_id, pkid);
+                .await
+                {
+                    // The message does not fit the client's Maximum Packet Size and is dropped,
+                    // the offset moves forward as if it was delivered.
+                    Ok(()) | Err(MqttBrokerError::PacketLengthError(_)) => {}
+                    Err(e) => {
+                        // The offset is not moved forward, the message is pushed again with a new pkid.
+                        complete_inflight_message(cache_manager, &inflight);
+                        return Err(e);
+                    }
+                }
+                Some(inflight)
             }
+        };
 
-            QoS::ExactlyOnce => {
-                let (wait_ack_sx, _) = broadcast::channel(1);
-                cache_manager.add_ack_packet(
-                    &client_id,
-                    pkid,
-                    QosAckPacketInfo {
-                        sx: wait_ack_sx.clone(),
-                        create_time: now_second(),
-                    },
-                );
+        // commit offset
+        loop_commit_offset(
+            message_storage,
+            &subscriber.topic_id,
+            group_id,
+            record_offset,
+        )
+        .await;
 
-                exclusive_publish_message_qos2(
-                    cache_manager,
-                    connection_manager,
-                    &sub_pub_param,
-                    sub_thread_stop_sx,
-                    &wait_ack_sx,
-                )
-                .await?;
+        // The inflight state is only dropped after the offset is committed, so a crash in
+        // between ends up retransmitting the message instead of losing it.
+        if let Some(inflight) = inflight {
+            complete_inflight_message(cache_manager, &inflight);
+        }
+    }
+
+    Ok(Some(results.last().unwrap().offset.unwrap()))
+}
+
+// Retransmit the messages of this subscription that were sent to the client but not completed
+// before the push thread stopped, e.g. because the broker restarted. Returns the offset of the
+// last retransmitted message.
+async fn retry_inflight_message<S>(
+    connection_manager: &Arc<ConnectionManager>,
+    message_storage: &MessageStorage<S>,
+    cache_manager: &Arc<CacheManager>,
+    subscriber: &Subscriber,
+    group_id: &str,
+    sub_ids: &[usize],
+    sub_thread_stop_sx: &broadcast::Sender<bool>,
+) -> Result<Option<u64>, MqttBrokerError>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    let mut inflight_list = inflight_list(cache_manager, &subscriber.client_id)
+        .await?
+        .into_iter()
+        .filter(|raw| raw.group_id == group_id)
+        .collect::<Vec<MqttInflightMessage>>();
+    inflight_list.sort_by_key(|raw| raw.offset);
+
+    let mut last_offset = None;
+    for inflight in inflight_list {
+        cache_manager.reserve_pkid(&inflight.client_id, inflight.pkid);
+
+        // A QoS 2 message that already got its PubRec only needs the PubRel again.
+        let qos = if inflight.state == MqttInflightState::WaitPubAck {
+            QoS::AtLeastOnce
+        } else {
+            QoS::ExactlyOnce
+        };
+
+        let record = message_storage
+            .read_topic_message(&subscriber.topic_id, inflight.offset, 1)
+            .await?
+            .into_iter()
+            .find(|raw| raw.offset == Some(inflight.offset));
+
+        let sub_pub_param = if let Some(record) = record {
+            buile_pub_message(
+                record,
+                group_id,
+                &qos,
+                subscriber,
+                cache_manager,
+                sub_ids,
+                Some(inflight.pkid),
+            )
+            .await?
+        } else {
+            None
+        };
 
-                cache_manager.remove_pkid_info(&client_id, pkid);
-                cache_manager.remove_ack_packet(&client_id, pkid);
+        if let Some(mut sub_pub_param) = sub_pub_param {
+            sub_pub_param.publish.dup = true;
+            match publish_inflight_message(
+                cache_manager,
+                connection_manager,
+                &sub_pub_param,
+                inflight.clone(),
+                sub_thread_stop_sx,
+            )
+            .await
+            {
+                Ok(()) | Err(MqttBrokerError::PacketLengthError(_)) => {}
+                Err(e) => {
+                    complete_inflight_message(cache_manager, &inflight);
+                    return Err(e);
+                }
             }
         }
 
-        // commit offset
         loop_commit_offset(
             message_storage,
             &subscriber.topic_id,
             group_id,
-            record_offset,
+            inflight.offset,
         )
         .await;
+
+        complete_inflight_message(cache_manager, &inflight);
+        last_offset = Some(inflight.offset);
     }
+    Ok(last_offset)
+}
 
-    Ok(Some(results.last().unwrap().offset.unwrap()))
+async fn publish_inflight_message(
+    cache_manager: &Arc<CacheManager>,
+    connection_manager: &Arc<ConnectionManager>,
+    sub_pub_param: &SubPublishParam,
+    inflight: MqttInflightMessage,
+    sub_thread_stop_sx: &broadcast::Sender<bool>,
+) -> Result<(), MqttBrokerError> {
+    let (wait_ack_sx, _) = broadcast::channel(1);
+    cache_manager.add_ack_packet(
+        &inflight.client_id,
+        inflight.pkid,
+        QosAckPacketInfo {
+            sx: wait_ack_sx.clone(),
+            create_time: now_second(),
+        },
+    );
+
+    // The slot holds an entry of the client's Receive Maximum until the exchange is completed.
+    let mut slot = InflightSlot::default();
+    if inflight.state == MqttInflightState::WaitPubAck {
+        exclusive_publish_message_qos1(
+            cache_manager,
+            connection_manager,
+            sub_pub_param,
+            sub_thread_stop_sx,
+            &wait_ack_sx,
+            &mut slot,
+        )
+        .await
+    } else {
+        exclusive_publish_message_qos2(
+            cache_manager,
+            connection_manager,
+            sub_pub_param,
+            sub_thread_stop_sx,
+            &wait_ack_sx,
+            &mut slot,
+            inflight,
+        )
+        .await
+    }
+}
+
+fn complete_inflight_message(cache_manager: &Arc<CacheManager>, inflight: &MqttInflightMessage) {
+    inflight_delete(cache_manager, &inflight.client_id, inflight.pkid);
+    cache_manager.remove_pkid_info(&inflight.client_id, inflight.pkid);
+    cache_manager.remove_ack_packet(&inflight.client_id, inflight.pkid);
 }
 
 async fn buile_pub_message(
@@ -313,6 +505,7 @@ async fn buile_pub_message(
     subscriber: &Subscriber,
     cache_manager: &Arc<CacheManager>,
     sub_ids: &[usize],
+    inflight_pkid: Option<u16>,
 ) -> Result<Option<SubPublishParam>, MqttBrokerError> {
     let msg = MqttMessage::decode_record(record.clone())?;
 
@@ -325,11 +518,7 @@ async fn buile_pub_message(
         return Ok(None);
     }
 
-    let retain = if subscriber.preserve_retain {
-        msg.retain
-    } else {
-        false
-    };
+    let retain = retain_as_published(subscriber.preserve_retain, msg.retain);
 
     let mut publish = Publish {
         dup: false,
@@ -351,7 +540,9 @@ async fn buile_pub_message(
         content_type: msg.content_type,
     };
 
-    let pkid = if *qos != QoS::AtMostOnce {
+    let pkid = if let Some(pkid) = inflight_pkid {
+        pkid
+    } else if *qos != QoS::AtMostOnce {
         cache_manager.get_pkid(&subscriber.client_id).await
     } else {
         0
@@ -378,6 +569,7 @@ pub async fn exclusive_publish_message_qos1(
     sub_pub_param: &SubPublishParam,
     stop_sx: &broadcast::Sender<bool>,
     wait_puback_sx: &broadcast::Sender<QosAckPackageData>,
+    slot: &mut InflightSlot,
 ) -> Result<(), MqttBrokerError> {
     let mut retry_times = 0;
     let mut publish = sub_pub_param.publish.clone();
@@ -396,14 +588,10 @@ pub async fn exclusive_publish_message_qos1(
                 continue;
             };
 
-        if let Some(conn) = metadata_cache.get_connection(connect_id) {
-            if publish.payload.len() > (conn.max_packet_size as usize) {
-                return Ok(());
-            }
-        }
+        slot.bind(metadata_cache, connect_id).await;
 
         retry_times += 1;
-        publish.dup = retry_times >= 2;
+        publish.dup = sub_pub_param.publish.dup || retry_times >= 2;
 
         let mut contain_properties = false;
         if let Some(protocol) = connection_manager.get_connect_protocol(connect_id) {
@@ -424,11 +612,22 @@ pub async fn exclusive_publish_message_qos1(
             }
         };
 
+        if is_publish_too_large(metadata_cache, connection_manager, &resp) {
+            return Err(MqttBrokerError::PacketLengthError(publish.payload.len()));
+        }
+
         match publish_message_to_client(resp, sub_pub_param, connection_manager, metadata_cache)
             .await
         {
             Ok(_) => {
-                if let Some(data) = wait_packet_ack(wait_puback_sx).await {
+                if let Some(data) = wait_packet_ack_or_reconnect(
+                    wait_puback_sx,
+                    metadata_cache,
+                    &sub_pub_param.subscribe.client_id,
+                    connect_id,
+                )
+                .await
+                {
                     if data.ack_type == QosAckPackageType::PubAck && data.pkid == sub_pub_param.pkid
                     {
                         return Ok(());
@@ -450,34 +649,57 @@ pub async fn exclusive_publish_message_qos1(
 // wait pubrec message
 // send pubrel message
 // wait pubcomp message
+// The exchange starts at the step recorded in the inflight state, which is moved to WaitPubComp
+// once the PubRec is received.
 pub async fn exclusive_publish_message_qos2(
     metadata_cache: &Arc<CacheManager>,
     connection_manager: &Arc<ConnectionManager>,
     sub_pub_param: &SubPublishParam,
     stop_sx: &broadcast::Sender<bool>,
     wait_ack_sx: &broadcast::Sender<QosAckPackageData>,
+    slot: &mut InflightSlot,
+    mut inflight: MqttInflightMessage,
 ) -> Result<(), MqttBrokerError> {
-    // 1. send Publish to Client
-    qos2_send_publish(connection_manager, metadata_cache, sub_pub_param, stop_sx).await?;
+    let client_id = &sub_pub_param.subscribe.client_id;
 
-    // 2. wait PubRec ack
-    loop {
-        if let Ok(flag) = stop_sx.subscribe().try_recv() {
-            if flag {
-                return Ok(());
+    if inflight.state == MqttInflightState::WaitPubRec {
+        // 1. send Publish to Client
+        let mut connect_id = wait_connect_id(metadata_cache, client_id).await;
+        slot.bind(metadata_cache, connect_id).await;
+        qos2_send_publish(connection_manager, metadata_cache, sub_pub_param, stop_sx).await?;
+
+        // 2. wait PubRec ack
+        let mut retry_param = sub_pub_param.clone();
+        retry_param.publish.dup = true;
+        loop {
+            if let Ok(flag) = stop_sx.subscribe().try_recv() {
+                if flag {
+                    return Ok(());
+                }
             }
-        }
-        if let Some(data) = wait_packet_ack(wait_ack_sx).await {
-            if data.ack_type == QosAckPackageType::PubRec && data.pkid == sub_pub_param.pkid {
-                break;
+            if let Some(data) =
+                wait_packet_ack_or_reconnect(wait_ack_sx, metadata_cache, client_id, connect_id)
+                    .await
+            {
+                if data.ack_type == QosAckPackageType::PubRec && data.pkid == sub_pub_param.pkid {
+                    break;
+                }
+            } else {
+                connect_id = wait_connect_id(metadata_cache, client_id).await;
+                slot.bind(metadata_cache, connect_id).await;
+                qos2_send_publish(connection_manager, metadata_cache, &retry_param, stop_sx)
+                    .await?;
             }
-        } else {
-            qos2_send_publish(connection_manager, metadata_cache, sub_pub_param, stop_sx).await?;
+            sleep(Duration::from_millis(1)).await;
         }
-        sleep(Duration::from_millis(1)).await;
+
+        inflight.state = MqttInflightState::WaitPubComp;
+        inflight_save(metadata_cache, inflight);
     }
 
     // 3. send PubRel to Client
+    let mut connect_id = wait_connect_id(metadata_cache, client_id).await;
+    slot.bind(metadata_cache, connect_id).await;
     qos2_send_pubrel(metadata_cache, sub_pub_param, connection_manager, stop_sx).await;
 
     // 4. wait pub comp
@@ -487,11 +709,15 @@ pub async fn exclusive_publish_message_qos2(
                 break;
             }
         }
-        if let Some(data) = wait_packet_ack(wait_ack_sx).await {
+        if let Some(data) =
+            wait_packet_ack_or_reconnect(wait_ack_sx, metadata_cache, client_id, connect_id).await
+        {
             if data.ack_type == QosAckPackageType::PubComp && data.pkid == sub_pub_param.pkid {
                 break;
             }
         } else {
+            connect_id = wait_connect_id(metadata_cache, client_id).await;
+            slot.bind(metadata_cache, connect_id).await;
             qos2_send_pubrel(metadata_cache, sub_pub_param, connection_manager, stop_sx).await;
         }
         sleep(Duration::from_millis(1)).await;
@@ -499,6 +725,15 @@ pub async fn exclusive_publish_message_qos2(
     Ok(())
 }
 
+async fn wait_connect_id(metadata_cache: &Arc<CacheManager>, client_id: &str) -> u64 {
+    loop {
+        if let Some(id) = metadata_cache.get_connect_id(client_id) {
+            return id;
+        }
+        sleep(Duration::from_secs(1)).await;
+    }
+}
+
 fn build_group_name(subscriber: &Subscriber) -> String {
     format!(
         "system_sub_{}_{}_{}",
@@ -521,3 +756,40 @@ fn build_sub_ids(subscriber: &Subscriber) -> Vec<usize> {
 
 #[cfg(test)]
 mod test {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use grpc_clients::pool::ClientPool;
+
+    use super::take_exclusive_replay;
+    use crate::handler::cache::CacheManager;
+    use crate::subscribe::subscribe_manager::SubscribeManager;
+    use crate::subscribe::subscriber::{SubscribeReplay, Subscriber};
+
+    #[test]
+    fn take_exclusive_replay_test() {
+        let client_pool = Arc::new(ClientPool::new(1));
+        let cache_manager = Arc::new(CacheManager::new(client_pool.clone(), "test".to_string()));
+        let subscribe_manager = Arc::new(SubscribeManager::new(cache_manager, client_pool));
+
+        subscribe_manager.exclusive_subscribe.insert(
+            "key".to_string(),
+            Subscriber {
+                client_id: "c1".to_string(),
+                replay: Some(SubscribeReplay::Timestamp(1700000000)),
+                ..Default::default()
+            },
+        );
+
+        // The replay is only used by the first push thread of the subscription
+        assert_eq!(
+            take_exclusive_replay(&subscribe_manager, "key"),
+            Some(SubscribeReplay::Timestamp(1700000000))
+        );
+        assert_eq!(take_exclusive_replay(&subscribe_manager, "key"), None);
+        assert!(subscribe_manager.exclusive_subscribe.contains_key("key"));
+        assert_eq!(take_exclusive_replay(&subscribe_manager, "unknown"), None);
+    }
+}
@@ -16,8 +16,9 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use bytes::Bytes;
-use common_base::tools::now_second;
+use common_base::tools::{now_mills, now_second};
 use log::{error, info};
+use metadata_struct::mqtt::inflight::{MqttInflightMessage, MqttInflightState};
 use metadata_struct::mqtt::message::MqttMessage;
 use protocol::mqtt::common::{MqttPacket, MqttProtocol, Publish, PublishProperties, QoS};
 use storage_adapter::storage::StorageAdapter;
@@ -26,12 +27,15 @@ use tokio::sync::broadcast::{self, Sender};
 use tokio::time::sleep;
 
 use super::sub_common::{
-    loop_commit_offset, min_qos, publish_message_qos0, publish_message_to_client,
-    qos2_send_publish, qos2_send_pubrel, wait_packet_ack,
+    get_sub_start_offset, is_publish_too_large, loop_commit_offset, min_qos, publish_message_qos0,
+    publish_message_to_client, qos2_send_publish, qos2_send_pubrel, retain_as_published,
+    wait_packet_ack, wait_packet_ack_or_reconnect,
 };
+use super::sub_share_strategy::ShareSubDispatcher;
 use super::subscribe_manager::{ShareLeaderSubscribeData, SubscribeManager};
 use crate::handler::cache::{CacheManager, QosAckPackageData, QosAckPackageType, QosAckPacketInfo};
 use crate::handler::error::MqttBrokerError;
+use crate::handler::inflight::{inflight_delete, inflight_list, inflight_save, InflightSlot};
 use crate::handler::message::is_message_expire;
 use crate::server::connection_manager::ConnectionManager;
 use crate::server::packet::ResponsePackage;
@@ -119,17 +123,13 @@ where
                 .share_leader_push_thread
                 .contains_key(&share_leader_key)
             {
-                self.push_by_round_robin(
-                    share_leader_key,
-                    sub_data,
-                    self.subscribe_manager.clone(),
-                )
-                .await;
+                self.push_by_strategy(share_leader_key, sub_data, self.subscribe_manager.clone())
+                    .await;
             }
         }
     }
 
-    async fn push_by_round_robin(
+    async fn push_by_strategy(
         &self,
         share_leader_key: String,
         sub_data: ShareLeaderSubscribeData,
@@ -141,12 +141,18 @@ where
             "system_sub_{}_{}_{}",
             sub_data.group_name, sub_data.sub_name, sub_data.topic_id
         );
-        let cursor_point = 0;
-
         let message_storage = MessageStorage::new(self.message_storage.clone());
 
-        // get current offset by group
-        let mut offset = match message_storage.get_group_offset(&group_id).await {
+        // The group always continues from its committed offset, a replay only rewinds the member
+        // that asked for it, see replay_to_share_member
+        let mut offset = match get_sub_start_offset(
+            &message_storage,
+            &group_id,
+            &sub_data.topic_id,
+            &None,
+        )
+        .await
+        {
             Ok(offset) => offset,
             Err(e) => {
                 error!("{}", e);
@@ -168,8 +174,34 @@ where
                 sub_data.group_name, sub_data.sub_name, sub_data.topic_name
             );
 
+            let mut dispatcher = ShareSubDispatcher::new(
+                cache_manager
+                    .get_cluster_info()
+                    .share_sub
+                    .get_strategy(&sub_data.group_name),
+            );
+
             let mut sub_list: Vec<Subscriber> =
                 build_share_leader_sub_list(&subscribe_manager, &share_leader_key);
+            retry_share_inflight_message(
+                &connection_manager,
+                &cache_manager,
+                &sub_list,
+                &group_id,
+                &sub_thread_stop_sx,
+            )
+            .await;
+            start_share_member_replay(
+                &subscribe_manager,
+                &connection_manager,
+                &cache_manager,
+                &message_storage,
+                &share_leader_key,
+                &sub_data,
+                &group_id,
+                offset,
+                &sub_thread_stop_sx,
+            );
             let mut pre_times = now_second();
             loop {
                 select! {
@@ -191,7 +223,7 @@ where
                         &sub_data,
                         &sub_list,
                         &group_id,
-                        cursor_point,
+                        &mut dispatcher,
                         offset,
                         &sub_thread_stop_sx
                     ) =>{
@@ -213,9 +245,27 @@ where
                             }
                         }
 
-                        // Refresh the subscriber list of shared subscriptions every second to ensure that new subscribers can get messages in time.
+                        // Refresh the subscriber list and the dispatch strategy of shared subscriptions every second
+                        // to ensure that new subscribers and strategy changes take effect in time.
                         if now_second() - pre_times >= 1{
                             sub_list = build_share_leader_sub_list(&subscribe_manager, &share_leader_key);
+                            start_share_member_replay(
+                                &subscribe_manager,
+                                &connection_manager,
+                                &cache_manager,
+                                &message_storage,
+                                &share_leader_key,
+                                &sub_data,
+                                &group_id,
+                                offset,
+                                &sub_thread_stop_sx,
+                            );
+                            dispatcher.set_strategy(
+                                cache_manager
+                                    .get_cluster_info()
+                                    .share_sub
+                                    .get_strategy(&sub_data.group_name),
+                            );
                             pre_times = now_second();
                         }
                     }
@@ -237,13 +287,17 @@ async fn read_message_process<S>(
     sub_data: &ShareLeaderSubscribeData,
     sub_list: &[Subscriber],
     group_id: &str,
-    mut cursor_point: usize,
+    dispatcher: &mut ShareSubDispatcher,
     offset: u64,
     stop_sx: &Sender<bool>,
 ) -> Result<Option<u64>, MqttBrokerError>
 where
     S: StorageAdapter + Sync + Send + 'static + Clone,
 {
+    if sub_list.is_empty() {
+        return Ok(None);
+    }
+
     let record_num = calc_record_num(sub_list.len());
 
     let results = message_storage
@@ -268,8 +322,14 @@ where
                 break;
             }
 
-            cursor_point = choose_available_sub(cursor_point, sub_list);
-            let subscribe = sub_list.get(cursor_point).unwrap();
+            let index = dispatcher.choose(
+                cache_manager,
+                sub_list,
+                &msg.client_id,
+                &sub_data.topic_name,
+                loop_times,
+            );
+            let subscribe = sub_list.get(index).unwrap();
 
             if let Some((mut publish, properties)) =
                 build_publish(cache_manager, subscribe, &sub_data.topic_name, &msg)
@@ -297,10 +357,12 @@ where
                     message_storage,
                     sub_pub_param,
                     record.offset.unwrap(),
+                    true,
                     stop_sx,
                 )
                 .await
                 {
+                    dispatcher.delivered(subscribe);
                     break;
                 }
             }
@@ -323,21 +385,15 @@ fn try_loop_times(sub_len: usize) -> usize {
     sub_len * 2
 }
 
-fn choose_available_sub(cursor_point: usize, sub_list: &[Subscriber]) -> usize {
-    let current_point = cursor_point + 1;
-    if current_point < sub_list.len() {
-        current_point
-    } else {
-        0
-    }
-}
-
+// A replay delivers messages the group has already moved past, so it does not commit their offsets
+#[allow(clippy::too_many_arguments)]
 async fn qos_publish<S>(
     connection_manager: &Arc<ConnectionManager>,
     cache_manager: &Arc<CacheManager>,
     message_storage: &MessageStorage<S>,
     sub_pub_param: SubPublishParam,
     offset: u64,
+    commit_offset: bool,
     stop_sx: &Sender<bool>,
 ) -> bool
 where
@@ -359,6 +415,10 @@ where
                     create_time: now_second(),
                 },
             );
+            inflight_save(
+                cache_manager,
+                build_share_inflight(&sub_pub_param, MqttInflightState::WaitPubAck, offset),
+            );
 
             match share_leader_publish_message_qos1(
                 cache_manager,
@@ -370,6 +430,11 @@ where
             {
                 Ok(()) => {
                     // remove data
+                    inflight_delete(
+                        cache_manager,
+                        &sub_pub_param.subscribe.client_id,
+                        sub_pub_param.pkid,
+                    );
                     cache_manager
                         .remove_pkid_info(&sub_pub_param.subscribe.client_id, sub_pub_param.pkid);
                     cache_manager
@@ -383,6 +448,15 @@ where
                         sub_pub_param.subscribe.client_id.clone(),
                         e.to_string()
                     );
+                    inflight_delete(
+                        cache_manager,
+                        &sub_pub_param.subscribe.client_id,
+                        sub_pub_param.pkid,
+                    );
+                    cache_manager
+                        .remove_pkid_info(&sub_pub_param.subscribe.client_id, sub_pub_param.pkid);
+                    cache_manager
+                        .remove_ack_packet(&sub_pub_param.subscribe.client_id, sub_pub_param.pkid);
                     false
                 }
             }
@@ -398,6 +472,10 @@ where
                     create_time: now_second(),
                 },
             );
+            inflight_save(
+                cache_manager,
+                build_share_inflight(&sub_pub_param, MqttInflightState::WaitPubRec, offset),
+            );
 
             match share_leader_publish_message_qos2(
                 cache_manager,
@@ -405,6 +483,7 @@ where
                 message_storage,
                 &sub_pub_param,
                 offset,
+                commit_offset,
                 stop_sx,
                 &wait_ack_sx,
             )
@@ -412,7 +491,21 @@ where
             {
                 Ok(()) => true,
                 Err(e) => {
-                    error!("{}", e);
+                    error!(
+                        "SharSub Leader failed to send QOS2 message to {}, error message :{},
+                     trying to deliver the message to another client.",
+                        sub_pub_param.subscribe.client_id.clone(),
+                        e.to_string()
+                    );
+                    inflight_delete(
+                        cache_manager,
+                        &sub_pub_param.subscribe.client_id,
+                        sub_pub_param.pkid,
+                    );
+                    cache_manager
+                        .remove_pkid_info(&sub_pub_param.subscribe.client_id, sub_pub_param.pkid);
+                    cache_manager
+                        .remove_ack_packet(&sub_pub_param.subscribe.client_id, sub_pub_param.pkid);
                     false
                 }
             }
@@ -420,6 +513,22 @@ where
     }
 }
 
+fn build_share_inflight(
+    sub_pub_param: &SubPublishParam,
+    state: MqttInflightState,
+    offset: u64,
+) -> MqttInflightMessage {
+    MqttInflightMessage {
+        client_id: sub_pub_param.subscribe.client_id.clone(),
+        pkid: sub_pub_param.pkid,
+        state,
+        group_id: sub_pub_param.group_id.clone(),
+        topic_id: sub_pub_param.subscribe.topic_id.clone(),
+        offset,
+        create_time: now_second(),
+    }
+}
+
 fn build_publish(
     metadata_cache: &Arc<CacheManager>,
     subscribe: &Subscriber,
@@ -429,11 +538,7 @@ fn build_publish(
     let cluster_qos = metadata_cache.get_cluster_info().protocol.max_qos;
     let qos = min_qos(cluster_qos, subscribe.qos);
 
-    let retain = if subscribe.preserve_retain {
-        msg.retain
-    } else {
-        false
-    };
+    let retain = retain_as_published(subscribe.preserve_retain, msg.retain);
 
     if subscribe.nolocal && (subscribe.client_id == msg.client_id) {
         return None;
@@ -484,14 +589,6 @@ async fn share_leader_publish_message_qos1(
             )));
         };
 
-    if let Some(conn) = metadata_cache.get_connection(connect_id) {
-        if sub_pub_param.publish.payload.len() > (conn.max_packet_size as usize) {
-            return Err(MqttBrokerError::PacketLengthError(
-                sub_pub_param.publish.payload.len(),
-            ));
-        }
-    }
-
     let mut contain_properties = false;
     if let Some(protocol) = connection_manager.get_connect_protocol(connect_id) {
         if MqttProtocol::is_mqtt5(&protocol) {
@@ -514,6 +611,16 @@ async fn share_leader_publish_message_qos1(
         }
     };
 
+    if is_publish_too_large(metadata_cache, connection_manager, &resp) {
+        return Err(MqttBrokerError::PacketLengthError(
+            sub_pub_param.publish.payload.len(),
+        ));
+    }
+
+    // Delivery to this client pauses while its Receive Maximum quota is used up.
+    let mut slot = InflightSlot::default();
+    slot.bind(metadata_cache, connect_id).await;
+
     match publish_message_to_client(
         resp.clone(),
         sub_pub_param,
@@ -523,7 +630,15 @@ async fn share_leader_publish_message_qos1(
     .await
     {
         Ok(_) => {
-            if let Some(data) = wait_packet_ack(wait_puback_sx).await {
+            // A member that disconnects before acknowledging gives the message back to the group.
+            if let Some(data) = wait_packet_ack_or_reconnect(
+                wait_puback_sx,
+                metadata_cache,
+                &sub_pub_param.subscribe.client_id,
+                connect_id,
+            )
+            .await
+            {
                 if data.ack_type == QosAckPackageType::PubAck && data.pkid == sub_pub_param.pkid {
                     return Ok(());
                 }
@@ -545,18 +660,34 @@ async fn share_leader_publish_message_qos1(
 // send pubrel message
 // wait pubcomp message
 
+#[allow(clippy::too_many_arguments)]
 async fn share_leader_publish_message_qos2<S>(
     cache_manager: &Arc<CacheManager>,
     connection_manager: &Arc<ConnectionManager>,
     message_storage: &MessageStorage<S>,
     sub_pub_param: &SubPublishParam,
     offset: u64,
+    commit_offset: bool,
     stop_sx: &broadcast::Sender<bool>,
     wait_ack_sx: &broadcast::Sender<QosAckPackageData>,
 ) -> Result<(), MqttBrokerError>
 where
     S: StorageAdapter + Sync + Send + 'static + Clone,
 {
+    let connect_id =
+        if let Some(id) = cache_manager.get_connect_id(&sub_pub_param.subscribe.client_id) {
+            id
+        } else {
+            return Err(MqttBrokerError::CommonError(format!(
+                "Client [{}] failed to get connect id, no connection available.",
+                sub_pub_param.subscribe.client_id
+            )));
+        };
+
+    // Delivery to this client pauses while its Receive Maximum quota is used up.
+    let mut slot = InflightSlot::default();
+    slot.bind(cache_manager, connect_id).await;
+
     // 1. send Publish to Client
     qos2_send_publish(connection_manager, cache_manager, sub_pub_param, stop_sx).await?;
 
@@ -567,17 +698,32 @@ where
                 return Ok(());
             }
         }
-        if let Some(data) = wait_packet_ack(wait_ack_sx).await {
+        // Until PubRec arrives the message is still owned by the group, a member that
+        // disconnects gives it back so that another member receives it.
+        if let Some(data) = wait_packet_ack_or_reconnect(
+            wait_ack_sx,
+            cache_manager,
+            &sub_pub_param.subscribe.client_id,
+            connect_id,
+        )
+        .await
+        {
             if data.ack_type == QosAckPackageType::PubRec && data.pkid == sub_pub_param.pkid {
                 // When sending a QOS2 message, as long as the pubrec is received, the offset can be submitted,
                 // the pubrel is sent asynchronously, and the pubcomp is waited for. Push the next message at the same time.
-                loop_commit_offset(
-                    message_storage,
-                    &sub_pub_param.subscribe.topic_id,
-                    &sub_pub_param.group_id,
-                    offset,
-                )
-                .await;
+                if commit_offset {
+                    loop_commit_offset(
+                        message_storage,
+                        &sub_pub_param.subscribe.topic_id,
+                        &sub_pub_param.group_id,
+                        offset,
+                    )
+                    .await;
+                }
+                inflight_save(
+                    cache_manager,
+                    build_share_inflight(sub_pub_param, MqttInflightState::WaitPubComp, offset),
+                );
                 break;
             }
         } else {
@@ -587,6 +733,24 @@ where
         }
     }
 
+    share_leader_wait_pubcomp(
+        cache_manager,
+        connection_manager,
+        sub_pub_param,
+        stop_sx,
+        wait_ack_sx,
+    )
+    .await;
+    Ok(())
+}
+
+async fn share_leader_wait_pubcomp(
+    cache_manager: &Arc<CacheManager>,
+    connection_manager: &Arc<ConnectionManager>,
+    sub_pub_param: &SubPublishParam,
+    stop_sx: &broadcast::Sender<bool>,
+    wait_ack_sx: &broadcast::Sender<QosAckPackageData>,
+) {
     // async wait
     // 3. send pub rel
     qos2_send_pubrel(cache_manager, sub_pub_param, connection_manager, stop_sx).await;
@@ -600,6 +764,11 @@ where
         }
         if let Some(data) = wait_packet_ack(wait_ack_sx).await {
             if data.ack_type == QosAckPackageType::PubComp && data.pkid == sub_pub_param.pkid {
+                inflight_delete(
+                    cache_manager,
+                    &sub_pub_param.subscribe.client_id,
+                    sub_pub_param.pkid,
+                );
                 cache_manager
                     .remove_pkid_info(&sub_pub_param.subscribe.client_id, sub_pub_param.pkid);
                 cache_manager
@@ -610,7 +779,243 @@ where
             qos2_send_pubrel(cache_manager, sub_pub_param, connection_manager, stop_sx).await;
         }
     }
+}
 
+/// When the push thread of a group starts, for example after the leader restarted, the messages a
+/// member already acknowledged with PubRec only need the PubRel again. The others were not
+/// committed yet and the group delivers them again from its offset, so their state is dropped.
+async fn retry_share_inflight_message(
+    connection_manager: &Arc<ConnectionManager>,
+    cache_manager: &Arc<CacheManager>,
+    sub_list: &[Subscriber],
+    group_id: &str,
+    stop_sx: &Sender<bool>,
+) {
+    for subscriber in sub_list {
+        let list = match inflight_list(cache_manager, &subscriber.client_id).await {
+            Ok(list) => list,
+            Err(e) => {
+                error!(
+                    "Failed to list inflight messages of client {}, error message: {}",
+                    subscriber.client_id, e
+                );
+                continue;
+            }
+        };
+
+        for inflight in list {
+            if inflight.group_id != group_id {
+                continue;
+            }
+
+            if inflight.state != MqttInflightState::WaitPubComp {
+                inflight_delete(cache_manager, &inflight.client_id, inflight.pkid);
+                continue;
+            }
+
+            cache_manager.reserve_pkid(&inflight.client_id, inflight.pkid);
+            let (wait_ack_sx, _) = broadcast::channel(1);
+            cache_manager.add_ack_packet(
+                &inflight.client_id,
+                inflight.pkid,
+                QosAckPacketInfo {
+                    sx: wait_ack_sx.clone(),
+                    create_time: now_second(),
+                },
+            );
+
+            let sub_pub_param = SubPublishParam::new(
+                subscriber.clone(),
+                Publish {
+                    pkid: inflight.pkid,
+                    ..Default::default()
+                },
+                None,
+                now_mills(),
+                inflight.group_id.clone(),
+                inflight.pkid,
+            );
+            let connection_manager = connection_manager.clone();
+            let cache_manager = cache_manager.clone();
+            let stop_sx = stop_sx.clone();
+            tokio::spawn(async move {
+                share_leader_wait_pubcomp(
+                    &cache_manager,
+                    &connection_manager,
+                    &sub_pub_param,
+                    &stop_sx,
+                    &wait_ack_sx,
+                )
+                .await;
+            });
+        }
+    }
+}
+
+/// Start a replay for every member that subscribed with a replay start point. The replay is taken
+/// off the member so that it runs once, and it delivers the messages before `end_offset`, where
+/// the group currently is, only to that member without moving the offset of the group.
+#[allow(clippy::too_many_arguments)]
+fn start_share_member_replay<S>(
+    subscribe_manager: &Arc<SubscribeManager>,
+    connection_manager: &Arc<ConnectionManager>,
+    cache_manager: &Arc<CacheManager>,
+    message_storage: &MessageStorage<S>,
+    share_leader_key: &str,
+    sub_data: &ShareLeaderSubscribeData,
+    group_id: &str,
+    end_offset: u64,
+    stop_sx: &Sender<bool>,
+) where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    for (sub_key, subscriber) in take_share_leader_replays(subscribe_manager, share_leader_key) {
+        let subscribe_manager = subscribe_manager.clone();
+        let connection_manager = connection_manager.clone();
+        let cache_manager = cache_manager.clone();
+        let message_storage = message_storage.clone();
+        let share_leader_key = share_leader_key.to_owned();
+        let topic_name = sub_data.topic_name.clone();
+        let group_id = group_id.to_owned();
+        let stop_sx = stop_sx.clone();
+        tokio::spawn(async move {
+            if let Err(e) = replay_to_share_member(
+                &subscribe_manager,
+                &connection_manager,
+                &cache_manager,
+                &message_storage,
+                &share_leader_key,
+                &sub_key,
+                subscriber,
+                &topic_name,
+                &group_id,
+                end_offset,
+                &stop_sx,
+            )
+            .await
+            {
+                error!(
+                    "Replay of shared subscription {} for member {} failed, error message: {}",
+                    share_leader_key, sub_key, e
+                );
+            }
+        });
+    }
+}
+
+fn take_share_leader_replays(
+    subscribe_manager: &Arc<SubscribeManager>,
+    key: &str,
+) -> Vec<(String, Subscriber)> {
+    let mut results = Vec::new();
+    if let Some(share_sub) = subscribe_manager.share_leader_subscribe.get(key) {
+        for mut raw in share_sub.sub_list.iter_mut() {
+            if raw.replay.is_some() {
+                let subscriber = raw.value().clone();
+                raw.replay = None;
+                results.push((raw.key().clone(), subscriber));
+            }
+        }
+    }
+    results
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn replay_to_share_member<S>(
+    subscribe_manager: &Arc<SubscribeManager>,
+    connection_manager: &Arc<ConnectionManager>,
+    cache_manager: &Arc<CacheManager>,
+    message_storage: &MessageStorage<S>,
+    share_leader_key: &str,
+    sub_key: &str,
+    subscriber: Subscriber,
+    topic_name: &str,
+    group_id: &str,
+    end_offset: u64,
+    stop_sx: &Sender<bool>,
+) -> Result<(), MqttBrokerError>
+where
+    S: StorageAdapter + Sync + Send + 'static + Clone,
+{
+    let mut offset = get_sub_start_offset(
+        message_storage,
+        group_id,
+        &subscriber.topic_id,
+        &subscriber.replay,
+    )
+    .await?;
+
+    // The progress of the replay is only kept in memory, the offset of the group is not touched
+    while offset < end_offset {
+        let still_member = subscribe_manager
+            .share_leader_subscribe
+            .get(share_leader_key)
+            .map(|share_sub| share_sub.sub_list.contains_key(sub_key))
+            .unwrap_or(false);
+        if !still_member {
+            return Ok(());
+        }
+
+        let record_num = calc_record_num(1).min((end_offset - offset) as usize);
+        let results = message_storage
+            .read_topic_message(&subscriber.topic_id, offset, record_num as u64)
+            .await?;
+        if results.is_empty() {
+            return Ok(());
+        }
+
+        for record in results.iter() {
+            let record_offset = record.offset.unwrap();
+            if record_offset >= end_offset {
+                return Ok(());
+            }
+            offset = record_offset + 1;
+
+            let msg = MqttMessage::decode_record(record.clone())?;
+            if is_message_expire(&msg) {
+                continue;
+            }
+
+            let Some((mut publish, properties)) =
+                build_publish(cache_manager, &subscriber, topic_name, &msg)
+            else {
+                continue;
+            };
+
+            let pkid = if publish.qos != QoS::AtMostOnce {
+                cache_manager.get_pkid(&subscriber.client_id).await
+            } else {
+                0
+            };
+            publish.pkid = pkid;
+
+            let sub_pub_param = SubPublishParam::new(
+                subscriber.clone(),
+                publish,
+                Some(properties),
+                record.timestamp as u128,
+                group_id.to_owned(),
+                pkid,
+            );
+
+            if !qos_publish(
+                connection_manager,
+                cache_manager,
+                message_storage,
+                sub_pub_param,
+                record_offset,
+                false,
+                stop_sx,
+            )
+            .await
+            {
+                return Err(MqttBrokerError::CommonError(format!(
+                    "message at offset {} could not be delivered",
+                    record_offset
+                )));
+            }
+        }
+    }
     Ok(())
 }
 
@@ -628,6 +1033,8 @@ fn build_share_leader_sub_list(
     for (_, sub) in sub_list {
         result.push(sub);
     }
+    // Keep a stable member order so that hash based strategies map keys consistently.
+    result.sort_by(|a, b| a.client_id.cmp(&b.client_id));
     result
 }
 
@@ -644,4 +1051,57 @@ fn calc_record_num(sub_len: usize) -> usize {
 }
 
 #[cfg(test)]
-mod tests {}
+mod tests {
+    use std::sync::Arc;
+
+    use dashmap::DashMap;
+    use grpc_clients::pool::ClientPool;
+
+    use super::take_share_leader_replays;
+    use crate::handler::cache::CacheManager;
+    use crate::subscribe::subscribe_manager::{ShareLeaderSubscribeData, SubscribeManager};
+    use crate::subscribe::subscriber::{SubscribeReplay, Subscriber};
+
+    #[test]
+    fn take_share_leader_replays_test() {
+        let client_pool = Arc::new(ClientPool::new(1));
+        let cache_manager = Arc::new(CacheManager::new(client_pool.clone(), "test".to_string()));
+        let subscribe_manager = Arc::new(SubscribeManager::new(cache_manager, client_pool));
+
+        let sub_list = DashMap::with_capacity(2);
+        sub_list.insert(
+            "c1_path".to_string(),
+            Subscriber {
+                client_id: "c1".to_string(),
+                replay: Some(SubscribeReplay::Offset(3)),
+                ..Default::default()
+            },
+        );
+        sub_list.insert(
+            "c2_path".to_string(),
+            Subscriber {
+                client_id: "c2".to_string(),
+                ..Default::default()
+            },
+        );
+        subscribe_manager.share_leader_subscribe.insert(
+            "key".to_string(),
+            ShareLeaderSubscribeData {
+                group_name: "g1".to_string(),
+                topic_id: "t1".to_string(),
+                topic_name: "/t1".to_string(),
+                sub_name: "/t1".to_string(),
+                sub_list,
+            },
+        );
+
+        // Only the member that asked for a replay gets one, and only once
+        let replays = take_share_leader_replays(&subscribe_manager, "key");
+        assert_eq!(replays.len(), 1);
+        assert_eq!(replays[0].0, "c1_path");
+        assert_eq!(replays[0].1.replay, Some(SubscribeReplay::Offset(3)));
+
+        assert!(take_share_leader_replays(&subscribe_manager, "key").is_empty());
+        assert!(take_share_leader_replays(&subscribe_manager, "unknown").is_empty());
+    }
+}
@@ -0,0 +1,266 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use crc32c::crc32c;
+use metadata_struct::mqtt::cluster::MqttShareSubStrategy;
+use rand::Rng;
+
+use super::subscriber::Subscriber;
+use crate::handler::cache::CacheManager;
+
+// Picks the member of a shared subscription group that receives the next message.
+// `attempt` counts the members that already failed to take the current message, so every
+// strategy moves on to another member when it is retried.
+pub struct ShareSubDispatcher {
+    strategy: MqttShareSubStrategy,
+    cursor: usize,
+    sticky_client_id: Option<String>,
+}
+
+impl ShareSubDispatcher {
+    pub fn new(strategy: MqttShareSubStrategy) -> Self {
+        ShareSubDispatcher {
+            strategy,
+            cursor: 0,
+            sticky_client_id: None,
+        }
+    }
+
+    pub fn set_strategy(&mut self, strategy: MqttShareSubStrategy) {
+        if self.strategy != strategy {
+            self.strategy = strategy;
+            self.sticky_client_id = None;
+        }
+    }
+
+    pub fn choose(
+        &mut self,
+        cache_manager: &Arc<CacheManager>,
+        sub_list: &[Subscriber],
+        publish_client_id: &str,
+        topic_name: &str,
+        attempt: usize,
+    ) -> usize {
+        let len = sub_list.len();
+        match self.strategy {
+            MqttShareSubStrategy::RoundRobin => self.next_cursor(len),
+
+            MqttShareSubStrategy::Random => rand::thread_rng().gen_range(0..len),
+
+            MqttShareSubStrategy::HashClientId => hash_index(publish_client_id, attempt, len),
+
+            MqttShareSubStrategy::HashTopic => hash_index(topic_name, attempt, len),
+
+            MqttShareSubStrategy::Sticky => {
+                if attempt == 0 {
+                    if let Some(client_id) = &self.sticky_client_id {
+                        if let Some(index) = sub_list.iter().position(|s| s.client_id == *client_id)
+                        {
+                            return index;
+                        }
+                    }
+                }
+                self.next_cursor(len)
+            }
+
+            MqttShareSubStrategy::LeastInflight => {
+                let mut indexes: Vec<(isize, usize)> = sub_list
+                    .iter()
+                    .enumerate()
+                    .map(|(index, subscriber)| (inflight_num(cache_manager, subscriber), index))
+                    .collect();
+                indexes.sort();
+                indexes[attempt % len].1
+            }
+        }
+    }
+
+    // Called once a member took the message.
+    pub fn delivered(&mut self, subscriber: &Subscriber) {
+        if self.strategy == MqttShareSubStrategy::Sticky {
+            self.sticky_client_id = Some(subscriber.client_id.clone());
+        }
+    }
+
+    fn next_cursor(&mut self, len: usize) -> usize {
+        self.cursor = (self.cursor + 1) % len;
+        self.cursor
+    }
+}
+
+// The hash has to give the same member on every broker and release, so that the affinity
+// survives a move of the group leader.
+fn hash_index(key: &str, attempt: usize, len: usize) -> usize {
+    ((crc32c(key.as_bytes()) as usize) + attempt) % len
+}
+
+// Members without a connection sort last.
+fn inflight_num(cache_manager: &Arc<CacheManager>, subscriber: &Subscriber) -> isize {
+    if let Some(connect_id) = cache_manager.get_connect_id(&subscriber.client_id) {
+        if let Some(conn) = cache_manager.get_connection(connect_id) {
+            return conn.get_send_qos_message();
+        }
+    }
+    isize::MAX
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use grpc_clients::pool::ClientPool;
+    use metadata_struct::mqtt::cluster::MqttShareSubStrategy;
+    use metadata_struct::mqtt::connection::{ConnectionConfig, MQTTConnection};
+    use metadata_struct::mqtt::session::MqttSession;
+
+    use super::{hash_index, ShareSubDispatcher};
+    use crate::handler::cache::CacheManager;
+    use crate::subscribe::subscriber::Subscriber;
+
+    fn build_sub_list(num: usize) -> Vec<Subscriber> {
+        (0..num)
+            .map(|i| Subscriber {
+                client_id: format!("c{}", i),
+                ..Default::default()
+            })
+            .collect()
+    }
+
+    fn build_cache_manager() -> Arc<CacheManager> {
+        let client_pool = Arc::new(ClientPool::new(1));
+        Arc::new(CacheManager::new(client_pool, "test".to_string()))
+    }
+
+    #[test]
+    fn round_robin_test() {
+        let cache_manager = build_cache_manager();
+        let sub_list = build_sub_list(3);
+        let mut dispatcher = ShareSubDispatcher::new(MqttShareSubStrategy::RoundRobin);
+        let res: Vec<usize> = (0..6)
+            .map(|_| dispatcher.choose(&cache_manager, &sub_list, "p", "t", 0))
+            .collect();
+        assert_eq!(res, vec![1, 2, 0, 1, 2, 0]);
+    }
+
+    #[test]
+    fn random_test() {
+        let cache_manager = build_cache_manager();
+        let sub_list = build_sub_list(3);
+        let mut dispatcher = ShareSubDispatcher::new(MqttShareSubStrategy::Random);
+        for _ in 0..100 {
+            assert!(dispatcher.choose(&cache_manager, &sub_list, "p", "t", 0) < 3);
+        }
+    }
+
+    #[test]
+    fn hash_test() {
+        let cache_manager = build_cache_manager();
+        let sub_list = build_sub_list(5);
+
+        let mut dispatcher = ShareSubDispatcher::new(MqttShareSubStrategy::HashClientId);
+        let first = dispatcher.choose(&cache_manager, &sub_list, "device-1", "t1", 0);
+        for topic in ["t1", "t2", "t3"] {
+            assert_eq!(
+                dispatcher.choose(&cache_manager, &sub_list, "device-1", topic, 0),
+                first
+            );
+        }
+        assert_eq!(
+            dispatcher.choose(&cache_manager, &sub_list, "device-1", "t1", 1),
+            (first + 1) % 5
+        );
+
+        let mut dispatcher = ShareSubDispatcher::new(MqttShareSubStrategy::HashTopic);
+        let first = dispatcher.choose(&cache_manager, &sub_list, "p1", "device/1", 0);
+        for client_id in ["p1", "p2", "p3"] {
+            assert_eq!(
+                dispatcher.choose(&cache_manager, &sub_list, client_id, "device/1", 0),
+                first
+            );
+        }
+    }
+
+    #[test]
+    fn hash_index_stable_test() {
+        // crc32c("123456789") is 0xE3069283
+        assert_eq!(hash_index("123456789", 0, 7), 0xE3069283 % 7);
+        assert_eq!(hash_index("123456789", 1, 7), (0xE3069283 + 1) % 7);
+    }
+
+    #[test]
+    fn sticky_test() {
+        let cache_manager = build_cache_manager();
+        let sub_list = build_sub_list(3);
+        let mut dispatcher = ShareSubDispatcher::new(MqttShareSubStrategy::Sticky);
+
+        let first = dispatcher.choose(&cache_manager, &sub_list, "p", "t", 0);
+        dispatcher.delivered(&sub_list[first]);
+        for _ in 0..3 {
+            assert_eq!(
+                dispatcher.choose(&cache_manager, &sub_list, "p", "t", 0),
+                first
+            );
+        }
+
+        // The sticky member failed, the message moves to another one which becomes sticky.
+        let second = dispatcher.choose(&cache_manager, &sub_list, "p", "t", 1);
+        assert_ne!(second, first);
+        dispatcher.delivered(&sub_list[second]);
+        assert_eq!(
+            dispatcher.choose(&cache_manager, &sub_list, "p", "t", 0),
+            second
+        );
+
+        // The sticky member left the group.
+        let sub_list: Vec<_> = sub_list
+            .into_iter()
+            .filter(|s| s.client_id != format!("c{}", second))
+            .collect();
+        assert!(dispatcher.choose(&cache_manager, &sub_list, "p", "t", 0) < 2);
+    }
+
+    #[test]
+    fn least_inflight_test() {
+        let cache_manager = build_cache_manager();
+        let sub_list = build_sub_list(3);
+
+        for (connect_id, subscriber) in sub_list.iter().enumerate().take(2) {
+            let session = MqttSession::new(subscriber.client_id.clone(), 60, false, None);
+            cache_manager.add_session(subscriber.client_id.clone(), session);
+            let config = ConnectionConfig {
+                connect_id: connect_id as u64,
+                client_id: subscriber.client_id.clone(),
+                receive_maximum: 10,
+                max_packet_size: 100,
+                topic_alias_max: 100,
+                request_problem_info: 0,
+                keep_alive: 60,
+                source_ip_addr: "127.0.0.1".to_string(),
+            };
+            cache_manager.add_connection(connect_id as u64, MQTTConnection::new(config));
+        }
+
+        let conn = cache_manager.get_connection(0).unwrap();
+        conn.send_qos_message_incr();
+        conn.send_qos_message_incr();
+
+        let mut dispatcher = ShareSubDispatcher::new(MqttShareSubStrategy::LeastInflight);
+        assert_eq!(dispatcher.choose(&cache_manager, &sub_list, "p", "t", 0), 1);
+        assert_eq!(dispatcher.choose(&cache_manager, &sub_list, "p", "t", 1), 0);
+        // c2 has no connection and is only chosen last.
+        assert_eq!(dispatcher.choose(&cache_manager, &sub_list, "p", "t", 2), 2);
+    }
+}
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -31,10 +32,11 @@ use tokio::time::sleep;
 
 use super::sub_common::{
     decode_queue_info, decode_share_info, delete_exclusive_topic, get_share_sub_leader,
-    is_queue_sub, is_share_sub, path_regex_match, set_nx_exclusive_topic,
+    is_queue_sub, is_share_sub, parse_subscribe_replay, path_regex_match, set_nx_exclusive_topic,
+    sub_path_filter,
 };
 use crate::handler::cache::CacheManager;
-use crate::subscribe::subscriber::Subscriber;
+use crate::subscribe::subscriber::{SubscribeReplay, Subscriber};
 
 #[derive(Clone, Serialize, Deserialize)]
 pub struct ShareSubShareSub {
@@ -65,6 +67,7 @@ struct ParseShareQueueSubscribeRequest {
     protocol: MqttProtocol,
     subscribe: Subscribe,
     sub_identifier: Option<usize>,
+    replay: Option<SubscribeReplay>,
     filter: Filter,
     sub_name: String,
     group_name: String,
@@ -121,24 +124,42 @@ impl SubscribeManager {
     }
 
     pub async fn parse_subscribe_by_new_topic(&self) {
-        for (topic_name, topic) in self.metadata_cache.topic_info.clone() {
-            for (client_id, sub_list) in self.metadata_cache.subscribe_filter.clone() {
-                for (_, data) in sub_list {
-                    let subscribe = Subscribe {
-                        packet_identifier: 0,
-                        filters: vec![data.filter],
+        let topics: Vec<(String, String)> = self
+            .metadata_cache
+            .topic_info
+            .iter()
+            .map(|topic| (topic.key().clone(), topic.topic_id.clone()))
+            .collect();
+
+        for (topic_name, topic_id) in topics {
+            for (client_id, path) in self
+                .metadata_cache
+                .get_subscribe_filter_by_topic(&topic_name)
+            {
+                let data =
+                    if let Some(sub_list) = self.metadata_cache.subscribe_filter.get(&client_id) {
+                        if let Some(data) = sub_list.get(&path) {
+                            data.clone()
+                        } else {
+                            continue;
+                        }
+                    } else {
+                        continue;
                     };
-                    let subscribe_properties = data.subscribe_properties;
-                    self.parse_subscribe(
-                        topic_name.clone(),
-                        topic.topic_id.clone(),
-                        client_id.clone(),
-                        data.protocol.clone(),
-                        subscribe,
-                        subscribe_properties,
-                    )
-                    .await;
-                }
+
+                let subscribe = Subscribe {
+                    packet_identifier: 0,
+                    filters: vec![data.filter],
+                };
+                self.parse_subscribe(
+                    topic_name.clone(),
+                    topic_id.clone(),
+                    client_id,
+                    data.protocol,
+                    subscribe,
+                    data.subscribe_properties,
+                )
+                .await;
             }
         }
     }
@@ -150,16 +171,32 @@ impl SubscribeManager {
         subscribe: Subscribe,
         subscribe_properties: Option<SubscribeProperties>,
     ) {
-        for (topic_name, topic) in self.metadata_cache.topic_info.clone() {
-            self.parse_subscribe(
-                topic_name,
-                topic.topic_id,
-                client_id.clone(),
-                protocol.clone(),
-                subscribe.clone(),
-                subscribe_properties.clone(),
-            )
-            .await;
+        for filter in subscribe.filters.iter() {
+            let filter_subscribe = Subscribe {
+                packet_identifier: subscribe.packet_identifier,
+                filters: vec![filter.clone()],
+            };
+            for topic_name in self
+                .metadata_cache
+                .topic_trie
+                .match_filter(&sub_path_filter(&filter.path))
+            {
+                let topic_id = if let Some(topic) = self.metadata_cache.topic_info.get(&topic_name)
+                {
+                    topic.topic_id.clone()
+                } else {
+                    continue;
+                };
+                self.parse_subscribe(
+                    topic_name,
+                    topic_id,
+                    client_id.clone(),
+                    protocol.clone(),
+                    filter_subscribe.clone(),
+                    subscribe_properties.clone(),
+                )
+                .await;
+            }
         }
     }
 
@@ -187,61 +224,55 @@ impl SubscribeManager {
     }
 
     pub fn remove_subscribe(&self, client_id: &str, filter_path: &[String]) {
-        for (topic_name, _) in self.metadata_cache.topic_info.clone() {
-            for path in filter_path {
-                if !path_regex_match(topic_name.clone(), path.clone()) {
-                    continue;
-                }
-
-                if is_share_sub(path.clone()) {
-                    let (group_name, sub_name) = decode_share_info(path.clone());
-                    // share leader
-                    for (key, data) in self.share_leader_subscribe.clone() {
-                        let mut flag = false;
-                        for (sub_key, share_sub) in data.sub_list {
-                            if share_sub.client_id == *client_id
-                                && (share_sub.group_name.is_some()
-                                    && share_sub.group_name.unwrap() == group_name)
-                                && share_sub.sub_path == sub_name
-                            {
-                                let mut_data = self.share_leader_subscribe.get_mut(&key).unwrap();
-                                mut_data.sub_list.remove(&sub_key);
-                                flag = true;
-                            }
+        for path in filter_path {
+            if is_share_sub(path.clone()) {
+                let (group_name, sub_name) = decode_share_info(path.clone());
+                // share leader
+                for (key, data) in self.share_leader_subscribe.clone() {
+                    let mut flag = false;
+                    for (sub_key, share_sub) in data.sub_list {
+                        if share_sub.client_id == *client_id
+                            && (share_sub.group_name.is_some()
+                                && share_sub.group_name.unwrap() == group_name)
+                            && share_sub.sub_path == sub_name
+                        {
+                            let mut_data = self.share_leader_subscribe.get_mut(&key).unwrap();
+                            mut_data.sub_list.remove(&sub_key);
+                            flag = true;
                         }
+                    }
 
-                        if flag {
-                            if let Some(sx) = self.share_leader_push_thread.get(&key) {
-                                match sx.send(true) {
-                                    Ok(_) => {}
-                                    Err(e) => error!("{}", e),
-                                }
+                    if flag {
+                        if let Some(sx) = self.share_leader_push_thread.get(&key) {
+                            match sx.send(true) {
+                                Ok(_) => {}
+                                Err(e) => error!("{}", e),
                             }
                         }
                     }
+                }
 
-                    // share follower
-                    for (key, data) in self.share_follower_subscribe.clone() {
-                        if data.client_id == *client_id && data.filter.path == *path {
-                            self.share_follower_subscribe.remove(&key);
-                            if let Some(sx) = self.share_follower_resub_thread.get(&key) {
-                                match sx.send(true) {
-                                    Ok(_) => {}
-                                    Err(e) => error!("{}", e),
-                                }
+                // share follower
+                for (key, data) in self.share_follower_subscribe.clone() {
+                    if data.client_id == *client_id && data.filter.path == *path {
+                        self.share_follower_subscribe.remove(&key);
+                        if let Some(sx) = self.share_follower_resub_thread.get(&key) {
+                            match sx.send(true) {
+                                Ok(_) => {}
+                                Err(e) => error!("{}", e),
                             }
                         }
                     }
-                } else {
-                    for (key, subscriber) in self.exclusive_subscribe.clone() {
-                        if subscriber.client_id == *client_id && subscriber.sub_path == *path {
-                            if let Some(sx) = self.exclusive_push_thread.get(&key) {
-                                match sx.send(true) {
-                                    Ok(_) => {}
-                                    Err(e) => error!("{}", e),
-                                }
-                                self.exclusive_subscribe.remove(&key);
+                }
+            } else {
+                for (key, subscriber) in self.exclusive_subscribe.clone() {
+                    if subscriber.client_id == *client_id && subscriber.sub_path == *path {
+                        if let Some(sx) = self.exclusive_push_thread.get(&key) {
+                            match sx.send(true) {
+                                Ok(_) => {}
+                                Err(e) => error!("{}", e),
                             }
+                            self.exclusive_subscribe.remove(&key);
                         }
                     }
                 }
@@ -249,11 +280,15 @@ impl SubscribeManager {
         }
     }
 
+    // Take the lock of every `$exclusive` filter for the session of the client. The filters
+    // that could not be locked are returned with their reason code, the others can be subscribed.
     pub async fn save_exclusive_subscribe(
         &self,
-        subscribe: Subscribe,
-    ) -> Result<Option<SubscribeReasonCode>, CommonError> {
-        for filter in subscribe.filters.clone() {
+        client_id: &str,
+        subscribe: &Subscribe,
+    ) -> Result<HashMap<String, SubscribeReasonCode>, CommonError> {
+        let mut rejected = HashMap::new();
+        for filter in subscribe.filters.iter() {
             if !topic_util::is_exclusive_sub(&filter.path) {
                 continue;
             }
@@ -264,61 +299,58 @@ impl SubscribeManager {
                 .exclusive_subscription_available
                 == AvailableFlag::Disable
             {
-                return Ok(Some(SubscribeReasonCode::ExclusiveSubscriptionDisabled));
+                rejected.insert(
+                    filter.path.clone(),
+                    SubscribeReasonCode::ExclusiveSubscriptionDisabled,
+                );
+                continue;
             }
             let topic_name = topic_util::decode_exclusive_sub_path_to_topic_name(&filter.path);
-            if !set_nx_exclusive_topic(self.client_pool.clone(), topic_name.to_owned())
-                .await?
-                .success
+            if !set_nx_exclusive_topic(
+                self.client_pool.clone(),
+                topic_name.to_owned(),
+                client_id.to_owned(),
+            )
+            .await?
+            .success
             {
-                return Ok(Some(SubscribeReasonCode::TopicSubscribed));
+                rejected.insert(filter.path.clone(), SubscribeReasonCode::TopicSubscribed);
             }
         }
-        Ok(None)
+        Ok(rejected)
     }
 
     pub async fn remove_exclusive_subscribe(
         &self,
+        client_id: &str,
         un_subscribe: Unsubscribe,
     ) -> Result<(), CommonError> {
-        for filter in un_subscribe.filters.clone() {
-            self.remove_exclusive_subscribe_by_sub_path(filter.clone())
-                .await?;
+        for un_sub_path in un_subscribe.filters.iter() {
+            if !topic_util::is_exclusive_sub(un_sub_path) {
+                continue;
+            }
+            let topic_name = topic_util::decode_exclusive_sub_path_to_topic_name(un_sub_path);
+            delete_exclusive_topic(
+                self.client_pool.clone(),
+                topic_name.to_owned(),
+                client_id.to_owned(),
+            )
+            .await?;
         }
         Ok(())
     }
 
+    // The locks belong to the session, they are released once the session is gone.
     pub async fn remove_exclusive_subscribe_by_client_id(
         &self,
         client_id: &str,
     ) -> Result<(), CommonError> {
-        for (_, subscriber) in self.exclusive_subscribe.clone() {
-            if subscriber.client_id == *client_id {
-                self.remove_exclusive_subscribe_by_sub_path(subscriber.sub_path)
-                    .await?;
-            }
-        }
-        Ok(())
-    }
-
-    async fn remove_exclusive_subscribe_by_sub_path(
-        &self,
-        un_sub_path: String,
-    ) -> Result<(), CommonError> {
-        if !topic_util::is_exclusive_sub(&un_sub_path) {
-            return Ok(());
-        }
-        if self
-            .metadata_cache
-            .get_cluster_info()
-            .feature
-            .exclusive_subscription_available
-            == AvailableFlag::Disable
-        {
-            return Ok(());
-        }
-        let topic_name = topic_util::decode_exclusive_sub_path_to_topic_name(&un_sub_path);
-        delete_exclusive_topic(self.client_pool.clone(), topic_name.to_owned()).await?;
+        delete_exclusive_topic(
+            self.client_pool.clone(),
+            "".to_string(),
+            client_id.to_owned(),
+        )
+        .await?;
         Ok(())
     }
 
@@ -350,6 +382,7 @@ impl SubscribeManager {
                             req.client_id.clone(),
                             req.protocol.clone(),
                             req.sub_identifier,
+                            req.replay.clone(),
                             req.filter.clone(),
                             req.group_name.clone(),
                             req.sub_name.clone(),
@@ -393,6 +426,7 @@ impl SubscribeManager {
         } else {
             None
         };
+        let replay = parse_subscribe_replay(&subscribe_properties);
 
         for filter in subscribe.filters.clone() {
             if is_share_sub(filter.path.clone()) {
@@ -403,6 +437,7 @@ impl SubscribeManager {
                     protocol: protocol.clone(),
                     subscribe: subscribe.clone(),
                     sub_identifier,
+                    replay: replay.clone(),
                     filter,
                     sub_name: "".to_string(),
                     group_name: "".to_string(),
@@ -416,6 +451,7 @@ impl SubscribeManager {
                     protocol: protocol.clone(),
                     subscribe: subscribe.clone(),
                     sub_identifier,
+                    replay: replay.clone(),
                     filter,
                     sub_name: "".to_string(),
                     group_name: "".to_string(),
@@ -428,6 +464,7 @@ impl SubscribeManager {
                     client_id.clone(),
                     protocol.clone(),
                     sub_identifier,
+                    replay.clone(),
                     filter,
                 );
             }
@@ -442,6 +479,7 @@ impl SubscribeManager {
         client_id: String,
         protocol: MqttProtocol,
         sub_identifier: Option<usize>,
+        replay: Option<SubscribeReplay>,
         filter: Filter,
         group_name: String,
         sub_name: String,
@@ -461,6 +499,7 @@ impl SubscribeManager {
             retain_forward_rule: filter.retain_forward_rule.clone(),
             subscription_identifier: sub_identifier,
             sub_path: filter.path.clone(),
+            replay,
         };
         if let Some(share_sub) = self.share_leader_subscribe.get_mut(&share_leader_key) {
             share_sub.sub_list.insert(leader_sub_key, sub);
@@ -507,6 +546,7 @@ impl SubscribeManager {
         self.share_follower_subscribe.insert(key, share_sub);
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn parse_exclusive_subscribe(
         &self,
         topic_name: String,
@@ -514,6 +554,7 @@ impl SubscribeManager {
         client_id: String,
         protocol: MqttProtocol,
         sub_identifier: Option<usize>,
+        replay: Option<SubscribeReplay>,
         filter: Filter,
     ) {
         if path_regex_match(topic_name.clone(), filter.path.clone()) {
@@ -530,6 +571,7 @@ impl SubscribeManager {
                 retain_forward_rule: filter.retain_forward_rule.clone(),
                 subscription_identifier: sub_identifier,
                 sub_path: filter.path.clone(),
+                replay,
             };
 
             self.exclusive_subscribe.insert(key, sub);
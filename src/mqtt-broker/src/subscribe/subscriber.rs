@@ -28,6 +28,14 @@ pub struct Subscriber {
     pub preserve_retain: bool,
     pub retain_forward_rule: RetainForwardRule,
     pub subscription_identifier: Option<usize>,
+    pub replay: Option<SubscribeReplay>,
+}
+
+// Where a new subscription starts reading the topic instead of the group's committed offset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SubscribeReplay {
+    Timestamp(u64),
+    Offset(u64),
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -81,6 +81,16 @@ pub enum PlacementCenterError {
     #[error("segment {0} state cache error, server current state {1}, passed state {2}")]
     SegmentStateError(String, String, String),
 
+    #[error(
+        "Segment {0} state is {1}, only segments that have not been written can be reassigned"
+    )]
+    NoAllowReassignSegment(String, String),
+
+    #[error(
+        "Segment {0} must have {1} replicas as configured on the shard, but {2} nodes were given"
+    )]
+    SegmentReplicaNumMismatch(String, u32, u32),
+
     #[error("Segment {0} state is {1} and no deletion is allowed")]
     NoAllowDeleteSegment(String, String),
 
@@ -121,4 +131,49 @@ pub enum PlacementCenterError {
 
     #[error("Segment {0} is in the wrong state. It should not be sealed.")]
     SegmentWrongState(String),
+
+    #[error("Namespace {0} does not exist")]
+    NamespaceDoesNotExist(String),
+
+    #[error("Namespace {0} still has {1} shards and cannot be deleted")]
+    NamespaceNotEmpty(String, usize),
+
+    #[error("Namespace {0} has reached its quota of {1} shards")]
+    NamespaceShardQuotaExceeded(String, u32),
+
+    #[error("Group {0} does not exist")]
+    GroupDoesNotExist(String),
+
+    #[error("Member {1} is not in group {0}, it needs to join the group again")]
+    GroupMemberDoesNotExist(String, String),
+
+    #[error("Invalid group assign strategy: {0}")]
+    InvalidGroupAssignStrategy(String),
+
+    #[error("Group session timeout {0}ms is out of the range [{1}ms, {2}ms]")]
+    InvalidGroupSessionTimeout(u64, u64, u64),
+
+    #[error("Member {1} of group {0} is in generation {2}, but the current generation is {3}")]
+    GroupGenerationFenced(String, String, u64, u64),
+
+    #[error("Shard {2} of group {0} is not assigned to member {1}")]
+    GroupShardNotAssigned(String, String, String),
+
+    #[error("Group {0} has active members, offsets can only be committed by its members")]
+    GroupHasActiveMembers(String),
+
+    #[error("Invalid auto subscribe rule: {0}")]
+    InvalidAutoSubscribeRule(String),
+
+    #[error("Rule [{0}] does not exist")]
+    RuleDoesNotExist(String),
+
+    #[error("Schema [{0}] does not exist")]
+    SchemaDoesNotExist(String),
+
+    #[error("Schema [{0}] is still bound to topic filters {1:?}, unbind it first")]
+    SchemaIsStillBound(String, Vec<String>),
+
+    #[error("Schema [{0}] is not bound to topic filter [{1}]")]
+    SchemaBindingDoesNotExist(String, String),
 }
@@ -14,38 +14,86 @@
 
 use std::sync::Arc;
 
+use common_base::tools::now_mills;
+use dashmap::mapref::one::RefMut;
 use dashmap::DashMap;
+use metadata_struct::journal::group::JournalGroup;
+use metadata_struct::journal::namespace::JournalNamespace;
 use metadata_struct::journal::segment::{JournalSegment, SegmentStatus};
 use metadata_struct::journal::segment_meta::JournalSegmentMetadata;
 use metadata_struct::journal::shard::JournalShard;
 use rocksdb_engine::RocksDBEngine;
 use serde::{Deserialize, Serialize};
+use tokio::sync::{Mutex, MutexGuard};
 
 use crate::core::error::PlacementCenterError;
+use crate::storage::journal::group::GroupStorage;
+use crate::storage::journal::namespace::NamespaceStorage;
 use crate::storage::journal::segment::SegmentStorage;
 use crate::storage::journal::segment_meta::SegmentMetadataStorage;
 use crate::storage::journal::shard::ShardStorage;
 
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
 pub struct JournalCacheManager {
+    namespace_list: DashMap<String, JournalNamespace>,
     shard_list: DashMap<String, JournalShard>,
     segment_list: DashMap<String, DashMap<u32, JournalSegment>>,
     segment_meta_list: DashMap<String, DashMap<u32, JournalSegmentMetadata>>,
     wait_delete_shard_list: DashMap<String, JournalShard>,
     wait_delete_segment_list: DashMap<String, JournalSegment>,
+    group_list: DashMap<String, JournalGroup>,
+    // Serializes the read-modify-write of groups, whose new state goes through raft
+    #[serde(skip)]
+    group_lock: Arc<Mutex<()>>,
 }
 
 impl JournalCacheManager {
     pub fn new() -> JournalCacheManager {
         JournalCacheManager {
+            namespace_list: DashMap::with_capacity(8),
             shard_list: DashMap::with_capacity(8),
             segment_list: DashMap::with_capacity(256),
             segment_meta_list: DashMap::with_capacity(256),
             wait_delete_shard_list: DashMap::with_capacity(8),
             wait_delete_segment_list: DashMap::with_capacity(8),
+            group_list: DashMap::with_capacity(8),
+            group_lock: Arc::new(Mutex::new(())),
         }
     }
 
+    pub fn get_namespace(&self, cluster_name: &str, namespace: &str) -> Option<JournalNamespace> {
+        let key = self.namespace_key(cluster_name, namespace);
+        let res = self.namespace_list.get(&key)?;
+        Some(res.clone())
+    }
+
+    pub fn get_namespaces(&self) -> Vec<JournalNamespace> {
+        let mut results = Vec::new();
+        for raw in self.namespace_list.iter() {
+            results.push(raw.value().clone());
+        }
+        results
+    }
+
+    pub fn set_namespace(&self, namespace: &JournalNamespace) {
+        self.namespace_list.insert(
+            self.namespace_key(&namespace.cluster_name, &namespace.namespace_name),
+            namespace.clone(),
+        );
+    }
+
+    pub fn remove_namespace(&self, cluster_name: &str, namespace: &str) {
+        let key = self.namespace_key(cluster_name, namespace);
+        self.namespace_list.remove(&key);
+    }
+
+    pub fn namespace_shard_num(&self, cluster_name: &str, namespace: &str) -> usize {
+        self.shard_list
+            .iter()
+            .filter(|raw| raw.cluster_name == cluster_name && raw.namespace == namespace)
+            .count()
+    }
+
     pub fn get_shard(
         &self,
         cluster_name: &str,
@@ -57,6 +105,14 @@ impl JournalCacheManager {
         Some(res.clone())
     }
 
+    pub fn get_shards(&self) -> Vec<JournalShard> {
+        let mut results = Vec::new();
+        for raw in self.shard_list.iter() {
+            results.push(raw.value().clone());
+        }
+        results
+    }
+
     pub fn set_shard(&self, shard: &JournalShard) {
         self.shard_list.insert(
             self.shard_key(&shard.cluster_name, &shard.namespace, &shard.shard_name),
@@ -269,6 +325,75 @@ impl JournalCacheManager {
         results
     }
 
+    pub fn get_group(
+        &self,
+        cluster_name: &str,
+        namespace: &str,
+        group_name: &str,
+    ) -> Option<JournalGroup> {
+        let key = self.group_key(cluster_name, namespace, group_name);
+        let res = self.group_list.get(&key)?;
+        Some(res.clone())
+    }
+
+    pub fn get_group_mut(
+        &self,
+        cluster_name: &str,
+        namespace: &str,
+        group_name: &str,
+    ) -> Option<RefMut<'_, String, JournalGroup>> {
+        let key = self.group_key(cluster_name, namespace, group_name);
+        self.group_list.get_mut(&key)
+    }
+
+    /// Heartbeats are not written through raft, so the heartbeat time this node has
+    /// already seen for a member is kept when it is newer than the one in the group.
+    pub fn set_group(&self, group: &JournalGroup) {
+        let key = self.group_key(&group.cluster_name, &group.namespace, &group.group_name);
+        let mut group = group.clone();
+        if let Some(current) = self.group_list.get(&key) {
+            for (member_id, member) in group.members.iter_mut() {
+                if let Some(raw) = current.members.get(member_id) {
+                    member.last_heartbeat_ms = member.last_heartbeat_ms.max(raw.last_heartbeat_ms);
+                }
+            }
+        }
+        self.group_list.insert(key, group);
+    }
+
+    pub async fn lock_group(&self) -> MutexGuard<'_, ()> {
+        self.group_lock.lock().await
+    }
+
+    pub fn get_groups(&self) -> Vec<JournalGroup> {
+        let mut results = Vec::new();
+        for raw in self.group_list.iter() {
+            results.push(raw.value().clone());
+        }
+        results
+    }
+
+    pub fn renew_group_members(&self, now: u128) {
+        for mut group in self.group_list.iter_mut() {
+            for member in group.members.values_mut() {
+                member.last_heartbeat_ms = now;
+            }
+        }
+    }
+
+    pub fn remove_group(&self, cluster_name: &str, namespace: &str, group_name: &str) {
+        let key = self.group_key(cluster_name, namespace, group_name);
+        self.group_list.remove(&key);
+    }
+
+    fn group_key(&self, cluster_name: &str, namespace: &str, group_name: &str) -> String {
+        format!("{}_{}_{}", cluster_name, namespace, group_name)
+    }
+
+    fn namespace_key(&self, cluster_name: &str, namespace: &str) -> String {
+        format!("{}_{}", cluster_name, namespace)
+    }
+
     fn shard_key(&self, cluster_name: &str, namespace: &str, shard_name: &str) -> String {
         format!("{}_{}_{}", cluster_name, namespace, shard_name)
     }
@@ -291,6 +416,12 @@ pub fn load_journal_cache(
     engine_cache: &Arc<JournalCacheManager>,
     rocksdb_engine_handler: &Arc<RocksDBEngine>,
 ) -> Result<(), PlacementCenterError> {
+    let namespace_storage = NamespaceStorage::new(rocksdb_engine_handler.clone());
+    let res = namespace_storage.all_namespace()?;
+    for namespace in res {
+        engine_cache.set_namespace(&namespace);
+    }
+
     let shard_storage = ShardStorage::new(rocksdb_engine_handler.clone());
     let res = shard_storage.all_shard()?;
     for shard in res {
@@ -308,5 +439,16 @@ pub fn load_journal_cache(
     for meta in res {
         engine_cache.set_segment_meta(&meta);
     }
+
+    // Members get a full session after a restart to send their next heartbeat.
+    let group_storage = GroupStorage::new(rocksdb_engine_handler.clone());
+    let res = group_storage.all_group()?;
+    let now = now_mills();
+    for mut group in res {
+        for member in group.members.values_mut() {
+            member.last_heartbeat_ms = now;
+        }
+        engine_cache.set_group(&group);
+    }
     Ok(())
 }
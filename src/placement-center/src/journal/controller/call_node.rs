@@ -19,6 +19,7 @@ use dashmap::DashMap;
 use grpc_clients::journal::inner::call::journal_inner_update_cache;
 use grpc_clients::pool::ClientPool;
 use log::{debug, error, info};
+use metadata_struct::journal::namespace::JournalNamespace;
 use metadata_struct::journal::segment::JournalSegment;
 use metadata_struct::journal::segment_meta::JournalSegmentMetadata;
 use metadata_struct::journal::shard::JournalShard;
@@ -187,6 +188,40 @@ pub async fn update_cache_by_delete_journal_node(
     Ok(())
 }
 
+pub async fn update_cache_by_set_namespace(
+    cluster_name: &str,
+    call_manager: &Arc<JournalInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    namespace: JournalNamespace,
+) -> Result<(), PlacementCenterError> {
+    let data = serde_json::to_string(&namespace)?;
+    let message = JournalInnerCallMessage {
+        action_type: JournalUpdateCacheActionType::Set,
+        resource_type: JournalUpdateCacheResourceType::Namespace,
+        cluster_name: cluster_name.to_string(),
+        data,
+    };
+    add_call_message(call_manager, cluster_name, client_pool, message).await?;
+    Ok(())
+}
+
+pub async fn update_cache_by_delete_namespace(
+    cluster_name: &str,
+    call_manager: &Arc<JournalInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    namespace: JournalNamespace,
+) -> Result<(), PlacementCenterError> {
+    let data = serde_json::to_string(&namespace)?;
+    let message = JournalInnerCallMessage {
+        action_type: JournalUpdateCacheActionType::Delete,
+        resource_type: JournalUpdateCacheResourceType::Namespace,
+        cluster_name: cluster_name.to_string(),
+        data,
+    };
+    add_call_message(call_manager, cluster_name, client_pool, message).await?;
+    Ok(())
+}
+
 pub async fn update_cache_by_set_shard(
     cluster_name: &str,
     call_manager: &Arc<JournalInnerCallManager>,
@@ -0,0 +1,125 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::tools::now_mills;
+use log::{info, warn};
+
+use crate::journal::cache::JournalCacheManager;
+use crate::journal::services::group::{
+    remove_expired_members, sync_delete_group_info, sync_save_group_info,
+};
+use crate::route::apply::RaftMachineApply;
+
+/// Remove the members whose session has timed out and rebalance their groups.
+/// Groups without any member left are removed.
+pub async fn expire_group_member_thread(
+    raft_machine_apply: Arc<RaftMachineApply>,
+    engine_cache: Arc<JournalCacheManager>,
+) {
+    let now = now_mills();
+    for raw in engine_cache.get_groups() {
+        let _lock = engine_cache.lock_group().await;
+        let Some(mut group) =
+            engine_cache.get_group(&raw.cluster_name, &raw.namespace, &raw.group_name)
+        else {
+            continue;
+        };
+
+        let expired = remove_expired_members(&mut group, now);
+        if expired.is_empty() {
+            continue;
+        }
+
+        let result = if group.members.is_empty() {
+            sync_delete_group_info(&raft_machine_apply, &group).await
+        } else {
+            sync_save_group_info(&raft_machine_apply, &group).await
+        };
+
+        match result {
+            Ok(()) => {
+                info!(
+                    "Members {:?} of group {} session timed out and were removed, group generation is {}",
+                    expired, group.group_name, group.generation
+                );
+            }
+            Err(e) => {
+                // This node may have lost the leadership in the meantime. Give the members a new
+                // session, so they are not removed again before their next heartbeat arrives.
+                warn!(
+                    "Failed to remove the timed out members {:?} of group {}, error message: {}",
+                    expired, group.group_name, e
+                );
+                if let Some(mut group) =
+                    engine_cache.get_group_mut(&raw.cluster_name, &raw.namespace, &raw.group_name)
+                {
+                    for member_id in expired.iter() {
+                        if let Some(member) = group.members.get_mut(member_id) {
+                            member.last_heartbeat_ms = now;
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use common_base::tools::now_mills;
+    use metadata_struct::journal::group::{JournalGroup, JournalGroupAssignStrategy};
+    use protocol::placement_center::placement_center_journal::JoinGroupRequest;
+
+    use crate::journal::services::group::{join_group_member, remove_expired_members};
+
+    #[test]
+    fn expire_group_member_test() {
+        let mut group = JournalGroup::default();
+        let mut req = JoinGroupRequest {
+            cluster_name: "c1".to_string(),
+            namespace: "n1".to_string(),
+            group_name: "g1".to_string(),
+            member_id: "m1".to_string(),
+            shard_names: vec!["s1".to_string(), "s2".to_string()],
+            strategy: "range".to_string(),
+            session_timeout_ms: 60000,
+        };
+        let strategy = JournalGroupAssignStrategy::from_str(&req.strategy).unwrap();
+        join_group_member(&mut group, &req, strategy);
+        assert_eq!(group.generation, 1);
+        assert_eq!(group.member_shards("m1").len(), 2);
+
+        req.member_id = "m2".to_string();
+        join_group_member(&mut group, &req, strategy);
+        assert_eq!(group.generation, 2);
+        assert_eq!(group.member_shards("m2"), vec!["s2".to_string()]);
+
+        assert!(remove_expired_members(&mut group, now_mills()).is_empty());
+        assert_eq!(group.members.len(), 2);
+
+        group.members.get_mut("m2").unwrap().last_heartbeat_ms = now_mills() - 61000;
+        let expired = remove_expired_members(&mut group, now_mills());
+        assert_eq!(expired, vec!["m2".to_string()]);
+        assert_eq!(group.generation, 3);
+        assert_eq!(group.member_shards("m1").len(), 2);
+
+        group.members.get_mut("m1").unwrap().last_heartbeat_ms = now_mills() - 61000;
+        remove_expired_members(&mut group, now_mills());
+        assert!(group.members.is_empty());
+    }
+}
@@ -15,10 +15,14 @@
 use std::sync::Arc;
 use std::time::Duration;
 
+use call_node::JournalInnerCallManager;
+use common_base::tools::now_mills;
 use gc::{gc_segment_thread, gc_shard_thread};
+use group::expire_group_member_thread;
 use grpc_clients::pool::ClientPool;
 use log::info;
 use preferred_election::PreferredElection;
+use retention::retention_shard_thread;
 use tokio::time::sleep;
 
 use super::cache::JournalCacheManager;
@@ -27,12 +31,15 @@ use crate::route::apply::RaftMachineApply;
 
 pub mod call_node;
 pub mod gc;
+pub mod group;
 pub mod preferred_election;
+pub mod retention;
 
 pub struct StorageEngineController {
     raft_machine_apply: Arc<RaftMachineApply>,
     engine_cache: Arc<JournalCacheManager>,
     cluster_cache: Arc<PlacementCacheManager>,
+    call_manager: Arc<JournalInnerCallManager>,
     client_pool: Arc<ClientPool>,
 }
 
@@ -41,12 +48,14 @@ impl StorageEngineController {
         raft_machine_apply: Arc<RaftMachineApply>,
         engine_cache: Arc<JournalCacheManager>,
         cluster_cache: Arc<PlacementCacheManager>,
+        call_manager: Arc<JournalInnerCallManager>,
         client_pool: Arc<ClientPool>,
     ) -> Self {
         StorageEngineController {
             raft_machine_apply,
             engine_cache,
             cluster_cache,
+            call_manager,
             client_pool,
         }
     }
@@ -54,7 +63,9 @@ impl StorageEngineController {
     pub async fn start(&self) {
         self.delete_shard_gc_thread();
         self.delete_segment_gc_thread();
+        self.shard_retention_thread();
         self.preferred_replica_election();
+        self.group_member_expire_thread();
         info!("Storage Engine Controller started successfully");
     }
 
@@ -96,6 +107,48 @@ impl StorageEngineController {
         });
     }
 
+    pub fn shard_retention_thread(&self) {
+        let raft_machine_apply = self.raft_machine_apply.clone();
+        let engine_cache = self.engine_cache.clone();
+        let call_manager = self.call_manager.clone();
+        let client_pool = self.client_pool.clone();
+        tokio::spawn(async move {
+            loop {
+                retention_shard_thread(
+                    raft_machine_apply.clone(),
+                    engine_cache.clone(),
+                    call_manager.clone(),
+                    client_pool.clone(),
+                )
+                .await;
+                sleep(Duration::from_secs(30)).await;
+            }
+        });
+    }
+
+    pub fn group_member_expire_thread(&self) {
+        let raft_machine_apply = self.raft_machine_apply.clone();
+        let engine_cache = self.engine_cache.clone();
+        tokio::spawn(async move {
+            let mut is_leader = false;
+            loop {
+                // Heartbeats are only accepted by the leader. A node that has just become the
+                // leader gives every member a full session before expiring any of them.
+                if raft_machine_apply.check_leader().is_ok() {
+                    if !is_leader {
+                        engine_cache.renew_group_members(now_mills());
+                        is_leader = true;
+                    }
+                    expire_group_member_thread(raft_machine_apply.clone(), engine_cache.clone())
+                        .await;
+                } else {
+                    is_leader = false;
+                }
+                sleep(Duration::from_secs(1)).await;
+            }
+        });
+    }
+
     pub fn preferred_replica_election(&self) {
         let election = PreferredElection::new();
         tokio::spawn(async move {
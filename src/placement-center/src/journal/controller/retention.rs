@@ -0,0 +1,332 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use common_base::tools::now_second;
+use grpc_clients::pool::ClientPool;
+use log::{error, info};
+use metadata_struct::journal::segment::{JournalSegment, SegmentStatus};
+use metadata_struct::journal::segment_meta::JournalSegmentMetadata;
+use metadata_struct::journal::shard::{JournalShard, JournalShardStatus};
+
+use super::call_node::{update_cache_by_set_segment, update_cache_by_set_shard};
+use crate::core::error::PlacementCenterError;
+use crate::journal::cache::JournalCacheManager;
+use crate::journal::controller::call_node::JournalInnerCallManager;
+use crate::journal::services::segmet::update_segment_status;
+use crate::journal::services::shard::update_start_segment_by_shard;
+use crate::route::apply::RaftMachineApply;
+
+pub async fn retention_shard_thread(
+    raft_machine_apply: Arc<RaftMachineApply>,
+    engine_cache: Arc<JournalCacheManager>,
+    call_manager: Arc<JournalInnerCallManager>,
+    client_pool: Arc<ClientPool>,
+) {
+    for shard in engine_cache.get_shards() {
+        if shard.status != JournalShardStatus::Run || !shard.config.enable_retention() {
+            continue;
+        }
+
+        if let Err(e) = retention_by_shard(
+            &raft_machine_apply,
+            &engine_cache,
+            &call_manager,
+            &client_pool,
+            shard.clone(),
+        )
+        .await
+        {
+            error!(
+                "Shard {} failed to apply the retention policy with error message: {}",
+                shard.name(),
+                e
+            );
+        }
+    }
+}
+
+async fn retention_by_shard(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    engine_cache: &Arc<JournalCacheManager>,
+    call_manager: &Arc<JournalInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    mut shard: JournalShard,
+) -> Result<(), PlacementCenterError> {
+    let segments = engine_cache.get_segment_list_by_shard(
+        &shard.cluster_name,
+        &shard.namespace,
+        &shard.shard_name,
+    );
+
+    let mut metas = HashMap::new();
+    for meta in engine_cache.get_segment_meta_list_by_shard(
+        &shard.cluster_name,
+        &shard.namespace,
+        &shard.shard_name,
+    ) {
+        metas.insert(meta.segment_seq, meta);
+    }
+
+    let expired_segments = calc_expired_segments(&shard, segments, &metas, now_second());
+    let last_segment_seq = if let Some(segment) = expired_segments.last() {
+        segment.segment_seq
+    } else {
+        return Ok(());
+    };
+
+    // Mark the expired segments as PreDelete, the segment gc thread is responsible for removing their data.
+    for mut segment in expired_segments {
+        update_segment_status(
+            engine_cache,
+            raft_machine_apply,
+            &segment,
+            SegmentStatus::PreDelete,
+        )
+        .await?;
+
+        segment.status = SegmentStatus::PreDelete;
+        engine_cache.add_wait_delete_segment(&segment);
+
+        update_cache_by_set_segment(
+            &segment.cluster_name,
+            call_manager,
+            client_pool,
+            segment.clone(),
+        )
+        .await?;
+
+        info!(
+            "Segment {} exceeds the retention policy of Shard {} and is marked for deletion",
+            segment.name(),
+            shard.name()
+        );
+    }
+
+    // Move the start segment forward, reads before it will get an offset out of range error.
+    update_start_segment_by_shard(
+        raft_machine_apply,
+        engine_cache,
+        &mut shard,
+        last_segment_seq + 1,
+    )
+    .await?;
+
+    update_cache_by_set_shard(
+        &shard.cluster_name,
+        call_manager,
+        client_pool,
+        shard.clone(),
+    )
+    .await?;
+
+    Ok(())
+}
+
+/// Returns the oldest contiguous SealUp segments that violate the retention policy of the shard.
+///
+/// The size of a segment is the end position reported by the journal server. A sealed segment that
+/// has not reported it yet is counted at its `max_segment_size`, the size at which it got sealed.
+pub fn calc_expired_segments(
+    shard: &JournalShard,
+    mut segments: Vec<JournalSegment>,
+    metas: &HashMap<u32, JournalSegmentMetadata>,
+    now_sec: u64,
+) -> Vec<JournalSegment> {
+    segments.retain(|segment| {
+        segment.segment_seq >= shard.start_segment_seq
+            && segment.status != SegmentStatus::PreDelete
+            && segment.status != SegmentStatus::Deleting
+    });
+    segments.sort_by_key(|segment| segment.segment_seq);
+
+    let config = &shard.config;
+    let mut remain_num = segments.len() as u64;
+    let mut remain_bytes: u64 = segments
+        .iter()
+        .map(|segment| segment_size(segment, metas))
+        .sum();
+
+    let mut results = Vec::new();
+    for segment in segments {
+        if segment.status != SegmentStatus::SealUp {
+            break;
+        }
+
+        let over_segment_num =
+            config.retention_segment_num > 0 && remain_num > config.retention_segment_num as u64;
+
+        let over_bytes = config.retention_bytes > 0 && remain_bytes > config.retention_bytes;
+
+        let over_time = if let Some(meta) = metas.get(&segment.segment_seq) {
+            config.retention_sec > 0
+                && meta.end_timestamp > 0
+                && (meta.end_timestamp as u64) + config.retention_sec < now_sec
+        } else {
+            false
+        };
+
+        if !(over_segment_num || over_bytes || over_time) {
+            break;
+        }
+
+        remain_num -= 1;
+        remain_bytes = remain_bytes.saturating_sub(segment_size(&segment, metas));
+        results.push(segment);
+    }
+    results
+}
+
+fn segment_size(segment: &JournalSegment, metas: &HashMap<u32, JournalSegmentMetadata>) -> u64 {
+    if let Some(meta) = metas.get(&segment.segment_seq) {
+        if meta.end_position > 0 {
+            return meta.end_position as u64;
+        }
+    }
+
+    if segment.status == SegmentStatus::SealUp {
+        segment.config.max_segment_size
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use metadata_struct::journal::segment::{JournalSegment, SegmentConfig, SegmentStatus};
+    use metadata_struct::journal::segment_meta::JournalSegmentMetadata;
+    use metadata_struct::journal::shard::{JournalShard, JournalShardConfig};
+
+    use super::calc_expired_segments;
+
+    fn build_segment(segment_seq: u32, status: SegmentStatus) -> JournalSegment {
+        JournalSegment {
+            segment_seq,
+            status,
+            config: SegmentConfig {
+                max_segment_size: 100,
+            },
+            ..Default::default()
+        }
+    }
+
+    fn build_meta(segment_seq: u32, end_timestamp: i64) -> JournalSegmentMetadata {
+        JournalSegmentMetadata {
+            segment_seq,
+            end_timestamp,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn calc_expired_segments_by_segment_num_test() {
+        let shard = JournalShard {
+            config: JournalShardConfig {
+                retention_segment_num: 2,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let segments = vec![
+            build_segment(2, SegmentStatus::Write),
+            build_segment(0, SegmentStatus::SealUp),
+            build_segment(1, SegmentStatus::SealUp),
+        ];
+        let res = calc_expired_segments(&shard, segments, &HashMap::new(), 0);
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].segment_seq, 0);
+    }
+
+    #[test]
+    fn calc_expired_segments_by_bytes_test() {
+        let shard = JournalShard {
+            start_segment_seq: 1,
+            config: JournalShardConfig {
+                retention_bytes: 150,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let segments = vec![
+            build_segment(1, SegmentStatus::SealUp),
+            build_segment(2, SegmentStatus::SealUp),
+            build_segment(3, SegmentStatus::SealUp),
+            build_segment(4, SegmentStatus::Write),
+        ];
+        let res = calc_expired_segments(&shard, segments, &HashMap::new(), 0);
+        let seqs: Vec<u32> = res.iter().map(|segment| segment.segment_seq).collect();
+        // the active segment 4 has not written anything yet
+        assert_eq!(seqs, vec![1, 2]);
+    }
+
+    #[test]
+    fn calc_expired_segments_by_end_position_test() {
+        let shard = JournalShard {
+            config: JournalShardConfig {
+                retention_bytes: 150,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let segments = vec![
+            build_segment(0, SegmentStatus::SealUp),
+            build_segment(1, SegmentStatus::SealUp),
+            build_segment(2, SegmentStatus::Write),
+        ];
+        let mut metas = HashMap::new();
+        for (segment_seq, end_position) in [(0, 60), (1, 60), (2, 80)] {
+            metas.insert(
+                segment_seq,
+                JournalSegmentMetadata {
+                    segment_seq,
+                    end_position,
+                    ..Default::default()
+                },
+            );
+        }
+
+        // 200 bytes in total, dropping segment 0 brings the shard back to 140 bytes
+        let res = calc_expired_segments(&shard, segments, &metas, 0);
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].segment_seq, 0);
+    }
+
+    #[test]
+    fn calc_expired_segments_by_time_test() {
+        let shard = JournalShard {
+            config: JournalShardConfig {
+                retention_sec: 10,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let segments = vec![
+            build_segment(0, SegmentStatus::SealUp),
+            build_segment(1, SegmentStatus::SealUp),
+            build_segment(2, SegmentStatus::Write),
+        ];
+        let mut metas = HashMap::new();
+        metas.insert(0, build_meta(0, 100));
+        metas.insert(1, build_meta(1, 195));
+        metas.insert(2, build_meta(2, -1));
+
+        let res = calc_expired_segments(&shard, segments, &metas, 200);
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].segment_seq, 0);
+    }
+}
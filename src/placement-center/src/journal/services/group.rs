@@ -0,0 +1,577 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeSet, HashMap};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use common_base::tools::now_mills;
+use metadata_struct::journal::group::{
+    JournalGroup, JournalGroupAssignStrategy, JournalGroupMember,
+};
+use prost::Message;
+use protocol::placement_center::placement_center_inner::{
+    SaveOffsetDataRequest, SaveOffsetDataRequestOffset,
+};
+use protocol::placement_center::placement_center_journal::{
+    CommitGroupOffsetReply, CommitGroupOffsetRequest, GroupHeartbeatReply, GroupHeartbeatRequest,
+    JoinGroupReply, JoinGroupRequest, LeaveGroupReply, LeaveGroupRequest,
+};
+
+use crate::core::error::PlacementCenterError;
+use crate::journal::cache::JournalCacheManager;
+use crate::route::apply::RaftMachineApply;
+use crate::route::data::{StorageData, StorageDataType};
+
+pub const GROUP_MIN_SESSION_TIMEOUT_MS: u64 = 6000;
+pub const GROUP_MAX_SESSION_TIMEOUT_MS: u64 = 300000;
+
+/// Membership changes go through raft, so the groups survive a Placement Center failover.
+/// Heartbeats only refresh the session on the leader.
+pub async fn join_group_by_req(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    engine_cache: &Arc<JournalCacheManager>,
+    req: &JoinGroupRequest,
+) -> Result<JoinGroupReply, PlacementCenterError> {
+    check_group_params(&req.namespace, &req.group_name, &req.member_id)?;
+
+    if !(GROUP_MIN_SESSION_TIMEOUT_MS..=GROUP_MAX_SESSION_TIMEOUT_MS)
+        .contains(&req.session_timeout_ms)
+    {
+        return Err(PlacementCenterError::InvalidGroupSessionTimeout(
+            req.session_timeout_ms,
+            GROUP_MIN_SESSION_TIMEOUT_MS,
+            GROUP_MAX_SESSION_TIMEOUT_MS,
+        ));
+    }
+
+    let strategy = match JournalGroupAssignStrategy::from_str(&req.strategy) {
+        Ok(strategy) => strategy,
+        Err(_) => {
+            return Err(PlacementCenterError::InvalidGroupAssignStrategy(
+                req.strategy.clone(),
+            ))
+        }
+    };
+
+    let _lock = engine_cache.lock_group().await;
+    let mut group = engine_cache
+        .get_group(&req.cluster_name, &req.namespace, &req.group_name)
+        .unwrap_or_else(|| JournalGroup {
+            cluster_name: req.cluster_name.clone(),
+            namespace: req.namespace.clone(),
+            group_name: req.group_name.clone(),
+            ..Default::default()
+        });
+    join_group_member(&mut group, req, strategy);
+    sync_save_group_info(raft_machine_apply, &group).await?;
+
+    Ok(JoinGroupReply {
+        generation: group.generation,
+        shard_names: group.member_shards(&req.member_id),
+    })
+}
+
+pub fn group_heartbeat_by_req(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    engine_cache: &Arc<JournalCacheManager>,
+    req: &GroupHeartbeatRequest,
+) -> Result<GroupHeartbeatReply, PlacementCenterError> {
+    check_group_params(&req.namespace, &req.group_name, &req.member_id)?;
+
+    // Heartbeats are only kept in memory, and only the leader expires members
+    raft_machine_apply.check_leader()?;
+
+    let Some(mut group) =
+        engine_cache.get_group_mut(&req.cluster_name, &req.namespace, &req.group_name)
+    else {
+        return Err(PlacementCenterError::GroupDoesNotExist(
+            req.group_name.clone(),
+        ));
+    };
+
+    let Some(member) = group.members.get_mut(&req.member_id) else {
+        return Err(PlacementCenterError::GroupMemberDoesNotExist(
+            req.group_name.clone(),
+            req.member_id.clone(),
+        ));
+    };
+    member.last_heartbeat_ms = now_mills();
+
+    Ok(GroupHeartbeatReply {
+        generation: group.generation,
+        shard_names: group.member_shards(&req.member_id),
+    })
+}
+
+pub async fn leave_group_by_req(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    engine_cache: &Arc<JournalCacheManager>,
+    req: &LeaveGroupRequest,
+) -> Result<LeaveGroupReply, PlacementCenterError> {
+    check_group_params(&req.namespace, &req.group_name, &req.member_id)?;
+
+    let _lock = engine_cache.lock_group().await;
+    let Some(mut group) =
+        engine_cache.get_group(&req.cluster_name, &req.namespace, &req.group_name)
+    else {
+        return Ok(LeaveGroupReply::default());
+    };
+
+    if group.members.remove(&req.member_id).is_none() {
+        return Ok(LeaveGroupReply::default());
+    }
+
+    if group.members.is_empty() {
+        sync_delete_group_info(raft_machine_apply, &group).await?;
+    } else {
+        rebalance_group(&mut group);
+        sync_save_group_info(raft_machine_apply, &group).await?;
+    }
+    Ok(LeaveGroupReply::default())
+}
+
+/// Offsets are saved under the group lock, so no rebalance can happen between the
+/// generation check and the write.
+pub async fn commit_group_offset_by_req(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    engine_cache: &Arc<JournalCacheManager>,
+    req: &CommitGroupOffsetRequest,
+) -> Result<CommitGroupOffsetReply, PlacementCenterError> {
+    if req.namespace.is_empty() {
+        return Err(PlacementCenterError::RequestParamsNotEmpty(
+            "namespace".to_string(),
+        ));
+    }
+    if req.group_name.is_empty() {
+        return Err(PlacementCenterError::RequestParamsNotEmpty(
+            "group_name".to_string(),
+        ));
+    }
+
+    let _lock = engine_cache.lock_group().await;
+    match engine_cache.get_group(&req.cluster_name, &req.namespace, &req.group_name) {
+        Some(group) => check_commit_generation(&group, req)?,
+        None => {
+            if !req.member_id.is_empty() {
+                return Err(PlacementCenterError::GroupMemberDoesNotExist(
+                    req.group_name.clone(),
+                    req.member_id.clone(),
+                ));
+            }
+        }
+    }
+
+    let request = SaveOffsetDataRequest {
+        cluster_name: req.cluster_name.clone(),
+        group: req.group_name.clone(),
+        offsets: req
+            .offsets
+            .iter()
+            .map(|raw| SaveOffsetDataRequestOffset {
+                namespace: req.namespace.clone(),
+                shard_name: raw.shard_name.clone(),
+                offset: raw.offset,
+            })
+            .collect(),
+    };
+    let data = StorageData::new(
+        StorageDataType::ClusterSaveOffset,
+        SaveOffsetDataRequest::encode_to_vec(&request),
+    );
+    raft_machine_apply.client_write(data).await?;
+    Ok(CommitGroupOffsetReply::default())
+}
+
+pub async fn sync_save_group_info(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    group: &JournalGroup,
+) -> Result<(), PlacementCenterError> {
+    let data = StorageData::new(StorageDataType::JournalSetGroup, serde_json::to_vec(group)?);
+    raft_machine_apply.client_write(data).await?;
+    Ok(())
+}
+
+pub async fn sync_delete_group_info(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    group: &JournalGroup,
+) -> Result<(), PlacementCenterError> {
+    let data = StorageData::new(
+        StorageDataType::JournalDeleteGroup,
+        serde_json::to_vec(group)?,
+    );
+    raft_machine_apply.client_write(data).await?;
+    Ok(())
+}
+
+/// Add or update the member, and start a new generation when the assignment has to change.
+pub fn join_group_member(
+    group: &mut JournalGroup,
+    req: &JoinGroupRequest,
+    strategy: JournalGroupAssignStrategy,
+) {
+    let mut need_rebalance = !group.members.contains_key(&req.member_id);
+
+    if group.strategy != strategy {
+        group.strategy = strategy;
+        need_rebalance = true;
+    }
+
+    // All members of a group consume the same shard list, the list of the latest join wins.
+    if !req.shard_names.is_empty() {
+        let shard_list: Vec<String> = req
+            .shard_names
+            .iter()
+            .cloned()
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect();
+        if group.shard_list != shard_list {
+            group.shard_list = shard_list;
+            need_rebalance = true;
+        }
+    }
+
+    group.members.insert(
+        req.member_id.clone(),
+        JournalGroupMember {
+            member_id: req.member_id.clone(),
+            session_timeout_ms: req.session_timeout_ms,
+            last_heartbeat_ms: now_mills(),
+        },
+    );
+
+    if need_rebalance {
+        rebalance_group(group);
+    }
+}
+
+/// Remove the members whose session has timed out and rebalance the group, returns the removed members.
+pub fn remove_expired_members(group: &mut JournalGroup, now: u128) -> Vec<String> {
+    let expired: Vec<String> = group
+        .members
+        .values()
+        .filter(|member| {
+            now.saturating_sub(member.last_heartbeat_ms) > member.session_timeout_ms as u128
+        })
+        .map(|member| member.member_id.clone())
+        .collect();
+
+    if !expired.is_empty() {
+        for member_id in expired.iter() {
+            group.members.remove(member_id);
+        }
+        rebalance_group(group);
+    }
+    expired
+}
+
+// An empty member id is only allowed for a group without members, e.g. offsets set by an admin.
+fn check_commit_generation(
+    group: &JournalGroup,
+    req: &CommitGroupOffsetRequest,
+) -> Result<(), PlacementCenterError> {
+    if req.member_id.is_empty() {
+        if !group.members.is_empty() {
+            return Err(PlacementCenterError::GroupHasActiveMembers(
+                req.group_name.clone(),
+            ));
+        }
+        return Ok(());
+    }
+
+    if !group.members.contains_key(&req.member_id) {
+        return Err(PlacementCenterError::GroupMemberDoesNotExist(
+            req.group_name.clone(),
+            req.member_id.clone(),
+        ));
+    }
+
+    if req.generation != group.generation {
+        return Err(PlacementCenterError::GroupGenerationFenced(
+            req.group_name.clone(),
+            req.member_id.clone(),
+            req.generation,
+            group.generation,
+        ));
+    }
+
+    let shards = group.member_shards(&req.member_id);
+    for raw in req.offsets.iter() {
+        if !shards.contains(&raw.shard_name) {
+            return Err(PlacementCenterError::GroupShardNotAssigned(
+                req.group_name.clone(),
+                req.member_id.clone(),
+                raw.shard_name.clone(),
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Reassign the shards of the group to its current members and start a new generation.
+pub fn rebalance_group(group: &mut JournalGroup) {
+    let mut members: Vec<String> = group.members.keys().cloned().collect();
+    members.sort();
+
+    group.assignment = match group.strategy {
+        JournalGroupAssignStrategy::Range => range_assign(&members, &group.shard_list),
+        JournalGroupAssignStrategy::RoundRobin => round_robin_assign(&members, &group.shard_list),
+        JournalGroupAssignStrategy::Sticky => {
+            sticky_assign(&members, &group.shard_list, &group.assignment)
+        }
+    };
+    group.generation += 1;
+}
+
+fn check_group_params(
+    namespace: &str,
+    group_name: &str,
+    member_id: &str,
+) -> Result<(), PlacementCenterError> {
+    if namespace.is_empty() {
+        return Err(PlacementCenterError::RequestParamsNotEmpty(
+            "namespace".to_string(),
+        ));
+    }
+    if group_name.is_empty() {
+        return Err(PlacementCenterError::RequestParamsNotEmpty(
+            "group_name".to_string(),
+        ));
+    }
+    if member_id.is_empty() {
+        return Err(PlacementCenterError::RequestParamsNotEmpty(
+            "member_id".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn empty_assignment(members: &[String]) -> HashMap<String, Vec<String>> {
+    members
+        .iter()
+        .map(|member| (member.clone(), Vec::new()))
+        .collect()
+}
+
+// Each member gets a contiguous range of shards, the first members get one more
+// shard when the shards can not be divided evenly.
+fn range_assign(members: &[String], shards: &[String]) -> HashMap<String, Vec<String>> {
+    let mut assignment = empty_assignment(members);
+    if members.is_empty() {
+        return assignment;
+    }
+
+    let base = shards.len() / members.len();
+    let extra = shards.len() % members.len();
+    let mut start = 0;
+    for (i, member) in members.iter().enumerate() {
+        let num = base + if i < extra { 1 } else { 0 };
+        assignment.insert(member.clone(), shards[start..start + num].to_vec());
+        start += num;
+    }
+    assignment
+}
+
+fn round_robin_assign(members: &[String], shards: &[String]) -> HashMap<String, Vec<String>> {
+    let mut assignment = empty_assignment(members);
+    if members.is_empty() {
+        return assignment;
+    }
+
+    for (i, shard) in shards.iter().enumerate() {
+        if let Some(list) = assignment.get_mut(&members[i % members.len()]) {
+            list.push(shard.clone());
+        }
+    }
+    assignment
+}
+
+// Keep as many shards as possible on the member that owned them in the previous generation,
+// while still leaving every member with a balanced number of shards.
+fn sticky_assign(
+    members: &[String],
+    shards: &[String],
+    previous: &HashMap<String, Vec<String>>,
+) -> HashMap<String, Vec<String>> {
+    let mut assignment = empty_assignment(members);
+    if members.is_empty() {
+        return assignment;
+    }
+
+    let mut unassigned: BTreeSet<String> = shards.iter().cloned().collect();
+    let owned = |member: &String| -> Vec<String> {
+        previous
+            .get(member)
+            .map(|list| {
+                list.iter()
+                    .filter(|shard| unassigned.contains(*shard))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    // Members that owned more shards get the larger quotas, so fewer shards have to move.
+    let mut ordered: Vec<(String, Vec<String>)> =
+        members.iter().map(|m| (m.clone(), owned(m))).collect();
+    ordered.sort_by(|a, b| b.1.len().cmp(&a.1.len()).then(a.0.cmp(&b.0)));
+
+    let base = shards.len() / members.len();
+    let extra = shards.len() % members.len();
+    let mut quotas = Vec::with_capacity(ordered.len());
+    for (i, (member, owned)) in ordered.iter().enumerate() {
+        let quota = base + if i < extra { 1 } else { 0 };
+        let mut list = Vec::with_capacity(quota);
+        for shard in owned.iter() {
+            if list.len() >= quota {
+                break;
+            }
+            if unassigned.remove(shard) {
+                list.push(shard.clone());
+            }
+        }
+        quotas.push((member.clone(), quota));
+        assignment.insert(member.clone(), list);
+    }
+
+    for (member, quota) in quotas {
+        let Some(list) = assignment.get_mut(&member) else {
+            continue;
+        };
+        while list.len() < quota {
+            let Some(shard) = unassigned.pop_first() else {
+                break;
+            };
+            list.push(shard);
+        }
+        list.sort();
+    }
+    assignment
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use metadata_struct::journal::group::{JournalGroup, JournalGroupAssignStrategy};
+    use protocol::placement_center::placement_center_journal::{
+        CommitGroupOffsetRequest, CommitGroupOffsetRequestOffset, JoinGroupRequest,
+    };
+
+    use super::{
+        check_commit_generation, join_group_member, range_assign, round_robin_assign, sticky_assign,
+    };
+
+    fn names(prefix: &str, num: usize) -> Vec<String> {
+        (0..num).map(|i| format!("{}-{}", prefix, i)).collect()
+    }
+
+    #[test]
+    fn range_assign_test() {
+        let members = names("m", 3);
+        let shards = names("s", 7);
+        let assignment = range_assign(&members, &shards);
+        assert_eq!(assignment.get("m-0").unwrap(), &shards[0..3].to_vec());
+        assert_eq!(assignment.get("m-1").unwrap(), &shards[3..5].to_vec());
+        assert_eq!(assignment.get("m-2").unwrap(), &shards[5..7].to_vec());
+
+        let assignment = range_assign(&names("m", 2), &[]);
+        assert!(assignment.values().all(|list| list.is_empty()));
+        assert!(range_assign(&[], &shards).is_empty());
+    }
+
+    #[test]
+    fn round_robin_assign_test() {
+        let members = names("m", 2);
+        let shards = names("s", 5);
+        let assignment = round_robin_assign(&members, &shards);
+        assert_eq!(
+            assignment.get("m-0").unwrap(),
+            &vec!["s-0".to_string(), "s-2".to_string(), "s-4".to_string()]
+        );
+        assert_eq!(
+            assignment.get("m-1").unwrap(),
+            &vec!["s-1".to_string(), "s-3".to_string()]
+        );
+    }
+
+    #[test]
+    fn sticky_assign_test() {
+        let shards = names("s", 6);
+        let members = names("m", 2);
+        let first = sticky_assign(&members, &shards, &HashMap::new());
+        assert_eq!(first.get("m-0").unwrap().len(), 3);
+        assert_eq!(first.get("m-1").unwrap().len(), 3);
+
+        // a new member only takes shards from the others, nothing moves between old members
+        let members = names("m", 3);
+        let second = sticky_assign(&members, &shards, &first);
+        for member in ["m-0", "m-1"] {
+            let list = second.get(member).unwrap();
+            assert_eq!(list.len(), 2);
+            assert!(list.iter().all(|s| first.get(member).unwrap().contains(s)));
+        }
+        assert_eq!(second.get("m-2").unwrap().len(), 2);
+
+        // the shards of a leaving member are spread over the remaining ones
+        let members = vec!["m-0".to_string(), "m-2".to_string()];
+        let third = sticky_assign(&members, &shards, &second);
+        for member in ["m-0", "m-2"] {
+            let list = third.get(member).unwrap();
+            assert_eq!(list.len(), 3);
+            assert!(second.get(member).unwrap().iter().all(|s| list.contains(s)));
+        }
+    }
+
+    #[test]
+    fn check_commit_generation_test() {
+        let mut group = JournalGroup::default();
+        let mut join = JoinGroupRequest {
+            member_id: "m1".to_string(),
+            shard_names: vec!["s1".to_string(), "s2".to_string()],
+            session_timeout_ms: 30000,
+            ..Default::default()
+        };
+        join_group_member(&mut group, &join, JournalGroupAssignStrategy::Range);
+
+        let mut req = CommitGroupOffsetRequest {
+            group_name: "g1".to_string(),
+            member_id: "m1".to_string(),
+            generation: 1,
+            offsets: vec![CommitGroupOffsetRequestOffset {
+                shard_name: "s2".to_string(),
+                offset: 10,
+            }],
+            ..Default::default()
+        };
+        assert!(check_commit_generation(&group, &req).is_ok());
+
+        // m2 takes s2 in generation 2, m1 is fenced out for its old generation and for s2
+        join.member_id = "m2".to_string();
+        join_group_member(&mut group, &join, JournalGroupAssignStrategy::Range);
+        assert_eq!(group.generation, 2);
+        assert!(check_commit_generation(&group, &req).is_err());
+        req.generation = 2;
+        assert!(check_commit_generation(&group, &req).is_err());
+        req.member_id = "m2".to_string();
+        assert!(check_commit_generation(&group, &req).is_ok());
+
+        req.member_id = "m3".to_string();
+        assert!(check_commit_generation(&group, &req).is_err());
+        req.member_id = String::new();
+        assert!(check_commit_generation(&group, &req).is_err());
+        assert!(check_commit_generation(&JournalGroup::default(), &req).is_ok());
+    }
+}
@@ -12,5 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod group;
+pub mod namespace;
+pub mod producer;
 pub mod segmet;
 pub mod shard;
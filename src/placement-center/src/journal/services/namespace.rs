@@ -0,0 +1,214 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::tools::now_mills;
+use grpc_clients::pool::ClientPool;
+use metadata_struct::journal::namespace::{JournalNamespace, JournalNamespaceQuota};
+use protocol::placement_center::placement_center_journal::{
+    CreateNamespaceReply, CreateNamespaceRequest, DeleteNamespaceReply, DeleteNamespaceRequest,
+};
+
+use crate::core::error::PlacementCenterError;
+use crate::journal::cache::JournalCacheManager;
+use crate::journal::controller::call_node::{
+    update_cache_by_delete_namespace, update_cache_by_set_namespace, JournalInnerCallManager,
+};
+use crate::route::apply::RaftMachineApply;
+use crate::route::data::{StorageData, StorageDataType};
+
+pub async fn create_namespace_by_req(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    engine_cache: &Arc<JournalCacheManager>,
+    call_manager: &Arc<JournalInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    req: &CreateNamespaceRequest,
+) -> Result<CreateNamespaceReply, PlacementCenterError> {
+    if req.namespace.is_empty() {
+        return Err(PlacementCenterError::RequestParamsNotEmpty(
+            "namespace".to_string(),
+        ));
+    }
+
+    let quota = JournalNamespaceQuota {
+        max_shard_num: req.max_shard_num,
+        max_storage_bytes: req.max_storage_bytes,
+        max_write_bytes_per_sec: req.max_write_bytes_per_sec,
+    };
+
+    let namespace = if let Some(mut namespace) =
+        engine_cache.get_namespace(&req.cluster_name, &req.namespace)
+    {
+        if namespace.quota == quota {
+            return Ok(CreateNamespaceReply::default());
+        }
+        namespace.quota = quota;
+        namespace
+    } else {
+        JournalNamespace {
+            cluster_name: req.cluster_name.clone(),
+            namespace_name: req.namespace.clone(),
+            quota,
+            create_time: now_mills(),
+        }
+    };
+
+    sync_save_namespace_info(raft_machine_apply, &namespace).await?;
+    update_cache_by_set_namespace(&req.cluster_name, call_manager, client_pool, namespace).await?;
+    Ok(CreateNamespaceReply::default())
+}
+
+pub async fn delete_namespace_by_req(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    engine_cache: &Arc<JournalCacheManager>,
+    call_manager: &Arc<JournalInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    req: &DeleteNamespaceRequest,
+) -> Result<DeleteNamespaceReply, PlacementCenterError> {
+    let Some(namespace) = engine_cache.get_namespace(&req.cluster_name, &req.namespace) else {
+        return Err(PlacementCenterError::NamespaceDoesNotExist(
+            req.namespace.clone(),
+        ));
+    };
+
+    let shard_num = engine_cache.namespace_shard_num(&req.cluster_name, &req.namespace);
+    if shard_num > 0 {
+        return Err(PlacementCenterError::NamespaceNotEmpty(
+            req.namespace.clone(),
+            shard_num,
+        ));
+    }
+
+    let data = StorageData::new(
+        StorageDataType::JournalDeleteNamespace,
+        serde_json::to_vec(&namespace)?,
+    );
+    raft_machine_apply.client_write(data).await?;
+
+    update_cache_by_delete_namespace(&req.cluster_name, call_manager, client_pool, namespace)
+        .await?;
+    Ok(DeleteNamespaceReply::default())
+}
+
+/// Namespaces that were never created explicitly are created on the first shard,
+/// without any quota, so that shards can still be created in any namespace.
+pub async fn try_auto_create_namespace(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    engine_cache: &Arc<JournalCacheManager>,
+    call_manager: &Arc<JournalInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    cluster_name: &str,
+    namespace: &str,
+) -> Result<JournalNamespace, PlacementCenterError> {
+    if let Some(namespace) = engine_cache.get_namespace(cluster_name, namespace) {
+        return Ok(namespace);
+    }
+
+    let namespace = JournalNamespace {
+        cluster_name: cluster_name.to_string(),
+        namespace_name: namespace.to_string(),
+        quota: JournalNamespaceQuota::default(),
+        create_time: now_mills(),
+    };
+    sync_save_namespace_info(raft_machine_apply, &namespace).await?;
+    update_cache_by_set_namespace(cluster_name, call_manager, client_pool, namespace.clone())
+        .await?;
+    Ok(namespace)
+}
+
+/// Check that one more shard can be created in the namespace.
+pub fn check_namespace_shard_quota(
+    engine_cache: &Arc<JournalCacheManager>,
+    namespace: &JournalNamespace,
+) -> Result<(), PlacementCenterError> {
+    if namespace.quota.max_shard_num == 0 {
+        return Ok(());
+    }
+
+    let shard_num =
+        engine_cache.namespace_shard_num(&namespace.cluster_name, &namespace.namespace_name);
+    if shard_num >= namespace.quota.max_shard_num as usize {
+        return Err(PlacementCenterError::NamespaceShardQuotaExceeded(
+            namespace.namespace_name.clone(),
+            namespace.quota.max_shard_num,
+        ));
+    }
+    Ok(())
+}
+
+async fn sync_save_namespace_info(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    namespace: &JournalNamespace,
+) -> Result<(), PlacementCenterError> {
+    let data = StorageData::new(
+        StorageDataType::JournalSetNamespace,
+        serde_json::to_vec(&namespace)?,
+    );
+    if (raft_machine_apply.client_write(data).await?).is_some() {
+        return Ok(());
+    }
+    Err(PlacementCenterError::ExecutionResultIsEmpty)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use metadata_struct::journal::namespace::{JournalNamespace, JournalNamespaceQuota};
+    use metadata_struct::journal::shard::JournalShard;
+
+    use super::check_namespace_shard_quota;
+    use crate::core::error::PlacementCenterError;
+    use crate::journal::cache::JournalCacheManager;
+
+    #[test]
+    fn check_namespace_shard_quota_test() {
+        let engine_cache = Arc::new(JournalCacheManager::new());
+        let mut namespace = JournalNamespace {
+            cluster_name: "c1".to_string(),
+            namespace_name: "n1".to_string(),
+            quota: JournalNamespaceQuota {
+                max_shard_num: 2,
+                ..Default::default()
+            },
+            create_time: 0,
+        };
+
+        for i in 0..2 {
+            assert!(check_namespace_shard_quota(&engine_cache, &namespace).is_ok());
+            engine_cache.set_shard(&JournalShard {
+                cluster_name: "c1".to_string(),
+                namespace: "n1".to_string(),
+                shard_name: format!("s{}", i),
+                ..Default::default()
+            });
+        }
+        // shards of other namespaces are not counted
+        engine_cache.set_shard(&JournalShard {
+            cluster_name: "c1".to_string(),
+            namespace: "n2".to_string(),
+            shard_name: "s0".to_string(),
+            ..Default::default()
+        });
+
+        assert!(matches!(
+            check_namespace_shard_quota(&engine_cache, &namespace),
+            Err(PlacementCenterError::NamespaceShardQuotaExceeded(_, 2))
+        ));
+
+        namespace.quota.max_shard_num = 0;
+        assert!(check_namespace_shard_quota(&engine_cache, &namespace).is_ok());
+    }
+}
@@ -0,0 +1,73 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use metadata_struct::journal::producer::JournalProducerSequence;
+use protocol::placement_center::placement_center_journal::{
+    GetProducerSequenceReply, GetProducerSequenceRequest, SetProducerSequenceReply,
+    SetProducerSequenceRequest,
+};
+use rocksdb_engine::RocksDBEngine;
+
+use crate::core::error::PlacementCenterError;
+use crate::route::apply::RaftMachineApply;
+use crate::route::data::{StorageData, StorageDataType};
+use crate::storage::journal::producer::ProducerSequenceStorage;
+
+/// The sequences of all the producers written by one journal write request go through a single raft write.
+pub async fn set_producer_sequence_by_req(
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    req: &SetProducerSequenceRequest,
+) -> Result<SetProducerSequenceReply, PlacementCenterError> {
+    let mut sequences = Vec::with_capacity(req.sequences.len());
+    for raw in req.sequences.iter() {
+        let sequence = serde_json::from_slice::<JournalProducerSequence>(raw)?;
+        if sequence.producer_id.is_empty() {
+            return Err(PlacementCenterError::RequestParamsNotEmpty(
+                "producer_id".to_string(),
+            ));
+        }
+        sequences.push(sequence);
+    }
+
+    if sequences.is_empty() {
+        return Ok(SetProducerSequenceReply::default());
+    }
+
+    let data = StorageData::new(
+        StorageDataType::JournalSetProducerSequence,
+        serde_json::to_vec(&sequences)?,
+    );
+    raft_machine_apply.client_write(data).await?;
+    Ok(SetProducerSequenceReply::default())
+}
+
+pub fn get_producer_sequence_by_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    req: &GetProducerSequenceRequest,
+) -> Result<GetProducerSequenceReply, PlacementCenterError> {
+    let storage = ProducerSequenceStorage::new(rocksdb_engine_handler.clone());
+    let sequence = if let Some(sequence) = storage.get(
+        &req.cluster_name,
+        &req.namespace,
+        &req.shard_name,
+        &req.producer_id,
+    )? {
+        serde_json::to_vec(&sequence)?
+    } else {
+        Vec::new()
+    };
+    Ok(GetProducerSequenceReply { sequence })
+}
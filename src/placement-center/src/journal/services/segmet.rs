@@ -23,7 +23,8 @@ use metadata_struct::journal::segment_meta::JournalSegmentMetadata;
 use metadata_struct::journal::shard::JournalShard;
 use protocol::placement_center::placement_center_journal::{
     CreateNextSegmentReply, CreateNextSegmentRequest, DeleteSegmentReply, DeleteSegmentRequest,
-    UpdateSegmentMetaRequest, UpdateSegmentStatusRequest,
+    UpdateSegmentMetaRequest, UpdateSegmentReplicaReply, UpdateSegmentReplicaRequest,
+    UpdateSegmentStatusRequest,
 };
 use rand::seq::SliceRandom;
 use rand::{thread_rng, Rng};
@@ -95,6 +96,7 @@ pub async fn create_segment_by_req(
             end_offset: -1,
             start_timestamp: -1,
             end_timestamp: -1,
+            end_position: 0,
         };
         sync_save_segment_metadata_info(raft_machine_apply, &metadata).await?;
 
@@ -248,6 +250,89 @@ pub async fn update_segment_status_req(
     Ok(())
 }
 
+/// Move the replicas of a segment to the given nodes, the first node becomes the leader.
+/// Segment data is not copied between nodes, so only segments that have not been written
+/// (Idle or PreWrite) can be reassigned.
+pub async fn update_segment_replica_req(
+    engine_cache: &Arc<JournalCacheManager>,
+    cluster_cache: &Arc<PlacementCacheManager>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    call_manager: &Arc<JournalInnerCallManager>,
+    client_pool: &Arc<ClientPool>,
+    req: UpdateSegmentReplicaRequest,
+) -> Result<UpdateSegmentReplicaReply, PlacementCenterError> {
+    if req.node_ids.is_empty() {
+        return Err(PlacementCenterError::RequestParamsNotEmpty(
+            "node_ids".to_string(),
+        ));
+    }
+
+    let shard = if let Some(shard) =
+        engine_cache.get_shard(&req.cluster_name, &req.namespace, &req.shard_name)
+    {
+        shard
+    } else {
+        return Err(PlacementCenterError::ShardDoesNotExist(
+            req.shard_name.to_string(),
+        ));
+    };
+
+    let mut segment = if let Some(segment) = engine_cache.get_segment(
+        &req.cluster_name,
+        &req.namespace,
+        &req.shard_name,
+        req.segment_seq,
+    ) {
+        segment
+    } else {
+        return Err(PlacementCenterError::SegmentDoesNotExist(format!(
+            "{}_{}",
+            req.shard_name, req.segment_seq
+        )));
+    };
+
+    if !(segment.status == SegmentStatus::Idle || segment.status == SegmentStatus::PreWrite) {
+        return Err(PlacementCenterError::NoAllowReassignSegment(
+            segment.name(),
+            segment.status.to_string(),
+        ));
+    }
+
+    let mut node_ids = Vec::new();
+    for node_id in req.node_ids {
+        if !node_ids.contains(&node_id) {
+            node_ids.push(node_id);
+        }
+    }
+
+    if node_ids.len() as u32 != shard.replica {
+        return Err(PlacementCenterError::SegmentReplicaNumMismatch(
+            segment.name(),
+            shard.replica,
+            node_ids.len() as u32,
+        ));
+    }
+
+    let replicas = build_replicas(cluster_cache, &req.cluster_name, &node_ids)?;
+    segment.leader = calc_leader_node(&replicas);
+    segment.leader_epoch += 1;
+    segment.isr = replicas.iter().map(|rep| rep.node_id).collect();
+    segment.replicas = replicas;
+
+    sync_save_segment_info(raft_machine_apply, &segment).await?;
+    update_cache_by_set_segment(
+        &req.cluster_name,
+        call_manager,
+        client_pool,
+        segment.clone(),
+    )
+    .await?;
+
+    Ok(UpdateSegmentReplicaReply {
+        segment: serde_json::to_vec(&segment)?,
+    })
+}
+
 pub async fn update_segment_meta_req(
     engine_cache: &Arc<JournalCacheManager>,
     raft_machine_apply: &Arc<RaftMachineApply>,
@@ -306,6 +391,10 @@ pub async fn update_segment_meta_req(
         segment_meta.end_timestamp = req.end_timestamp;
     }
 
+    if req.end_position > 0 {
+        segment_meta.end_position = req.end_position;
+    }
+
     sync_save_segment_metadata_info(raft_machine_apply, &segment_meta).await?;
 
     update_cache_by_set_segment_meta(&req.cluster_name, call_manager, client_pool, segment_meta)
@@ -343,16 +432,7 @@ pub async fn build_segment(
         .choose_multiple(&mut rng, shard_info.replica as usize)
         .cloned()
         .collect();
-    let mut replicas = Vec::new();
-    for i in 0..node_ids.len() {
-        let node_id = *node_ids.get(i).unwrap();
-        let fold = calc_node_fold(cluster_cache, &shard_info.cluster_name, node_id)?;
-        replicas.push(Replica {
-            replica_seq: i as u64,
-            node_id,
-            fold,
-        });
-    }
+    let replicas = build_replicas(cluster_cache, &shard_info.cluster_name, &node_ids)?;
 
     if replicas.len() != (shard_info.replica as usize) {
         return Err(PlacementCenterError::NumberOfReplicasIsIncorrect(
@@ -377,6 +457,23 @@ pub async fn build_segment(
     })
 }
 
+fn build_replicas(
+    cluster_cache: &Arc<PlacementCacheManager>,
+    cluster_name: &str,
+    node_ids: &[u64],
+) -> Result<Vec<Replica>, PlacementCenterError> {
+    let mut replicas = Vec::new();
+    for (i, node_id) in node_ids.iter().enumerate() {
+        let fold = calc_node_fold(cluster_cache, cluster_name, *node_id)?;
+        replicas.push(Replica {
+            replica_seq: i as u64,
+            node_id: *node_id,
+            fold,
+        });
+    }
+    Ok(replicas)
+}
+
 fn calc_leader_node(replicas: &[Replica]) -> u64 {
     replicas.first().unwrap().node_id
 }
@@ -483,7 +580,7 @@ mod tests {
     use protocol::placement_center::placement_center_inner::ClusterType;
     use rocksdb_engine::RocksDBEngine;
 
-    use super::calc_node_fold;
+    use super::{build_replicas, calc_node_fold};
     use crate::core::cache::PlacementCacheManager;
     use crate::storage::rocksdb::{column_family_list, storage_data_fold};
 
@@ -517,6 +614,42 @@ mod tests {
         assert!(!res.is_empty())
     }
 
+    #[tokio::test]
+    async fn build_replicas_test() {
+        let config = placement_center_test_conf();
+        let rocksdb_engine_handler = Arc::new(RocksDBEngine::new(
+            &storage_data_fold(&config.rocksdb.data_path),
+            config.rocksdb.max_open_files.unwrap(),
+            column_family_list(),
+        ));
+        let cluster_cache = Arc::new(PlacementCacheManager::new(rocksdb_engine_handler));
+        let extend_info = JournalNodeExtend {
+            data_fold: vec!["/tmp/t1".to_string()],
+            tcp_addr: "127.0.0.1:3110".to_string(),
+            tcps_addr: "127.0.0.1:3110".to_string(),
+        };
+        for node_id in [1, 2] {
+            cluster_cache.add_broker_node(BrokerNode {
+                cluster_name: config.cluster_name.clone(),
+                cluster_type: ClusterType::JournalServer.as_str_name().to_string(),
+                create_time: now_mills(),
+                extend: serde_json::to_string(&extend_info).unwrap(),
+                node_id,
+                node_inner_addr: "".to_string(),
+                node_ip: "".to_string(),
+            });
+        }
+
+        let replicas = build_replicas(&cluster_cache, &config.cluster_name, &[2, 1]).unwrap();
+        assert_eq!(replicas.len(), 2);
+        assert_eq!(replicas[0].node_id, 2);
+        assert_eq!(replicas[0].replica_seq, 0);
+        assert_eq!(replicas[1].node_id, 1);
+        assert_eq!(replicas[1].fold, "/tmp/t1");
+
+        assert!(build_replicas(&cluster_cache, &config.cluster_name, &[1, 3]).is_err());
+    }
+
     // #[tokio::test]
     // async fn create_segment_test() {
     //     let config = placement_center_test_conf();
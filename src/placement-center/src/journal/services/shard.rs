@@ -18,11 +18,12 @@ use common_base::tools::{now_mills, unique_id};
 use grpc_clients::pool::ClientPool;
 use metadata_struct::journal::segment::SegmentStatus;
 use metadata_struct::journal::segment_meta::JournalSegmentMetadata;
-use metadata_struct::journal::shard::{JournalShard, JournalShardStatus};
+use metadata_struct::journal::shard::{JournalShard, JournalShardConfig, JournalShardStatus};
 use protocol::placement_center::placement_center_journal::{
     CreateShardReply, CreateShardRequest, DeleteShardReply, DeleteShardRequest,
 };
 
+use super::namespace::{check_namespace_shard_quota, try_auto_create_namespace};
 use super::segmet::{
     build_segment, sync_save_segment_info, sync_save_segment_metadata_info, update_segment_status,
 };
@@ -56,6 +57,23 @@ pub async fn create_shard_by_req(
     {
         shard
     } else {
+        let namespace = try_auto_create_namespace(
+            raft_machine_apply,
+            engine_cache,
+            call_manager,
+            client_pool,
+            &req.cluster_name,
+            &req.namespace,
+        )
+        .await?;
+        check_namespace_shard_quota(engine_cache, &namespace)?;
+
+        let config = if req.shard_config.is_empty() {
+            JournalShardConfig::default()
+        } else {
+            serde_json::from_slice::<JournalShardConfig>(&req.shard_config)?
+        };
+
         let shard = JournalShard {
             shard_uid: unique_id(),
             cluster_name: req.cluster_name.clone(),
@@ -67,6 +85,7 @@ pub async fn create_shard_by_req(
             last_segment_seq: 0,
             status: JournalShardStatus::Run,
             create_time: now_mills(),
+            config,
         };
 
         sync_save_shard_info(raft_machine_apply, &shard).await?;
@@ -95,6 +114,7 @@ pub async fn create_shard_by_req(
             end_offset: -1,
             start_timestamp: 0,
             end_timestamp: -1,
+            end_position: 0,
         };
 
         sync_save_segment_metadata_info(raft_machine_apply, &metadata).await?;
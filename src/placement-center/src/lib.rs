@@ -215,6 +215,7 @@ impl PlacementCenter {
             self.cluster_cache.clone(),
             raft_machine_apply.clone(),
             self.rocksdb_engine_handler.clone(),
+            self.client_pool.clone(),
         );
 
         tokio::spawn(async move {
@@ -263,6 +264,7 @@ impl PlacementCenter {
             raft_machine_apply.clone(),
             self.engine_cache.clone(),
             self.cluster_cache.clone(),
+            self.call_manager.clone(),
             self.client_pool.clone(),
         );
         tokio::spawn(async move {
@@ -15,17 +15,23 @@
 use std::sync::Arc;
 
 use common_base::tools::now_second;
-use grpc_clients::mqtt::inner::call::{broker_mqtt_delete_session, send_last_will_message};
+use grpc_clients::mqtt::inner::call::{
+    broker_mqtt_delete_session, broker_mqtt_update_cache, send_last_will_message,
+};
 use grpc_clients::pool::ClientPool;
 use log::{debug, error, warn};
 use metadata_struct::mqtt::lastwill::LastWillData;
 use metadata_struct::mqtt::session::MqttSession;
-use protocol::broker_mqtt::broker_mqtt_inner::{DeleteSessionRequest, SendLastWillMessageRequest};
+use protocol::broker_mqtt::broker_mqtt_inner::{
+    DeleteSessionRequest, MqttBrokerUpdateCacheActionType, MqttBrokerUpdateCacheResourceType,
+    SendLastWillMessageRequest, UpdateCacheRequest,
+};
 
 use super::session_expire::ExpireLastWill;
 use crate::core::cache::PlacementCacheManager;
 use crate::mqtt::cache::MqttCacheManager;
 use crate::storage::mqtt::session::MqttSessionStorage;
+use crate::storage::mqtt::topic::MqttTopicStorage;
 use crate::storage::rocksdb::RocksDBEngine;
 
 pub struct MqttBrokerCall {
@@ -86,7 +92,14 @@ impl MqttBrokerCall {
             debug!("Session expired call Broker status: {}", success);
             if success {
                 let session_storage = MqttSessionStorage::new(self.rocksdb_engine_handler.clone());
+                let topic_storage = MqttTopicStorage::new(self.rocksdb_engine_handler.clone());
                 for ms in raw {
+                    // The exclusive topic locks belong to the session and expire with it.
+                    if let Err(e) = topic_storage
+                        .delete_exclusive_topic_by_client(&self.cluster_name, &ms.client_id)
+                    {
+                        error!("{}", e);
+                    }
                     match session_storage.delete(&self.cluster_name, &ms.client_id) {
                         Ok(()) => {
                             let delay = ms.last_will_delay_interval.unwrap_or_default();
@@ -136,6 +149,69 @@ impl MqttBrokerCall {
     }
 }
 
+// Push a resource change to every broker of the cluster so that it takes effect without a restart.
+// A broker that misses the push picks the resource up from the Placement Center when it restarts.
+pub async fn update_broker_cache(
+    placement_cache_manager: &Arc<PlacementCacheManager>,
+    client_pool: &Arc<ClientPool>,
+    cluster_name: &str,
+    action_type: MqttBrokerUpdateCacheActionType,
+    resource_type: MqttBrokerUpdateCacheResourceType,
+    resource_name: &str,
+    data: Vec<u8>,
+) {
+    for addr in placement_cache_manager.get_broker_node_addr_by_cluster(cluster_name) {
+        let request = UpdateCacheRequest {
+            cluster_name: cluster_name.to_string(),
+            action_type: action_type.into(),
+            resource_type: resource_type.into(),
+            data: data.clone(),
+        };
+        if let Err(e) = broker_mqtt_update_cache(client_pool, &[addr.clone()], request).await {
+            warn!(
+                "Failed to push {:?} {} to broker {}, error message: {}",
+                resource_type, resource_name, addr, e
+            );
+        }
+    }
+}
+
+// Push a resource change to a single broker, for changes that only concern the clients connected
+// to it.
+#[allow(clippy::too_many_arguments)]
+pub async fn update_broker_node_cache(
+    placement_cache_manager: &Arc<PlacementCacheManager>,
+    client_pool: &Arc<ClientPool>,
+    cluster_name: &str,
+    broker_id: u64,
+    action_type: MqttBrokerUpdateCacheActionType,
+    resource_type: MqttBrokerUpdateCacheResourceType,
+    resource_name: &str,
+    data: Vec<u8>,
+) {
+    let Some(node) = placement_cache_manager.get_broker_node(cluster_name, broker_id) else {
+        warn!(
+            "Broker {} is not registered in cluster {}, {:?} {} is not pushed",
+            broker_id, cluster_name, resource_type, resource_name
+        );
+        return;
+    };
+    let request = UpdateCacheRequest {
+        cluster_name: cluster_name.to_string(),
+        action_type: action_type.into(),
+        resource_type: resource_type.into(),
+        data,
+    };
+    if let Err(e) =
+        broker_mqtt_update_cache(client_pool, &[node.node_inner_addr.clone()], request).await
+    {
+        warn!(
+            "Failed to push {:?} {} to broker {}, error message: {}",
+            resource_type, resource_name, node.node_inner_addr, e
+        );
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
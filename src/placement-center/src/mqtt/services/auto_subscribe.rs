@@ -0,0 +1,93 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use grpc_clients::pool::ClientPool;
+use prost::Message;
+use protocol::broker_mqtt::broker_mqtt_inner::{
+    MqttBrokerUpdateCacheActionType, MqttBrokerUpdateCacheResourceType,
+};
+use protocol::placement_center::placement_center_mqtt::{
+    DeleteAutoSubscribeRuleRequest, SetAutoSubscribeRuleRequest,
+};
+
+use crate::core::cache::PlacementCacheManager;
+use crate::core::error::PlacementCenterError;
+use crate::mqtt::controller::call_broker::update_broker_cache;
+use crate::route::apply::RaftMachineApply;
+use crate::route::data::{StorageData, StorageDataType};
+use crate::route::mqtt::build_auto_subscribe_rule;
+use crate::storage::mqtt::auto_subscribe::MqttAutoSubscribeStorage;
+use crate::storage::rocksdb::RocksDBEngine;
+
+pub async fn set_auto_subscribe_rule_req(
+    cluster_cache: &Arc<PlacementCacheManager>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    client_pool: &Arc<ClientPool>,
+    req: SetAutoSubscribeRuleRequest,
+) -> Result<(), PlacementCenterError> {
+    let auto_subscribe_rule = build_auto_subscribe_rule(&req)?;
+    let data = StorageData::new(
+        StorageDataType::MqttSetAutoSubscribeRule,
+        SetAutoSubscribeRuleRequest::encode_to_vec(&req),
+    );
+    raft_machine_apply.client_write(data).await?;
+
+    update_broker_cache(
+        cluster_cache,
+        client_pool,
+        &req.cluster_name,
+        MqttBrokerUpdateCacheActionType::Add,
+        MqttBrokerUpdateCacheResourceType::AutoSubscribeRule,
+        &auto_subscribe_rule.topic,
+        auto_subscribe_rule.encode(),
+    )
+    .await;
+    Ok(())
+}
+
+pub async fn delete_auto_subscribe_rule_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    cluster_cache: &Arc<PlacementCacheManager>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    client_pool: &Arc<ClientPool>,
+    req: DeleteAutoSubscribeRuleRequest,
+) -> Result<(), PlacementCenterError> {
+    let storage = MqttAutoSubscribeStorage::new(rocksdb_engine_handler.clone());
+    let auto_subscribe_rule = storage
+        .list(&req.cluster_name)?
+        .into_iter()
+        .find(|rule| rule.topic == req.topic);
+
+    let data = StorageData::new(
+        StorageDataType::MqttDeleteAutoSubscribeRule,
+        DeleteAutoSubscribeRuleRequest::encode_to_vec(&req),
+    );
+    raft_machine_apply.client_write(data).await?;
+
+    if let Some(auto_subscribe_rule) = auto_subscribe_rule {
+        update_broker_cache(
+            cluster_cache,
+            client_pool,
+            &req.cluster_name,
+            MqttBrokerUpdateCacheActionType::Delete,
+            MqttBrokerUpdateCacheResourceType::AutoSubscribeRule,
+            &auto_subscribe_rule.topic,
+            auto_subscribe_rule.encode(),
+        )
+        .await;
+    }
+    Ok(())
+}
@@ -12,5 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod auto_subscribe;
+pub mod rule;
+pub mod schema;
 pub mod share_sub;
 pub mod topic;
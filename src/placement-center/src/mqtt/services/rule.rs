@@ -0,0 +1,90 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::rule::MqttRule;
+use prost::Message;
+use protocol::broker_mqtt::broker_mqtt_inner::{
+    MqttBrokerUpdateCacheActionType, MqttBrokerUpdateCacheResourceType,
+};
+use protocol::placement_center::placement_center_mqtt::{CreateRuleRequest, DeleteRuleRequest};
+
+use crate::core::cache::PlacementCacheManager;
+use crate::core::error::PlacementCenterError;
+use crate::mqtt::controller::call_broker::update_broker_cache;
+use crate::route::apply::RaftMachineApply;
+use crate::route::data::{StorageData, StorageDataType};
+use crate::storage::mqtt::rule::MqttRuleStorage;
+use crate::storage::rocksdb::RocksDBEngine;
+
+pub async fn create_rule_req(
+    cluster_cache: &Arc<PlacementCacheManager>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    client_pool: &Arc<ClientPool>,
+    req: CreateRuleRequest,
+) -> Result<(), PlacementCenterError> {
+    let rule = serde_json::from_slice::<MqttRule>(&req.content)?;
+    let data = StorageData::new(
+        StorageDataType::MqttCreateRule,
+        CreateRuleRequest::encode_to_vec(&req),
+    );
+    raft_machine_apply.client_write(data).await?;
+
+    update_broker_cache(
+        cluster_cache,
+        client_pool,
+        &req.cluster_name,
+        MqttBrokerUpdateCacheActionType::Add,
+        MqttBrokerUpdateCacheResourceType::Rule,
+        &rule.rule_name,
+        rule.encode(),
+    )
+    .await;
+    Ok(())
+}
+
+pub async fn delete_rule_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    cluster_cache: &Arc<PlacementCacheManager>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    client_pool: &Arc<ClientPool>,
+    req: DeleteRuleRequest,
+) -> Result<(), PlacementCenterError> {
+    let storage = MqttRuleStorage::new(rocksdb_engine_handler.clone());
+    let rule = if let Some(rule) = storage.get(&req.cluster_name, &req.rule_name)? {
+        rule
+    } else {
+        return Err(PlacementCenterError::RuleDoesNotExist(req.rule_name));
+    };
+
+    let data = StorageData::new(
+        StorageDataType::MqttDeleteRule,
+        DeleteRuleRequest::encode_to_vec(&req),
+    );
+    raft_machine_apply.client_write(data).await?;
+
+    update_broker_cache(
+        cluster_cache,
+        client_pool,
+        &req.cluster_name,
+        MqttBrokerUpdateCacheActionType::Delete,
+        MqttBrokerUpdateCacheResourceType::Rule,
+        &rule.rule_name,
+        rule.encode(),
+    )
+    .await;
+    Ok(())
+}
@@ -0,0 +1,175 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::schema::{MqttSchema, MqttSchemaBinding};
+use prost::Message;
+use protocol::broker_mqtt::broker_mqtt_inner::{
+    MqttBrokerUpdateCacheActionType, MqttBrokerUpdateCacheResourceType,
+};
+use protocol::placement_center::placement_center_mqtt::{
+    BindSchemaRequest, CreateSchemaRequest, DeleteSchemaRequest, UnbindSchemaRequest,
+};
+
+use crate::core::cache::PlacementCacheManager;
+use crate::core::error::PlacementCenterError;
+use crate::mqtt::controller::call_broker::update_broker_cache;
+use crate::route::apply::RaftMachineApply;
+use crate::route::data::{StorageData, StorageDataType};
+use crate::storage::mqtt::schema::MqttSchemaStorage;
+use crate::storage::rocksdb::RocksDBEngine;
+
+pub async fn create_schema_req(
+    cluster_cache: &Arc<PlacementCacheManager>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    client_pool: &Arc<ClientPool>,
+    req: CreateSchemaRequest,
+) -> Result<(), PlacementCenterError> {
+    let schema = serde_json::from_slice::<MqttSchema>(&req.content)?;
+    let data = StorageData::new(
+        StorageDataType::MqttCreateSchema,
+        CreateSchemaRequest::encode_to_vec(&req),
+    );
+    raft_machine_apply.client_write(data).await?;
+
+    update_broker_cache(
+        cluster_cache,
+        client_pool,
+        &req.cluster_name,
+        MqttBrokerUpdateCacheActionType::Add,
+        MqttBrokerUpdateCacheResourceType::Schema,
+        &schema.schema_name,
+        schema.encode(),
+    )
+    .await;
+    Ok(())
+}
+
+pub async fn delete_schema_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    cluster_cache: &Arc<PlacementCacheManager>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    client_pool: &Arc<ClientPool>,
+    req: DeleteSchemaRequest,
+) -> Result<(), PlacementCenterError> {
+    let storage = MqttSchemaStorage::new(rocksdb_engine_handler.clone());
+    let schema = if let Some(schema) = storage.get(&req.cluster_name, &req.schema_name)? {
+        schema
+    } else {
+        return Err(PlacementCenterError::SchemaDoesNotExist(req.schema_name));
+    };
+
+    let bindings = storage.list_binding_by_schema(&req.cluster_name, &req.schema_name)?;
+    if !bindings.is_empty() {
+        return Err(PlacementCenterError::SchemaIsStillBound(
+            req.schema_name,
+            bindings.into_iter().map(|raw| raw.topic_filter).collect(),
+        ));
+    }
+
+    let data = StorageData::new(
+        StorageDataType::MqttDeleteSchema,
+        DeleteSchemaRequest::encode_to_vec(&req),
+    );
+    raft_machine_apply.client_write(data).await?;
+
+    update_broker_cache(
+        cluster_cache,
+        client_pool,
+        &req.cluster_name,
+        MqttBrokerUpdateCacheActionType::Delete,
+        MqttBrokerUpdateCacheResourceType::Schema,
+        &schema.schema_name,
+        schema.encode(),
+    )
+    .await;
+    Ok(())
+}
+
+pub async fn bind_schema_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    cluster_cache: &Arc<PlacementCacheManager>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    client_pool: &Arc<ClientPool>,
+    req: BindSchemaRequest,
+) -> Result<(), PlacementCenterError> {
+    let binding = serde_json::from_slice::<MqttSchemaBinding>(&req.content)?;
+    let storage = MqttSchemaStorage::new(rocksdb_engine_handler.clone());
+    if storage
+        .get(&req.cluster_name, &binding.schema_name)?
+        .is_none()
+    {
+        return Err(PlacementCenterError::SchemaDoesNotExist(
+            binding.schema_name,
+        ));
+    }
+
+    let data = StorageData::new(
+        StorageDataType::MqttBindSchema,
+        BindSchemaRequest::encode_to_vec(&req),
+    );
+    raft_machine_apply.client_write(data).await?;
+
+    update_broker_cache(
+        cluster_cache,
+        client_pool,
+        &req.cluster_name,
+        MqttBrokerUpdateCacheActionType::Add,
+        MqttBrokerUpdateCacheResourceType::SchemaBinding,
+        &binding.schema_name,
+        binding.encode(),
+    )
+    .await;
+    Ok(())
+}
+
+pub async fn unbind_schema_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    cluster_cache: &Arc<PlacementCacheManager>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    client_pool: &Arc<ClientPool>,
+    req: UnbindSchemaRequest,
+) -> Result<(), PlacementCenterError> {
+    let storage = MqttSchemaStorage::new(rocksdb_engine_handler.clone());
+    let binding = if let Some(binding) =
+        storage.get_binding(&req.cluster_name, &req.schema_name, &req.topic_filter)?
+    {
+        binding
+    } else {
+        return Err(PlacementCenterError::SchemaBindingDoesNotExist(
+            req.schema_name,
+            req.topic_filter,
+        ));
+    };
+
+    let data = StorageData::new(
+        StorageDataType::MqttUnbindSchema,
+        UnbindSchemaRequest::encode_to_vec(&req),
+    );
+    raft_machine_apply.client_write(data).await?;
+
+    update_broker_cache(
+        cluster_cache,
+        client_pool,
+        &req.cluster_name,
+        MqttBrokerUpdateCacheActionType::Delete,
+        MqttBrokerUpdateCacheResourceType::SchemaBinding,
+        &binding.schema_name,
+        binding.encode(),
+    )
+    .await;
+    Ok(())
+}
@@ -16,8 +16,19 @@ use std::collections::HashMap;
 use std::sync::Arc;
 
 use common_base::error::common::CommonError;
+use grpc_clients::pool::ClientPool;
+use metadata_struct::mqtt::cluster::MqttShareSubGroupStrategy;
+use prost::Message;
+use protocol::broker_mqtt::broker_mqtt_inner::{
+    MqttBrokerUpdateCacheActionType, MqttBrokerUpdateCacheResourceType,
+};
+use protocol::placement_center::placement_center_mqtt::SetShareSubStrategyRequest;
 
 use crate::core::cache::PlacementCacheManager;
+use crate::core::error::PlacementCenterError;
+use crate::mqtt::controller::call_broker::update_broker_cache;
+use crate::route::apply::RaftMachineApply;
+use crate::route::data::{StorageData, StorageDataType};
 use crate::storage::keys::storage_key_mqtt_node_sub_group_leader;
 use crate::storage::placement::kv::KvStorage;
 use crate::storage::rocksdb::RocksDBEngine;
@@ -207,6 +218,41 @@ impl ShareSubLeader {
     }
 }
 
+pub async fn set_share_sub_strategy_req(
+    cluster_cache: &Arc<PlacementCacheManager>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    client_pool: &Arc<ClientPool>,
+    req: SetShareSubStrategyRequest,
+) -> Result<(), PlacementCenterError> {
+    let strategy = serde_json::from_slice::<MqttShareSubGroupStrategy>(&req.content)?;
+    if strategy.group_name != req.group_name {
+        return Err(CommonError::InvalidParameterFormat(
+            "group_name".to_string(),
+            req.group_name.clone(),
+        )
+        .into());
+    }
+
+    let data = StorageData::new(
+        StorageDataType::MqttSetShareSubStrategy,
+        SetShareSubStrategyRequest::encode_to_vec(&req),
+    );
+    raft_machine_apply.client_write(data).await?;
+
+    // The leader of a group can run on any broker, so every broker has to see the change.
+    update_broker_cache(
+        cluster_cache,
+        client_pool,
+        &req.cluster_name,
+        MqttBrokerUpdateCacheActionType::Add,
+        MqttBrokerUpdateCacheResourceType::ShareSubStrategy,
+        &req.group_name,
+        strategy.encode(),
+    )
+    .await;
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use std::fs::remove_dir_all;
@@ -14,13 +14,19 @@
 
 use std::sync::Arc;
 
+use grpc_clients::pool::ClientPool;
+use prost::Message;
+use protocol::broker_mqtt::broker_mqtt_inner::{
+    MqttBrokerUpdateCacheActionType, MqttBrokerUpdateCacheResourceType,
+};
 use protocol::placement_center::placement_center_mqtt::{
-    CreateTopicRequest, SetTopicRetainMessageRequest,
+    CreateTopicRequest, DeleteExclusiveTopicRequest, SetTopicRetainMessageRequest,
 };
 use rocksdb_engine::RocksDBEngine;
 
 use crate::core::cache::PlacementCacheManager;
 use crate::core::error::PlacementCenterError;
+use crate::mqtt::controller::call_broker::update_broker_node_cache;
 use crate::route::apply::RaftMachineApply;
 use crate::route::data::{StorageData, StorageDataType};
 use crate::storage::mqtt::topic::MqttTopicStorage;
@@ -71,3 +77,41 @@ pub async fn set_topic_retain_message_req(
     raft_machine_apply.client_write(data).await?;
     Ok(())
 }
+
+pub async fn delete_exclusive_topic_req(
+    rocksdb_engine_handler: &Arc<RocksDBEngine>,
+    cluster_cache: &Arc<PlacementCacheManager>,
+    raft_machine_apply: &Arc<RaftMachineApply>,
+    client_pool: &Arc<ClientPool>,
+    req: DeleteExclusiveTopicRequest,
+) -> Result<(), PlacementCenterError> {
+    // Without a client id the lock is released whoever holds it, the subscription of the
+    // holder has to go as well or it keeps receiving the messages of the topic.
+    let forced_lock = if req.client_id.is_empty() && !req.topic_name.is_empty() {
+        let topic_storage = MqttTopicStorage::new(rocksdb_engine_handler.clone());
+        topic_storage.get_exclusive_topic(&req.cluster_name, &req.topic_name)?
+    } else {
+        None
+    };
+
+    let data = StorageData::new(
+        StorageDataType::MqttDeleteExclusiveTopic,
+        DeleteExclusiveTopicRequest::encode_to_vec(&req),
+    );
+    raft_machine_apply.client_write(data).await?;
+
+    if let Some(lock) = forced_lock {
+        update_broker_node_cache(
+            cluster_cache,
+            client_pool,
+            &req.cluster_name,
+            lock.broker_id,
+            MqttBrokerUpdateCacheActionType::Delete,
+            MqttBrokerUpdateCacheResourceType::ExclusiveTopic,
+            &req.topic_name,
+            lock.encode(),
+        )
+        .await;
+    }
+    Ok(())
+}
@@ -14,6 +14,7 @@
 
 use std::time::Duration;
 
+use openraft::error::{ClientWriteError, ForwardToLeader, RaftError};
 use openraft::raft::ClientWriteResponse;
 use openraft::Raft;
 use tokio::time::timeout;
@@ -41,6 +42,30 @@ impl RaftMachineApply {
         }
     }
 
+    /// Requests that only change the in-memory state of the leader must be served by the leader.
+    /// On other nodes this returns the same forward error as a raft write, so the client follows it.
+    pub fn check_leader(&self) -> Result<(), PlacementCenterError> {
+        let metrics = self.openraft_node.metrics().borrow().clone();
+        if metrics.current_leader == Some(metrics.id) {
+            return Ok(());
+        }
+
+        let leader_node = metrics.current_leader.and_then(|leader_id| {
+            metrics
+                .membership_config
+                .membership()
+                .get_node(&leader_id)
+                .cloned()
+        });
+        Err(
+            RaftError::APIError(ClientWriteError::ForwardToLeader(ForwardToLeader {
+                leader_id: metrics.current_leader,
+                leader_node,
+            }))
+            .into(),
+        )
+    }
+
     async fn raft_write(
         &self,
         data: StorageData,
@@ -20,7 +20,7 @@ use metadata_struct::placement::cluster::ClusterInfo;
 use metadata_struct::placement::node::BrokerNode;
 use prost::Message as _;
 use protocol::placement_center::placement_center_inner::{
-    DeleteIdempotentDataRequest, DeleteResourceConfigRequest, SaveOffsetDataRequest,
+    ClusterType, DeleteIdempotentDataRequest, DeleteResourceConfigRequest, SaveOffsetDataRequest,
     SetIdempotentDataRequest, SetResourceConfigRequest, UnRegisterNodeRequest,
 };
 use protocol::placement_center::placement_center_mqtt::{
@@ -31,6 +31,7 @@ use crate::core::cache::PlacementCacheManager;
 use crate::core::error::PlacementCenterError;
 use crate::storage::mqtt::acl::AclStorage;
 use crate::storage::mqtt::blacklist::MqttBlackListStorage;
+use crate::storage::mqtt::topic::MqttTopicStorage;
 use crate::storage::placement::cluster::ClusterStorage;
 use crate::storage::placement::config::ResourceConfigStorage;
 use crate::storage::placement::idempotent::IdempotentStorage;
@@ -82,6 +83,13 @@ impl DataRouteCluster {
         node_storage.delete(&req.cluster_name, req.node_id)?;
         self.cluster_cache
             .remove_broker_node(&req.cluster_name, req.node_id);
+
+        // The exclusive topic locks taken through this broker are only valid while it is
+        // registered, a broker that stopped sending heartbeats releases them.
+        if req.cluster_type() == ClusterType::MqttBrokerServer {
+            let topic_storage = MqttTopicStorage::new(self.rocksdb_engine_handler.clone());
+            topic_storage.delete_exclusive_topic_by_broker(&req.cluster_name, req.node_id)?;
+        }
         Ok(())
     }
 
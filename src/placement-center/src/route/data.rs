@@ -50,12 +50,17 @@ pub enum StorageDataType {
     ClusterDeleteOffset,
 
     // Journal
+    JournalSetNamespace,
+    JournalDeleteNamespace,
     JournalSetShard,
     JournalDeleteShard,
     JournalSetSegment,
     JournalDeleteSegment,
     JournalSetSegmentMetadata,
     JournalDeleteSegmentMetadata,
+    JournalSetProducerSequence,
+    JournalSetGroup,
+    JournalDeleteGroup,
 
     // kv
     KvSet,
@@ -78,4 +83,14 @@ pub enum StorageDataType {
     MqttDeleteExclusiveTopic,
     MqttCreateTopicRewriteRule,
     MqttDeleteTopicRewriteRule,
+    MqttSetAutoSubscribeRule,
+    MqttDeleteAutoSubscribeRule,
+    MqttCreateRule,
+    MqttDeleteRule,
+    MqttCreateSchema,
+    MqttDeleteSchema,
+    MqttBindSchema,
+    MqttUnbindSchema,
+    MqttBatchInflightMessage,
+    MqttSetShareSubStrategy,
 }
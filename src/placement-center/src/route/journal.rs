@@ -14,12 +14,18 @@
 
 use std::sync::Arc;
 
+use metadata_struct::journal::group::JournalGroup;
+use metadata_struct::journal::namespace::JournalNamespace;
+use metadata_struct::journal::producer::JournalProducerSequence;
 use metadata_struct::journal::segment::JournalSegment;
 use metadata_struct::journal::segment_meta::JournalSegmentMetadata;
 use metadata_struct::journal::shard::JournalShard;
 
 use crate::core::error::PlacementCenterError;
 use crate::journal::cache::JournalCacheManager;
+use crate::storage::journal::group::GroupStorage;
+use crate::storage::journal::namespace::NamespaceStorage;
+use crate::storage::journal::producer::ProducerSequenceStorage;
 use crate::storage::journal::segment::SegmentStorage;
 use crate::storage::journal::segment_meta::SegmentMetadataStorage;
 use crate::storage::journal::shard::ShardStorage;
@@ -42,6 +48,29 @@ impl DataRouteJournal {
         }
     }
 
+    pub async fn set_namespace(&self, value: Vec<u8>) -> Result<Vec<u8>, PlacementCenterError> {
+        let namespace = serde_json::from_slice::<JournalNamespace>(&value)?;
+
+        let storage = NamespaceStorage::new(self.rocksdb_engine_handler.clone());
+        storage.save(&namespace)?;
+
+        self.engine_cache.set_namespace(&namespace);
+
+        Ok(value)
+    }
+
+    pub async fn delete_namespace(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let namespace = serde_json::from_slice::<JournalNamespace>(&value)?;
+
+        let storage = NamespaceStorage::new(self.rocksdb_engine_handler.clone());
+        storage.delete(&namespace.cluster_name, &namespace.namespace_name)?;
+
+        self.engine_cache
+            .remove_namespace(&namespace.cluster_name, &namespace.namespace_name);
+
+        Ok(())
+    }
+
     pub async fn set_shard(&self, value: Vec<u8>) -> Result<Vec<u8>, PlacementCenterError> {
         let shard_storage = ShardStorage::new(self.rocksdb_engine_handler.clone());
 
@@ -63,6 +92,13 @@ impl DataRouteJournal {
             &shard_info.shard_name,
         )?;
 
+        let producer_storage = ProducerSequenceStorage::new(self.rocksdb_engine_handler.clone());
+        producer_storage.delete_by_shard(
+            &shard_info.cluster_name,
+            &shard_info.namespace,
+            &shard_info.shard_name,
+        )?;
+
         self.engine_cache.remove_shard(
             &shard_info.cluster_name,
             &shard_info.namespace,
@@ -133,4 +169,35 @@ impl DataRouteJournal {
         );
         Ok(())
     }
+
+    pub fn set_producer_sequence(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let sequences = serde_json::from_slice::<Vec<JournalProducerSequence>>(&value)?;
+
+        let storage = ProducerSequenceStorage::new(self.rocksdb_engine_handler.clone());
+        for sequence in sequences {
+            storage.save(sequence)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_group(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let group = serde_json::from_slice::<JournalGroup>(&value)?;
+
+        let storage = GroupStorage::new(self.rocksdb_engine_handler.clone());
+        storage.save(&group)?;
+
+        self.engine_cache.set_group(&group);
+        Ok(())
+    }
+
+    pub fn delete_group(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let group = serde_json::from_slice::<JournalGroup>(&value)?;
+
+        let storage = GroupStorage::new(self.rocksdb_engine_handler.clone());
+        storage.delete(&group.cluster_name, &group.namespace, &group.group_name)?;
+
+        self.engine_cache
+            .remove_group(&group.cluster_name, &group.namespace, &group.group_name);
+        Ok(())
+    }
 }
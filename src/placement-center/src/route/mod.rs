@@ -124,6 +124,15 @@ impl DataRoute {
             }
 
             // Journal Engine
+            StorageDataType::JournalSetNamespace => Ok(Some(
+                self.route_journal.set_namespace(storage_data.value).await?,
+            )),
+            StorageDataType::JournalDeleteNamespace => {
+                self.route_journal
+                    .delete_namespace(storage_data.value)
+                    .await?;
+                Ok(None)
+            }
             StorageDataType::JournalSetShard => Ok(Some(
                 self.route_journal.set_shard(storage_data.value).await?,
             )),
@@ -152,6 +161,19 @@ impl DataRoute {
                     .await?;
                 Ok(None)
             }
+            StorageDataType::JournalSetProducerSequence => {
+                self.route_journal
+                    .set_producer_sequence(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::JournalSetGroup => {
+                self.route_journal.set_group(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::JournalDeleteGroup => {
+                self.route_journal.delete_group(storage_data.value)?;
+                Ok(None)
+            }
 
             // Mqtt Broker
             StorageDataType::MqttSetAcl => {
@@ -220,6 +242,48 @@ impl DataRoute {
                     .delete_topic_rewrite_rule(storage_data.value)?;
                 Ok(None)
             }
+            StorageDataType::MqttSetAutoSubscribeRule => {
+                self.route_mqtt
+                    .set_auto_subscribe_rule(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::MqttDeleteAutoSubscribeRule => {
+                self.route_mqtt
+                    .delete_auto_subscribe_rule(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::MqttCreateRule => {
+                self.route_mqtt.create_rule(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::MqttDeleteRule => {
+                self.route_mqtt.delete_rule(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::MqttCreateSchema => {
+                self.route_mqtt.create_schema(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::MqttDeleteSchema => {
+                self.route_mqtt.delete_schema(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::MqttBindSchema => {
+                self.route_mqtt.bind_schema(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::MqttUnbindSchema => {
+                self.route_mqtt.unbind_schema(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::MqttBatchInflightMessage => {
+                self.route_mqtt.batch_inflight_message(storage_data.value)?;
+                Ok(None)
+            }
+            StorageDataType::MqttSetShareSubStrategy => {
+                self.route_mqtt.set_share_sub_strategy(storage_data.value)?;
+                Ok(None)
+            }
         }
     }
 
@@ -15,20 +15,31 @@
 use std::sync::Arc;
 
 use common_base::utils::time_util::get_current_millisecond_timestamp;
+use metadata_struct::mqtt::auto_subscribe_rule::MqttAutoSubscribeRule;
+use metadata_struct::mqtt::exclusive_topic::MqttExclusiveTopic;
 use metadata_struct::mqtt::session::MqttSession;
 use metadata_struct::mqtt::topic::MqttTopic;
 use metadata_struct::mqtt::topic_rewrite_rule::MqttTopicRewriteRule;
 use prost::Message as _;
+use protocol::mqtt::common::{qos, retain_forward_rule};
 use protocol::placement_center::placement_center_mqtt::{
+    BatchInflightMessageRequest, BindSchemaRequest, CreateRuleRequest, CreateSchemaRequest,
     CreateSessionRequest, CreateTopicRewriteRuleRequest, CreateUserRequest,
-    DeleteExclusiveTopicRequest, DeleteSessionRequest, DeleteTopicRequest,
-    DeleteTopicRewriteRuleRequest, DeleteUserRequest, SaveLastWillMessageRequest,
-    SetExclusiveTopicRequest, UpdateSessionRequest,
+    DeleteAutoSubscribeRuleRequest, DeleteExclusiveTopicRequest, DeleteRuleRequest,
+    DeleteSchemaRequest, DeleteSessionRequest, DeleteTopicRequest, DeleteTopicRewriteRuleRequest,
+    DeleteUserRequest, SaveLastWillMessageRequest, SetAutoSubscribeRuleRequest,
+    SetExclusiveTopicRequest, SetShareSubStrategyRequest, UnbindSchemaRequest,
+    UpdateSessionRequest,
 };
 
 use crate::core::error::PlacementCenterError;
+use crate::storage::mqtt::auto_subscribe::MqttAutoSubscribeStorage;
+use crate::storage::mqtt::inflight::MqttInflightStorage;
 use crate::storage::mqtt::lastwill::MqttLastWillStorage;
+use crate::storage::mqtt::rule::MqttRuleStorage;
+use crate::storage::mqtt::schema::MqttSchemaStorage;
 use crate::storage::mqtt::session::MqttSessionStorage;
+use crate::storage::mqtt::share_sub_strategy::MqttShareSubStrategyStorage;
 use crate::storage::mqtt::topic::MqttTopicStorage;
 use crate::storage::mqtt::user::MqttUserStorage;
 use crate::storage::rocksdb::RocksDBEngine;
@@ -126,18 +137,45 @@ impl DataRouteMqtt {
         let req = DeleteSessionRequest::decode(value.as_ref())?;
         let storage = MqttSessionStorage::new(self.rocksdb_engine_handler.clone());
         storage.delete(&req.cluster_name, &req.client_id)?;
+
+        // The outbound inflight state belongs to the session and goes away with it.
+        let inflight_storage = MqttInflightStorage::new(self.rocksdb_engine_handler.clone());
+        inflight_storage.delete_by_client(&req.cluster_name, &req.client_id)?;
+
+        // So are the locks of its exclusive subscriptions.
+        let topic_storage = MqttTopicStorage::new(self.rocksdb_engine_handler.clone());
+        topic_storage.delete_exclusive_topic_by_client(&req.cluster_name, &req.client_id)?;
         Ok(())
     }
 
     pub fn set_nx_exclusive_topic(&self, value: Vec<u8>) -> Result<bool, PlacementCenterError> {
         let req = SetExclusiveTopicRequest::decode(value.as_ref())?;
         let storage = MqttTopicStorage::new(self.rocksdb_engine_handler.clone());
-        storage.set_nx_exclisve_topic(&req.cluster_name, &req.topic_name, value.clone())
+        storage.set_nx_exclusive_topic(MqttExclusiveTopic {
+            cluster_name: req.cluster_name,
+            topic_name: req.topic_name,
+            client_id: req.client_id,
+            broker_id: req.broker_id,
+            create_time: req.create_time,
+        })
     }
+
     pub fn delete_exclusive_topic(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
         let req = DeleteExclusiveTopicRequest::decode(value.as_ref())?;
         let storage = MqttTopicStorage::new(self.rocksdb_engine_handler.clone());
-        storage.delete_exclisve_topic(&req.cluster_name, &req.topic_name)
+        if req.topic_name.is_empty() {
+            return storage.delete_exclusive_topic_by_client(&req.cluster_name, &req.client_id);
+        }
+
+        // A client only releases its own lock, the topic may have been taken over since.
+        if !req.client_id.is_empty() {
+            if let Some(lock) = storage.get_exclusive_topic(&req.cluster_name, &req.topic_name)? {
+                if lock.client_id != req.client_id {
+                    return Ok(());
+                }
+            }
+        }
+        storage.delete_exclusive_topic(&req.cluster_name, &req.topic_name)
     }
 
     pub fn create_topic_rewrite_rule(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
@@ -164,4 +202,97 @@ impl DataRouteMqtt {
         let storage = MqttTopicStorage::new(self.rocksdb_engine_handler.clone());
         storage.delete_topic_rewrite_rule(&req.cluster_name, &req.action, &req.source_topic)
     }
+
+    pub fn set_auto_subscribe_rule(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let req = SetAutoSubscribeRuleRequest::decode(value.as_ref())?;
+        let storage = MqttAutoSubscribeStorage::new(self.rocksdb_engine_handler.clone());
+        let auto_subscribe_rule = build_auto_subscribe_rule(&req)?;
+        storage.save(&req.cluster_name, &req.topic, auto_subscribe_rule)
+    }
+
+    pub fn delete_auto_subscribe_rule(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let req = DeleteAutoSubscribeRuleRequest::decode(value.as_ref())?;
+        let storage = MqttAutoSubscribeStorage::new(self.rocksdb_engine_handler.clone());
+        storage.delete(&req.cluster_name, &req.topic)
+    }
+
+    pub fn create_rule(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let req = CreateRuleRequest::decode(value.as_ref())?;
+        let storage = MqttRuleStorage::new(self.rocksdb_engine_handler.clone());
+        let rule = serde_json::from_slice(&req.content)?;
+        storage.save(&req.cluster_name, &req.rule_name, rule)
+    }
+
+    pub fn delete_rule(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let req = DeleteRuleRequest::decode(value.as_ref())?;
+        let storage = MqttRuleStorage::new(self.rocksdb_engine_handler.clone());
+        storage.delete(&req.cluster_name, &req.rule_name)
+    }
+
+    pub fn create_schema(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let req = CreateSchemaRequest::decode(value.as_ref())?;
+        let storage = MqttSchemaStorage::new(self.rocksdb_engine_handler.clone());
+        let schema = serde_json::from_slice(&req.content)?;
+        storage.save(&req.cluster_name, &req.schema_name, schema)
+    }
+
+    pub fn delete_schema(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let req = DeleteSchemaRequest::decode(value.as_ref())?;
+        let storage = MqttSchemaStorage::new(self.rocksdb_engine_handler.clone());
+        storage.delete(&req.cluster_name, &req.schema_name)
+    }
+
+    pub fn bind_schema(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let req = BindSchemaRequest::decode(value.as_ref())?;
+        let storage = MqttSchemaStorage::new(self.rocksdb_engine_handler.clone());
+        let binding = serde_json::from_slice(&req.content)?;
+        storage.save_binding(binding)
+    }
+
+    pub fn unbind_schema(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let req = UnbindSchemaRequest::decode(value.as_ref())?;
+        let storage = MqttSchemaStorage::new(self.rocksdb_engine_handler.clone());
+        storage.delete_binding(&req.cluster_name, &req.schema_name, &req.topic_filter)
+    }
+
+    pub fn batch_inflight_message(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let req = BatchInflightMessageRequest::decode(value.as_ref())?;
+        let storage = MqttInflightStorage::new(self.rocksdb_engine_handler.clone());
+        for raw in req.saves {
+            let message = serde_json::from_slice(&raw)?;
+            storage.save(&req.cluster_name, message)?;
+        }
+        for key in req.deletes {
+            storage.delete(&req.cluster_name, &key.client_id, key.pkid, key.inbound)?;
+        }
+        Ok(())
+    }
+
+    pub fn set_share_sub_strategy(&self, value: Vec<u8>) -> Result<(), PlacementCenterError> {
+        let req = SetShareSubStrategyRequest::decode(value.as_ref())?;
+        let storage = MqttShareSubStrategyStorage::new(self.rocksdb_engine_handler.clone());
+        let strategy = serde_json::from_slice(&req.content)?;
+        storage.save(&req.cluster_name, strategy)
+    }
+}
+
+pub fn build_auto_subscribe_rule(
+    req: &SetAutoSubscribeRuleRequest,
+) -> Result<MqttAutoSubscribeRule, PlacementCenterError> {
+    Ok(MqttAutoSubscribeRule {
+        cluster: req.cluster_name.clone(),
+        topic: req.topic.clone(),
+        qos: qos(req.qos as u8).ok_or(PlacementCenterError::InvalidAutoSubscribeRule(format!(
+            "qos {}",
+            req.qos
+        )))?,
+        no_local: req.no_local,
+        retain_as_published: req.retain_as_published,
+        retained_handling: retain_forward_rule(req.retained_handling as u8).ok_or(
+            PlacementCenterError::InvalidAutoSubscribeRule(format!(
+                "retained_handling {}",
+                req.retained_handling
+            )),
+        )?,
+    })
 }
@@ -17,10 +17,15 @@ use std::sync::Arc;
 use grpc_clients::pool::ClientPool;
 use protocol::placement_center::placement_center_journal::engine_service_server::EngineService;
 use protocol::placement_center::placement_center_journal::{
+    CommitGroupOffsetReply, CommitGroupOffsetRequest, CreateNamespaceReply, CreateNamespaceRequest,
     CreateNextSegmentReply, CreateNextSegmentRequest, CreateShardReply, CreateShardRequest,
-    DeleteSegmentReply, DeleteSegmentRequest, DeleteShardReply, DeleteShardRequest,
-    ListSegmentMetaReply, ListSegmentMetaRequest, ListSegmentReply, ListSegmentRequest,
-    ListShardReply, ListShardRequest, UpdateSegmentMetaReply, UpdateSegmentMetaRequest,
+    DeleteNamespaceReply, DeleteNamespaceRequest, DeleteSegmentReply, DeleteSegmentRequest,
+    DeleteShardReply, DeleteShardRequest, GetProducerSequenceReply, GetProducerSequenceRequest,
+    GroupHeartbeatReply, GroupHeartbeatRequest, JoinGroupReply, JoinGroupRequest, LeaveGroupReply,
+    LeaveGroupRequest, ListNamespaceReply, ListNamespaceRequest, ListSegmentMetaReply,
+    ListSegmentMetaRequest, ListSegmentReply, ListSegmentRequest, ListShardReply, ListShardRequest,
+    SetProducerSequenceReply, SetProducerSequenceRequest, UpdateSegmentMetaReply,
+    UpdateSegmentMetaRequest, UpdateSegmentReplicaReply, UpdateSegmentReplicaRequest,
     UpdateSegmentStatusReply, UpdateSegmentStatusRequest,
 };
 use rocksdb_engine::RocksDBEngine;
@@ -30,12 +35,20 @@ use crate::core::cache::PlacementCacheManager;
 use crate::core::error::PlacementCenterError;
 use crate::journal::cache::JournalCacheManager;
 use crate::journal::controller::call_node::JournalInnerCallManager;
+use crate::journal::services::group::{
+    commit_group_offset_by_req, group_heartbeat_by_req, join_group_by_req, leave_group_by_req,
+};
+use crate::journal::services::namespace::{create_namespace_by_req, delete_namespace_by_req};
+use crate::journal::services::producer::{
+    get_producer_sequence_by_req, set_producer_sequence_by_req,
+};
 use crate::journal::services::segmet::{
     create_segment_by_req, delete_segment_by_req, update_segment_meta_req,
-    update_segment_status_req,
+    update_segment_replica_req, update_segment_status_req,
 };
 use crate::journal::services::shard::{create_shard_by_req, delete_shard_by_req};
 use crate::route::apply::RaftMachineApply;
+use crate::storage::journal::namespace::NamespaceStorage;
 use crate::storage::journal::segment::SegmentStorage;
 use crate::storage::journal::segment_meta::SegmentMetadataStorage;
 use crate::storage::journal::shard::ShardStorage;
@@ -323,6 +336,34 @@ impl EngineService for GrpcEngineService {
         }
     }
 
+    async fn update_segment_replica(
+        &self,
+        request: Request<UpdateSegmentReplicaRequest>,
+    ) -> Result<Response<UpdateSegmentReplicaReply>, Status> {
+        let req = request.into_inner();
+        if req.cluster_name.is_empty() {
+            return Err(Status::cancelled(
+                PlacementCenterError::RequestParamsNotEmpty(req.cluster_name).to_string(),
+            ));
+        }
+
+        match update_segment_replica_req(
+            &self.engine_cache,
+            &self.cluster_cache,
+            &self.raft_machine_apply,
+            &self.call_manager,
+            &self.client_pool,
+            req,
+        )
+        .await
+        {
+            Ok(reply) => return Ok(Response::new(reply)),
+            Err(e) => {
+                return Err(Status::cancelled(e.to_string()));
+            }
+        }
+    }
+
     async fn list_segment_meta(
         &self,
         request: Request<ListSegmentMetaRequest>,
@@ -401,4 +442,169 @@ impl EngineService for GrpcEngineService {
             }
         }
     }
+
+    async fn join_group(
+        &self,
+        request: Request<JoinGroupRequest>,
+    ) -> Result<Response<JoinGroupReply>, Status> {
+        let req = request.into_inner();
+
+        if self.cluster_cache.get_cluster(&req.cluster_name).is_none() {
+            return Err(Status::cancelled(
+                PlacementCenterError::ClusterDoesNotExist(req.cluster_name).to_string(),
+            ));
+        }
+
+        match join_group_by_req(&self.raft_machine_apply, &self.engine_cache, &req).await {
+            Ok(data) => Ok(Response::new(data)),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn group_heartbeat(
+        &self,
+        request: Request<GroupHeartbeatRequest>,
+    ) -> Result<Response<GroupHeartbeatReply>, Status> {
+        let req = request.into_inner();
+
+        match group_heartbeat_by_req(&self.raft_machine_apply, &self.engine_cache, &req) {
+            Ok(data) => Ok(Response::new(data)),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn leave_group(
+        &self,
+        request: Request<LeaveGroupRequest>,
+    ) -> Result<Response<LeaveGroupReply>, Status> {
+        let req = request.into_inner();
+
+        match leave_group_by_req(&self.raft_machine_apply, &self.engine_cache, &req).await {
+            Ok(data) => Ok(Response::new(data)),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn commit_group_offset(
+        &self,
+        request: Request<CommitGroupOffsetRequest>,
+    ) -> Result<Response<CommitGroupOffsetReply>, Status> {
+        let req = request.into_inner();
+
+        match commit_group_offset_by_req(&self.raft_machine_apply, &self.engine_cache, &req).await {
+            Ok(data) => Ok(Response::new(data)),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn create_namespace(
+        &self,
+        request: Request<CreateNamespaceRequest>,
+    ) -> Result<Response<CreateNamespaceReply>, Status> {
+        let req = request.into_inner();
+
+        if self.cluster_cache.get_cluster(&req.cluster_name).is_none() {
+            return Err(Status::cancelled(
+                PlacementCenterError::ClusterDoesNotExist(req.cluster_name).to_string(),
+            ));
+        }
+
+        match create_namespace_by_req(
+            &self.raft_machine_apply,
+            &self.engine_cache,
+            &self.call_manager,
+            &self.client_pool,
+            &req,
+        )
+        .await
+        {
+            Ok(data) => Ok(Response::new(data)),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn list_namespace(
+        &self,
+        request: Request<ListNamespaceRequest>,
+    ) -> Result<Response<ListNamespaceReply>, Status> {
+        let req = request.into_inner();
+        if req.cluster_name.is_empty() {
+            return Err(Status::cancelled(
+                PlacementCenterError::RequestParamsNotEmpty("cluster_name".to_string()).to_string(),
+            ));
+        }
+
+        let storage = NamespaceStorage::new(self.rocksdb_engine_handler.clone());
+        let res = if req.namespace.is_empty() {
+            match storage.list_by_cluster(&req.cluster_name) {
+                Ok(list) => list,
+                Err(e) => {
+                    return Err(Status::cancelled(e.to_string()));
+                }
+            }
+        } else {
+            match storage.get(&req.cluster_name, &req.namespace) {
+                Ok(Some(namespace)) => vec![namespace],
+                Ok(None) => Vec::new(),
+                Err(e) => {
+                    return Err(Status::cancelled(e.to_string()));
+                }
+            }
+        };
+
+        match serde_json::to_vec(&res) {
+            Ok(namespaces) => Ok(Response::new(ListNamespaceReply { namespaces })),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn delete_namespace(
+        &self,
+        request: Request<DeleteNamespaceRequest>,
+    ) -> Result<Response<DeleteNamespaceReply>, Status> {
+        let req = request.into_inner();
+
+        if self.cluster_cache.get_cluster(&req.cluster_name).is_none() {
+            return Err(Status::cancelled(
+                PlacementCenterError::ClusterDoesNotExist(req.cluster_name).to_string(),
+            ));
+        }
+
+        match delete_namespace_by_req(
+            &self.raft_machine_apply,
+            &self.engine_cache,
+            &self.call_manager,
+            &self.client_pool,
+            &req,
+        )
+        .await
+        {
+            Ok(data) => Ok(Response::new(data)),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn set_producer_sequence(
+        &self,
+        request: Request<SetProducerSequenceRequest>,
+    ) -> Result<Response<SetProducerSequenceReply>, Status> {
+        let req = request.into_inner();
+
+        match set_producer_sequence_by_req(&self.raft_machine_apply, &req).await {
+            Ok(data) => Ok(Response::new(data)),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn get_producer_sequence(
+        &self,
+        request: Request<GetProducerSequenceRequest>,
+    ) -> Result<Response<GetProducerSequenceReply>, Status> {
+        let req = request.into_inner();
+
+        match get_producer_sequence_by_req(&self.rocksdb_engine_handler, &req) {
+            Ok(data) => Ok(Response::new(data)),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
 }
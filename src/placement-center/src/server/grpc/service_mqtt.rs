@@ -15,35 +15,59 @@
 use std::sync::Arc;
 
 use common_base::utils::vec_util;
+use grpc_clients::pool::ClientPool;
 use prost::Message;
 use protocol::placement_center::placement_center_mqtt::mqtt_service_server::MqttService;
 use protocol::placement_center::placement_center_mqtt::{
+    BatchInflightMessageReply, BatchInflightMessageRequest, BindSchemaReply, BindSchemaRequest,
     CreateAclReply, CreateAclRequest, CreateBlacklistReply, CreateBlacklistRequest,
-    CreateSessionReply, CreateSessionRequest, CreateTopicReply, CreateTopicRequest,
-    CreateTopicRewriteRuleReply, CreateTopicRewriteRuleRequest, CreateUserReply, CreateUserRequest,
-    DeleteAclReply, DeleteAclRequest, DeleteBlacklistReply, DeleteBlacklistRequest,
-    DeleteExclusiveTopicReply, DeleteExclusiveTopicRequest, DeleteSessionReply,
-    DeleteSessionRequest, DeleteTopicReply, DeleteTopicRequest, DeleteTopicRewriteRuleReply,
-    DeleteTopicRewriteRuleRequest, DeleteUserReply, DeleteUserRequest, GetShareSubLeaderReply,
-    GetShareSubLeaderRequest, ListAclReply, ListAclRequest, ListBlacklistReply,
-    ListBlacklistRequest, ListSessionReply, ListSessionRequest, ListTopicReply, ListTopicRequest,
-    ListTopicRewriteRuleReply, ListTopicRewriteRuleRequest, ListUserReply, ListUserRequest,
-    SaveLastWillMessageReply, SaveLastWillMessageRequest, SetExclusiveTopicReply,
-    SetExclusiveTopicRequest, SetTopicRetainMessageReply, SetTopicRetainMessageRequest,
-    UpdateSessionReply, UpdateSessionRequest,
+    CreateRuleReply, CreateRuleRequest, CreateSchemaReply, CreateSchemaRequest, CreateSessionReply,
+    CreateSessionRequest, CreateTopicReply, CreateTopicRequest, CreateTopicRewriteRuleReply,
+    CreateTopicRewriteRuleRequest, CreateUserReply, CreateUserRequest, DeleteAclReply,
+    DeleteAclRequest, DeleteAutoSubscribeRuleReply, DeleteAutoSubscribeRuleRequest,
+    DeleteBlacklistReply, DeleteBlacklistRequest, DeleteExclusiveTopicReply,
+    DeleteExclusiveTopicRequest, DeleteRuleReply, DeleteRuleRequest, DeleteSchemaReply,
+    DeleteSchemaRequest, DeleteSessionReply, DeleteSessionRequest, DeleteTopicReply,
+    DeleteTopicRequest, DeleteTopicRewriteRuleReply, DeleteTopicRewriteRuleRequest,
+    DeleteUserReply, DeleteUserRequest, GetShareSubLeaderReply, GetShareSubLeaderRequest,
+    ListAclReply, ListAclRequest, ListAutoSubscribeRuleReply, ListAutoSubscribeRuleRequest,
+    ListBlacklistReply, ListBlacklistRequest, ListExclusiveTopicReply, ListExclusiveTopicRequest,
+    ListInflightMessageReply, ListInflightMessageRequest, ListRuleReply, ListRuleRequest,
+    ListSchemaBindingReply, ListSchemaBindingRequest, ListSchemaReply, ListSchemaRequest,
+    ListSessionReply, ListSessionRequest, ListShareSubStrategyReply, ListShareSubStrategyRequest,
+    ListTopicReply, ListTopicRequest, ListTopicRewriteRuleReply, ListTopicRewriteRuleRequest,
+    ListUserReply, ListUserRequest, SaveLastWillMessageReply, SaveLastWillMessageRequest,
+    SetAutoSubscribeRuleReply, SetAutoSubscribeRuleRequest, SetExclusiveTopicReply,
+    SetExclusiveTopicRequest, SetShareSubStrategyReply, SetShareSubStrategyRequest,
+    SetTopicRetainMessageReply, SetTopicRetainMessageRequest, UnbindSchemaReply,
+    UnbindSchemaRequest, UpdateSessionReply, UpdateSessionRequest,
 };
 use tonic::{Request, Response, Status};
 
 use crate::core::cache::PlacementCacheManager;
 use crate::core::error::PlacementCenterError;
-use crate::mqtt::services::share_sub::ShareSubLeader;
-use crate::mqtt::services::topic::{create_topic_req, set_topic_retain_message_req};
+use crate::mqtt::services::auto_subscribe::{
+    delete_auto_subscribe_rule_req, set_auto_subscribe_rule_req,
+};
+use crate::mqtt::services::rule::{create_rule_req, delete_rule_req};
+use crate::mqtt::services::schema::{
+    bind_schema_req, create_schema_req, delete_schema_req, unbind_schema_req,
+};
+use crate::mqtt::services::share_sub::{set_share_sub_strategy_req, ShareSubLeader};
+use crate::mqtt::services::topic::{
+    create_topic_req, delete_exclusive_topic_req, set_topic_retain_message_req,
+};
 use crate::route::apply::RaftMachineApply;
 use crate::route::data::{StorageData, StorageDataType};
 use crate::server::grpc::validate::ValidateExt;
 use crate::storage::mqtt::acl::AclStorage;
+use crate::storage::mqtt::auto_subscribe::MqttAutoSubscribeStorage;
 use crate::storage::mqtt::blacklist::MqttBlackListStorage;
+use crate::storage::mqtt::inflight::MqttInflightStorage;
+use crate::storage::mqtt::rule::MqttRuleStorage;
+use crate::storage::mqtt::schema::MqttSchemaStorage;
 use crate::storage::mqtt::session::MqttSessionStorage;
+use crate::storage::mqtt::share_sub_strategy::MqttShareSubStrategyStorage;
 use crate::storage::mqtt::topic::MqttTopicStorage;
 use crate::storage::mqtt::user::MqttUserStorage;
 use crate::storage::rocksdb::RocksDBEngine;
@@ -52,6 +76,7 @@ pub struct GrpcMqttService {
     cluster_cache: Arc<PlacementCacheManager>,
     raft_machine_apply: Arc<RaftMachineApply>,
     rocksdb_engine_handler: Arc<RocksDBEngine>,
+    client_pool: Arc<ClientPool>,
 }
 
 impl GrpcMqttService {
@@ -59,11 +84,13 @@ impl GrpcMqttService {
         cluster_cache: Arc<PlacementCacheManager>,
         raft_machine_apply: Arc<RaftMachineApply>,
         rocksdb_engine_handler: Arc<RocksDBEngine>,
+        client_pool: Arc<ClientPool>,
     ) -> Self {
         GrpcMqttService {
             cluster_cache,
             raft_machine_apply,
             rocksdb_engine_handler,
+            client_pool,
         }
     }
 }
@@ -302,6 +329,7 @@ impl MqttService for GrpcMqttService {
         &self,
         request: Request<SetExclusiveTopicRequest>,
     ) -> Result<Response<SetExclusiveTopicReply>, Status> {
+        let _ = request.get_ref().validate_ext()?;
         let mut reply = SetExclusiveTopicReply::default();
         let data = StorageData::new(
             StorageDataType::MqttSetNxExclusiveTopic,
@@ -331,18 +359,19 @@ impl MqttService for GrpcMqttService {
         &self,
         request: Request<DeleteExclusiveTopicRequest>,
     ) -> Result<Response<DeleteExclusiveTopicReply>, Status> {
-        let reply = DeleteExclusiveTopicReply::default();
-        let data = StorageData::new(
-            StorageDataType::MqttDeleteExclusiveTopic,
-            DeleteExclusiveTopicRequest::encode_to_vec(request.get_ref()),
-        );
-        match self.raft_machine_apply.client_write(data).await {
-            Ok(_) => {
-                return Ok(Response::new(reply));
-            }
-            Err(e) => {
-                return Err(Status::cancelled(e.to_string()));
-            }
+        let req = request.into_inner();
+        let _ = req.validate_ext()?;
+        match delete_exclusive_topic_req(
+            &self.rocksdb_engine_handler,
+            &self.cluster_cache,
+            &self.raft_machine_apply,
+            &self.client_pool,
+            req,
+        )
+        .await
+        {
+            Ok(_) => Ok(Response::new(DeleteExclusiveTopicReply::default())),
+            Err(e) => Err(Status::cancelled(e.to_string())),
         }
     }
 
@@ -571,4 +600,330 @@ impl MqttService for GrpcMqttService {
             Err(e) => Err(Status::cancelled(e.to_string())),
         }
     }
+
+    async fn set_auto_subscribe_rule(
+        &self,
+        request: Request<SetAutoSubscribeRuleRequest>,
+    ) -> Result<Response<SetAutoSubscribeRuleReply>, Status> {
+        let req = request.into_inner();
+        let _ = req.validate_ext()?;
+        match set_auto_subscribe_rule_req(
+            &self.cluster_cache,
+            &self.raft_machine_apply,
+            &self.client_pool,
+            req,
+        )
+        .await
+        {
+            Ok(_) => Ok(Response::new(SetAutoSubscribeRuleReply::default())),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn delete_auto_subscribe_rule(
+        &self,
+        request: Request<DeleteAutoSubscribeRuleRequest>,
+    ) -> Result<Response<DeleteAutoSubscribeRuleReply>, Status> {
+        let req = request.into_inner();
+        match delete_auto_subscribe_rule_req(
+            &self.rocksdb_engine_handler,
+            &self.cluster_cache,
+            &self.raft_machine_apply,
+            &self.client_pool,
+            req,
+        )
+        .await
+        {
+            Ok(_) => Ok(Response::new(DeleteAutoSubscribeRuleReply::default())),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn list_auto_subscribe_rule(
+        &self,
+        request: Request<ListAutoSubscribeRuleRequest>,
+    ) -> Result<Response<ListAutoSubscribeRuleReply>, Status> {
+        let req = request.into_inner();
+        let storage = MqttAutoSubscribeStorage::new(self.rocksdb_engine_handler.clone());
+        match storage.list(&req.cluster_name) {
+            Ok(data) => {
+                let mut result = Vec::new();
+                for raw in data {
+                    result.push(raw.encode());
+                }
+                Ok(Response::new(ListAutoSubscribeRuleReply {
+                    auto_subscribe_rules: result,
+                }))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn create_rule(
+        &self,
+        request: Request<CreateRuleRequest>,
+    ) -> Result<Response<CreateRuleReply>, Status> {
+        let req = request.into_inner();
+        let _ = req.validate_ext()?;
+        match create_rule_req(
+            &self.cluster_cache,
+            &self.raft_machine_apply,
+            &self.client_pool,
+            req,
+        )
+        .await
+        {
+            Ok(_) => Ok(Response::new(CreateRuleReply::default())),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn delete_rule(
+        &self,
+        request: Request<DeleteRuleRequest>,
+    ) -> Result<Response<DeleteRuleReply>, Status> {
+        let req = request.into_inner();
+        let _ = req.validate_ext()?;
+        match delete_rule_req(
+            &self.rocksdb_engine_handler,
+            &self.cluster_cache,
+            &self.raft_machine_apply,
+            &self.client_pool,
+            req,
+        )
+        .await
+        {
+            Ok(_) => Ok(Response::new(DeleteRuleReply::default())),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn list_rule(
+        &self,
+        request: Request<ListRuleRequest>,
+    ) -> Result<Response<ListRuleReply>, Status> {
+        let req = request.into_inner();
+        let storage = MqttRuleStorage::new(self.rocksdb_engine_handler.clone());
+        match storage.list(&req.cluster_name) {
+            Ok(data) => {
+                let mut result = Vec::new();
+                for raw in data {
+                    result.push(raw.encode());
+                }
+                Ok(Response::new(ListRuleReply { rules: result }))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn create_schema(
+        &self,
+        request: Request<CreateSchemaRequest>,
+    ) -> Result<Response<CreateSchemaReply>, Status> {
+        let req = request.into_inner();
+        let _ = req.validate_ext()?;
+        match create_schema_req(
+            &self.cluster_cache,
+            &self.raft_machine_apply,
+            &self.client_pool,
+            req,
+        )
+        .await
+        {
+            Ok(_) => Ok(Response::new(CreateSchemaReply::default())),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn delete_schema(
+        &self,
+        request: Request<DeleteSchemaRequest>,
+    ) -> Result<Response<DeleteSchemaReply>, Status> {
+        let req = request.into_inner();
+        let _ = req.validate_ext()?;
+        match delete_schema_req(
+            &self.rocksdb_engine_handler,
+            &self.cluster_cache,
+            &self.raft_machine_apply,
+            &self.client_pool,
+            req,
+        )
+        .await
+        {
+            Ok(_) => Ok(Response::new(DeleteSchemaReply::default())),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn list_schema(
+        &self,
+        request: Request<ListSchemaRequest>,
+    ) -> Result<Response<ListSchemaReply>, Status> {
+        let req = request.into_inner();
+        let storage = MqttSchemaStorage::new(self.rocksdb_engine_handler.clone());
+        match storage.list(&req.cluster_name) {
+            Ok(data) => {
+                let mut result = Vec::new();
+                for raw in data {
+                    result.push(raw.encode());
+                }
+                Ok(Response::new(ListSchemaReply { schemas: result }))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn bind_schema(
+        &self,
+        request: Request<BindSchemaRequest>,
+    ) -> Result<Response<BindSchemaReply>, Status> {
+        let req = request.into_inner();
+        let _ = req.validate_ext()?;
+        match bind_schema_req(
+            &self.rocksdb_engine_handler,
+            &self.cluster_cache,
+            &self.raft_machine_apply,
+            &self.client_pool,
+            req,
+        )
+        .await
+        {
+            Ok(_) => Ok(Response::new(BindSchemaReply::default())),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn unbind_schema(
+        &self,
+        request: Request<UnbindSchemaRequest>,
+    ) -> Result<Response<UnbindSchemaReply>, Status> {
+        let req = request.into_inner();
+        let _ = req.validate_ext()?;
+        match unbind_schema_req(
+            &self.rocksdb_engine_handler,
+            &self.cluster_cache,
+            &self.raft_machine_apply,
+            &self.client_pool,
+            req,
+        )
+        .await
+        {
+            Ok(_) => Ok(Response::new(UnbindSchemaReply::default())),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn list_schema_binding(
+        &self,
+        request: Request<ListSchemaBindingRequest>,
+    ) -> Result<Response<ListSchemaBindingReply>, Status> {
+        let req = request.into_inner();
+        let storage = MqttSchemaStorage::new(self.rocksdb_engine_handler.clone());
+        match storage.list_binding(&req.cluster_name) {
+            Ok(data) => {
+                let mut result = Vec::new();
+                for raw in data {
+                    result.push(raw.encode());
+                }
+                Ok(Response::new(ListSchemaBindingReply { bindings: result }))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn batch_inflight_message(
+        &self,
+        request: Request<BatchInflightMessageRequest>,
+    ) -> Result<Response<BatchInflightMessageReply>, Status> {
+        let req = request.into_inner();
+        let _ = req.validate_ext()?;
+        let data = StorageData::new(
+            StorageDataType::MqttBatchInflightMessage,
+            BatchInflightMessageRequest::encode_to_vec(&req),
+        );
+
+        match self.raft_machine_apply.client_write(data).await {
+            Ok(_) => Ok(Response::new(BatchInflightMessageReply::default())),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn set_share_sub_strategy(
+        &self,
+        request: Request<SetShareSubStrategyRequest>,
+    ) -> Result<Response<SetShareSubStrategyReply>, Status> {
+        let req = request.into_inner();
+        let _ = req.validate_ext()?;
+        match set_share_sub_strategy_req(
+            &self.cluster_cache,
+            &self.raft_machine_apply,
+            &self.client_pool,
+            req,
+        )
+        .await
+        {
+            Ok(_) => Ok(Response::new(SetShareSubStrategyReply::default())),
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn list_share_sub_strategy(
+        &self,
+        request: Request<ListShareSubStrategyRequest>,
+    ) -> Result<Response<ListShareSubStrategyReply>, Status> {
+        let req = request.into_inner();
+        let storage = MqttShareSubStrategyStorage::new(self.rocksdb_engine_handler.clone());
+        match storage.list(&req.cluster_name) {
+            Ok(data) => {
+                let mut result = Vec::new();
+                for raw in data {
+                    result.push(raw.encode());
+                }
+                Ok(Response::new(ListShareSubStrategyReply {
+                    strategies: result,
+                }))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn list_inflight_message(
+        &self,
+        request: Request<ListInflightMessageRequest>,
+    ) -> Result<Response<ListInflightMessageReply>, Status> {
+        let req = request.into_inner();
+        let _ = req.validate_ext()?;
+        let storage = MqttInflightStorage::new(self.rocksdb_engine_handler.clone());
+        match storage.list(&req.cluster_name, &req.client_id) {
+            Ok(data) => {
+                let mut result = Vec::new();
+                for raw in data {
+                    result.push(raw.encode());
+                }
+                Ok(Response::new(ListInflightMessageReply { messages: result }))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
+
+    async fn list_exclusive_topic(
+        &self,
+        request: Request<ListExclusiveTopicRequest>,
+    ) -> Result<Response<ListExclusiveTopicReply>, Status> {
+        let req = request.into_inner();
+        let _ = req.validate_ext()?;
+        let storage = MqttTopicStorage::new(self.rocksdb_engine_handler.clone());
+        match storage.list_exclusive_topic(&req.cluster_name) {
+            Ok(data) => {
+                let mut result = Vec::new();
+                for raw in data {
+                    result.push(raw.encode());
+                }
+                Ok(Response::new(ListExclusiveTopicReply {
+                    exclusive_topics: result,
+                }))
+            }
+            Err(e) => Err(Status::cancelled(e.to_string())),
+        }
+    }
 }
@@ -19,7 +19,12 @@ use protocol::placement_center::placement_center_inner::{
     ClusterType, DeleteIdempotentDataRequest, GetResourceConfigRequest, RegisterNodeRequest,
     SetIdempotentDataRequest, SetResourceConfigRequest, UnRegisterNodeRequest,
 };
-use protocol::placement_center::placement_center_mqtt::GetShareSubLeaderRequest;
+use protocol::placement_center::placement_center_mqtt::{
+    BatchInflightMessageRequest, BindSchemaRequest, CreateRuleRequest, CreateSchemaRequest,
+    DeleteExclusiveTopicRequest, DeleteRuleRequest, DeleteSchemaRequest, GetShareSubLeaderRequest,
+    ListExclusiveTopicRequest, ListInflightMessageRequest, SetAutoSubscribeRuleRequest,
+    SetExclusiveTopicRequest, SetShareSubStrategyRequest, UnbindSchemaRequest,
+};
 use tonic::Status;
 
 pub trait ValidateExt {
@@ -158,6 +163,176 @@ impl ValidateExt for GetShareSubLeaderRequest {
     }
 }
 
+impl ValidateExt for SetAutoSubscribeRuleRequest {
+    // validate params:
+    // 1. cluster_name, topic can not empty
+    // 2. qos and retained_handling are in the range of the MQTT subscription options
+    fn validate_ext(&self) -> Result<(), Status> {
+        ensure_param_not_empty("cluster_name", &self.cluster_name)?;
+        ensure_param_not_empty("topic", &self.topic)?;
+
+        if self.qos > 2 {
+            return Err(Status::invalid_argument(
+                CommonError::InvalidParameterFormat("qos".to_string(), self.qos.to_string())
+                    .to_string(),
+            ));
+        }
+
+        if self.retained_handling > 2 {
+            return Err(Status::invalid_argument(
+                CommonError::InvalidParameterFormat(
+                    "retained_handling".to_string(),
+                    self.retained_handling.to_string(),
+                )
+                .to_string(),
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+impl ValidateExt for CreateRuleRequest {
+    // validate params: cluster_name, rule_name, content can not empty
+    fn validate_ext(&self) -> Result<(), Status> {
+        ensure_param_not_empty("cluster_name", &self.cluster_name)?;
+        ensure_param_not_empty("rule_name", &self.rule_name)?;
+
+        if self.content.is_empty() {
+            return Err(Status::invalid_argument(
+                CommonError::ParameterCannotBeNull("content".to_string()).to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl ValidateExt for DeleteRuleRequest {
+    // validate params: cluster_name, rule_name can not empty
+    fn validate_ext(&self) -> Result<(), Status> {
+        ensure_param_not_empty("cluster_name", &self.cluster_name)?;
+        ensure_param_not_empty("rule_name", &self.rule_name)?;
+        Ok(())
+    }
+}
+
+impl ValidateExt for CreateSchemaRequest {
+    // validate params: cluster_name, schema_name, content can not empty
+    fn validate_ext(&self) -> Result<(), Status> {
+        ensure_param_not_empty("cluster_name", &self.cluster_name)?;
+        ensure_param_not_empty("schema_name", &self.schema_name)?;
+
+        if self.content.is_empty() {
+            return Err(Status::invalid_argument(
+                CommonError::ParameterCannotBeNull("content".to_string()).to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl ValidateExt for DeleteSchemaRequest {
+    // validate params: cluster_name, schema_name can not empty
+    fn validate_ext(&self) -> Result<(), Status> {
+        ensure_param_not_empty("cluster_name", &self.cluster_name)?;
+        ensure_param_not_empty("schema_name", &self.schema_name)?;
+        Ok(())
+    }
+}
+
+impl ValidateExt for BindSchemaRequest {
+    // validate params: cluster_name, content can not empty
+    fn validate_ext(&self) -> Result<(), Status> {
+        ensure_param_not_empty("cluster_name", &self.cluster_name)?;
+
+        if self.content.is_empty() {
+            return Err(Status::invalid_argument(
+                CommonError::ParameterCannotBeNull("content".to_string()).to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl ValidateExt for UnbindSchemaRequest {
+    // validate params: cluster_name, schema_name, topic_filter can not empty
+    fn validate_ext(&self) -> Result<(), Status> {
+        ensure_param_not_empty("cluster_name", &self.cluster_name)?;
+        ensure_param_not_empty("schema_name", &self.schema_name)?;
+        ensure_param_not_empty("topic_filter", &self.topic_filter)?;
+        Ok(())
+    }
+}
+
+impl ValidateExt for BatchInflightMessageRequest {
+    // validate params: cluster_name, saves and client_id of deletes can not empty
+    fn validate_ext(&self) -> Result<(), Status> {
+        ensure_param_not_empty("cluster_name", &self.cluster_name)?;
+
+        if self.saves.iter().any(|raw| raw.is_empty()) {
+            return Err(Status::invalid_argument(
+                CommonError::ParameterCannotBeNull("saves".to_string()).to_string(),
+            ));
+        }
+        for key in self.deletes.iter() {
+            ensure_param_not_empty("client_id", &key.client_id)?;
+        }
+        Ok(())
+    }
+}
+
+impl ValidateExt for SetShareSubStrategyRequest {
+    // validate params: cluster_name, content can not empty
+    fn validate_ext(&self) -> Result<(), Status> {
+        ensure_param_not_empty("cluster_name", &self.cluster_name)?;
+
+        if self.content.is_empty() {
+            return Err(Status::invalid_argument(
+                CommonError::ParameterCannotBeNull("content".to_string()).to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl ValidateExt for ListInflightMessageRequest {
+    // validate params: cluster_name, client_id can not empty
+    fn validate_ext(&self) -> Result<(), Status> {
+        ensure_param_not_empty("cluster_name", &self.cluster_name)?;
+        ensure_param_not_empty("client_id", &self.client_id)?;
+        Ok(())
+    }
+}
+
+impl ValidateExt for SetExclusiveTopicRequest {
+    // validate params: cluster_name, topic_name, client_id can not empty
+    fn validate_ext(&self) -> Result<(), Status> {
+        ensure_param_not_empty("cluster_name", &self.cluster_name)?;
+        ensure_param_not_empty("topic_name", &self.topic_name)?;
+        ensure_param_not_empty("client_id", &self.client_id)?;
+        Ok(())
+    }
+}
+
+impl ValidateExt for DeleteExclusiveTopicRequest {
+    // validate params: cluster_name can not empty, topic_name and client_id can not both be empty
+    fn validate_ext(&self) -> Result<(), Status> {
+        ensure_param_not_empty("cluster_name", &self.cluster_name)?;
+        if self.topic_name.is_empty() {
+            ensure_param_not_empty("client_id", &self.client_id)?;
+        }
+        Ok(())
+    }
+}
+
+impl ValidateExt for ListExclusiveTopicRequest {
+    // validate params: cluster_name can not empty
+    fn validate_ext(&self) -> Result<(), Status> {
+        ensure_param_not_empty("cluster_name", &self.cluster_name)?;
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod validate_test {
     use super::*;
@@ -0,0 +1,108 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::sync::Arc;
+
+use common_base::error::common::CommonError;
+use metadata_struct::journal::group::JournalGroup;
+
+use crate::storage::engine::{
+    engine_delete_by_cluster, engine_prefix_list_by_cluster, engine_save_by_cluster,
+};
+use crate::storage::keys::{key_all_group, key_group};
+use crate::storage::rocksdb::RocksDBEngine;
+
+pub struct GroupStorage {
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+}
+
+impl GroupStorage {
+    pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> Self {
+        GroupStorage {
+            rocksdb_engine_handler,
+        }
+    }
+
+    pub fn save(&self, group: &JournalGroup) -> Result<(), CommonError> {
+        let key = key_group(&group.cluster_name, &group.namespace, &group.group_name);
+        engine_save_by_cluster(self.rocksdb_engine_handler.clone(), key, group.clone())
+    }
+
+    pub fn delete(
+        &self,
+        cluster_name: &str,
+        namespace: &str,
+        group_name: &str,
+    ) -> Result<(), CommonError> {
+        let key = key_group(cluster_name, namespace, group_name);
+        engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key)
+    }
+
+    pub fn all_group(&self) -> Result<Vec<JournalGroup>, CommonError> {
+        let prefix_key = key_all_group();
+        let data = engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)?;
+
+        let mut results = Vec::new();
+        for raw in data {
+            results.push(serde_json::from_slice::<JournalGroup>(&raw.data)?);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::remove_dir_all;
+    use std::sync::Arc;
+
+    use common_base::tools::unique_id;
+    use metadata_struct::journal::group::JournalGroup;
+
+    use super::GroupStorage;
+    use crate::storage::rocksdb::{column_family_list, RocksDBEngine};
+
+    #[test]
+    fn group_storage_test() {
+        let path = format!("/tmp/robustmq_test/{}", unique_id());
+        let rocksdb_engine_handler = Arc::new(RocksDBEngine::new(&path, 100, column_family_list()));
+        let storage = GroupStorage::new(rocksdb_engine_handler.clone());
+
+        let group = JournalGroup {
+            cluster_name: "c1".to_string(),
+            namespace: "n1".to_string(),
+            group_name: "g1".to_string(),
+            generation: 3,
+            ..Default::default()
+        };
+        storage.save(&group).unwrap();
+        storage
+            .save(&JournalGroup {
+                group_name: "g2".to_string(),
+                ..group.clone()
+            })
+            .unwrap();
+
+        let res = storage.all_group().unwrap();
+        assert_eq!(res.len(), 2);
+        assert!(res
+            .iter()
+            .any(|raw| raw.group_name == "g1" && raw.generation == 3));
+
+        storage.delete("c1", "n1", "g1").unwrap();
+        let res = storage.all_group().unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].group_name, "g2");
+
+        remove_dir_all(path).unwrap();
+    }
+}
@@ -12,6 +12,9 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub mod group;
+pub mod namespace;
+pub mod producer;
 pub mod segment;
 pub mod segment_meta;
 pub mod shard;
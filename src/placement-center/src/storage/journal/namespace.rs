@@ -0,0 +1,133 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::error::common::CommonError;
+use metadata_struct::journal::namespace::JournalNamespace;
+
+use crate::storage::engine::{
+    engine_delete_by_cluster, engine_get_by_cluster, engine_prefix_list_by_cluster,
+    engine_save_by_cluster,
+};
+use crate::storage::keys::{key_all_namespace, key_namespace, key_namespace_cluster_prefix};
+use crate::storage::rocksdb::RocksDBEngine;
+
+pub struct NamespaceStorage {
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+}
+
+impl NamespaceStorage {
+    pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> Self {
+        NamespaceStorage {
+            rocksdb_engine_handler,
+        }
+    }
+
+    pub fn save(&self, namespace: &JournalNamespace) -> Result<(), CommonError> {
+        let key = key_namespace(&namespace.cluster_name, &namespace.namespace_name);
+        engine_save_by_cluster(self.rocksdb_engine_handler.clone(), key, namespace)
+    }
+
+    pub fn get(
+        &self,
+        cluster_name: &str,
+        namespace: &str,
+    ) -> Result<Option<JournalNamespace>, CommonError> {
+        let key = key_namespace(cluster_name, namespace);
+        if let Some(data) = engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key)? {
+            return Ok(Some(serde_json::from_slice::<JournalNamespace>(
+                &data.data,
+            )?));
+        }
+        Ok(None)
+    }
+
+    pub fn delete(&self, cluster_name: &str, namespace: &str) -> Result<(), CommonError> {
+        let key = key_namespace(cluster_name, namespace);
+        engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key)
+    }
+
+    pub fn all_namespace(&self) -> Result<Vec<JournalNamespace>, CommonError> {
+        let prefix_key = key_all_namespace();
+        let data = engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)?;
+
+        let mut results = Vec::new();
+        for raw in data {
+            results.push(serde_json::from_slice::<JournalNamespace>(&raw.data)?);
+        }
+        Ok(results)
+    }
+
+    pub fn list_by_cluster(
+        &self,
+        cluster_name: &str,
+    ) -> Result<Vec<JournalNamespace>, CommonError> {
+        let prefix_key = key_namespace_cluster_prefix(cluster_name);
+        let data = engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)?;
+
+        let mut results = Vec::new();
+        for raw in data {
+            results.push(serde_json::from_slice::<JournalNamespace>(&raw.data)?);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::remove_dir_all;
+    use std::sync::Arc;
+
+    use common_base::tools::unique_id;
+    use metadata_struct::journal::namespace::{JournalNamespace, JournalNamespaceQuota};
+
+    use super::NamespaceStorage;
+    use crate::storage::rocksdb::{column_family_list, RocksDBEngine};
+
+    #[test]
+    fn namespace_storage_test() {
+        let path = format!("/tmp/robustmq_test/{}", unique_id());
+        let rocksdb_engine_handler = Arc::new(RocksDBEngine::new(&path, 100, column_family_list()));
+        let storage = NamespaceStorage::new(rocksdb_engine_handler.clone());
+
+        let namespace = JournalNamespace {
+            cluster_name: "c1".to_string(),
+            namespace_name: "n1".to_string(),
+            quota: JournalNamespaceQuota {
+                max_shard_num: 10,
+                ..Default::default()
+            },
+            create_time: 0,
+        };
+        storage.save(&namespace).unwrap();
+        storage
+            .save(&JournalNamespace {
+                cluster_name: "c2".to_string(),
+                namespace_name: "n1".to_string(),
+                ..Default::default()
+            })
+            .unwrap();
+
+        let res = storage.get("c1", "n1").unwrap().unwrap();
+        assert_eq!(res.quota.max_shard_num, 10);
+        assert_eq!(storage.list_by_cluster("c1").unwrap().len(), 1);
+        assert_eq!(storage.all_namespace().unwrap().len(), 2);
+
+        storage.delete("c1", "n1").unwrap();
+        assert!(storage.get("c1", "n1").unwrap().is_none());
+
+        remove_dir_all(path).unwrap();
+    }
+}
@@ -0,0 +1,129 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use common_base::error::common::CommonError;
+use metadata_struct::journal::producer::JournalProducerSequence;
+
+use crate::storage::engine::{
+    engine_delete_by_cluster, engine_get_by_cluster, engine_prefix_list_by_cluster,
+    engine_save_by_cluster,
+};
+use crate::storage::keys::{key_producer_sequence, key_producer_sequence_shard_prefix};
+use crate::storage::rocksdb::RocksDBEngine;
+
+pub struct ProducerSequenceStorage {
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+}
+
+impl ProducerSequenceStorage {
+    pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> Self {
+        ProducerSequenceStorage {
+            rocksdb_engine_handler,
+        }
+    }
+
+    pub fn save(&self, sequence: JournalProducerSequence) -> Result<(), CommonError> {
+        let key = key_producer_sequence(
+            &sequence.cluster_name,
+            &sequence.namespace,
+            &sequence.shard_name,
+            &sequence.producer_id,
+        );
+        engine_save_by_cluster(self.rocksdb_engine_handler.clone(), key, sequence)
+    }
+
+    pub fn get(
+        &self,
+        cluster_name: &str,
+        namespace: &str,
+        shard_name: &str,
+        producer_id: &str,
+    ) -> Result<Option<JournalProducerSequence>, CommonError> {
+        let key = key_producer_sequence(cluster_name, namespace, shard_name, producer_id);
+        if let Some(data) = engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key)? {
+            return Ok(Some(serde_json::from_slice::<JournalProducerSequence>(
+                &data.data,
+            )?));
+        }
+        Ok(None)
+    }
+
+    pub fn delete_by_shard(
+        &self,
+        cluster_name: &str,
+        namespace: &str,
+        shard_name: &str,
+    ) -> Result<(), CommonError> {
+        let prefix_key = key_producer_sequence_shard_prefix(cluster_name, namespace, shard_name);
+        let data = engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)?;
+        for raw in data {
+            let sequence = serde_json::from_slice::<JournalProducerSequence>(&raw.data)?;
+            let key = key_producer_sequence(
+                &sequence.cluster_name,
+                &sequence.namespace,
+                &sequence.shard_name,
+                &sequence.producer_id,
+            );
+            engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs::remove_dir_all;
+    use std::sync::Arc;
+
+    use common_base::tools::unique_id;
+    use metadata_struct::journal::producer::JournalProducerSequence;
+
+    use super::ProducerSequenceStorage;
+    use crate::storage::rocksdb::{column_family_list, RocksDBEngine};
+
+    #[test]
+    fn producer_sequence_storage_test() {
+        let path = format!("/tmp/robustmq_test/{}", unique_id());
+        let rocksdb_engine_handler = Arc::new(RocksDBEngine::new(&path, 100, column_family_list()));
+        let storage = ProducerSequenceStorage::new(rocksdb_engine_handler.clone());
+
+        let sequence = JournalProducerSequence {
+            cluster_name: "c1".to_string(),
+            namespace: "n1".to_string(),
+            shard_name: "s1".to_string(),
+            producer_id: "p1".to_string(),
+            last_pkid: 10,
+            ..Default::default()
+        };
+        storage.save(sequence.clone()).unwrap();
+        storage
+            .save(JournalProducerSequence {
+                shard_name: "s2".to_string(),
+                ..sequence.clone()
+            })
+            .unwrap();
+
+        let res = storage.get("c1", "n1", "s1", "p1").unwrap();
+        assert_eq!(res, Some(sequence));
+        assert!(storage.get("c1", "n1", "s1", "p2").unwrap().is_none());
+
+        storage.delete_by_shard("c1", "n1", "s1").unwrap();
+        assert!(storage.get("c1", "n1", "s1", "p1").unwrap().is_none());
+        assert!(storage.get("c1", "n1", "s2", "p1").unwrap().is_some());
+
+        remove_dir_all(path).unwrap();
+    }
+}
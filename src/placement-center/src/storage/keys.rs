@@ -57,6 +57,18 @@ pub fn key_offset_by_group(cluster_name: &str, group: &str) -> String {
 }
 
 /** ===========Journal========== */
+pub fn key_namespace(cluster_name: &str, namespace: &str) -> String {
+    format!("/journal/namespace/{}/{}", cluster_name, namespace)
+}
+
+pub fn key_namespace_cluster_prefix(cluster_name: &str) -> String {
+    format!("/journal/namespace/{}/", cluster_name)
+}
+
+pub fn key_all_namespace() -> String {
+    "/journal/namespace/".to_string()
+}
+
 pub fn key_shard(cluster_name: &str, namespace: &str, shard_name: &str) -> String {
     format!(
         "/journal/shard/{}/{}/{}",
@@ -142,6 +154,40 @@ pub fn key_segment_metadata_shard_prefix(
     )
 }
 
+pub fn key_group(cluster_name: &str, namespace: &str, group_name: &str) -> String {
+    format!(
+        "/journal/group/{}/{}/{}",
+        cluster_name, namespace, group_name
+    )
+}
+
+pub fn key_all_group() -> String {
+    "/journal/group/".to_string()
+}
+
+pub fn key_producer_sequence(
+    cluster_name: &str,
+    namespace: &str,
+    shard_name: &str,
+    producer_id: &str,
+) -> String {
+    format!(
+        "/journal/producer/{}/{}/{}/{}",
+        cluster_name, namespace, shard_name, producer_id
+    )
+}
+
+pub fn key_producer_sequence_shard_prefix(
+    cluster_name: &str,
+    namespace: &str,
+    shard_name: &str,
+) -> String {
+    format!(
+        "/journal/producer/{}/{}/{}/",
+        cluster_name, namespace, shard_name
+    )
+}
+
 /** ===========MQTT========== */
 pub fn storage_key_mqtt_user(cluster_name: &str, user_name: &str) -> String {
     format!("/mqtt/user/{}/{}", cluster_name, user_name)
@@ -236,3 +282,75 @@ pub fn storage_key_mqtt_topic_rewrite_rule(
 pub fn storage_key_mqtt_topic_rewrite_rule_prefix(cluster_name: &str) -> String {
     format!("/mqtt/topic_rewrite_rule/{}/", cluster_name)
 }
+
+pub fn storage_key_mqtt_auto_subscribe_rule(cluster_name: &str, topic: &str) -> String {
+    format!("/mqtt/auto_subscribe_rule/{}/{}", cluster_name, topic)
+}
+
+pub fn storage_key_mqtt_auto_subscribe_rule_prefix(cluster_name: &str) -> String {
+    format!("/mqtt/auto_subscribe_rule/{}/", cluster_name)
+}
+
+// An empty group_name is the cluster default.
+pub fn storage_key_mqtt_share_sub_strategy(cluster_name: &str, group_name: &str) -> String {
+    format!("/mqtt/share_sub_strategy/{}/{}", cluster_name, group_name)
+}
+
+pub fn storage_key_mqtt_share_sub_strategy_prefix(cluster_name: &str) -> String {
+    format!("/mqtt/share_sub_strategy/{}/", cluster_name)
+}
+
+pub fn storage_key_mqtt_rule(cluster_name: &str, rule_name: &str) -> String {
+    format!("/mqtt/rule/{}/{}", cluster_name, rule_name)
+}
+
+pub fn storage_key_mqtt_rule_prefix(cluster_name: &str) -> String {
+    format!("/mqtt/rule/{}/", cluster_name)
+}
+
+pub fn storage_key_mqtt_schema(cluster_name: &str, schema_name: &str) -> String {
+    format!("/mqtt/schema/{}/{}", cluster_name, schema_name)
+}
+
+pub fn storage_key_mqtt_schema_prefix(cluster_name: &str) -> String {
+    format!("/mqtt/schema/{}/", cluster_name)
+}
+
+pub fn storage_key_mqtt_schema_binding(
+    cluster_name: &str,
+    schema_name: &str,
+    topic_filter: &str,
+) -> String {
+    format!(
+        "/mqtt/schema_binding/{}/{}/{}",
+        cluster_name, schema_name, topic_filter
+    )
+}
+
+pub fn storage_key_mqtt_schema_binding_prefix(cluster_name: &str) -> String {
+    format!("/mqtt/schema_binding/{}/", cluster_name)
+}
+
+pub fn storage_key_mqtt_schema_binding_schema_prefix(
+    cluster_name: &str,
+    schema_name: &str,
+) -> String {
+    format!("/mqtt/schema_binding/{}/{}/", cluster_name, schema_name)
+}
+
+pub fn storage_key_mqtt_inflight_message(
+    cluster_name: &str,
+    client_id: &str,
+    pkid: u32,
+    inbound: bool,
+) -> String {
+    let direction = if inbound { "in" } else { "out" };
+    format!(
+        "/mqtt/inflight/{}/{}/{}/{}",
+        cluster_name, client_id, direction, pkid
+    )
+}
+
+pub fn storage_key_mqtt_inflight_message_prefix(cluster_name: &str, client_id: &str) -> String {
+    format!("/mqtt/inflight/{}/{}/", cluster_name, client_id)
+}
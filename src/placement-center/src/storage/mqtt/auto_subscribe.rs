@@ -0,0 +1,121 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use metadata_struct::mqtt::auto_subscribe_rule::MqttAutoSubscribeRule;
+
+use crate::core::error::PlacementCenterError;
+use crate::storage::engine::{
+    engine_delete_by_cluster, engine_prefix_list_by_cluster, engine_save_by_cluster,
+};
+use crate::storage::keys::{
+    storage_key_mqtt_auto_subscribe_rule, storage_key_mqtt_auto_subscribe_rule_prefix,
+};
+use crate::storage::rocksdb::RocksDBEngine;
+
+pub struct MqttAutoSubscribeStorage {
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+}
+
+impl MqttAutoSubscribeStorage {
+    pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> Self {
+        MqttAutoSubscribeStorage {
+            rocksdb_engine_handler,
+        }
+    }
+
+    pub fn save(
+        &self,
+        cluster_name: &str,
+        topic: &str,
+        auto_subscribe_rule: MqttAutoSubscribeRule,
+    ) -> Result<(), PlacementCenterError> {
+        let key = storage_key_mqtt_auto_subscribe_rule(cluster_name, topic);
+        engine_save_by_cluster(
+            self.rocksdb_engine_handler.clone(),
+            key,
+            auto_subscribe_rule,
+        )?;
+        Ok(())
+    }
+
+    pub fn list(
+        &self,
+        cluster_name: &str,
+    ) -> Result<Vec<MqttAutoSubscribeRule>, PlacementCenterError> {
+        let prefix_key = storage_key_mqtt_auto_subscribe_rule_prefix(cluster_name);
+        let data = engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)?;
+        let mut results = Vec::new();
+        for raw in data {
+            let rule = serde_json::from_slice::<MqttAutoSubscribeRule>(&raw.data)?;
+            results.push(rule);
+        }
+        Ok(results)
+    }
+
+    pub fn delete(&self, cluster_name: &str, topic: &str) -> Result<(), PlacementCenterError> {
+        let key = storage_key_mqtt_auto_subscribe_rule(cluster_name, topic);
+        engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_base::config::placement_center::placement_center_test_conf;
+    use metadata_struct::mqtt::auto_subscribe_rule::MqttAutoSubscribeRule;
+    use protocol::mqtt::common::{QoS, RetainForwardRule};
+    use tokio::fs::remove_dir_all;
+
+    use crate::storage::mqtt::auto_subscribe::MqttAutoSubscribeStorage;
+    use crate::storage::rocksdb::{column_family_list, RocksDBEngine};
+
+    #[tokio::test]
+    async fn auto_subscribe_storage_test() {
+        let config = placement_center_test_conf();
+
+        let rs = Arc::new(RocksDBEngine::new(
+            config.rocksdb.data_path.as_str(),
+            config.rocksdb.max_open_files.unwrap(),
+            column_family_list(),
+        ));
+        let storage = MqttAutoSubscribeStorage::new(rs);
+        let cluster_name = "test_cluster".to_string();
+
+        for topic in ["cmd/${clientid}", "user/${username}/notify"] {
+            let rule = MqttAutoSubscribeRule {
+                cluster: cluster_name.clone(),
+                topic: topic.to_string(),
+                qos: QoS::AtLeastOnce,
+                no_local: false,
+                retain_as_published: false,
+                retained_handling: RetainForwardRule::OnEverySubscribe,
+            };
+            storage.save(&cluster_name, topic, rule).unwrap();
+        }
+
+        let res = storage.list(&cluster_name).unwrap();
+        assert_eq!(res.len(), 2);
+
+        storage.delete(&cluster_name, "cmd/${clientid}").unwrap();
+        let res = storage.list(&cluster_name).unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].topic, "user/${username}/notify");
+
+        remove_dir_all(config.rocksdb.data_path).await.unwrap();
+    }
+}
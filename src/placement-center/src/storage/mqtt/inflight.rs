@@ -0,0 +1,167 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use metadata_struct::mqtt::inflight::MqttInflightMessage;
+
+use crate::core::error::PlacementCenterError;
+use crate::storage::engine::{
+    engine_delete_by_cluster, engine_prefix_list_by_cluster, engine_save_by_cluster,
+};
+use crate::storage::keys::{
+    storage_key_mqtt_inflight_message, storage_key_mqtt_inflight_message_prefix,
+};
+use crate::storage::rocksdb::RocksDBEngine;
+
+pub struct MqttInflightStorage {
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+}
+
+impl MqttInflightStorage {
+    pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> Self {
+        MqttInflightStorage {
+            rocksdb_engine_handler,
+        }
+    }
+
+    pub fn save(
+        &self,
+        cluster_name: &str,
+        message: MqttInflightMessage,
+    ) -> Result<(), PlacementCenterError> {
+        let key = storage_key_mqtt_inflight_message(
+            cluster_name,
+            &message.client_id,
+            message.pkid as u32,
+            message.is_inbound(),
+        );
+        engine_save_by_cluster(self.rocksdb_engine_handler.clone(), key, message)?;
+        Ok(())
+    }
+
+    pub fn list(
+        &self,
+        cluster_name: &str,
+        client_id: &str,
+    ) -> Result<Vec<MqttInflightMessage>, PlacementCenterError> {
+        let prefix_key = storage_key_mqtt_inflight_message_prefix(cluster_name, client_id);
+        let data = engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)?;
+        let mut results = Vec::new();
+        for raw in data {
+            let message = serde_json::from_slice::<MqttInflightMessage>(&raw.data)?;
+            results.push(message);
+        }
+        Ok(results)
+    }
+
+    pub fn delete(
+        &self,
+        cluster_name: &str,
+        client_id: &str,
+        pkid: u32,
+        inbound: bool,
+    ) -> Result<(), PlacementCenterError> {
+        let key = storage_key_mqtt_inflight_message(cluster_name, client_id, pkid, inbound);
+        engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key)?;
+        Ok(())
+    }
+
+    pub fn delete_by_client(
+        &self,
+        cluster_name: &str,
+        client_id: &str,
+    ) -> Result<(), PlacementCenterError> {
+        for message in self.list(cluster_name, client_id)? {
+            self.delete(
+                cluster_name,
+                client_id,
+                message.pkid as u32,
+                message.is_inbound(),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_base::config::placement_center::placement_center_test_conf;
+    use metadata_struct::mqtt::inflight::{MqttInflightMessage, MqttInflightState};
+    use tokio::fs::remove_dir_all;
+
+    use crate::storage::mqtt::inflight::MqttInflightStorage;
+    use crate::storage::rocksdb::{column_family_list, RocksDBEngine};
+
+    #[tokio::test]
+    async fn inflight_storage_test() {
+        let config = placement_center_test_conf();
+
+        let rs = Arc::new(RocksDBEngine::new(
+            config.rocksdb.data_path.as_str(),
+            config.rocksdb.max_open_files.unwrap(),
+            column_family_list(),
+        ));
+        let storage = MqttInflightStorage::new(rs);
+        let cluster_name = "test_cluster".to_string();
+
+        for (client_id, pkid) in [("c1", 1), ("c1", 2), ("c10", 1)] {
+            let message = MqttInflightMessage {
+                client_id: client_id.to_string(),
+                pkid,
+                state: MqttInflightState::WaitPubRec,
+                group_id: format!("system_sub_{}_t_1", client_id),
+                topic_id: "1".to_string(),
+                offset: pkid as u64,
+                create_time: 0,
+            };
+            storage.save(&cluster_name, message).unwrap();
+        }
+
+        let res = storage.list(&cluster_name, "c1").unwrap();
+        assert_eq!(res.len(), 2);
+
+        let mut message = res[0].clone();
+        message.state = MqttInflightState::WaitPubComp;
+        storage.save(&cluster_name, message.clone()).unwrap();
+        let res = storage.list(&cluster_name, "c1").unwrap();
+        assert!(res.contains(&message));
+
+        // An inbound message may use the same pkid as an outbound one.
+        let inbound = MqttInflightMessage {
+            client_id: "c1".to_string(),
+            pkid: 2,
+            state: MqttInflightState::WaitPubRel,
+            group_id: "".to_string(),
+            topic_id: "".to_string(),
+            offset: 0,
+            create_time: 0,
+        };
+        storage.save(&cluster_name, inbound.clone()).unwrap();
+        assert_eq!(storage.list(&cluster_name, "c1").unwrap().len(), 3);
+
+        storage.delete(&cluster_name, "c1", 2, false).unwrap();
+        let res = storage.list(&cluster_name, "c1").unwrap();
+        assert_eq!(res.len(), 2);
+        assert!(res.contains(&inbound));
+
+        storage.delete_by_client(&cluster_name, "c1").unwrap();
+        assert!(storage.list(&cluster_name, "c1").unwrap().is_empty());
+        assert_eq!(storage.list(&cluster_name, "c10").unwrap().len(), 1);
+
+        remove_dir_all(config.rocksdb.data_path).await.unwrap();
+    }
+}
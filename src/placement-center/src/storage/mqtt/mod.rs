@@ -25,8 +25,13 @@
 // limitations under the License.
 
 pub mod acl;
+pub mod auto_subscribe;
 pub mod blacklist;
+pub mod inflight;
 pub mod lastwill;
+pub mod rule;
+pub mod schema;
 pub mod session;
+pub mod share_sub_strategy;
 pub mod topic;
 pub mod user;
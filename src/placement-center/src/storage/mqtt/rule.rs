@@ -0,0 +1,133 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use metadata_struct::mqtt::rule::MqttRule;
+
+use crate::core::error::PlacementCenterError;
+use crate::storage::engine::{
+    engine_delete_by_cluster, engine_get_by_cluster, engine_prefix_list_by_cluster,
+    engine_save_by_cluster,
+};
+use crate::storage::keys::{storage_key_mqtt_rule, storage_key_mqtt_rule_prefix};
+use crate::storage::rocksdb::RocksDBEngine;
+
+pub struct MqttRuleStorage {
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+}
+
+impl MqttRuleStorage {
+    pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> Self {
+        MqttRuleStorage {
+            rocksdb_engine_handler,
+        }
+    }
+
+    pub fn save(
+        &self,
+        cluster_name: &str,
+        rule_name: &str,
+        rule: MqttRule,
+    ) -> Result<(), PlacementCenterError> {
+        let key = storage_key_mqtt_rule(cluster_name, rule_name);
+        engine_save_by_cluster(self.rocksdb_engine_handler.clone(), key, rule)?;
+        Ok(())
+    }
+
+    pub fn list(&self, cluster_name: &str) -> Result<Vec<MqttRule>, PlacementCenterError> {
+        let prefix_key = storage_key_mqtt_rule_prefix(cluster_name);
+        let data = engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)?;
+        let mut results = Vec::new();
+        for raw in data {
+            let rule = serde_json::from_slice::<MqttRule>(&raw.data)?;
+            results.push(rule);
+        }
+        Ok(results)
+    }
+
+    pub fn get(
+        &self,
+        cluster_name: &str,
+        rule_name: &str,
+    ) -> Result<Option<MqttRule>, PlacementCenterError> {
+        let key = storage_key_mqtt_rule(cluster_name, rule_name);
+        if let Some(data) = engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key)? {
+            return Ok(Some(serde_json::from_slice::<MqttRule>(&data.data)?));
+        }
+        Ok(None)
+    }
+
+    pub fn delete(&self, cluster_name: &str, rule_name: &str) -> Result<(), PlacementCenterError> {
+        let key = storage_key_mqtt_rule(cluster_name, rule_name);
+        engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_base::config::placement_center::placement_center_test_conf;
+    use metadata_struct::mqtt::rule::{MqttRule, MqttRuleAction};
+    use protocol::mqtt::common::QoS;
+    use tokio::fs::remove_dir_all;
+
+    use crate::storage::mqtt::rule::MqttRuleStorage;
+    use crate::storage::rocksdb::{column_family_list, RocksDBEngine};
+
+    #[tokio::test]
+    async fn rule_storage_test() {
+        let config = placement_center_test_conf();
+
+        let rs = Arc::new(RocksDBEngine::new(
+            config.rocksdb.data_path.as_str(),
+            config.rocksdb.max_open_files.unwrap(),
+            column_family_list(),
+        ));
+        let storage = MqttRuleStorage::new(rs);
+        let cluster_name = "test_cluster".to_string();
+
+        for rule_name in ["r1", "r2"] {
+            let rule = MqttRule {
+                cluster: cluster_name.clone(),
+                rule_name: rule_name.to_string(),
+                sql: "SELECT * FROM \"t/#\"".to_string(),
+                actions: vec![MqttRuleAction::Republish {
+                    topic: "r/${clientid}".to_string(),
+                    qos: QoS::AtLeastOnce,
+                    retain: false,
+                }],
+                description: "".to_string(),
+                create_time: 0,
+            };
+            storage.save(&cluster_name, rule_name, rule).unwrap();
+        }
+
+        let res = storage.list(&cluster_name).unwrap();
+        assert_eq!(res.len(), 2);
+
+        let res = storage.get(&cluster_name, "r1").unwrap().unwrap();
+        assert_eq!(res.rule_name, "r1");
+
+        storage.delete(&cluster_name, "r1").unwrap();
+        assert!(storage.get(&cluster_name, "r1").unwrap().is_none());
+        let res = storage.list(&cluster_name).unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].rule_name, "r2");
+
+        remove_dir_all(config.rocksdb.data_path).await.unwrap();
+    }
+}
@@ -0,0 +1,229 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use metadata_struct::mqtt::schema::{MqttSchema, MqttSchemaBinding};
+
+use crate::core::error::PlacementCenterError;
+use crate::storage::engine::{
+    engine_delete_by_cluster, engine_get_by_cluster, engine_prefix_list_by_cluster,
+    engine_save_by_cluster,
+};
+use crate::storage::keys::{
+    storage_key_mqtt_schema, storage_key_mqtt_schema_binding,
+    storage_key_mqtt_schema_binding_prefix, storage_key_mqtt_schema_binding_schema_prefix,
+    storage_key_mqtt_schema_prefix,
+};
+use crate::storage::rocksdb::RocksDBEngine;
+
+pub struct MqttSchemaStorage {
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+}
+
+impl MqttSchemaStorage {
+    pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> Self {
+        MqttSchemaStorage {
+            rocksdb_engine_handler,
+        }
+    }
+
+    pub fn save(
+        &self,
+        cluster_name: &str,
+        schema_name: &str,
+        schema: MqttSchema,
+    ) -> Result<(), PlacementCenterError> {
+        let key = storage_key_mqtt_schema(cluster_name, schema_name);
+        engine_save_by_cluster(self.rocksdb_engine_handler.clone(), key, schema)?;
+        Ok(())
+    }
+
+    pub fn list(&self, cluster_name: &str) -> Result<Vec<MqttSchema>, PlacementCenterError> {
+        let prefix_key = storage_key_mqtt_schema_prefix(cluster_name);
+        let data = engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)?;
+        let mut results = Vec::new();
+        for raw in data {
+            let schema = serde_json::from_slice::<MqttSchema>(&raw.data)?;
+            results.push(schema);
+        }
+        Ok(results)
+    }
+
+    pub fn get(
+        &self,
+        cluster_name: &str,
+        schema_name: &str,
+    ) -> Result<Option<MqttSchema>, PlacementCenterError> {
+        let key = storage_key_mqtt_schema(cluster_name, schema_name);
+        if let Some(data) = engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key)? {
+            return Ok(Some(serde_json::from_slice::<MqttSchema>(&data.data)?));
+        }
+        Ok(None)
+    }
+
+    pub fn delete(
+        &self,
+        cluster_name: &str,
+        schema_name: &str,
+    ) -> Result<(), PlacementCenterError> {
+        let key = storage_key_mqtt_schema(cluster_name, schema_name);
+        engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key)?;
+        Ok(())
+    }
+
+    pub fn save_binding(&self, binding: MqttSchemaBinding) -> Result<(), PlacementCenterError> {
+        let key = storage_key_mqtt_schema_binding(
+            &binding.cluster,
+            &binding.schema_name,
+            &binding.topic_filter,
+        );
+        engine_save_by_cluster(self.rocksdb_engine_handler.clone(), key, binding)?;
+        Ok(())
+    }
+
+    pub fn list_binding(
+        &self,
+        cluster_name: &str,
+    ) -> Result<Vec<MqttSchemaBinding>, PlacementCenterError> {
+        let prefix_key = storage_key_mqtt_schema_binding_prefix(cluster_name);
+        self.list_binding_by_prefix(prefix_key)
+    }
+
+    pub fn list_binding_by_schema(
+        &self,
+        cluster_name: &str,
+        schema_name: &str,
+    ) -> Result<Vec<MqttSchemaBinding>, PlacementCenterError> {
+        let prefix_key = storage_key_mqtt_schema_binding_schema_prefix(cluster_name, schema_name);
+        self.list_binding_by_prefix(prefix_key)
+    }
+
+    pub fn get_binding(
+        &self,
+        cluster_name: &str,
+        schema_name: &str,
+        topic_filter: &str,
+    ) -> Result<Option<MqttSchemaBinding>, PlacementCenterError> {
+        let key = storage_key_mqtt_schema_binding(cluster_name, schema_name, topic_filter);
+        if let Some(data) = engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key)? {
+            return Ok(Some(serde_json::from_slice::<MqttSchemaBinding>(
+                &data.data,
+            )?));
+        }
+        Ok(None)
+    }
+
+    pub fn delete_binding(
+        &self,
+        cluster_name: &str,
+        schema_name: &str,
+        topic_filter: &str,
+    ) -> Result<(), PlacementCenterError> {
+        let key = storage_key_mqtt_schema_binding(cluster_name, schema_name, topic_filter);
+        engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key)?;
+        Ok(())
+    }
+
+    fn list_binding_by_prefix(
+        &self,
+        prefix_key: String,
+    ) -> Result<Vec<MqttSchemaBinding>, PlacementCenterError> {
+        let data = engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)?;
+        let mut results = Vec::new();
+        for raw in data {
+            let binding = serde_json::from_slice::<MqttSchemaBinding>(&raw.data)?;
+            results.push(binding);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_base::config::placement_center::placement_center_test_conf;
+    use metadata_struct::mqtt::schema::{
+        MqttSchema, MqttSchemaBinding, MqttSchemaFailureStrategy, MqttSchemaType,
+    };
+    use tokio::fs::remove_dir_all;
+
+    use crate::storage::mqtt::schema::MqttSchemaStorage;
+    use crate::storage::rocksdb::{column_family_list, RocksDBEngine};
+
+    #[tokio::test]
+    async fn schema_storage_test() {
+        let config = placement_center_test_conf();
+
+        let rs = Arc::new(RocksDBEngine::new(
+            config.rocksdb.data_path.as_str(),
+            config.rocksdb.max_open_files.unwrap(),
+            column_family_list(),
+        ));
+        let storage = MqttSchemaStorage::new(rs);
+        let cluster_name = "test_cluster".to_string();
+
+        for schema_name in ["s1", "s10"] {
+            let schema = MqttSchema {
+                cluster: cluster_name.clone(),
+                schema_name: schema_name.to_string(),
+                schema_type: MqttSchemaType::Json,
+                schema: br#"{"type":"object"}"#.to_vec(),
+                message_name: "".to_string(),
+                description: "".to_string(),
+                create_time: 0,
+            };
+            storage.save(&cluster_name, schema_name, schema).unwrap();
+        }
+
+        let res = storage.list(&cluster_name).unwrap();
+        assert_eq!(res.len(), 2);
+        let res = storage.get(&cluster_name, "s1").unwrap().unwrap();
+        assert_eq!(res.schema_type, MqttSchemaType::Json);
+
+        for (schema_name, topic_filter) in [("s1", "sensor/+/temp"), ("s10", "device/#")] {
+            let binding = MqttSchemaBinding {
+                cluster: cluster_name.clone(),
+                schema_name: schema_name.to_string(),
+                topic_filter: topic_filter.to_string(),
+                failure_strategy: MqttSchemaFailureStrategy::Reject,
+            };
+            storage.save_binding(binding).unwrap();
+        }
+
+        assert_eq!(storage.list_binding(&cluster_name).unwrap().len(), 2);
+        let res = storage.list_binding_by_schema(&cluster_name, "s1").unwrap();
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].topic_filter, "sensor/+/temp");
+        assert!(storage
+            .get_binding(&cluster_name, "s10", "device/#")
+            .unwrap()
+            .is_some());
+
+        storage
+            .delete_binding(&cluster_name, "s1", "sensor/+/temp")
+            .unwrap();
+        assert!(storage
+            .list_binding_by_schema(&cluster_name, "s1")
+            .unwrap()
+            .is_empty());
+
+        storage.delete(&cluster_name, "s1").unwrap();
+        assert!(storage.get(&cluster_name, "s1").unwrap().is_none());
+        assert_eq!(storage.list(&cluster_name).unwrap().len(), 1);
+
+        remove_dir_all(config.rocksdb.data_path).await.unwrap();
+    }
+}
@@ -0,0 +1,107 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::sync::Arc;
+
+use metadata_struct::mqtt::cluster::MqttShareSubGroupStrategy;
+
+use crate::core::error::PlacementCenterError;
+use crate::storage::engine::{engine_prefix_list_by_cluster, engine_save_by_cluster};
+use crate::storage::keys::{
+    storage_key_mqtt_share_sub_strategy, storage_key_mqtt_share_sub_strategy_prefix,
+};
+use crate::storage::rocksdb::RocksDBEngine;
+
+pub struct MqttShareSubStrategyStorage {
+    rocksdb_engine_handler: Arc<RocksDBEngine>,
+}
+
+impl MqttShareSubStrategyStorage {
+    pub fn new(rocksdb_engine_handler: Arc<RocksDBEngine>) -> Self {
+        MqttShareSubStrategyStorage {
+            rocksdb_engine_handler,
+        }
+    }
+
+    pub fn save(
+        &self,
+        cluster_name: &str,
+        strategy: MqttShareSubGroupStrategy,
+    ) -> Result<(), PlacementCenterError> {
+        let key = storage_key_mqtt_share_sub_strategy(cluster_name, &strategy.group_name);
+        engine_save_by_cluster(self.rocksdb_engine_handler.clone(), key, strategy)?;
+        Ok(())
+    }
+
+    pub fn list(
+        &self,
+        cluster_name: &str,
+    ) -> Result<Vec<MqttShareSubGroupStrategy>, PlacementCenterError> {
+        let prefix_key = storage_key_mqtt_share_sub_strategy_prefix(cluster_name);
+        let data = engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key)?;
+        let mut results = Vec::new();
+        for raw in data {
+            let strategy = serde_json::from_slice::<MqttShareSubGroupStrategy>(&raw.data)?;
+            results.push(strategy);
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_base::config::placement_center::placement_center_test_conf;
+    use metadata_struct::mqtt::cluster::{MqttShareSubGroupStrategy, MqttShareSubStrategy};
+    use tokio::fs::remove_dir_all;
+
+    use crate::storage::mqtt::share_sub_strategy::MqttShareSubStrategyStorage;
+    use crate::storage::rocksdb::{column_family_list, RocksDBEngine};
+
+    #[tokio::test]
+    async fn share_sub_strategy_storage_test() {
+        let config = placement_center_test_conf();
+
+        let rs = Arc::new(RocksDBEngine::new(
+            config.rocksdb.data_path.as_str(),
+            config.rocksdb.max_open_files.unwrap(),
+            column_family_list(),
+        ));
+        let storage = MqttShareSubStrategyStorage::new(rs);
+        let cluster_name = "test_cluster".to_string();
+
+        for (group_name, strategy) in [
+            ("", MqttShareSubStrategy::Random),
+            ("g1", MqttShareSubStrategy::HashTopic),
+            ("g1", MqttShareSubStrategy::Sticky),
+        ] {
+            let data = MqttShareSubGroupStrategy {
+                cluster_name: cluster_name.clone(),
+                group_name: group_name.to_string(),
+                strategy,
+            };
+            storage.save(&cluster_name, data).unwrap();
+        }
+
+        // A later strategy of the same group replaces the earlier one.
+        let res = storage.list(&cluster_name).unwrap();
+        assert_eq!(res.len(), 2);
+        let g1 = res.iter().find(|raw| raw.group_name == "g1").unwrap();
+        assert_eq!(g1.strategy, MqttShareSubStrategy::Sticky);
+        let default = res.iter().find(|raw| raw.group_name.is_empty()).unwrap();
+        assert_eq!(default.strategy, MqttShareSubStrategy::Random);
+
+        remove_dir_all(config.rocksdb.data_path).await.unwrap();
+    }
+}
@@ -15,6 +15,7 @@
 use std::sync::Arc;
 
 use common_base::utils::topic_util::topic_name_regex_match;
+use metadata_struct::mqtt::exclusive_topic::MqttExclusiveTopic;
 use metadata_struct::mqtt::topic::MqttTopic;
 use metadata_struct::mqtt::topic_rewrite_rule::MqttTopicRewriteRule;
 use prost::Message;
@@ -30,6 +31,7 @@ use crate::storage::keys::{
     storage_key_mqtt_topic, storage_key_mqtt_topic_cluster_prefix,
     storage_key_mqtt_topic_rewrite_rule, storage_key_mqtt_topic_rewrite_rule_prefix,
 };
+use crate::storage::placement::node::NodeStorage;
 use crate::storage::rocksdb::RocksDBEngine;
 
 pub struct MqttTopicStorage {
@@ -85,54 +87,117 @@ impl MqttTopicStorage {
         Ok(())
     }
 
-    pub fn set_nx_exclisve_topic(
+    // Take the lock of an exclusive topic. A lock held by the same client is taken over, so that
+    // the client can subscribe again after it reconnected to another broker. A lock whose broker
+    // has left the cluster, or that was written before locks had an owner, is stale and dropped.
+    pub fn set_nx_exclusive_topic(
         &self,
-        cluster_name: &str,
-        topic_name: &str,
-        value: Vec<u8>,
+        exclusive_topic: MqttExclusiveTopic,
     ) -> Result<bool, PlacementCenterError> {
-        if !self.can_add_exclisve_topic(cluster_name, topic_name)? {
+        let cluster_name = &exclusive_topic.cluster_name;
+        let node_storage = NodeStorage::new(self.rocksdb_engine_handler.clone());
+        let prefix_key_name = storage_key_mqtt_exclusive_topic_prefix(cluster_name);
+        let exclusive_topic_list =
+            engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key_name)?;
+        for wrap in exclusive_topic_list {
+            let lock = match serde_json::from_slice::<MqttExclusiveTopic>(&wrap.data) {
+                Ok(lock) => lock,
+                Err(_) => {
+                    if let Ok(req) = SetExclusiveTopicRequest::decode(wrap.data.as_ref()) {
+                        self.delete_exclusive_topic(cluster_name, &req.topic_name)?;
+                    }
+                    continue;
+                }
+            };
+
+            if !topic_name_regex_match(&lock.topic_name, &exclusive_topic.topic_name) {
+                continue;
+            }
+
+            if lock.client_id == exclusive_topic.client_id
+                && lock.topic_name == exclusive_topic.topic_name
+            {
+                continue;
+            }
+
+            if node_storage.get(cluster_name, lock.broker_id)?.is_none() {
+                self.delete_exclusive_topic(cluster_name, &lock.topic_name)?;
+                continue;
+            }
             return Ok(false);
         }
-        self.set_exclisve_topic(cluster_name, topic_name, value)?;
+
+        let key_name =
+            storage_key_mqtt_exclusive_topic_name(cluster_name, &exclusive_topic.topic_name);
+        engine_save_by_cluster(
+            self.rocksdb_engine_handler.clone(),
+            key_name,
+            exclusive_topic,
+        )?;
         Ok(true)
     }
 
-    pub fn delete_exclisve_topic(
+    pub fn get_exclusive_topic(
         &self,
         cluster_name: &str,
         topic_name: &str,
-    ) -> Result<(), PlacementCenterError> {
+    ) -> Result<Option<MqttExclusiveTopic>, PlacementCenterError> {
         let key_name = storage_key_mqtt_exclusive_topic_name(cluster_name, topic_name);
-        engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key_name)?;
-        Ok(())
+        if let Some(data) = engine_get_by_cluster(self.rocksdb_engine_handler.clone(), key_name)? {
+            return Ok(serde_json::from_slice::<MqttExclusiveTopic>(&data.data).ok());
+        }
+        Ok(None)
     }
 
-    fn can_add_exclisve_topic(
+    pub fn list_exclusive_topic(
         &self,
         cluster_name: &str,
-        topic_name: &str,
-    ) -> Result<bool, PlacementCenterError> {
+    ) -> Result<Vec<MqttExclusiveTopic>, PlacementCenterError> {
         let prefix_key_name = storage_key_mqtt_exclusive_topic_prefix(cluster_name);
-        let exclusive_topic_name_list =
+        let data =
             engine_prefix_list_by_cluster(self.rocksdb_engine_handler.clone(), prefix_key_name)?;
-        for wrap in &exclusive_topic_name_list {
-            let req = SetExclusiveTopicRequest::decode(wrap.data.as_ref())?;
-            if topic_name_regex_match(&req.topic_name, topic_name) {
-                return Ok(false);
+        let mut results = Vec::new();
+        for raw in data {
+            if let Ok(lock) = serde_json::from_slice::<MqttExclusiveTopic>(&raw.data) {
+                results.push(lock);
             }
         }
-        Ok(true)
+        Ok(results)
     }
 
-    fn set_exclisve_topic(
+    pub fn delete_exclusive_topic(
         &self,
         cluster_name: &str,
         topic_name: &str,
-        value: Vec<u8>,
     ) -> Result<(), PlacementCenterError> {
         let key_name = storage_key_mqtt_exclusive_topic_name(cluster_name, topic_name);
-        engine_save_by_cluster(self.rocksdb_engine_handler.clone(), key_name, value)?;
+        engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), key_name)?;
+        Ok(())
+    }
+
+    pub fn delete_exclusive_topic_by_client(
+        &self,
+        cluster_name: &str,
+        client_id: &str,
+    ) -> Result<(), PlacementCenterError> {
+        for lock in self.list_exclusive_topic(cluster_name)? {
+            if lock.client_id == client_id {
+                self.delete_exclusive_topic(cluster_name, &lock.topic_name)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn delete_exclusive_topic_by_broker(
+        &self,
+        cluster_name: &str,
+        broker_id: u64,
+    ) -> Result<(), PlacementCenterError> {
+        for lock in self.list_exclusive_topic(cluster_name)? {
+            if lock.broker_id == broker_id {
+                self.delete_exclusive_topic(cluster_name, &lock.topic_name)?;
+            }
+        }
         Ok(())
     }
 
@@ -179,10 +244,13 @@ mod tests {
     use std::sync::Arc;
 
     use common_base::config::placement_center::placement_center_test_conf;
+    use metadata_struct::mqtt::exclusive_topic::MqttExclusiveTopic;
     use metadata_struct::mqtt::topic::MqttTopic;
+    use metadata_struct::placement::node::BrokerNode;
     use tokio::fs::remove_dir_all;
 
     use crate::storage::mqtt::topic::MqttTopicStorage;
+    use crate::storage::placement::node::NodeStorage;
     use crate::storage::rocksdb::{column_family_list, RocksDBEngine};
 
     #[tokio::test]
@@ -234,4 +302,81 @@ mod tests {
 
         remove_dir_all(config.rocksdb.data_path).await.unwrap();
     }
+
+    #[tokio::test]
+    async fn exclusive_topic_storage_test() {
+        let config = placement_center_test_conf();
+
+        let rs = Arc::new(RocksDBEngine::new(
+            config.rocksdb.data_path.as_str(),
+            config.rocksdb.max_open_files.unwrap(),
+            column_family_list(),
+        ));
+        let topic_storage = MqttTopicStorage::new(rs.clone());
+        let cluster_name = "test_exclusive_cluster".to_string();
+
+        let node_storage = NodeStorage::new(rs);
+        node_storage
+            .save(&BrokerNode {
+                cluster_name: cluster_name.clone(),
+                node_id: 1,
+                ..Default::default()
+            })
+            .unwrap();
+
+        let build_lock = |client_id: &str, broker_id: u64| MqttExclusiveTopic {
+            cluster_name: cluster_name.clone(),
+            topic_name: "/tests/exclusive".to_string(),
+            client_id: client_id.to_string(),
+            broker_id,
+            create_time: 0,
+        };
+
+        assert!(topic_storage
+            .set_nx_exclusive_topic(build_lock("c1", 1))
+            .unwrap());
+        assert!(!topic_storage
+            .set_nx_exclusive_topic(build_lock("c2", 1))
+            .unwrap());
+
+        // the holder can take its lock again, e.g. after reconnecting to another broker
+        assert!(topic_storage
+            .set_nx_exclusive_topic(build_lock("c1", 2))
+            .unwrap());
+        let lock = topic_storage
+            .get_exclusive_topic(&cluster_name, "/tests/exclusive")
+            .unwrap()
+            .unwrap();
+        assert_eq!(lock.broker_id, 2);
+
+        // broker 2 is not registered, so the lock is stale
+        assert!(topic_storage
+            .set_nx_exclusive_topic(build_lock("c2", 1))
+            .unwrap());
+        assert_eq!(
+            topic_storage.list_exclusive_topic(&cluster_name).unwrap(),
+            vec![build_lock("c2", 1)]
+        );
+
+        topic_storage
+            .delete_exclusive_topic_by_client(&cluster_name, "c1")
+            .unwrap();
+        assert_eq!(
+            topic_storage
+                .list_exclusive_topic(&cluster_name)
+                .unwrap()
+                .len(),
+            1
+        );
+
+        topic_storage
+            .delete_exclusive_topic_by_broker(&cluster_name, 1)
+            .unwrap();
+        assert!(topic_storage
+            .list_exclusive_topic(&cluster_name)
+            .unwrap()
+            .is_empty());
+
+        remove_dir_all(config.rocksdb.data_path).await.unwrap();
+    }
 }
@@ -45,7 +45,6 @@ impl NodeStorage {
         engine_delete_by_cluster(self.rocksdb_engine_handler.clone(), node_key)
     }
 
-    #[allow(dead_code)]
     pub fn get(&self, cluster_name: &str, node_id: u64) -> Result<Option<BrokerNode>, CommonError> {
         let node_key = key_node(cluster_name, node_id);
         if let Some(data) = engine_get_by_cluster(self.rocksdb_engine_handler.clone(), node_key)? {
@@ -19,11 +19,15 @@ use prost::Message as _;
 use tokio_util::codec;
 
 use super::journal_engine::{
-    ApiKey, CreateShardReq, CreateShardReqBody, CreateShardResp, CreateShardRespBody,
-    DeleteShardReq, DeleteShardReqBody, DeleteShardResp, DeleteShardRespBody, FetchOffsetReq,
-    FetchOffsetReqBody, FetchOffsetResp, FetchOffsetRespBody, GetClusterMetadataReq,
-    GetClusterMetadataResp, GetClusterMetadataRespBody, GetShardMetadataReq,
-    GetShardMetadataReqBody, GetShardMetadataResp, GetShardMetadataRespBody, ReadReq, ReadReqBody,
+    ApiKey, CommitOffsetReq, CommitOffsetReqBody, CommitOffsetResp, CommitOffsetRespBody,
+    CreateShardReq, CreateShardReqBody, CreateShardResp, CreateShardRespBody, DeleteShardReq,
+    DeleteShardReqBody, DeleteShardResp, DeleteShardRespBody, FetchOffsetReq, FetchOffsetReqBody,
+    FetchOffsetResp, FetchOffsetRespBody, GetClusterMetadataReq, GetClusterMetadataResp,
+    GetClusterMetadataRespBody, GetCommitOffsetReq, GetCommitOffsetReqBody, GetCommitOffsetResp,
+    GetCommitOffsetRespBody, GetShardMetadataReq, GetShardMetadataReqBody, GetShardMetadataResp,
+    GetShardMetadataRespBody, GroupHeartbeatReq, GroupHeartbeatReqBody, GroupHeartbeatResp,
+    GroupHeartbeatRespBody, JoinGroupReq, JoinGroupReqBody, JoinGroupResp, JoinGroupRespBody,
+    LeaveGroupReq, LeaveGroupReqBody, LeaveGroupResp, LeaveGroupRespBody, ReadReq, ReadReqBody,
     ReadResp, ReadRespBody, ReqHeader, RespHeader, WriteReq, WriteReqBody, WriteResp,
     WriteRespBody,
 };
@@ -61,6 +65,26 @@ pub enum JournalEnginePacket {
     // DeleteShard
     DeleteShardReq(DeleteShardReq),
     DeleteShardResp(DeleteShardResp),
+
+    // JoinGroup
+    JoinGroupReq(JoinGroupReq),
+    JoinGroupResp(JoinGroupResp),
+
+    // GroupHeartbeat
+    GroupHeartbeatReq(GroupHeartbeatReq),
+    GroupHeartbeatResp(GroupHeartbeatResp),
+
+    // LeaveGroup
+    LeaveGroupReq(LeaveGroupReq),
+    LeaveGroupResp(LeaveGroupResp),
+
+    // CommitOffset
+    CommitOffsetReq(CommitOffsetReq),
+    CommitOffsetResp(CommitOffsetResp),
+
+    // GetCommitOffset
+    GetCommitOffsetReq(GetCommitOffsetReq),
+    GetCommitOffsetResp(GetCommitOffsetResp),
 }
 
 impl fmt::Display for JournalEnginePacket {
@@ -80,6 +104,16 @@ impl fmt::Display for JournalEnginePacket {
             JournalEnginePacket::CreateShardResp(_) => write!(f, "CreateShardResp"),
             JournalEnginePacket::DeleteShardReq(_) => write!(f, "DeleteShardReq"),
             JournalEnginePacket::DeleteShardResp(_) => write!(f, "DeleteShardResp"),
+            JournalEnginePacket::JoinGroupReq(_) => write!(f, "JoinGroupReq"),
+            JournalEnginePacket::JoinGroupResp(_) => write!(f, "JoinGroupResp"),
+            JournalEnginePacket::GroupHeartbeatReq(_) => write!(f, "GroupHeartbeatReq"),
+            JournalEnginePacket::GroupHeartbeatResp(_) => write!(f, "GroupHeartbeatResp"),
+            JournalEnginePacket::LeaveGroupReq(_) => write!(f, "LeaveGroupReq"),
+            JournalEnginePacket::LeaveGroupResp(_) => write!(f, "LeaveGroupResp"),
+            JournalEnginePacket::CommitOffsetReq(_) => write!(f, "CommitOffsetReq"),
+            JournalEnginePacket::CommitOffsetResp(_) => write!(f, "CommitOffsetResp"),
+            JournalEnginePacket::GetCommitOffsetReq(_) => write!(f, "GetCommitOffsetReq"),
+            JournalEnginePacket::GetCommitOffsetResp(_) => write!(f, "GetCommitOffsetResp"),
         }
     }
 }
@@ -213,6 +247,81 @@ impl codec::Encoder<JournalEnginePacket> for JournalServerCodec {
                 header_byte = RespHeader::encode_to_vec(&header);
                 body_byte = DeleteShardRespBody::encode_to_vec(&body);
             }
+
+            // JoinGroup
+            JournalEnginePacket::JoinGroupReq(data) => {
+                let header = data.header.unwrap();
+                let body = data.body.unwrap();
+                header_byte = ReqHeader::encode_to_vec(&header);
+                body_byte = JoinGroupReqBody::encode_to_vec(&body);
+                req_type = 1;
+            }
+            JournalEnginePacket::JoinGroupResp(data) => {
+                let header = data.header.unwrap();
+                let body = data.body.unwrap();
+                header_byte = RespHeader::encode_to_vec(&header);
+                body_byte = JoinGroupRespBody::encode_to_vec(&body);
+            }
+
+            // GroupHeartbeat
+            JournalEnginePacket::GroupHeartbeatReq(data) => {
+                let header = data.header.unwrap();
+                let body = data.body.unwrap();
+                header_byte = ReqHeader::encode_to_vec(&header);
+                body_byte = GroupHeartbeatReqBody::encode_to_vec(&body);
+                req_type = 1;
+            }
+            JournalEnginePacket::GroupHeartbeatResp(data) => {
+                let header = data.header.unwrap();
+                let body = data.body.unwrap();
+                header_byte = RespHeader::encode_to_vec(&header);
+                body_byte = GroupHeartbeatRespBody::encode_to_vec(&body);
+            }
+
+            // LeaveGroup
+            JournalEnginePacket::LeaveGroupReq(data) => {
+                let header = data.header.unwrap();
+                let body = data.body.unwrap();
+                header_byte = ReqHeader::encode_to_vec(&header);
+                body_byte = LeaveGroupReqBody::encode_to_vec(&body);
+                req_type = 1;
+            }
+            JournalEnginePacket::LeaveGroupResp(data) => {
+                let header = data.header.unwrap();
+                let body = data.body.unwrap();
+                header_byte = RespHeader::encode_to_vec(&header);
+                body_byte = LeaveGroupRespBody::encode_to_vec(&body);
+            }
+
+            // CommitOffset
+            JournalEnginePacket::CommitOffsetReq(data) => {
+                let header = data.header.unwrap();
+                let body = data.body.unwrap();
+                header_byte = ReqHeader::encode_to_vec(&header);
+                body_byte = CommitOffsetReqBody::encode_to_vec(&body);
+                req_type = 1;
+            }
+            JournalEnginePacket::CommitOffsetResp(data) => {
+                let header = data.header.unwrap();
+                let body = data.body.unwrap();
+                header_byte = RespHeader::encode_to_vec(&header);
+                body_byte = CommitOffsetRespBody::encode_to_vec(&body);
+            }
+
+            // GetCommitOffset
+            JournalEnginePacket::GetCommitOffsetReq(data) => {
+                let header = data.header.unwrap();
+                let body = data.body.unwrap();
+                header_byte = ReqHeader::encode_to_vec(&header);
+                body_byte = GetCommitOffsetReqBody::encode_to_vec(&body);
+                req_type = 1;
+            }
+            JournalEnginePacket::GetCommitOffsetResp(data) => {
+                let header = data.header.unwrap();
+                let body = data.body.unwrap();
+                header_byte = RespHeader::encode_to_vec(&header);
+                body_byte = GetCommitOffsetRespBody::encode_to_vec(&body);
+            }
         }
 
         let header_len = header_byte.len();
@@ -339,6 +448,16 @@ impl codec::Decoder for JournalServerCodec {
 
                         ApiKey::DeleteShard => delete_shard_req(body_bytes, header),
 
+                        ApiKey::JoinGroup => join_group_req(body_bytes, header),
+
+                        ApiKey::GroupHeartbeat => group_heartbeat_req(body_bytes, header),
+
+                        ApiKey::LeaveGroup => leave_group_req(body_bytes, header),
+
+                        ApiKey::CommitOffset => commit_offset_req(body_bytes, header),
+
+                        ApiKey::GetCommitOffset => get_commit_offset_req(body_bytes, header),
+
                         _ => Err(Error::NotAvailableRequestType(req_type)),
                     },
                     Err(e) => Err(Error::DecodeHeaderError(e.to_string())),
@@ -361,6 +480,16 @@ impl codec::Decoder for JournalServerCodec {
 
                     ApiKey::DeleteShard => delete_shard_resp(body_bytes, header),
 
+                    ApiKey::JoinGroup => join_group_resp(body_bytes, header),
+
+                    ApiKey::GroupHeartbeat => group_heartbeat_resp(body_bytes, header),
+
+                    ApiKey::LeaveGroup => leave_group_resp(body_bytes, header),
+
+                    ApiKey::CommitOffset => commit_offset_resp(body_bytes, header),
+
+                    ApiKey::GetCommitOffset => get_commit_offset_resp(body_bytes, header),
+
                     _ => Err(Error::NotAvailableRequestType(req_type)),
                 },
                 Err(e) => Err(Error::DecodeHeaderError(e.to_string())),
@@ -624,6 +753,196 @@ fn fetch_offset_resp(
     }
 }
 
+fn join_group_req(
+    body_bytes: BytesMut,
+    header: ReqHeader,
+) -> Result<Option<JournalEnginePacket>, Error> {
+    match JoinGroupReqBody::decode(body_bytes.as_ref()) {
+        Ok(body) => {
+            let item = JournalEnginePacket::JoinGroupReq(JoinGroupReq {
+                header: Some(header),
+                body: Some(body),
+            });
+            Ok(Some(item))
+        }
+        Err(e) => Err(Error::DecodeBodyError(
+            "join_group_req".to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+fn join_group_resp(
+    body_bytes: BytesMut,
+    header: RespHeader,
+) -> Result<Option<JournalEnginePacket>, Error> {
+    match JoinGroupRespBody::decode(body_bytes.as_ref()) {
+        Ok(body) => {
+            let item = JournalEnginePacket::JoinGroupResp(JoinGroupResp {
+                header: Some(header),
+                body: Some(body),
+            });
+            Ok(Some(item))
+        }
+        Err(e) => Err(Error::DecodeBodyError(
+            "join_group_resp".to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+fn group_heartbeat_req(
+    body_bytes: BytesMut,
+    header: ReqHeader,
+) -> Result<Option<JournalEnginePacket>, Error> {
+    match GroupHeartbeatReqBody::decode(body_bytes.as_ref()) {
+        Ok(body) => {
+            let item = JournalEnginePacket::GroupHeartbeatReq(GroupHeartbeatReq {
+                header: Some(header),
+                body: Some(body),
+            });
+            Ok(Some(item))
+        }
+        Err(e) => Err(Error::DecodeBodyError(
+            "group_heartbeat_req".to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+fn group_heartbeat_resp(
+    body_bytes: BytesMut,
+    header: RespHeader,
+) -> Result<Option<JournalEnginePacket>, Error> {
+    match GroupHeartbeatRespBody::decode(body_bytes.as_ref()) {
+        Ok(body) => {
+            let item = JournalEnginePacket::GroupHeartbeatResp(GroupHeartbeatResp {
+                header: Some(header),
+                body: Some(body),
+            });
+            Ok(Some(item))
+        }
+        Err(e) => Err(Error::DecodeBodyError(
+            "group_heartbeat_resp".to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+fn leave_group_req(
+    body_bytes: BytesMut,
+    header: ReqHeader,
+) -> Result<Option<JournalEnginePacket>, Error> {
+    match LeaveGroupReqBody::decode(body_bytes.as_ref()) {
+        Ok(body) => {
+            let item = JournalEnginePacket::LeaveGroupReq(LeaveGroupReq {
+                header: Some(header),
+                body: Some(body),
+            });
+            Ok(Some(item))
+        }
+        Err(e) => Err(Error::DecodeBodyError(
+            "leave_group_req".to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+fn leave_group_resp(
+    body_bytes: BytesMut,
+    header: RespHeader,
+) -> Result<Option<JournalEnginePacket>, Error> {
+    match LeaveGroupRespBody::decode(body_bytes.as_ref()) {
+        Ok(body) => {
+            let item = JournalEnginePacket::LeaveGroupResp(LeaveGroupResp {
+                header: Some(header),
+                body: Some(body),
+            });
+            Ok(Some(item))
+        }
+        Err(e) => Err(Error::DecodeBodyError(
+            "leave_group_resp".to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+fn commit_offset_req(
+    body_bytes: BytesMut,
+    header: ReqHeader,
+) -> Result<Option<JournalEnginePacket>, Error> {
+    match CommitOffsetReqBody::decode(body_bytes.as_ref()) {
+        Ok(body) => {
+            let item = JournalEnginePacket::CommitOffsetReq(CommitOffsetReq {
+                header: Some(header),
+                body: Some(body),
+            });
+            Ok(Some(item))
+        }
+        Err(e) => Err(Error::DecodeBodyError(
+            "commit_offset_req".to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+fn commit_offset_resp(
+    body_bytes: BytesMut,
+    header: RespHeader,
+) -> Result<Option<JournalEnginePacket>, Error> {
+    match CommitOffsetRespBody::decode(body_bytes.as_ref()) {
+        Ok(body) => {
+            let item = JournalEnginePacket::CommitOffsetResp(CommitOffsetResp {
+                header: Some(header),
+                body: Some(body),
+            });
+            Ok(Some(item))
+        }
+        Err(e) => Err(Error::DecodeBodyError(
+            "commit_offset_resp".to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+fn get_commit_offset_req(
+    body_bytes: BytesMut,
+    header: ReqHeader,
+) -> Result<Option<JournalEnginePacket>, Error> {
+    match GetCommitOffsetReqBody::decode(body_bytes.as_ref()) {
+        Ok(body) => {
+            let item = JournalEnginePacket::GetCommitOffsetReq(GetCommitOffsetReq {
+                header: Some(header),
+                body: Some(body),
+            });
+            Ok(Some(item))
+        }
+        Err(e) => Err(Error::DecodeBodyError(
+            "get_commit_offset_req".to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
+fn get_commit_offset_resp(
+    body_bytes: BytesMut,
+    header: RespHeader,
+) -> Result<Option<JournalEnginePacket>, Error> {
+    match GetCommitOffsetRespBody::decode(body_bytes.as_ref()) {
+        Ok(body) => {
+            let item = JournalEnginePacket::GetCommitOffsetResp(GetCommitOffsetResp {
+                header: Some(header),
+                body: Some(body),
+            });
+            Ok(Some(item))
+        }
+        Err(e) => Err(Error::DecodeBodyError(
+            "get_commit_offset_resp".to_string(),
+            e.to_string(),
+        )),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::Duration;
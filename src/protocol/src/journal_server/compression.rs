@@ -0,0 +1,93 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::io;
+
+use super::journal_engine::CompressionType;
+
+const ZSTD_COMPRESSION_LEVEL: i32 = 3;
+
+/// Compress the record content with the given algorithm.
+pub fn compress(compression: CompressionType, data: &[u8]) -> Result<Vec<u8>, io::Error> {
+    match compression {
+        CompressionType::Uncompressed => Ok(data.to_vec()),
+        CompressionType::Lz4 => Ok(lz4_flex::compress_prepend_size(data)),
+        CompressionType::Zstd => zstd::encode_all(data, ZSTD_COMPRESSION_LEVEL),
+        CompressionType::Snappy => snap::raw::Encoder::new()
+            .compress_vec(data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+    }
+}
+
+/// Decompress the record content that was compressed by [`compress`].
+pub fn decompress(compression: CompressionType, data: &[u8]) -> Result<Vec<u8>, io::Error> {
+    match compression {
+        CompressionType::Uncompressed => Ok(data.to_vec()),
+        CompressionType::Lz4 => lz4_flex::decompress_size_prepended(data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+        CompressionType::Zstd => zstd::decode_all(data),
+        CompressionType::Snappy => snap::raw::Decoder::new()
+            .decompress_vec(data)
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+    }
+}
+
+/// Parse the compression type stored in `JournalRecord.compression`.
+pub fn compression_type(value: i32) -> Result<CompressionType, io::Error> {
+    CompressionType::try_from(value).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("unknown compression type {}", value),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compress, compression_type, decompress};
+    use crate::journal_server::journal_engine::CompressionType;
+
+    #[test]
+    fn compress_decompress_test() {
+        let data = "{\"device\":\"d1\",\"temperature\":21.5,\"humidity\":40}".repeat(100);
+        for compression in [
+            CompressionType::Uncompressed,
+            CompressionType::Lz4,
+            CompressionType::Zstd,
+            CompressionType::Snappy,
+        ] {
+            let compressed = compress(compression, data.as_bytes()).unwrap();
+            if compression != CompressionType::Uncompressed {
+                assert!(compressed.len() < data.len());
+            }
+            let raw = decompress(compression, &compressed).unwrap();
+            assert_eq!(raw, data.as_bytes());
+        }
+    }
+
+    #[test]
+    fn decompress_invalid_data_test() {
+        let data = vec![5, 0, 0, 0, 255];
+        assert!(decompress(CompressionType::Lz4, &data).is_err());
+        assert!(decompress(CompressionType::Zstd, &data).is_err());
+        assert!(decompress(CompressionType::Snappy, &data).is_err());
+    }
+
+    #[test]
+    fn compression_type_test() {
+        assert_eq!(compression_type(0).unwrap(), CompressionType::Uncompressed);
+        assert_eq!(compression_type(2).unwrap(), CompressionType::Zstd);
+        assert!(compression_type(10).is_err());
+    }
+}
@@ -902,6 +902,16 @@ impl From<RetainForwardRule> for u8 {
     }
 }
 
+/// Maps a number to RetainForwardRule
+pub fn retain_forward_rule(num: u8) -> Option<RetainForwardRule> {
+    match num {
+        0 => Some(RetainForwardRule::OnEverySubscribe),
+        1 => Some(RetainForwardRule::OnNewSubscribe),
+        2 => Some(RetainForwardRule::Never),
+        _ => None,
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Default, Serialize, Deserialize)]
 pub struct SubscribeProperties {
     pub subscription_identifier: Option<usize>,
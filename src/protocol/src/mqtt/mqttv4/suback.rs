@@ -61,7 +61,7 @@ fn code(reason: SubscribeReasonCode) -> u8 {
         SubscribeReasonCode::SubscriptionIdNotSupported => 161,
         SubscribeReasonCode::WildcardSubscriptionsNotSupported => 162,
         SubscribeReasonCode::ExclusiveSubscriptionDisabled => 143,
-        SubscribeReasonCode::TopicSubscribed => 151,
+        SubscribeReasonCode::TopicSubscribed => 0x80,
     }
 }
 
@@ -111,4 +111,12 @@ mod tests {
         let return_code_read: Vec<u8> = suback.return_codes.iter().map(|&c| code(c)).collect();
         assert_eq!(return_code_read.first().unwrap(), &0x01);
     }
+
+    #[test]
+    fn test_suback_topic_subscribed_v4_failure() {
+        use super::*;
+        // MQTT 3.1.1 only defines 0x80 as a failure return code
+        assert_eq!(code(SubscribeReasonCode::TopicSubscribed), 0x80);
+        assert_eq!(reason(0x80).unwrap(), SubscribeReasonCode::Failure);
+    }
 }
@@ -192,6 +192,37 @@ fn code(value: SubscribeReasonCode) -> u8 {
         SubscribeReasonCode::SubscriptionIdNotSupported => 161,
         SubscribeReasonCode::WildcardSubscriptionsNotSupported => 162,
         SubscribeReasonCode::ExclusiveSubscriptionDisabled => 143,
-        SubscribeReasonCode::TopicSubscribed => 151,
+        SubscribeReasonCode::TopicSubscribed => 143,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn test_suback_v5_exclusive_topic_subscribed() {
+        use super::*;
+
+        let mut buffer = BytesMut::new();
+        let suback = SubAck {
+            pkid: 5u16,
+            return_codes: vec![
+                SubscribeReasonCode::QoS1,
+                SubscribeReasonCode::TopicSubscribed,
+            ],
+        };
+        write(&suback, &None, &mut buffer).unwrap();
+
+        // the exclusive topic is held by another session: Topic Filter Invalid (0x8F)
+        assert_eq!(buffer[buffer.len() - 1], 0x8F);
+
+        let fixed_header: FixedHeader = parse_fixed_header(buffer.iter()).unwrap();
+        let (x, _) = read(fixed_header, buffer.copy_to_bytes(buffer.len())).unwrap();
+        assert_eq!(
+            x.return_codes,
+            vec![
+                SubscribeReasonCode::QoS1,
+                SubscribeReasonCode::TopicFilterInvalid
+            ]
+        );
     }
 }
@@ -19,8 +19,9 @@ use axum::async_trait;
 use common_base::error::common::CommonError;
 use grpc_clients::pool::ClientPool;
 use journal_client::client::{JournalClient, JournalClientWriteData};
-use metadata_struct::adapter::read_config::ReadConfig;
+use metadata_struct::adapter::read_config::{ReadConfig, ReadTimeRange};
 use metadata_struct::adapter::record::Record;
+use metadata_struct::journal::shard::JournalShardConfig;
 use offset::PlaceOffsetManager;
 
 use crate::storage::{ShardConfig, ShardOffset, StorageAdapter};
@@ -58,9 +59,22 @@ impl StorageAdapter for JournalStorageAdapter {
         shard_name: String,
         shard_config: ShardConfig,
     ) -> Result<(), CommonError> {
+        let journal_shard_config = JournalShardConfig {
+            retention_sec: shard_config.retention_sec,
+            retention_bytes: shard_config.retention_bytes,
+            retention_segment_num: shard_config.retention_segment_num,
+            enable_compact: shard_config.enable_compact,
+            tombstone_retention_sec: shard_config.tombstone_retention_sec,
+            ..Default::default()
+        };
         if let Err(e) = self
             .client
-            .create_shard(&namespace, &shard_name, shard_config.replica_num)
+            .create_shard(
+                &namespace,
+                &shard_name,
+                shard_config.replica_num,
+                &journal_shard_config,
+            )
             .await
         {
             return Err(CommonError::CommonError(e.to_string()));
@@ -191,6 +205,32 @@ impl StorageAdapter for JournalStorageAdapter {
         }
     }
 
+    async fn read_by_time_range(
+        &self,
+        namespace: String,
+        shard_name: String,
+        time_range: ReadTimeRange,
+        page_token: Option<String>,
+        read_config: ReadConfig,
+    ) -> Result<(Vec<Record>, Option<String>), CommonError> {
+        match self
+            .client
+            .read_by_time_range(
+                &namespace,
+                &shard_name,
+                &time_range,
+                page_token.as_deref(),
+                &read_config,
+            )
+            .await
+        {
+            Ok(results) => Ok(results),
+            Err(e) => {
+                return Err(CommonError::CommonError(e.to_string()));
+            }
+        }
+    }
+
     async fn get_offset_by_group(&self, group: String) -> Result<Vec<ShardOffset>, CommonError> {
         self.offset_manager
             .get_shard_offset(&self.cluster_name, &group)
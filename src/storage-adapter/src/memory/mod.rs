@@ -17,7 +17,7 @@ use std::collections::HashMap;
 use axum::async_trait;
 use common_base::error::common::CommonError;
 use dashmap::DashMap;
-use metadata_struct::adapter::read_config::ReadConfig;
+use metadata_struct::adapter::read_config::{ReadConfig, ReadTimeRange};
 use metadata_struct::adapter::record::Record;
 
 use crate::storage::{ShardConfig, ShardOffset, StorageAdapter};
@@ -174,12 +174,75 @@ impl StorageAdapter for MemoryStorageAdapter {
         return Ok(Vec::new());
     }
 
+    async fn read_by_time_range(
+        &self,
+        namespace: String,
+        shard_name: String,
+        time_range: ReadTimeRange,
+        page_token: Option<String>,
+        read_config: ReadConfig,
+    ) -> Result<(Vec<Record>, Option<String>), CommonError> {
+        let shard_key = self.shard_key(&namespace, &shard_name);
+
+        // The page token is the index of the next record to scan
+        let start = if let Some(token) = page_token {
+            token.parse::<usize>()?
+        } else {
+            0
+        };
+
+        let mut result = Vec::new();
+        if let Some(data_list) = self.shard_data.get(&shard_key) {
+            for (i, record) in data_list.iter().enumerate().skip(start) {
+                if result.len() as u64 >= read_config.max_record_num {
+                    return Ok((result, Some(i.to_string())));
+                }
+
+                if record.timestamp < time_range.start_timestamp
+                    || (time_range.end_timestamp > 0
+                        && record.timestamp >= time_range.end_timestamp)
+                {
+                    continue;
+                }
+
+                if let Some(key) = &time_range.key {
+                    if record.key != *key {
+                        continue;
+                    }
+                }
+
+                if let Some(tag) = &time_range.tag {
+                    if !record.tags.contains(tag) {
+                        continue;
+                    }
+                }
+
+                result.push(record.clone());
+            }
+        }
+
+        Ok((result, None))
+    }
+
     async fn get_offset_by_timestamp(
         &self,
-        _namespace: String,
-        _shard_name: String,
-        _timestamp: u64,
+        namespace: String,
+        shard_name: String,
+        timestamp: u64,
     ) -> Result<Option<ShardOffset>, CommonError> {
+        let shard_key = self.shard_key(&namespace, &shard_name);
+        if let Some(data_list) = self.shard_data.get(&shard_key) {
+            for record in data_list.iter() {
+                if record.timestamp >= timestamp {
+                    return Ok(Some(ShardOffset {
+                        namespace,
+                        shard_name,
+                        offset: record.offset.unwrap_or_default(),
+                        ..Default::default()
+                    }));
+                }
+            }
+        }
         Ok(None)
     }
 
@@ -232,7 +295,7 @@ mod tests {
     use std::collections::HashMap;
 
     use common_base::tools::unique_id;
-    use metadata_struct::adapter::read_config::ReadConfig;
+    use metadata_struct::adapter::read_config::{ReadConfig, ReadTimeRange};
     use metadata_struct::adapter::record::Record;
 
     use super::MemoryStorageAdapter;
@@ -395,4 +458,89 @@ mod tests {
             .await
             .unwrap();
     }
+
+    #[tokio::test]
+    async fn read_by_time_range_test() {
+        let storage_adapter = MemoryStorageAdapter::new();
+        let namespace = unique_id();
+        let shard_name = "test-time-range".to_string();
+
+        let mut data = Vec::new();
+        for i in 0..10 {
+            let mut record = Record::build_str(format!("data-{}", i));
+            record.timestamp = 100 + i;
+            record.set_key(format!("device-{}", i % 2));
+            record.set_tags(vec![format!("tag-{}", i % 3)]);
+            data.push(record);
+        }
+        storage_adapter
+            .batch_write(namespace.clone(), shard_name.clone(), data)
+            .await
+            .unwrap();
+
+        let mut read_config = ReadConfig::new();
+        read_config.max_record_num = 2;
+
+        // [102, 108) of device-0: 102, 104, 106
+        let time_range = ReadTimeRange {
+            start_timestamp: 102,
+            end_timestamp: 108,
+            key: Some("device-0".to_string()),
+            tag: None,
+        };
+        let (res, token) = storage_adapter
+            .read_by_time_range(
+                namespace.clone(),
+                shard_name.clone(),
+                time_range.clone(),
+                None,
+                read_config.clone(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            res.iter().map(|r| r.timestamp).collect::<Vec<u64>>(),
+            vec![102, 104]
+        );
+        assert!(token.is_some());
+
+        let (res, token) = storage_adapter
+            .read_by_time_range(
+                namespace.clone(),
+                shard_name.clone(),
+                time_range,
+                token,
+                read_config.clone(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            res.iter().map(|r| r.timestamp).collect::<Vec<u64>>(),
+            vec![106]
+        );
+        assert!(token.is_none());
+
+        // key and tag together: device-1 with tag-0 is only 103 and 109
+        let time_range = ReadTimeRange {
+            start_timestamp: 100,
+            end_timestamp: 0,
+            key: Some("device-1".to_string()),
+            tag: Some("tag-0".to_string()),
+        };
+        let (res, token) = storage_adapter
+            .read_by_time_range(
+                namespace.clone(),
+                shard_name.clone(),
+                time_range,
+                None,
+                read_config,
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            res.iter().map(|r| r.timestamp).collect::<Vec<u64>>(),
+            vec![103, 109]
+        );
+        assert!(token.is_none());
+    }
 }
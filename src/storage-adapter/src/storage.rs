@@ -16,12 +16,19 @@ use std::collections::HashMap;
 
 use axum::async_trait;
 use common_base::error::common::CommonError;
-use metadata_struct::adapter::read_config::ReadConfig;
+use metadata_struct::adapter::read_config::{ReadConfig, ReadTimeRange};
 use metadata_struct::adapter::record::Record;
 
 #[derive(Default, Clone)]
 pub struct ShardConfig {
     pub replica_num: u32,
+    // Retention limits of the shard, 0 means no limit
+    pub retention_sec: u64,
+    pub retention_bytes: u64,
+    pub retention_segment_num: u32,
+    // Keep only the newest record per key, tombstones expire after tombstone_retention_sec
+    pub enable_compact: bool,
+    pub tombstone_retention_sec: u64,
 }
 
 #[derive(Default, Clone)]
@@ -83,6 +90,17 @@ pub trait StorageAdapter {
         read_config: ReadConfig,
     ) -> Result<Vec<Record>, CommonError>;
 
+    /// Read one page of the records within `time_range`. The returned token continues the read
+    /// from where this page stopped, `None` means the window has been read to the end.
+    async fn read_by_time_range(
+        &self,
+        namespace: String,
+        shard_name: String,
+        time_range: ReadTimeRange,
+        page_token: Option<String>,
+        read_config: ReadConfig,
+    ) -> Result<(Vec<Record>, Option<String>), CommonError>;
+
     async fn get_offset_by_timestamp(
         &self,
         namespace: String,
@@ -65,6 +65,7 @@ mod tests {
                 namespace: "b1".to_string(),
                 shard_name: "s1".to_string(),
                 replica_num: 1,
+                ..Default::default()
             }),
         });
 
@@ -146,8 +147,11 @@ mod tests {
                         key: "k1".to_string(),
                         value: serde_json::to_vec(&now_second().to_string()).unwrap(),
                         tags: vec!["t1".to_string()],
+                        ..Default::default()
                     }],
+                    ..Default::default()
                 }],
+                ..Default::default()
             }),
         });
 
@@ -75,6 +75,7 @@ mod tests {
                 namespace: namespace.clone(),
                 shard_name: shard_name.clone(),
                 replica_num,
+                ..Default::default()
             }),
         });
 
@@ -148,8 +149,11 @@ mod tests {
                             key: key.clone(),
                             value: value.clone(),
                             tags: tags.clone(),
+                            ..Default::default()
                         }],
+                        ..Default::default()
                     }],
+                    ..Default::default()
                 }),
             });
 
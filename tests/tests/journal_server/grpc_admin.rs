@@ -15,15 +15,71 @@
 #[cfg(test)]
 mod tests {
     use std::sync::Arc;
+    use std::time::Duration;
 
+    use common_base::tools::unique_id;
     use grpc_clients::journal::admin::call::{
-        journal_admin_list_segment, journal_admin_list_shard,
+        journal_admin_describe_segment, journal_admin_list_segment, journal_admin_list_shard,
+        journal_admin_reassign_segment_replica, journal_admin_rebuild_segment_index,
+        journal_admin_sealup_segment,
     };
+    use grpc_clients::placement::journal::call::create_next_segment;
     use grpc_clients::pool::ClientPool;
-    use protocol::journal_server::journal_admin::{ListSegmentRequest, ListShardRequest};
+    use journal_client::client::{JournalClient, JournalClientWriteData};
+    use metadata_struct::journal::segment::{JournalSegment, SegmentStatus};
+    use metadata_struct::journal::shard::JournalShardConfig;
+    use protocol::journal_server::journal_admin::{
+        DescribeSegmentRequest, ListSegmentRequest, ListShardRequest,
+        ReassignSegmentReplicaRequest, RebuildSegmentIndexRequest, SealUpSegmentRequest,
+    };
+    use protocol::placement_center::placement_center_journal::CreateNextSegmentRequest;
+    use tokio::time::sleep;
 
+    use crate::journal_client::common::journal_tcp_addr;
     use crate::journal_server::common::journal_grpc_addr;
 
+    async fn create_shard_with_data(namespace: &str, shard_name: &str) {
+        let client = JournalClient::new(vec![journal_tcp_addr()]);
+        client.connect().await.unwrap();
+        client
+            .create_shard(namespace, shard_name, 1, &JournalShardConfig::default())
+            .await
+            .unwrap();
+        sleep(Duration::from_secs(3)).await;
+
+        for i in 0..10 {
+            let data = JournalClientWriteData {
+                key: format!("k{}", i),
+                content: format!("v{}", i).into_bytes(),
+                tags: vec!["t1".to_string()],
+            };
+            client
+                .write(namespace.to_string(), shard_name.to_string(), data)
+                .await
+                .unwrap();
+        }
+    }
+
+    async fn list_segments(
+        client_pool: &Arc<ClientPool>,
+        namespace: &str,
+        shard_name: &str,
+    ) -> Vec<JournalSegment> {
+        let request = ListSegmentRequest {
+            namespace: namespace.to_string(),
+            shard_name: shard_name.to_string(),
+            segment_no: -1,
+        };
+        let reply = journal_admin_list_segment(client_pool, &[journal_grpc_addr()], request)
+            .await
+            .unwrap();
+        reply
+            .segments
+            .iter()
+            .map(|raw| serde_json::from_str::<JournalSegment>(raw).unwrap())
+            .collect()
+    }
+
     #[tokio::test]
     async fn list_shard_list() {
         let addr = journal_grpc_addr();
@@ -51,4 +107,147 @@ mod tests {
             .unwrap();
         println!("{:?}", data.segments);
     }
+
+    #[tokio::test]
+    async fn describe_segment_and_rebuild_index_test() {
+        let client_pool = Arc::new(ClientPool::new(10));
+        let addrs = vec![journal_grpc_addr()];
+        let namespace = unique_id();
+        let shard_name = "s1".to_string();
+        create_shard_with_data(&namespace, &shard_name).await;
+
+        let request = DescribeSegmentRequest {
+            namespace: namespace.clone(),
+            shard_name: shard_name.clone(),
+            segment_no: 0,
+        };
+        let reply = journal_admin_describe_segment(&client_pool, &addrs, request.clone())
+            .await
+            .unwrap();
+        println!("{:?}", reply);
+        assert!(!reply.segment.is_empty());
+        assert!(reply.file_exists);
+        assert!(reply.file_size > 0);
+        assert_eq!(reply.local_end_offset, 9);
+        assert_eq!(reply.replicas.len(), 1);
+        assert!(reply.replicas[0].is_leader);
+        assert!(reply.replicas[0].in_isr);
+
+        let rebuild = RebuildSegmentIndexRequest {
+            namespace: namespace.clone(),
+            shard_name: shard_name.clone(),
+            segment_no: 0,
+        };
+        journal_admin_rebuild_segment_index(&client_pool, &addrs, rebuild)
+            .await
+            .unwrap();
+        sleep(Duration::from_secs(3)).await;
+
+        let reply = journal_admin_describe_segment(&client_pool, &addrs, request)
+            .await
+            .unwrap();
+        assert_eq!(reply.index_last_offset, 9);
+
+        // unknown segment
+        let request = DescribeSegmentRequest {
+            namespace: namespace.clone(),
+            shard_name: shard_name.clone(),
+            segment_no: 100,
+        };
+        assert!(
+            journal_admin_describe_segment(&client_pool, &addrs, request)
+                .await
+                .is_err()
+        );
+    }
+
+    #[tokio::test]
+    async fn sealup_segment_test() {
+        let client_pool = Arc::new(ClientPool::new(10));
+        let addrs = vec![journal_grpc_addr()];
+        let namespace = unique_id();
+        let shard_name = "s1".to_string();
+        create_shard_with_data(&namespace, &shard_name).await;
+
+        let request = SealUpSegmentRequest {
+            namespace: namespace.clone(),
+            shard_name: shard_name.clone(),
+            segment_no: 0,
+        };
+        let reply = journal_admin_sealup_segment(&client_pool, &addrs, request.clone())
+            .await
+            .unwrap();
+        assert_eq!(reply.end_offset, 9);
+
+        let segments = list_segments(&client_pool, &namespace, &shard_name).await;
+        let first = segments.iter().find(|seg| seg.segment_seq == 0).unwrap();
+        assert_eq!(first.status, SegmentStatus::SealUp);
+        let next = segments.iter().find(|seg| seg.segment_seq == 1).unwrap();
+        assert_eq!(next.status, SegmentStatus::Write);
+
+        // a sealed segment cannot be sealed again
+        assert!(journal_admin_sealup_segment(&client_pool, &addrs, request)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn reassign_segment_replica_test() {
+        let client_pool = Arc::new(ClientPool::new(10));
+        let addrs = vec![journal_grpc_addr()];
+        let namespace = unique_id();
+        let shard_name = "s1".to_string();
+        create_shard_with_data(&namespace, &shard_name).await;
+
+        // the active segment has data and cannot be reassigned
+        let request = ReassignSegmentReplicaRequest {
+            namespace: namespace.clone(),
+            shard_name: shard_name.clone(),
+            segment_no: 0,
+            node_ids: vec![1],
+        };
+        assert!(
+            journal_admin_reassign_segment_replica(&client_pool, &addrs, request)
+                .await
+                .is_err()
+        );
+
+        // the next segment is idle
+        let request = CreateNextSegmentRequest {
+            cluster_name: "JournalCluster1".to_string(),
+            namespace: namespace.clone(),
+            shard_name: shard_name.clone(),
+        };
+        create_next_segment(&client_pool, &["127.0.0.1:1228"], request)
+            .await
+            .unwrap();
+        sleep(Duration::from_secs(2)).await;
+
+        let request = ReassignSegmentReplicaRequest {
+            namespace: namespace.clone(),
+            shard_name: shard_name.clone(),
+            segment_no: 1,
+            node_ids: vec![1, 1],
+        };
+        let reply = journal_admin_reassign_segment_replica(&client_pool, &addrs, request)
+            .await
+            .unwrap();
+        let segment = serde_json::from_str::<JournalSegment>(&reply.segment).unwrap();
+        assert_eq!(segment.replicas.len(), 1);
+        assert_eq!(segment.leader, 1);
+        assert_eq!(segment.leader_epoch, 1);
+
+        // an unknown node cannot hold a replica
+        let request = ReassignSegmentReplicaRequest {
+            namespace,
+            shard_name,
+            segment_no: 1,
+            node_ids: vec![10000],
+        };
+        assert!(
+            journal_admin_reassign_segment_replica(&client_pool, &addrs, request)
+                .await
+                .is_err()
+        );
+    }
 }
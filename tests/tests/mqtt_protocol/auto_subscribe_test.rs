@@ -0,0 +1,76 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use common_base::tools::unique_id;
+    use grpc_clients::mqtt::admin::call::{
+        mqtt_broker_delete_auto_subscribe_rule, mqtt_broker_set_auto_subscribe_rule,
+    };
+    use grpc_clients::pool::ClientPool;
+    use paho_mqtt::{Message, QOS_1};
+    use protocol::broker_mqtt::broker_mqtt_admin::{
+        DeleteAutoSubscribeRuleRequest, SetAutoSubscribeRuleRequest,
+    };
+
+    use crate::mqtt_protocol::common::{
+        broker_addr, broker_grpc_addr, connect_server5, distinct_conn,
+    };
+
+    #[tokio::test]
+    async fn client5_auto_subscribe_test() {
+        let client_pool = Arc::new(ClientPool::new(3));
+        let addrs = vec![broker_grpc_addr()];
+
+        let prefix = format!("/tests_auto_sub/{}", unique_id());
+        let template = format!("{}/${{clientid}}", prefix);
+        let req = SetAutoSubscribeRuleRequest {
+            topic: template.clone(),
+            qos: 1,
+            no_local: false,
+            retain_as_published: false,
+            retained_handling: 0,
+        };
+        mqtt_broker_set_auto_subscribe_rule(&client_pool, &addrs, req)
+            .await
+            .unwrap();
+
+        let addr = broker_addr();
+        let sub_client_id = unique_id();
+        let sub_cli = connect_server5(&sub_client_id, &addr, false, false);
+        let rx = sub_cli.start_consuming();
+
+        let pub_cli = connect_server5(&unique_id(), &addr, false, false);
+        let message_content = "mqtt auto subscribe message".to_string();
+        let topic = format!("{}/{}", prefix, sub_client_id);
+        let msg = Message::new(topic.clone(), message_content.clone(), QOS_1);
+        pub_cli.publish(msg).unwrap();
+
+        let msg = rx.recv_timeout(Duration::from_secs(10)).unwrap().unwrap();
+        assert_eq!(msg.topic(), topic);
+        let payload = String::from_utf8(msg.payload().to_vec()).unwrap();
+        assert_eq!(payload, message_content);
+
+        distinct_conn(pub_cli);
+        distinct_conn(sub_cli);
+
+        let req = DeleteAutoSubscribeRuleRequest { topic: template };
+        mqtt_broker_delete_auto_subscribe_rule(&client_pool, &addrs, req)
+            .await
+            .unwrap();
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 pub mod acl_authorization_test;
+pub mod auto_subscribe_test;
 pub mod common;
 pub mod connect34_test;
 pub mod connect5_test;
@@ -24,6 +25,8 @@ pub mod permission5_test;
 pub mod pub_qos_test;
 pub mod req_resp_test;
 pub mod retain_message_test;
+pub mod rule_engine_test;
+pub mod schema_registry_test;
 pub mod share_sub_test;
 pub mod sub34_qos_test;
 pub mod sub5_qos_test;
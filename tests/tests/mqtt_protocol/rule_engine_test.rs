@@ -0,0 +1,87 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use common_base::tools::unique_id;
+    use grpc_clients::mqtt::admin::call::{mqtt_broker_create_rule, mqtt_broker_delete_rule};
+    use grpc_clients::pool::ClientPool;
+    use paho_mqtt::{Message, QOS_1};
+    use protocol::broker_mqtt::broker_mqtt_admin::{
+        CreateRuleRequest, DeleteRuleRequest, RuleAction, RuleActionType,
+    };
+    use serde_json::{json, Value};
+
+    use crate::mqtt_protocol::common::{
+        broker_addr, broker_grpc_addr, connect_server5, distinct_conn,
+    };
+
+    #[tokio::test]
+    async fn client5_rule_republish_test() {
+        let client_pool = Arc::new(ClientPool::new(3));
+        let addrs = vec![broker_grpc_addr()];
+
+        let uuid = unique_id();
+        let rule_name = format!("rule_{}", uuid);
+        let source_topic = format!("/tests_rule/{}/data", uuid);
+        let dest_topic = format!("/tests_rule_out/{}", uuid);
+        let req = CreateRuleRequest {
+            rule_name: rule_name.clone(),
+            sql: format!(
+                "SELECT payload.temp AS temp, clientid FROM \"/tests_rule/{}/#\" WHERE payload.temp > 30",
+                uuid
+            ),
+            actions: vec![RuleAction {
+                action_type: RuleActionType::Republish.into(),
+                topic: dest_topic.clone(),
+                qos: 1,
+                ..Default::default()
+            }],
+            description: String::new(),
+        };
+        mqtt_broker_create_rule(&client_pool, &addrs, req)
+            .await
+            .unwrap();
+
+        let addr = broker_addr();
+        let sub_cli = connect_server5(&unique_id(), &addr, false, false);
+        let rx = sub_cli.start_consuming();
+        sub_cli.subscribe(&dest_topic, QOS_1).unwrap();
+
+        let pub_client_id = unique_id();
+        let pub_cli = connect_server5(&pub_client_id, &addr, false, false);
+        for temp in [10, 35] {
+            let payload = json!({ "temp": temp }).to_string();
+            let msg = Message::new(source_topic.clone(), payload, QOS_1);
+            pub_cli.publish(msg).unwrap();
+        }
+
+        // Only the message that satisfies the WHERE condition is republished
+        let msg = rx.recv_timeout(Duration::from_secs(10)).unwrap().unwrap();
+        assert_eq!(msg.topic(), dest_topic);
+        let payload = serde_json::from_slice::<Value>(msg.payload()).unwrap();
+        assert_eq!(payload, json!({"temp": 35, "clientid": pub_client_id}));
+
+        distinct_conn(pub_cli);
+        distinct_conn(sub_cli);
+
+        let req = DeleteRuleRequest { rule_name };
+        mqtt_broker_delete_rule(&client_pool, &addrs, req)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,124 @@
+// Copyright 2023 RobustMQ Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use common_base::tools::unique_id;
+    use grpc_clients::mqtt::admin::call::{
+        mqtt_broker_bind_schema, mqtt_broker_create_schema, mqtt_broker_delete_schema,
+        mqtt_broker_unbind_schema,
+    };
+    use grpc_clients::pool::ClientPool;
+    use paho_mqtt::{Message, PropertyCode, QOS_1};
+    use protocol::broker_mqtt::broker_mqtt_admin::{
+        BindSchemaRequest, CreateSchemaRequest, DeleteSchemaRequest, SchemaFailureStrategy,
+        SchemaType, UnbindSchemaRequest,
+    };
+    use serde_json::json;
+
+    use crate::mqtt_protocol::common::{
+        broker_addr, broker_grpc_addr, connect_server5, distinct_conn,
+    };
+
+    #[tokio::test]
+    async fn client5_schema_validation_test() {
+        let client_pool = Arc::new(ClientPool::new(3));
+        let addrs = vec![broker_grpc_addr()];
+
+        let uuid = unique_id();
+        let schema_name = format!("schema_{}", uuid);
+        let reject_filter = format!("/tests_schema/{}/reject/#", uuid);
+        let tag_filter = format!("/tests_schema/{}/tag/#", uuid);
+
+        let schema = json!({
+            "type": "object",
+            "properties": { "temp": { "type": "number" } },
+            "required": ["temp"]
+        });
+        let req = CreateSchemaRequest {
+            schema_name: schema_name.clone(),
+            schema_type: SchemaType::Json.into(),
+            schema: schema.to_string().into_bytes(),
+            ..Default::default()
+        };
+        mqtt_broker_create_schema(&client_pool, &addrs, req)
+            .await
+            .unwrap();
+
+        for (topic_filter, failure_strategy) in [
+            (reject_filter.clone(), SchemaFailureStrategy::Reject),
+            (tag_filter.clone(), SchemaFailureStrategy::Tag),
+        ] {
+            let req = BindSchemaRequest {
+                schema_name: schema_name.clone(),
+                topic_filter,
+                failure_strategy: failure_strategy.into(),
+            };
+            mqtt_broker_bind_schema(&client_pool, &addrs, req)
+                .await
+                .unwrap();
+        }
+
+        let addr = broker_addr();
+        let valid = json!({ "temp": 25.5 }).to_string();
+        let invalid = json!({ "temp": "hot" }).to_string();
+
+        // Reject: the invalid payload is answered with Payload Format Invalid
+        let reject_topic = format!("/tests_schema/{}/reject/1", uuid);
+        let pub_cli = connect_server5(&unique_id(), &addr, false, false);
+        let msg = Message::new(reject_topic.clone(), valid.clone(), QOS_1);
+        pub_cli.publish(msg).unwrap();
+        let msg = Message::new(reject_topic, invalid.clone(), QOS_1);
+        assert!(pub_cli.publish(msg).is_err());
+        distinct_conn(pub_cli);
+
+        // Tag: the invalid payload is delivered with the validation error attached
+        let tag_topic = format!("/tests_schema/{}/tag/1", uuid);
+        let sub_cli = connect_server5(&unique_id(), &addr, false, false);
+        let rx = sub_cli.start_consuming();
+        sub_cli.subscribe(&tag_topic, QOS_1).unwrap();
+
+        let pub_cli = connect_server5(&unique_id(), &addr, false, false);
+        let msg = Message::new(tag_topic.clone(), invalid.clone(), QOS_1);
+        pub_cli.publish(msg).unwrap();
+
+        let msg = rx.recv_timeout(Duration::from_secs(10)).unwrap().unwrap();
+        assert_eq!(msg.payload_str(), invalid);
+        let (key, _) = msg
+            .properties()
+            .get_string_pair_at(PropertyCode::UserProperty, 0)
+            .unwrap();
+        assert_eq!(key, "schema_validation_error");
+
+        distinct_conn(pub_cli);
+        distinct_conn(sub_cli);
+
+        for topic_filter in [reject_filter, tag_filter] {
+            let req = UnbindSchemaRequest {
+                schema_name: schema_name.clone(),
+                topic_filter,
+            };
+            mqtt_broker_unbind_schema(&client_pool, &addrs, req)
+                .await
+                .unwrap();
+        }
+        let req = DeleteSchemaRequest { schema_name };
+        mqtt_broker_delete_schema(&client_pool, &addrs, req)
+            .await
+            .unwrap();
+    }
+}
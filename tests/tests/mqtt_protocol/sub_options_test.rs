@@ -95,15 +95,15 @@ mod tests {
         let cli = connect_server5(&client_id, &addr, false, false);
 
         let message_content = format!("mqtt {payload_flag} message");
-        // publish
-        let msg = Message::new_retained(pub_topic.clone(), message_content.clone(), QOS_0);
-        assert!(cli.publish(msg).is_ok());
-
-        // subscribe
+        // subscribe, Retain As Published applies to messages forwarded after the subscription
         let rx = cli.start_consuming();
         assert!(cli
             .subscribe_many_with_options(sub_topics, sub_qos, sub_opts, None)
             .is_ok());
+
+        // publish
+        let msg = Message::new_retained(pub_topic.clone(), message_content.clone(), QOS_0);
+        assert!(cli.publish(msg).is_ok());
         let recv = rx.recv_timeout(Duration::from_secs(5));
         assert!(recv.is_ok());
         assert_eq!(recv.unwrap().unwrap().retained(), retain_as_published);
@@ -259,8 +259,115 @@ mod tests {
         println!("{:?}", res);
         assert!(res.is_ok());
 
-        assert!(rx.recv_timeout(Duration::from_secs(5)).is_ok());
         assert!(rx.recv_timeout(Duration::from_secs(5)).is_err());
+        distinct_conn(consumer_cli);
+        distinct_conn(cli);
+    }
+
+    #[tokio::test]
+    async fn send_retained_on_new_resubscribe_test() {
+        let topic = format!("/tests/{}", unique_id());
+        let sub_topics = &[topic.clone()];
+        let sub_qos = &[QOS_0];
+        let sub_opts = &[SubscribeOptions::new(
+            false,
+            false,
+            RetainHandling::SendRetainedOnNew,
+        )];
+
+        let addr = broker_addr();
+        let cli = connect_server5(&unique_id(), &addr, false, false);
+        let message_content = "mqtt retain on new message".to_string();
+        let msg = Message::new_retained(topic.clone(), message_content.clone(), QOS_0);
+        assert!(cli.publish(msg).is_ok());
+
+        // new subscribe, the retained message is sent
+        let consumer_cli = connect_server5(&unique_id(), &addr, false, false);
+        let rx = consumer_cli.start_consuming();
+        assert!(consumer_cli
+            .subscribe_many_with_options(sub_topics, sub_qos, sub_opts, None)
+            .is_ok());
+        recv_retain_msg(message_content.clone(), rx.clone()).await;
+
+        // the subscription already exists, the retained message is not sent again
+        assert!(consumer_cli
+            .subscribe_many_with_options(sub_topics, sub_qos, sub_opts, None)
+            .is_ok());
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_err());
+
+        distinct_conn(consumer_cli);
+        distinct_conn(cli);
+    }
+
+    #[tokio::test]
+    async fn send_retained_wildcard_filter_test() {
+        let prefix = format!("/tests/{}", unique_id());
+        let pub_topics = vec![format!("{}/a/1", prefix), format!("{}/b/1", prefix)];
+
+        let addr = broker_addr();
+        let cli = connect_server5(&unique_id(), &addr, false, false);
+        for topic in pub_topics.iter() {
+            let msg = Message::new_retained(topic.clone(), format!("retain {}", topic), QOS_0);
+            assert!(cli.publish(msg).is_ok());
+        }
+
+        // Retained messages sent on subscribe have the RETAIN flag set, whatever Retain As Published is
+        for (sub_topic, retain_as_published) in [
+            (format!("{}/+/1", prefix), true),
+            (format!("{}/#", prefix), true),
+            (format!("{}/#", prefix), false),
+        ] {
+            let consumer_cli = connect_server5(&unique_id(), &addr, false, false);
+            let rx = consumer_cli.start_consuming();
+            let sub_opts = &[SubscribeOptions::new(
+                false,
+                retain_as_published,
+                RetainHandling::SendRetainedOnSubscribe,
+            )];
+            assert!(consumer_cli
+                .subscribe_many_with_options(&[sub_topic], &[QOS_0], sub_opts, None)
+                .is_ok());
+
+            let mut recv_topics = Vec::new();
+            while recv_topics.len() < pub_topics.len() {
+                let msg = rx.recv_timeout(Duration::from_secs(5)).unwrap().unwrap();
+                assert!(msg.retained());
+                assert_eq!(
+                    String::from_utf8(msg.payload().to_vec()).unwrap(),
+                    format!("retain {}", msg.topic())
+                );
+                recv_topics.push(msg.topic().to_string());
+            }
+            recv_topics.sort();
+            assert_eq!(recv_topics, pub_topics);
+            distinct_conn(consumer_cli);
+        }
+        distinct_conn(cli);
+    }
+
+    #[tokio::test]
+    async fn share_sub_not_send_retained_test() {
+        let topic = format!("/tests/{}", unique_id());
+        let addr = broker_addr();
+        let cli = connect_server5(&unique_id(), &addr, false, false);
+        let msg = Message::new_retained(topic.clone(), "mqtt share retain message", QOS_0);
+        assert!(cli.publish(msg).is_ok());
+
+        let consumer_cli = connect_server5(&unique_id(), &addr, false, false);
+        let rx = consumer_cli.start_consuming();
+        let sub_topics = &[format!("$share/{}{}", unique_id(), topic)];
+        let sub_opts = &[SubscribeOptions::new(
+            false,
+            true,
+            RetainHandling::SendRetainedOnSubscribe,
+        )];
+        assert!(consumer_cli
+            .subscribe_many_with_options(sub_topics, &[QOS_0], sub_opts, None)
+            .is_ok());
+        assert!(rx.recv_timeout(Duration::from_secs(5)).is_err());
+
+        distinct_conn(consumer_cli);
+        distinct_conn(cli);
     }
 
     async fn recv_retain_msg(message_content: String, rx: paho_mqtt::Receiver<Option<Message>>) {
@@ -117,4 +117,51 @@ mod tests {
         }
         distinct_conn(cli);
     }
+
+    #[tokio::test]
+    async fn outbound_topic_alias_test() {
+        // The client sends a Topic Alias Maximum, so the broker publishes every message after the
+        // first one with the alias only and the client has to resolve the topic from it.
+        let client_id = unique_id();
+        let addr = broker_addr();
+        let topic = format!("/tests/{}", unique_id());
+        let sub_topics = &[topic.clone()];
+
+        let cli = connect_server5(&client_id, &addr, false, false);
+        let sub_qos = &[1];
+        let rx = cli.start_consuming();
+        match cli.subscribe_many(sub_topics, sub_qos) {
+            Ok(_) => {}
+            Err(e) => {
+                panic!("{}", e)
+            }
+        }
+
+        let message_count = 3;
+        for i in 0..message_count {
+            let msg = MessageBuilder::new()
+                .payload(format!("mqtt message {}", i))
+                .topic(topic.clone())
+                .qos(QOS_1)
+                .retained(false)
+                .finalize();
+            match cli.publish(msg) {
+                Ok(_) => {}
+                Err(e) => {
+                    panic!("{:?}", e);
+                }
+            }
+        }
+
+        let mut recv_count = 0;
+        for msg in rx.iter() {
+            let msg = msg.unwrap();
+            assert_eq!(msg.topic(), topic);
+            recv_count += 1;
+            if recv_count == message_count {
+                break;
+            }
+        }
+        distinct_conn(cli);
+    }
 }
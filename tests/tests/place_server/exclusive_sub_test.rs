@@ -56,24 +56,54 @@ mod tests {
         let req = SetExclusiveTopicRequest {
             cluster_name: cluster_name.clone(),
             topic_name: topic_name.clone(),
+            client_id: "c1".to_string(),
+            broker_id: node_id,
+            create_time: 0,
+        };
+        let other_req = SetExclusiveTopicRequest {
+            client_id: "c2".to_string(),
+            ..req.clone()
         };
 
         let resp = placement_set_nx_exclusive_topic(&client_pool, &addrs, req.clone())
             .await
             .unwrap();
         assert!(resp.success);
+
+        // the holder may re-acquire its own lock
         let resp = placement_set_nx_exclusive_topic(&client_pool, &addrs, req.clone())
             .await
             .unwrap();
+        assert!(resp.success);
+
+        let resp = placement_set_nx_exclusive_topic(&client_pool, &addrs, other_req.clone())
+            .await
+            .unwrap();
         assert!(!resp.success);
+
+        // another client cannot release the lock
         let delete_req = DeleteExclusiveTopicRequest {
             cluster_name: cluster_name.clone(),
             topic_name: topic_name.clone(),
+            client_id: "c2".to_string(),
         };
         placement_delete_exclusive_topic(&client_pool, &addrs, delete_req.clone())
             .await
             .unwrap();
-        let resp = placement_set_nx_exclusive_topic(&client_pool, &addrs, req.clone())
+        let resp = placement_set_nx_exclusive_topic(&client_pool, &addrs, other_req.clone())
+            .await
+            .unwrap();
+        assert!(!resp.success);
+
+        let delete_req = DeleteExclusiveTopicRequest {
+            cluster_name: cluster_name.clone(),
+            topic_name: topic_name.clone(),
+            client_id: "c1".to_string(),
+        };
+        placement_delete_exclusive_topic(&client_pool, &addrs, delete_req.clone())
+            .await
+            .unwrap();
+        let resp = placement_set_nx_exclusive_topic(&client_pool, &addrs, other_req.clone())
             .await
             .unwrap();
         assert!(resp.success);